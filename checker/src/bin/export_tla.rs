@@ -0,0 +1,125 @@
+use paxos_checker::{
+    explore::Bounds,
+    low_level::{quorum::{all_hosts, QuorumSystem}, Constants},
+    tla::{conformance, conformance_module, CONFORMANCE_CONFIG, SPEC},
+};
+use std::{env, fs, path::PathBuf, process::ExitCode};
+
+const USAGE: &str = "\
+usage: export_tla [options]
+  writes Paxos.tla and a PaxosConformance module for TLC to check against it
+  --out DIR          output directory (.)
+  --hosts N          number of hosts (3)
+  --failures F       tolerated failures (1)
+  --phase1 N         phase-1 quorum size (hosts - failures)
+  --phase2 N         phase-2 quorum size (hosts - failures)
+  --keys K           instances 0..K (1)
+  --max-ballot B     highest ballot number a host may prepare (3)
+  --proposers P      hosts 0..P may prepare ballots (2)
+  --values V,..      one client request per operation (1,2)
+  --max-pending P    longest pending queue (1)
+  --consume          remove messages from flight on receipt
+  --faults           enable drop, duplicate and garbage-collect steps
+  --crashes          enable crash and recover steps
+  --batching         enable batch steps
+  --limit N          states to explore before sampling (20000)
+  --sources N        states whose steps are exported (20)";
+
+fn parse<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    value.and_then(|value| value.parse().ok()).ok_or(format!("{flag} needs a numeric argument"))
+}
+
+fn main() -> ExitCode {
+    let mut bounds = Bounds::default();
+    let (mut num_hosts, mut num_failures) = (3, 1);
+    let (mut phase1_size, mut phase2_size) = (None, None);
+    let mut consume_on_receive = false;
+    let (mut limit, mut sources) = (20_000, 20);
+    let mut out = PathBuf::from(".");
+
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let parsed = match flag.as_str() {
+            "--out" => args.next().map(|dir| out = PathBuf::from(dir)).ok_or(format!("{flag} needs a directory")),
+            "--hosts" => parse(&flag, args.next()).map(|n| num_hosts = n),
+            "--failures" => parse(&flag, args.next()).map(|f| num_failures = f),
+            "--phase1" => parse(&flag, args.next()).map(|n| phase1_size = Some(n)),
+            "--phase2" => parse(&flag, args.next()).map(|n| phase2_size = Some(n)),
+            "--keys" => parse(&flag, args.next()).map(|k| bounds.num_keys = k),
+            "--max-ballot" => parse(&flag, args.next()).map(|b| bounds.max_ballot = b),
+            "--proposers" => parse(&flag, args.next()).map(|p| bounds.proposers = p),
+            "--max-pending" => parse(&flag, args.next()).map(|p| bounds.max_pending = p),
+            "--limit" => parse(&flag, args.next()).map(|n| limit = n),
+            "--sources" => parse(&flag, args.next()).map(|n| sources = n),
+            "--values" => args
+                .next()
+                .and_then(|values| values.split(',').map(|value| value.parse().ok()).collect::<Option<Vec<_>>>())
+                .map(|values| bounds.values = values)
+                .ok_or(format!("{flag} needs a comma-separated list of integers")),
+            "--consume" => {
+                consume_on_receive = true;
+                Ok(())
+            },
+            "--faults" => {
+                bounds.network_faults = true;
+                Ok(())
+            },
+            "--crashes" => {
+                bounds.crashes = true;
+                Ok(())
+            },
+            "--batching" => {
+                bounds.batching = true;
+                Ok(())
+            },
+            "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            },
+            _ => Err(format!("unknown option {flag}")),
+        };
+        if let Err(error) = parsed {
+            eprintln!("{error}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let quorums = QuorumSystem {
+        acceptors: all_hosts(num_hosts),
+        phase1_size: phase1_size.unwrap_or(num_hosts.saturating_sub(num_failures)),
+        phase2_size: phase2_size.unwrap_or(num_hosts.saturating_sub(num_failures)),
+    };
+    let c = Constants::new(num_hosts, num_failures, quorums, consume_on_receive);
+    if !c.well_formed() {
+        eprintln!("constants are not well-formed: need hosts >= 2 * failures + 1, phase1 + phase2 > hosts and both phases <= hosts - failures");
+        return ExitCode::FAILURE;
+    }
+    if bounds.num_keys == 0 {
+        eprintln!("--keys must be at least 1");
+        return ExitCode::FAILURE;
+    }
+
+    let exported = conformance(&c, &bounds, limit, sources);
+    let files = [
+        ("Paxos.tla", SPEC.to_string()),
+        ("PaxosConformance.tla", conformance_module(&c, &bounds, &exported)),
+        ("PaxosConformance.cfg", CONFORMANCE_CONFIG.to_string()),
+    ];
+    for (name, text) in files {
+        let path = out.join(name);
+        if let Err(error) = fs::write(&path, text) {
+            eprintln!("{}: {error}", path.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    println!(
+        "exported {} states, {} steps and {} corrupted steps to {}",
+        exported.states.len(),
+        exported.steps.len(),
+        exported.non_steps.len(),
+        out.display(),
+    );
+    println!("check with: java -cp tla2tools.jar tlc2.TLC -config PaxosConformance.cfg PaxosConformance");
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,122 @@
+use paxos_checker::{
+    explore::{check, describe_host, describe_step, Bounds, Outcome},
+    low_level::{quorum::{all_hosts, QuorumSystem}, Constants},
+};
+use std::{collections::BTreeSet, env, process::ExitCode};
+
+const USAGE: &str = "\
+usage: model_check [options]
+  --hosts N          number of hosts (3)
+  --failures F       tolerated failures (1)
+  --phase1 N         phase-1 quorum size (hosts - failures)
+  --phase2 N         phase-2 quorum size (hosts - failures)
+  --keys K           instances 0..K (1)
+  --max-ballot B     highest ballot number a host may prepare (3)
+  --proposers P      hosts 0..P may prepare ballots (2)
+  --learners H,..    hosts that count Accepted messages and decide on their own (none)
+  --values V,..      one client request per operation (1,2)
+  --max-pending P    longest pending queue (1)
+  --consume          remove messages from flight on receipt
+  --faults           enable drop, duplicate and garbage-collect steps
+  --crashes          enable crash and recover steps
+  --batching         enable batch steps
+  --max-states N     give up after N states (5000000)
+exits 0 when every state within the bounds was checked, 1 on a violation and 2 when --max-states cut the search short";
+
+fn parse<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    value.and_then(|value| value.parse().ok()).ok_or(format!("{flag} needs a numeric argument"))
+}
+
+fn main() -> ExitCode {
+    let mut bounds = Bounds::default();
+    let (mut num_hosts, mut num_failures) = (3, 1);
+    let (mut phase1_size, mut phase2_size) = (None, None);
+    let mut consume_on_receive = false;
+    let mut max_states = 5_000_000;
+    let mut learners = BTreeSet::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let parsed = match flag.as_str() {
+            "--hosts" => parse(&flag, args.next()).map(|n| num_hosts = n),
+            "--failures" => parse(&flag, args.next()).map(|f| num_failures = f),
+            "--phase1" => parse(&flag, args.next()).map(|n| phase1_size = Some(n)),
+            "--phase2" => parse(&flag, args.next()).map(|n| phase2_size = Some(n)),
+            "--keys" => parse(&flag, args.next()).map(|k| bounds.num_keys = k),
+            "--max-ballot" => parse(&flag, args.next()).map(|b| bounds.max_ballot = b),
+            "--proposers" => parse(&flag, args.next()).map(|p| bounds.proposers = p),
+            "--max-pending" => parse(&flag, args.next()).map(|p| bounds.max_pending = p),
+            "--max-states" => parse(&flag, args.next()).map(|n| max_states = n),
+            "--learners" => args
+                .next()
+                .and_then(|hosts| hosts.split(',').map(|host| host.parse().ok()).collect::<Option<BTreeSet<_>>>())
+                .map(|hosts| learners = hosts)
+                .ok_or(format!("{flag} needs a comma-separated list of host ids")),
+            "--values" => args
+                .next()
+                .and_then(|values| values.split(',').map(|value| value.parse().ok()).collect::<Option<Vec<_>>>())
+                .map(|values| bounds.values = values)
+                .ok_or(format!("{flag} needs a comma-separated list of integers")),
+            "--consume" => {
+                consume_on_receive = true;
+                Ok(())
+            },
+            "--faults" => {
+                bounds.network_faults = true;
+                Ok(())
+            },
+            "--crashes" => {
+                bounds.crashes = true;
+                Ok(())
+            },
+            "--batching" => {
+                bounds.batching = true;
+                Ok(())
+            },
+            "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            },
+            _ => Err(format!("unknown option {flag}")),
+        };
+        if let Err(error) = parsed {
+            eprintln!("{error}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let quorums = QuorumSystem {
+        acceptors: all_hosts(num_hosts),
+        phase1_size: phase1_size.unwrap_or(num_hosts.saturating_sub(num_failures)),
+        phase2_size: phase2_size.unwrap_or(num_hosts.saturating_sub(num_failures)),
+    };
+    let c = Constants::new(num_hosts, num_failures, quorums, consume_on_receive).with_roles((0..num_hosts).collect(), learners);
+    if !c.well_formed() {
+        eprintln!("constants are not well-formed: need hosts >= 2 * failures + 1, phase1 + phase2 > hosts and both phases <= hosts - failures");
+        return ExitCode::FAILURE;
+    }
+
+    match check(&c, &bounds, max_states) {
+        Outcome::Verified { states, depth } => {
+            println!("no violation in {states} states (depth {depth})");
+            ExitCode::SUCCESS
+        },
+        Outcome::Incomplete { states, depth } => {
+            println!("incomplete: no violation in the first {states} states (depth {depth}), but the search stopped at --max-states");
+            ExitCode::from(2)
+        },
+        Outcome::Violation { violated, init, trace } => {
+            println!("violated: {}", violated.join(", "));
+            println!("trace of {} steps:", trace.len());
+            for (i, (step, _)) in trace.iter().enumerate() {
+                println!("  {:>3}. {}", i + 1, describe_step(step));
+            }
+            println!("final state:");
+            let last = trace.last().map_or(&init, |(_, v)| v);
+            for (id, host) in last.hosts.iter().enumerate() {
+                println!("  {}", describe_host(id, host));
+            }
+            ExitCode::FAILURE
+        },
+    }
+}
@@ -0,0 +1,123 @@
+use paxos_checker::{
+    simulate::{describe_action, simulate, Config},
+    trace::{format_trace, from_simulation},
+};
+use std::{env, fs, process::ExitCode};
+
+const USAGE: &str = "\
+usage: simulate [options]
+  --seed S           first seed (0)
+  --runs R           seeds S..S+R (1)
+  --steps N          scheduling decisions per run (2000)
+  --hosts N          number of hosts (3)
+  --failures F       tolerated failures, and most hosts down at once (1)
+  --keys K           instances 0..K (2)
+  --values V,..      one client request per operation (1,2,3)
+  --drop P           percent of packets dropped (10)
+  --duplicate P      percent of packets delivered twice (10)
+  --max-delay T      most ticks a packet waits before it is due (5)
+  --prepare P        percent chance an idle host starts a new ballot (5)
+  --prepare-from P   percent of new ballots started with a PrepareFrom (0)
+  --batch P          percent of local steps that send a batch (0)
+  --crash P          percent chance a chosen host crashes (1)
+  --recover P        percent chance a chosen down host recovers (20)
+  --proposers H,..   hosts that may prepare ballots (all)
+  --learners H,..    hosts that count Accepted messages and decide on their own (none)
+  --fast-clients C,.. clients whose requests go straight to the acceptors at the fast ballot (none)
+  --trace            print every step of the last run
+  --log FILE         write the last run as a trace for validate_trace";
+
+fn parse<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    value.and_then(|value| value.parse().ok()).ok_or(format!("{flag} needs a numeric argument"))
+}
+
+fn hosts(flag: &str, value: Option<String>) -> Result<Vec<u64>, String> {
+    value
+        .and_then(|hosts| hosts.split(',').map(|host| host.parse().ok()).collect())
+        .ok_or(format!("{flag} needs a comma-separated list of host ids"))
+}
+
+fn main() -> ExitCode {
+    let mut config = Config::default();
+    let (mut first_seed, mut runs) = (0, 1);
+    let mut print_trace = false;
+    let mut log = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let parsed = match flag.as_str() {
+            "--seed" => parse(&flag, args.next()).map(|s| first_seed = s),
+            "--runs" => parse(&flag, args.next()).map(|r| runs = r),
+            "--steps" => parse(&flag, args.next()).map(|n| config.steps = n),
+            "--hosts" => parse(&flag, args.next()).map(|n| config.num_hosts = n),
+            "--failures" => parse(&flag, args.next()).map(|f| config.num_failures = f),
+            "--keys" => parse(&flag, args.next()).map(|k| config.num_keys = k),
+            "--drop" => parse(&flag, args.next()).map(|p| config.drop_percent = p),
+            "--duplicate" => parse(&flag, args.next()).map(|p| config.duplicate_percent = p),
+            "--max-delay" => parse(&flag, args.next()).map(|t| config.max_delay = t),
+            "--prepare" => parse(&flag, args.next()).map(|p| config.prepare_percent = p),
+            "--prepare-from" => parse(&flag, args.next()).map(|p| config.prepare_from_percent = p),
+            "--batch" => parse(&flag, args.next()).map(|p| config.batch_percent = p),
+            "--crash" => parse(&flag, args.next()).map(|p| config.crash_percent = p),
+            "--recover" => parse(&flag, args.next()).map(|p| config.recover_percent = p),
+            "--proposers" => hosts(&flag, args.next()).map(|hosts| config.proposers = Some(hosts)),
+            "--learners" => hosts(&flag, args.next()).map(|hosts| config.learners = hosts),
+            "--fast-clients" => hosts(&flag, args.next()).map(|clients| config.fast_clients = clients),
+            "--values" => args
+                .next()
+                .and_then(|values| values.split(',').map(|value| value.parse().ok()).collect::<Option<Vec<_>>>())
+                .map(|values| config.values = values)
+                .ok_or(format!("{flag} needs a comma-separated list of integers")),
+            "--trace" => {
+                print_trace = true;
+                Ok(())
+            },
+            "--log" => args.next().map(|path| log = Some(path)).ok_or(format!("{flag} needs a file name")),
+            "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            },
+            _ => Err(format!("unknown option {flag}")),
+        };
+        if let Err(error) = parsed {
+            eprintln!("{error}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if config.num_hosts <= 2 * config.num_failures {
+        eprintln!("need hosts >= 2 * failures + 1");
+        return ExitCode::FAILURE;
+    }
+
+    for run in 0..runs {
+        let seed = first_seed + run;
+        let report = simulate(&config, seed);
+        if report.violation.is_some() || (print_trace && run + 1 == runs) {
+            for step in &report.trace {
+                println!("  {}", describe_action(step));
+            }
+        }
+
+        if let (Some(path), true) = (&log, run + 1 == runs) {
+            if let Err(error) = fs::write(path, format_trace(&from_simulation(&report.trace))) {
+                eprintln!("{path}: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+
+        println!(
+            "seed {seed}: {} steps, {} dropped, {} duplicated, decided {:?}",
+            report.trace.len(),
+            report.dropped,
+            report.duplicated,
+            report.decided,
+        );
+        if let Some(step) = report.violation {
+            println!("agreement violated after step {} (replay with --seed {seed})", step + 1);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    ExitCode::SUCCESS
+}
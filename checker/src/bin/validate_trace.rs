@@ -0,0 +1,91 @@
+use paxos_checker::{
+    low_level::{quorum::{all_hosts, QuorumSystem}, Constants},
+    trace::{format_record, parse_trace, validate, Verdict},
+};
+use std::{env, fs, io::Read, process::ExitCode};
+
+const USAGE: &str = "\
+usage: validate_trace [options] [FILE]
+  reads the trace from FILE, or from standard input without one
+  --hosts N          number of hosts (3)
+  --failures F       tolerated failures (1)
+  --phase1 N         phase-1 quorum size (hosts - failures)
+  --phase2 N         phase-2 quorum size (hosts - failures)";
+
+fn parse<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    value.and_then(|value| value.parse().ok()).ok_or(format!("{flag} needs a numeric argument"))
+}
+
+fn main() -> ExitCode {
+    let (mut num_hosts, mut num_failures) = (3, 1);
+    let (mut phase1_size, mut phase2_size) = (None, None);
+    let mut path = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let parsed = match flag.as_str() {
+            "--hosts" => parse(&flag, args.next()).map(|n| num_hosts = n),
+            "--failures" => parse(&flag, args.next()).map(|f| num_failures = f),
+            "--phase1" => parse(&flag, args.next()).map(|n| phase1_size = Some(n)),
+            "--phase2" => parse(&flag, args.next()).map(|n| phase2_size = Some(n)),
+            "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            },
+            _ if !flag.starts_with("--") && path.is_none() => {
+                path = Some(flag);
+                Ok(())
+            },
+            _ => Err(format!("unknown option {flag}")),
+        };
+        if let Err(error) = parsed {
+            eprintln!("{error}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let quorums = QuorumSystem {
+        acceptors: all_hosts(num_hosts),
+        phase1_size: phase1_size.unwrap_or(num_hosts.saturating_sub(num_failures)),
+        phase2_size: phase2_size.unwrap_or(num_hosts.saturating_sub(num_failures)),
+    };
+    let c = Constants::new(num_hosts, num_failures, quorums, false);
+    if !c.well_formed() {
+        eprintln!("constants are not well-formed: need hosts >= 2 * failures + 1, phase1 + phase2 > hosts and both phases <= hosts - failures");
+        return ExitCode::FAILURE;
+    }
+
+    let text = match &path {
+        Some(path) => fs::read_to_string(path).map_err(|error| format!("{path}: {error}")),
+        None => {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text).map(|_| text).map_err(|error| error.to_string())
+        },
+    };
+    let records = match text.map_err(|error| error.to_string()).and_then(|text| {
+        parse_trace(&text).map_err(|(line, error)| format!("line {line}: {error}"))
+    }) {
+        Ok(records) => records,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        },
+    };
+
+    match validate(&c, &records) {
+        Verdict::Valid { steps } => {
+            println!("all {steps} steps are legal and every state is safe");
+            ExitCode::SUCCESS
+        },
+        Verdict::IllegalStep { step, record, reason } => {
+            println!("step {} is not a legal host step: {reason}", step + 1);
+            println!("  {}", format_record(&record));
+            ExitCode::FAILURE
+        },
+        Verdict::Unsafe { step, record } => {
+            println!("step {} breaks safety", step + 1);
+            println!("  {}", format_record(&record));
+            ExitCode::FAILURE
+        },
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    low_level::{batch, batch_successors, host, host_successors, init, invariants, network, some_host_decided, Constants, Message, NetworkOperation, Transition, Variables},
+    Event, Value,
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+// What keeps the state space finite. The spec itself allows unboundedly many keys, ballots, client
+// requests and pending entries; everything past these bounds is cut off, not checked.
+#[derive(Clone, Debug)]
+pub struct Bounds {
+    pub num_keys: u64,
+    pub max_ballot: u64,
+    // Only hosts `0..proposers` run `send_prepare`.
+    pub proposers: usize,
+    // Client `i` submits the operation `values[i]` once, with `seq` 0.
+    pub values: Vec<i64>,
+    pub max_pending: usize,
+    pub network_faults: bool,
+    pub crashes: bool,
+    // Also take the `batch_next` steps.
+    pub batching: bool,
+}
+
+impl Default for Bounds {
+    fn default() -> Bounds {
+        Bounds { num_keys: 1, max_ballot: 3, proposers: 2, values: vec![1, 2], max_pending: 1, network_faults: false, crashes: false, batching: false }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub transition: Transition,
+    pub event: Event,
+}
+
+fn within_bounds(bounds: &Bounds, u: &Variables, step: &Step, v: &Variables) -> bool {
+    match &step.transition {
+        Transition::HostStep { host_id, net_op, .. } | Transition::BatchStep { host_id, net_op, .. } => {
+            let host_id = *host_id;
+            let pending_grew = v.hosts[host_id].pending.len() > u.hosts[host_id].pending.len();
+
+            let over_ballot = matches!(net_op.send, Some(Message::Prepare { ballot, .. } | Message::PrepareFrom { ballot, .. }) if ballot.num > bounds.max_ballot || host_id >= bounds.proposers);
+            let over_pending = pending_grew && v.hosts[host_id].pending.len() > bounds.max_pending;
+
+            !over_ballot
+                && !over_pending
+                && (bounds.crashes || v.hosts[host_id].up == u.hosts[host_id].up)
+        },
+        _ => bounds.network_faults,
+    }
+}
+
+// Every successor of `u` under `low_level::next` that stays within `bounds`.
+pub fn next(c: &Constants, bounds: &Bounds, u: &Variables) -> Vec<(Step, Variables)> {
+    let mut steps = Vec::new();
+
+    for host_id in 0..u.hosts.len() {
+        if u.hosts[host_id].up {
+            for (client_id, op) in bounds.values.iter().enumerate() {
+                let value = Value { client_id: client_id as u64, seq: 0, op: *op };
+                let message = Message::ClientRequest { value };
+                if !u.network.sent_messages.contains(&message) {
+                    let net_op = NetworkOperation { send: Some(message), recv: None };
+                    let event = Event::Submit { value };
+                    let network = network::step(&c.network, &u.network, &net_op).unwrap();
+                    let transition = Transition::HostStep { host_id, instance: 0, net_op };
+                    steps.push((Step { transition, event }, u.after_host_step(host_id, u.hosts[host_id].clone(), network)));
+                }
+            }
+        }
+
+        for key in 0..bounds.num_keys {
+            for (transition, event, v) in host_successors(c, u, host_id, key, None) {
+                steps.push((Step { transition, event }, v));
+            }
+        }
+        for message in u.network.in_flight_messages.iter().filter(|message| !batch::is_batch(message)) {
+            for key in network::receiving_instances(message) {
+                for (transition, event, v) in host_successors(c, u, host_id, key, Some(message)) {
+                    steps.push((Step { transition, event }, v));
+                }
+            }
+        }
+
+        if bounds.batching {
+            for (transition, event, v) in batch_successors(c, u, host_id, None) {
+                steps.push((Step { transition, event }, v));
+            }
+            for message in u.network.in_flight_messages.iter().filter(|message| batch::is_batch(message)) {
+                for (transition, event, v) in batch_successors(c, u, host_id, Some(message)) {
+                    steps.push((Step { transition, event }, v));
+                }
+            }
+        }
+    }
+
+    for message in &u.network.in_flight_messages {
+        let v = u.with_network(network::drop(&u.network, message));
+        steps.push((Step { transition: Transition::DropStep { message: message.clone() }, event: Event::NoOp }, v));
+    }
+    for message in u.network.sent_messages.difference(&u.network.in_flight_messages) {
+        let v = u.with_network(network::duplicate(&u.network, message).unwrap());
+        steps.push((Step { transition: Transition::DuplicateStep { message: message.clone() }, event: Event::NoOp }, v));
+    }
+    for key in (0..bounds.num_keys).filter(|key| some_host_decided(u, *key)) {
+        let v = u.with_network(network::garbage_collect(&u.network, key));
+        if v != *u {
+            steps.push((Step { transition: Transition::GarbageCollectStep { key }, event: Event::NoOp }, v));
+        }
+    }
+
+    steps.retain(|(step, v)| within_bounds(bounds, u, step, v));
+    steps
+}
+
+pub enum Outcome {
+    // Every reachable state within the bounds satisfies every invariant.
+    Verified { states: usize, depth: usize },
+    // `trace` is a shortest path from `init` to the first violating state found.
+    Violation { violated: Vec<&'static str>, init: Variables, trace: Vec<(Step, Variables)> },
+    // The search stopped after `max_states` states without finding a violation.
+    Incomplete { states: usize, depth: usize },
+}
+
+// Replays `steps` from `init`, returning every state along the way.
+pub fn replay(c: &Constants, bounds: &Bounds, steps: &[Step]) -> Option<Vec<Variables>> {
+    let mut states = vec![init(c)];
+    for step in steps {
+        let (_, v) = next(c, bounds, states.last().unwrap()).into_iter().find(|(s, _)| s == step)?;
+        states.push(v);
+    }
+    Some(states)
+}
+
+fn fingerprint(u: &Variables) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    u.hash(&mut hasher);
+    hasher.finish()
+}
+
+// Breadth-first search from `init`, checking `invariants::INVARIANTS` on every state as it is
+// discovered, so the first violation found is at the smallest possible depth. Like TLC, it only
+// remembers a 64-bit fingerprint of each visited state and the step that reached it; a fingerprint
+// collision could hide a state, and traces are rebuilt by replaying the steps.
+pub fn check(c: &Constants, bounds: &Bounds, max_states: usize) -> Outcome {
+    check_invariants(c, bounds, max_states, invariants::INVARIANTS)
+}
+
+// `check` against any list of invariants, such as a single one under investigation.
+pub fn check_invariants(c: &Constants, bounds: &Bounds, max_states: usize, invariants: &[(&'static str, invariants::Invariant)]) -> Outcome {
+    let broken = |u: &Variables| -> Vec<&'static str> {
+        invariants.iter().filter(|(_, invariant)| !invariant(c, u)).map(|(name, _)| *name).collect()
+    };
+    let initial = init(c);
+    let mut visited = HashSet::from([fingerprint(&initial)]);
+    let mut parents: Vec<Option<(usize, Step)>> = vec![None];
+    let mut queue = VecDeque::new();
+
+    let violation = |parents: &Vec<Option<(usize, Step)>>, mut current: usize, violated: Vec<&'static str>| {
+        let mut steps = Vec::new();
+        while let Some((parent, step)) = &parents[current] {
+            steps.push(step.clone());
+            current = *parent;
+        }
+        steps.reverse();
+
+        let mut states = replay(c, bounds, &steps).unwrap();
+        let init = states.remove(0);
+        Outcome::Violation { violated, init, trace: steps.into_iter().zip(states).collect() }
+    };
+
+    let violated = broken(&initial);
+    if !violated.is_empty() {
+        return violation(&parents, 0, violated);
+    }
+    queue.push_back((0, 0, initial));
+
+    let mut depth = 0;
+    while let Some((current, current_depth, u)) = queue.pop_front() {
+        depth = current_depth;
+        for (step, v) in next(c, bounds, &u) {
+            if !visited.insert(fingerprint(&v)) {
+                continue;
+            }
+            if parents.len() >= max_states {
+                return Outcome::Incomplete { states: parents.len(), depth };
+            }
+
+            let id = parents.len();
+            parents.push(Some((current, step)));
+            let violated = broken(&v);
+            if !violated.is_empty() {
+                return violation(&parents, id, violated);
+            }
+            queue.push_back((id, current_depth + 1, v));
+        }
+    }
+
+    Outcome::Verified { states: parents.len(), depth }
+}
+
+pub fn describe_step(step: &Step) -> String {
+    let transition = match &step.transition {
+        Transition::HostStep { host_id, net_op, .. } | Transition::BatchStep { host_id, net_op } => {
+            let mut text = match &step.transition {
+                Transition::HostStep { instance, .. } => format!("host {host_id}, key {instance}"),
+                _ => format!("host {host_id}, batch"),
+            };
+            if let Some(message) = &net_op.recv {
+                text += &format!(", recv {message:?}");
+            }
+            if let Some(message) = &net_op.send {
+                text += &format!(", send {message:?}");
+            }
+            text
+        },
+        Transition::DropStep { message } => format!("drop {message:?}"),
+        Transition::DuplicateStep { message } => format!("duplicate {message:?}"),
+        Transition::GarbageCollectStep { key } => format!("garbage-collect key {key}"),
+    };
+
+    match &step.event {
+        Event::NoOp => transition,
+        event => format!("{transition} [{event:?}]"),
+    }
+}
+
+pub fn describe_host(id: usize, host: &host::Variables) -> String {
+    let mut text = format!("host {id}{}:", if host.up { "" } else { " (down)" });
+    for (key, instance) in &host.instances {
+        text += &format!(
+            "\n    key {key}: current {:?}, max seen {:?}, accept {:?} = {:?}, decide {:?}",
+            instance.current_ballot, instance.max_seen_ballot, instance.accept_ballot, instance.accept_value, instance.decide_value,
+        );
+        let volatile = &instance.volatile;
+        for (ballot, promises) in &volatile.promised {
+            text += &format!("\n      {ballot:?}: promised {promises:?}, proposed {:?}, accepted {:?}", volatile.proposed_value.get(ballot), volatile.accepted.get(ballot));
+        }
+    }
+    if !host.pending.is_empty() {
+        text += &format!("\n    pending {:?}", host.pending);
+    }
+    text
+}
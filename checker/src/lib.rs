@@ -0,0 +1,79 @@
+// Executable mirror of the Verus specification in `src/distributed_system`, for checking small
+// configurations by brute force. Nothing here is verified: every function is a transcription of the
+// spec function of the same name and has to be kept in step with it by hand.
+pub mod explore;
+pub mod low_level;
+pub mod simulate;
+pub mod tla;
+pub mod trace;
+
+use std::{fmt, str::FromStr};
+
+// A client operation together with the id `(client_id, seq)` of the request that carries it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Value {
+    pub client_id: u64,
+    pub seq: u64,
+    pub op: i64,
+}
+
+impl Value {
+    pub fn request(&self) -> (u64, u64) {
+        (self.client_id, self.seq)
+    }
+
+    pub fn is_no_op(&self) -> bool {
+        self.client_id == NO_OP_CLIENT
+    }
+}
+
+// The client id of no-ops, which no client uses.
+pub const NO_OP_CLIENT: u64 = u64::MAX;
+
+// What a leader proposes in instance `key` when no request is waiting.
+pub fn no_op(key: u64) -> Value {
+    Value { client_id: NO_OP_CLIENT, seq: key, op: 0 }
+}
+
+// `<client_id>:<seq>:<op>`, the form traces and command lines use.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.client_id, self.seq, self.op)
+    }
+}
+
+impl FromStr for Value {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Value, ()> {
+        let mut fields = text.split(':');
+        let (Some(client_id), Some(seq), Some(op), None) = (fields.next(), fields.next(), fields.next(), fields.next()) else { return Err(()) };
+        Ok(Value { client_id: client_id.parse().map_err(|_| ())?, seq: seq.parse().map_err(|_| ())?, op: op.parse().map_err(|_| ())? })
+    }
+}
+
+// A batch event owns its entries, so unlike `Value` this is not `Copy`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Event {
+    Decide { key: u64, value: Value },
+    Propose { key: u64, value: Value },
+    Submit { value: Value },
+    ProposeBatch { entries: Vec<(u64, Value)> },
+    DecideBatch { entries: Vec<(u64, Value)> },
+    NoOp,
+}
+
+impl Event {
+    pub fn is_batch(&self) -> bool {
+        matches!(self, Event::ProposeBatch { .. } | Event::DecideBatch { .. })
+    }
+
+    // The single-key events a batch event stands for, one per entry and in order.
+    pub fn unbatch(&self) -> Vec<Event> {
+        match self {
+            Event::ProposeBatch { entries } => entries.iter().map(|&(key, value)| Event::Propose { key, value }).collect(),
+            Event::DecideBatch { entries } => entries.iter().map(|&(key, value)| Event::Decide { key, value }).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use super::{
+    host::{self, Ballot, Constants, Variables},
+    Message, NetworkOperation,
+};
+use crate::{Event, Value};
+use std::collections::BTreeSet;
+
+pub fn is_batch(message: &Message) -> bool {
+    matches!(message, Message::AcceptBatch { .. } | Message::AcceptedBatch { .. } | Message::DecideBatch { .. })
+}
+
+pub fn entries(message: &Message) -> &[(u64, Value)] {
+    match message {
+        Message::AcceptBatch { entries, .. } => entries,
+        Message::AcceptedBatch { entries, .. } => entries,
+        Message::DecideBatch { entries, .. } => entries,
+        _ => &[],
+    }
+}
+
+pub fn unbatch(message: &Message) -> Vec<Message> {
+    match message {
+        Message::AcceptBatch { ballot, entries } => {
+            entries.iter().map(|&(key, value)| Message::Accept { key, ballot: *ballot, value }).collect()
+        },
+        Message::AcceptedBatch { sender, ballot, entries } => {
+            entries.iter().map(|&(key, value)| Message::Accepted { key, sender: *sender, ballot: *ballot, value }).collect()
+        },
+        Message::DecideBatch { ballot, entries } => {
+            entries.iter().map(|&(key, value)| Message::Decide { key, ballot: *ballot, value }).collect()
+        },
+        _ => Vec::new(),
+    }
+}
+
+pub fn has_distinct_keys(entries: &[(u64, Value)]) -> bool {
+    entries.iter().map(|(key, _)| key).collect::<BTreeSet<_>>().len() == entries.len()
+}
+
+// The ballots `propose_batch` and `send_decide_batch` can batch at: those some instance of `u` is at.
+pub fn ballots(u: &Variables) -> BTreeSet<Ballot> {
+    u.instances.values().map(|instance| instance.current_ballot).collect()
+}
+
+// The spec lets a leader batch any of its proposals at `ballot`; this proposes in every instance at
+// `ballot` that `send_accept` can propose in, in key order. Each proposal may take a pending request,
+// so it runs on the host as the ones before it left it (`propose_entries`).
+pub fn propose_batch(c: &Constants, u: &Variables, ballot: Ballot) -> Option<(Event, Variables, Option<Message>)> {
+    let mut v = u.clone();
+    let mut entries = Vec::new();
+    for (key, instance) in &u.instances {
+        if instance.current_ballot != ballot {
+            continue;
+        }
+        if let Some((s, Some(Message::Accept { value, .. }))) = host::send_accept(c, &v, *key) {
+            v = s;
+            entries.push((*key, value));
+        }
+    }
+    if entries.is_empty() {
+        return None;
+    }
+
+    Some((Event::ProposeBatch { entries: entries.clone() }, v, Some(Message::AcceptBatch { ballot, entries })))
+}
+
+pub fn accept_batch(c: &Constants, u: &Variables, recv: &Message) -> host::Outcome {
+    let Message::AcceptBatch { ballot, entries } = recv else { return None };
+    if entries.is_empty() || !has_distinct_keys(entries) {
+        return None;
+    }
+    for (key, _) in entries {
+        let current_ballot = u.instances.get(key)?.current_ballot;
+        if !c.is_acceptor() || current_ballot < host::floor_for(u, *key) || *ballot < current_ballot {
+            return None;
+        }
+    }
+
+    let mut v = u.clone();
+    for (key, value) in entries {
+        let current = v.instances.get_mut(key).unwrap();
+        current.current_ballot = *ballot;
+        current.max_seen_ballot = current.max_seen_ballot.max(*ballot);
+        current.accept_ballot = Some(*ballot);
+        current.accept_value = Some(*value);
+    }
+    Some((v, Some(Message::AcceptedBatch { sender: c.id, ballot: *ballot, entries: entries.clone() })))
+}
+
+pub fn accepted_batch(u: &Variables, recv: &Message) -> host::Outcome {
+    let Message::AcceptedBatch { sender, ballot, entries } = recv else { return None };
+    if entries.is_empty() || !has_distinct_keys(entries) {
+        return None;
+    }
+    for (key, _) in entries {
+        if !u.instances.get(key)?.volatile.accepted.contains_key(ballot) {
+            return None;
+        }
+    }
+
+    let mut v = u.clone();
+    for (key, _) in entries {
+        v.instances.get_mut(key).unwrap().volatile.accepted.get_mut(ballot).unwrap().insert(*sender);
+    }
+    Some((v, None))
+}
+
+// Every decision `send_decide` would announce at `ballot`, in key order.
+pub fn send_decide_batch(c: &Constants, u: &Variables, ballot: Ballot) -> host::Outcome {
+    let entries: Vec<_> = u
+        .instances
+        .iter()
+        .filter(|(_, instance)| instance.current_ballot == ballot)
+        .filter_map(|(key, _)| match host::send_decide(c, u, *key)? {
+            (_, Some(Message::Decide { key, value, .. })) => Some((key, value)),
+            _ => None,
+        })
+        .collect();
+    if entries.is_empty() {
+        return None;
+    }
+
+    Some((u.clone(), Some(Message::DecideBatch { ballot, entries })))
+}
+
+// A host takes every decision of a DecideBatch at once, checking each as it would a Decide.
+pub fn decide_batch(u: &Variables, recv: &Message) -> Option<(Event, Variables, Option<Message>)> {
+    let Message::DecideBatch { ballot, entries } = recv else { return None };
+    if entries.is_empty() || !has_distinct_keys(entries) {
+        return None;
+    }
+    for (key, _) in entries {
+        if *ballot < u.instances.get(key)?.current_ballot {
+            return None;
+        }
+    }
+
+    let mut v = u.clone();
+    for (key, value) in entries {
+        let current = v.instances.get_mut(key).unwrap();
+        current.current_ballot = *ballot;
+        current.max_seen_ballot = current.max_seen_ballot.max(*ballot);
+        current.decide_value = Some(*value);
+    }
+    Some((Event::DecideBatch { entries: entries.clone() }, v, None))
+}
+
+// Every `(event, successor, sent message)` that `batch::step` allows for `recv`.
+pub fn successors(c: &Constants, u: &Variables, recv: Option<&Message>) -> Vec<(Event, Variables, Option<Message>)> {
+    let no_op = |outcome: host::Outcome| outcome.map(|(v, send)| (Event::NoOp, v, send));
+    if !u.up {
+        return Vec::new();
+    }
+
+    match recv {
+        None => ballots(u).into_iter().flat_map(|ballot| no_op(send_decide_batch(c, u, ballot)).into_iter().chain(propose_batch(c, u, ballot))).collect(),
+        Some(message) => no_op(accept_batch(c, u, message))
+            .into_iter()
+            .chain(no_op(accepted_batch(u, message)))
+            .chain(decide_batch(u, message))
+            .collect(),
+    }
+}
+
+// `batch::step` as a predicate over a given successor and sent message.
+pub fn step(c: &Constants, u: &Variables, v: &Variables, recv: Option<&Message>, send: Option<&Message>, event: &Event) -> bool {
+    successors(c, u, recv)
+        .into_iter()
+        .any(|(e, successor, sent)| e == *event && successor == *v && sent.as_ref() == send)
+}
+
+// `batch::is_run`: `states` goes from its first to its last element by one single-key `host::step`
+// per element of `steps`.
+pub fn is_run(c: &Constants, states: &[Variables], steps: &[(u64, NetworkOperation, Event)]) -> bool {
+    states.len() == steps.len() + 1
+        && steps.iter().enumerate().all(|(j, (key, net_op, event))| {
+            host::step(c, &states[j], &states[j + 1], *key, net_op.recv.as_ref(), net_op.send.as_ref(), event)
+        })
+}
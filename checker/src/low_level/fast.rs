@@ -0,0 +1,59 @@
+use super::{
+    host::{get_max_accepted_value, is_fast_ballot, Ballot},
+    quorum::QuorumSystem,
+};
+use crate::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+// `fast::FastQuorumSystem` whose fast quorums are every set of at least `fast_size` acceptors of
+// `classic`, as in `fast_quorums_by_size`. `is_fast_ballot` is always `host::is_fast_ballot`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FastQuorumSystem {
+    pub classic: QuorumSystem,
+    pub fast_size: u64,
+}
+
+impl FastQuorumSystem {
+    pub fn is_fast_quorum(&self, hosts: &BTreeSet<u64>) -> bool {
+        self.classic.members(hosts) >= self.fast_size
+    }
+
+    // `well_formed`: any two fast quorums and a phase-1 quorum share a host, by the size argument of
+    // `fast_quorums_by_size_are_well_formed`.
+    pub fn well_formed(&self, num_failures: u64) -> bool {
+        self.classic.well_formed(num_failures) && self.classic.phase1_size + 2 * self.fast_size > 2 * self.classic.size()
+    }
+}
+
+// The smallest fast quorums `classic` admits. For majority quorums that is `fast_quorum_size`, ⌈3n/4⌉.
+pub fn smallest_fast_quorums(classic: QuorumSystem) -> FastQuorumSystem {
+    FastQuorumSystem { classic, fast_size: (2 * classic.size()).saturating_sub(classic.phase1_size) / 2 + 1 }
+}
+
+// Some fast quorum may have accepted `(ballot, value)`: enough hosts either reported it or did not
+// report at all.
+pub fn value_may_have_been_chosen(fq: &FastQuorumSystem, promises: &BTreeMap<u64, Option<(Ballot, Value)>>, ballot: Ballot, value: Value) -> bool {
+    let may_have_accepted = (0..64)
+        .filter(|h| fq.classic.contains(*h))
+        .filter(|h| promises.get(h).is_none_or(|accepted| *accepted == Some((ballot, value))))
+        .collect();
+    fq.is_fast_quorum(&may_have_accepted)
+}
+
+// The spec `choose`s among the values that may have been chosen. Over a phase-1 quorum at most one
+// can be (`at_most_one_value_may_have_been_chosen`), and it was reported (`selected_value_was_reported`).
+// Otherwise the value of the lowest-numbered host at `ballot` is taken (`is_lowest_reporting_sender`).
+pub fn select_value(fq: &FastQuorumSystem, promises: &BTreeMap<u64, Option<(Ballot, Value)>>) -> Option<(Ballot, Value)> {
+    let max_accepted_value = get_max_accepted_value(promises)?;
+    let ballot = max_accepted_value.0;
+    if !is_fast_ballot(ballot) {
+        return Some(max_accepted_value);
+    }
+
+    let mut reported = promises.values().flatten().filter(|(b, _)| *b == ballot);
+    reported
+        .clone()
+        .find(|(_, value)| value_may_have_been_chosen(fq, promises, ballot, *value))
+        .or_else(|| reported.next())
+        .copied()
+}
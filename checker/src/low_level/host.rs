@@ -0,0 +1,653 @@
+use super::{
+    fast::{select_value, FastQuorumSystem},
+    quorum::QuorumSystem,
+    reconfig::Reconfiguration,
+    Message,
+};
+use crate::{no_op, Event, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+// The derived order compares `num` and then `pid`, which is `host::Ballot::cmp`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ballot {
+    pub num: u64,
+    pub pid: u64,
+}
+
+// `host::fast_ballot`: every instance starts at it, and every ballot a host prepares is above it.
+pub fn fast_ballot() -> Ballot {
+    Ballot { num: 0, pid: 0 }
+}
+
+pub fn is_fast_ballot(ballot: Ballot) -> bool {
+    ballot.num == 0
+}
+
+// `host::fast_slot`: a fast client numbers its requests by the instance they are for.
+pub fn fast_slot(value: Value) -> u64 {
+    value.seq
+}
+
+// Every host is an acceptor: `quorums` always spans `all_hosts(num_hosts)`. The fast quorums of the
+// spec's `fast` are the sets of at least `fast_size` hosts, and the clients in `fast_clients` send
+// their requests to the acceptors directly. These are the initial configuration, which decided
+// commands can replace (`reconfig`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constants {
+    pub id: u64,
+    pub num_hosts: u64,
+    pub num_failures: u64,
+    pub quorums: QuorumSystem,
+    pub fast_size: u64,
+    pub proposers: BTreeSet<u64>,
+    pub learners: BTreeSet<u64>,
+    pub fast_clients: BTreeSet<u64>,
+    pub reconfig: Reconfiguration,
+}
+
+// `configuration` is fixed when `init_request` creates the instance; see `host::Instance`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instance {
+    pub current_ballot: Ballot,
+    pub max_seen_ballot: Ballot,
+    pub accept_ballot: Option<Ballot>,
+    pub accept_value: Option<Value>,
+    pub decide_value: Option<Value>,
+    pub configuration: FastQuorumSystem,
+    pub volatile: Volatile,
+}
+
+// What `recover` empties; see `host::Volatile`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Volatile {
+    pub promised: BTreeMap<Ballot, BTreeMap<u64, Option<(Ballot, Value)>>>,
+    pub proposed_value: BTreeMap<Ballot, Value>,
+    pub accepted: BTreeMap<Ballot, BTreeSet<u64>>,
+    pub learned: BTreeMap<(Ballot, Value), BTreeSet<u64>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Variables {
+    pub instances: BTreeMap<u64, Instance>,
+    pub pending: Vec<Value>,
+    pub assigned: BTreeSet<(u64, u64)>,
+    pub up: bool,
+    pub multi: MultiPaxos,
+}
+
+// `promise_floor` and `floor_from` survive a crash; `leading` does not. See `host::MultiPaxos`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MultiPaxos {
+    pub promise_floor: Ballot,
+    pub floor_from: u64,
+    pub leading: Option<(u64, Ballot)>,
+}
+
+impl Constants {
+    pub fn is_proposer(&self) -> bool {
+        self.proposers.contains(&self.id)
+    }
+
+    pub fn is_acceptor(&self) -> bool {
+        self.quorums.contains(self.id)
+    }
+
+    pub fn is_learner(&self) -> bool {
+        self.learners.contains(&self.id)
+    }
+
+    pub fn is_fast_request(&self, value: Value) -> bool {
+        self.fast_clients.contains(&value.client_id)
+    }
+
+    pub fn owns_request(&self, client_id: u64) -> bool {
+        request_owner(&self.proposers, client_id) == self.id
+    }
+
+    pub fn fast_quorums(&self) -> FastQuorumSystem {
+        FastQuorumSystem { classic: self.quorums, fast_size: self.fast_size }
+    }
+
+    // The quorums of instance `key`, given the decisions `u` has learned.
+    pub fn configuration(&self, u: &Variables, key: u64) -> FastQuorumSystem {
+        self.reconfig.configuration(self.fast_quorums(), self.num_failures, &u.decisions(), key)
+    }
+
+    pub fn knows_configuration(&self, u: &Variables, key: u64) -> bool {
+        self.reconfig.knows_configuration(&u.decisions(), key)
+    }
+}
+
+impl Variables {
+    pub fn decisions(&self) -> BTreeMap<u64, Value> {
+        self.instances.iter().filter_map(|(key, instance)| Some((*key, instance.decide_value?))).collect()
+    }
+}
+
+// `request_owner` in the spec is any map into the proposers; clients are dealt to the proposers in
+// ascending order of id. `proposers` must not be empty.
+pub fn request_owner(proposers: &BTreeSet<u64>, client_id: u64) -> u64 {
+    let index = client_id % proposers.len() as u64;
+    *proposers.iter().nth(index as usize).unwrap()
+}
+
+// A transition's successor and the message it sends; `None` when the transition is not enabled.
+pub type Outcome = Option<(Variables, Option<Message>)>;
+
+pub fn init() -> Variables {
+    Variables {
+        instances: BTreeMap::new(),
+        pending: Vec::new(),
+        assigned: BTreeSet::new(),
+        up: true,
+        multi: MultiPaxos { promise_floor: Ballot { num: 0, pid: 0 }, floor_from: 0, leading: None },
+    }
+}
+
+pub fn crash(u: &Variables) -> Outcome {
+    if !u.up {
+        return None;
+    }
+
+    Some((Variables { up: false, ..u.clone() }, None))
+}
+
+// `host::durable_part`
+pub fn durable_part(instance: &Instance) -> Instance {
+    Instance { volatile: Volatile::default(), ..instance.clone() }
+}
+
+pub fn recover(u: &Variables) -> Outcome {
+    if u.up {
+        return None;
+    }
+
+    Some((
+        Variables {
+            instances: u.instances.iter().map(|(key, instance)| (*key, durable_part(instance))).collect(),
+            pending: Vec::new(),
+            assigned: u.assigned.clone(),
+            up: true,
+            multi: MultiPaxos { leading: None, ..u.multi },
+        },
+        None,
+    ))
+}
+
+pub fn submit_request(u: &Variables, value: Value) -> Outcome {
+    if value.is_no_op() {
+        return None;
+    }
+    Some((u.clone(), Some(Message::ClientRequest { value })))
+}
+
+pub fn is_queued(pending: &[Value], client_id: u64, seq: u64) -> bool {
+    pending.iter().any(|request| request.client_id == client_id && request.seq == seq)
+}
+
+// A request that is already queued or was already proposed is received without being queued again.
+// A fast client's requests go to `fast_accept` instead.
+pub fn receive_request(c: &Constants, u: &Variables, recv: &Message) -> Outcome {
+    let Message::ClientRequest { value } = *recv else { return None };
+    if !c.owns_request(value.client_id) || c.is_fast_request(value) {
+        return None;
+    }
+
+    let mut v = u.clone();
+    if !u.assigned.contains(&value.request()) && !is_queued(&u.pending, value.client_id, value.seq) {
+        v.pending.push(value);
+    }
+    Some((v, None))
+}
+
+// The ballot below which instance `key` may not act: the promise floor if the last PrepareFrom
+// covers `key`, and no bound otherwise.
+pub fn floor_for(u: &Variables, key: u64) -> Ballot {
+    if key >= u.multi.floor_from {
+        u.multi.promise_floor
+    } else {
+        Ballot { num: 0, pid: 0 }
+    }
+}
+
+// The Promise an acceptor of instance `key` sends for `ballot`, as in `promise`.
+pub fn promise_msg(c: &Constants, instance: &Instance, key: u64, ballot: Ballot) -> Message {
+    let accepted = instance.accept_ballot.map(|accept_ballot| (accept_ballot, instance.accept_value.unwrap()));
+    Message::Promise { key, sender: c.id, ballot, accepted }
+}
+
+// A new instance starts at its floor, and an acceptor promises the floor for it right away. The host
+// must have learned the decisions that fix the configuration of `key` first.
+pub fn init_request(c: &Constants, u: &Variables, key: u64) -> Outcome {
+    if u.instances.contains_key(&key) || !c.knows_configuration(u, key) {
+        return None;
+    }
+
+    let floor = floor_for(u, key);
+    let mut v = u.clone();
+    v.instances.insert(key, Instance {
+        current_ballot: floor,
+        max_seen_ballot: floor,
+        accept_ballot: None,
+        accept_value: None,
+        decide_value: None,
+        configuration: c.configuration(u, key),
+        volatile: Volatile::default(),
+    });
+    let send = (c.is_acceptor() && floor.num > 0).then(|| promise_msg(c, &v.instances[&key], key, floor));
+    Some((v, send))
+}
+
+// The lowest ballot owned by this host that is above everything it has seen for the instance.
+pub fn next_ballot(c: &Constants, instance: &Instance) -> Ballot {
+    Ballot { num: instance.max_seen_ballot.num + 1, pid: c.id }
+}
+
+pub fn send_prepare(c: &Constants, u: &Variables, key: u64) -> Outcome {
+    if !c.is_proposer() {
+        return None;
+    }
+    let instance = u.instances.get(&key)?;
+    let new_ballot = next_ballot(c, instance);
+
+    if instance.decide_value.is_some() {
+        return None;
+    }
+
+    let mut v = u.clone();
+    let instance = v.instances.get_mut(&key).unwrap();
+    instance.max_seen_ballot = new_ballot;
+    instance.volatile.promised.insert(new_ballot, BTreeMap::new());
+    instance.volatile.accepted.insert(new_ballot, BTreeSet::new());
+    Some((v, Some(Message::Prepare { key, ballot: new_ballot })))
+}
+
+// Multi-Paxos phase 1 for instance `key` and every instance above it. The spec allows any ballot of
+// this host above its floor and above every covered instance it has; this takes the lowest.
+pub fn send_prepare_from(c: &Constants, u: &Variables, key: u64) -> Outcome {
+    if !c.is_proposer() {
+        return None;
+    }
+
+    let highest = u.instances.range(key..).map(|(_, instance)| instance.max_seen_ballot.num).fold(u.multi.promise_floor.num, u64::max);
+    let ballot = Ballot { num: highest + 1, pid: c.id };
+    let mut v = u.clone();
+    v.multi.leading = Some((key, ballot));
+    Some((v, Some(Message::PrepareFrom { from: key, ballot })))
+}
+
+// The acceptor takes `ballot` as its floor from `from` on; the covered instances promise it later.
+pub fn promise_from(u: &Variables, key: u64, recv: &Message) -> Outcome {
+    let Message::PrepareFrom { from, ballot } = *recv else { return None };
+    if from != key || ballot <= u.multi.promise_floor {
+        return None;
+    }
+
+    let mut v = u.clone();
+    v.multi.promise_floor = ballot;
+    v.multi.floor_from = from;
+    Some((v, None))
+}
+
+// Moves an instance created before the PrepareFrom up to its floor, promising it.
+pub fn raise_to_floor(c: &Constants, u: &Variables, key: u64) -> Outcome {
+    let floor = floor_for(u, key);
+    let instance = u.instances.get(&key)?;
+    if instance.current_ballot >= floor {
+        return None;
+    }
+
+    let send = c.is_acceptor().then(|| promise_msg(c, instance, key, floor));
+    let mut v = u.clone();
+    let instance = v.instances.get_mut(&key).unwrap();
+    instance.current_ballot = floor;
+    instance.max_seen_ballot = instance.max_seen_ballot.max(floor);
+    Some((v, send))
+}
+
+// The leader of a PrepareFrom starts collecting promises for a covered instance under its ballot.
+pub fn join_prepare_from(u: &Variables, key: u64) -> Outcome {
+    let (from, ballot) = u.multi.leading?;
+    let instance = u.instances.get(&key)?;
+    if from > key
+        || instance.volatile.promised.contains_key(&ballot)
+        || instance.volatile.proposed_value.contains_key(&ballot)
+        || instance.volatile.accepted.contains_key(&ballot)
+        || instance.decide_value.is_some()
+    {
+        return None;
+    }
+
+    let mut v = u.clone();
+    let instance = v.instances.get_mut(&key).unwrap();
+    instance.max_seen_ballot = instance.max_seen_ballot.max(ballot);
+    instance.volatile.promised.insert(ballot, BTreeMap::new());
+    instance.volatile.accepted.insert(ballot, BTreeSet::new());
+    Some((v, None))
+}
+
+pub fn promise(c: &Constants, u: &Variables, key: u64, recv: &Message) -> Outcome {
+    let Message::Prepare { key: instance, ballot } = *recv else { return None };
+    if instance != key || !c.is_acceptor() {
+        return None;
+    }
+    let current = u.instances.get(&instance)?;
+    if current.current_ballot < floor_for(u, instance) || ballot <= current.current_ballot {
+        return None;
+    }
+
+    let accepted = current.accept_ballot.map(|accept_ballot| (accept_ballot, current.accept_value.unwrap()));
+    let mut v = u.clone();
+    let current = v.instances.get_mut(&instance).unwrap();
+    current.current_ballot = ballot;
+    current.max_seen_ballot = current.max_seen_ballot.max(ballot);
+    Some((v, Some(Message::Promise { key, sender: c.id, ballot, accepted })))
+}
+
+// An acceptor that has moved past a Prepare's ballot tells its owner which ballot it is at.
+pub fn reject_prepare(c: &Constants, u: &Variables, key: u64, recv: &Message) -> Outcome {
+    let Message::Prepare { key: instance, ballot } = *recv else { return None };
+    if instance != key || !c.is_acceptor() {
+        return None;
+    }
+    let higher = u.instances.get(&instance)?.current_ballot;
+    if higher < floor_for(u, instance) || ballot >= higher {
+        return None;
+    }
+
+    Some((u.clone(), Some(Message::Nack { key, ballot, higher })))
+}
+
+pub fn promised(u: &Variables, key: u64, recv: &Message) -> Outcome {
+    let Message::Promise { key: instance, sender, ballot, accepted } = *recv else { return None };
+    if instance != key {
+        return None;
+    }
+    let current = u.instances.get(&instance)?;
+    if !current.volatile.promised.contains_key(&ballot) || current.volatile.proposed_value.contains_key(&ballot) {
+        return None;
+    }
+
+    let mut v = u.clone();
+    v.instances.get_mut(&instance).unwrap().volatile.promised.get_mut(&ballot).unwrap().insert(sender, accepted);
+    Some((v, None))
+}
+
+pub fn max_accepted_value_by_ballot(a: Option<(Ballot, Value)>, b: Option<(Ballot, Value)>) -> Option<(Ballot, Value)> {
+    match (a, b) {
+        (None, None) => None,
+        (None, _) => b,
+        (_, None) => a,
+        (Some(a), Some(b)) => if a.0 >= b.0 { Some(a) } else { Some(b) },
+    }
+}
+
+// The spec folds over `dom().choose()`; this takes the senders in ascending order, which is one of
+// the choices it allows.
+pub fn get_max_accepted_value(accepted_map: &BTreeMap<u64, Option<(Ballot, Value)>>) -> Option<(Ballot, Value)> {
+    accepted_map.values().rev().fold(None, |rest, value| max_accepted_value_by_ballot(*value, rest))
+}
+
+pub fn send_accept(_c: &Constants, u: &Variables, key: u64) -> Outcome {
+    let instance = u.instances.get(&key)?;
+    let ballot = instance.current_ballot;
+    let promises = instance.volatile.promised.get(&ballot)?;
+
+    if !instance.configuration.classic.is_phase1_quorum(&promises.keys().copied().collect()) || instance.volatile.proposed_value.contains_key(&ballot) {
+        return None;
+    }
+
+    let mut v = u.clone();
+    let value = match select_value(&instance.configuration, promises) {
+        Some((_, value)) => value,
+        None if u.pending.is_empty() => no_op(key),
+        None => {
+            let request = v.pending.remove(0);
+            v.assigned.insert(request.request());
+            request
+        },
+    };
+    v.instances.get_mut(&key).unwrap().volatile.proposed_value.insert(ballot, value);
+    Some((v, Some(Message::Accept { key, ballot, value })))
+}
+
+pub fn accept(c: &Constants, u: &Variables, key: u64, recv: &Message) -> Outcome {
+    let Message::Accept { key: instance, ballot, value } = *recv else { return None };
+    if instance != key || !c.is_acceptor() {
+        return None;
+    }
+    let current = u.instances.get(&instance)?.current_ballot;
+    if current < floor_for(u, instance) || ballot < current {
+        return None;
+    }
+
+    let mut v = u.clone();
+    let current = v.instances.get_mut(&instance).unwrap();
+    current.current_ballot = ballot;
+    current.max_seen_ballot = current.max_seen_ballot.max(ballot);
+    current.accept_ballot = Some(ballot);
+    current.accept_value = Some(value);
+    Some((v, Some(Message::Accepted { key, sender: c.id, ballot, value })))
+}
+
+// The fast round: an acceptor takes a fast client's request straight off the network into its
+// `fast_slot`. It takes at most one value in the fast round, and only while it has promised no
+// classic ballot for the instance.
+pub fn fast_accept(c: &Constants, u: &Variables, key: u64, recv: &Message) -> Outcome {
+    let Message::ClientRequest { value } = *recv else { return None };
+    let instance = fast_slot(value);
+    if instance != key || !c.is_fast_request(value) || !c.is_acceptor() {
+        return None;
+    }
+    let current = u.instances.get(&instance)?;
+    if current.current_ballot < floor_for(u, instance) || current.current_ballot != fast_ballot() || current.accept_ballot.is_some() {
+        return None;
+    }
+
+    let mut v = u.clone();
+    let current = v.instances.get_mut(&instance).unwrap();
+    current.accept_ballot = Some(fast_ballot());
+    current.accept_value = Some(value);
+    Some((v, Some(Message::Accepted { key, sender: c.id, ballot: fast_ballot(), value })))
+}
+
+pub fn reject_accept(c: &Constants, u: &Variables, key: u64, recv: &Message) -> Outcome {
+    let Message::Accept { key: instance, ballot, .. } = *recv else { return None };
+    if instance != key || !c.is_acceptor() {
+        return None;
+    }
+    let higher = u.instances.get(&instance)?.current_ballot;
+    if higher < floor_for(u, instance) || ballot >= higher {
+        return None;
+    }
+
+    Some((u.clone(), Some(Message::Nack { key, ballot, higher })))
+}
+
+// The owner of a rejected ballot raises `max_seen_ballot` to `higher`, so its next `send_prepare`
+// outbids it; `current_ballot` stays at a ballot the host actually promised.
+pub fn nacked(c: &Constants, u: &Variables, key: u64, recv: &Message) -> Outcome {
+    let Message::Nack { key: instance, ballot, higher } = *recv else { return None };
+    if instance != key || ballot.pid != c.id {
+        return None;
+    }
+    if higher <= u.instances.get(&instance)?.max_seen_ballot {
+        return None;
+    }
+
+    let mut v = u.clone();
+    v.instances.get_mut(&instance).unwrap().max_seen_ballot = higher;
+    Some((v, None))
+}
+
+pub fn accepted(u: &Variables, key: u64, recv: &Message) -> Outcome {
+    let Message::Accepted { key: instance, sender, ballot, .. } = *recv else { return None };
+    if instance != key {
+        return None;
+    }
+    if !u.instances.get(&instance)?.volatile.accepted.contains_key(&ballot) {
+        return None;
+    }
+
+    let mut v = u.clone();
+    v.instances.get_mut(&instance).unwrap().volatile.accepted.get_mut(&ballot).unwrap().insert(sender);
+    Some((v, None))
+}
+
+// Acceptors send Accepted to the ballot owner and to every learner; a learner counts matching ones
+// per ballot and value, whichever host led the ballot.
+pub fn observe_accepted(c: &Constants, u: &Variables, key: u64, recv: &Message) -> Outcome {
+    let Message::Accepted { key: instance, sender, ballot, value } = *recv else { return None };
+    if instance != key || !c.is_learner() || !u.instances.contains_key(&instance) {
+        return None;
+    }
+
+    let mut v = u.clone();
+    let current = v.instances.get_mut(&instance).unwrap();
+    current.volatile.learned.entry((ballot, value)).or_default().insert(sender);
+    current.max_seen_ballot = current.max_seen_ballot.max(ballot);
+    Some((v, None))
+}
+
+// The fast round has no leader, so a learner decides it on a fast quorum rather than a phase-2 one.
+fn is_learner_quorum(instance: &Instance, ballot: Ballot, senders: &BTreeSet<u64>) -> bool {
+    if is_fast_ballot(ballot) {
+        instance.configuration.is_fast_quorum(senders)
+    } else {
+        instance.configuration.classic.is_phase2_quorum(senders)
+    }
+}
+
+// A learner that has counted a quorum of Accepted for `(ballot, value)` decides and announces it.
+pub fn learner_decide(c: &Constants, u: &Variables, key: u64, ballot: Ballot, value: Value) -> Outcome {
+    let instance = u.instances.get(&key)?;
+    if !c.is_learner() || !instance.volatile.learned.get(&(ballot, value)).is_some_and(|senders| is_learner_quorum(instance, ballot, senders)) {
+        return None;
+    }
+
+    let mut v = u.clone();
+    v.instances.get_mut(&key).unwrap().decide_value = Some(value);
+    Some((v, Some(Message::Decide { key, ballot, value })))
+}
+
+// The `(ballot, value)` pairs `learner_decide` can decide on for `key`.
+pub fn learnable(c: &Constants, u: &Variables, key: u64) -> Vec<(Ballot, Value)> {
+    match u.instances.get(&key) {
+        Some(instance) if c.is_learner() => instance
+            .volatile
+            .learned
+            .iter()
+            .filter(|((ballot, _), senders)| is_learner_quorum(instance, *ballot, senders))
+            .map(|(learned, _)| *learned)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+pub fn send_decide(_c: &Constants, u: &Variables, key: u64) -> Outcome {
+    let instance = u.instances.get(&key)?;
+    let ballot = instance.current_ballot;
+    let value = *instance.volatile.proposed_value.get(&ballot)?;
+
+    if !instance.configuration.classic.is_phase2_quorum(instance.volatile.accepted.get(&ballot)?) {
+        return None;
+    }
+
+    Some((u.clone(), Some(Message::Decide { key, ballot, value })))
+}
+
+// The leader that gathered an Accepted quorum for its current ballot learns the decision itself and announces it.
+pub fn learn(c: &Constants, u: &Variables, key: u64) -> Outcome {
+    let (mut v, send) = send_decide(c, u, key)?;
+    let Some(Message::Decide { value, .. }) = send else { return None };
+    v.instances.get_mut(&key).unwrap().decide_value = Some(value);
+    Some((v, send))
+}
+
+pub fn decide(u: &Variables, key: u64, recv: &Message) -> Outcome {
+    let Message::Decide { key: instance, ballot, value } = *recv else { return None };
+    if instance != key {
+        return None;
+    }
+    if ballot < u.instances.get(&instance)?.current_ballot {
+        return None;
+    }
+
+    let mut v = u.clone();
+    let current = v.instances.get_mut(&instance).unwrap();
+    current.current_ballot = ballot;
+    current.max_seen_ballot = current.max_seen_ballot.max(ballot);
+    current.decide_value = Some(value);
+    Some((v, None))
+}
+
+// Every `(event, successor, sent message)` that `host::step` allows for `key` and `recv`, except for
+// `Event::Submit`, whose request is a free parameter (see `submit_request`).
+pub fn successors(c: &Constants, u: &Variables, key: u64, recv: Option<&Message>) -> Vec<(Event, Variables, Option<Message>)> {
+    let mut steps = Vec::new();
+    let mut push = |event: Event, outcome: Outcome| {
+        if let Some((v, send)) = outcome {
+            steps.push((event, v, send));
+        }
+    };
+
+    match recv {
+        None => {
+            push(Event::NoOp, crash(u));
+            push(Event::NoOp, recover(u));
+            if u.up {
+                push(Event::NoOp, init_request(c, u, key));
+                push(Event::NoOp, send_prepare(c, u, key));
+                push(Event::NoOp, send_prepare_from(c, u, key));
+                push(Event::NoOp, raise_to_floor(c, u, key));
+                push(Event::NoOp, join_prepare_from(u, key));
+                push(Event::NoOp, send_decide(c, u, key));
+                if let Some((v, send)) = learn(c, u, key) {
+                    let value = v.instances[&key].decide_value.unwrap();
+                    push(Event::Decide { key, value }, Some((v, send)));
+                }
+                for (ballot, value) in learnable(c, u, key) {
+                    push(Event::Decide { key, value }, learner_decide(c, u, key, ballot, value));
+                }
+                if let Some((v, send)) = send_accept(c, u, key) {
+                    let value = v.instances[&key].volatile.proposed_value[&v.instances[&key].current_ballot];
+                    push(Event::Propose { key, value }, Some((v, send)));
+                }
+            }
+        },
+        Some(message) => {
+            if u.up {
+                push(Event::NoOp, promise(c, u, key, message));
+                push(Event::NoOp, promise_from(u, key, message));
+                push(Event::NoOp, reject_prepare(c, u, key, message));
+                push(Event::NoOp, promised(u, key, message));
+                push(Event::NoOp, accept(c, u, key, message));
+                if let Message::ClientRequest { value } = *message {
+                    push(Event::Propose { key, value }, fast_accept(c, u, key, message));
+                }
+                push(Event::NoOp, reject_accept(c, u, key, message));
+                push(Event::NoOp, nacked(c, u, key, message));
+                push(Event::NoOp, accepted(u, key, message));
+                push(Event::NoOp, observe_accepted(c, u, key, message));
+                push(Event::NoOp, receive_request(c, u, message));
+                if let Message::Decide { value, .. } = *message {
+                    push(Event::Decide { key, value }, decide(u, key, message));
+                }
+            }
+        },
+    }
+
+    steps
+}
+
+// `host::step` as a predicate over a given successor and sent message.
+pub fn step(c: &Constants, u: &Variables, v: &Variables, key: u64, recv: Option<&Message>, send: Option<&Message>, event: &Event) -> bool {
+    match event {
+        Event::Submit { value } => {
+            u.up && recv.is_none() && submit_request(u, *value) == Some((v.clone(), send.cloned()))
+        },
+        _ => successors(c, u, key, recv)
+            .into_iter()
+            .any(|(e, successor, sent)| e == *event && successor == *v && sent.as_ref() == send),
+    }
+}
@@ -0,0 +1,694 @@
+use super::{
+    batch,
+    fast::{select_value, FastQuorumSystem},
+    host::{fast_ballot, fast_slot, get_max_accepted_value, is_fast_ballot, request_owner, Ballot, Instance, Volatile},
+    network, quorum, safety, Constants, Message, Variables,
+};
+use crate::{no_op, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+// The conjuncts of `inductive` in `low_level/mod.rs`, one function per spec predicate and under the
+// same name. Quantifiers over ballots, senders and values are evaluated over the ones that actually
+// occur in the state, which is all the spec predicates can constrain. An index the spec leaves
+// unconstrained (a host id past `hosts.len()`, a missing map key) counts as a violation.
+pub type Invariant = fn(&Constants, &Variables) -> bool;
+
+// Every instance of every host, with the host's `history` for it. The leader maps of the spec
+// invariants are the ones in `history`; only `learned` is read from the instance itself.
+fn instances(u: &Variables) -> impl Iterator<Item = (usize, u64, &Instance, &Volatile)> {
+    u.hosts.iter().zip(&u.history).enumerate().flat_map(|(i, (host, history))| {
+        host.instances.iter().filter_map(move |(key, instance)| Some((i, *key, instance, history.get(key)?)))
+    })
+}
+
+fn instance(u: &Variables, host_id: u64, key: u64) -> Option<&Instance> {
+    u.hosts.get(host_id as usize)?.instances.get(&key)
+}
+
+fn history_of(u: &Variables, host_id: u64, key: u64) -> Option<&Volatile> {
+    instance(u, host_id, key)?;
+    u.history.get(host_id as usize)?.get(&key)
+}
+
+fn sent(u: &Variables) -> impl Iterator<Item = &Message> {
+    u.network.sent_messages.iter()
+}
+
+fn has_sent(u: &Variables, message: Message) -> bool {
+    u.network.sent_messages.contains(&message)
+}
+
+// A Prepare for `key`, or a PrepareFrom covering it, was sent with `ballot`.
+fn ballot_was_prepared(u: &Variables, key: u64, ballot: Ballot) -> bool {
+    has_sent(u, Message::Prepare { key, ballot })
+        || sent(u).any(|message| matches!(*message, Message::PrepareFrom { from, ballot: b } if from <= key && b == ballot))
+}
+
+fn members<V>(map: &BTreeMap<u64, V>) -> BTreeSet<u64> {
+    map.keys().copied().collect()
+}
+
+// `Variables::configuration`; an instance no host has created has none.
+fn configuration(u: &Variables, key: u64) -> Option<&FastQuorumSystem> {
+    u.configurations.get(&key)
+}
+
+// The value of every Decide that was sent, by instance. `all_decide_messages_hold_same_value` makes
+// this the only choice of `decisions` that `decisions_were_sent` can make.
+fn sent_decisions(u: &Variables) -> BTreeMap<u64, Value> {
+    sent(u)
+        .filter_map(|message| match *message {
+            Message::Decide { key, value, .. } => Some((key, value)),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn well_formed(c: &Constants, u: &Variables) -> bool {
+    u.well_formed(c)
+}
+
+pub fn in_flight_messages_are_sent(_c: &Constants, u: &Variables) -> bool {
+    u.network.in_flight_messages.is_subset(&u.network.sent_messages)
+}
+
+// Concrete maps and sets are finite by construction, and so is `sent_messages`.
+pub fn all_maps_and_sets_are_finite(_c: &Constants, _u: &Variables) -> bool {
+    true
+}
+
+pub fn all_map_keys_and_set_values_are_valid(c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, _, instance, history)| {
+        history.promised.values().all(|promises| promises.keys().all(|sender| *sender < c.num_hosts))
+            && history.accepted.values().all(|senders| senders.iter().all(|sender| *sender < c.num_hosts))
+            && instance.volatile.learned.values().all(|senders| senders.iter().all(|sender| *sender < c.num_hosts))
+    })
+}
+
+pub fn all_map_and_set_sizes_are_bounded(c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, _, _, history)| {
+        history.promised.values().all(|promises| promises.len() as u64 <= c.num_hosts)
+            && history.accepted.values().all(|senders| senders.len() as u64 <= c.num_hosts)
+    })
+}
+
+pub fn all_ballot_pids_in_all_maps_correspond_to_respective_host_id(c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(i, _, _, history)| {
+        let id = c.hosts[i].id;
+
+        history.promised.keys().all(|ballot| ballot.pid == id)
+            && history.proposed_value.keys().all(|ballot| ballot.pid == id)
+            && history.accepted.keys().all(|ballot| ballot.pid == id)
+    })
+}
+
+pub fn volatile_state_is_recorded_in_history(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, _, instance, history)| {
+        let volatile = &instance.volatile;
+        volatile.promised.iter().all(|(ballot, promises)| {
+            history.promised.get(ballot) == Some(promises)
+                && history.proposed_value.contains_key(ballot) == volatile.proposed_value.contains_key(ballot)
+                && history.accepted.contains_key(ballot) == volatile.accepted.contains_key(ballot)
+        }) && volatile.proposed_value.iter().all(|(ballot, value)| {
+            volatile.promised.contains_key(ballot) && history.proposed_value.get(ballot) == Some(value)
+        }) && volatile.accepted.iter().all(|(ballot, senders)| {
+            volatile.promised.contains_key(ballot) && history.accepted.get(ballot) == Some(senders)
+        })
+    })
+}
+
+pub fn leading_ballot_is_only_in_live_instances(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(i, key, instance, history)| match u.hosts[i].multi.leading {
+        Some((from, ballot)) if from <= key => {
+            let in_history = history.promised.contains_key(&ballot)
+                || history.proposed_value.contains_key(&ballot)
+                || history.accepted.contains_key(&ballot);
+            !in_history || instance.volatile.promised.contains_key(&ballot)
+        },
+        _ => true,
+    })
+}
+
+pub fn learned_is_recorded_in_history(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, _, instance, history)| {
+        instance.volatile.learned.iter().all(|(learned, senders)| history.learned.get(learned) == Some(senders))
+    })
+}
+
+// `is_valid_configuration`: quorums of hosts that tolerate `num_failures`. `is_fast_ballot` is the
+// same in every configuration by construction.
+pub fn configurations_are_valid(c: &Constants, u: &Variables) -> bool {
+    u.configurations.values().all(|fq| fq.classic.acceptors & !quorum::all_hosts(c.num_hosts) == 0 && fq.well_formed(c.num_failures))
+}
+
+pub fn configurations_were_decided(c: &Constants, u: &Variables) -> bool {
+    let decisions = sent_decisions(u);
+    u.configurations.iter().all(|(key, fq)| {
+        c.reconfig.knows_configuration(&decisions, *key) && *fq == c.reconfig.configuration(c.fast_quorums(), c.num_failures, &decisions, *key)
+    })
+}
+
+pub fn instances_use_recorded_configurations(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, instance, _)| configuration(u, key) == Some(&instance.configuration))
+}
+
+pub fn instances_know_their_configuration(c: &Constants, u: &Variables) -> bool {
+    u.hosts.iter().zip(&c.hosts).all(|(host, constants)| host.instances.keys().all(|key| constants.knows_configuration(host, *key)))
+}
+
+pub fn message_keys_have_configurations(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| network::message_key(message).is_none_or(|key| u.configurations.contains_key(&key)))
+}
+
+pub fn prepare_msg_in_network_implies_sender_map_has_ballot_key(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| match *message {
+        Message::Prepare { key, ballot } => history_of(u, ballot.pid, key)
+            .is_some_and(|leader| leader.promised.contains_key(&ballot) && leader.accepted.contains_key(&ballot)),
+        _ => true,
+    })
+}
+
+pub fn promise_msg_in_network_implies_sender_has_promised(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| match *message {
+        Message::Promise { key, sender, ballot, .. } => instance(u, sender, key).is_some_and(|acceptor| acceptor.current_ballot >= ballot),
+        _ => true,
+    })
+}
+
+pub fn accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| match *message {
+        Message::Accept { key, ballot, value } => history_of(u, ballot.pid, key).is_some_and(|leader| {
+            leader.promised.get(&ballot).is_some_and(|promises| configuration(u, key).is_some_and(|fq| fq.classic.is_phase1_quorum(&members(promises))))
+                && leader.proposed_value.get(&ballot) == Some(&value)
+        }),
+        _ => true,
+    })
+}
+
+pub fn accepted_msg_in_network_implies_sender_has_accepted_some_value(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| match *message {
+        Message::Accepted { key, sender, ballot, .. } => instance(u, sender, key).is_some_and(|acceptor| {
+            acceptor.current_ballot >= ballot
+                && acceptor.accept_ballot.is_some_and(|accept_ballot| accept_ballot >= ballot)
+                && acceptor.accept_value.is_some()
+        }),
+        _ => true,
+    })
+}
+
+fn accepted_senders(u: &Variables, key: u64, ballot: Ballot, value: Value) -> BTreeSet<u64> {
+    sent(u)
+        .filter_map(|message| match *message {
+            Message::Accepted { key: k, sender, ballot: b, value: v } if k == key && b == ballot && v == value => Some(sender),
+            _ => None,
+        })
+        .collect()
+}
+
+// `accepted_by` holds for some set exactly when it holds for every sender of a matching Accepted.
+pub fn quorum_has_accepted(_c: &Constants, u: &Variables, key: u64, ballot: Ballot, value: Value) -> bool {
+    configuration(u, key).is_some_and(|fq| fq.classic.is_phase2_quorum(&accepted_senders(u, key, ballot, value)))
+}
+
+// `fast_accepted_by`, likewise.
+pub fn fast_quorum_has_accepted(_c: &Constants, u: &Variables, key: u64, value: Value) -> bool {
+    configuration(u, key).is_some_and(|fq| fq.is_fast_quorum(&accepted_senders(u, key, fast_ballot(), value)))
+}
+
+// The Accept an acceptor took `(ballot, value)` from, or in the fast round the client's request.
+fn accept_was_sent(u: &Variables, key: u64, ballot: Ballot, value: Value) -> bool {
+    if is_fast_ballot(ballot) {
+        ballot == fast_ballot() && has_sent(u, Message::ClientRequest { value }) && key == fast_slot(value)
+    } else {
+        has_sent(u, Message::Accept { key, ballot, value })
+    }
+}
+
+pub fn decide_msg_in_network_implies_quorum_has_accepted_some_value(c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| match *message {
+        Message::Decide { key, ballot, value } if is_fast_ballot(ballot) => ballot == fast_ballot() && fast_quorum_has_accepted(c, u, key, value),
+        Message::Decide { key, ballot, value } => {
+            history_of(u, ballot.pid, key).is_some_and(|leader| leader.proposed_value.get(&ballot) == Some(&value))
+                && quorum_has_accepted(c, u, key, ballot, value)
+        },
+        _ => true,
+    })
+}
+
+pub fn nack_msg_in_network_implies_higher_ballot_was_prepared(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| match *message {
+        Message::Nack { key, ballot, higher } => higher > ballot && ballot_was_prepared(u, key, higher),
+        _ => true,
+    })
+}
+
+pub fn promised_state_implies_network_has_prepare_msg(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, instance, _)| {
+        instance.current_ballot.num == 0 || ballot_was_prepared(u, key, instance.current_ballot)
+    })
+}
+
+pub fn someone_promised_implies_network_has_their_promise_msg(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, _, history)| {
+        history.promised.iter().all(|(ballot, promises)| {
+            promises.iter().all(|(sender, accepted)| has_sent(u, Message::Promise { key, sender: *sender, ballot: *ballot, accepted: *accepted }))
+        })
+    })
+}
+
+pub fn if_accept_ballot_is_some_then_accept_value_is_some(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, _, instance, _)| instance.accept_ballot.is_some() == instance.accept_value.is_some())
+}
+
+pub fn accepted_state_implies_network_has_accept_message(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, instance, _)| match (instance.accept_ballot, instance.accept_value) {
+        (None, None) => true,
+        (Some(ballot), Some(value)) => accept_was_sent(u, key, ballot, value),
+        _ => false,
+    })
+}
+
+pub fn accepted_state_implies_network_has_accepted_message(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(i, key, instance, _)| match instance.accept_ballot {
+        Some(ballot) => instance.accept_value.is_some_and(|value| has_sent(u, Message::Accepted { key, sender: i as u64, ballot, value })),
+        None => true,
+    })
+}
+
+pub fn someone_accepted_implies_network_has_their_accepted_msg(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, _, history)| {
+        history.accepted.iter().all(|(ballot, senders)| {
+            senders.iter().all(|sender| {
+                history.proposed_value.get(ballot).is_some_and(|value| has_sent(u, Message::Accepted { key, sender: *sender, ballot: *ballot, value: *value }))
+            })
+        })
+    })
+}
+
+pub fn learned_senders_have_sent_accepted(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, instance, _)| {
+        instance.volatile.learned.iter().all(|((ballot, value), senders)| {
+            senders.iter().all(|sender| has_sent(u, Message::Accepted { key, sender: *sender, ballot: *ballot, value: *value }))
+        })
+    })
+}
+
+pub fn proposals_were_sent(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, _, history)| {
+        history.proposed_value.iter().all(|(ballot, value)| has_sent(u, Message::Accept { key, ballot: *ballot, value: *value }))
+    })
+}
+
+pub fn decided_state_implies_network_has_decide_message(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, instance, _)| match instance.decide_value {
+        Some(value) => sent(u).any(|message| matches!(*message, Message::Decide { key: k, value: v, .. } if k == key && v == value)),
+        None => true,
+    })
+}
+
+pub fn network_msgs_have_valid_sender_and_ballot_pid(_c: &Constants, u: &Variables) -> bool {
+    let valid = |id: u64| (id as usize) < u.hosts.len();
+
+    sent(u).all(|message| match *message {
+        Message::Prepare { ballot, .. } | Message::PrepareFrom { ballot, .. } => ballot.num > 0 && valid(ballot.pid),
+        Message::Promise { sender, ballot, accepted, .. } => {
+            ballot.num > 0 && valid(sender) && valid(ballot.pid) && accepted.is_none_or(|(accepted_ballot, _)| valid(accepted_ballot.pid))
+        },
+        Message::Accept { ballot, .. } => ballot.num > 0 && valid(ballot.pid),
+        Message::Accepted { sender, ballot, .. } => (ballot.num > 0 || ballot == fast_ballot()) && valid(sender) && valid(ballot.pid),
+        Message::Nack { ballot, higher, .. } => ballot.num > 0 && valid(ballot.pid) && higher.num > 0 && valid(higher.pid),
+        Message::Decide { ballot, .. } => ballot.num == 0 || valid(ballot.pid),
+        Message::ClientRequest { .. } => true,
+        Message::AcceptBatch { .. } | Message::AcceptedBatch { .. } | Message::DecideBatch { .. } => true,
+    })
+}
+
+pub fn promise_msgs_from_same_sender_for_same_ballot_have_same_accepted(_c: &Constants, u: &Variables) -> bool {
+    let mut seen = BTreeMap::new();
+
+    sent(u).all(|message| match *message {
+        Message::Promise { key, sender, ballot, accepted } => *seen.entry((key, sender, ballot)).or_insert(accepted) == accepted,
+        _ => true,
+    })
+}
+
+pub fn ballot_in_accepted_is_smaller_than_promise_message_ballot(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| match *message {
+        Message::Promise { ballot, accepted: Some((accepted_ballot, _)), .. } => accepted_ballot < ballot,
+        _ => true,
+    })
+}
+
+// Only a classic ballot has a leader whose proposal the reported value is.
+pub fn value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| match *message {
+        Message::Promise { key, accepted: Some((accepted_ballot, accepted_value)), .. } if !is_fast_ballot(accepted_ballot) => {
+            history_of(u, accepted_ballot.pid, key).is_some_and(|leader| leader.proposed_value.get(&accepted_ballot) == Some(&accepted_value))
+        },
+        _ => true,
+    })
+}
+
+pub fn if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| match *message {
+        Message::Promise { key, sender, accepted: Some((accepted_ballot, accepted_value)), .. } => {
+            has_sent(u, Message::Accepted { key, sender, ballot: accepted_ballot, value: accepted_value })
+        },
+        _ => true,
+    })
+}
+
+pub fn network_has_at_most_one_accept_message_for_any_ballot(_c: &Constants, u: &Variables) -> bool {
+    let mut seen = BTreeMap::new();
+
+    sent(u).all(|message| match *message {
+        Message::Accept { key, ballot, value } => *seen.entry((key, ballot)).or_insert(value) == value,
+        _ => true,
+    })
+}
+
+pub fn accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| match *message {
+        Message::Accepted { key, sender, ballot: accepted_ballot, .. } => sent(u).all(|other| match *other {
+            Message::Promise { key: k, sender: s, ballot: future_ballot, accepted } if k == key && s == sender && future_ballot > accepted_ballot => {
+                accepted.is_some_and(|(ballot, _)| ballot >= accepted_ballot)
+            },
+            _ => true,
+        }),
+        _ => true,
+    })
+}
+
+pub fn accepted_msg_in_network_implies_network_has_corresponding_accept_msg(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| match *message {
+        Message::Accepted { key, ballot, value, .. } => accept_was_sent(u, key, ballot, value),
+        _ => true,
+    })
+}
+
+pub fn fast_accepted_msgs_from_same_sender_have_same_value(_c: &Constants, u: &Variables) -> bool {
+    let mut seen = BTreeMap::new();
+
+    sent(u).all(|message| match *message {
+        Message::Accepted { key, sender, ballot, value } if ballot == fast_ballot() => *seen.entry((key, sender)).or_insert(value) == value,
+        _ => true,
+    })
+}
+
+pub fn all_decide_messages_hold_same_value(_c: &Constants, u: &Variables) -> bool {
+    let mut seen = BTreeMap::new();
+
+    sent(u).all(|message| match *message {
+        Message::Decide { key, value, .. } => *seen.entry(key).or_insert(value) == value,
+        _ => true,
+    })
+}
+
+pub fn batch_msgs_in_network_carry_sent_msgs(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| batch::unbatch(message).iter().all(|carried| u.network.sent_messages.contains(carried)))
+}
+
+pub fn if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, _, history)| {
+        history
+            .promised
+            .keys()
+            .chain(history.proposed_value.keys())
+            .chain(history.accepted.keys())
+            .all(|ballot| ballot_was_prepared(u, key, *ballot))
+    })
+}
+
+pub fn floors_and_leads_were_prepared_from(c: &Constants, u: &Variables) -> bool {
+    u.hosts.iter().zip(&c.hosts).all(|(host, constants)| {
+        let floor = host.multi.promise_floor;
+        (floor == Ballot { num: 0, pid: 0 } || has_sent(u, Message::PrepareFrom { from: host.multi.floor_from, ballot: floor }))
+            && host.multi.leading.is_none_or(|(from, ballot)| ballot.pid == constants.id && has_sent(u, Message::PrepareFrom { from, ballot }))
+    })
+}
+
+pub fn if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, _, history)| {
+        let Some(fq) = configuration(u, key) else { return false };
+        history.proposed_value.iter().all(|(ballot, value)| {
+            match history.promised.get(ballot).and_then(|promises| select_value(fq, promises)) {
+                Some((_, selected_value)) => *value == selected_value,
+                None => true,
+            }
+        })
+    })
+}
+
+pub fn host_accept_ballot_is_none_or_leq_to_current_ballot(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, _, instance, _)| instance.accept_ballot.is_none_or(|ballot| ballot <= instance.current_ballot))
+}
+
+pub fn host_ballots_are_at_most_max_seen_ballot(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, _, instance, history)| {
+        let max = instance.max_seen_ballot;
+        instance.current_ballot <= max
+            && history.promised.keys().all(|ballot| *ballot <= max)
+            && history.proposed_value.keys().all(|ballot| *ballot <= max)
+            && history.accepted.keys().all(|ballot| *ballot <= max)
+    })
+}
+
+pub fn any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, i1, _)| {
+        u.hosts.iter().all(|h2| match h2.instances.get(&key) {
+            Some(i2) if i1.accept_ballot.is_some_and(|ballot| !is_fast_ballot(ballot)) && i1.accept_ballot == i2.accept_ballot => {
+                i1.accept_value.is_some() && i1.accept_value == i2.accept_value
+            },
+            _ => true,
+        })
+    })
+}
+
+pub fn if_someone_has_accepted_then_someone_has_proposed(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, _, _, history)| {
+        history.accepted.iter().all(|(ballot, senders)| senders.is_empty() || history.proposed_value.contains_key(ballot))
+    })
+}
+
+pub fn same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, _, _, history)| {
+        history.promised.values().all(|promises| {
+            let mut seen = BTreeMap::new();
+            promises.values().flatten().filter(|(ballot, _)| !is_fast_ballot(*ballot)).all(|(ballot, value)| seen.entry(*ballot).or_insert(*value) == value)
+        })
+    })
+}
+
+pub fn pending_requests_are_in_network(_c: &Constants, u: &Variables) -> bool {
+    u.hosts.iter().all(|host| {
+        host.pending.iter().all(|request| has_sent(u, Message::ClientRequest { value: *request }))
+    })
+}
+
+// The `(key, value)` of every fast-ballot Accepted, which `value_was_fast_proposed` ranges over.
+fn fast_proposals(u: &Variables) -> impl Iterator<Item = (u64, Value)> + '_ {
+    sent(u).filter_map(|message| match *message {
+        Message::Accepted { key, ballot, value, .. } if ballot == fast_ballot() => Some((key, value)),
+        _ => None,
+    })
+}
+
+// The `(key, value)` of every proposal a leader made.
+fn classic_proposals(u: &Variables) -> impl Iterator<Item = (u64, Value)> + '_ {
+    instances(u).flat_map(|(_, key, _, history)| history.proposed_value.values().map(move |value| (key, *value)))
+}
+
+// The `(key, value)` of every proposal and every fast proposal.
+fn proposals(u: &Variables) -> impl Iterator<Item = (u64, Value)> + '_ {
+    classic_proposals(u).chain(fast_proposals(u))
+}
+
+pub fn submitted_values_are_not_no_ops(_c: &Constants, u: &Variables) -> bool {
+    sent(u).all(|message| !matches!(message, Message::ClientRequest { value } if value.is_no_op()))
+}
+
+// A leader proposes a request some client submitted, or the no-op of the instance. An acceptor
+// only takes a fast client's request in the fast round.
+pub fn proposed_values_were_submitted(c: &Constants, u: &Variables) -> bool {
+    let submitted = |value: Value| has_sent(u, Message::ClientRequest { value });
+    instances(u).all(|(_, key, _, history)| history.proposed_value.values().all(|value| submitted(*value) || *value == no_op(key)))
+        && fast_proposals(u).all(|(_, value)| submitted(value) && c.is_fast_request(value))
+}
+
+fn value_was_proposed(u: &Variables, value: Value) -> bool {
+    proposals(u).any(|(_, v)| v == value)
+}
+
+fn proposed_values(u: &Variables) -> impl Iterator<Item = Value> + '_ {
+    proposals(u).map(|(_, value)| value)
+}
+
+// Fast clients skip the owner, so no queue holds their requests.
+pub fn pending_requests_are_owned_and_distinct(c: &Constants, u: &Variables) -> bool {
+    u.hosts.iter().enumerate().all(|(i, host)| {
+        let ids: BTreeSet<(u64, u64)> = host.pending.iter().map(Value::request).collect();
+        ids.len() == host.pending.len()
+            && host.pending.iter().all(|request| request_owner(&c.proposers, request.client_id) == i as u64 && !c.is_fast_request(*request))
+    })
+}
+
+pub fn pending_requests_were_not_proposed(_c: &Constants, u: &Variables) -> bool {
+    u.hosts.iter().all(|host| host.pending.iter().all(|request| !value_was_proposed(u, *request)))
+}
+
+pub fn pending_requests_are_not_assigned(_c: &Constants, u: &Variables) -> bool {
+    u.hosts.iter().all(|host| host.pending.iter().all(|request| !host.assigned.contains(&request.request())))
+}
+
+// The owner of every proposed request has recorded it in `assigned` and will not queue it again.
+// No-ops and fast requests are never queued, so they have no owner to record them.
+pub fn proposed_requests_are_assigned(c: &Constants, u: &Variables) -> bool {
+    proposed_values(u).filter(|value| !value.is_no_op() && !c.is_fast_request(*value)).all(|value| {
+        u.hosts.get(request_owner(&c.proposers, value.client_id) as usize).is_some_and(|owner| owner.assigned.contains(&value.request()))
+    })
+}
+
+// Acceptors may take different operations for a fast request, but all of them for its `fast_slot`.
+pub fn proposed_requests_have_one_value(c: &Constants, u: &Variables) -> bool {
+    let mut seen = BTreeMap::new();
+    proposed_values(u).filter(|value| !c.is_fast_request(*value)).all(|value| *seen.entry(value.request()).or_insert(value) == value)
+}
+
+// A leader proposes a value in one instance only, and a fast request only in its `fast_slot`.
+pub fn proposed_values_are_proposed_in_one_slot(c: &Constants, u: &Variables) -> bool {
+    let mut slots = BTreeMap::new();
+    classic_proposals(u).all(|(key, value)| *slots.entry(value).or_insert(key) == key && (!c.is_fast_request(value) || key == fast_slot(value)))
+}
+
+pub fn if_host_proposed_then_quorum_has_promised(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, _, history)| {
+        history.proposed_value.keys().all(|ballot| {
+            history.promised.get(ballot).is_some_and(|promises| configuration(u, key).is_some_and(|fq| fq.classic.is_phase1_quorum(&members(promises))))
+        })
+    })
+}
+
+// The `(h1, h2, accepted_ballot, future_ballot)` for which `two_maps_contain_phase2_and_phase1_quorums`
+// holds on `instance` and `future_ballot.cmp(&accepted_ballot) > 0`, in the quorums of the instance.
+// An instance with no recorded configuration has none; `instances_use_recorded_configurations`
+// rules that out.
+fn accepted_and_future_quorums(u: &Variables) -> impl Iterator<Item = (&Volatile, &Volatile, Ballot, Ballot)> {
+    instances(u).filter_map(move |(_, key, _, i1)| Some((key, i1, *configuration(u, key)?))).flat_map(move |(key, i1, fq)| {
+        (0..u.hosts.len() as u64).filter_map(move |h2| history_of(u, h2, key)).flat_map(move |i2| {
+            i1.accepted
+                .iter()
+                .filter(move |(_, senders)| fq.classic.is_phase2_quorum(senders))
+                .flat_map(move |(accepted_ballot, _)| {
+                    i2.promised
+                        .iter()
+                        .filter(move |(future_ballot, promises)| {
+                            *future_ballot > accepted_ballot && fq.classic.is_phase1_quorum(&members(promises))
+                        })
+                        .map(move |(future_ballot, _)| (i1, i2, *accepted_ballot, *future_ballot))
+                })
+        })
+    })
+}
+
+pub fn if_system_accepted_exists_some_accept_value_in_future_promise_quorum(_c: &Constants, u: &Variables) -> bool {
+    accepted_and_future_quorums(u).all(|(i1, i2, accepted_ballot, future_ballot)| {
+        i2.promised[&future_ballot]
+            .iter()
+            .any(|(sender, accepted)| accepted.is_some() && i1.accepted[&accepted_ballot].contains(sender))
+    })
+}
+
+pub fn accepted_system_calculates_same_proposed_value_in_future(_c: &Constants, u: &Variables) -> bool {
+    accepted_and_future_quorums(u).all(|(i1, i2, accepted_ballot, future_ballot)| {
+        let old_accepted_value = i1.proposed_value.get(&accepted_ballot).copied();
+        let calculated_new_proposed = get_max_accepted_value(&i2.promised[&future_ballot]);
+
+        calculated_new_proposed.is_some() && calculated_new_proposed.map(|(_, value)| value) == old_accepted_value
+    })
+}
+
+pub fn accepted_system_always_proposes_same_value_in_future(_c: &Constants, u: &Variables) -> bool {
+    instances(u).all(|(_, key, _, history)| {
+        let Some(fq) = configuration(u, key) else { return false };
+        history.accepted.iter().filter(|(_, senders)| fq.classic.is_phase2_quorum(senders)).all(|(accepted_ballot, _)| {
+            (0..u.hosts.len()).filter_map(|j| Some((j, history_of(u, j as u64, key)?))).all(|(j, future)| {
+                future
+                    .proposed_value
+                    .iter()
+                    .filter(|(future_ballot, _)| future_ballot.pid as usize == j && *future_ballot >= accepted_ballot)
+                    .all(|(_, value)| history.proposed_value.get(accepted_ballot) == Some(value))
+            })
+        })
+    })
+}
+
+// `safety` followed by the conjuncts of `inductive`, in the order `inductive` lists them.
+pub const INVARIANTS: &[(&str, Invariant)] = &[
+    ("safety", safety),
+    ("well_formed", well_formed),
+    ("in_flight_messages.subset_of(sent_messages)", in_flight_messages_are_sent),
+    ("all_maps_and_sets_are_finite", all_maps_and_sets_are_finite),
+    ("all_map_keys_and_set_values_are_valid", all_map_keys_and_set_values_are_valid),
+    ("all_map_and_set_sizes_are_bounded", all_map_and_set_sizes_are_bounded),
+    ("all_ballot_pids_in_all_maps_correspond_to_respective_host_id", all_ballot_pids_in_all_maps_correspond_to_respective_host_id),
+    ("volatile_state_is_recorded_in_history", volatile_state_is_recorded_in_history),
+    ("leading_ballot_is_only_in_live_instances", leading_ballot_is_only_in_live_instances),
+    ("learned_is_recorded_in_history", learned_is_recorded_in_history),
+    ("configurations_are_valid", configurations_are_valid),
+    ("configurations_were_decided", configurations_were_decided),
+    ("instances_use_recorded_configurations", instances_use_recorded_configurations),
+    ("instances_know_their_configuration", instances_know_their_configuration),
+    ("message_keys_have_configurations", message_keys_have_configurations),
+    ("prepare_msg_in_network_implies_sender_map_has_ballot_key", prepare_msg_in_network_implies_sender_map_has_ballot_key),
+    ("promise_msg_in_network_implies_sender_has_promised", promise_msg_in_network_implies_sender_has_promised),
+    ("accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender", accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender),
+    ("accepted_msg_in_network_implies_sender_has_accepted_some_value", accepted_msg_in_network_implies_sender_has_accepted_some_value),
+    ("decide_msg_in_network_implies_quorum_has_accepted_some_value", decide_msg_in_network_implies_quorum_has_accepted_some_value),
+    ("nack_msg_in_network_implies_higher_ballot_was_prepared", nack_msg_in_network_implies_higher_ballot_was_prepared),
+    ("promised_state_implies_network_has_prepare_msg", promised_state_implies_network_has_prepare_msg),
+    ("someone_promised_implies_network_has_their_promise_msg", someone_promised_implies_network_has_their_promise_msg),
+    ("if_accept_ballot_is_some_then_accept_value_is_some", if_accept_ballot_is_some_then_accept_value_is_some),
+    ("accepted_state_implies_network_has_accept_message", accepted_state_implies_network_has_accept_message),
+    ("accepted_state_implies_network_has_accepted_message", accepted_state_implies_network_has_accepted_message),
+    ("someone_accepted_implies_network_has_their_accepted_msg", someone_accepted_implies_network_has_their_accepted_msg),
+    ("learned_senders_have_sent_accepted", learned_senders_have_sent_accepted),
+    ("proposals_were_sent", proposals_were_sent),
+    ("decided_state_implies_network_has_decide_message", decided_state_implies_network_has_decide_message),
+    ("network_msgs_have_valid_sender_and_ballot_pid", network_msgs_have_valid_sender_and_ballot_pid),
+    ("promise_msgs_from_same_sender_for_same_ballot_have_same_accepted", promise_msgs_from_same_sender_for_same_ballot_have_same_accepted),
+    ("ballot_in_accepted_is_smaller_than_promise_message_ballot", ballot_in_accepted_is_smaller_than_promise_message_ballot),
+    ("value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot", value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot),
+    ("if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message", if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message),
+    ("network_has_at_most_one_accept_message_for_any_ballot", network_has_at_most_one_accept_message_for_any_ballot),
+    ("accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted", accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted),
+    ("accepted_msg_in_network_implies_network_has_corresponding_accept_msg", accepted_msg_in_network_implies_network_has_corresponding_accept_msg),
+    ("fast_accepted_msgs_from_same_sender_have_same_value", fast_accepted_msgs_from_same_sender_have_same_value),
+    ("all_decide_messages_hold_same_value", all_decide_messages_hold_same_value),
+    ("batch_msgs_in_network_carry_sent_msgs", batch_msgs_in_network_carry_sent_msgs),
+    ("if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot", if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot),
+    ("floors_and_leads_were_prepared_from", floors_and_leads_were_prepared_from),
+    ("if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some", if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some),
+    ("host_accept_ballot_is_none_or_leq_to_current_ballot", host_accept_ballot_is_none_or_leq_to_current_ballot),
+    ("host_ballots_are_at_most_max_seen_ballot", host_ballots_are_at_most_max_seen_ballot),
+    ("any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value", any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value),
+    ("if_someone_has_accepted_then_someone_has_proposed", if_someone_has_accepted_then_someone_has_proposed),
+    ("same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts", same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts),
+    ("pending_requests_are_in_network", pending_requests_are_in_network),
+    ("submitted_values_are_not_no_ops", submitted_values_are_not_no_ops),
+    ("proposed_values_were_submitted", proposed_values_were_submitted),
+    ("pending_requests_are_owned_and_distinct", pending_requests_are_owned_and_distinct),
+    ("pending_requests_were_not_proposed", pending_requests_were_not_proposed),
+    ("pending_requests_are_not_assigned", pending_requests_are_not_assigned),
+    ("proposed_requests_are_assigned", proposed_requests_are_assigned),
+    ("proposed_requests_have_one_value", proposed_requests_have_one_value),
+    ("proposed_values_are_proposed_in_one_slot", proposed_values_are_proposed_in_one_slot),
+    ("if_host_proposed_then_quorum_has_promised", if_host_proposed_then_quorum_has_promised),
+    ("if_system_accepted_exists_some_accept_value_in_future_promise_quorum", if_system_accepted_exists_some_accept_value_in_future_promise_quorum),
+    ("accepted_system_calculates_same_proposed_value_in_future", accepted_system_calculates_same_proposed_value_in_future),
+    ("accepted_system_always_proposes_same_value_in_future", accepted_system_always_proposes_same_value_in_future),
+];
+
+// The names of the invariants `u` breaks.
+pub fn violated(c: &Constants, u: &Variables) -> Vec<&'static str> {
+    INVARIANTS.iter().filter(|(_, invariant)| !invariant(c, u)).map(|(name, _)| *name).collect()
+}
@@ -0,0 +1,292 @@
+pub mod batch;
+pub mod fast;
+pub mod host;
+pub mod invariants;
+pub mod network;
+pub mod quorum;
+pub mod reconfig;
+
+use crate::{Event, Value};
+use host::Ballot;
+use std::collections::{BTreeMap, BTreeSet};
+
+// A batch owns its entries, so unlike the spec's `Message` this one is not `Copy`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Message {
+    Prepare { key: u64, ballot: Ballot },
+    PrepareFrom { from: u64, ballot: Ballot },
+    Promise { key: u64, sender: u64, ballot: Ballot, accepted: Option<(Ballot, Value)> },
+    Accept { key: u64, ballot: Ballot, value: Value },
+    Accepted { key: u64, sender: u64, ballot: Ballot, value: Value },
+    Nack { key: u64, ballot: Ballot, higher: Ballot },
+    Decide { key: u64, ballot: Ballot, value: Value },
+    ClientRequest { value: Value },
+    AcceptBatch { ballot: Ballot, entries: Vec<(u64, Value)> },
+    AcceptedBatch { sender: u64, ballot: Ballot, entries: Vec<(u64, Value)> },
+    DecideBatch { ballot: Ballot, entries: Vec<(u64, Value)> },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NetworkOperation {
+    pub send: Option<Message>,
+    pub recv: Option<Message>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constants {
+    pub num_failures: u64,
+    pub num_hosts: u64,
+    pub hosts: Vec<host::Constants>,
+    pub network: network::Constants,
+    pub quorums: quorum::QuorumSystem,
+    pub fast_size: u64,
+    pub proposers: BTreeSet<u64>,
+    pub learners: BTreeSet<u64>,
+    pub fast_clients: BTreeSet<u64>,
+    pub reconfig: reconfig::Reconfiguration,
+}
+
+// `history` is the spec's ghost history of what each host has held in the volatile part of each
+// instance; `record` keeps it in step with the hosts. `configurations` is the spec's ghost map of the
+// quorums each instance was created with, which `record_configurations` only ever extends.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Variables {
+    pub hosts: Vec<host::Variables>,
+    pub network: network::Variables,
+    pub history: Vec<BTreeMap<u64, host::Volatile>>,
+    pub configurations: BTreeMap<u64, fast::FastQuorumSystem>,
+}
+
+impl Constants {
+    // Every host proposes and none of them learns; see `with_roles`. The fast quorums are the
+    // smallest `quorums` admits, no client is fast and no command takes effect; see
+    // `with_fast_clients` and `with_reconfig`.
+    pub fn new(num_hosts: u64, num_failures: u64, quorums: quorum::QuorumSystem, consume_on_receive: bool) -> Constants {
+        Constants {
+            num_failures,
+            num_hosts,
+            hosts: Vec::new(),
+            network: network::Constants { consume_on_receive },
+            quorums,
+            fast_size: fast::smallest_fast_quorums(quorums).fast_size,
+            proposers: BTreeSet::new(),
+            learners: BTreeSet::new(),
+            fast_clients: BTreeSet::new(),
+            reconfig: reconfig::Reconfiguration::none(),
+        }
+        .with_roles((0..num_hosts).collect(), BTreeSet::new())
+    }
+
+    pub fn with_roles(self, proposers: BTreeSet<u64>, learners: BTreeSet<u64>) -> Constants {
+        let (num_hosts, num_failures, quorums, fast_size) = (self.num_hosts, self.num_failures, self.quorums, self.fast_size);
+        let hosts = (0..num_hosts)
+            .map(|id| host::Constants {
+                id,
+                num_hosts,
+                num_failures,
+                quorums,
+                fast_size,
+                proposers: proposers.clone(),
+                learners: learners.clone(),
+                fast_clients: self.fast_clients.clone(),
+                reconfig: self.reconfig,
+            })
+            .collect();
+
+        Constants { hosts, proposers, learners, ..self }
+    }
+
+    pub fn with_fast_clients(mut self, fast_clients: BTreeSet<u64>) -> Constants {
+        for host in &mut self.hosts {
+            host.fast_clients = fast_clients.clone();
+        }
+        Constants { fast_clients, ..self }
+    }
+
+    // Commands decided in instance `j` take effect from `j + alpha` on.
+    pub fn with_reconfig(mut self, alpha: u64) -> Constants {
+        let reconfig = reconfig::Reconfiguration { alpha };
+        for host in &mut self.hosts {
+            host.reconfig = reconfig;
+        }
+        Constants { reconfig, ..self }
+    }
+
+    pub fn well_formed(&self) -> bool {
+        self.num_hosts > 0
+            && self.num_hosts > 2 * self.num_failures
+            && !self.proposers.is_empty()
+            && self.proposers.iter().all(|id| *id < self.num_hosts)
+            && self.hosts.len() as u64 == self.num_hosts
+            && self.num_hosts <= 64
+            && self.quorums.acceptors == quorum::all_hosts(self.num_hosts)
+            && self.quorums.well_formed(self.num_failures)
+            && self.fast_quorums().well_formed(self.num_failures)
+            && self.reconfig.well_formed()
+            && self.hosts.iter().enumerate().all(|(i, host)| {
+                host.id == i as u64
+                    && host.num_hosts == self.num_hosts
+                    && host.num_failures == self.num_failures
+                    && host.quorums == self.quorums
+                    && host.fast_size == self.fast_size
+                    && host.proposers == self.proposers
+                    && host.learners == self.learners
+                    && host.fast_clients == self.fast_clients
+                    && host.reconfig == self.reconfig
+            })
+    }
+
+    pub fn fast_quorums(&self) -> fast::FastQuorumSystem {
+        fast::FastQuorumSystem { classic: self.quorums, fast_size: self.fast_size }
+    }
+
+    pub fn is_fast_request(&self, value: Value) -> bool {
+        self.fast_clients.contains(&value.client_id)
+    }
+}
+
+impl Variables {
+    pub fn well_formed(&self, c: &Constants) -> bool {
+        c.well_formed()
+            && self.hosts.len() == c.hosts.len()
+            && self.history.len() == self.hosts.len()
+            && self.hosts.iter().zip(&self.history).all(|(host, history)| history.keys().eq(host.instances.keys()))
+    }
+
+    // The successor of a host step that leaves `host` at `host_id` and the network at `network`.
+    pub fn after_host_step(&self, host_id: usize, host: host::Variables, network: network::Variables) -> Variables {
+        let configurations = record_configurations(&self.configurations, &host);
+        let mut v = Variables { hosts: self.hosts.clone(), network, history: self.history.clone(), configurations };
+        v.history[host_id] = record(&self.history[host_id], &host);
+        v.hosts[host_id] = host;
+        v
+    }
+
+    // The successor of a step that only touches the network.
+    pub fn with_network(&self, network: network::Variables) -> Variables {
+        Variables { network, ..self.clone() }
+    }
+}
+
+// `merge`: every map of `volatile` on top of the same map of `history`.
+pub fn merge(history: &host::Volatile, volatile: &host::Volatile) -> host::Volatile {
+    let mut merged = history.clone();
+    merged.promised.extend(volatile.promised.iter().map(|(ballot, promises)| (*ballot, promises.clone())));
+    merged.proposed_value.extend(&volatile.proposed_value);
+    merged.accepted.extend(volatile.accepted.iter().map(|(ballot, senders)| (*ballot, senders.clone())));
+    merged.learned.extend(volatile.learned.iter().map(|(entry, senders)| (*entry, senders.clone())));
+    merged
+}
+
+// `record`: the history of a host after one of its steps.
+pub fn record(history: &BTreeMap<u64, host::Volatile>, host: &host::Variables) -> BTreeMap<u64, host::Volatile> {
+    host.instances
+        .iter()
+        .map(|(key, instance)| (*key, merge(&history.get(key).cloned().unwrap_or_default(), &instance.volatile)))
+        .collect()
+}
+
+// `record_configurations`: the configuration the host fixed for each instance it has that no host had
+// before. Recorded ones are never replaced.
+pub fn record_configurations(configurations: &BTreeMap<u64, fast::FastQuorumSystem>, host: &host::Variables) -> BTreeMap<u64, fast::FastQuorumSystem> {
+    let mut recorded = configurations.clone();
+    for (key, instance) in &host.instances {
+        recorded.entry(*key).or_insert(instance.configuration);
+    }
+    recorded
+}
+
+pub fn init(c: &Constants) -> Variables {
+    Variables {
+        hosts: c.hosts.iter().map(|_| host::init()).collect(),
+        network: network::init(),
+        history: vec![BTreeMap::new(); c.hosts.len()],
+        configurations: BTreeMap::new(),
+    }
+}
+
+// `BatchStep` is `batch_host_step`, which the spec keeps out of `Transition` as `batch_next`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Transition {
+    HostStep { host_id: usize, instance: u64, net_op: NetworkOperation },
+    BatchStep { host_id: usize, net_op: NetworkOperation },
+    DropStep { message: Message },
+    DuplicateStep { message: Message },
+    GarbageCollectStep { key: u64 },
+}
+
+pub fn some_host_decided(u: &Variables, key: u64) -> bool {
+    u.hosts.iter().any(|host| host.instances.get(&key).is_some_and(|instance| instance.decide_value.is_some()))
+}
+
+// Every `HostStep` of `host_id` on `instance` that receives `recv`, except for `Event::Submit`.
+pub fn host_successors(c: &Constants, u: &Variables, host_id: usize, instance: u64, recv: Option<&Message>) -> Vec<(Transition, Event, Variables)> {
+    host::successors(&c.hosts[host_id], &u.hosts[host_id], instance, recv)
+        .into_iter()
+        .filter_map(|(event, host, send)| {
+            let net_op = NetworkOperation { send, recv: recv.cloned() };
+            let network = network::step(&c.network, &u.network, &net_op)?;
+            Some((Transition::HostStep { host_id, instance, net_op }, event, u.after_host_step(host_id, host, network)))
+        })
+        .collect()
+}
+
+// Every `BatchStep` of `host_id` that receives `recv`.
+pub fn batch_successors(c: &Constants, u: &Variables, host_id: usize, recv: Option<&Message>) -> Vec<(Transition, Event, Variables)> {
+    batch::successors(&c.hosts[host_id], &u.hosts[host_id], recv)
+        .into_iter()
+        .filter_map(|(event, host, send)| {
+            let net_op = NetworkOperation { send, recv: recv.cloned() };
+            let network = network::step(&c.network, &u.network, &net_op)?;
+            Some((Transition::BatchStep { host_id, net_op }, event, u.after_host_step(host_id, host, network)))
+        })
+        .collect()
+}
+
+pub fn is_valid_transition(c: &Constants, u: &Variables, v: &Variables, transition: &Transition, event: &Event) -> bool {
+    if !u.well_formed(c) || !v.well_formed(c) {
+        return false;
+    }
+
+    match transition {
+        Transition::HostStep { host_id, instance, net_op } => {
+            *host_id < u.hosts.len()
+                && host::step(&c.hosts[*host_id], &u.hosts[*host_id], &v.hosts[*host_id], *instance, net_op.recv.as_ref(), net_op.send.as_ref(), event)
+                && (0..u.hosts.len()).all(|i| i == *host_id || u.hosts[i] == v.hosts[i])
+                && (0..u.history.len()).all(|i| v.history[i] == if i == *host_id { record(&u.history[i], &v.hosts[i]) } else { u.history[i].clone() })
+                && v.configurations == record_configurations(&u.configurations, &v.hosts[*host_id])
+                && network::step(&c.network, &u.network, net_op).as_ref() == Some(&v.network)
+        },
+        Transition::BatchStep { host_id, net_op } => {
+            *host_id < u.hosts.len()
+                && batch::step(&c.hosts[*host_id], &u.hosts[*host_id], &v.hosts[*host_id], net_op.recv.as_ref(), net_op.send.as_ref(), event)
+                && (0..u.hosts.len()).all(|i| i == *host_id || u.hosts[i] == v.hosts[i])
+                && (0..u.history.len()).all(|i| v.history[i] == if i == *host_id { record(&u.history[i], &v.hosts[i]) } else { u.history[i].clone() })
+                && v.configurations == record_configurations(&u.configurations, &v.hosts[*host_id])
+                && network::step(&c.network, &u.network, net_op).as_ref() == Some(&v.network)
+        },
+        Transition::DropStep { message } => {
+            *event == Event::NoOp && v.hosts == u.hosts && v.history == u.history && v.configurations == u.configurations && network::drop(&u.network, message) == v.network
+        },
+        Transition::DuplicateStep { message } => {
+            *event == Event::NoOp && v.hosts == u.hosts && v.history == u.history && v.configurations == u.configurations && network::duplicate(&u.network, message).as_ref() == Some(&v.network)
+        },
+        Transition::GarbageCollectStep { key } => {
+            *event == Event::NoOp && some_host_decided(u, *key) && v.hosts == u.hosts && v.history == u.history && v.configurations == u.configurations && network::garbage_collect(&u.network, *key) == v.network
+        },
+    }
+}
+
+pub fn safety(c: &Constants, u: &Variables) -> bool {
+    u.well_formed(c)
+        && u.hosts.iter().all(|h1| {
+            u.hosts.iter().all(|h2| {
+                h1.instances.iter().all(|(key, i1)| {
+                    match (i1.decide_value, h2.instances.get(key).and_then(|i2| i2.decide_value)) {
+                        (Some(v1), Some(v2)) => v1 == v2,
+                        _ => true,
+                    }
+                })
+            })
+        })
+}
@@ -0,0 +1,101 @@
+use super::{batch, host, Message, NetworkOperation};
+use std::collections::BTreeSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Constants {
+    pub consume_on_receive: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Variables {
+    pub sent_messages: BTreeSet<Message>,
+    pub in_flight_messages: BTreeSet<Message>,
+}
+
+pub fn message_key(message: &Message) -> Option<u64> {
+    match message {
+        Message::Prepare { key, .. } => Some(*key),
+        Message::PrepareFrom { .. } => None,
+        Message::Promise { key, .. } => Some(*key),
+        Message::Accept { key, .. } => Some(*key),
+        Message::Accepted { key, .. } => Some(*key),
+        Message::Nack { key, .. } => Some(*key),
+        Message::Decide { key, .. } => Some(*key),
+        Message::ClientRequest { .. } => None,
+        Message::AcceptBatch { .. } | Message::AcceptedBatch { .. } | Message::DecideBatch { .. } => None,
+    }
+}
+
+// The instance a host step that receives `message` runs on. A PrepareFrom is taken on its first
+// instance, a batch on the instance of its first entry, and a ClientRequest, which belongs to no
+// instance, on instance 0.
+pub fn receiving_instance(message: &Message) -> u64 {
+    match message {
+        Message::PrepareFrom { from, .. } => *from,
+        _ if batch::is_batch(message) => batch::entries(message).first().map_or(0, |(key, _)| *key),
+        _ => message_key(message).unwrap_or(0),
+    }
+}
+
+// Every instance a step that receives `message` may run on: a DecideBatch is taken one entry at a
+// time, on the instance of the entry, and a fast client's ClientRequest by `fast_accept` on its
+// `fast_slot`.
+pub fn receiving_instances(message: &Message) -> Vec<u64> {
+    match message {
+        Message::DecideBatch { entries, .. } => entries.iter().map(|(key, _)| *key).collect(),
+        Message::ClientRequest { value } if host::fast_slot(*value) != 0 => vec![0, host::fast_slot(*value)],
+        _ => vec![receiving_instance(message)],
+    }
+}
+
+pub fn is_collectable(message: &Message, key: u64) -> bool {
+    message_key(message) == Some(key) && !matches!(message, Message::Decide { .. })
+}
+
+pub fn init() -> Variables {
+    Variables { sent_messages: BTreeSet::new(), in_flight_messages: BTreeSet::new() }
+}
+
+// The successor of `step`, or `None` when `net_op.recv` is not in flight.
+pub fn step(c: &Constants, u: &Variables, net_op: &NetworkOperation) -> Option<Variables> {
+    let mut v = u.clone();
+
+    if let Some(message) = &net_op.recv {
+        if !u.in_flight_messages.contains(message) {
+            return None;
+        }
+        if c.consume_on_receive {
+            v.in_flight_messages.remove(message);
+        }
+    }
+    // A batch is recorded in the sent history together with every message it carries.
+    if let Some(message) = &net_op.send {
+        v.sent_messages.insert(message.clone());
+        v.sent_messages.extend(batch::unbatch(message));
+        v.in_flight_messages.insert(message.clone());
+    }
+
+    Some(v)
+}
+
+pub fn drop(u: &Variables, message: &Message) -> Variables {
+    let mut v = u.clone();
+    v.in_flight_messages.remove(message);
+    v
+}
+
+pub fn duplicate(u: &Variables, message: &Message) -> Option<Variables> {
+    if !u.sent_messages.contains(message) {
+        return None;
+    }
+
+    let mut v = u.clone();
+    v.in_flight_messages.insert(message.clone());
+    Some(v)
+}
+
+pub fn garbage_collect(u: &Variables, key: u64) -> Variables {
+    let mut v = u.clone();
+    v.in_flight_messages.retain(|message| !is_collectable(message, key));
+    v
+}
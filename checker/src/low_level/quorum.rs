@@ -0,0 +1,56 @@
+use std::collections::BTreeSet;
+
+// `quorum::quorums_by_size(acceptors, phase1_size, phase2_size)`, the only family the checker
+// instantiates. `acceptors` has bit `h` set for every acceptor `h`, so the checker runs at most 64
+// hosts; the initial quorums span `all_hosts(num_hosts)`, and those of a reconfiguration command
+// span its members.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct QuorumSystem {
+    pub acceptors: u64,
+    pub phase1_size: u64,
+    pub phase2_size: u64,
+}
+
+impl QuorumSystem {
+    pub fn contains(&self, host: u64) -> bool {
+        host < 64 && self.acceptors & (1 << host) != 0
+    }
+
+    pub fn size(&self) -> u64 {
+        self.acceptors.count_ones() as u64
+    }
+
+    pub fn members(&self, hosts: &BTreeSet<u64>) -> u64 {
+        hosts.iter().filter(|h| self.contains(**h)).count() as u64
+    }
+
+    pub fn is_phase1_quorum(&self, hosts: &BTreeSet<u64>) -> bool {
+        self.members(hosts) >= self.phase1_size
+    }
+
+    pub fn is_phase2_quorum(&self, hosts: &BTreeSet<u64>) -> bool {
+        self.members(hosts) >= self.phase2_size
+    }
+
+    // `well_formed` and `tolerates_failures` of `quorums_by_size`, by the size argument of
+    // `quorums_by_size_are_well_formed`.
+    pub fn well_formed(&self, num_failures: u64) -> bool {
+        self.phase1_size + self.phase2_size > self.size()
+            && self.phase1_size + num_failures <= self.size()
+            && self.phase2_size + num_failures <= self.size()
+    }
+}
+
+// `all_hosts(num_hosts)` as a set of acceptors; `num_hosts` is at most 64.
+pub fn all_hosts(num_hosts: u64) -> u64 {
+    if num_hosts >= 64 {
+        u64::MAX
+    } else {
+        (1 << num_hosts) - 1
+    }
+}
+
+pub fn majority_quorums(acceptors: u64, num_failures: u64) -> QuorumSystem {
+    let size = acceptors.count_ones() as u64;
+    QuorumSystem { acceptors, phase1_size: size.saturating_sub(num_failures), phase2_size: size.saturating_sub(num_failures) }
+}
@@ -0,0 +1,56 @@
+use super::{fast::FastQuorumSystem, quorum::majority_quorums};
+use crate::Value;
+use std::collections::BTreeMap;
+
+// The client id of reconfiguration commands, which no other client uses. A request of this client is
+// a command naming the acceptors whose bits are set in its `op`.
+pub const RECONFIG_CLIENT: u64 = u64::MAX - 1;
+
+// `reconfig::Reconfiguration`. The spec's `members` is any function into sets of acceptors; the
+// checker's is `members` below. `well_formed` cannot look at every value, so the simulator only
+// submits commands that name at least `2 * num_failures + 1` acceptors, and
+// `configurations_are_valid` checks every configuration that comes of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reconfiguration {
+    pub alpha: u64,
+}
+
+impl Reconfiguration {
+    // An `alpha` past every key the checker reaches: no command takes effect, and an instance needs no
+    // decision to start. This is what the TLA+ spec and the executable hosts run.
+    pub fn none() -> Reconfiguration {
+        Reconfiguration { alpha: u64::MAX }
+    }
+
+    pub fn well_formed(&self) -> bool {
+        self.alpha > 0
+    }
+
+    pub fn members(&self, value: Value) -> Option<u64> {
+        (value.client_id == RECONFIG_CLIENT).then_some(value.op as u64)
+    }
+
+    // Every decision `j` with `j + alpha <= key` is in `decisions`.
+    pub fn knows_configuration(&self, decisions: &BTreeMap<u64, Value>, key: u64) -> bool {
+        key < self.alpha || (0..=key - self.alpha).all(|j| decisions.contains_key(&j))
+    }
+
+    // The quorums of instance `key`: those of the last command decided at or below `key - alpha`, or
+    // `initial` if there is none. The spec recurses on `key - 1`; this walks down instead.
+    pub fn configuration(&self, initial: FastQuorumSystem, num_failures: u64, decisions: &BTreeMap<u64, Value>, key: u64) -> FastQuorumSystem {
+        let mut key = key;
+        while key > 0 && key >= self.alpha {
+            if let Some(members) = decisions.get(&(key - self.alpha)).and_then(|value| self.members(*value)) {
+                return member_configuration(members, num_failures);
+            }
+            key -= 1;
+        }
+        initial
+    }
+}
+
+// `majority_quorums` over `members`, with fast quorums of ⌈3n/4⌉ of them.
+pub fn member_configuration(members: u64, num_failures: u64) -> FastQuorumSystem {
+    let size = members.count_ones() as u64;
+    FastQuorumSystem { classic: majority_quorums(members, num_failures), fast_size: (3 * size).div_ceil(4) }
+}
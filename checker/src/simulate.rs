@@ -0,0 +1,428 @@
+use crate::{
+    low_level::{batch, host, init, network, quorum, reconfig::RECONFIG_CLIENT, safety, Constants, Message, NetworkOperation, Transition, Variables},
+    Event, Value,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+// SplitMix64. Every random choice the simulator makes is drawn from one generator, so a run is a
+// function of its seed and `Config`.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in `0..bound`; `bound` must be positive.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    pub fn percent(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+}
+
+// Rates are percentages, drawn once per packet (drop, duplicate) or per scheduling decision
+// (prepare, crash, recover).
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub num_hosts: u64,
+    pub num_failures: u64,
+    pub num_keys: u64,
+    // Client `i` submits the operation `values[i]`, with `seq` 0, or if it is one of `fast_clients`
+    // with `seq` `i % num_keys`, the instance it goes to.
+    pub values: Vec<i64>,
+    pub steps: usize,
+    pub drop_percent: u64,
+    pub duplicate_percent: u64,
+    // A packet is delivered between 0 and `max_delay` ticks after it is sent; the simulator picks
+    // uniformly among the packets that are due, so delivery order is shuffled as well.
+    pub max_delay: u64,
+    pub prepare_percent: u64,
+    // Of the ballots a host starts, the percentage it starts with a PrepareFrom for its key and every
+    // key above it rather than a Prepare for the key alone.
+    pub prepare_from_percent: u64,
+    pub crash_percent: u64,
+    pub recover_percent: u64,
+    // A client resubmits one of its earlier requests, as it would after a timeout.
+    pub resubmit_percent: u64,
+    // Of the local steps a host takes, the percentage it spends sending the Decides, or failing that
+    // proposing, in every instance at the key's ballot as one batch.
+    pub batch_percent: u64,
+    // `None` makes every host a proposer.
+    pub proposers: Option<Vec<u64>>,
+    pub learners: Vec<u64>,
+    // Clients whose requests the acceptors take straight into the fast round.
+    pub fast_clients: Vec<u64>,
+    // Commands decided in instance `j` take effect from `j + alpha` on; `None` runs without
+    // reconfiguration.
+    pub reconfig_alpha: Option<u64>,
+    // After the requests in `values`, `RECONFIG_CLIENT` submits command `i` with `seq` `i`, naming
+    // the hosts whose bits are set in `commands[i]`; each must name at least `2 * num_failures + 1`.
+    pub commands: Vec<u64>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            num_hosts: 3,
+            num_failures: 1,
+            num_keys: 2,
+            values: vec![1, 2, 3],
+            steps: 2_000,
+            drop_percent: 10,
+            duplicate_percent: 10,
+            max_delay: 5,
+            prepare_percent: 5,
+            prepare_from_percent: 0,
+            crash_percent: 1,
+            recover_percent: 20,
+            resubmit_percent: 0,
+            batch_percent: 0,
+            proposers: None,
+            learners: Vec::new(),
+            fast_clients: Vec::new(),
+            reconfig_alpha: None,
+            commands: Vec::new(),
+        }
+    }
+}
+
+// One copy of a message on its way to one host.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Packet {
+    pub to: usize,
+    pub deliver_at: u64,
+    pub message: Message,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    Deliver { to: usize, message: Message },
+    Local { host_id: usize, key: u64 },
+    Submit { host_id: usize, request: Value },
+    Crash { host_id: usize },
+    Recover { host_id: usize },
+}
+
+// A step that changed something: the action, the `low_level::next` transition it is, and its event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub tick: u64,
+    pub action: Action,
+    pub transition: Transition,
+    pub event: Event,
+}
+
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub seed: u64,
+    pub trace: Vec<Step>,
+    pub decided: BTreeMap<u64, Value>,
+    pub dropped: usize,
+    pub duplicated: usize,
+    // The step after which `safety` first failed, as an index into `trace`.
+    pub violation: Option<usize>,
+}
+
+// Prepare, PrepareFrom, Accept, Decide, ClientRequest, AcceptBatch and DecideBatch go to
+// every host; Promise and Nack go back to the ballot owner, Accepted to the ballot owner and every
+// learner, and AcceptedBatch, which only the leader counts, to the ballot owner. `implementation::transport::destinations`
+// broadcasts Accepted instead, which only adds deliveries that no handler takes.
+fn destinations(c: &Constants, message: &Message) -> Vec<usize> {
+    match message {
+        Message::Promise { ballot, .. } | Message::Nack { ballot, .. } | Message::AcceptedBatch { ballot, .. } => vec![ballot.pid as usize],
+        Message::Accepted { ballot, .. } => {
+            let owner = BTreeSet::from([ballot.pid]);
+            owner.union(&c.learners).map(|id| *id as usize).collect()
+        },
+        _ => (0..c.num_hosts as usize).collect(),
+    }
+}
+
+pub struct Simulator<'a> {
+    pub c: Constants,
+    pub config: &'a Config,
+    pub rng: Rng,
+    pub tick: u64,
+    pub state: Variables,
+    pub packets: Vec<Packet>,
+    pub next_request: usize,
+    pub report: Report,
+}
+
+impl<'a> Simulator<'a> {
+    pub fn new(config: &'a Config, seed: u64) -> Simulator<'a> {
+        let quorums = quorum::majority_quorums(quorum::all_hosts(config.num_hosts), config.num_failures);
+        let proposers = match &config.proposers {
+            Some(proposers) => proposers.iter().copied().collect(),
+            None => (0..config.num_hosts).collect(),
+        };
+        let mut c = Constants::new(config.num_hosts, config.num_failures, quorums, false)
+            .with_roles(proposers, config.learners.iter().copied().collect())
+            .with_fast_clients(config.fast_clients.iter().copied().collect());
+        if let Some(alpha) = config.reconfig_alpha {
+            c = c.with_reconfig(alpha);
+        }
+        let state = init(&c);
+        let report = Report { seed, trace: Vec::new(), decided: BTreeMap::new(), dropped: 0, duplicated: 0, violation: None };
+
+        Simulator { c, config, rng: Rng::new(seed), tick: 0, state, packets: Vec::new(), next_request: 0, report }
+    }
+
+    fn send(&mut self, message: Message) {
+        for to in destinations(&self.c, &message) {
+            if self.rng.percent(self.config.drop_percent) {
+                self.report.dropped += 1;
+                continue;
+            }
+            let copies = if self.rng.percent(self.config.duplicate_percent) { 2 } else { 1 };
+            self.report.duplicated += copies - 1;
+            for _ in 0..copies {
+                let deliver_at = self.tick + self.rng.below(self.config.max_delay + 1);
+                self.packets.push(Packet { to, deliver_at, message: message.clone() });
+            }
+        }
+    }
+
+    // Applies the host or batch step `transition`, with `host` as the successor of its host, and
+    // records it. Receives never consume (`consume_on_receive` is false), so every delivered packet
+    // carries an in-flight message.
+    fn commit(&mut self, action: Action, transition: Transition, event: Event, host: host::Variables) {
+        let (Transition::HostStep { host_id, net_op, .. } | Transition::BatchStep { host_id, net_op, .. }) = &transition else { unreachable!() };
+        let network = network::step(&self.c.network, &self.state.network, net_op).unwrap();
+        self.state = self.state.after_host_step(*host_id, host, network);
+        if let Some(message) = net_op.send.clone() {
+            self.send(message);
+        }
+
+        self.report.trace.push(Step { tick: self.tick, action, transition, event });
+    }
+
+    fn deliver(&mut self, index: usize) {
+        let packet = self.packets.swap_remove(index);
+        let (to, message) = (packet.to, &packet.message);
+        if batch::is_batch(message) {
+            return self.deliver_batch(packet);
+        }
+        let mut steps: Vec<_> = network::receiving_instances(message)
+            .into_iter()
+            .flat_map(|key| host::successors(&self.c.hosts[to], &self.state.hosts[to], key, Some(message)).into_iter().map(move |step| (key, step)))
+            .collect();
+
+        // A message no handler accepts (stale ballot, unknown instance, host down) is discarded.
+        if !steps.is_empty() {
+            let (key, (event, host, send)) = steps.swap_remove(self.rng.below(steps.len() as u64) as usize);
+            let net_op = NetworkOperation { send, recv: Some(message.clone()) };
+            self.commit(Action::Deliver { to, message: message.clone() }, Transition::HostStep { host_id: to, instance: key, net_op }, event, host);
+        }
+    }
+
+    // A host takes a batch whole, so unlike a Decide a DecideBatch needs one delivery.
+    fn deliver_batch(&mut self, packet: Packet) {
+        let (to, message) = (packet.to, &packet.message);
+        let mut steps = batch::successors(&self.c.hosts[to], &self.state.hosts[to], Some(message));
+
+        if !steps.is_empty() {
+            let (event, host, send) = steps.swap_remove(self.rng.below(steps.len() as u64) as usize);
+            let net_op = NetworkOperation { send, recv: Some(message.clone()) };
+            self.commit(Action::Deliver { to, message: message.clone() }, Transition::BatchStep { host_id: to, net_op }, event, host);
+        }
+    }
+
+    // With `batch_percent`, a `send_decide_batch` or `propose_batch` at the key's ballot. Then the
+    // first enabled one of `init_request`, `raise_to_floor`, `join_prepare_from`, `send_accept`,
+    // `learn` and `learner_decide` (until the host has decided) and `send_decide`; failing those, a
+    // `send_prepare` or `send_prepare_from` that fires with `prepare_percent` as a retry timeout.
+    // Preparing whenever it is enabled would preempt every ballot before it could finish.
+    fn local(&mut self, host_id: usize, key: u64) {
+        let (c, u) = (&self.c.hosts[host_id], &self.state.hosts[host_id]);
+        if self.config.batch_percent > 0 && self.rng.percent(self.config.batch_percent) {
+            if let Some(ballot) = u.instances.get(&key).map(|instance| instance.current_ballot) {
+                let decide = batch::send_decide_batch(c, u, ballot).map(|(host, send)| (Event::NoOp, host, send));
+                if let Some((event, host, send)) = decide.or_else(|| batch::propose_batch(c, u, ballot)) {
+                    let net_op = NetworkOperation { send, recv: None };
+                    return self.commit(Action::Local { host_id, key }, Transition::BatchStep { host_id, net_op }, event, host);
+                }
+            }
+        }
+
+        let step = host::init_request(c, u, key)
+            .or_else(|| host::raise_to_floor(c, u, key))
+            .or_else(|| host::join_prepare_from(u, key))
+            .map(|(v, send)| (Event::NoOp, v, send))
+            .or_else(|| {
+                host::send_accept(c, u, key).map(|(v, send)| {
+                    let value = v.instances[&key].volatile.proposed_value[&v.instances[&key].current_ballot];
+                    (Event::Propose { key, value }, v, send)
+                })
+            })
+            .or_else(|| {
+                if u.instances.get(&key).is_some_and(|instance| instance.decide_value.is_none()) {
+                    host::learn(c, u, key)
+                        .or_else(|| {
+                            let (ballot, value) = *host::learnable(c, u, key).first()?;
+                            host::learner_decide(c, u, key, ballot, value)
+                        })
+                        .map(|(v, send)| (Event::Decide { key, value: v.instances[&key].decide_value.unwrap() }, v, send))
+                } else {
+                    None
+                }
+            })
+            .or_else(|| host::send_decide(c, u, key).map(|(v, send)| (Event::NoOp, v, send)))
+            .or_else(|| {
+                if !self.rng.percent(self.config.prepare_percent) {
+                    None
+                } else if self.config.prepare_from_percent > 0 && self.rng.percent(self.config.prepare_from_percent) {
+                    host::send_prepare_from(c, u, key).map(|(v, send)| (Event::NoOp, v, send))
+                } else {
+                    host::send_prepare(c, u, key).map(|(v, send)| (Event::NoOp, v, send))
+                }
+            });
+
+        if let Some((event, host, send)) = step {
+            let net_op = NetworkOperation { send, recv: None };
+            self.commit(Action::Local { host_id, key }, Transition::HostStep { host_id, instance: key, net_op }, event, host);
+        }
+    }
+
+    fn num_requests(&self) -> usize {
+        self.config.values.len() + self.config.commands.len()
+    }
+
+    // Submits request `index`, the first time or again. The indices past `values` are the commands.
+    fn submit(&mut self, host_id: usize, index: usize) {
+        let request = match index.checked_sub(self.config.values.len()) {
+            Some(command) => Value { client_id: RECONFIG_CLIENT, seq: command as u64, op: self.config.commands[command] as i64 },
+            None => {
+                let client_id = index as u64;
+                let seq = if self.config.fast_clients.contains(&client_id) { client_id % self.config.num_keys.max(1) } else { 0 };
+                Value { client_id, seq, op: self.config.values[index] }
+            },
+        };
+        if index == self.next_request {
+            self.next_request += 1;
+        }
+
+        let (host, send) = host::submit_request(&self.state.hosts[host_id], request).unwrap();
+        let event = Event::Submit { value: request };
+        let net_op = NetworkOperation { send, recv: None };
+        self.commit(Action::Submit { host_id, request }, Transition::HostStep { host_id, instance: 0, net_op }, event, host);
+    }
+
+    fn fail(&mut self, host_id: usize, recover: bool) {
+        let u = &self.state.hosts[host_id];
+        let (outcome, action) = if recover {
+            (host::recover(u), Action::Recover { host_id })
+        } else {
+            (host::crash(u), Action::Crash { host_id })
+        };
+
+        if let Some((host, send)) = outcome {
+            // A crashed host loses whatever was addressed to it while it was down.
+            if !recover {
+                self.packets.retain(|packet| packet.to != host_id);
+            }
+            let net_op = NetworkOperation { send, recv: None };
+            self.commit(action, Transition::HostStep { host_id, instance: 0, net_op }, Event::NoOp, host);
+        }
+    }
+
+    // At most `num_failures` hosts are down at once, so a quorum can always make progress.
+    fn can_crash(&self) -> bool {
+        self.state.hosts.iter().filter(|host| !host.up).count() < self.config.num_failures as usize
+    }
+
+    // One scheduling decision: deliver a due packet, or let a random host act.
+    pub fn step(&mut self) {
+        let num_hosts = self.c.num_hosts;
+        let due: Vec<usize> = (0..self.packets.len()).filter(|i| self.packets[*i].deliver_at <= self.tick).collect();
+
+        if !due.is_empty() && self.rng.percent(70) {
+            let index = due[self.rng.below(due.len() as u64) as usize];
+            self.deliver(index);
+        } else {
+            let host_id = self.rng.below(num_hosts) as usize;
+            if !self.state.hosts[host_id].up {
+                if self.rng.percent(self.config.recover_percent) {
+                    self.fail(host_id, true);
+                }
+            } else if self.can_crash() && self.rng.percent(self.config.crash_percent) {
+                self.fail(host_id, false);
+            } else if self.next_request < self.num_requests() && self.rng.percent(10) {
+                self.submit(host_id, self.next_request);
+            } else if self.next_request > 0 && self.config.resubmit_percent > 0 && self.rng.percent(self.config.resubmit_percent) {
+                let index = self.rng.below(self.next_request as u64) as usize;
+                self.submit(host_id, index);
+            } else if self.config.num_keys > 0 {
+                let key = self.rng.below(self.config.num_keys);
+                self.local(host_id, key);
+            }
+        }
+
+        self.tick += 1;
+    }
+
+    fn record_decisions(&mut self) {
+        for host in &self.state.hosts {
+            for (key, instance) in &host.instances {
+                if let Some(value) = instance.decide_value {
+                    self.report.decided.entry(*key).or_insert(value);
+                }
+            }
+        }
+    }
+
+    // Runs `config.steps` scheduling decisions, checking `safety` (agreement on every decided
+    // value) after each one and stopping at the first violation.
+    pub fn run(mut self) -> Report {
+        for _ in 0..self.config.steps {
+            let recorded = self.report.trace.len();
+            self.step();
+            if self.report.trace.len() > recorded && !safety(&self.c, &self.state) {
+                self.report.violation = Some(self.report.trace.len() - 1);
+                break;
+            }
+        }
+
+        self.record_decisions();
+        self.report
+    }
+}
+
+pub fn simulate(config: &Config, seed: u64) -> Report {
+    Simulator::new(config, seed).run()
+}
+
+pub fn describe_action(step: &Step) -> String {
+    let action = match &step.action {
+        Action::Deliver { to, message } => format!("deliver to host {to}: {message:?}"),
+        Action::Local { host_id, key } => match &step.transition {
+            Transition::HostStep { net_op: NetworkOperation { send: Some(message), .. }, .. }
+            | Transition::BatchStep { net_op: NetworkOperation { send: Some(message), .. }, .. } => {
+                format!("host {host_id}, key {key}: send {message:?}")
+            },
+            _ => format!("host {host_id}, key {key}: init_request"),
+        },
+        Action::Submit { host_id, request } => format!("host {host_id}: submit {request:?}"),
+        Action::Crash { host_id } => format!("host {host_id}: crash"),
+        Action::Recover { host_id } => format!("host {host_id}: recover"),
+    };
+
+    match &step.event {
+        Event::NoOp | Event::Submit { .. } => format!("[tick {}] {action}", step.tick),
+        event => format!("[tick {}] {action} [{event:?}]", step.tick),
+    }
+}
@@ -0,0 +1,1096 @@
+// An evaluator for the constant-level TLA+ that `Paxos.tla` is written in, so that the tests can
+// compare its operators with the executable mirror on concrete states without TLC. It knows the
+// expression forms the module uses, the operators of Integers, Sequences and FiniteSets it calls,
+// and TLC's `:>` and `@@`. A definition outside that subset (a temporal formula, a primed variable)
+// fails to parse and is an error only when something evaluates it.
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+    rc::Rc,
+};
+
+// A TLA+ value. Records, tuples and sequences are all functions, as in TLA+, so that `<<>>`, the
+// empty record and a function on `{}` are the same value.
+#[derive(Clone, Debug)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Str(Rc<str>),
+    Set(Rc<BTreeSet<Value>>),
+    Fun(Rc<BTreeMap<Value, Value>>),
+}
+
+impl Value {
+    fn rank(&self) -> u8 {
+        match self {
+            Value::Bool(_) => 0,
+            Value::Int(_) => 1,
+            Value::Str(_) => 2,
+            Value::Set(_) => 3,
+            Value::Fun(_) => 4,
+        }
+    }
+
+    fn bool(&self) -> Result<bool, String> {
+        match self {
+            Value::Bool(b) => Ok(*b),
+            _ => Err(format!("expected a boolean, got {self}")),
+        }
+    }
+
+    fn int(&self) -> Result<i64, String> {
+        match self {
+            Value::Int(n) => Ok(*n),
+            _ => Err(format!("expected an integer, got {self}")),
+        }
+    }
+
+    fn set(&self) -> Result<&Rc<BTreeSet<Value>>, String> {
+        match self {
+            Value::Set(set) => Ok(set),
+            _ => Err(format!("expected a set, got {self}")),
+        }
+    }
+
+    fn fun(&self) -> Result<&Rc<BTreeMap<Value, Value>>, String> {
+        match self {
+            Value::Fun(fun) => Ok(fun),
+            _ => Err(format!("expected a function, got {self}")),
+        }
+    }
+
+    fn sequence(elements: impl IntoIterator<Item = Value>) -> Value {
+        Value::Fun(Rc::new(elements.into_iter().enumerate().map(|(n, element)| (Value::Int(n as i64 + 1), element)).collect()))
+    }
+
+    fn is_sequence(fun: &BTreeMap<Value, Value>) -> bool {
+        fun.keys().enumerate().all(|(n, key)| matches!(key, Value::Int(k) if *k == n as i64 + 1))
+    }
+}
+
+// States share most of their structure, so equal parts are usually the same allocation.
+impl Ord for Value {
+    fn cmp(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+            (Value::Str(a), Value::Str(b)) => a.cmp(b),
+            (Value::Set(a), Value::Set(b)) if Rc::ptr_eq(a, b) => Ordering::Equal,
+            (Value::Set(a), Value::Set(b)) => a.cmp(b),
+            (Value::Fun(a), Value::Fun(b)) if Rc::ptr_eq(a, b) => Ordering::Equal,
+            (Value::Fun(a), Value::Fun(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Value) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Value {}
+
+fn join<'a>(f: &mut fmt::Formatter, elements: impl Iterator<Item = &'a Value>) -> fmt::Result {
+    for (n, element) in elements.enumerate() {
+        write!(f, "{}{element}", if n == 0 { "" } else { ", " })?;
+    }
+    Ok(())
+}
+
+// As TLC prints values: a function is a record, a sequence or `(k :> v @@ ..)`.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
+            Value::Int(n) => write!(f, "{n}"),
+            Value::Str(s) => write!(f, "{s:?}"),
+            Value::Set(set) => {
+                write!(f, "{{")?;
+                join(f, set.iter())?;
+                write!(f, "}}")
+            },
+            Value::Fun(fun) if Value::is_sequence(fun) => {
+                write!(f, "<<")?;
+                join(f, fun.values())?;
+                write!(f, ">>")
+            },
+            Value::Fun(fun) if fun.keys().all(|key| matches!(key, Value::Str(_))) => {
+                write!(f, "[")?;
+                for (n, (key, value)) in fun.iter().enumerate() {
+                    let Value::Str(name) = key else { unreachable!() };
+                    write!(f, "{}{name} |-> {value}", if n == 0 { "" } else { ", " })?;
+                }
+                write!(f, "]")
+            },
+            Value::Fun(fun) => {
+                write!(f, "(")?;
+                for (n, (key, value)) in fun.iter().enumerate() {
+                    write!(f, "{}{key} :> {value}", if n == 0 { "" } else { " @@ " })?;
+                }
+                write!(f, ")")
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tok {
+    Ident(String),
+    Num(i64),
+    Str(String),
+    Sym(&'static str),
+}
+
+// `first` marks the first token of its line, whose column ends junction list items and LET
+// definitions.
+#[derive(Clone, Debug)]
+struct Token {
+    tok: Tok,
+    line: usize,
+    col: usize,
+    first: bool,
+}
+
+// Longest first, so that `|->` is not read as `|` and `->`.
+const SYMBOLS: &[&str] = &[
+    "|->", "<<", ">>", "->", "==", "<=", ">=", "=>", "/\\", "[]", "..", ":>", "@@", "#", "=", "<", ">", "(", ")", "[", "]", "{", "}", ",", ":", ".", "+", "-", "*",
+    "%", "~", "!", "@", "'",
+];
+
+const BACKSLASH_WORDS: &[&str] = &["\\in", "\\notin", "\\cup", "\\cap", "\\subseteq", "\\A", "\\E"];
+
+// Splits `text` into tokens, dropping comments and the `----` and `====` lines around a module.
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut depth = 0;
+    for (line, source) in text.lines().enumerate() {
+        if depth == 0 && (source.starts_with("----") || source.starts_with("====")) {
+            continue;
+        }
+        let bytes = source.as_bytes();
+        let mut col = 0;
+        let mut first = true;
+        while col < bytes.len() {
+            let rest = &bytes[col..];
+            if depth > 0 {
+                if rest.starts_with(b"(*") {
+                    depth += 1;
+                    col += 2;
+                } else if rest.starts_with(b"*)") {
+                    depth -= 1;
+                    col += 2;
+                } else {
+                    col += 1;
+                }
+                continue;
+            }
+            if rest[0].is_ascii_whitespace() {
+                col += 1;
+                continue;
+            }
+            if rest.starts_with(b"(*") {
+                depth += 1;
+                col += 2;
+                continue;
+            }
+            if rest.starts_with(b"\\*") {
+                break;
+            }
+
+            let start = col;
+            let word = |from: usize| from + bytes[from..].iter().take_while(|b| b.is_ascii_alphanumeric() || **b == b'_').count();
+            let tok = if rest[0].is_ascii_alphabetic() || rest[0] == b'_' {
+                col = word(col);
+                Tok::Ident(source[start..col].to_string())
+            } else if rest[0].is_ascii_digit() {
+                col += rest.iter().take_while(|b| b.is_ascii_digit()).count();
+                Tok::Num(source[start..col].parse().map_err(|_| format!("line {}: number out of range", line + 1))?)
+            } else if rest[0] == b'"' {
+                let Some(length) = rest[1..].iter().position(|b| *b == b'"') else { return Err(format!("line {}: unterminated string", line + 1)) };
+                col += length + 2;
+                Tok::Str(source[start + 1..col - 1].to_string())
+            } else if rest.starts_with(b"\\/") {
+                col += 2;
+                Tok::Sym("\\/")
+            } else if rest[0] == b'\\' {
+                col = word(col + 1);
+                let text = &source[start..col];
+                if text == "\\" {
+                    Tok::Sym("\\")
+                } else {
+                    Tok::Sym(BACKSLASH_WORDS.iter().find(|w| **w == text).ok_or(format!("line {}: unsupported operator {text}", line + 1))?)
+                }
+            } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(symbol.as_bytes())) {
+                col += symbol.len();
+                Tok::Sym(symbol)
+            } else {
+                return Err(format!("line {}: unexpected character {:?}", line + 1, rest[0] as char));
+            };
+            tokens.push(Token { tok, line, col: start, first });
+            first = false;
+        }
+    }
+    if depth > 0 {
+        return Err("unterminated comment".to_string());
+    }
+    Ok(tokens)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BinOp {
+    Implies,
+    Or,
+    And,
+    Eq,
+    Neq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    In,
+    NotIn,
+    Subseteq,
+    AtAt,
+    ColonGt,
+    Cup,
+    Cap,
+    SetMinus,
+    Range,
+    Add,
+    Sub,
+    Mod,
+    Mul,
+}
+
+// Precedences from Lamport's table, with `/\` above `\/` rather than level with it: the module never
+// mixes them without brackets or bullets, where the difference would matter.
+fn infix(tok: &Tok) -> Option<(BinOp, u8)> {
+    let Tok::Sym(symbol) = tok else { return None };
+    Some(match *symbol {
+        "=>" => (BinOp::Implies, 1),
+        "\\/" => (BinOp::Or, 2),
+        "/\\" => (BinOp::And, 3),
+        "=" => (BinOp::Eq, 5),
+        "#" => (BinOp::Neq, 5),
+        "<" => (BinOp::Lt, 5),
+        "<=" => (BinOp::Le, 5),
+        ">" => (BinOp::Gt, 5),
+        ">=" => (BinOp::Ge, 5),
+        "\\in" => (BinOp::In, 5),
+        "\\notin" => (BinOp::NotIn, 5),
+        "\\subseteq" => (BinOp::Subseteq, 5),
+        "@@" => (BinOp::AtAt, 6),
+        ":>" => (BinOp::ColonGt, 7),
+        "\\cup" => (BinOp::Cup, 8),
+        "\\cap" => (BinOp::Cap, 8),
+        "\\" => (BinOp::SetMinus, 8),
+        ".." => (BinOp::Range, 9),
+        "+" => (BinOp::Add, 10),
+        "-" => (BinOp::Sub, 10),
+        "%" => (BinOp::Mod, 10),
+        "*" => (BinOp::Mul, 13),
+        _ => return None,
+    })
+}
+
+#[derive(Clone, Debug)]
+enum Path {
+    Field(Rc<str>),
+    Index(Expr),
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Bool(bool),
+    Int(i64),
+    Str(Rc<str>),
+    Name(Rc<str>),
+    At,
+    Call(Rc<str>, Vec<Expr>),
+    Apply(Box<Expr>, Box<Expr>),
+    Field(Box<Expr>, Rc<str>),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Domain(Box<Expr>),
+    Union(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    If(Box<Expr>, Box<Expr>, Box<Expr>),
+    Let(Vec<Rc<Definition>>, Box<Expr>),
+    Case(Vec<(Expr, Expr)>, Option<Box<Expr>>),
+    Quantifier(bool, Vec<(Rc<str>, Expr)>, Box<Expr>),
+    Choose(Rc<str>, Box<Expr>, Box<Expr>),
+    SetOf(Vec<Expr>),
+    Filter(Rc<str>, Box<Expr>, Box<Expr>),
+    Map(Box<Expr>, Vec<(Rc<str>, Expr)>),
+    Tuple(Vec<Expr>),
+    Record(Vec<(Rc<str>, Expr)>),
+    Function(Rc<str>, Box<Expr>, Box<Expr>),
+    Except(Box<Expr>, Vec<(Vec<Path>, Expr)>),
+}
+
+#[derive(Debug)]
+struct Definition {
+    name: Rc<str>,
+    params: Vec<Rc<str>>,
+    body: Result<Expr, String>,
+}
+
+// A recursive-descent parser. `fence` is the column of the innermost junction list bullet or LET
+// definition: a token that starts a line at or left of it ends the item or definition.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    fence: Option<usize>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Tok> {
+        self.tokens.get(self.pos + offset).map(|token| &token.tok)
+    }
+
+    fn at(&self, symbol: &str) -> bool {
+        matches!(self.peek_at(0), Some(Tok::Sym(s)) if *s == symbol)
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek_at(0), Some(Tok::Ident(s)) if s == keyword)
+    }
+
+    fn fenced(&self) -> bool {
+        match (self.peek(), self.fence) {
+            (None, _) => true,
+            (Some(token), Some(fence)) => token.first && token.col <= fence,
+            (Some(_), None) => false,
+        }
+    }
+
+    fn error(&self, expected: &str) -> String {
+        match self.peek() {
+            Some(token) => format!("line {}: expected {expected}, found {:?}", token.line + 1, token.tok),
+            None => format!("expected {expected} at the end of the input"),
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), String> {
+        if !self.at(symbol) {
+            return Err(self.error(symbol));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), String> {
+        if !self.at_keyword(keyword) {
+            return Err(self.error(keyword));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn ident(&mut self) -> Result<Rc<str>, String> {
+        match self.peek_at(0) {
+            Some(Tok::Ident(name)) => {
+                let name = Rc::from(name.as_str());
+                self.pos += 1;
+                Ok(name)
+            },
+            _ => Err(self.error("an identifier")),
+        }
+    }
+
+    // Everything up to the next token in column 0, for the declarations the evaluator ignores.
+    fn skip_unit(&mut self) {
+        self.pos += 1;
+        while self.peek().is_some_and(|token| !(token.first && token.col == 0)) {
+            self.pos += 1;
+        }
+    }
+
+    fn params(&mut self) -> Result<Vec<Rc<str>>, String> {
+        let mut params = Vec::new();
+        if self.at("(") {
+            self.pos += 1;
+            params.push(self.ident()?);
+            while self.at(",") {
+                self.pos += 1;
+                params.push(self.ident()?);
+            }
+            self.expect(")")?;
+        }
+        Ok(params)
+    }
+
+    fn list(&mut self, close: &str) -> Result<Vec<Expr>, String> {
+        let mut elements = Vec::new();
+        if !self.at(close) {
+            elements.push(self.expr(0)?);
+            while self.at(",") {
+                self.pos += 1;
+                elements.push(self.expr(0)?);
+            }
+        }
+        self.expect(close)?;
+        Ok(elements)
+    }
+
+    // `x \in S, y, z \in T`, with one entry per bound name.
+    fn binders(&mut self) -> Result<Vec<(Rc<str>, Expr)>, String> {
+        let mut binders = Vec::new();
+        loop {
+            let mut names = vec![self.ident()?];
+            while self.at(",") {
+                self.pos += 1;
+                names.push(self.ident()?);
+            }
+            self.expect("\\in")?;
+            let set = self.expr(0)?;
+            binders.extend(names.into_iter().map(|name| (name, set.clone())));
+            if !self.at(",") {
+                return Ok(binders);
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn expr(&mut self, min_prec: u8) -> Result<Expr, String> {
+        let mut lhs = self.prefix()?;
+        while !self.fenced() {
+            let Some((op, prec)) = self.peek_at(0).and_then(infix) else { break };
+            if prec < min_prec {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.expr(if op == BinOp::Implies { prec } else { prec + 1 })?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn junction_list(&mut self) -> Result<Expr, String> {
+        let token = self.peek().unwrap().clone();
+        let op = if token.tok == Tok::Sym("/\\") { BinOp::And } else { BinOp::Or };
+        let saved = self.fence;
+        let mut items = Vec::new();
+        while self.peek().is_some_and(|next| next.tok == token.tok && next.col == token.col) {
+            self.pos += 1;
+            self.fence = Some(token.col);
+            let item = self.expr(0);
+            self.fence = saved;
+            items.push(item?);
+        }
+        let mut items = items.into_iter();
+        let first = items.next().unwrap();
+        Ok(items.fold(first, |lhs, rhs| Expr::Binary(op, Box::new(lhs), Box::new(rhs))))
+    }
+
+    fn prefix(&mut self) -> Result<Expr, String> {
+        let Some(token) = self.peek() else { return Err(self.error("an expression")) };
+        match &token.tok {
+            Tok::Sym("/\\") | Tok::Sym("\\/") => self.junction_list(),
+            Tok::Sym("~") => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.expr(4)?)))
+            },
+            Tok::Sym("-") => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.expr(12)?)))
+            },
+            Tok::Sym("\\A") | Tok::Sym("\\E") => {
+                let forall = token.tok == Tok::Sym("\\A");
+                self.pos += 1;
+                let binders = self.binders()?;
+                self.expect(":")?;
+                Ok(Expr::Quantifier(forall, binders, Box::new(self.expr(0)?)))
+            },
+            Tok::Ident(keyword) => match keyword.as_str() {
+                "DOMAIN" => {
+                    self.pos += 1;
+                    Ok(Expr::Domain(Box::new(self.expr(9)?)))
+                },
+                "UNION" => {
+                    self.pos += 1;
+                    Ok(Expr::Union(Box::new(self.expr(9)?)))
+                },
+                "IF" => {
+                    self.pos += 1;
+                    let condition = self.expr(0)?;
+                    self.expect_keyword("THEN")?;
+                    let then = self.expr(0)?;
+                    self.expect_keyword("ELSE")?;
+                    Ok(Expr::If(Box::new(condition), Box::new(then), Box::new(self.expr(0)?)))
+                },
+                "LET" => self.let_in(),
+                "CASE" => self.case(),
+                "CHOOSE" => {
+                    self.pos += 1;
+                    let name = self.ident()?;
+                    self.expect("\\in")?;
+                    let set = self.expr(0)?;
+                    self.expect(":")?;
+                    Ok(Expr::Choose(name, Box::new(set), Box::new(self.expr(0)?)))
+                },
+                _ => self.postfix(),
+            },
+            _ => self.postfix(),
+        }
+    }
+
+    fn let_in(&mut self) -> Result<Expr, String> {
+        self.pos += 1;
+        let saved = self.fence;
+        let mut definitions = Vec::new();
+        while !self.at_keyword("IN") {
+            let col = self.peek().ok_or(self.error("IN"))?.col;
+            let name = self.ident()?;
+            let params = self.params()?;
+            self.expect("==")?;
+            self.fence = Some(col.max(saved.unwrap_or(0)));
+            let body = self.expr(0);
+            self.fence = saved;
+            definitions.push(Rc::new(Definition { name, params, body: Ok(body?) }));
+        }
+        self.pos += 1;
+        Ok(Expr::Let(definitions, Box::new(self.expr(0)?)))
+    }
+
+    fn case(&mut self) -> Result<Expr, String> {
+        self.pos += 1;
+        let mut arms = Vec::new();
+        let mut other = None;
+        loop {
+            if self.at_keyword("OTHER") {
+                self.pos += 1;
+                self.expect("->")?;
+                other = Some(Box::new(self.expr(0)?));
+                break;
+            }
+            let guard = self.expr(0)?;
+            self.expect("->")?;
+            arms.push((guard, self.expr(0)?));
+            if !self.at("[]") {
+                break;
+            }
+            self.pos += 1;
+        }
+        Ok(Expr::Case(arms, other))
+    }
+
+    fn postfix(&mut self) -> Result<Expr, String> {
+        let mut expr = self.primary()?;
+        while !self.fenced() {
+            if self.at(".") {
+                self.pos += 1;
+                expr = Expr::Field(Box::new(expr), self.ident()?);
+            } else if self.at("[") {
+                self.pos += 1;
+                let index = self.expr(0)?;
+                self.expect("]")?;
+                expr = Expr::Apply(Box::new(expr), Box::new(index));
+            } else {
+                break;
+            }
+        }
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let Some(tok) = self.peek_at(0).cloned() else { return Err(self.error("an expression")) };
+        self.pos += 1;
+        match tok {
+            Tok::Num(n) => Ok(Expr::Int(n)),
+            Tok::Str(s) => Ok(Expr::Str(Rc::from(s.as_str()))),
+            Tok::Ident(name) if name == "TRUE" || name == "FALSE" => Ok(Expr::Bool(name == "TRUE")),
+            Tok::Ident(name) if self.at("(") => {
+                self.pos += 1;
+                Ok(Expr::Call(Rc::from(name.as_str()), self.list(")")?))
+            },
+            Tok::Ident(name) => Ok(Expr::Name(Rc::from(name.as_str()))),
+            Tok::Sym("@") => Ok(Expr::At),
+            Tok::Sym("(") => {
+                let expr = self.expr(0)?;
+                self.expect(")")?;
+                Ok(expr)
+            },
+            Tok::Sym("<<") => Ok(Expr::Tuple(self.list(">>")?)),
+            Tok::Sym("{") => self.set(),
+            Tok::Sym("[") => self.bracket(),
+            _ => {
+                self.pos -= 1;
+                Err(self.error("an expression"))
+            },
+        }
+    }
+
+    // `{a, b}`, `{x \in S : P}` or `{e : x \in S}`.
+    fn set(&mut self) -> Result<Expr, String> {
+        if self.at("}") {
+            self.pos += 1;
+            return Ok(Expr::SetOf(Vec::new()));
+        }
+        let first = self.expr(0)?;
+        if !self.at(":") {
+            let mut elements = vec![first];
+            while self.at(",") {
+                self.pos += 1;
+                elements.push(self.expr(0)?);
+            }
+            self.expect("}")?;
+            return Ok(Expr::SetOf(elements));
+        }
+        self.pos += 1;
+        let expr = match first {
+            Expr::Binary(BinOp::In, name, set) if matches!(*name, Expr::Name(_)) => {
+                let Expr::Name(name) = *name else { unreachable!() };
+                Expr::Filter(name, set, Box::new(self.expr(0)?))
+            },
+            map => Expr::Map(Box::new(map), self.binders()?),
+        };
+        self.expect("}")?;
+        Ok(expr)
+    }
+
+    // `[a |-> e, ..]`, `[x \in S |-> e]` or `[f EXCEPT !.a[k] = e, ..]`.
+    fn bracket(&mut self) -> Result<Expr, String> {
+        let record = matches!((self.peek_at(0), self.peek_at(1)), (Some(Tok::Ident(_)), Some(Tok::Sym("|->"))));
+        let function = matches!((self.peek_at(0), self.peek_at(1)), (Some(Tok::Ident(_)), Some(Tok::Sym("\\in"))));
+        if record {
+            let mut fields = Vec::new();
+            loop {
+                let name = self.ident()?;
+                self.expect("|->")?;
+                fields.push((name, self.expr(0)?));
+                if !self.at(",") {
+                    break;
+                }
+                self.pos += 1;
+            }
+            self.expect("]")?;
+            return Ok(Expr::Record(fields));
+        }
+        if function {
+            let name = self.ident()?;
+            self.expect("\\in")?;
+            let set = self.expr(0)?;
+            self.expect("|->")?;
+            let body = self.expr(0)?;
+            self.expect("]")?;
+            return Ok(Expr::Function(name, Box::new(set), Box::new(body)));
+        }
+
+        let base = self.expr(0)?;
+        self.expect_keyword("EXCEPT")?;
+        let mut updates = Vec::new();
+        loop {
+            self.expect("!")?;
+            let mut path = Vec::new();
+            while !self.at("=") {
+                if self.at(".") {
+                    self.pos += 1;
+                    path.push(Path::Field(self.ident()?));
+                } else {
+                    self.expect("[")?;
+                    path.push(Path::Index(self.expr(0)?));
+                    self.expect("]")?;
+                }
+            }
+            self.pos += 1;
+            updates.push((path, self.expr(0)?));
+            if !self.at(",") {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.expect("]")?;
+        Ok(Expr::Except(Box::new(base), updates))
+    }
+}
+
+// A quantifier binder with its set evaluated.
+type Bound = (Rc<str>, Rc<BTreeSet<Value>>);
+
+// What a name stands for in an expression: a bound value, a LET definition without parameters,
+// which is evaluated on first use, or a LET operator.
+enum Binding {
+    Value(Value),
+    Lazy(Rc<Definition>, Env, RefCell<Option<Value>>),
+    Operator(Rc<Definition>, Env),
+}
+
+struct Frame {
+    name: Rc<str>,
+    binding: Binding,
+    next: Env,
+}
+
+#[derive(Clone, Default)]
+struct Env(Option<Rc<Frame>>);
+
+impl Env {
+    fn bind(&self, name: Rc<str>, binding: Binding) -> Env {
+        Env(Some(Rc::new(Frame { name, binding, next: self.clone() })))
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Binding> {
+        let mut env = self;
+        while let Some(frame) = &env.0 {
+            if &*frame.name == name {
+                return Some(&frame.binding);
+            }
+            env = &frame.next;
+        }
+        None
+    }
+}
+
+// A parsed module whose CONSTANTS are given values with `define`. Every operator application is
+// cached, since `Successors` and the invariants apply the same operators to the same states many
+// times over.
+pub struct Module {
+    definitions: HashMap<Rc<str>, (usize, Rc<Definition>)>,
+    declared: HashSet<Rc<str>>,
+    constants: HashMap<Rc<str>, Value>,
+    cache: RefCell<BTreeMap<(usize, Vec<Value>), Value>>,
+}
+
+fn parse_expression(text: &str) -> Result<Expr, String> {
+    let mut parser = Parser { tokens: tokenize(text)?, pos: 0, fence: None };
+    let expr = parser.expr(0)?;
+    if parser.peek().is_some() {
+        return Err(parser.error("the end of the expression"));
+    }
+    Ok(expr)
+}
+
+impl Module {
+    // A name may be declared or defined only once, as SANY requires.
+    pub fn parse(text: &str) -> Result<Module, String> {
+        let mut parser = Parser { tokens: tokenize(text)?, pos: 0, fence: Some(0) };
+        let mut module = Module { definitions: HashMap::new(), declared: HashSet::new(), constants: HashMap::new(), cache: RefCell::default() };
+
+        while let Some(token) = parser.peek() {
+            let line = token.line + 1;
+            let Tok::Ident(keyword) = token.tok.clone() else { return Err(parser.error("a definition")) };
+            match keyword.as_str() {
+                "CONSTANT" | "CONSTANTS" | "VARIABLE" | "VARIABLES" => {
+                    let start = parser.pos;
+                    parser.skip_unit();
+                    for token in &parser.tokens[start + 1..parser.pos] {
+                        if let Tok::Ident(name) = &token.tok {
+                            if !module.declared.insert(Rc::from(name.as_str())) {
+                                return Err(format!("line {}: {name} is declared twice", token.line + 1));
+                            }
+                        }
+                    }
+                },
+                "EXTENDS" | "RECURSIVE" | "ASSUME" => parser.skip_unit(),
+                _ => {
+                    let name = parser.ident()?;
+                    let params = parser.params()?;
+                    parser.expect("==")?;
+                    let mut body = parser.expr(0);
+                    if body.is_ok() && !parser.fenced() {
+                        body = Err(parser.error("the next definition"));
+                    }
+                    if body.is_err() {
+                        parser.pos -= 1;
+                        parser.skip_unit();
+                    }
+                    if module.declared.contains(&name) || module.definitions.contains_key(&name) {
+                        return Err(format!("line {line}: {name} is defined twice"));
+                    }
+                    let index = module.definitions.len();
+                    module.definitions.insert(name.clone(), (index, Rc::new(Definition { name, params, body })));
+                },
+            }
+        }
+        Ok(module)
+    }
+
+    // Gives the declared constant `name` the value of the expression `text`.
+    pub fn define(&mut self, name: &str, text: &str) -> Result<(), String> {
+        if !self.declared.contains(name) {
+            return Err(format!("{name} is not a declared constant"));
+        }
+        let value = self.evaluate(&parse_expression(text)?, &Env::default())?;
+        self.constants.insert(Rc::from(name), value);
+        self.cache.borrow_mut().clear();
+        Ok(())
+    }
+
+    // The value of the expression `text`, which may use every definition of the module.
+    pub fn eval(&self, text: &str) -> Result<Value, String> {
+        self.evaluate(&parse_expression(text)?, &Env::default())
+    }
+
+    // The operator `name` of the module applied to `args`.
+    pub fn apply(&self, name: &str, args: &[Value]) -> Result<Value, String> {
+        let Some((index, definition)) = self.definitions.get(name) else { return Err(format!("{name} is not defined")) };
+        self.apply_definition(*index, definition, args.to_vec())
+    }
+
+    fn apply_definition(&self, index: usize, definition: &Definition, args: Vec<Value>) -> Result<Value, String> {
+        if definition.params.len() != args.len() {
+            return Err(format!("{} takes {} arguments, not {}", definition.name, definition.params.len(), args.len()));
+        }
+        let key = (index, args);
+        if let Some(value) = self.cache.borrow().get(&key) {
+            return Ok(value.clone());
+        }
+        let body = definition.body.as_ref().map_err(|error| format!("{} is outside the supported subset: {error}", definition.name))?;
+        let env = definition.params.iter().zip(&key.1).fold(Env::default(), |env, (param, arg)| env.bind(param.clone(), Binding::Value(arg.clone())));
+        let value = self.evaluate(body, &env).map_err(|error| format!("{error}\n  in {}", definition.name))?;
+        self.cache.borrow_mut().insert(key, value.clone());
+        Ok(value)
+    }
+
+    fn builtin(&self, name: &str, args: &[Value]) -> Result<Value, String> {
+        match (name, args) {
+            ("Cardinality", [set]) => Ok(Value::Int(set.set()?.len() as i64)),
+            ("Len", [seq]) => Ok(Value::Int(seq.fun()?.len() as i64)),
+            ("Append", [seq, element]) => Ok(Value::sequence(seq.fun()?.values().cloned().chain([element.clone()]))),
+            ("Head", [seq]) => seq.fun()?.get(&Value::Int(1)).cloned().ok_or("Head of an empty sequence".to_string()),
+            ("Tail", [seq]) if !seq.fun()?.is_empty() => Ok(Value::sequence(seq.fun()?.values().skip(1).cloned())),
+            ("Tail", [_]) => Err("Tail of an empty sequence".to_string()),
+            _ => Err(format!("{name} is not defined for {} arguments", args.len())),
+        }
+    }
+
+    fn evaluate_all(&self, exprs: &[Expr], env: &Env) -> Result<Vec<Value>, String> {
+        exprs.iter().map(|expr| self.evaluate(expr, env)).collect()
+    }
+
+    fn name(&self, name: &str, env: &Env) -> Result<Value, String> {
+        match env.lookup(name) {
+            Some(Binding::Value(value)) => return Ok(value.clone()),
+            Some(Binding::Lazy(definition, env, value)) => {
+                if let Some(value) = &*value.borrow() {
+                    return Ok(value.clone());
+                }
+                let body = definition.body.as_ref().map_err(Clone::clone)?;
+                let result = self.evaluate(body, env)?;
+                *value.borrow_mut() = Some(result.clone());
+                return Ok(result);
+            },
+            Some(Binding::Operator(..)) => return Err(format!("operator {name} used as a value")),
+            None => {},
+        }
+        if let Some(value) = self.constants.get(name) {
+            return Ok(value.clone());
+        }
+        if self.declared.contains(name) {
+            return Err(format!("{name} has no value"));
+        }
+        self.apply(name, &[])
+    }
+
+    fn call(&self, name: &str, args: &[Expr], env: &Env) -> Result<Value, String> {
+        let args = self.evaluate_all(args, env)?;
+        if let Some(Binding::Operator(definition, captured)) = env.lookup(name) {
+            if definition.params.len() != args.len() {
+                return Err(format!("{name} takes {} arguments, not {}", definition.params.len(), args.len()));
+            }
+            let env = definition.params.iter().zip(args).fold(captured.clone(), |env, (param, arg)| env.bind(param.clone(), Binding::Value(arg)));
+            return self.evaluate(definition.body.as_ref().map_err(Clone::clone)?, &env);
+        }
+        match self.definitions.get(name) {
+            Some((index, definition)) => self.apply_definition(*index, definition, args),
+            None => self.builtin(name, &args),
+        }
+    }
+
+    // Calls `visit` with `env` extended by every combination of values of `binders`, until it
+    // returns false; returns whether it never did.
+    fn each(&self, binders: &[Bound], env: &Env, visit: &mut dyn FnMut(&Env) -> Result<bool, String>) -> Result<bool, String> {
+        let Some(((name, set), rest)) = binders.split_first() else { return visit(env) };
+        for value in set.iter() {
+            if !self.each(rest, &env.bind(name.clone(), Binding::Value(value.clone())), visit)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn bound_sets(&self, binders: &[(Rc<str>, Expr)], env: &Env) -> Result<Vec<Bound>, String> {
+        binders.iter().map(|(name, set)| Ok((name.clone(), self.evaluate(set, env)?.set()?.clone()))).collect()
+    }
+
+    // `value` with the part at `path` replaced by `update`, in which `@` is the old part. As in
+    // TLA+, a path through an argument outside a function's domain leaves the value unchanged.
+    fn except(&self, value: Value, path: &[Path], update: &Expr, env: &Env) -> Result<Value, String> {
+        let Some((step, rest)) = path.split_first() else { return self.evaluate(update, &env.bind(Rc::from("@"), Binding::Value(value))) };
+        let key = match step {
+            Path::Field(name) => Value::Str(name.clone()),
+            Path::Index(index) => self.evaluate(index, env)?,
+        };
+        let fun = value.fun()?;
+        let Some(old) = fun.get(&key) else { return Ok(value) };
+        let new = self.except(old.clone(), rest, update, env)?;
+        let mut fun = (**fun).clone();
+        fun.insert(key, new);
+        Ok(Value::Fun(Rc::new(fun)))
+    }
+
+    fn binary(&self, op: BinOp, lhs: &Expr, rhs: &Expr, env: &Env) -> Result<Value, String> {
+        match op {
+            BinOp::And => return Ok(Value::Bool(self.evaluate(lhs, env)?.bool()? && self.evaluate(rhs, env)?.bool()?)),
+            BinOp::Or => return Ok(Value::Bool(self.evaluate(lhs, env)?.bool()? || self.evaluate(rhs, env)?.bool()?)),
+            BinOp::Implies => return Ok(Value::Bool(!self.evaluate(lhs, env)?.bool()? || self.evaluate(rhs, env)?.bool()?)),
+            _ => {},
+        }
+        let (a, b) = (self.evaluate(lhs, env)?, self.evaluate(rhs, env)?);
+        let set = |elements: BTreeSet<Value>| Value::Set(Rc::new(elements));
+        Ok(match op {
+            BinOp::Eq => Value::Bool(a == b),
+            BinOp::Neq => Value::Bool(a != b),
+            BinOp::Lt => Value::Bool(a.int()? < b.int()?),
+            BinOp::Le => Value::Bool(a.int()? <= b.int()?),
+            BinOp::Gt => Value::Bool(a.int()? > b.int()?),
+            BinOp::Ge => Value::Bool(a.int()? >= b.int()?),
+            BinOp::In => Value::Bool(b.set()?.contains(&a)),
+            BinOp::NotIn => Value::Bool(!b.set()?.contains(&a)),
+            BinOp::Subseteq => Value::Bool(a.set()?.is_subset(b.set()?)),
+            BinOp::Cup => set(a.set()?.union(b.set()?).cloned().collect()),
+            BinOp::Cap => set(a.set()?.intersection(b.set()?).cloned().collect()),
+            BinOp::SetMinus => set(a.set()?.difference(b.set()?).cloned().collect()),
+            BinOp::Range => set((a.int()?..=b.int()?).map(Value::Int).collect()),
+            BinOp::Add => Value::Int(a.int()? + b.int()?),
+            BinOp::Sub => Value::Int(a.int()? - b.int()?),
+            BinOp::Mul => Value::Int(a.int()? * b.int()?),
+            BinOp::Mod => Value::Int(a.int()?.rem_euclid(b.int()?)),
+            BinOp::ColonGt => Value::Fun(Rc::new(BTreeMap::from([(a, b)]))),
+            BinOp::AtAt => {
+                let mut fun = (**b.fun()?).clone();
+                fun.extend(a.fun()?.iter().map(|(key, value)| (key.clone(), value.clone())));
+                Value::Fun(Rc::new(fun))
+            },
+            BinOp::And | BinOp::Or | BinOp::Implies => unreachable!(),
+        })
+    }
+
+    fn evaluate(&self, expr: &Expr, env: &Env) -> Result<Value, String> {
+        Ok(match expr {
+            Expr::Bool(b) => Value::Bool(*b),
+            Expr::Int(n) => Value::Int(*n),
+            Expr::Str(s) => Value::Str(s.clone()),
+            Expr::Name(name) => self.name(name, env)?,
+            Expr::At => self.name("@", env)?,
+            Expr::Call(name, args) => self.call(name, args, env)?,
+            Expr::Apply(fun, arg) => {
+                let (fun, arg) = (self.evaluate(fun, env)?, self.evaluate(arg, env)?);
+                fun.fun()?.get(&arg).cloned().ok_or(format!("{arg} is not in the domain of {fun}"))?
+            },
+            Expr::Field(record, name) => {
+                let record = self.evaluate(record, env)?;
+                record.fun()?.get(&Value::Str(name.clone())).cloned().ok_or(format!("{record} has no field {name}"))?
+            },
+            Expr::Not(operand) => Value::Bool(!self.evaluate(operand, env)?.bool()?),
+            Expr::Neg(operand) => Value::Int(-self.evaluate(operand, env)?.int()?),
+            Expr::Domain(fun) => Value::Set(Rc::new(self.evaluate(fun, env)?.fun()?.keys().cloned().collect())),
+            Expr::Union(sets) => {
+                let sets = self.evaluate(sets, env)?;
+                let mut union = BTreeSet::new();
+                for set in sets.set()?.iter() {
+                    union.extend(set.set()?.iter().cloned());
+                }
+                Value::Set(Rc::new(union))
+            },
+            Expr::Binary(op, lhs, rhs) => self.binary(*op, lhs, rhs, env)?,
+            Expr::If(condition, then, otherwise) => {
+                if self.evaluate(condition, env)?.bool()? {
+                    self.evaluate(then, env)?
+                } else {
+                    self.evaluate(otherwise, env)?
+                }
+            },
+            Expr::Let(definitions, body) => {
+                let mut env = env.clone();
+                for definition in definitions {
+                    let binding = if definition.params.is_empty() {
+                        Binding::Lazy(definition.clone(), env.clone(), RefCell::new(None))
+                    } else {
+                        Binding::Operator(definition.clone(), env.clone())
+                    };
+                    env = env.bind(definition.name.clone(), binding);
+                }
+                self.evaluate(body, &env)?
+            },
+            Expr::Case(arms, other) => {
+                for (guard, value) in arms {
+                    if self.evaluate(guard, env)?.bool()? {
+                        return self.evaluate(value, env);
+                    }
+                }
+                match other {
+                    Some(other) => self.evaluate(other, env)?,
+                    None => return Err("no CASE arm applies".to_string()),
+                }
+            },
+            Expr::Quantifier(forall, binders, body) => {
+                let sets = self.bound_sets(binders, env)?;
+                let completed = self.each(&sets, env, &mut |env| Ok(self.evaluate(body, env)?.bool()? == *forall))?;
+                Value::Bool(completed == *forall)
+            },
+            Expr::Choose(name, set, condition) => {
+                let set = self.evaluate(set, env)?;
+                for value in set.set()?.iter() {
+                    if self.evaluate(condition, &env.bind(name.clone(), Binding::Value(value.clone())))?.bool()? {
+                        return Ok(value.clone());
+                    }
+                }
+                return Err(format!("CHOOSE {name} found nothing in {set}"));
+            },
+            Expr::SetOf(elements) => Value::Set(Rc::new(self.evaluate_all(elements, env)?.into_iter().collect())),
+            Expr::Filter(name, set, condition) => {
+                let mut filtered = BTreeSet::new();
+                for value in self.evaluate(set, env)?.set()?.iter() {
+                    if self.evaluate(condition, &env.bind(name.clone(), Binding::Value(value.clone())))?.bool()? {
+                        filtered.insert(value.clone());
+                    }
+                }
+                Value::Set(Rc::new(filtered))
+            },
+            Expr::Map(element, binders) => {
+                let sets = self.bound_sets(binders, env)?;
+                let mut mapped = BTreeSet::new();
+                self.each(&sets, env, &mut |env| {
+                    mapped.insert(self.evaluate(element, env)?);
+                    Ok(true)
+                })?;
+                Value::Set(Rc::new(mapped))
+            },
+            Expr::Tuple(elements) => Value::sequence(self.evaluate_all(elements, env)?),
+            Expr::Record(fields) => {
+                let fields = fields.iter().map(|(name, value)| Ok((Value::Str(name.clone()), self.evaluate(value, env)?)));
+                Value::Fun(Rc::new(fields.collect::<Result<_, String>>()?))
+            },
+            Expr::Function(name, set, body) => {
+                let mut fun = BTreeMap::new();
+                for value in self.evaluate(set, env)?.set()?.iter() {
+                    fun.insert(value.clone(), self.evaluate(body, &env.bind(name.clone(), Binding::Value(value.clone())))?);
+                }
+                Value::Fun(Rc::new(fun))
+            },
+            Expr::Except(base, updates) => {
+                let mut value = self.evaluate(base, env)?;
+                for (path, update) in updates {
+                    value = self.except(value, path, update, env)?;
+                }
+                value
+            },
+        })
+    }
+}
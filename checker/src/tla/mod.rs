@@ -0,0 +1,348 @@
+use crate::{
+    explore::{next, Bounds},
+    low_level::{host, init, invariants::INVARIANTS, Constants, Message, Variables},
+    Value,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt::Write,
+};
+
+pub mod eval;
+
+// The TLA+ version of the executable mirror in `checker/src/low_level`, `checker/tla/Paxos.tla`.
+pub const SPEC: &str = include_str!("../../tla/Paxos.tla");
+
+// The `Paxos.tla` operator for an `invariants::INVARIANTS` entry. Only the inline conjunct
+// `in_flight_messages.subset_of(sent_messages)` has no spec function to take its name from.
+pub fn operator_name(invariant: &str) -> &str {
+    match invariant {
+        "in_flight_messages.subset_of(sent_messages)" => "in_flight_messages_are_sent",
+        name => name,
+    }
+}
+
+fn ballot(ballot: &host::Ballot) -> String {
+    format!("[num |-> {}, pid |-> {}]", ballot.num, ballot.pid)
+}
+
+fn value(value: &Value) -> String {
+    if value.is_no_op() {
+        return format!("NoOp({})", value.seq);
+    }
+    format!("[client_id |-> {}, seq |-> {}, op |-> {}]", value.client_id, value.seq, value.op)
+}
+
+fn option<T>(value: &Option<T>, format: impl Fn(&T) -> String) -> String {
+    value.as_ref().map_or("<<>>".to_string(), |value| format!("<<{}>>", format(value)))
+}
+
+fn set(elements: impl IntoIterator<Item = String>) -> String {
+    format!("{{{}}}", elements.into_iter().collect::<Vec<_>>().join(", "))
+}
+
+// A non-empty function as `(k1 :> v1) @@ (k2 :> v2)`, which the TLC module defines.
+fn map(entries: impl IntoIterator<Item = (String, String)>) -> String {
+    let entries: Vec<String> = entries.into_iter().map(|(key, value)| format!("({key} :> {value})")).collect();
+    if entries.is_empty() {
+        "EmptyMap".to_string()
+    } else {
+        format!("({})", entries.join(" @@ "))
+    }
+}
+
+// Batch entries as a sequence of `<<key, value>>` pairs.
+fn format_entries(entries: &[(u64, Value)]) -> String {
+    format!("<<{}>>", entries.iter().map(|(key, v)| format!("<<{key}, {}>>", value(v))).collect::<Vec<_>>().join(", "))
+}
+
+pub fn format_message(message: &Message) -> String {
+    match message {
+        Message::Prepare { key, ballot: b } => format!("Prepare({key}, {})", ballot(b)),
+        Message::PrepareFrom { from, ballot: b } => format!("PrepareFrom({from}, {})", ballot(b)),
+        Message::Promise { key, sender, ballot: b, accepted } => {
+            let accepted = option(accepted, |(accepted_ballot, v)| format!("<<{}, {}>>", ballot(accepted_ballot), value(v)));
+            format!("Promise({key}, {sender}, {}, {accepted})", ballot(b))
+        },
+        Message::Accept { key, ballot: b, value: v } => format!("Accept({key}, {}, {})", ballot(b), value(v)),
+        Message::Accepted { key, sender, ballot: b, value: v } => format!("Accepted({key}, {sender}, {}, {})", ballot(b), value(v)),
+        Message::Nack { key, ballot: b, higher } => format!("Nack({key}, {}, {})", ballot(b), ballot(higher)),
+        Message::Decide { key, ballot: b, value: v } => format!("Decide({key}, {}, {})", ballot(b), value(v)),
+        Message::ClientRequest { value: v } => format!("ClientRequest({})", value(v)),
+        Message::AcceptBatch { ballot: b, entries } => format!("AcceptBatch({}, {})", ballot(b), format_entries(entries)),
+        Message::AcceptedBatch { sender, ballot: b, entries } => format!("AcceptedBatch({sender}, {}, {})", ballot(b), format_entries(entries)),
+        Message::DecideBatch { ballot: b, entries } => format!("DecideBatch({}, {})", ballot(b), format_entries(entries)),
+    }
+}
+
+fn format_volatile(volatile: &host::Volatile) -> String {
+    let promised = map(volatile.promised.iter().map(|(b, promises)| {
+        let promises = promises.iter().map(|(sender, accepted)| {
+            (sender.to_string(), option(accepted, |(accepted_ballot, v)| format!("<<{}, {}>>", ballot(accepted_ballot), value(v))))
+        });
+        (ballot(b), map(promises))
+    }));
+    let proposed_value = map(volatile.proposed_value.iter().map(|(b, v)| (ballot(b), value(v))));
+    let accepted = map(volatile.accepted.iter().map(|(b, senders)| (ballot(b), set(senders.iter().map(u64::to_string)))));
+    let learned = map(volatile.learned.iter().map(|((b, v), senders)| (format!("<<{}, {}>>", ballot(b), value(v)), set(senders.iter().map(u64::to_string)))));
+
+    format!("[promised |-> {promised}, proposed_value |-> {proposed_value}, accepted |-> {accepted}, learned |-> {learned}]")
+}
+
+fn format_instance(instance: &host::Instance) -> String {
+    format!(
+        "[current_ballot |-> {}, max_seen_ballot |-> {}, accept_ballot |-> {}, accept_value |-> {}, decide_value |-> {}, volatile |-> {}]",
+        ballot(&instance.current_ballot),
+        ballot(&instance.max_seen_ballot),
+        option(&instance.accept_ballot, ballot),
+        option(&instance.accept_value, value),
+        option(&instance.decide_value, value),
+        format_volatile(&instance.volatile),
+    )
+}
+
+fn format_host(host: &host::Variables) -> String {
+    let instances = map(host.instances.iter().map(|(key, instance)| (key.to_string(), format_instance(instance))));
+    let pending: Vec<String> = host.pending.iter().map(value).collect();
+    let assigned = set(host.assigned.iter().map(|(client_id, seq)| format!("<<{client_id}, {seq}>>")));
+    let multi = format!(
+        "[promise_floor |-> {}, floor_from |-> {}, leading |-> {}]",
+        ballot(&host.multi.promise_floor),
+        host.multi.floor_from,
+        option(&host.multi.leading, |(from, b)| format!("<<{from}, {}>>", ballot(b))),
+    );
+
+    format!(
+        "[instances |-> {instances}, pending |-> <<{}>>, assigned |-> {assigned}, up |-> {}, multi |-> {multi}]",
+        pending.join(", "),
+        if host.up { "TRUE" } else { "FALSE" },
+    )
+}
+
+// `u` as a value of `Paxos.tla`'s `state` variable.
+pub fn format_state(u: &Variables) -> String {
+    let hosts = map(u.hosts.iter().enumerate().map(|(id, host)| (id.to_string(), format_host(host))));
+    let history = map(u.history.iter().enumerate().map(|(id, history)| {
+        (id.to_string(), map(history.iter().map(|(key, volatile)| (key.to_string(), format_volatile(volatile)))))
+    }));
+    format!(
+        "[hosts |-> {hosts}, sent |-> {}, in_flight |-> {}, history |-> {history}]",
+        set(u.network.sent_messages.iter().map(format_message)),
+        set(u.network.in_flight_messages.iter().map(format_message)),
+    )
+}
+
+// Concrete states and pairs of them to evaluate `Paxos.tla` on. `steps` are `low_level::next`
+// steps, which `NextS` must allow; `non_steps` pair a state with a corrupted copy of itself that no
+// transition reaches, which `NextS` must reject. Every state also carries the verdict of each
+// `INVARIANTS` entry, so the corrupted states check that the TLA+ invariants fail where the Rust
+// ones do.
+#[derive(Clone, Debug, Default)]
+pub struct Conformance {
+    pub states: Vec<Variables>,
+    pub steps: Vec<(usize, usize)>,
+    pub non_steps: Vec<(usize, usize)>,
+}
+
+impl Conformance {
+    fn index(&mut self, indices: &mut BTreeMap<Variables, usize>, u: &Variables) -> usize {
+        *indices.entry(u.clone()).or_insert_with(|| {
+            self.states.push(u.clone());
+            self.states.len() - 1
+        })
+    }
+}
+
+// Every successor `Paxos.tla`'s `Successors` allows: `low_level::next` without the bounds, plus the
+// stutters `Successors` does not bother to exclude (dropping or duplicating a message that is not
+// or already is in flight, resubmitting a request).
+pub fn unbounded_successors(c: &Constants, bounds: &Bounds, u: &Variables) -> BTreeSet<Variables> {
+    let unbounded = Bounds {
+        max_ballot: u64::MAX,
+        proposers: usize::MAX,
+        max_pending: usize::MAX,
+        network_faults: true,
+        crashes: true,
+        batching: true,
+        ..bounds.clone()
+    };
+    let mut successors: BTreeSet<Variables> = next(c, &unbounded, u).into_iter().map(|(_, v)| v).collect();
+    successors.insert(u.clone());
+    successors
+}
+
+// Ways to corrupt `v` that usually break some invariant and that, from `v`, no single transition
+// takes. A value of a client past `bounds.values` is never submitted, so no host can hold or send it.
+fn corruptions(bounds: &Bounds, v: &Variables) -> Vec<Variables> {
+    let foreign = Value { client_id: bounds.values.len() as u64, seq: 0, op: bounds.values.iter().copied().max().unwrap_or(0) + 100 };
+    let mut corrupted = Vec::new();
+
+    if let Some((host_id, key)) = v.hosts.iter().enumerate().find_map(|(id, host)| host.instances.keys().next().map(|key| (id, *key))) {
+        let mut w = v.clone();
+        w.hosts[host_id].instances.get_mut(&key).unwrap().decide_value = Some(foreign);
+        corrupted.push(w);
+
+        let mut w = v.clone();
+        w.hosts[host_id].instances.get_mut(&key).unwrap().accept_ballot = Some(host::Ballot { num: bounds.max_ballot + 1, pid: 0 });
+        corrupted.push(w);
+    }
+
+    let mut w = v.clone();
+    let accept = Message::Accept { key: 0, ballot: host::Ballot { num: 1, pid: 0 }, value: foreign };
+    w.network.sent_messages.insert(accept.clone());
+    w.network.in_flight_messages.insert(accept);
+    corrupted.push(w);
+
+    let mut w = v.clone();
+    w.hosts[0].pending.push(foreign);
+    corrupted.push(w);
+
+    // `assigned` only grows, even across a crash.
+    if let Some(host_id) = v.hosts.iter().position(|host| !host.assigned.is_empty()) {
+        let mut w = v.clone();
+        w.hosts[host_id].assigned.clear();
+        corrupted.push(w);
+    }
+
+    corrupted
+}
+
+// Explores up to `limit` states breadth-first within `bounds` and samples `sources` of them, evenly
+// spread so that deep states are represented.
+pub fn conformance(c: &Constants, bounds: &Bounds, limit: usize, sources: usize) -> Conformance {
+    let mut reachable = vec![init(c)];
+    let mut seen = BTreeSet::from([init(c)]);
+    let mut queue = VecDeque::from([init(c)]);
+    while let Some(u) = queue.pop_front() {
+        for (_, v) in next(c, bounds, &u) {
+            if reachable.len() < limit && seen.insert(v.clone()) {
+                reachable.push(v.clone());
+                queue.push_back(v);
+            }
+        }
+    }
+
+    let stride = reachable.len().div_ceil(sources.max(1)).max(1);
+    let sources: Vec<Variables> = reachable.into_iter().step_by(stride).collect();
+    sample(c, bounds, &sources)
+}
+
+// `sources` together with all their successors and corruptions.
+pub fn sample(c: &Constants, bounds: &Bounds, sources: &[Variables]) -> Conformance {
+    let mut conformance = Conformance::default();
+    let mut indices = BTreeMap::new();
+    for u in sources {
+        let from = conformance.index(&mut indices, u);
+        let successors = unbounded_successors(c, bounds, u);
+        for (_, v) in next(c, bounds, u) {
+            let to = conformance.index(&mut indices, &v);
+            conformance.steps.push((from, to));
+        }
+        for w in corruptions(bounds, u) {
+            if !successors.contains(&w) {
+                let to = conformance.index(&mut indices, &w);
+                conformance.non_steps.push((from, to));
+            }
+        }
+    }
+    conformance
+}
+
+fn pairs(pairs: &[(usize, usize)]) -> String {
+    let pairs: Vec<String> = pairs.iter().map(|(u, v)| format!("<<{}, {}>>", u + 1, v + 1)).collect();
+    format!("<<{}>>", pairs.join(", "))
+}
+
+// The value `Paxos.tla` gets for each of its CONSTANTS from `c` and `bounds`.
+pub fn constants(c: &Constants, bounds: &Bounds) -> Vec<(&'static str, String)> {
+    let boolean = |b: bool| if b { "TRUE" } else { "FALSE" }.to_string();
+    let values: Vec<String> = bounds.values.iter().map(i64::to_string).collect();
+    vec![
+        ("NumHosts", c.num_hosts.to_string()),
+        ("NumFailures", c.num_failures.to_string()),
+        ("Phase1Size", c.quorums.phase1_size.to_string()),
+        ("Phase2Size", c.quorums.phase2_size.to_string()),
+        ("FastSize", c.fast_size.to_string()),
+        ("ConsumeOnReceive", boolean(c.network.consume_on_receive)),
+        ("Proposers", set(c.proposers.iter().map(u64::to_string))),
+        ("Learners", set(c.learners.iter().map(u64::to_string))),
+        ("FastClients", set(c.fast_clients.iter().map(u64::to_string))),
+        ("Keys", format!("0..{}", bounds.num_keys as i64 - 1)),
+        ("MaxBallot", bounds.max_ballot.to_string()),
+        ("Values", format!("<<{}>>", values.join(", "))),
+    ]
+}
+
+// `Paxos.tla` with `constants(c, bounds)`, for `eval` to evaluate its operators without TLC.
+pub fn instance(c: &Constants, bounds: &Bounds) -> Result<eval::Module, String> {
+    let mut module = eval::Module::parse(SPEC)?;
+    for (name, value) in constants(c, bounds) {
+        module.define(name, &value)?;
+    }
+    Ok(module)
+}
+
+// A module that instantiates `Paxos` with `c` and `bounds` and asserts, as TLC evaluates its
+// ASSUMEs, that `NextS` and `Invariants` agree with the executable mirror on `conformance`.
+pub fn conformance_module(c: &Constants, bounds: &Bounds, conformance: &Conformance) -> String {
+    let mut text = String::new();
+    let boolean = |b: bool| if b { "TRUE" } else { "FALSE" };
+
+    writeln!(text, "--------------------------- MODULE PaxosConformance ---------------------------").unwrap();
+    writeln!(text, "\\* Generated by `export_tla` from the executable mirror in `checker/src`; do not edit.").unwrap();
+    writeln!(text, "EXTENDS Integers, Sequences, TLC").unwrap();
+    writeln!(text).unwrap();
+    writeln!(text, "VARIABLE state").unwrap();
+    writeln!(text).unwrap();
+    writeln!(text, "INSTANCE Paxos WITH").unwrap();
+    let substitutions: Vec<String> = constants(c, bounds).into_iter().map(|(name, value)| format!("    {name} <- {value}")).collect();
+    writeln!(text, "{}", substitutions.join(",\n")).unwrap();
+    writeln!(text).unwrap();
+
+    writeln!(text, "States == <<").unwrap();
+    let states: Vec<String> = conformance.states.iter().map(|u| format!("    {}", format_state(u))).collect();
+    writeln!(text, "{}", states.join(",\n")).unwrap();
+    writeln!(text, ">>").unwrap();
+    writeln!(text).unwrap();
+    writeln!(text, "Steps == {}", pairs(&conformance.steps)).unwrap();
+    writeln!(text).unwrap();
+    writeln!(text, "NonSteps == {}", pairs(&conformance.non_steps)).unwrap();
+    writeln!(text).unwrap();
+
+    writeln!(text, "Expected == <<").unwrap();
+    let expected: Vec<String> = conformance
+        .states
+        .iter()
+        .map(|u| {
+            let fields: Vec<String> = INVARIANTS.iter().map(|(name, invariant)| format!("{} |-> {}", operator_name(name), boolean(invariant(c, u)))).collect();
+            format!("    [{}]", fields.join(", "))
+        })
+        .collect();
+    writeln!(text, "{}", expected.join(",\n")).unwrap();
+    writeln!(text, ">>").unwrap();
+    writeln!(text).unwrap();
+
+    text.push_str(
+        "\
+ASSUME \\A n \\in DOMAIN Steps :
+    Assert(NextS(States[Steps[n][1]], States[Steps[n][2]]), <<\"NextS rejects a low_level::next step\", Steps[n]>>)
+
+ASSUME \\A n \\in DOMAIN NonSteps :
+    Assert(~NextS(States[NonSteps[n][1]], States[NonSteps[n][2]]), <<\"NextS allows a corrupted step\", NonSteps[n]>>)
+
+ASSUME \\A n \\in DOMAIN States :
+    Assert(Invariants(States[n]) = Expected[n], <<\"invariants disagree on state\", n, Invariants(States[n])>>)
+
+ConformanceInit == state = InitState
+ConformanceNext == UNCHANGED state
+=============================================================================
+",
+    );
+    text
+}
+
+pub const CONFORMANCE_CONFIG: &str = "\
+\\* java -cp tla2tools.jar tlc2.TLC -config PaxosConformance.cfg PaxosConformance
+INIT ConformanceInit
+NEXT ConformanceNext
+";
@@ -0,0 +1,312 @@
+use crate::{
+    low_level::{batch, host, init, network, safety, Constants, Message, NetworkOperation, Variables},
+    simulate::Step,
+    Event, Value,
+};
+use std::{collections::BTreeSet, fmt::Write};
+
+// One logged host step: `low_level::Transition::HostStep { host_id, instance: key, net_op }`, or
+// `BatchStep { host_id, net_op }` when it receives or sends a batch, with its event. A batch step
+// logs the key of its first entry, which replaying it ignores. A trace is one record per line:
+//
+//   <host_id> ; <key> ; <recv> ; <send> ; <event>
+//
+// where an absent message is `-` and messages and events are written as
+//
+//   Prepare <key> <ballot>                 Promise <key> <sender> <ballot> <accepted>
+//   Accept <key> <ballot> <value>          Accepted <key> <sender> <ballot> <value>
+//   Nack <key> <ballot> <higher>           Decide <key> <ballot> <value>
+//   ClientRequest <value>                  PrepareFrom <from> <ballot>
+//   AcceptBatch <ballot> <entries>         AcceptedBatch <sender> <ballot> <entries>
+//   DecideBatch <ballot> <entries>
+//   NoOp   Decide <key> <value>   Propose <key> <value>   Submit <value>
+//   ProposeBatch <entries>                 DecideBatch <entries>
+//
+// with a ballot as `<num>.<pid>`, a value as `<client_id>:<seq>:<op>` and `<accepted>` as `-` or
+// `<ballot>=<value>` and `<entries>` as one `<key>=<value>` per entry. Blank lines and lines starting with `#` are ignored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub host_id: usize,
+    pub key: u64,
+    pub net_op: NetworkOperation,
+    pub event: Event,
+}
+
+fn format_ballot(ballot: &host::Ballot) -> String {
+    format!("{}.{}", ballot.num, ballot.pid)
+}
+
+fn format_entries(entries: &[(u64, Value)]) -> String {
+    entries.iter().map(|(key, value)| format!("{key}={value}")).collect::<Vec<_>>().join(" ")
+}
+
+pub fn format_message(message: &Message) -> String {
+    match message {
+        Message::Prepare { key, ballot } => format!("Prepare {key} {}", format_ballot(ballot)),
+        Message::PrepareFrom { from, ballot } => format!("PrepareFrom {from} {}", format_ballot(ballot)),
+        Message::Promise { key, sender, ballot, accepted } => {
+            let accepted = match accepted {
+                Some((accepted_ballot, value)) => format!("{}={value}", format_ballot(accepted_ballot)),
+                None => "-".to_string(),
+            };
+            format!("Promise {key} {sender} {} {accepted}", format_ballot(ballot))
+        },
+        Message::Accept { key, ballot, value } => format!("Accept {key} {} {value}", format_ballot(ballot)),
+        Message::Accepted { key, sender, ballot, value } => format!("Accepted {key} {sender} {} {value}", format_ballot(ballot)),
+        Message::Nack { key, ballot, higher } => format!("Nack {key} {} {}", format_ballot(ballot), format_ballot(higher)),
+        Message::Decide { key, ballot, value } => format!("Decide {key} {} {value}", format_ballot(ballot)),
+        Message::ClientRequest { value } => format!("ClientRequest {value}"),
+        Message::AcceptBatch { ballot, entries } => format!("AcceptBatch {} {}", format_ballot(ballot), format_entries(entries)),
+        Message::AcceptedBatch { sender, ballot, entries } => {
+            format!("AcceptedBatch {sender} {} {}", format_ballot(ballot), format_entries(entries))
+        },
+        Message::DecideBatch { ballot, entries } => format!("DecideBatch {} {}", format_ballot(ballot), format_entries(entries)),
+    }
+}
+
+pub fn format_event(event: &Event) -> String {
+    match event {
+        Event::NoOp => "NoOp".to_string(),
+        Event::Decide { key, value } => format!("Decide {key} {value}"),
+        Event::Propose { key, value } => format!("Propose {key} {value}"),
+        Event::Submit { value } => format!("Submit {value}"),
+        Event::ProposeBatch { entries } => format!("ProposeBatch {}", format_entries(entries)),
+        Event::DecideBatch { entries } => format!("DecideBatch {}", format_entries(entries)),
+    }
+}
+
+pub fn format_record(record: &Record) -> String {
+    let message = |message: &Option<Message>| message.as_ref().map_or("-".to_string(), format_message);
+
+    format!(
+        "{} ; {} ; {} ; {} ; {}",
+        record.host_id,
+        record.key,
+        message(&record.net_op.recv),
+        message(&record.net_op.send),
+        format_event(&record.event),
+    )
+}
+
+pub fn format_trace(records: &[Record]) -> String {
+    let mut text = String::new();
+    for record in records {
+        writeln!(text, "{}", format_record(record)).unwrap();
+    }
+    text
+}
+
+fn number<T: std::str::FromStr>(token: Option<&str>) -> Result<T, String> {
+    let token = token.ok_or("missing field")?;
+    token.parse().map_err(|_| format!("`{token}` is not a number"))
+}
+
+fn value(token: Option<&str>) -> Result<Value, String> {
+    let token = token.ok_or("missing value")?;
+    token.parse().map_err(|_| format!("`{token}` is not a value `<client_id>:<seq>:<op>`"))
+}
+
+fn ballot(token: Option<&str>) -> Result<host::Ballot, String> {
+    let token = token.ok_or("missing ballot")?;
+    let (num, pid) = token.split_once('.').ok_or(format!("`{token}` is not a ballot `<num>.<pid>`"))?;
+    Ok(host::Ballot { num: number(Some(num))?, pid: number(Some(pid))? })
+}
+
+// The rest of the line as batch entries, at least one.
+fn entries(tokens: std::str::SplitWhitespace) -> Result<Vec<(u64, Value)>, String> {
+    let entries = tokens
+        .map(|token| {
+            let (key, entry_value) = token.split_once('=').ok_or(format!("`{token}` is not `<key>=<value>`"))?;
+            Ok((number(Some(key))?, value(Some(entry_value))?))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if entries.is_empty() {
+        return Err("missing entries".to_string());
+    }
+    Ok(entries)
+}
+
+fn finish<T>(mut tokens: std::str::SplitWhitespace, parsed: T) -> Result<T, String> {
+    match tokens.next() {
+        Some(token) => Err(format!("unexpected `{token}`")),
+        None => Ok(parsed),
+    }
+}
+
+pub fn parse_message(text: &str) -> Result<Option<Message>, String> {
+    let mut tokens = text.split_whitespace();
+    let message = match tokens.next() {
+        Some("-") => return finish(tokens, None),
+        Some("Prepare") => Message::Prepare { key: number(tokens.next())?, ballot: ballot(tokens.next())? },
+        Some("PrepareFrom") => Message::PrepareFrom { from: number(tokens.next())?, ballot: ballot(tokens.next())? },
+        Some("Promise") => {
+            let (key, sender, promise_ballot) = (number(tokens.next())?, number(tokens.next())?, ballot(tokens.next())?);
+            let accepted = match tokens.next() {
+                Some("-") => None,
+                Some(token) => {
+                    let (accepted_ballot, accepted_value) = token.split_once('=').ok_or(format!("`{token}` is not `<ballot>=<value>`"))?;
+                    Some((ballot(Some(accepted_ballot))?, value(Some(accepted_value))?))
+                },
+                None => return Err("missing accepted".to_string()),
+            };
+            Message::Promise { key, sender, ballot: promise_ballot, accepted }
+        },
+        Some("Accept") => Message::Accept { key: number(tokens.next())?, ballot: ballot(tokens.next())?, value: value(tokens.next())? },
+        Some("Accepted") => Message::Accepted {
+            key: number(tokens.next())?,
+            sender: number(tokens.next())?,
+            ballot: ballot(tokens.next())?,
+            value: value(tokens.next())?,
+        },
+        Some("Nack") => Message::Nack { key: number(tokens.next())?, ballot: ballot(tokens.next())?, higher: ballot(tokens.next())? },
+        Some("Decide") => Message::Decide { key: number(tokens.next())?, ballot: ballot(tokens.next())?, value: value(tokens.next())? },
+        Some("ClientRequest") => Message::ClientRequest { value: value(tokens.next())? },
+        Some("AcceptBatch") => return Ok(Some(Message::AcceptBatch { ballot: ballot(tokens.next())?, entries: entries(tokens)? })),
+        Some("AcceptedBatch") => {
+            let (sender, batch_ballot) = (number(tokens.next())?, ballot(tokens.next())?);
+            return Ok(Some(Message::AcceptedBatch { sender, ballot: batch_ballot, entries: entries(tokens)? }));
+        },
+        Some("DecideBatch") => return Ok(Some(Message::DecideBatch { ballot: ballot(tokens.next())?, entries: entries(tokens)? })),
+        Some(token) => return Err(format!("unknown message `{token}`")),
+        None => return Err("missing message".to_string()),
+    };
+    finish(tokens, Some(message))
+}
+
+pub fn parse_event(text: &str) -> Result<Event, String> {
+    let mut tokens = text.split_whitespace();
+    let event = match tokens.next() {
+        Some("NoOp") => Event::NoOp,
+        Some("Decide") => Event::Decide { key: number(tokens.next())?, value: value(tokens.next())? },
+        Some("Propose") => Event::Propose { key: number(tokens.next())?, value: value(tokens.next())? },
+        Some("Submit") => Event::Submit { value: value(tokens.next())? },
+        Some("ProposeBatch") => return Ok(Event::ProposeBatch { entries: entries(tokens)? }),
+        Some("DecideBatch") => return Ok(Event::DecideBatch { entries: entries(tokens)? }),
+        Some(token) => return Err(format!("unknown event `{token}`")),
+        None => return Err("missing event".to_string()),
+    };
+    finish(tokens, event)
+}
+
+pub fn parse_record(line: &str) -> Result<Record, String> {
+    let fields: Vec<&str> = line.split(';').map(str::trim).collect();
+    let [host_id, key, recv, send, event] = fields[..] else {
+        return Err(format!("expected 5 `;`-separated fields, found {}", fields.len()));
+    };
+
+    Ok(Record {
+        host_id: number(Some(host_id))?,
+        key: number(Some(key))?,
+        net_op: NetworkOperation { send: parse_message(send)?, recv: parse_message(recv)? },
+        event: parse_event(event)?,
+    })
+}
+
+// The records of `text`, or the 1-based line number of the first malformed one and why.
+pub fn parse_trace(text: &str) -> Result<Vec<Record>, (usize, String)> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| parse_record(line).map_err(|error| (i + 1, error)))
+        .collect()
+}
+
+// The records of the host and batch steps in a simulated run, as a production node would log them.
+pub fn from_simulation(trace: &[Step]) -> Vec<Record> {
+    trace
+        .iter()
+        .map(|step| match &step.transition {
+            crate::low_level::Transition::HostStep { host_id, instance, net_op } => {
+                Record { host_id: *host_id, key: *instance, net_op: net_op.clone(), event: step.event.clone() }
+            },
+            crate::low_level::Transition::BatchStep { host_id, net_op } => {
+                let batch = net_op.recv.iter().chain(&net_op.send).find(|message| batch::is_batch(message)).unwrap();
+                Record { host_id: *host_id, key: batch::entries(batch)[0].0, net_op: net_op.clone(), event: step.event.clone() }
+            },
+            _ => unreachable!("the simulator only takes host and batch steps"),
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    // Every record is a legal `HostStep` or `BatchStep` and no state along the way breaks `safety`.
+    Valid { steps: usize },
+    // Record `step` (0-based) is not a `HostStep` or `BatchStep` from any state the earlier records
+    // can lead to.
+    IllegalStep { step: usize, record: Record, reason: String },
+    // Every state record `step` can lead to breaks `safety`.
+    Unsafe { step: usize, record: Record },
+}
+
+// The successors of `u` under `HostStep { host_id, instance: key, net_op }` with `event`, or
+// `BatchStep` when a batch is received or sent: the `host::step` (or `batch::step`) and
+// `network::step` conjuncts of `is_valid_transition`, solved for `v`.
+fn successors(c: &Constants, u: &Variables, record: &Record) -> Result<Vec<Variables>, String> {
+    let Record { host_id, key, net_op, event } = record.clone();
+    if host_id >= u.hosts.len() {
+        return Err(format!("host {host_id} does not exist"));
+    }
+    let Some(network) = network::step(&c.network, &u.network, &net_op) else {
+        return Err("the received message was never sent".to_string());
+    };
+
+    let (lc, lu) = (&c.hosts[host_id], &u.hosts[host_id]);
+    let hosts: Vec<host::Variables> = match event {
+        Event::Submit { value } => match (lu.up, &net_op.recv) {
+            (true, None) => host::submit_request(lu, value)
+                .filter(|(_, send)| *send == net_op.send)
+                .map(|(v, _)| v)
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        },
+        _ if net_op.recv.iter().chain(&net_op.send).any(batch::is_batch) => batch::successors(lc, lu, net_op.recv.as_ref())
+            .into_iter()
+            .filter(|(e, _, send)| *e == event && *send == net_op.send)
+            .map(|(_, v, _)| v)
+            .collect(),
+        _ => host::successors(lc, lu, key, net_op.recv.as_ref())
+            .into_iter()
+            .filter(|(e, _, send)| *e == event && *send == net_op.send)
+            .map(|(_, v, _)| v)
+            .collect(),
+    };
+
+    if hosts.is_empty() {
+        return Err(if lu.up { "no host transition allows this step" } else { "the host is down" }.to_string());
+    }
+    Ok(hosts
+        .into_iter()
+        .map(|host| u.after_host_step(host_id, host, network.clone()))
+        .collect())
+}
+
+// Replays `records` from `init(c)`. A log does not say which transition a record is, and some
+// records fit more than one (a `crash` and an `init_request` both log `- ; - ; NoOp`), so this
+// tracks every state the trace can have reached so far.
+pub fn validate(c: &Constants, records: &[Record]) -> Verdict {
+    let mut states = BTreeSet::from([init(c)]);
+
+    for (step, record) in records.iter().enumerate() {
+        let mut next = BTreeSet::new();
+        let mut reason = String::new();
+        for u in &states {
+            match successors(c, u, record) {
+                Ok(successors) => next.extend(successors),
+                Err(error) => reason = error,
+            }
+        }
+
+        if next.is_empty() {
+            return Verdict::IllegalStep { step, record: record.clone(), reason };
+        }
+        next.retain(|v| safety(c, v));
+        if next.is_empty() {
+            return Verdict::Unsafe { step, record: record.clone() };
+        }
+        states = next;
+    }
+
+    Verdict::Valid { steps: records.len() }
+}
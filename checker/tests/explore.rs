@@ -0,0 +1,70 @@
+use paxos_checker::{
+    explore::{check, check_invariants, next, replay, Bounds, Outcome},
+    low_level::{init, quorum::{all_hosts, majority_quorums}, some_host_decided, Constants, Variables},
+};
+use std::collections::HashSet;
+
+fn constants() -> Constants {
+    Constants::new(3, 1, majority_quorums(all_hosts(3), 1), false)
+}
+
+fn tiny() -> Bounds {
+    Bounds { max_ballot: 1, proposers: 1, values: vec![1], ..Bounds::default() }
+}
+
+// Deliberately false: the checker has to find a run in which key 0 gets decided.
+fn nothing_is_decided(_: &Constants, u: &Variables) -> bool {
+    !some_host_decided(u, 0)
+}
+
+#[test]
+fn small_bounds_are_verified() {
+    match check(&constants(), &tiny(), 100_000) {
+        Outcome::Verified { states, depth } => assert!(states > 1 && depth > 0),
+        Outcome::Incomplete { states, .. } => panic!("search stopped after {states} states"),
+        Outcome::Violation { violated, .. } => panic!("violated: {}", violated.join(", ")),
+    }
+}
+
+// Batch steps are runs of single-key steps, so they reach no state that breaks an invariant. With a
+// second key the state space is out of reach even for two hosts, so every batch here has one entry;
+// `batching_keeps_every_invariant` in `tests/simulate.rs` runs longer batches.
+#[test]
+fn batching_is_verified() {
+    let c = Constants::new(2, 0, majority_quorums(all_hosts(2), 0), false);
+    match check(&c, &Bounds { batching: true, ..tiny() }, 100_000) {
+        Outcome::Verified { states, depth } => assert!(states > 1 && depth > 0),
+        Outcome::Incomplete { states, .. } => panic!("search stopped after {states} states"),
+        Outcome::Violation { violated, .. } => panic!("violated: {}", violated.join(", ")),
+    }
+}
+
+#[test]
+fn a_search_cut_short_is_incomplete() {
+    assert!(matches!(check(&constants(), &tiny(), 100), Outcome::Incomplete { states: 100, .. }));
+}
+
+#[test]
+fn a_broken_invariant_yields_a_shortest_trace() {
+    let (c, bounds) = (constants(), tiny());
+    let Outcome::Violation { violated, init: initial, trace } = check_invariants(&c, &bounds, 100_000, &[("nothing_is_decided", nothing_is_decided)]) else {
+        panic!("no violation found");
+    };
+    assert_eq!(violated, vec!["nothing_is_decided"]);
+    assert_eq!(initial, init(&c));
+
+    // The trace replays, and only its last state breaks the invariant.
+    let steps: Vec<_> = trace.iter().map(|(step, _)| step.clone()).collect();
+    let states = replay(&c, &bounds, &steps).expect("the trace does not replay");
+    assert_eq!(&states[1..], trace.iter().map(|(_, v)| v.clone()).collect::<Vec<_>>());
+    assert!(!nothing_is_decided(&c, states.last().unwrap()));
+    assert!(states[..states.len() - 1].iter().all(|u| nothing_is_decided(&c, u)));
+
+    // No state fewer steps away breaks it.
+    let mut seen = HashSet::from([initial.clone()]);
+    let mut level = vec![initial];
+    for _ in 1..trace.len() {
+        level = level.iter().flat_map(|u| next(&c, &bounds, u)).map(|(_, v)| v).filter(|v| seen.insert(v.clone())).collect();
+        assert!(level.iter().all(|v| nothing_is_decided(&c, v)));
+    }
+}
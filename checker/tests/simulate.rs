@@ -0,0 +1,424 @@
+use paxos_checker::{
+    low_level::{
+        batch::{accept_batch, accepted_batch, ballots, decide_batch, entries, is_run, propose_batch, send_decide_batch, unbatch},
+        host::{self, fast_ballot, send_prepare, Ballot, Outcome},
+        invariants::violated,
+        is_valid_transition, Message, NetworkOperation, Transition, Variables,
+    },
+    simulate::{simulate, Action, Config, Simulator},
+    Event, Value,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+// Replays `seed` under `config` and panics with the seed and the failing step, so a red run can be
+// reproduced with `cargo run --bin simulate -- --seed <seed> --trace`.
+fn assert_agreement(config: &Config, seed: u64) {
+    let report = simulate(config, seed);
+    assert!(report.violation.is_none(), "seed {seed}: agreement violated after step {:?}", report.violation);
+}
+
+#[test]
+fn agreement_holds_under_default_faults() {
+    let config = Config::default();
+    for seed in 0..200 {
+        assert_agreement(&config, seed);
+    }
+}
+
+#[test]
+fn agreement_holds_under_heavy_faults() {
+    let config = Config { drop_percent: 40, duplicate_percent: 40, max_delay: 20, crash_percent: 10, recover_percent: 10, ..Config::default() };
+    for seed in 0..200 {
+        assert_agreement(&config, seed);
+    }
+}
+
+#[test]
+fn agreement_holds_with_five_hosts() {
+    let config = Config { num_hosts: 5, num_failures: 2, num_keys: 3, ..Config::default() };
+    for seed in 0..100 {
+        assert_agreement(&config, seed);
+    }
+}
+
+// Host 0 is the only proposer and hosts 1 and 2 learn on their own, so every decision a learner
+// makes locally comes from counting Accepted messages rather than from a Decide.
+#[test]
+fn agreement_holds_with_separate_learners() {
+    let config = Config { proposers: Some(vec![0]), learners: vec![1, 2], ..Config::default() };
+    let mut learner_decisions = 0;
+    for seed in 0..100 {
+        let report = simulate(&config, seed);
+        assert!(report.violation.is_none(), "seed {seed}: agreement violated after step {:?}", report.violation);
+        learner_decisions += report
+            .trace
+            .iter()
+            .filter(|step| matches!(step.action, Action::Local { host_id, .. } if host_id != 0) && matches!(step.event, Event::Decide { .. }))
+            .count();
+    }
+    assert!(learner_decisions > 0, "no learner decided without a Decide");
+}
+
+// Once a proposer has taken a Nack, every Prepare it sends for that key outbids the acceptor's ballot.
+#[test]
+fn nacked_proposers_prepare_above_the_higher_ballot() {
+    let config = Config { crash_percent: 0, ..Config::default() };
+    let mut nacks = 0;
+    for seed in 0..100 {
+        let report = simulate(&config, seed);
+        let mut floor = BTreeMap::new();
+        for step in &report.trace {
+            let Transition::HostStep { host_id, instance, net_op: NetworkOperation { send, recv } } = step.transition.clone() else { continue };
+            if let Some(Message::Nack { higher, .. }) = recv {
+                floor.insert((host_id, instance), higher);
+                nacks += 1;
+            }
+            if let (Some(Message::Prepare { ballot, .. }), Some(higher)) = (send, floor.get(&(host_id, instance))) {
+                assert!(ballot > *higher, "seed {seed}: host {host_id} prepared {ballot:?} after a Nack for {higher:?}");
+            }
+        }
+    }
+    assert!(nacks > 0, "no proposer took a Nack");
+}
+
+// `host::next_ballot` is above every ballot a proposer has in its maps, so an undecided instance never
+// leaves a proposer unable to prepare, however far behind its `current_ballot` is.
+#[test]
+fn proposers_can_always_prepare() {
+    let config = Config { crash_percent: 5, ..Config::default() };
+    for seed in 0..50 {
+        let mut simulator = Simulator::new(&config, seed);
+        for _ in 0..config.steps {
+            simulator.step();
+            for (id, host) in simulator.state.hosts.iter().enumerate() {
+                for (key, instance) in &host.instances {
+                    if host.up && instance.decide_value.is_none() {
+                        assert!(send_prepare(&simulator.c.hosts[id], host, *key).is_some(), "seed {seed}: host {id} cannot prepare key {key}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Half of the new ballots come from a PrepareFrom, so instances are prepared by the promise floor
+// (`init_request`, `raise_to_floor`) as well as one Prepare at a time, and every invariant the
+// proof relies on still holds along the way.
+#[test]
+fn prepare_from_runs_keep_every_invariant() {
+    let config = Config { prepare_percent: 10, prepare_from_percent: 50, crash_percent: 5, steps: 1_000, ..Config::default() };
+    let (mut floors, mut decided) = (0, 0);
+    for seed in 0..30 {
+        let mut simulator = Simulator::new(&config, seed);
+        for _ in 0..config.steps {
+            simulator.step();
+            let broken = violated(&simulator.c, &simulator.state);
+            assert!(broken.is_empty(), "seed {seed}: {broken:?} violated after step {}", simulator.report.trace.len());
+        }
+        floors += simulator
+            .report
+            .trace
+            .iter()
+            .filter(|step| {
+                matches!(step.action, Action::Local { .. })
+                    && matches!(step.transition, Transition::HostStep { net_op: NetworkOperation { send: Some(Message::Promise { .. }), .. }, .. })
+            })
+            .count();
+        decided += simulator.state.hosts.iter().flat_map(|host| host.instances.values()).filter(|instance| instance.decide_value.is_some()).count();
+    }
+    assert!(floors > 0, "no instance promised a floor");
+    assert!(decided > 0, "nothing was decided");
+}
+
+// Clients 0 and 2 send their requests straight to the acceptors of instance 0, so some fast rounds
+// collide and a later classic ballot has to recover whatever a fast quorum may have accepted
+// (`select_value`), while client 1 goes through its owner; every invariant holds throughout.
+#[test]
+fn fast_rounds_keep_every_invariant() {
+    let config = Config { fast_clients: vec![0, 2], learners: vec![0, 1, 2], drop_percent: 5, steps: 1_000, ..Config::default() };
+    let mut fast_decisions = 0;
+    for seed in 0..30 {
+        let mut simulator = Simulator::new(&config, seed);
+        for _ in 0..config.steps {
+            simulator.step();
+            let broken = violated(&simulator.c, &simulator.state);
+            assert!(broken.is_empty(), "seed {seed}: {broken:?} violated after step {}", simulator.report.trace.len());
+        }
+        fast_decisions += simulator.state.network.sent_messages.iter().filter(|m| matches!(m, Message::Decide { ballot, .. } if ballot == &fast_ballot())).count();
+    }
+    assert!(fast_decisions > 0, "no fast round was decided");
+}
+
+#[test]
+fn runs_are_reproducible_from_the_seed() {
+    let config = Config { crash_percent: 5, ..Config::default() };
+    for seed in 0..20 {
+        let (first, second) = (simulate(&config, seed), simulate(&config, seed));
+        assert_eq!(first.trace, second.trace, "seed {seed}");
+        assert_eq!(first.decided, second.decided, "seed {seed}");
+    }
+}
+
+// The owner of every Decide ballot has recorded the decision itself, so a client talking to the
+// leader can learn the outcome.
+#[test]
+fn leaders_learn_their_own_decisions() {
+    let config = Config { crash_percent: 0, ..Config::default() };
+    for seed in 0..50 {
+        let mut simulator = Simulator::new(&config, seed);
+        for _ in 0..config.steps {
+            simulator.step();
+        }
+
+        for message in &simulator.state.network.sent_messages {
+            if let Message::Decide { key, ballot, value } = *message {
+                let leader = &simulator.state.hosts[ballot.pid as usize].instances[&key];
+                assert_eq!(leader.decide_value, Some(value), "seed {seed}: leader of {ballot:?} has not learned key {key}");
+            }
+        }
+    }
+}
+
+// Every recorded step is a `low_level::next` transition from the state before it.
+#[test]
+fn every_step_is_a_valid_transition() {
+    let config = Config { crash_percent: 5, ..Config::default() };
+    for seed in 0..20 {
+        let simulator = Simulator::new(&config, seed);
+        let c = simulator.c.clone();
+        let report = simulator.run();
+
+        let mut replay = Simulator::new(&config, seed);
+        let mut states: Vec<Variables> = vec![replay.state.clone()];
+        while states.len() <= report.trace.len() {
+            let recorded = replay.report.trace.len();
+            replay.step();
+            if replay.report.trace.len() > recorded {
+                states.push(replay.state.clone());
+            }
+        }
+
+        for (i, step) in report.trace.iter().enumerate() {
+            assert!(
+                is_valid_transition(&c, &states[i], &states[i + 1], &step.transition, &step.event),
+                "seed {seed}, step {i}: {step:?}",
+            );
+        }
+    }
+}
+
+// Clients resubmit their requests and the network duplicates them, yet the owner queues each request
+// once and no request id is decided in two slots.
+#[test]
+fn resubmitted_requests_are_decided_at_most_once() {
+    let config = Config { num_keys: 4, duplicate_percent: 30, resubmit_percent: 20, ..Config::default() };
+    let mut resubmitted = 0;
+    for seed in 0..100 {
+        let report = simulate(&config, seed);
+        assert!(report.violation.is_none(), "seed {seed}: agreement violated after step {:?}", report.violation);
+
+        let mut slots = BTreeMap::new();
+        for (key, value) in &report.decided {
+            if let Some(other) = slots.insert(value.request(), *key) {
+                panic!("seed {seed}: request {value} decided in keys {other} and {key}");
+            }
+        }
+        let mut submitted = BTreeSet::new();
+        resubmitted += report
+            .trace
+            .iter()
+            .filter(|step| matches!(step.action, Action::Submit { request, .. } if !submitted.insert(request.client_id)))
+            .count();
+    }
+    assert!(resubmitted > 0, "no client resubmitted a request");
+}
+
+// Requests are told apart by `(client_id, seq)`, not by operation, so clients may submit the same
+// operation and each request is still decided in its own slot.
+#[test]
+fn requests_with_the_same_operation_are_decided_separately() {
+    let config = Config { num_keys: 4, values: vec![7, 7, 7], resubmit_percent: 20, ..Config::default() };
+    let mut repeated = 0;
+    for seed in 0..50 {
+        let report = simulate(&config, seed);
+        assert!(report.violation.is_none(), "seed {seed}: agreement violated after step {:?}", report.violation);
+
+        let requests: BTreeSet<(u64, u64)> = report.decided.values().map(Value::request).collect();
+        assert_eq!(requests.len(), report.decided.len(), "seed {seed}: a request was decided twice");
+        if requests.len() > 1 {
+            repeated += 1;
+        }
+    }
+    assert!(repeated > 0, "no run decided two requests with the same operation");
+}
+
+// The Accept, Accepted and Decide messages sent so far, packed into one batch per ballot (and sender),
+// keeping the first entry for each key.
+fn batches(sent: &BTreeSet<Message>) -> Vec<Message> {
+    let mut accepts: BTreeMap<Ballot, BTreeMap<u64, Value>> = BTreeMap::new();
+    let mut accepteds: BTreeMap<(u64, Ballot), BTreeMap<u64, Value>> = BTreeMap::new();
+    let mut decides: BTreeMap<Ballot, BTreeMap<u64, Value>> = BTreeMap::new();
+    for message in sent {
+        match *message {
+            Message::Accept { key, ballot, value } => accepts.entry(ballot).or_default().entry(key).or_insert(value),
+            Message::Accepted { key, sender, ballot, value } => accepteds.entry((sender, ballot)).or_default().entry(key).or_insert(value),
+            Message::Decide { key, ballot, value } => decides.entry(ballot).or_default().entry(key).or_insert(value),
+            _ => continue,
+        };
+    }
+
+    let accepts = accepts.into_iter().map(|(ballot, entries)| Message::AcceptBatch { ballot, entries: entries.into_iter().collect() });
+    let accepteds = accepteds
+        .into_iter()
+        .map(|((sender, ballot), entries)| Message::AcceptedBatch { sender, ballot, entries: entries.into_iter().collect() });
+    let decides = decides.into_iter().map(|(ballot, entries)| Message::DecideBatch { ballot, entries: entries.into_iter().collect() });
+    accepts.chain(accepteds).chain(decides).collect()
+}
+
+// The single-key transition that `unbatch` stands for.
+fn single_step(c: &host::Constants, u: &host::Variables, key: u64, recv: &Message) -> Outcome {
+    match recv {
+        Message::Accept { .. } => host::accept(c, u, key, recv),
+        Message::Accepted { .. } => host::accepted(u, key, recv),
+        Message::Decide { .. } => host::decide(u, key, recv),
+        _ => None,
+    }
+}
+
+// The event of the single-key step that takes `recv`.
+fn single_event(recv: &Message) -> Event {
+    match *recv {
+        Message::Decide { key, value, .. } => Event::Decide { key, value },
+        _ => Event::NoOp,
+    }
+}
+
+// A batch step a host receives succeeds exactly when the single-key steps for its entries succeed
+// one after the other, ends in the same state, replies with the batch of their replies and has the
+// events they have; the states in between form a `batch::is_run`. A batch a leader proposes is the
+// run of `send_accept`s for its entries, and a DecideBatch a leader sends carries Decides it would send.
+#[test]
+fn batches_are_runs_of_single_key_steps() {
+    let config = Config { crash_percent: 5, ..Config::default() };
+    let (mut batched, mut decided, mut proposed) = (0, 0, 0);
+    for seed in 0..5 {
+        let mut simulator = Simulator::new(&config, seed);
+        for _ in 0..config.steps {
+            simulator.step();
+            let sent = &simulator.state.network.sent_messages;
+            for recv in batches(sent) {
+                for (id, u) in simulator.state.hosts.iter().enumerate().filter(|(_, u)| u.up) {
+                    let c = &simulator.c.hosts[id];
+                    let outcome = match recv {
+                        Message::AcceptBatch { .. } => accept_batch(c, u, &recv).map(|(v, send)| (Event::NoOp, v, send)),
+                        Message::AcceptedBatch { .. } => accepted_batch(u, &recv).map(|(v, send)| (Event::NoOp, v, send)),
+                        _ => decide_batch(u, &recv),
+                    };
+
+                    let mut states = vec![u.clone()];
+                    let mut steps = Vec::new();
+                    for (message, (key, _)) in unbatch(&recv).into_iter().zip(entries(&recv)) {
+                        let Some((v, send)) = single_step(c, states.last().unwrap(), *key, &message) else { break };
+                        states.push(v);
+                        steps.push((*key, NetworkOperation { send, recv: Some(message.clone()) }, single_event(&message)));
+                    }
+
+                    let Some((event, v, send)) = outcome else {
+                        assert!(steps.len() < entries(&recv).len(), "seed {seed}: host {id} rejected {recv:?} but took every entry");
+                        continue;
+                    };
+                    assert_eq!(steps.len(), entries(&recv).len(), "seed {seed}: host {id} took {recv:?} but not every entry");
+                    assert_eq!(states.last(), Some(&v), "seed {seed}: host {id} on {recv:?}");
+                    let replies: Vec<Message> = steps.iter().filter_map(|(_, net_op, _)| net_op.send.clone()).collect();
+                    assert_eq!(send.as_ref().map(unbatch), if replies.is_empty() { None } else { Some(replies) }, "seed {seed}: host {id} on {recv:?}");
+                    if event.is_batch() {
+                        assert_eq!(event.unbatch(), steps.iter().map(|(_, _, e)| e.clone()).collect::<Vec<_>>(), "seed {seed}: host {id} on {recv:?}");
+                        decided += 1;
+                    }
+                    assert!(is_run(c, &states, &steps), "seed {seed}: host {id} on {recv:?}");
+                    batched += 1;
+                }
+            }
+
+            for (id, u) in simulator.state.hosts.iter().enumerate().filter(|(_, u)| u.up) {
+                let c = &simulator.c.hosts[id];
+                for ballot in ballots(u) {
+                    if let Some((v, send)) = send_decide_batch(c, u, ballot) {
+                        assert_eq!(&v, u, "seed {seed}: host {id} changed by sending a batch");
+                        for message in unbatch(send.as_ref().unwrap()) {
+                            let Message::Decide { key, .. } = message else { panic!("seed {seed}: host {id} batched {message:?}") };
+                            assert_eq!(host::send_decide(c, u, key).and_then(|(_, send)| send), Some(message));
+                        }
+                    }
+
+                    let Some((event, v, send)) = propose_batch(c, u, ballot) else { continue };
+                    let mut states = vec![u.clone()];
+                    let mut steps = Vec::new();
+                    for message in unbatch(send.as_ref().unwrap()) {
+                        let Message::Accept { key, value, .. } = message else { panic!("seed {seed}: host {id} batched {message:?}") };
+                        let (s, accept) = host::send_accept(c, states.last().unwrap(), key).expect("a batched proposal send_accept cannot make");
+                        assert_eq!(accept, Some(message), "seed {seed}: host {id} at {ballot:?}");
+                        states.push(s);
+                        steps.push((key, NetworkOperation { send: accept, recv: None }, Event::Propose { key, value }));
+                    }
+                    assert_eq!(states.last(), Some(&v), "seed {seed}: host {id} at {ballot:?}");
+                    assert_eq!(event.unbatch(), steps.iter().map(|(_, _, e)| e.clone()).collect::<Vec<_>>(), "seed {seed}: host {id} at {ballot:?}");
+                    assert!(is_run(c, &states, &steps), "seed {seed}: host {id} at {ballot:?}");
+                    proposed += 1;
+                }
+            }
+        }
+    }
+    assert!(batched > 0, "no host took a batch");
+    assert!(decided > 0, "no host took the decisions of a batch");
+    assert!(proposed > 0, "no host proposed a batch");
+}
+
+// A fifth of the local steps send a batch, which acceptors take whole and hosts decide from in one
+// step; every invariant holds throughout and the batches do get decisions made.
+#[test]
+fn batching_keeps_every_invariant() {
+    let config = Config { num_keys: 3, batch_percent: 20, steps: 1_000, ..Config::default() };
+    let mut batch_decisions = 0;
+    for seed in 0..30 {
+        let mut simulator = Simulator::new(&config, seed);
+        for _ in 0..config.steps {
+            simulator.step();
+            let broken = violated(&simulator.c, &simulator.state);
+            assert!(broken.is_empty(), "seed {seed}: {broken:?} violated after step {}", simulator.report.trace.len());
+        }
+        batch_decisions += simulator
+            .report
+            .trace
+            .iter()
+            .filter(|step| matches!(step.transition, Transition::BatchStep { .. }) && matches!(step.event, Event::DecideBatch { .. }))
+            .count();
+    }
+    assert!(batch_decisions > 0, "no host decided from a DecideBatch");
+}
+
+// Five hosts, and commands that hand the votes to hosts 0-2 and then to hosts 2-4, two instances
+// after they are decided. Hosts wait for the decisions that fix an instance's quorums before they
+// start it, and every invariant holds with instances counted in different configurations.
+#[test]
+fn reconfiguration_keeps_every_invariant() {
+    let config = Config {
+        num_hosts: 5,
+        num_keys: 6,
+        reconfig_alpha: Some(2),
+        commands: vec![0b00111, 0b11100],
+        steps: 1_500,
+        ..Config::default()
+    };
+    let mut reconfigured = 0;
+    for seed in 0..30 {
+        let mut simulator = Simulator::new(&config, seed);
+        for _ in 0..config.steps {
+            simulator.step();
+            let broken = violated(&simulator.c, &simulator.state);
+            assert!(broken.is_empty(), "seed {seed}: {broken:?} violated after step {}", simulator.report.trace.len());
+        }
+        reconfigured += simulator.state.configurations.values().filter(|fq| fq.classic != simulator.c.quorums).count();
+    }
+    assert!(reconfigured > 0, "no instance ran in a command's quorums");
+}
@@ -0,0 +1,164 @@
+use paxos_checker::{
+    explore::Bounds,
+    low_level::{init, invariants::INVARIANTS, quorum::{all_hosts, majority_quorums}, Constants, Message, Variables},
+    simulate::{Config, Simulator},
+    tla::{conformance, conformance_module, eval::Value, format_message, format_state, instance, operator_name, sample, unbounded_successors, CONFORMANCE_CONFIG, SPEC},
+};
+use std::{collections::BTreeSet, env, fs, process::Command};
+
+const TRANSITIONS: &[&str] = &[
+    "crash",
+    "recover",
+    "receive_request",
+    "init_request",
+    "send_prepare",
+    "send_prepare_from",
+    "promise_from",
+    "raise_to_floor",
+    "join_prepare_from",
+    "promise",
+    "reject_prepare",
+    "promised",
+    "get_max_accepted_value",
+    "select_value",
+    "send_accept",
+    "accept",
+    "fast_accept",
+    "reject_accept",
+    "nacked",
+    "accepted",
+    "observe_accepted",
+    "send_decide",
+    "learn",
+    "learner_decide",
+    "decide",
+    "propose_batch",
+    "accept_batch",
+    "accepted_batch",
+    "send_decide_batch",
+    "decide_batch",
+];
+
+fn defines(operator: &str) -> bool {
+    SPEC.lines().any(|line| line.starts_with(&format!("{operator}(")) && line.contains("=="))
+}
+
+#[test]
+fn every_invariant_has_a_tla_operator() {
+    for (name, _) in INVARIANTS {
+        let operator = operator_name(name);
+        assert!(defines(operator), "Paxos.tla does not define {operator}");
+        assert!(SPEC.contains(&format!("{operator} |-> {operator}(s)")), "Invariants(s) in Paxos.tla misses {operator}");
+    }
+}
+
+#[test]
+fn every_host_transition_has_a_tla_operator() {
+    for transition in TRANSITIONS {
+        assert!(defines(transition), "Paxos.tla does not define {transition}");
+    }
+}
+
+#[test]
+fn the_export_has_steps_corruptions_and_violations() {
+    let c = Constants::new(3, 1, majority_quorums(all_hosts(3), 1), false);
+    let bounds = Bounds::default();
+    let exported = conformance(&c, &bounds, 5_000, 10);
+
+    assert!(!exported.steps.is_empty());
+    assert!(!exported.non_steps.is_empty());
+    assert!(exported.states.iter().any(|u| INVARIANTS.iter().any(|(_, invariant)| !invariant(&c, u))));
+
+    let module = conformance_module(&c, &bounds, &exported);
+    assert!(module.starts_with("----") && module.trim_end().ends_with("===="));
+    assert_eq!(module.lines().filter(|line| line.starts_with("    [hosts |-> ")).count(), exported.states.len());
+}
+
+// Evaluates `Paxos.tla` with `tla::eval` and diffs it against the executable mirror on concrete
+// states: `InitState`, every successor of states sampled from simulations, and the invariants of
+// those states and of their corruptions. A simulation is sampled every 25 steps and right after it
+// first sends each kind of message or crashes a host, where the steps that take it are enabled.
+#[test]
+fn paxos_tla_agrees_with_the_checker_on_concrete_states() {
+    let config = Config { values: vec![1, 2], prepare_from_percent: 20, batch_percent: 20, learners: vec![2], fast_clients: vec![0], ..Config::default() };
+    let mut sources = Vec::new();
+    for seed in 0..2 {
+        let mut simulator = Simulator::new(&config, seed);
+        let mut seen = BTreeSet::new();
+        for step in 1..=200 {
+            simulator.step();
+            let u = &simulator.state;
+            let kinds = u.network.sent_messages.iter().map(|m| format_message(m).split('(').next().unwrap().to_string());
+            let crashed = u.hosts.iter().any(|host| !host.up).then(|| "crash".to_string());
+            let firsts = kinds.chain(crashed).filter(|kind| seen.insert(kind.clone())).count();
+            if firsts > 0 || step % 25 == 0 {
+                sources.push(u.clone());
+            }
+        }
+    }
+    assert!(sources.iter().any(|u| u.network.sent_messages.iter().any(|m| matches!(m, Message::Decide { .. }))));
+
+    let c = Simulator::new(&config, 0).c;
+    let bounds = Bounds { num_keys: config.num_keys, values: config.values.clone(), ..Bounds::default() };
+    let exported = sample(&c, &bounds, &sources);
+    let module = instance(&c, &bounds).unwrap();
+    let state = |u: &Variables| module.eval(&format_state(u)).unwrap();
+
+    assert_eq!(module.eval("InitState").unwrap(), state(&init(&c)), "InitState differs from low_level::init");
+
+    let mut checked = BTreeSet::new();
+    for &(from, to) in &exported.non_steps {
+        checked.extend([from, to]);
+    }
+    for from in exported.steps.iter().map(|(from, _)| *from).collect::<BTreeSet<_>>() {
+        let u = &exported.states[from];
+        let Value::Set(successors) = module.apply("Successors", &[state(u)]).unwrap() else { panic!("Successors is not a set") };
+        // `unbounded_successors` counts the stutter even where `Successors` has no step that takes it.
+        let mut tla = (*successors).clone();
+        tla.insert(state(u));
+        let checker: BTreeSet<Value> = unbounded_successors(&c, &bounds, u).iter().map(state).collect();
+        let only_tla: Vec<String> = tla.difference(&checker).map(Value::to_string).collect();
+        let only_checker: Vec<String> = checker.difference(&tla).map(Value::to_string).collect();
+        assert!(
+            only_tla.is_empty() && only_checker.is_empty(),
+            "successors of state {from} differ\nfrom: {}\nonly in Paxos.tla: {only_tla:#?}\nonly in the checker: {only_checker:#?}",
+            state(u),
+        );
+    }
+
+    for n in checked {
+        let u = &exported.states[n];
+        let Value::Fun(verdicts) = module.apply("Invariants", &[state(u)]).unwrap() else { panic!("Invariants is not a record") };
+        for (name, invariant) in INVARIANTS {
+            let verdict = verdicts.get(&Value::Str(operator_name(name).into()));
+            assert_eq!(verdict, Some(&Value::Bool(invariant(&c, u))), "{} differs on state {n}: {}", operator_name(name), state(u));
+        }
+    }
+}
+
+// TLC is not a dependency of this crate: point TLA2TOOLS_JAR at `tla2tools.jar` and run with `--ignored`.
+#[test]
+#[ignore = "needs java and TLA2TOOLS_JAR pointing at tla2tools.jar"]
+fn tlc_accepts_the_conformance_module() {
+    let jar = env::var("TLA2TOOLS_JAR").expect("TLA2TOOLS_JAR must point at tla2tools.jar");
+
+    let c = Constants::new(3, 1, majority_quorums(all_hosts(3), 1), false);
+    let bounds = Bounds { network_faults: true, crashes: true, batching: true, ..Bounds::default() };
+    let exported = conformance(&c, &bounds, 20_000, 20);
+
+    let dir = env::temp_dir().join(format!("paxos-tla-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("Paxos.tla"), SPEC).unwrap();
+    fs::write(dir.join("PaxosConformance.tla"), conformance_module(&c, &bounds, &exported)).unwrap();
+    fs::write(dir.join("PaxosConformance.cfg"), CONFORMANCE_CONFIG).unwrap();
+
+    let output = Command::new("java")
+        .args(["-cp", &jar, "tlc2.TLC", "-config", "PaxosConformance.cfg", "-metadir"])
+        .arg(dir.join("states"))
+        .arg("PaxosConformance")
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run java");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success() && !stdout.contains("Error"), "TLC rejected the conformance module:\n{stdout}");
+}
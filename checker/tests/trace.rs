@@ -0,0 +1,87 @@
+use paxos_checker::{
+    low_level::{quorum::{all_hosts, majority_quorums}, Constants, Message},
+    simulate::{simulate, Config},
+    trace::{format_trace, from_simulation, parse_trace, validate, Record, Verdict},
+    Value,
+};
+
+fn constants(config: &Config) -> Constants {
+    Constants::new(config.num_hosts, config.num_failures, majority_quorums(all_hosts(config.num_hosts), config.num_failures), false)
+}
+
+fn simulated(config: &Config, seed: u64) -> Vec<Record> {
+    from_simulation(&simulate(config, seed).trace)
+}
+
+#[test]
+fn simulated_traces_round_trip_through_the_text_format() {
+    let config = Config { crash_percent: 5, ..Config::default() };
+    for seed in 0..20 {
+        let records = simulated(&config, seed);
+        assert_eq!(parse_trace(&format_trace(&records)), Ok(records), "seed {seed}");
+    }
+}
+
+#[test]
+fn simulated_traces_are_valid() {
+    let config = Config { crash_percent: 5, ..Config::default() };
+    for seed in 0..20 {
+        let records = simulated(&config, seed);
+        assert_eq!(validate(&constants(&config), &records), Verdict::Valid { steps: records.len() }, "seed {seed}");
+    }
+}
+
+// Batch steps are logged like host steps, with the batch as the received or sent message.
+#[test]
+fn batched_traces_round_trip_and_are_valid() {
+    let config = Config { num_keys: 3, batch_percent: 20, ..Config::default() };
+    for seed in 0..20 {
+        let records = simulated(&config, seed);
+        assert_eq!(parse_trace(&format_trace(&records)), Ok(records.clone()), "seed {seed}");
+        assert_eq!(validate(&constants(&config), &records), Verdict::Valid { steps: records.len() }, "seed {seed}");
+    }
+}
+
+#[test]
+fn a_tampered_accept_is_the_first_illegal_step() {
+    let config = Config::default();
+    for seed in 0..20 {
+        let mut records = simulated(&config, seed);
+        let Some(step) = records.iter().position(|record| matches!(record.net_op.recv, Some(Message::Accept { .. }))) else { continue };
+        let Some(Message::Accept { key, ballot, value }) = records[step].net_op.recv else { unreachable!() };
+        records[step].net_op.recv = Some(Message::Accept { key, ballot, value: Value { op: value.op + 100, ..value } });
+
+        match validate(&constants(&config), &records) {
+            Verdict::IllegalStep { step: failed, reason, .. } => {
+                assert_eq!(failed, step, "seed {seed}");
+                assert_eq!(reason, "the received message was never sent", "seed {seed}");
+            },
+            verdict => panic!("seed {seed}: {verdict:?}"),
+        }
+    }
+}
+
+#[test]
+fn a_decide_the_host_cannot_take_is_illegal() {
+    let c = constants(&Config::default());
+    let records = parse_trace(
+        "# host 0 opens key 0 and prepares ballot 1.0
+         0 ; 0 ; - ; - ; NoOp
+         0 ; 0 ; - ; Prepare 0 1.0 ; NoOp
+         1 ; 0 ; - ; - ; NoOp
+         1 ; 0 ; Prepare 0 1.0 ; Promise 0 1 1.0 - ; NoOp
+         1 ; 0 ; Prepare 0 1.0 ; - ; Decide 0 0:0:5",
+    )
+    .unwrap();
+
+    match validate(&c, &records) {
+        Verdict::IllegalStep { step, .. } => assert_eq!(step, 4),
+        verdict => panic!("{verdict:?}"),
+    }
+}
+
+#[test]
+fn malformed_lines_are_reported_by_line_number() {
+    assert_eq!(parse_trace("0 ; 0 ; - ; - ; NoOp\n\n0 ; 0 ; Prepare 0 1 ; - ; NoOp").unwrap_err().0, 3);
+    assert_eq!(parse_trace("0 ; 0 ; - ; - ; NoOp ; extra").unwrap_err().0, 1);
+}
@@ -0,0 +1,7 @@
+------------------------------- MODULE MCPaxos -------------------------------
+(* Three hosts, one key, two client values, ballots up to 3 and pending    *)
+(* queues of one request, with crashes and network faults enabled.         *)
+EXTENDS Paxos
+
+MCValues == <<1, 2>>
+=============================================================================
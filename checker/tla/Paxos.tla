@@ -0,0 +1,1061 @@
+-------------------------------- MODULE Paxos --------------------------------
+(***************************************************************************)
+(* TLA+ version of the executable checker machine in                       *)
+(* `checker/src/low_level`, itself a hand-written copy of                  *)
+(* `src/distributed_system/low_level`. `checker/tests/tla.rs` evaluates it *)
+(* on concrete states sampled from the checker and compares initial        *)
+(* states, successors and invariant verdicts. Nothing compares it with     *)
+(* the Verus spec.                                                         *)
+(*                                                                         *)
+(* Every host transition is written the way the executable mirror writes   *)
+(* it: the `host::step` predicate solved for the successor, as a           *)
+(* set of [host |-> v, send |-> message] records that is empty when the    *)
+(* transition is not enabled. Operators that mirror a spec function carry  *)
+(* its name. The whole state is one record, so that `NextS(s, t)` and the  *)
+(* invariants can be evaluated on concrete states.                         *)
+(*                                                                         *)
+(* Reconfiguration is left out: the checker compared with this module runs *)
+(* `Reconfiguration::none`, where no decided command takes effect, so      *)
+(* every instance is in the quorums below and the configuration            *)
+(* invariants only depend on which instances the hosts have created.       *)
+(***************************************************************************)
+EXTENDS Integers, Sequences, FiniteSets
+
+CONSTANTS
+    NumHosts,
+    NumFailures,
+    Phase1Size,         \* quorum::quorums_by_size(all_hosts(NumHosts), Phase1Size, Phase2Size)
+    Phase2Size,
+    FastSize,           \* host::Constants::fast: the sets of at least FastSize hosts
+    ConsumeOnReceive,   \* network::Constants::consume_on_receive
+    Proposers,          \* host::Constants::proposers; every host is an acceptor
+    Learners,           \* host::Constants::learners
+    FastClients,        \* host::Constants::fast_clients
+    Keys,               \* the instances hosts may step on
+    MaxBallot,          \* used only by StateConstraint
+    Values              \* client `i - 1` submits the operation `Values[i]` with seq 0
+
+VARIABLE state
+
+Hosts == 0..(NumHosts - 1)
+
+-----------------------------------------------------------------------------
+(* Option<T> is `<<>>` or `<<x>>`; Map<K, V> is a function. *)
+
+None == <<>>
+Some(x) == <<x>>
+IsSome(o) == o # <<>>
+
+EmptyMap == [x \in {} |-> None]
+Put(f, k, x) == [y \in DOMAIN f \cup {k} |-> IF y = k THEN x ELSE f[y]]
+
+Ballot(n, p) == [num |-> n, pid |-> p]
+
+\* host::Ballot::cmp
+Cmp(a, b) ==
+    IF a.num < b.num THEN -1
+    ELSE IF a.num > b.num THEN 1
+    ELSE IF a.pid < b.pid THEN -1
+    ELSE IF a.pid > b.pid THEN 1
+    ELSE 0
+
+\* host::max_ballot
+max_ballot(a, b) == IF Cmp(a, b) >= 0 THEN a ELSE b
+
+Members(S) == S \cap Hosts
+IsPhase1Quorum(S) == Cardinality(Members(S)) >= Phase1Size
+IsPhase2Quorum(S) == Cardinality(Members(S)) >= Phase2Size
+IsFastQuorum(S) == Cardinality(Members(S)) >= FastSize
+
+\* host::fast_ballot: every instance starts at it, and every ballot a host prepares is above it.
+FastBallot == Ballot(0, 0)
+IsFastBallot(b) == b.num = 0
+
+\* host::fast_slot: a fast client numbers its requests by the instance they are for.
+FastSlot(v) == v.seq
+IsFastRequest(v) == v.client_id \in FastClients
+
+-----------------------------------------------------------------------------
+(* low_level::Message *)
+
+Prepare(k, b) == [type |-> "Prepare", key |-> k, ballot |-> b]
+PrepareFrom(f, b) == [type |-> "PrepareFrom", from |-> f, ballot |-> b]
+Promise(k, s, b, a) == [type |-> "Promise", key |-> k, sender |-> s, ballot |-> b, accepted |-> a]
+Accept(k, b, v) == [type |-> "Accept", key |-> k, ballot |-> b, value |-> v]
+Accepted(k, s, b, v) == [type |-> "Accepted", key |-> k, sender |-> s, ballot |-> b, value |-> v]
+Nack(k, b, h) == [type |-> "Nack", key |-> k, ballot |-> b, higher |-> h]
+Decide(k, b, v) == [type |-> "Decide", key |-> k, ballot |-> b, value |-> v]
+ClientRequest(v) == [type |-> "ClientRequest", value |-> v]
+AcceptBatch(b, es) == [type |-> "AcceptBatch", ballot |-> b, entries |-> es]
+AcceptedBatch(s, b, es) == [type |-> "AcceptedBatch", sender |-> s, ballot |-> b, entries |-> es]
+DecideBatch(b, es) == [type |-> "DecideBatch", ballot |-> b, entries |-> es]
+
+\* batch::is_batch and batch::unbatch, the latter as a set. The entries of a batch are a sequence of
+\* <<key, value>> pairs.
+IsBatch(m) == m.type \in {"AcceptBatch", "AcceptedBatch", "DecideBatch"}
+Unbatch(m) ==
+    CASE m.type = "AcceptBatch" -> {Accept(m.entries[n][1], m.ballot, m.entries[n][2]) : n \in DOMAIN m.entries}
+      [] m.type = "AcceptedBatch" -> {Accepted(m.entries[n][1], m.sender, m.ballot, m.entries[n][2]) : n \in DOMAIN m.entries}
+      [] m.type = "DecideBatch" -> {Decide(m.entries[n][1], m.ballot, m.entries[n][2]) : n \in DOMAIN m.entries}
+      [] OTHER -> {}
+
+\* Value and Value::request: an operation tagged with the id of the request that carries it.
+Value(c, q, o) == [client_id |-> c, seq |-> q, op |-> o]
+RequestId(v) == <<v.client_id, v.seq>>
+
+\* The no-op of instance `k`. Its client id stands for the checker's u64::MAX, which TLC cannot hold.
+NoOpClient == -1
+NoOp(k) == Value(NoOpClient, k, 0)
+IsNoOp(v) == v.client_id = NoOpClient
+
+Requests == {ClientRequest(Value(i - 1, 0, Values[i])) : i \in DOMAIN Values}
+
+\* host::request_owner: clients are dealt to the proposers in ascending order of id.
+RequestOwner(client) == CHOOSE p \in Proposers : Cardinality({q \in Proposers : q < p}) = client % Cardinality(Proposers)
+
+message_key(m) == IF m.type \in {"ClientRequest", "PrepareFrom"} \/ IsBatch(m) THEN None ELSE Some(m.key)
+
+is_collectable(m, k) == message_key(m) = Some(k) /\ m.type # "Decide"
+
+-----------------------------------------------------------------------------
+(* low_level::host *)
+
+\* host::Volatile, what `recover` empties.
+NewVolatile == [promised |-> EmptyMap, proposed_value |-> EmptyMap, accepted |-> EmptyMap, learned |-> EmptyMap]
+
+NewInstance ==
+    [current_ballot |-> Ballot(0, 0),
+     max_seen_ballot |-> Ballot(0, 0),
+     accept_ballot |-> None,
+     accept_value |-> None,
+     decide_value |-> None,
+     volatile |-> NewVolatile]
+
+\* host::durable_part
+durable_part(inst) == [inst EXCEPT !.volatile = NewVolatile]
+
+HostInit ==
+    [instances |-> EmptyMap, pending |-> <<>>, assigned |-> {}, up |-> TRUE,
+     multi |-> [promise_floor |-> Ballot(0, 0), floor_from |-> 0, leading |-> None]]
+
+Step(v, send) == [host |-> v, send |-> send]
+
+\* The spec folds over `dom().choose()`; this breaks ties between equal ballots towards the smallest
+\* sender, as the executable mirror does.
+get_max_accepted_value(map) ==
+    LET W == {s \in DOMAIN map : IsSome(map[s])}
+    IN IF W = {} THEN None
+       ELSE map[CHOOSE s \in W :
+                    \A o \in W : \/ Cmp(map[s][1][1], map[o][1][1]) > 0
+                                 \/ Cmp(map[s][1][1], map[o][1][1]) = 0 /\ s <= o]
+
+\* fast::value_may_have_been_chosen: enough hosts either reported <<b, v>> or did not report at all.
+value_may_have_been_chosen(map, b, v) ==
+    IsFastQuorum({h \in Hosts : h \notin DOMAIN map \/ map[h] = Some(<<b, v>>)})
+
+\* fast::select_value. Over a phase-1 quorum at most one value may have been chosen; this takes the
+\* one of the smallest sender, as the executable mirror does. Failing that, `max` is the value of the
+\* smallest sender at the highest ballot, which is the spec's `is_lowest_reporting_sender`.
+select_value(map) ==
+    LET max == get_max_accepted_value(map)
+    IN IF ~IsSome(max) \/ ~IsFastBallot(max[1][1]) THEN max
+       ELSE LET C == {s \in DOMAIN map : IsSome(map[s]) /\ map[s][1][1] = max[1][1] /\
+                                         value_may_have_been_chosen(map, max[1][1], map[s][1][2])}
+            IN IF C = {} THEN max ELSE Some(map[CHOOSE s \in C : \A o \in C : s <= o][1])
+
+crash(u) == IF u.up THEN {Step([u EXCEPT !.up = FALSE], None)} ELSE {}
+
+recover(u) ==
+    IF u.up THEN {}
+    ELSE {Step([u EXCEPT !.instances = [k \in DOMAIN @ |-> durable_part(@[k])], !.up = TRUE, !.pending = <<>>,
+                         !.multi.leading = None],
+               None)}
+
+is_queued(pending, c, q) == \E n \in DOMAIN pending : pending[n].client_id = c /\ pending[n].seq = q
+
+\* A request that is already queued or was already proposed is received without being queued again.
+\* A fast client's requests go to fast_accept instead.
+receive_request(id, u, m) ==
+    IF m.type # "ClientRequest" \/ RequestOwner(m.value.client_id) # id \/ IsFastRequest(m.value) THEN {}
+    ELSE IF RequestId(m.value) \in u.assigned \/ is_queued(u.pending, m.value.client_id, m.value.seq) THEN {Step(u, None)}
+    ELSE {Step([u EXCEPT !.pending = Append(@, m.value)], None)}
+
+IsAcceptor(id) == id \in Hosts
+
+\* host::floor_for: the promise floor if the last PrepareFrom covers `key`, and no bound otherwise.
+floor_for(u, key) == IF key >= u.multi.floor_from THEN u.multi.promise_floor ELSE Ballot(0, 0)
+
+\* host::promise_msg
+promise_msg(id, inst, key, b) ==
+    Promise(key, id, b, IF IsSome(inst.accept_ballot) THEN Some(<<inst.accept_ballot[1], inst.accept_value[1]>>) ELSE None)
+
+\* A new instance starts at its floor, and an acceptor promises the floor for it right away.
+init_request(id, u, key) ==
+    IF key \in DOMAIN u.instances THEN {}
+    ELSE LET floor == floor_for(u, key)
+             inst == [NewInstance EXCEPT !.current_ballot = floor, !.max_seen_ballot = floor]
+         IN {Step([u EXCEPT !.instances = Put(@, key, inst)],
+                  IF IsAcceptor(id) /\ floor.num > 0 THEN Some(promise_msg(id, inst, key, floor)) ELSE None)}
+
+\* The lowest ballot owned by `id` that is above everything it has seen for the instance.
+next_ballot(id, inst) == Ballot(inst.max_seen_ballot.num + 1, id)
+
+send_prepare(id, u, key) ==
+    IF id \notin Proposers \/ key \notin DOMAIN u.instances THEN {}
+    ELSE LET inst == u.instances[key]
+             b == next_ballot(id, inst)
+         IN IF IsSome(inst.decide_value) THEN {}
+            ELSE {Step([u EXCEPT !.instances[key].max_seen_ballot = b,
+                                 !.instances[key].volatile.promised = Put(@, b, EmptyMap),
+                                 !.instances[key].volatile.accepted = Put(@, b, {})],
+                       Some(Prepare(key, b)))}
+
+Max(S) == CHOOSE n \in S : \A m \in S : m <= n
+
+\* Multi-Paxos phase 1 for `key` and every instance above it, with the lowest ballot of `id` above
+\* its floor and above every covered instance it has.
+send_prepare_from(id, u, key) ==
+    IF id \notin Proposers THEN {}
+    ELSE LET highest == Max({u.multi.promise_floor.num} \cup
+                            {u.instances[k].max_seen_ballot.num : k \in {k \in DOMAIN u.instances : k >= key}})
+             b == Ballot(highest + 1, id)
+         IN {Step([u EXCEPT !.multi.leading = Some(<<key, b>>)], Some(PrepareFrom(key, b)))}
+
+\* The acceptor takes the ballot as its floor from `from` on; the covered instances promise it later.
+promise_from(u, key, m) ==
+    IF m.type # "PrepareFrom" \/ m.from # key \/ Cmp(m.ballot, u.multi.promise_floor) <= 0 THEN {}
+    ELSE {Step([u EXCEPT !.multi.promise_floor = m.ballot, !.multi.floor_from = m.from], None)}
+
+\* Moves an instance created before the PrepareFrom up to its floor, promising it.
+raise_to_floor(id, u, key) ==
+    IF key \notin DOMAIN u.instances THEN {}
+    ELSE LET inst == u.instances[key]
+             floor == floor_for(u, key)
+         IN IF Cmp(inst.current_ballot, floor) >= 0 THEN {}
+            ELSE {Step([u EXCEPT !.instances[key].current_ballot = floor,
+                                 !.instances[key].max_seen_ballot = max_ballot(@, floor)],
+                       IF IsAcceptor(id) THEN Some(promise_msg(id, inst, key, floor)) ELSE None)}
+
+\* The leader of a PrepareFrom starts collecting promises for a covered instance under its ballot.
+join_prepare_from(u, key) ==
+    IF ~IsSome(u.multi.leading) \/ key \notin DOMAIN u.instances THEN {}
+    ELSE LET inst == u.instances[key]
+             from == u.multi.leading[1][1]
+             b == u.multi.leading[1][2]
+         IN IF \/ from > key
+               \/ b \in DOMAIN inst.volatile.promised
+               \/ b \in DOMAIN inst.volatile.proposed_value
+               \/ b \in DOMAIN inst.volatile.accepted
+               \/ IsSome(inst.decide_value)
+            THEN {}
+            ELSE {Step([u EXCEPT !.instances[key].max_seen_ballot = max_ballot(@, b),
+                                 !.instances[key].volatile.promised = Put(@, b, EmptyMap),
+                                 !.instances[key].volatile.accepted = Put(@, b, {})],
+                       None)}
+
+promise(id, u, key, m) ==
+    IF m.type # "Prepare" \/ m.key # key \/ ~IsAcceptor(id) \/ key \notin DOMAIN u.instances THEN {}
+    ELSE LET inst == u.instances[key]
+         IN IF Cmp(inst.current_ballot, floor_for(u, key)) < 0 \/ Cmp(m.ballot, inst.current_ballot) # 1 THEN {}
+            ELSE {Step([u EXCEPT !.instances[key].current_ballot = m.ballot,
+                                 !.instances[key].max_seen_ballot = max_ballot(@, m.ballot)],
+                       Some(Promise(key, id, m.ballot,
+                                    IF IsSome(inst.accept_ballot)
+                                    THEN Some(<<inst.accept_ballot[1], inst.accept_value[1]>>)
+                                    ELSE None)))}
+
+\* An acceptor that has moved past a Prepare's ballot tells its owner which ballot it is at.
+reject_prepare(id, u, key, m) ==
+    IF m.type # "Prepare" \/ m.key # key \/ ~IsAcceptor(id) \/ key \notin DOMAIN u.instances THEN {}
+    ELSE LET higher == u.instances[key].current_ballot
+         IN IF Cmp(higher, floor_for(u, key)) < 0 \/ Cmp(m.ballot, higher) >= 0 THEN {}
+            ELSE {Step(u, Some(Nack(key, m.ballot, higher)))}
+
+promised(u, key, m) ==
+    IF m.type # "Promise" \/ m.key # key \/ key \notin DOMAIN u.instances THEN {}
+    ELSE LET inst == u.instances[key]
+         IN IF m.ballot \notin DOMAIN inst.volatile.promised \/ m.ballot \in DOMAIN inst.volatile.proposed_value THEN {}
+            ELSE {Step([u EXCEPT !.instances[key].volatile.promised[m.ballot] = Put(@, m.sender, m.accepted)], None)}
+
+send_accept(u, key) ==
+    IF key \notin DOMAIN u.instances THEN {}
+    ELSE LET inst == u.instances[key]
+             b == inst.current_ballot
+         IN IF \/ b \notin DOMAIN inst.volatile.promised
+               \/ ~IsPhase1Quorum(DOMAIN inst.volatile.promised[b])
+               \/ b \in DOMAIN inst.volatile.proposed_value
+            THEN {}
+            ELSE LET max == select_value(inst.volatile.promised[b])
+                     takes == ~IsSome(max) /\ Len(u.pending) > 0
+                     value == IF IsSome(max) THEN max[1][2] ELSE IF takes THEN Head(u.pending) ELSE NoOp(key)
+                 IN {Step([u EXCEPT !.instances[key].volatile.proposed_value = Put(@, b, value),
+                                    !.pending = IF takes THEN Tail(@) ELSE @,
+                                    !.assigned = IF takes THEN @ \cup {RequestId(Head(u.pending))} ELSE @],
+                          Some(Accept(key, b, value)))}
+
+accept(id, u, key, m) ==
+    IF m.type # "Accept" \/ m.key # key \/ ~IsAcceptor(id) \/ key \notin DOMAIN u.instances THEN {}
+    ELSE IF \/ Cmp(u.instances[key].current_ballot, floor_for(u, key)) < 0
+            \/ Cmp(m.ballot, u.instances[key].current_ballot) < 0
+         THEN {}
+    ELSE {Step([u EXCEPT !.instances[key].current_ballot = m.ballot,
+                         !.instances[key].max_seen_ballot = max_ballot(@, m.ballot),
+                         !.instances[key].accept_ballot = Some(m.ballot),
+                         !.instances[key].accept_value = Some(m.value)],
+               Some(Accepted(key, id, m.ballot, m.value)))}
+
+\* The fast round: an acceptor takes a fast client's request straight off the network into its
+\* FastSlot. It takes at most one value in the fast round, and only while it has promised no
+\* classic ballot for the instance.
+fast_accept(id, u, key, m) ==
+    IF \/ m.type # "ClientRequest" \/ FastSlot(m.value) # key \/ ~IsFastRequest(m.value)
+       \/ ~IsAcceptor(id) \/ key \notin DOMAIN u.instances
+    THEN {}
+    ELSE LET inst == u.instances[key]
+         IN IF \/ Cmp(inst.current_ballot, floor_for(u, key)) < 0
+               \/ inst.current_ballot # FastBallot
+               \/ IsSome(inst.accept_ballot)
+            THEN {}
+            ELSE {Step([u EXCEPT !.instances[key].accept_ballot = Some(FastBallot),
+                                 !.instances[key].accept_value = Some(m.value)],
+                       Some(Accepted(key, id, FastBallot, m.value)))}
+
+reject_accept(id, u, key, m) ==
+    IF m.type # "Accept" \/ m.key # key \/ ~IsAcceptor(id) \/ key \notin DOMAIN u.instances THEN {}
+    ELSE LET higher == u.instances[key].current_ballot
+         IN IF Cmp(higher, floor_for(u, key)) < 0 \/ Cmp(m.ballot, higher) >= 0 THEN {}
+            ELSE {Step(u, Some(Nack(key, m.ballot, higher)))}
+
+\* The owner of a rejected ballot raises max_seen_ballot to `higher`, so its next send_prepare
+\* outbids it; current_ballot stays at a ballot the host actually promised.
+nacked(id, u, key, m) ==
+    IF m.type # "Nack" \/ m.key # key \/ m.ballot.pid # id \/ key \notin DOMAIN u.instances THEN {}
+    ELSE IF Cmp(m.higher, u.instances[key].max_seen_ballot) <= 0 THEN {}
+    ELSE {Step([u EXCEPT !.instances[key].max_seen_ballot = m.higher], None)}
+
+accepted(u, key, m) ==
+    IF m.type # "Accepted" \/ m.key # key \/ key \notin DOMAIN u.instances THEN {}
+    ELSE IF m.ballot \notin DOMAIN u.instances[key].volatile.accepted THEN {}
+    ELSE {Step([u EXCEPT !.instances[key].volatile.accepted[m.ballot] = @ \cup {m.sender}], None)}
+
+\* A learner counts Accepted messages per ballot and value, whichever host led the ballot.
+observe_accepted(id, u, key, m) ==
+    IF m.type # "Accepted" \/ m.key # key \/ id \notin Learners \/ key \notin DOMAIN u.instances THEN {}
+    ELSE LET learned == u.instances[key].volatile.learned
+             bv == <<m.ballot, m.value>>
+             senders == IF bv \in DOMAIN learned THEN learned[bv] ELSE {}
+         IN {Step([u EXCEPT !.instances[key].volatile.learned = Put(@, bv, senders \cup {m.sender}),
+                            !.instances[key].max_seen_ballot = max_ballot(@, m.ballot)],
+                  None)}
+
+\* The fast round has no leader, so a learner decides it on a fast quorum rather than a phase-2 one.
+IsLearnerQuorum(b, S) == IF IsFastBallot(b) THEN IsFastQuorum(S) ELSE IsPhase2Quorum(S)
+
+\* A learner that has counted a quorum of Accepted for <<ballot, value>> decides and announces it.
+learner_decide(id, u, key) ==
+    IF id \notin Learners \/ key \notin DOMAIN u.instances THEN {}
+    ELSE LET learned == u.instances[key].volatile.learned
+         IN {Step([u EXCEPT !.instances[key].decide_value = Some(bv[2])], Some(Decide(key, bv[1], bv[2])))
+                : bv \in {bv \in DOMAIN learned : IsLearnerQuorum(bv[1], learned[bv])}}
+
+send_decide(u, key) ==
+    IF key \notin DOMAIN u.instances THEN {}
+    ELSE LET inst == u.instances[key]
+             b == inst.current_ballot
+         IN IF \/ b \notin DOMAIN inst.volatile.proposed_value
+               \/ b \notin DOMAIN inst.volatile.accepted
+               \/ ~IsPhase2Quorum(inst.volatile.accepted[b])
+            THEN {}
+            ELSE {Step(u, Some(Decide(key, b, inst.volatile.proposed_value[b])))}
+
+\* The leader that gathered an Accepted quorum for its current ballot learns the decision and announces it.
+learn(u, key) ==
+    {Step([u EXCEPT !.instances[key].decide_value = Some(r.send[1].value)], r.send) : r \in send_decide(u, key)}
+
+decide(u, key, m) ==
+    IF m.type # "Decide" \/ m.key # key \/ key \notin DOMAIN u.instances THEN {}
+    ELSE IF Cmp(m.ballot, u.instances[key].current_ballot) < 0 THEN {}
+    ELSE {Step([u EXCEPT !.instances[key].current_ballot = m.ballot,
+                         !.instances[key].max_seen_ballot = max_ballot(@, m.ballot),
+                         !.instances[key].decide_value = Some(m.value)],
+               None)}
+
+\* host::step for every event but Submit, whose request is a free parameter (see SubmitSuccessors).
+HostSuccessors(id, u, key, recv) ==
+    IF recv = None
+    THEN crash(u) \cup recover(u) \cup
+         (IF u.up
+          THEN init_request(id, u, key) \cup send_prepare(id, u, key) \cup send_prepare_from(id, u, key) \cup
+               raise_to_floor(id, u, key) \cup join_prepare_from(u, key) \cup send_accept(u, key) \cup send_decide(u, key) \cup
+               learn(u, key) \cup learner_decide(id, u, key)
+          ELSE {})
+    ELSE IF ~u.up THEN {}
+    ELSE LET m == recv[1]
+         IN promise(id, u, key, m) \cup promise_from(u, key, m) \cup reject_prepare(id, u, key, m) \cup promised(u, key, m) \cup
+            accept(id, u, key, m) \cup fast_accept(id, u, key, m) \cup reject_accept(id, u, key, m) \cup nacked(id, u, key, m) \cup
+            accepted(u, key, m) \cup observe_accepted(id, u, key, m) \cup receive_request(id, u, m) \cup decide(u, key, m)
+
+-----------------------------------------------------------------------------
+(* low_level::batch *)
+
+EntryKeys(es) == {es[n][1] : n \in DOMAIN es}
+HasDistinctKeys(es) == Cardinality(EntryKeys(es)) = Len(es)
+EntryValue(es, k) == es[CHOOSE n \in DOMAIN es : es[n][1] = k][2]
+
+\* The <<key, value>> pairs of S in ascending order of key; the keys of S are distinct.
+SortedEntries(S) == [n \in 1..Cardinality(S) |-> CHOOSE e \in S : Cardinality({f \in S : f[1] < e[1]}) = n - 1]
+
+\* The keys of S in ascending order.
+SortedKeys(S) == [n \in 1..Cardinality(S) |-> CHOOSE k \in S : Cardinality({j \in S : j < k}) = n - 1]
+
+\* batch::propose_entries: send_accept in each key of `ks` at `b` that it can propose in, one after the
+\* other, with the <<key, value>> entries proposed so far in `es`.
+RECURSIVE ProposeEntries(_, _, _, _)
+ProposeEntries(u, b, ks, es) ==
+    IF ks = <<>> THEN <<u, es>>
+    ELSE LET k == Head(ks)
+         IN IF u.instances[k].current_ballot = b /\ send_accept(u, k) # {}
+            THEN LET r == CHOOSE r \in send_accept(u, k) : TRUE
+                 IN ProposeEntries(r.host, b, Tail(ks), Append(es, <<k, r.send[1].value>>))
+            ELSE ProposeEntries(u, b, Tail(ks), es)
+
+\* The spec lets a leader batch any of its proposals at `b`; as in the mirror, this proposes in every
+\* instance at `b` it can, in key order.
+propose_batch(u, b) ==
+    LET r == ProposeEntries(u, b, SortedKeys(DOMAIN u.instances), <<>>)
+    IN IF r[2] = <<>> THEN {} ELSE {Step(r[1], Some(AcceptBatch(b, r[2])))}
+
+accept_batch(id, u, m) ==
+    IF m.type # "AcceptBatch" \/ Len(m.entries) = 0 \/ ~HasDistinctKeys(m.entries) \/ ~IsAcceptor(id) THEN {}
+    ELSE IF \E k \in EntryKeys(m.entries) :
+              \/ k \notin DOMAIN u.instances
+              \/ Cmp(u.instances[k].current_ballot, floor_for(u, k)) < 0
+              \/ Cmp(m.ballot, u.instances[k].current_ballot) < 0
+         THEN {}
+    ELSE {Step([u EXCEPT !.instances = [k \in DOMAIN u.instances |->
+                    IF k \in EntryKeys(m.entries)
+                    THEN [u.instances[k] EXCEPT !.current_ballot = m.ballot,
+                                               !.max_seen_ballot = max_ballot(@, m.ballot),
+                                               !.accept_ballot = Some(m.ballot),
+                                               !.accept_value = Some(EntryValue(m.entries, k))]
+                    ELSE u.instances[k]]],
+               Some(AcceptedBatch(id, m.ballot, m.entries)))}
+
+accepted_batch(u, m) ==
+    IF m.type # "AcceptedBatch" \/ Len(m.entries) = 0 \/ ~HasDistinctKeys(m.entries) THEN {}
+    ELSE IF \E k \in EntryKeys(m.entries) :
+              k \notin DOMAIN u.instances \/ m.ballot \notin DOMAIN u.instances[k].volatile.accepted
+         THEN {}
+    ELSE {Step([u EXCEPT !.instances = [k \in DOMAIN u.instances |->
+                    IF k \in EntryKeys(m.entries)
+                    THEN [u.instances[k] EXCEPT !.volatile.accepted[m.ballot] = @ \cup {m.sender}]
+                    ELSE u.instances[k]]],
+               None)}
+
+\* Every decision send_decide would announce at `b`.
+send_decide_batch(u, b) ==
+    LET S == {<<k, u.instances[k].volatile.proposed_value[b]>>
+                : k \in {k \in DOMAIN u.instances : u.instances[k].current_ballot = b /\ send_decide(u, k) # {}}}
+    IN IF S = {} THEN {} ELSE {Step(u, Some(DecideBatch(b, SortedEntries(S))))}
+
+\* A host takes every decision of a DecideBatch at once, checking each as it would a Decide.
+decide_batch(u, m) ==
+    IF m.type # "DecideBatch" \/ Len(m.entries) = 0 \/ ~HasDistinctKeys(m.entries) THEN {}
+    ELSE IF \E k \in EntryKeys(m.entries) :
+              k \notin DOMAIN u.instances \/ Cmp(m.ballot, u.instances[k].current_ballot) < 0
+         THEN {}
+    ELSE {Step([u EXCEPT !.instances = [k \in DOMAIN u.instances |->
+                    IF k \in EntryKeys(m.entries)
+                    THEN [u.instances[k] EXCEPT !.current_ballot = m.ballot,
+                                               !.max_seen_ballot = max_ballot(@, m.ballot),
+                                               !.decide_value = Some(EntryValue(m.entries, k))]
+                    ELSE u.instances[k]]],
+               None)}
+
+\* batch::step, for any event.
+BatchSuccessors(id, u, recv) ==
+    IF ~u.up THEN {}
+    ELSE IF recv = None
+    THEN UNION {propose_batch(u, b) \cup send_decide_batch(u, b) : b \in {u.instances[k].current_ballot : k \in DOMAIN u.instances}}
+    ELSE accept_batch(id, u, recv[1]) \cup accepted_batch(u, recv[1]) \cup decide_batch(u, recv[1])
+
+-----------------------------------------------------------------------------
+(* low_level::network and low_level::next *)
+
+\* merge: every map of `volatile` on top of the same map of `history`.
+Merge(history, volatile) ==
+    [promised |-> [b \in DOMAIN history.promised \cup DOMAIN volatile.promised |->
+                      IF b \in DOMAIN volatile.promised THEN volatile.promised[b] ELSE history.promised[b]],
+     proposed_value |-> [b \in DOMAIN history.proposed_value \cup DOMAIN volatile.proposed_value |->
+                            IF b \in DOMAIN volatile.proposed_value THEN volatile.proposed_value[b] ELSE history.proposed_value[b]],
+     accepted |-> [b \in DOMAIN history.accepted \cup DOMAIN volatile.accepted |->
+                      IF b \in DOMAIN volatile.accepted THEN volatile.accepted[b] ELSE history.accepted[b]],
+     learned |-> [bv \in DOMAIN history.learned \cup DOMAIN volatile.learned |->
+                     IF bv \in DOMAIN volatile.learned THEN volatile.learned[bv] ELSE history.learned[bv]]]
+
+\* record: the history of a host after one of its steps.
+Record(history, v) ==
+    [k \in DOMAIN v.instances |-> Merge(IF k \in DOMAIN history THEN history[k] ELSE NewVolatile, v.instances[k].volatile)]
+
+\* network::step, solved for the successor. A batch is recorded together with every message it carries.
+After(s, id, recv, r) ==
+    LET remaining == IF recv # None /\ ConsumeOnReceive THEN s.in_flight \ {recv[1]} ELSE s.in_flight
+    IN [hosts |-> [s.hosts EXCEPT ![id] = r.host],
+        sent |-> IF r.send = None THEN s.sent ELSE s.sent \cup {r.send[1]} \cup Unbatch(r.send[1]),
+        in_flight |-> IF r.send = None THEN remaining ELSE remaining \cup {r.send[1]},
+        history |-> [s.history EXCEPT ![id] = Record(@, r.host)]]
+
+Receivable(s) == {None} \cup {Some(m) : m \in s.in_flight}
+
+some_host_decided(s, k) ==
+    \E i \in DOMAIN s.hosts : k \in DOMAIN s.hosts[i].instances /\ IsSome(s.hosts[i].instances[k].decide_value)
+
+HostStepSuccessors(s) ==
+    UNION {UNION {UNION {{After(s, id, recv, r) : r \in HostSuccessors(id, s.hosts[id], key, recv)}
+                         : recv \in Receivable(s)}
+                  : key \in Keys}
+           : id \in Hosts}
+
+\* batch_next: the batch steps `Successors` adds to low_level::next.
+BatchStepSuccessors(s) ==
+    UNION {UNION {{After(s, id, recv, r) : r \in BatchSuccessors(id, s.hosts[id], recv)}
+                  : recv \in Receivable(s)}
+           : id \in Hosts}
+
+SubmitSuccessors(s) ==
+    {After(s, id, None, Step(s.hosts[id], Some(m))) : id \in {i \in Hosts : s.hosts[i].up}, m \in Requests}
+
+\* Dropping a message that is not in flight is a stutter, as in the spec.
+DropSuccessors(s) == {[s EXCEPT !.in_flight = @ \ {m}] : m \in s.sent}
+
+DuplicateSuccessors(s) == {[s EXCEPT !.in_flight = @ \cup {m}] : m \in s.sent}
+
+GarbageCollectSuccessors(s) ==
+    {[s EXCEPT !.in_flight = {m \in @ : ~is_collectable(m, k)}] : k \in {k \in Keys : some_host_decided(s, k)}}
+
+Successors(s) ==
+    HostStepSuccessors(s) \cup BatchStepSuccessors(s) \cup SubmitSuccessors(s) \cup DropSuccessors(s) \cup
+    DuplicateSuccessors(s) \cup GarbageCollectSuccessors(s)
+
+\* low_level::next on concrete states, for any event.
+NextS(s, t) == t \in Successors(s)
+
+InitState == [hosts |-> [i \in Hosts |-> HostInit], sent |-> {}, in_flight |-> {}, history |-> [i \in Hosts |-> EmptyMap]]
+
+Init == state = InitState
+Next == \E t \in Successors(state) : state' = t
+Spec == Init /\ [][Next]_state
+
+StateConstraint ==
+    /\ \A m \in state.sent : m.type \in {"Prepare", "PrepareFrom"} => m.ballot.num <= MaxBallot
+    /\ \A i \in Hosts : Len(state.hosts[i].pending) <= 1
+
+-----------------------------------------------------------------------------
+(* safety and the conjuncts of `inductive`. An index the spec leaves       *)
+(* unconstrained (a host that does not exist, a missing map key) counts as *)
+(* a violation, as in `checker/src/low_level/invariants.rs`.               *)
+
+HostIds(s) == DOMAIN s.hosts
+HasInstance(s, i, k) == i \in HostIds(s) /\ k \in DOMAIN s.hosts[i].instances
+Inst(s, i, k) == s.hosts[i].instances[k]
+\* The leader maps of the invariants are the ones in `history`; only `learned` is read from the instance.
+Hist(s, i, k) == s.history[i][k]
+InstancePairs(s) == UNION {{<<i, k>> : k \in DOMAIN s.hosts[i].instances} : i \in HostIds(s)}
+
+SentOf(s, type) == {m \in s.sent : m.type = type}
+
+ConstantsWellFormed ==
+    /\ NumHosts > 0
+    /\ NumHosts > 2 * NumFailures
+    /\ Proposers # {}
+    /\ Proposers \subseteq Hosts
+    /\ Phase1Size + Phase2Size > NumHosts
+    /\ Phase1Size + NumFailures <= NumHosts
+    /\ Phase2Size + NumFailures <= NumHosts
+    /\ Phase1Size + 2 * FastSize > 2 * NumHosts
+
+well_formed(s) ==
+    /\ ConstantsWellFormed
+    /\ HostIds(s) = Hosts
+    /\ DOMAIN s.history = HostIds(s)
+    /\ \A i \in HostIds(s) : DOMAIN s.history[i] = DOMAIN s.hosts[i].instances
+
+safety(s) ==
+    /\ well_formed(s)
+    /\ \A p \in InstancePairs(s), j \in HostIds(s) :
+        LET i1 == Inst(s, p[1], p[2])
+        IN p[2] \in DOMAIN s.hosts[j].instances =>
+            (IsSome(i1.decide_value) /\ IsSome(Inst(s, j, p[2]).decide_value) =>
+                i1.decide_value = Inst(s, j, p[2]).decide_value)
+
+in_flight_messages_are_sent(s) == s.in_flight \subseteq s.sent
+
+all_maps_and_sets_are_finite(s) == TRUE
+
+all_map_keys_and_set_values_are_valid(s) ==
+    \A p \in InstancePairs(s) :
+        LET inst == Inst(s, p[1], p[2])
+            hist == Hist(s, p[1], p[2])
+        IN /\ \A b \in DOMAIN hist.promised : \A snd \in DOMAIN hist.promised[b] : snd < NumHosts
+           /\ \A b \in DOMAIN hist.accepted : \A snd \in hist.accepted[b] : snd < NumHosts
+           /\ \A bv \in DOMAIN inst.volatile.learned : \A snd \in inst.volatile.learned[bv] : snd < NumHosts
+
+all_map_and_set_sizes_are_bounded(s) ==
+    \A p \in InstancePairs(s) :
+        LET hist == Hist(s, p[1], p[2])
+        IN /\ \A b \in DOMAIN hist.promised : Cardinality(DOMAIN hist.promised[b]) <= NumHosts
+           /\ \A b \in DOMAIN hist.accepted : Cardinality(hist.accepted[b]) <= NumHosts
+
+all_ballot_pids_in_all_maps_correspond_to_respective_host_id(s) ==
+    \A p \in InstancePairs(s) :
+        LET hist == Hist(s, p[1], p[2])
+        IN \A b \in DOMAIN hist.promised \cup DOMAIN hist.proposed_value \cup DOMAIN hist.accepted : b.pid = p[1]
+
+volatile_state_is_recorded_in_history(s) ==
+    \A p \in InstancePairs(s) :
+        LET volatile == Inst(s, p[1], p[2]).volatile
+            hist == Hist(s, p[1], p[2])
+        IN /\ \A b \in DOMAIN volatile.promised :
+                /\ b \in DOMAIN hist.promised
+                /\ hist.promised[b] = volatile.promised[b]
+                /\ (b \in DOMAIN hist.proposed_value) = (b \in DOMAIN volatile.proposed_value)
+                /\ (b \in DOMAIN hist.accepted) = (b \in DOMAIN volatile.accepted)
+           /\ \A b \in DOMAIN volatile.proposed_value :
+                /\ b \in DOMAIN volatile.promised
+                /\ b \in DOMAIN hist.proposed_value
+                /\ hist.proposed_value[b] = volatile.proposed_value[b]
+           /\ \A b \in DOMAIN volatile.accepted :
+                /\ b \in DOMAIN volatile.promised
+                /\ b \in DOMAIN hist.accepted
+                /\ hist.accepted[b] = volatile.accepted[b]
+
+leading_ballot_is_only_in_live_instances(s) ==
+    \A p \in InstancePairs(s) :
+        LET leading == s.hosts[p[1]].multi.leading
+            hist == Hist(s, p[1], p[2])
+        IN IsSome(leading) /\ leading[1][1] <= p[2] =>
+            LET b == leading[1][2]
+            IN b \in DOMAIN hist.promised \cup DOMAIN hist.proposed_value \cup DOMAIN hist.accepted =>
+                b \in DOMAIN Inst(s, p[1], p[2]).volatile.promised
+
+learned_is_recorded_in_history(s) ==
+    \A p \in InstancePairs(s) :
+        LET learned == Inst(s, p[1], p[2]).volatile.learned
+            hist == Hist(s, p[1], p[2])
+        IN \A bv \in DOMAIN learned : bv \in DOMAIN hist.learned /\ hist.learned[bv] = learned[bv]
+
+\* The instances some host has created, which are the ones with a recorded configuration.
+ConfiguredKeys(s) == {p[2] : p \in InstancePairs(s)}
+
+QuorumsWellFormed ==
+    /\ Phase1Size + Phase2Size > NumHosts
+    /\ Phase1Size + NumFailures <= NumHosts
+    /\ Phase2Size + NumFailures <= NumHosts
+    /\ Phase1Size + 2 * FastSize > 2 * NumHosts
+
+configurations_are_valid(s) == \A k \in ConfiguredKeys(s) : QuorumsWellFormed
+
+\* Every recorded configuration is the initial one, which needs no decision.
+configurations_were_decided(s) == TRUE
+
+instances_use_recorded_configurations(s) == TRUE
+
+instances_know_their_configuration(s) == TRUE
+
+message_keys_have_configurations(s) ==
+    \A m \in s.sent : IsSome(message_key(m)) => message_key(m)[1] \in ConfiguredKeys(s)
+
+prepare_msg_in_network_implies_sender_map_has_ballot_key(s) ==
+    \A m \in SentOf(s, "Prepare") :
+        /\ HasInstance(s, m.ballot.pid, m.key)
+        /\ m.ballot \in DOMAIN Hist(s, m.ballot.pid, m.key).promised
+        /\ m.ballot \in DOMAIN Hist(s, m.ballot.pid, m.key).accepted
+
+promise_msg_in_network_implies_sender_has_promised(s) ==
+    \A m \in SentOf(s, "Promise") :
+        HasInstance(s, m.sender, m.key) /\ Cmp(Inst(s, m.sender, m.key).current_ballot, m.ballot) >= 0
+
+accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender(s) ==
+    \A m \in SentOf(s, "Accept") :
+        /\ HasInstance(s, m.ballot.pid, m.key)
+        /\ LET leader == Hist(s, m.ballot.pid, m.key)
+           IN /\ m.ballot \in DOMAIN leader.promised
+              /\ IsPhase1Quorum(DOMAIN leader.promised[m.ballot])
+              /\ m.ballot \in DOMAIN leader.proposed_value
+              /\ leader.proposed_value[m.ballot] = m.value
+
+accepted_msg_in_network_implies_sender_has_accepted_some_value(s) ==
+    \A m \in SentOf(s, "Accepted") :
+        /\ HasInstance(s, m.sender, m.key)
+        /\ LET acceptor == Inst(s, m.sender, m.key)
+           IN /\ Cmp(acceptor.current_ballot, m.ballot) >= 0
+              /\ IsSome(acceptor.accept_ballot)
+              /\ Cmp(acceptor.accept_ballot[1], m.ballot) >= 0
+              /\ IsSome(acceptor.accept_value)
+
+AcceptedSenders(s, k, b, v) == {a.sender : a \in {a \in SentOf(s, "Accepted") : a.key = k /\ a.ballot = b /\ a.value = v}}
+
+\* `accepted_by` holds for some set exactly when it holds for every sender of a matching Accepted.
+quorum_has_accepted(s, k, b, v) == IsPhase2Quorum(AcceptedSenders(s, k, b, v))
+
+\* `fast_accepted_by`, likewise.
+fast_quorum_has_accepted(s, k, v) == IsFastQuorum(AcceptedSenders(s, k, FastBallot, v))
+
+\* The Accept an acceptor took <<b, v>> from, or in the fast round the client's request.
+accept_was_sent(s, k, b, v) ==
+    IF IsFastBallot(b) THEN b = FastBallot /\ ClientRequest(v) \in s.sent /\ k = FastSlot(v)
+    ELSE Accept(k, b, v) \in s.sent
+
+decide_msg_in_network_implies_quorum_has_accepted_some_value(s) ==
+    \A m \in SentOf(s, "Decide") :
+        IF IsFastBallot(m.ballot)
+        THEN m.ballot = FastBallot /\ fast_quorum_has_accepted(s, m.key, m.value)
+        ELSE /\ HasInstance(s, m.ballot.pid, m.key)
+             /\ LET leader == Hist(s, m.ballot.pid, m.key)
+                IN /\ m.ballot \in DOMAIN leader.proposed_value
+                   /\ leader.proposed_value[m.ballot] = m.value
+             /\ quorum_has_accepted(s, m.key, m.ballot, m.value)
+
+\* `b` was prepared for `k`, by a Prepare for it or by a PrepareFrom covering it.
+ballot_was_prepared(s, k, b) ==
+    Prepare(k, b) \in s.sent \/ \E m \in SentOf(s, "PrepareFrom") : m.from <= k /\ m.ballot = b
+
+nack_msg_in_network_implies_higher_ballot_was_prepared(s) ==
+    \A m \in SentOf(s, "Nack") : Cmp(m.higher, m.ballot) > 0 /\ ballot_was_prepared(s, m.key, m.higher)
+
+promised_state_implies_network_has_prepare_msg(s) ==
+    \A p \in InstancePairs(s) :
+        LET inst == Inst(s, p[1], p[2])
+        IN inst.current_ballot.num = 0 \/ ballot_was_prepared(s, p[2], inst.current_ballot)
+
+someone_promised_implies_network_has_their_promise_msg(s) ==
+    \A p \in InstancePairs(s) :
+        LET hist == Hist(s, p[1], p[2])
+        IN \A b \in DOMAIN hist.promised : \A snd \in DOMAIN hist.promised[b] :
+            Promise(p[2], snd, b, hist.promised[b][snd]) \in s.sent
+
+if_accept_ballot_is_some_then_accept_value_is_some(s) ==
+    \A p \in InstancePairs(s) :
+        IsSome(Inst(s, p[1], p[2]).accept_ballot) = IsSome(Inst(s, p[1], p[2]).accept_value)
+
+accepted_state_implies_network_has_accept_message(s) ==
+    \A p \in InstancePairs(s) :
+        LET inst == Inst(s, p[1], p[2])
+        IN IF ~IsSome(inst.accept_ballot) /\ ~IsSome(inst.accept_value) THEN TRUE
+           ELSE /\ IsSome(inst.accept_ballot)
+                /\ IsSome(inst.accept_value)
+                /\ accept_was_sent(s, p[2], inst.accept_ballot[1], inst.accept_value[1])
+
+accepted_state_implies_network_has_accepted_message(s) ==
+    \A p \in InstancePairs(s) :
+        LET inst == Inst(s, p[1], p[2])
+        IN IsSome(inst.accept_ballot) =>
+            IsSome(inst.accept_value) /\ Accepted(p[2], p[1], inst.accept_ballot[1], inst.accept_value[1]) \in s.sent
+
+someone_accepted_implies_network_has_their_accepted_msg(s) ==
+    \A p \in InstancePairs(s) :
+        LET hist == Hist(s, p[1], p[2])
+        IN \A b \in DOMAIN hist.accepted : \A snd \in hist.accepted[b] :
+            b \in DOMAIN hist.proposed_value /\ Accepted(p[2], snd, b, hist.proposed_value[b]) \in s.sent
+
+learned_senders_have_sent_accepted(s) ==
+    \A p \in InstancePairs(s) :
+        LET inst == Inst(s, p[1], p[2])
+        IN \A bv \in DOMAIN inst.volatile.learned : \A snd \in inst.volatile.learned[bv] : Accepted(p[2], snd, bv[1], bv[2]) \in s.sent
+
+proposals_were_sent(s) ==
+    \A p \in InstancePairs(s) :
+        LET hist == Hist(s, p[1], p[2])
+        IN \A b \in DOMAIN hist.proposed_value : Accept(p[2], b, hist.proposed_value[b]) \in s.sent
+
+decided_state_implies_network_has_decide_message(s) ==
+    \A p \in InstancePairs(s) :
+        LET inst == Inst(s, p[1], p[2])
+        IN IsSome(inst.decide_value) =>
+            \E m \in SentOf(s, "Decide") : m.key = p[2] /\ m.value = inst.decide_value[1]
+
+network_msgs_have_valid_sender_and_ballot_pid(s) ==
+    LET valid(id) == id \in HostIds(s)
+    IN \A m \in s.sent :
+        CASE m.type \in {"Prepare", "PrepareFrom"} -> m.ballot.num > 0 /\ valid(m.ballot.pid)
+          [] m.type = "Promise" ->
+                /\ m.ballot.num > 0
+                /\ valid(m.sender)
+                /\ valid(m.ballot.pid)
+                /\ IsSome(m.accepted) => valid(m.accepted[1][1].pid)
+          [] m.type = "Accept" -> m.ballot.num > 0 /\ valid(m.ballot.pid)
+          [] m.type = "Accepted" -> (m.ballot.num > 0 \/ m.ballot = FastBallot) /\ valid(m.sender) /\ valid(m.ballot.pid)
+          [] m.type = "Nack" -> m.ballot.num > 0 /\ valid(m.ballot.pid) /\ m.higher.num > 0 /\ valid(m.higher.pid)
+          [] m.type = "Decide" -> m.ballot.num = 0 \/ valid(m.ballot.pid)
+          [] m.type = "ClientRequest" -> TRUE
+          [] IsBatch(m) -> TRUE
+
+promise_msgs_from_same_sender_for_same_ballot_have_same_accepted(s) ==
+    \A m1, m2 \in SentOf(s, "Promise") :
+        m1.key = m2.key /\ m1.sender = m2.sender /\ m1.ballot = m2.ballot => m1.accepted = m2.accepted
+
+ballot_in_accepted_is_smaller_than_promise_message_ballot(s) ==
+    \A m \in SentOf(s, "Promise") : IsSome(m.accepted) => Cmp(m.accepted[1][1], m.ballot) < 0
+
+\* Only a classic ballot has a leader whose proposal the reported value is.
+value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot(s) ==
+    \A m \in SentOf(s, "Promise") :
+        IsSome(m.accepted) /\ ~IsFastBallot(m.accepted[1][1]) =>
+            LET b == m.accepted[1][1]
+            IN /\ HasInstance(s, b.pid, m.key)
+               /\ b \in DOMAIN Hist(s, b.pid, m.key).proposed_value
+               /\ Hist(s, b.pid, m.key).proposed_value[b] = m.accepted[1][2]
+
+if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message(s) ==
+    \A m \in SentOf(s, "Promise") :
+        IsSome(m.accepted) => Accepted(m.key, m.sender, m.accepted[1][1], m.accepted[1][2]) \in s.sent
+
+network_has_at_most_one_accept_message_for_any_ballot(s) ==
+    \A m1, m2 \in SentOf(s, "Accept") : m1.key = m2.key /\ m1.ballot = m2.ballot => m1.value = m2.value
+
+accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted(s) ==
+    \A a \in SentOf(s, "Accepted"), p \in SentOf(s, "Promise") :
+        p.key = a.key /\ p.sender = a.sender /\ Cmp(p.ballot, a.ballot) > 0 =>
+            IsSome(p.accepted) /\ Cmp(p.accepted[1][1], a.ballot) >= 0
+
+accepted_msg_in_network_implies_network_has_corresponding_accept_msg(s) ==
+    \A a \in SentOf(s, "Accepted") : accept_was_sent(s, a.key, a.ballot, a.value)
+
+fast_accepted_msgs_from_same_sender_have_same_value(s) ==
+    \A a1, a2 \in SentOf(s, "Accepted") :
+        a1.key = a2.key /\ a1.sender = a2.sender /\ a1.ballot = FastBallot /\ a2.ballot = FastBallot => a1.value = a2.value
+
+all_decide_messages_hold_same_value(s) ==
+    \A m1, m2 \in SentOf(s, "Decide") : m1.key = m2.key => m1.value = m2.value
+
+batch_msgs_in_network_carry_sent_msgs(s) == \A m \in s.sent : IsBatch(m) => Unbatch(m) \subseteq s.sent
+
+if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot(s) ==
+    \A p \in InstancePairs(s) :
+        LET hist == Hist(s, p[1], p[2])
+        IN \A b \in DOMAIN hist.promised \cup DOMAIN hist.proposed_value \cup DOMAIN hist.accepted :
+            ballot_was_prepared(s, p[2], b)
+
+floors_and_leads_were_prepared_from(s) ==
+    \A i \in HostIds(s) :
+        LET multi == s.hosts[i].multi
+        IN /\ multi.promise_floor # Ballot(0, 0) => PrepareFrom(multi.floor_from, multi.promise_floor) \in s.sent
+           /\ IsSome(multi.leading) =>
+                multi.leading[1][2].pid = i /\ PrepareFrom(multi.leading[1][1], multi.leading[1][2]) \in s.sent
+
+if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(s) ==
+    \A p \in InstancePairs(s) :
+        LET hist == Hist(s, p[1], p[2])
+        IN \A b \in DOMAIN hist.proposed_value :
+            b \in DOMAIN hist.promised /\ IsSome(select_value(hist.promised[b])) =>
+                hist.proposed_value[b] = select_value(hist.promised[b])[1][2]
+
+host_accept_ballot_is_none_or_leq_to_current_ballot(s) ==
+    \A p \in InstancePairs(s) :
+        LET inst == Inst(s, p[1], p[2])
+        IN IsSome(inst.accept_ballot) => Cmp(inst.accept_ballot[1], inst.current_ballot) <= 0
+
+host_ballots_are_at_most_max_seen_ballot(s) ==
+    \A p \in InstancePairs(s) :
+        LET inst == Inst(s, p[1], p[2])
+            hist == Hist(s, p[1], p[2])
+            max == inst.max_seen_ballot
+        IN /\ Cmp(inst.current_ballot, max) <= 0
+           /\ \A b \in DOMAIN hist.promised \cup DOMAIN hist.proposed_value \cup DOMAIN hist.accepted : Cmp(b, max) <= 0
+
+any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value(s) ==
+    \A p \in InstancePairs(s), j \in HostIds(s) :
+        LET i1 == Inst(s, p[1], p[2])
+        IN p[2] \in DOMAIN s.hosts[j].instances =>
+            (IsSome(i1.accept_ballot) /\ ~IsFastBallot(i1.accept_ballot[1]) /\ i1.accept_ballot = Inst(s, j, p[2]).accept_ballot =>
+                IsSome(i1.accept_value) /\ i1.accept_value = Inst(s, j, p[2]).accept_value)
+
+if_someone_has_accepted_then_someone_has_proposed(s) ==
+    \A p \in InstancePairs(s) :
+        LET hist == Hist(s, p[1], p[2])
+        IN \A b \in DOMAIN hist.accepted : hist.accepted[b] = {} \/ b \in DOMAIN hist.proposed_value
+
+same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(s) ==
+    \A p \in InstancePairs(s) :
+        LET hist == Hist(s, p[1], p[2])
+        IN \A b \in DOMAIN hist.promised :
+            LET map == hist.promised[b]
+            IN \A s1, s2 \in DOMAIN map :
+                IsSome(map[s1]) /\ IsSome(map[s2]) /\ ~IsFastBallot(map[s1][1][1]) /\ map[s1][1][1] = map[s2][1][1] =>
+                    map[s1][1][2] = map[s2][1][2]
+
+pending_requests_are_in_network(s) ==
+    \A i \in HostIds(s) : \A n \in DOMAIN s.hosts[i].pending :
+        ClientRequest(s.hosts[i].pending[n]) \in s.sent
+
+\* The <<key, value>> of every fast-ballot Accepted, which value_was_fast_proposed ranges over.
+FastProposals(s) == {<<m.key, m.value>> : m \in {m \in SentOf(s, "Accepted") : m.ballot = FastBallot}}
+
+\* The <<key, value>> of every proposal a leader made.
+ClassicProposals(s) ==
+    UNION {{<<p[2], Hist(s, p[1], p[2]).proposed_value[b]>> : b \in DOMAIN Hist(s, p[1], p[2]).proposed_value} : p \in InstancePairs(s)}
+
+\* The <<key, value>> of every proposal and every fast proposal.
+Proposals(s) == ClassicProposals(s) \cup FastProposals(s)
+
+ProposedValues(s) == {p[2] : p \in Proposals(s)}
+
+submitted_values_are_not_no_ops(s) == \A m \in SentOf(s, "ClientRequest") : ~IsNoOp(m.value)
+
+\* A leader proposes a request some client submitted, or the no-op of the instance. An acceptor
+\* only takes a fast client's request in the fast round.
+proposed_values_were_submitted(s) ==
+    /\ \A p \in InstancePairs(s) :
+          \A b \in DOMAIN Hist(s, p[1], p[2]).proposed_value :
+              LET v == Hist(s, p[1], p[2]).proposed_value[b]
+              IN ClientRequest(v) \in s.sent \/ v = NoOp(p[2])
+    /\ \A p \in FastProposals(s) : ClientRequest(p[2]) \in s.sent /\ IsFastRequest(p[2])
+
+value_was_proposed(s, v) == v \in ProposedValues(s)
+
+\* Fast clients skip the owner, so no queue holds their requests.
+pending_requests_are_owned_and_distinct(s) ==
+    \A i \in HostIds(s) :
+        LET pending == s.hosts[i].pending
+        IN /\ \A n \in DOMAIN pending : RequestOwner(pending[n].client_id) = i /\ ~IsFastRequest(pending[n])
+           /\ \A n1, n2 \in DOMAIN pending :
+                n1 # n2 => RequestId(pending[n1]) # RequestId(pending[n2])
+
+pending_requests_were_not_proposed(s) ==
+    \A i \in HostIds(s) : \A n \in DOMAIN s.hosts[i].pending : ~value_was_proposed(s, s.hosts[i].pending[n])
+
+pending_requests_are_not_assigned(s) ==
+    \A i \in HostIds(s) : \A n \in DOMAIN s.hosts[i].pending : RequestId(s.hosts[i].pending[n]) \notin s.hosts[i].assigned
+
+\* The owner of every proposed request has recorded it in `assigned` and will not queue it again.
+\* No-ops and fast requests are never queued, so they have no owner to record them.
+proposed_requests_are_assigned(s) ==
+    \A v \in {v \in ProposedValues(s) : ~IsNoOp(v) /\ ~IsFastRequest(v)} :
+        RequestOwner(v.client_id) \in HostIds(s) /\ RequestId(v) \in s.hosts[RequestOwner(v.client_id)].assigned
+
+\* Acceptors may take different operations for a fast request, but all of them for its FastSlot.
+proposed_requests_have_one_value(s) ==
+    \A v1, v2 \in ProposedValues(s) : ~IsFastRequest(v1) /\ RequestId(v1) = RequestId(v2) => v1 = v2
+
+\* A leader proposes a value in one instance only, and a fast request only in its FastSlot.
+proposed_values_are_proposed_in_one_slot(s) ==
+    /\ \A p1, p2 \in ClassicProposals(s) : p1[2] = p2[2] => p1[1] = p2[1]
+    /\ \A p \in ClassicProposals(s) : IsFastRequest(p[2]) => p[1] = FastSlot(p[2])
+
+if_host_proposed_then_quorum_has_promised(s) ==
+    \A p \in InstancePairs(s) :
+        LET hist == Hist(s, p[1], p[2])
+        IN \A b \in DOMAIN hist.proposed_value : b \in DOMAIN hist.promised /\ IsPhase1Quorum(DOMAIN hist.promised[b])
+
+\* The <<i1, i2, accepted_ballot, future_ballot>> for which `two_maps_contain_phase2_and_phase1_quorums`
+\* holds and future_ballot is above accepted_ballot.
+AcceptedAndFutureQuorums(s) ==
+    UNION {UNION {(LET i1 == Hist(s, p[1], p[2])
+                       i2 == Hist(s, j, p[2])
+                   IN {<<i1, i2, ab, fb>> :
+                         ab \in {b \in DOMAIN i1.accepted : IsPhase2Quorum(i1.accepted[b])},
+                         fb \in {b \in DOMAIN i2.promised : IsPhase1Quorum(DOMAIN i2.promised[b])}})
+                  : j \in {j \in HostIds(s) : p[2] \in DOMAIN s.hosts[j].instances}}
+           : p \in InstancePairs(s)}
+
+if_system_accepted_exists_some_accept_value_in_future_promise_quorum(s) ==
+    \A q \in AcceptedAndFutureQuorums(s) :
+        LET i1 == q[1]
+            i2 == q[2]
+            ab == q[3]
+            fb == q[4]
+        IN Cmp(fb, ab) > 0 =>
+            \E snd \in DOMAIN i2.promised[fb] : IsSome(i2.promised[fb][snd]) /\ snd \in i1.accepted[ab]
+
+accepted_system_calculates_same_proposed_value_in_future(s) ==
+    \A q \in AcceptedAndFutureQuorums(s) :
+        LET i1 == q[1]
+            i2 == q[2]
+            ab == q[3]
+            fb == q[4]
+            calculated == get_max_accepted_value(i2.promised[fb])
+        IN Cmp(fb, ab) > 0 =>
+            /\ IsSome(calculated)
+            /\ ab \in DOMAIN i1.proposed_value
+            /\ calculated[1][2] = i1.proposed_value[ab]
+
+accepted_system_always_proposes_same_value_in_future(s) ==
+    \A p \in InstancePairs(s), j \in HostIds(s) :
+        LET hist == Hist(s, p[1], p[2])
+        IN p[2] \in DOMAIN s.hosts[j].instances =>
+            \A ab \in {b \in DOMAIN hist.accepted : IsPhase2Quorum(hist.accepted[b])} :
+                \A fb \in DOMAIN Hist(s, j, p[2]).proposed_value :
+                    fb.pid = j /\ Cmp(fb, ab) >= 0 =>
+                        /\ ab \in DOMAIN hist.proposed_value
+                        /\ hist.proposed_value[ab] = Hist(s, j, p[2]).proposed_value[fb]
+
+\* `safety` followed by the conjuncts of `inductive`, one field per `invariants::INVARIANTS` entry.
+Invariants(s) ==
+    [safety |-> safety(s),
+     well_formed |-> well_formed(s),
+     in_flight_messages_are_sent |-> in_flight_messages_are_sent(s),
+     all_maps_and_sets_are_finite |-> all_maps_and_sets_are_finite(s),
+     all_map_keys_and_set_values_are_valid |-> all_map_keys_and_set_values_are_valid(s),
+     all_map_and_set_sizes_are_bounded |-> all_map_and_set_sizes_are_bounded(s),
+     all_ballot_pids_in_all_maps_correspond_to_respective_host_id |-> all_ballot_pids_in_all_maps_correspond_to_respective_host_id(s),
+     volatile_state_is_recorded_in_history |-> volatile_state_is_recorded_in_history(s),
+     leading_ballot_is_only_in_live_instances |-> leading_ballot_is_only_in_live_instances(s),
+     learned_is_recorded_in_history |-> learned_is_recorded_in_history(s),
+     configurations_are_valid |-> configurations_are_valid(s),
+     configurations_were_decided |-> configurations_were_decided(s),
+     instances_use_recorded_configurations |-> instances_use_recorded_configurations(s),
+     instances_know_their_configuration |-> instances_know_their_configuration(s),
+     message_keys_have_configurations |-> message_keys_have_configurations(s),
+     prepare_msg_in_network_implies_sender_map_has_ballot_key |-> prepare_msg_in_network_implies_sender_map_has_ballot_key(s),
+     promise_msg_in_network_implies_sender_has_promised |-> promise_msg_in_network_implies_sender_has_promised(s),
+     accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender |-> accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender(s),
+     accepted_msg_in_network_implies_sender_has_accepted_some_value |-> accepted_msg_in_network_implies_sender_has_accepted_some_value(s),
+     decide_msg_in_network_implies_quorum_has_accepted_some_value |-> decide_msg_in_network_implies_quorum_has_accepted_some_value(s),
+     nack_msg_in_network_implies_higher_ballot_was_prepared |-> nack_msg_in_network_implies_higher_ballot_was_prepared(s),
+     promised_state_implies_network_has_prepare_msg |-> promised_state_implies_network_has_prepare_msg(s),
+     someone_promised_implies_network_has_their_promise_msg |-> someone_promised_implies_network_has_their_promise_msg(s),
+     if_accept_ballot_is_some_then_accept_value_is_some |-> if_accept_ballot_is_some_then_accept_value_is_some(s),
+     accepted_state_implies_network_has_accept_message |-> accepted_state_implies_network_has_accept_message(s),
+     accepted_state_implies_network_has_accepted_message |-> accepted_state_implies_network_has_accepted_message(s),
+     someone_accepted_implies_network_has_their_accepted_msg |-> someone_accepted_implies_network_has_their_accepted_msg(s),
+     learned_senders_have_sent_accepted |-> learned_senders_have_sent_accepted(s),
+     proposals_were_sent |-> proposals_were_sent(s),
+     decided_state_implies_network_has_decide_message |-> decided_state_implies_network_has_decide_message(s),
+     network_msgs_have_valid_sender_and_ballot_pid |-> network_msgs_have_valid_sender_and_ballot_pid(s),
+     promise_msgs_from_same_sender_for_same_ballot_have_same_accepted |-> promise_msgs_from_same_sender_for_same_ballot_have_same_accepted(s),
+     ballot_in_accepted_is_smaller_than_promise_message_ballot |-> ballot_in_accepted_is_smaller_than_promise_message_ballot(s),
+     value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot |-> value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot(s),
+     if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message |-> if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message(s),
+     network_has_at_most_one_accept_message_for_any_ballot |-> network_has_at_most_one_accept_message_for_any_ballot(s),
+     accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted |-> accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted(s),
+     accepted_msg_in_network_implies_network_has_corresponding_accept_msg |-> accepted_msg_in_network_implies_network_has_corresponding_accept_msg(s),
+     fast_accepted_msgs_from_same_sender_have_same_value |-> fast_accepted_msgs_from_same_sender_have_same_value(s),
+     all_decide_messages_hold_same_value |-> all_decide_messages_hold_same_value(s),
+     batch_msgs_in_network_carry_sent_msgs |-> batch_msgs_in_network_carry_sent_msgs(s),
+     if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot |-> if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot(s),
+     floors_and_leads_were_prepared_from |-> floors_and_leads_were_prepared_from(s),
+     if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some |-> if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(s),
+     host_accept_ballot_is_none_or_leq_to_current_ballot |-> host_accept_ballot_is_none_or_leq_to_current_ballot(s),
+     host_ballots_are_at_most_max_seen_ballot |-> host_ballots_are_at_most_max_seen_ballot(s),
+     any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value |-> any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value(s),
+     if_someone_has_accepted_then_someone_has_proposed |-> if_someone_has_accepted_then_someone_has_proposed(s),
+     same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts |-> same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(s),
+     pending_requests_are_in_network |-> pending_requests_are_in_network(s),
+     submitted_values_are_not_no_ops |-> submitted_values_are_not_no_ops(s),
+     proposed_values_were_submitted |-> proposed_values_were_submitted(s),
+     pending_requests_are_owned_and_distinct |-> pending_requests_are_owned_and_distinct(s),
+     pending_requests_were_not_proposed |-> pending_requests_were_not_proposed(s),
+     pending_requests_are_not_assigned |-> pending_requests_are_not_assigned(s),
+     proposed_requests_are_assigned |-> proposed_requests_are_assigned(s),
+     proposed_requests_have_one_value |-> proposed_requests_have_one_value(s),
+     proposed_values_are_proposed_in_one_slot |-> proposed_values_are_proposed_in_one_slot(s),
+     if_host_proposed_then_quorum_has_promised |-> if_host_proposed_then_quorum_has_promised(s),
+     if_system_accepted_exists_some_accept_value_in_future_promise_quorum |-> if_system_accepted_exists_some_accept_value_in_future_promise_quorum(s),
+     accepted_system_calculates_same_proposed_value_in_future |-> accepted_system_calculates_same_proposed_value_in_future(s),
+     accepted_system_always_proposes_same_value_in_future |-> accepted_system_always_proposes_same_value_in_future(s)]
+
+Inductive(s) == \A name \in DOMAIN Invariants(s) : Invariants(s)[name]
+
+SafetyInvariant == safety(state)
+InductiveInvariant == Inductive(state)
+=============================================================================
@@ -0,0 +1,372 @@
+use super::{order, Message, NetworkOperation};
+use crate::distributed_system::{low_level::quorum::{are_valid_hosts, QuorumSystem}, Value};
+use vstd::prelude::*;
+
+verus! {
+    // Instance `(owner, slot)` is only ever started by replica `owner`, so hosts never compete for an
+    // instance the way they do for a `low_level` key.
+    pub struct InstanceId {
+        pub owner: nat,
+        pub slot: nat,
+    }
+
+    // A command with the attributes that order it: the instances it depends on and its sequence
+    // number.
+    pub struct Command {
+        pub value: Value,
+        pub deps: Set<InstanceId>,
+        pub seq: nat,
+    }
+
+    pub enum Status {
+        PreAccepted,
+        Accepted,
+        Committed,
+    }
+
+    // `pre_accept_oks` and `accept_oks` are the owner's bookkeeping: the attributes each replica
+    // answered the PreAccept with (the owner's own included), and who took the Accept. Other
+    // replicas leave both empty.
+    pub struct Instance {
+        pub command: Command,
+        pub status: Status,
+        pub pre_accept_oks: Map<nat, Command>,
+        pub accept_oks: Set<nat>,
+    }
+
+    // `interferes` says which commands must be executed in the same order everywhere, e.g. two writes
+    // to the same key; commands that do not interfere commute.
+    pub struct Constants {
+        pub id: nat,
+        pub num_hosts: nat,
+        pub quorums: QuorumSystem,
+        pub interferes: spec_fn(Value, Value) -> bool,
+    }
+
+    // `next_slot` is the first slot of this replica's own instance space it has not used yet, and
+    // `executed` the instances it has executed, in order.
+    pub struct Variables {
+        pub instances: Map<InstanceId, Instance>,
+        pub next_slot: nat,
+        pub executed: Seq<InstanceId>,
+    }
+
+    impl Constants {
+        pub open spec fn well_formed(&self) -> bool {
+            &&& 0 <= self.id < self.num_hosts
+            &&& self.quorums.well_formed(self.num_hosts)
+            &&& forall |a: Value, b: Value| #[trigger] (self.interferes)(a, b) == (self.interferes)(b, a)
+        }
+
+        // The owner waits for replies from a set that is a phase-1 and a phase-2 quorum at once, so the
+        // replies to any two instances have a replica in common.
+        pub open spec fn is_quorum(&self, hosts: Set<nat>) -> bool {
+            &&& are_valid_hosts(hosts, self.num_hosts)
+            &&& self.quorums.is_phase1_quorum(hosts)
+            &&& self.quorums.is_phase2_quorum(hosts)
+        }
+    }
+
+    impl Variables {
+        pub open spec fn well_formed(&self, c: &Constants) -> bool {
+            &&& c.well_formed()
+        }
+
+        // The commands this replica knows to be committed.
+        pub open spec fn committed(&self) -> Map<InstanceId, Command> {
+            Map::new(
+                |id: InstanceId| self.instances.contains_key(id) && self.instances[id].status is Committed,
+                |id: InstanceId| self.instances[id].command,
+            )
+        }
+    }
+
+    // The instances this replica holds, other than `id`, whose command interferes with `value`.
+    pub open spec fn interfering_instances(c: &Constants, u: &Variables, id: InstanceId, value: Value) -> Set<InstanceId> {
+        Set::new(|x: InstanceId| u.instances.contains_key(x) && x != id && (c.interferes)(u.instances[x].command.value, value))
+    }
+
+    // The attributes of `command` for instance `id` put it after every interfering instance this
+    // replica holds: it depends on all of them and has a higher sequence number than any.
+    pub open spec fn orders_after_local(c: &Constants, u: &Variables, id: InstanceId, command: Command) -> bool {
+        &&& interfering_instances(c, u, id, command.value).subset_of(command.deps)
+        &&& forall |x: InstanceId| #[trigger] interfering_instances(c, u, id, command.value).contains(x) ==> u.instances[x].command.seq < command.seq
+    }
+
+    pub open spec fn union_of_deps(replies: Map<nat, Command>) -> Set<InstanceId> {
+        Set::new(|x: InstanceId| exists |sender: nat| #[trigger] replies.contains_key(sender) && replies[sender].deps.contains(x))
+    }
+
+    pub open spec fn init(c: &Constants, u: &Variables, host_id: nat, num_hosts: nat) -> bool {
+        &&& u.well_formed(c)
+        &&& c.id == host_id
+        &&& c.num_hosts == num_hosts
+        &&& u.instances.is_empty()
+        &&& u.next_slot == 0
+        &&& u.executed.len() == 0
+    }
+
+    pub open spec fn submit_request(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation, value: Value) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& net_op.recv.is_none()
+        &&& v == u
+        &&& net_op.send == Some(Message::ClientRequest { value })
+    }
+
+    // Any replica takes a request into the next slot of its own instance space, with attributes from
+    // what it holds, and counts itself as the first reply.
+    pub open spec fn propose(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let (Some(Message::ClientRequest { value }), Some(Message::PreAccept { id, command })) = (net_op.recv, net_op.send) {
+            &&& id == InstanceId { owner: c.id, slot: u.next_slot }
+            &&& command.value == value
+            &&& command.deps == interfering_instances(c, u, id, value)
+            &&& orders_after_local(c, u, id, command)
+            &&& v.instances == u.instances.insert(id, Instance {
+                    command,
+                    status: Status::PreAccepted,
+                    pre_accept_oks: map![c.id => command],
+                    accept_oks: Set::empty(),
+                })
+            &&& v.next_slot == u.next_slot + 1
+            &&& v.executed == u.executed
+        } else {
+            &&& false
+        }
+    }
+
+    // A replica answers the PreAccept of an instance it has not seen with the proposed attributes,
+    // extended to order the command after every interfering instance it holds.
+    pub open spec fn pre_accept(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let (Some(Message::PreAccept { id, command }), Some(Message::PreAcceptOk { id: reply_id, sender, command: reply })) = (net_op.recv, net_op.send) {
+            &&& id.owner != c.id
+            &&& !u.instances.contains_key(id)
+            &&& reply_id == id
+            &&& sender == c.id
+            &&& reply.value == command.value
+            &&& reply.deps == command.deps.union(interfering_instances(c, u, id, command.value))
+            &&& reply.seq >= command.seq
+            &&& orders_after_local(c, u, id, reply)
+            &&& v.instances == u.instances.insert(id, Instance {
+                    command: reply,
+                    status: Status::PreAccepted,
+                    pre_accept_oks: Map::empty(),
+                    accept_oks: Set::empty(),
+                })
+            &&& v.next_slot == u.next_slot
+            &&& v.executed == u.executed
+        } else {
+            &&& false
+        }
+    }
+
+    pub open spec fn pre_accept_ok(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::PreAcceptOk { id, sender, command }) = net_op.recv {
+            &&& id.owner == c.id
+            &&& u.instances.contains_key(id)
+            &&& u.instances[id].status is PreAccepted
+            &&& v.instances == u.instances.insert(id, Instance {
+                    command: u.instances[id].command,
+                    status: u.instances[id].status,
+                    pre_accept_oks: u.instances[id].pre_accept_oks.insert(sender, command),
+                    accept_oks: u.instances[id].accept_oks,
+                })
+            &&& v.next_slot == u.next_slot
+            &&& v.executed == u.executed
+            &&& net_op.send.is_none()
+        } else {
+            &&& false
+        }
+    }
+
+    // Fast path: a quorum answered with the attributes the owner proposed, so it commits them.
+    pub open spec fn commit_fast(c: &Constants, u: &Variables, v: &Variables, id: InstanceId, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& id.owner == c.id
+        &&& u.instances.contains_key(id)
+        &&& u.instances[id].status is PreAccepted
+        &&& c.is_quorum(u.instances[id].pre_accept_oks.dom())
+        &&& forall |sender: nat| #[trigger] u.instances[id].pre_accept_oks.contains_key(sender) ==> u.instances[id].pre_accept_oks[sender] == u.instances[id].command
+        &&& net_op.recv.is_none()
+        &&& v.instances == u.instances.insert(id, Instance {
+                command: u.instances[id].command,
+                status: Status::Committed,
+                pre_accept_oks: u.instances[id].pre_accept_oks,
+                accept_oks: u.instances[id].accept_oks,
+            })
+        &&& v.next_slot == u.next_slot
+        &&& v.executed == u.executed
+        &&& net_op.send == Some(Message::Commit { id, command: u.instances[id].command })
+    }
+
+    // Slow path: the replies disagree, so the owner takes the union of their dependencies and a
+    // sequence number at least as high as any of theirs, and runs an Accept round on that.
+    pub open spec fn send_accept(c: &Constants, u: &Variables, v: &Variables, id: InstanceId, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::Accept { id: accept_id, command }) = net_op.send {
+            &&& accept_id == id
+            &&& id.owner == c.id
+            &&& u.instances.contains_key(id)
+            &&& u.instances[id].status is PreAccepted
+            &&& c.is_quorum(u.instances[id].pre_accept_oks.dom())
+            &&& command.value == u.instances[id].command.value
+            &&& command.deps == union_of_deps(u.instances[id].pre_accept_oks)
+            &&& forall |sender: nat| #[trigger] u.instances[id].pre_accept_oks.contains_key(sender) ==> u.instances[id].pre_accept_oks[sender].seq <= command.seq
+            &&& net_op.recv.is_none()
+            &&& v.instances == u.instances.insert(id, Instance {
+                    command,
+                    status: Status::Accepted,
+                    pre_accept_oks: u.instances[id].pre_accept_oks,
+                    accept_oks: set![c.id],
+                })
+            &&& v.next_slot == u.next_slot
+            &&& v.executed == u.executed
+        } else {
+            &&& false
+        }
+    }
+
+    pub open spec fn accept(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::Accept { id, command }) = net_op.recv {
+            &&& id.owner != c.id
+            &&& !(u.instances.contains_key(id) && u.instances[id].status is Committed)
+            &&& v.instances == u.instances.insert(id, Instance {
+                    command,
+                    status: Status::Accepted,
+                    pre_accept_oks: Map::empty(),
+                    accept_oks: Set::empty(),
+                })
+            &&& v.next_slot == u.next_slot
+            &&& v.executed == u.executed
+            &&& net_op.send == Some(Message::AcceptOk { id, sender: c.id })
+        } else {
+            &&& false
+        }
+    }
+
+    pub open spec fn accept_ok(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::AcceptOk { id, sender }) = net_op.recv {
+            &&& id.owner == c.id
+            &&& u.instances.contains_key(id)
+            &&& u.instances[id].status is Accepted
+            &&& v.instances == u.instances.insert(id, Instance {
+                    command: u.instances[id].command,
+                    status: u.instances[id].status,
+                    pre_accept_oks: u.instances[id].pre_accept_oks,
+                    accept_oks: u.instances[id].accept_oks.insert(sender),
+                })
+            &&& v.next_slot == u.next_slot
+            &&& v.executed == u.executed
+            &&& net_op.send.is_none()
+        } else {
+            &&& false
+        }
+    }
+
+    pub open spec fn commit(c: &Constants, u: &Variables, v: &Variables, id: InstanceId, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& id.owner == c.id
+        &&& u.instances.contains_key(id)
+        &&& u.instances[id].status is Accepted
+        &&& are_valid_hosts(u.instances[id].accept_oks, c.num_hosts)
+        &&& c.quorums.is_phase2_quorum(u.instances[id].accept_oks)
+        &&& net_op.recv.is_none()
+        &&& v.instances == u.instances.insert(id, Instance {
+                command: u.instances[id].command,
+                status: Status::Committed,
+                pre_accept_oks: u.instances[id].pre_accept_oks,
+                accept_oks: u.instances[id].accept_oks,
+            })
+        &&& v.next_slot == u.next_slot
+        &&& v.executed == u.executed
+        &&& net_op.send == Some(Message::Commit { id, command: u.instances[id].command })
+    }
+
+    pub open spec fn receive_commit(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::Commit { id, command }) = net_op.recv {
+            &&& id.owner != c.id
+            &&& !(u.instances.contains_key(id) && u.instances[id].status is Committed)
+            &&& v.instances == u.instances.insert(id, Instance {
+                    command,
+                    status: Status::Committed,
+                    pre_accept_oks: Map::empty(),
+                    accept_oks: Set::empty(),
+                })
+            &&& v.next_slot == u.next_slot
+            &&& v.executed == u.executed
+            &&& net_op.send.is_none()
+        } else {
+            &&& false
+        }
+    }
+
+    // A replica executes a committed instance once everything it depends on is committed here and
+    // everything that goes before it (`order::executes_before`) has been executed.
+    pub open spec fn execute(c: &Constants, u: &Variables, v: &Variables, id: InstanceId, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& order::closure_is_committed(u.committed(), id)
+        &&& !u.executed.contains(id)
+        &&& forall |x: InstanceId| x != id && #[trigger] order::executes_before(u.committed(), x, id) ==> u.executed.contains(x)
+        &&& v.instances == u.instances
+        &&& v.next_slot == u.next_slot
+        &&& v.executed == u.executed.push(id)
+        &&& net_op.recv.is_none()
+        &&& net_op.send.is_none()
+    }
+
+    pub open spec fn step(c: &Constants, u: &Variables, v: &Variables, id: InstanceId, net_op: NetworkOperation) -> bool {
+        &&& u.well_formed(c)
+        &&& v.well_formed(c)
+        &&& {
+            ||| exists |value: Value| #[trigger] submit_request(c, u, v, net_op, value)
+            ||| propose(c, u, v, net_op)
+            ||| pre_accept(c, u, v, net_op)
+            ||| pre_accept_ok(c, u, v, net_op)
+            ||| commit_fast(c, u, v, id, net_op)
+            ||| send_accept(c, u, v, id, net_op)
+            ||| accept(c, u, v, net_op)
+            ||| accept_ok(c, u, v, net_op)
+            ||| commit(c, u, v, id, net_op)
+            ||| receive_commit(c, u, v, net_op)
+            ||| execute(c, u, v, id, net_op)
+        }
+    }
+}
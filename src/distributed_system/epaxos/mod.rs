@@ -0,0 +1,927 @@
+use super::{low_level::quorum::{are_valid_hosts, QuorumSystem}, Value};
+use vstd::prelude::*;
+
+verus! {
+    pub mod host;
+    pub mod order;
+
+    // A leaderless (EPaxos-style) protocol next to the Paxos machine in `low_level`. Every replica
+    // owns its own instance space `(replica, slot)` and commits its commands there together with their
+    // dependencies and sequence numbers; replicas then execute committed commands in the order those
+    // attributes give (`order::executes_before`). Only the owner ever proposes in an instance, so
+    // there is no recovery by other replicas and a replica that stops leaves its open instances open.
+    pub enum Message {
+        ClientRequest { value: Value },
+        PreAccept { id: host::InstanceId, command: host::Command },
+        PreAcceptOk { id: host::InstanceId, sender: nat, command: host::Command },
+        Accept { id: host::InstanceId, command: host::Command },
+        AcceptOk { id: host::InstanceId, sender: nat },
+        Commit { id: host::InstanceId, command: host::Command },
+    }
+
+    pub struct NetworkOperation {
+        pub send: Option<Message>,
+        pub recv: Option<Message>,
+    }
+
+    pub struct Constants {
+        pub num_hosts: nat,
+        pub hosts: Seq<host::Constants>,
+        pub quorums: QuorumSystem,
+        pub interferes: spec_fn(Value, Value) -> bool,
+    }
+
+    // Sent messages are never removed, so the network may deliver any of them any number of times and
+    // in any order; a lost message is one that is never delivered.
+    pub struct Variables {
+        pub hosts: Seq<host::Variables>,
+        pub sent_messages: Set<Message>,
+    }
+
+    impl Constants {
+        pub open spec fn well_formed(&self) -> bool {
+            &&& self.num_hosts > 0
+            &&& self.hosts.len() == self.num_hosts
+            &&& self.quorums.well_formed(self.num_hosts)
+            &&& forall |a: Value, b: Value| #[trigger] (self.interferes)(a, b) == (self.interferes)(b, a)
+            &&& forall |i: nat| #![auto]
+                    0 <= i < self.num_hosts ==>
+                    self.hosts[i as int].id == i &&
+                    self.hosts[i as int].num_hosts == self.num_hosts &&
+                    self.hosts[i as int].quorums == self.quorums &&
+                    self.hosts[i as int].interferes == self.interferes
+        }
+
+        pub open spec fn is_quorum(&self, hosts: Set<nat>) -> bool {
+            &&& are_valid_hosts(hosts, self.num_hosts)
+            &&& self.quorums.is_phase1_quorum(hosts)
+            &&& self.quorums.is_phase2_quorum(hosts)
+        }
+    }
+
+    impl Variables {
+        pub open spec fn well_formed(&self, c: &Constants) -> bool {
+            &&& c.well_formed()
+            &&& self.hosts.len() == c.hosts.len()
+            &&& forall |idx: nat| #![auto] 0 <= idx < self.hosts.len() ==> self.hosts[idx as int].well_formed(&c.hosts[idx as int])
+        }
+    }
+
+    pub open spec fn init(c: &Constants, u: &Variables) -> bool {
+        &&& u.well_formed(c)
+        &&& forall |idx: nat| #![auto]
+                0 <= idx < u.hosts.len() ==>
+                host::init(&c.hosts[idx as int], &u.hosts[idx as int], idx, u.hosts.len())
+        &&& u.sent_messages.is_empty()
+    }
+
+    pub open spec fn network_step(u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool {
+        &&& if let Some(message) = net_op.recv { u.sent_messages.contains(message) } else { true }
+        &&& if let Some(message) = net_op.send { v.sent_messages == u.sent_messages.insert(message) } else { v.sent_messages == u.sent_messages }
+    }
+
+    pub open spec fn host_step(c: &Constants, u: &Variables, v: &Variables, host_id: int, id: host::InstanceId, net_op: NetworkOperation) -> bool {
+        &&& u.well_formed(c)
+        &&& v.well_formed(c)
+        &&& 0 <= host_id < u.hosts.len()
+        &&& host::step(&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id], id, net_op)
+        &&& forall |i: int| #![auto] 0 <= i < v.hosts.len() && i != host_id ==> u.hosts[i] == v.hosts[i]
+        &&& network_step(u, v, net_op)
+    }
+
+    pub open spec fn next(c: &Constants, u: &Variables, v: &Variables) -> bool {
+        exists |host_id: int, id: host::InstanceId, net_op: NetworkOperation| #[trigger] host_step(c, u, v, host_id, id, net_op)
+    }
+
+    pub open spec fn message_id(message: Message) -> Option<host::InstanceId> {
+        match message {
+            Message::ClientRequest { .. } => None,
+            Message::PreAccept { id, .. } => Some(id),
+            Message::PreAcceptOk { id, .. } => Some(id),
+            Message::Accept { id, .. } => Some(id),
+            Message::AcceptOk { id, .. } => Some(id),
+            Message::Commit { id, .. } => Some(id),
+        }
+    }
+
+    impl Variables {
+        pub open spec fn was_proposed(&self, id: host::InstanceId) -> bool {
+            exists |command: host::Command| #[trigger] self.sent_messages.contains(Message::PreAccept { id, command })
+        }
+
+        // `sender` computed `command` as its attributes for `id`: in a PreAcceptOk, or as the owner
+        // proposing it.
+        pub open spec fn attested(&self, id: host::InstanceId, sender: nat, command: host::Command) -> bool {
+            ||| self.sent_messages.contains(Message::PreAcceptOk { id, sender, command })
+            ||| sender == id.owner && self.sent_messages.contains(Message::PreAccept { id, command })
+        }
+
+        // `replies` is a quorum of attributes for `id`, all of whose dependencies `command` has.
+        pub open spec fn covered_by(&self, c: &Constants, id: host::InstanceId, command: host::Command, replies: Map<nat, host::Command>) -> bool {
+            &&& c.is_quorum(replies.dom())
+            &&& forall |sender: nat| #[trigger] replies.contains_key(sender) ==> self.attested(id, sender, replies[sender]) && replies[sender].deps.subset_of(command.deps)
+        }
+
+        pub open spec fn covers(&self, c: &Constants, id: host::InstanceId, command: host::Command) -> bool {
+            exists |replies: Map<nat, host::Command>| #[trigger] self.covered_by(c, id, command, replies)
+        }
+
+        // Every message about an instance, and every instance a replica holds, goes back to the
+        // owner's PreAccept, and the owner has used the slot.
+        pub open spec fn instances_were_proposed(&self, c: &Constants) -> bool {
+            &&& forall |message: Message| #[trigger] self.sent_messages.contains(message) && message_id(message).is_some() ==> self.was_proposed(message_id(message).unwrap())
+            &&& forall |i: int, id: host::InstanceId| #![auto] 0 <= i < self.hosts.len() && self.hosts[i].instances.contains_key(id) ==> self.was_proposed(id)
+            &&& forall |id: host::InstanceId, command: host::Command| #[trigger] self.sent_messages.contains(Message::PreAccept { id, command }) ==>
+                    id.owner < c.num_hosts &&
+                    self.hosts[id.owner as int].instances.contains_key(id) &&
+                    id.slot < self.hosts[id.owner as int].next_slot
+        }
+
+        pub open spec fn pre_accepts_are_unique(&self, c: &Constants) -> bool {
+            forall |id: host::InstanceId, c1: host::Command, c2: host::Command|
+                #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: c1 }) &&
+                #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: c2 }) ==>
+                c1 == c2
+        }
+
+        // Attributes change as an instance goes through the protocol; its command's value never does.
+        pub open spec fn commands_keep_proposed_values(&self, c: &Constants) -> bool {
+            &&& forall |id: host::InstanceId, proposal: host::Command, sender: nat, command: host::Command|
+                    #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: proposal }) &&
+                    #[trigger] self.sent_messages.contains(Message::PreAcceptOk { id, sender, command }) ==>
+                    command.value == proposal.value
+            &&& forall |id: host::InstanceId, proposal: host::Command, command: host::Command|
+                    #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: proposal }) &&
+                    #[trigger] self.sent_messages.contains(Message::Accept { id, command }) ==>
+                    command.value == proposal.value
+            &&& forall |id: host::InstanceId, proposal: host::Command, command: host::Command|
+                    #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: proposal }) &&
+                    #[trigger] self.sent_messages.contains(Message::Commit { id, command }) ==>
+                    command.value == proposal.value
+            &&& forall |i: int, id: host::InstanceId, proposal: host::Command|
+                    0 <= i < self.hosts.len() &&
+                    #[trigger] self.hosts[i].instances.contains_key(id) &&
+                    #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: proposal }) ==>
+                    self.hosts[i].instances[id].command.value == proposal.value
+        }
+
+        pub open spec fn replies_come_from_valid_hosts(&self, c: &Constants) -> bool {
+            &&& forall |id: host::InstanceId, sender: nat, command: host::Command| #[trigger] self.sent_messages.contains(Message::PreAcceptOk { id, sender, command }) ==> sender < c.num_hosts
+            &&& forall |id: host::InstanceId, sender: nat| #[trigger] self.sent_messages.contains(Message::AcceptOk { id, sender }) ==> sender < c.num_hosts
+        }
+
+        // A replica keeps every instance it has computed attributes for.
+        pub open spec fn attestations_are_recorded(&self, c: &Constants) -> bool {
+            forall |id: host::InstanceId, sender: nat, command: host::Command|
+                #[trigger] self.attested(id, sender, command) ==>
+                sender < c.num_hosts && self.hosts[sender as int].instances.contains_key(id)
+        }
+
+        // Whichever of two interfering instances a replica saw second, its attributes for that one
+        // depend on the first.
+        pub open spec fn attestations_are_dependent(&self, c: &Constants) -> bool {
+            forall |a: host::InstanceId, b: host::InstanceId, sender: nat, ca: host::Command, cb: host::Command|
+                #![trigger self.attested(a, sender, ca), self.attested(b, sender, cb)]
+                a != b &&
+                self.attested(a, sender, ca) &&
+                self.attested(b, sender, cb) &&
+                (c.interferes)(ca.value, cb.value) ==>
+                ca.deps.contains(b) || cb.deps.contains(a)
+        }
+
+        // The owner only counts replies that were sent, and still proposes what it sent in PreAccept
+        // until it moves past the pre-accept phase.
+        pub open spec fn owner_bookkeeping_is_attested(&self, c: &Constants) -> bool {
+            forall |i: int, id: host::InstanceId| #![auto]
+                0 <= i < self.hosts.len() &&
+                id.owner == i &&
+                self.hosts[i].instances.contains_key(id) ==>
+                are_valid_hosts(self.hosts[i].instances[id].pre_accept_oks.dom(), c.num_hosts) &&
+                are_valid_hosts(self.hosts[i].instances[id].accept_oks, c.num_hosts) &&
+                (forall |sender: nat| #[trigger] self.hosts[i].instances[id].pre_accept_oks.contains_key(sender) ==>
+                    self.attested(id, sender, self.hosts[i].instances[id].pre_accept_oks[sender])) &&
+                (self.hosts[i].instances[id].status is PreAccepted ==>
+                    self.sent_messages.contains(Message::PreAccept { id, command: self.hosts[i].instances[id].command }))
+        }
+
+        // Past the pre-accept phase, the owner's attributes include those of a quorum of replies.
+        pub open spec fn owner_attributes_cover_a_quorum(&self, c: &Constants) -> bool {
+            forall |i: int, id: host::InstanceId| #![auto]
+                0 <= i < self.hosts.len() &&
+                id.owner == i &&
+                self.hosts[i].instances.contains_key(id) &&
+                !(self.hosts[i].instances[id].status is PreAccepted) ==>
+                self.covers(c, id, self.hosts[i].instances[id].command)
+        }
+
+        // Only the owner commits, once, and then never touches the instance again.
+        pub open spec fn commits_come_from_owners(&self, c: &Constants) -> bool {
+            forall |id: host::InstanceId, command: host::Command|
+                #[trigger] self.sent_messages.contains(Message::Commit { id, command }) ==>
+                id.owner < c.num_hosts &&
+                self.hosts[id.owner as int].instances.contains_key(id) &&
+                self.hosts[id.owner as int].instances[id].status is Committed &&
+                self.hosts[id.owner as int].instances[id].command == command
+        }
+
+        pub open spec fn committed_instances_were_sent(&self, c: &Constants) -> bool {
+            forall |i: int, id: host::InstanceId| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(id) &&
+                self.hosts[i].instances[id].status is Committed ==>
+                self.sent_messages.contains(Message::Commit { id, command: self.hosts[i].instances[id].command })
+        }
+
+        // Every replica has executed each instance once, after its whole closure was committed and
+        // after everything that goes before it.
+        pub open spec fn executions_are_ordered(&self, c: &Constants) -> bool {
+            &&& forall |i: int| #![auto] 0 <= i < self.hosts.len() ==> self.hosts[i].executed.no_duplicates()
+            &&& forall |i: int, k: int| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    0 <= k < self.hosts[i].executed.len() ==>
+                    order::closure_is_committed(self.hosts[i].committed(), self.hosts[i].executed[k])
+            &&& forall |i: int, k: int, x: host::InstanceId|
+                    0 <= i < self.hosts.len() &&
+                    0 <= k < self.hosts[i].executed.len() &&
+                    x != self.hosts[i].executed[k] &&
+                    #[trigger] order::executes_before(self.hosts[i].committed(), x, self.hosts[i].executed[k]) ==>
+                    exists |earlier: int| 0 <= earlier < k && #[trigger] self.hosts[i].executed[earlier] == x
+        }
+    }
+
+    pub open spec fn inductive(c: &Constants, u: &Variables) -> bool {
+        &&& u.well_formed(c)
+        &&& u.instances_were_proposed(c)
+        &&& u.pre_accepts_are_unique(c)
+        &&& u.commands_keep_proposed_values(c)
+        &&& u.replies_come_from_valid_hosts(c)
+        &&& u.attestations_are_recorded(c)
+        &&& u.attestations_are_dependent(c)
+        &&& u.owner_bookkeeping_is_attested(c)
+        &&& u.owner_attributes_cover_a_quorum(c)
+        &&& u.commits_come_from_owners(c)
+        &&& u.committed_instances_were_sent(c)
+        &&& u.executions_are_ordered(c)
+    }
+
+    // Every command any replica has committed, as its owner committed it.
+    pub open spec fn committed_commands(u: &Variables) -> Map<host::InstanceId, host::Command> {
+        Map::new(
+            |id: host::InstanceId| exists |command: host::Command| #[trigger] u.sent_messages.contains(Message::Commit { id, command }),
+            |id: host::InstanceId| choose |command: host::Command| #[trigger] u.sent_messages.contains(Message::Commit { id, command }),
+        )
+    }
+
+    pub proof fn init_implies_inductive(c: &Constants, u: &Variables)
+    requires
+        init(c, u),
+    ensures
+        inductive(c, u),
+    {
+        assert forall |i: int, id: host::InstanceId| #![auto] 0 <= i < u.hosts.len() implies !u.hosts[i].instances.contains_key(id) by {
+            assert(host::init(&c.hosts[i], &u.hosts[i], i as nat, u.hosts.len()));
+        };
+        assert forall |i: int| #![auto] 0 <= i < u.hosts.len() implies u.hosts[i].executed.len() == 0 by {
+            assert(host::init(&c.hosts[i], &u.hosts[i], i as nat, u.hosts.len()));
+        };
+    }
+
+    // What one host step does to the state every invariant talks about: the network only gains the
+    // message the step sends, other hosts stay put, and the stepping host keeps every instance it had
+    // and never touches one it has committed.
+    pub proof fn host_step_facts(c: &Constants, u: &Variables, v: &Variables, host_id: int, id: host::InstanceId, net_op: NetworkOperation)
+    requires
+        inductive(c, u),
+        host_step(c, u, v, host_id, id, net_op),
+    ensures
+        u.sent_messages.subset_of(v.sent_messages),
+        forall |message: Message| #[trigger] v.sent_messages.contains(message) && !u.sent_messages.contains(message) ==> net_op.send == Some(message),
+        forall |i: int| #![auto] 0 <= i < v.hosts.len() && i != host_id ==> v.hosts[i] == u.hosts[i],
+        u.hosts[host_id].instances.dom().subset_of(v.hosts[host_id].instances.dom()),
+        u.hosts[host_id].next_slot <= v.hosts[host_id].next_slot,
+        forall |x: host::InstanceId| #![auto]
+            u.hosts[host_id].instances.contains_key(x) &&
+            u.hosts[host_id].instances[x].status is Committed ==>
+            v.hosts[host_id].instances[x] == u.hosts[host_id].instances[x],
+        u.hosts[host_id].committed().submap_of(v.hosts[host_id].committed()),
+        forall |i: int| #![auto] 0 <= i < v.hosts.len() ==> u.hosts[i].committed().submap_of(v.hosts[i].committed()),
+    {
+        let (hc, hu, hv) = (&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id]);
+        if (host::propose(hc, hu, hv, net_op)) {
+            // The new slot is unused: no replica holds it, since nothing about it was proposed yet.
+            let new_id = host::InstanceId { owner: hc.id, slot: hu.next_slot };
+            if (hu.instances.contains_key(new_id)) {
+                assert(u.was_proposed(new_id));
+                let command = choose |command: host::Command| #[trigger] u.sent_messages.contains(Message::PreAccept { id: new_id, command });
+                assert(new_id.slot < u.hosts[host_id].next_slot);
+            }
+        }
+        assert forall |x: host::InstanceId| #![auto]
+            hu.instances.contains_key(x) &&
+            hu.instances[x].status is Committed implies
+            hv.instances[x] == hu.instances[x]
+        by {
+            if (host::propose(hc, hu, hv, net_op)) {
+                let new_id = host::InstanceId { owner: hc.id, slot: hu.next_slot };
+                assert(x != new_id);
+            }
+        };
+        assert(hu.committed().submap_of(hv.committed()));
+        assert forall |i: int| #![auto] 0 <= i < v.hosts.len() implies u.hosts[i].committed().submap_of(v.hosts[i].committed()) by {
+            if (i != host_id) { assert(v.hosts[i] == u.hosts[i]); }
+        };
+    }
+
+    impl Variables {
+        pub proof fn attested_is_monotonic(&self, c: &Constants, u: &Variables, id: host::InstanceId, sender: nat, command: host::Command)
+        requires
+            u.sent_messages.subset_of(self.sent_messages),
+            u.attested(id, sender, command),
+        ensures
+            self.attested(id, sender, command),
+        {
+        }
+
+        pub proof fn covers_is_monotonic(&self, c: &Constants, u: &Variables, id: host::InstanceId, command: host::Command)
+        requires
+            u.sent_messages.subset_of(self.sent_messages),
+            u.covers(c, id, command),
+        ensures
+            self.covers(c, id, command),
+        {
+            let replies = choose |replies: Map<nat, host::Command>| #[trigger] u.covered_by(c, id, command, replies);
+            assert forall |sender: nat| #[trigger] replies.contains_key(sender) implies self.attested(id, sender, replies[sender]) && replies[sender].deps.subset_of(command.deps) by {
+                self.attested_is_monotonic(c, u, id, sender, replies[sender]);
+            };
+            assert(self.covered_by(c, id, command, replies));
+        }
+
+        // A new attestation comes from the stepping host, which now holds the instance, and depends on
+        // every interfering instance that host held before.
+        pub proof fn new_attestation_is_from_stepping_host(&self, c: &Constants, u: &Variables, host_id: int, step_id: host::InstanceId, net_op: NetworkOperation, id: host::InstanceId, sender: nat, command: host::Command)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, step_id, net_op),
+            self.attested(id, sender, command),
+            !u.attested(id, sender, command),
+        ensures
+            sender == host_id,
+            self.hosts[host_id].instances.contains_key(id),
+            !u.hosts[host_id].instances.contains_key(id),
+            host::interfering_instances(&c.hosts[host_id], &u.hosts[host_id], id, command.value).subset_of(command.deps),
+            net_op.send == Some(Message::PreAcceptOk { id, sender, command }) || net_op.send == Some(Message::PreAccept { id, command }),
+        {
+            host_step_facts(c, u, self, host_id, step_id, net_op);
+            let (hc, hu, hv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            if (self.sent_messages.contains(Message::PreAcceptOk { id, sender, command }) && !u.sent_messages.contains(Message::PreAcceptOk { id, sender, command })) {
+                assert(net_op.send == Some(Message::PreAcceptOk { id, sender, command }));
+                assert(host::pre_accept(hc, hu, hv, net_op));
+            } else {
+                assert(net_op.send == Some(Message::PreAccept { id, command }));
+                assert(host::propose(hc, hu, hv, net_op));
+                if (hu.instances.contains_key(id)) {
+                    assert(u.was_proposed(id));
+                    let proposal = choose |proposal: host::Command| #[trigger] u.sent_messages.contains(Message::PreAccept { id, command: proposal });
+                }
+            }
+        }
+
+        pub proof fn instances_were_proposed_is_inductive(&self, c: &Constants, u: &Variables)
+        requires
+            inductive(c, u),
+            next(c, u, self),
+        ensures
+            self.instances_were_proposed(c),
+            self.pre_accepts_are_unique(c),
+        {
+            let (host_id, step_id, net_op) = choose |host_id: int, id: host::InstanceId, net_op: NetworkOperation| #[trigger] host_step(c, u, self, host_id, id, net_op);
+            let (hc, hu, hv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, step_id, net_op);
+
+            assert forall |id: host::InstanceId| #[trigger] u.was_proposed(id) implies self.was_proposed(id) by {
+                let command = choose |command: host::Command| #[trigger] u.sent_messages.contains(Message::PreAccept { id, command });
+                assert(self.sent_messages.contains(Message::PreAccept { id, command }));
+            };
+
+            assert forall |message: Message| #[trigger] self.sent_messages.contains(message) && message_id(message).is_some() implies self.was_proposed(message_id(message).unwrap()) by {
+                if (!u.sent_messages.contains(message)) {
+                    match message {
+                        Message::PreAccept { id, command } => {},
+                        Message::PreAcceptOk { id, .. } => {
+                            assert(host::pre_accept(hc, hu, hv, net_op));
+                            assert(u.sent_messages.contains(net_op.recv.unwrap()));
+                        },
+                        Message::AcceptOk { id, .. } => {
+                            assert(host::accept(hc, hu, hv, net_op));
+                            assert(u.sent_messages.contains(net_op.recv.unwrap()));
+                        },
+                        Message::Accept { id, .. } => { assert(hu.instances.contains_key(id)); },
+                        Message::Commit { id, .. } => { assert(hu.instances.contains_key(id)); },
+                        Message::ClientRequest { .. } => {},
+                    }
+                }
+            };
+
+            assert forall |i: int, id: host::InstanceId| #![auto] 0 <= i < self.hosts.len() && self.hosts[i].instances.contains_key(id) implies self.was_proposed(id) by {
+                if (i != host_id || hu.instances.contains_key(id)) {
+                    assert(u.was_proposed(id));
+                } else if (host::propose(hc, hu, hv, net_op)) {
+                    assert(self.sent_messages.contains(net_op.send.unwrap()));
+                } else {
+                    // `pre_accept`, `accept` and `receive_commit` create it from a message about it.
+                    assert(u.sent_messages.contains(net_op.recv.unwrap()));
+                    assert(message_id(net_op.recv.unwrap()) == Some(id));
+                }
+            };
+
+            assert forall |id: host::InstanceId, command: host::Command| #[trigger] self.sent_messages.contains(Message::PreAccept { id, command }) implies
+                id.owner < c.num_hosts &&
+                self.hosts[id.owner as int].instances.contains_key(id) &&
+                id.slot < self.hosts[id.owner as int].next_slot
+            by {
+                if (!u.sent_messages.contains(Message::PreAccept { id, command })) {
+                    assert(host::propose(hc, hu, hv, net_op));
+                    assert(id.owner == host_id);
+                }
+            };
+
+            assert forall |id: host::InstanceId, c1: host::Command, c2: host::Command|
+                #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: c1 }) &&
+                #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: c2 }) implies
+                c1 == c2
+            by {
+                // A new PreAccept is for the proposer's next slot, which no earlier one used.
+                if (!u.sent_messages.contains(Message::PreAccept { id, command: c1 })) {
+                    assert(host::propose(hc, hu, hv, net_op) && id.slot == hu.next_slot);
+                    if (u.sent_messages.contains(Message::PreAccept { id, command: c2 })) { assert(id.slot < hu.next_slot); }
+                } else if (!u.sent_messages.contains(Message::PreAccept { id, command: c2 })) {
+                    assert(host::propose(hc, hu, hv, net_op) && id.slot == hu.next_slot);
+                    assert(id.slot < hu.next_slot);
+                }
+            };
+        }
+
+        pub proof fn commands_keep_proposed_values_is_inductive(&self, c: &Constants, u: &Variables)
+        requires
+            inductive(c, u),
+            next(c, u, self),
+        ensures
+            self.commands_keep_proposed_values(c),
+        {
+            let (host_id, step_id, net_op) = choose |host_id: int, id: host::InstanceId, net_op: NetworkOperation| #[trigger] host_step(c, u, self, host_id, id, net_op);
+            let (hc, hu, hv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, step_id, net_op);
+            self.instances_were_proposed_is_inductive(c, u);
+
+            // A proposal `self` has for `id` is the one `u` has, unless this step proposed `id`, in
+            // which case nothing else about `id` was sent or held before.
+            assert forall |id: host::InstanceId, proposal: host::Command|
+                #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: proposal }) &&
+                !u.sent_messages.contains(Message::PreAccept { id, command: proposal }) implies
+                !u.was_proposed(id) && host::propose(hc, hu, hv, net_op) && id.owner == host_id && hv.instances[id].command == proposal
+            by {
+                assert(host::propose(hc, hu, hv, net_op));
+                if (u.was_proposed(id)) {
+                    let other = choose |command: host::Command| #[trigger] u.sent_messages.contains(Message::PreAccept { id, command });
+                    assert(id.slot < hu.next_slot);
+                }
+            };
+
+            assert forall |id: host::InstanceId, proposal: host::Command, sender: nat, command: host::Command|
+                #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: proposal }) &&
+                #[trigger] self.sent_messages.contains(Message::PreAcceptOk { id, sender, command }) implies
+                command.value == proposal.value
+            by {
+                if (!u.sent_messages.contains(Message::PreAccept { id, command: proposal })) {
+                    assert(!u.sent_messages.contains(Message::PreAcceptOk { id, sender, command }));
+                } else if (!u.sent_messages.contains(Message::PreAcceptOk { id, sender, command })) {
+                    assert(host::pre_accept(hc, hu, hv, net_op));
+                    let Message::PreAccept { id: _, command: received } = net_op.recv.unwrap() else { assert(false); return; };
+                    assert(u.sent_messages.contains(Message::PreAccept { id, command: received }));
+                    assert(received == proposal);
+                }
+            };
+
+            assert forall |id: host::InstanceId, proposal: host::Command, command: host::Command|
+                #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: proposal }) &&
+                #[trigger] self.sent_messages.contains(Message::Accept { id, command }) implies
+                command.value == proposal.value
+            by {
+                if (!u.sent_messages.contains(Message::PreAccept { id, command: proposal })) {
+                    assert(!u.sent_messages.contains(Message::Accept { id, command }));
+                } else if (!u.sent_messages.contains(Message::Accept { id, command })) {
+                    assert(host::send_accept(hc, hu, hv, step_id, net_op));
+                    assert(hu.instances.contains_key(id));
+                }
+            };
+
+            assert forall |id: host::InstanceId, proposal: host::Command, command: host::Command|
+                #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: proposal }) &&
+                #[trigger] self.sent_messages.contains(Message::Commit { id, command }) implies
+                command.value == proposal.value
+            by {
+                if (!u.sent_messages.contains(Message::PreAccept { id, command: proposal })) {
+                    assert(!u.sent_messages.contains(Message::Commit { id, command }));
+                } else if (!u.sent_messages.contains(Message::Commit { id, command })) {
+                    assert(host::commit_fast(hc, hu, hv, step_id, net_op) || host::commit(hc, hu, hv, step_id, net_op));
+                    assert(hu.instances.contains_key(id));
+                }
+            };
+
+            assert forall |i: int, id: host::InstanceId, proposal: host::Command|
+                0 <= i < self.hosts.len() &&
+                #[trigger] self.hosts[i].instances.contains_key(id) &&
+                #[trigger] self.sent_messages.contains(Message::PreAccept { id, command: proposal }) implies
+                self.hosts[i].instances[id].command.value == proposal.value
+            by {
+                if (!u.sent_messages.contains(Message::PreAccept { id, command: proposal })) {
+                    // Only the proposer holds the new instance, with the proposal itself.
+                    if (i != host_id || hu.instances.contains_key(id)) { assert(u.was_proposed(id)); }
+                } else if (i == host_id && (!hu.instances.contains_key(id) || hv.instances[id] != hu.instances[id])) {
+                    if (host::pre_accept(hc, hu, hv, net_op)) {
+                        let Message::PreAccept { id: _, command: received } = net_op.recv.unwrap() else { assert(false); return; };
+                        assert(u.sent_messages.contains(Message::PreAccept { id, command: received }));
+                    } else if (host::accept(hc, hu, hv, net_op) || host::receive_commit(hc, hu, hv, net_op)) {
+                        assert(u.sent_messages.contains(net_op.recv.unwrap()));
+                    } else if (host::propose(hc, hu, hv, net_op)) {
+                        assert(id == step_id || hu.instances.contains_key(id));
+                    }
+                }
+            };
+        }
+
+        pub proof fn replies_come_from_valid_hosts_is_inductive(&self, c: &Constants, u: &Variables)
+        requires
+            inductive(c, u),
+            next(c, u, self),
+        ensures
+            self.replies_come_from_valid_hosts(c),
+        {
+            let (host_id, step_id, net_op) = choose |host_id: int, id: host::InstanceId, net_op: NetworkOperation| #[trigger] host_step(c, u, self, host_id, id, net_op);
+            host_step_facts(c, u, self, host_id, step_id, net_op);
+            assert(c.hosts[host_id].id == host_id);
+        }
+
+        pub proof fn attestations_are_recorded_is_inductive(&self, c: &Constants, u: &Variables)
+        requires
+            inductive(c, u),
+            next(c, u, self),
+        ensures
+            self.attestations_are_recorded(c),
+            self.attestations_are_dependent(c),
+        {
+            let (host_id, step_id, net_op) = choose |host_id: int, id: host::InstanceId, net_op: NetworkOperation| #[trigger] host_step(c, u, self, host_id, id, net_op);
+            host_step_facts(c, u, self, host_id, step_id, net_op);
+            self.instances_were_proposed_is_inductive(c, u);
+            self.commands_keep_proposed_values_is_inductive(c, u);
+
+            assert forall |id: host::InstanceId, sender: nat, command: host::Command|
+                #[trigger] self.attested(id, sender, command) implies
+                sender < c.num_hosts && self.hosts[sender as int].instances.contains_key(id)
+            by {
+                if (!u.attested(id, sender, command)) {
+                    self.new_attestation_is_from_stepping_host(c, u, host_id, step_id, net_op, id, sender, command);
+                } else if (sender != host_id) {
+                    assert(self.hosts[sender as int] == u.hosts[sender as int]);
+                }
+            };
+
+            // The step sends at most one attestation. If it is `b`'s, the sender held `a` before, with
+            // the same value, so `b`'s attributes depend on `a`.
+            assert forall |a: host::InstanceId, b: host::InstanceId, sender: nat, ca: host::Command, cb: host::Command|
+                #![trigger self.attested(a, sender, ca), self.attested(b, sender, cb)]
+                a != b &&
+                self.attested(a, sender, ca) &&
+                self.attested(b, sender, cb) &&
+                (c.interferes)(ca.value, cb.value) implies
+                ca.deps.contains(b) || cb.deps.contains(a)
+            by {
+                if (u.attested(a, sender, ca) && u.attested(b, sender, cb)) {
+                } else if (!u.attested(b, sender, cb)) {
+                    self.new_attestation_is_from_stepping_host(c, u, host_id, step_id, net_op, b, sender, cb);
+                    if (!u.attested(a, sender, ca)) {
+                        self.new_attestation_is_from_stepping_host(c, u, host_id, step_id, net_op, a, sender, ca);
+                    }
+                    self.held_attestation_interferes(c, u, host_id, a, sender, ca, b, cb);
+                } else {
+                    self.new_attestation_is_from_stepping_host(c, u, host_id, step_id, net_op, a, sender, ca);
+                    assert((c.interferes)(cb.value, ca.value));
+                    self.held_attestation_interferes(c, u, host_id, b, sender, cb, a, ca);
+                }
+            };
+        }
+
+        // `u` has `sender`'s attestation of `a`, and `sender` is about to attest `b`: `a` is among the
+        // instances it holds that interfere with `b`.
+        pub proof fn held_attestation_interferes(&self, c: &Constants, u: &Variables, host_id: int, a: host::InstanceId, sender: nat, ca: host::Command, b: host::InstanceId, cb: host::Command)
+        requires
+            inductive(c, u),
+            0 <= host_id < u.hosts.len(),
+            sender == host_id,
+            u.attested(a, sender, ca),
+            a != b,
+            (c.interferes)(ca.value, cb.value),
+        ensures
+            host::interfering_instances(&c.hosts[host_id], &u.hosts[host_id], b, cb.value).contains(a),
+        {
+            assert(u.hosts[host_id].instances.contains_key(a));
+            assert(u.was_proposed(a));
+            let proposal = choose |command: host::Command| #[trigger] u.sent_messages.contains(Message::PreAccept { id: a, command });
+            assert(u.hosts[host_id].instances[a].command.value == proposal.value);
+            if (u.sent_messages.contains(Message::PreAcceptOk { id: a, sender, command: ca })) {
+                assert(ca.value == proposal.value);
+            } else {
+                assert(ca == proposal);
+            }
+            assert(c.hosts[host_id].interferes == c.interferes);
+        }
+
+        pub proof fn owner_bookkeeping_is_attested_is_inductive(&self, c: &Constants, u: &Variables)
+        requires
+            inductive(c, u),
+            next(c, u, self),
+        ensures
+            self.owner_bookkeeping_is_attested(c),
+            self.owner_attributes_cover_a_quorum(c),
+        {
+            let (host_id, step_id, net_op) = choose |host_id: int, id: host::InstanceId, net_op: NetworkOperation| #[trigger] host_step(c, u, self, host_id, id, net_op);
+            let (hc, hu, hv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, step_id, net_op);
+            self.replies_come_from_valid_hosts_is_inductive(c, u);
+
+            assert forall |i: int, id: host::InstanceId| #![auto]
+                0 <= i < self.hosts.len() &&
+                id.owner == i &&
+                self.hosts[i].instances.contains_key(id) implies
+                are_valid_hosts(self.hosts[i].instances[id].pre_accept_oks.dom(), c.num_hosts) &&
+                are_valid_hosts(self.hosts[i].instances[id].accept_oks, c.num_hosts) &&
+                (forall |sender: nat| #[trigger] self.hosts[i].instances[id].pre_accept_oks.contains_key(sender) ==>
+                    self.attested(id, sender, self.hosts[i].instances[id].pre_accept_oks[sender])) &&
+                (self.hosts[i].instances[id].status is PreAccepted ==>
+                    self.sent_messages.contains(Message::PreAccept { id, command: self.hosts[i].instances[id].command })) &&
+                (!(self.hosts[i].instances[id].status is PreAccepted) ==> self.covers(c, id, self.hosts[i].instances[id].command))
+            by {
+                let (old, new) = (u.hosts[i].instances[id], self.hosts[i].instances[id]);
+                if (i != host_id || (hu.instances.contains_key(id) && new == old)) {
+                    assert forall |sender: nat| #[trigger] new.pre_accept_oks.contains_key(sender) implies self.attested(id, sender, new.pre_accept_oks[sender]) by {
+                        self.attested_is_monotonic(c, u, id, sender, old.pre_accept_oks[sender]);
+                    };
+                    if (!(new.status is PreAccepted)) { self.covers_is_monotonic(c, u, id, old.command); }
+                } else if (host::propose(hc, hu, hv, net_op)) {
+                    assert(new.pre_accept_oks.dom() =~= set![hc.id]);
+                    assert(self.sent_messages.contains(net_op.send.unwrap()));
+                } else if (host::pre_accept_ok(hc, hu, hv, net_op)) {
+                    let Message::PreAcceptOk { id: _, sender: from, command: reply } = net_op.recv.unwrap() else { assert(false); return; };
+                    assert(u.sent_messages.contains(Message::PreAcceptOk { id, sender: from, command: reply }));
+                    assert(new.pre_accept_oks.dom() =~= old.pre_accept_oks.dom().insert(from));
+                    assert forall |sender: nat| #[trigger] new.pre_accept_oks.contains_key(sender) implies self.attested(id, sender, new.pre_accept_oks[sender]) by {
+                        if (sender != from) { self.attested_is_monotonic(c, u, id, sender, old.pre_accept_oks[sender]); }
+                    };
+                } else if (host::accept_ok(hc, hu, hv, net_op)) {
+                    assert(u.sent_messages.contains(net_op.recv.unwrap()));
+                    assert forall |sender: nat| #[trigger] new.pre_accept_oks.contains_key(sender) implies self.attested(id, sender, new.pre_accept_oks[sender]) by {
+                        self.attested_is_monotonic(c, u, id, sender, old.pre_accept_oks[sender]);
+                    };
+                    self.covers_is_monotonic(c, u, id, old.command);
+                } else {
+                    // The owner moves past the pre-accept phase: its replies are a quorum, and the
+                    // attributes it takes include every one of them.
+                    assert(host::send_accept(hc, hu, hv, step_id, net_op) || host::commit_fast(hc, hu, hv, step_id, net_op) || host::commit(hc, hu, hv, step_id, net_op));
+                    assert forall |sender: nat| #[trigger] new.pre_accept_oks.contains_key(sender) implies self.attested(id, sender, new.pre_accept_oks[sender]) by {
+                        self.attested_is_monotonic(c, u, id, sender, old.pre_accept_oks[sender]);
+                    };
+                    if (host::commit(hc, hu, hv, step_id, net_op)) {
+                        self.covers_is_monotonic(c, u, id, old.command);
+                    } else {
+                        let replies = old.pre_accept_oks;
+                        assert(hc.is_quorum(replies.dom()));
+                        assert forall |sender: nat| #[trigger] replies.contains_key(sender) implies replies[sender].deps.subset_of(new.command.deps) by {
+                            if (host::send_accept(hc, hu, hv, step_id, net_op)) {
+                                assert forall |x: host::InstanceId| replies[sender].deps.contains(x) implies #[trigger] new.command.deps.contains(x) by {
+                                    assert(host::union_of_deps(replies).contains(x));
+                                };
+                            }
+                        };
+                        if (host::send_accept(hc, hu, hv, step_id, net_op)) { assert(new.accept_oks =~= set![hc.id]); }
+                        assert(self.covered_by(c, id, new.command, replies));
+                    }
+                }
+            };
+        }
+
+        pub proof fn commits_come_from_owners_is_inductive(&self, c: &Constants, u: &Variables)
+        requires
+            inductive(c, u),
+            next(c, u, self),
+        ensures
+            self.commits_come_from_owners(c),
+            self.committed_instances_were_sent(c),
+        {
+            let (host_id, step_id, net_op) = choose |host_id: int, id: host::InstanceId, net_op: NetworkOperation| #[trigger] host_step(c, u, self, host_id, id, net_op);
+            let (hc, hu, hv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, step_id, net_op);
+
+            assert forall |id: host::InstanceId, command: host::Command|
+                #[trigger] self.sent_messages.contains(Message::Commit { id, command }) implies
+                id.owner < c.num_hosts &&
+                self.hosts[id.owner as int].instances.contains_key(id) &&
+                self.hosts[id.owner as int].instances[id].status is Committed &&
+                self.hosts[id.owner as int].instances[id].command == command
+            by {
+                if (!u.sent_messages.contains(Message::Commit { id, command })) {
+                    assert(host::commit_fast(hc, hu, hv, step_id, net_op) || host::commit(hc, hu, hv, step_id, net_op));
+                    assert(id.owner == host_id);
+                } else if (id.owner != host_id) {
+                    assert(self.hosts[id.owner as int] == u.hosts[id.owner as int]);
+                }
+            };
+
+            assert forall |i: int, id: host::InstanceId| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(id) &&
+                self.hosts[i].instances[id].status is Committed implies
+                self.sent_messages.contains(Message::Commit { id, command: self.hosts[i].instances[id].command })
+            by {
+                if (i == host_id && !(hu.instances.contains_key(id) && hv.instances[id] == hu.instances[id])) {
+                    if (host::receive_commit(hc, hu, hv, net_op)) {
+                        assert(u.sent_messages.contains(net_op.recv.unwrap()));
+                    } else {
+                        assert(host::commit_fast(hc, hu, hv, step_id, net_op) || host::commit(hc, hu, hv, step_id, net_op));
+                    }
+                }
+            };
+        }
+
+        pub proof fn executions_are_ordered_is_inductive(&self, c: &Constants, u: &Variables)
+        requires
+            inductive(c, u),
+            next(c, u, self),
+        ensures
+            self.executions_are_ordered(c),
+        {
+            let (host_id, step_id, net_op) = choose |host_id: int, id: host::InstanceId, net_op: NetworkOperation| #[trigger] host_step(c, u, self, host_id, id, net_op);
+            let (hc, hu, hv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, step_id, net_op);
+
+            if (host::execute(hc, hu, hv, step_id, net_op)) {
+                assert(hv.committed() =~= hu.committed());
+            } else {
+                assert(hv.executed == hu.executed);
+            }
+
+            assert forall |i: int, k: int| #![auto]
+                0 <= i < self.hosts.len() &&
+                0 <= k < self.hosts[i].executed.len() implies
+                order::closure_is_committed(self.hosts[i].committed(), self.hosts[i].executed[k])
+            by {
+                if (k < u.hosts[i].executed.len()) {
+                    assert(self.hosts[i].executed[k] == u.hosts[i].executed[k]);
+                    order::closure_is_stable(u.hosts[i].committed(), self.hosts[i].committed(), u.hosts[i].executed[k]);
+                }
+            };
+
+            assert forall |i: int, k: int, x: host::InstanceId|
+                0 <= i < self.hosts.len() &&
+                0 <= k < self.hosts[i].executed.len() &&
+                x != self.hosts[i].executed[k] &&
+                #[trigger] order::executes_before(self.hosts[i].committed(), x, self.hosts[i].executed[k]) implies
+                exists |earlier: int| 0 <= earlier < k && #[trigger] self.hosts[i].executed[earlier] == x
+            by {
+                let executed = u.hosts[i].executed;
+                if (k < executed.len()) {
+                    assert(self.hosts[i].executed[k] == executed[k]);
+                    order::executes_before_is_stable(u.hosts[i].committed(), self.hosts[i].committed(), x, executed[k]);
+                    let earlier = choose |earlier: int| 0 <= earlier < k && #[trigger] executed[earlier] == x;
+                    assert(self.hosts[i].executed[earlier] == x);
+                } else {
+                    // The instance just executed: everything before it was executed already.
+                    assert(i == host_id && host::execute(hc, hu, hv, step_id, net_op));
+                    assert(executed.contains(x));
+                    let earlier = choose |earlier: int| 0 <= earlier < executed.len() && executed[earlier] == x;
+                    assert(self.hosts[i].executed[earlier] == x);
+                }
+            };
+        }
+    }
+
+    pub proof fn inductive_next(c: &Constants, u: &Variables, v: &Variables)
+    requires
+        inductive(c, u),
+        next(c, u, v),
+    ensures
+        inductive(c, v),
+    {
+        v.instances_were_proposed_is_inductive(c, u);
+        v.commands_keep_proposed_values_is_inductive(c, u);
+        v.replies_come_from_valid_hosts_is_inductive(c, u);
+        v.attestations_are_recorded_is_inductive(c, u);
+        v.owner_bookkeeping_is_attested_is_inductive(c, u);
+        v.commits_come_from_owners_is_inductive(c, u);
+        v.executions_are_ordered_is_inductive(c, u);
+    }
+
+    // Every replica's committed commands are the ones their owners committed.
+    pub proof fn host_commits_are_committed_commands(c: &Constants, u: &Variables, i: int)
+    requires
+        inductive(c, u),
+        0 <= i < u.hosts.len(),
+    ensures
+        u.hosts[i].committed().submap_of(committed_commands(u)),
+    {
+        assert forall |id: host::InstanceId| #[trigger] u.hosts[i].committed().contains_key(id) implies
+            committed_commands(u).contains_key(id) && committed_commands(u)[id] == u.hosts[i].committed()[id]
+        by {
+            let command = u.hosts[i].instances[id].command;
+            assert(u.sent_messages.contains(Message::Commit { id, command }));
+            let chosen = committed_commands(u)[id];
+            assert(u.sent_messages.contains(Message::Commit { id, command: chosen }));
+        };
+    }
+
+    // Two committed instances whose commands interfere: their owners' reply quorums share a replica,
+    // which attested one after the other, and each owner committed a superset of the attributes it
+    // was sent.
+    pub proof fn committed_commands_are_dependent(c: &Constants, u: &Variables)
+    requires
+        inductive(c, u),
+    ensures
+        order::interfering_commands_are_dependent(committed_commands(u), c.interferes),
+    {
+        let committed = committed_commands(u);
+        assert forall |a: host::InstanceId, b: host::InstanceId|
+            a != b &&
+            committed.contains_key(a) &&
+            committed.contains_key(b) &&
+            #[trigger] (c.interferes)(committed[a].value, committed[b].value) implies
+            committed[a].deps.contains(b) || committed[b].deps.contains(a)
+        by {
+            let (ca, cb) = (committed[a], committed[b]);
+            assert(u.sent_messages.contains(Message::Commit { id: a, command: ca }));
+            assert(u.sent_messages.contains(Message::Commit { id: b, command: cb }));
+            let (owner_a, owner_b) = (a.owner as int, b.owner as int);
+            assert(u.covers(c, a, u.hosts[owner_a].instances[a].command));
+            assert(u.covers(c, b, u.hosts[owner_b].instances[b].command));
+            let replies_a = choose |replies: Map<nat, host::Command>| #[trigger] u.covered_by(c, a, ca, replies);
+            let replies_b = choose |replies: Map<nat, host::Command>| #[trigger] u.covered_by(c, b, cb, replies);
+
+            assert(c.quorums.quorums_intersect(c.num_hosts));
+            assert(c.quorums.is_phase1_quorum(replies_a.dom()) && c.quorums.is_phase2_quorum(replies_b.dom()));
+            let sender = choose |h: nat| #![auto] replies_a.dom().contains(h) && replies_b.dom().contains(h);
+            let (ra, rb) = (replies_a[sender], replies_b[sender]);
+            assert(u.attested(a, sender, ra) && u.attested(b, sender, rb));
+
+            // Both replies carry their instance's value, which is what the owners committed.
+            assert(u.was_proposed(a) && u.was_proposed(b));
+            let pa = choose |command: host::Command| #[trigger] u.sent_messages.contains(Message::PreAccept { id: a, command });
+            let pb = choose |command: host::Command| #[trigger] u.sent_messages.contains(Message::PreAccept { id: b, command });
+            assert(ca.value == pa.value && cb.value == pb.value);
+            if (u.sent_messages.contains(Message::PreAcceptOk { id: a, sender, command: ra })) { assert(ra.value == pa.value); } else { assert(ra == pa); }
+            if (u.sent_messages.contains(Message::PreAcceptOk { id: b, sender, command: rb })) { assert(rb.value == pb.value); } else { assert(rb == pb); }
+
+            assert(ra.deps.contains(b) || rb.deps.contains(a));
+            assert(ra.deps.subset_of(ca.deps) && rb.deps.subset_of(cb.deps));
+        };
+    }
+
+    // Execution consistency: if two replicas have both executed two instances whose commands
+    // interfere, they executed them in the same order.
+    pub proof fn interfering_commands_execute_in_same_order(c: &Constants, u: &Variables, i: int, j: int, ka: int, kb: int, la: int, lb: int)
+    requires
+        inductive(c, u),
+        0 <= i < u.hosts.len(),
+        0 <= j < u.hosts.len(),
+        0 <= ka < kb < u.hosts[i].executed.len(),
+        0 <= la < u.hosts[j].executed.len(),
+        0 <= lb < u.hosts[j].executed.len(),
+        u.hosts[j].executed[la] == u.hosts[i].executed[ka],
+        u.hosts[j].executed[lb] == u.hosts[i].executed[kb],
+        (c.interferes)(u.hosts[i].committed()[u.hosts[i].executed[ka]].value, u.hosts[i].committed()[u.hosts[i].executed[kb]].value),
+    ensures
+        la < lb,
+    {
+        let (a, b) = (u.hosts[i].executed[ka], u.hosts[i].executed[kb]);
+        let (committed_i, committed_j, committed) = (u.hosts[i].committed(), u.hosts[j].committed(), committed_commands(u));
+        assert(a != b);
+        assert(order::closure_is_committed(committed_i, a) && order::closure_is_committed(committed_i, b));
+        assert(order::closure_is_committed(committed_j, b));
+        host_commits_are_committed_commands(c, u, i);
+        host_commits_are_committed_commands(c, u, j);
+
+        // Replica `i` executed `b` after `a`, so `b` does not go before `a`.
+        if (order::executes_before(committed_i, b, a)) {
+            let earlier = choose |earlier: int| 0 <= earlier < ka && #[trigger] u.hosts[i].executed[earlier] == b;
+            assert(u.hosts[i].executed[earlier] == u.hosts[i].executed[kb]);
+        }
+        order::executes_before_is_stable(committed_i, committed, b, a);
+
+        // Interfering commands are ordered one way or the other, so `a` goes before `b`, everywhere.
+        committed_commands_are_dependent(c, u);
+        assert(committed[a] == committed_i[a] && committed[b] == committed_i[b]);
+        order::interfering_commands_are_ordered(committed, c.interferes, a, b);
+        order::executes_before_is_stable(committed_j, committed, a, b);
+        assert(order::executes_before(committed_j, a, b));
+
+        let earlier = choose |earlier: int| 0 <= earlier < lb && #[trigger] u.hosts[j].executed[earlier] == a;
+        assert(u.hosts[j].executed[earlier] == u.hosts[j].executed[la]);
+    }
+}
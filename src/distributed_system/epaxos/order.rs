@@ -0,0 +1,227 @@
+use super::host::{Command, InstanceId};
+use crate::distributed_system::Value;
+use vstd::prelude::*;
+
+verus! {
+    // The dependency graph of a replica's committed commands, and the order it executes them in.
+
+    // Consecutive instances on `path` are committed and each depends on the next one.
+    pub open spec fn is_path(committed: Map<InstanceId, Command>, path: Seq<InstanceId>) -> bool {
+        &&& path.len() > 0
+        &&& forall |i: int| 0 <= i < path.len() ==> #[trigger] committed.contains_key(path[i])
+        &&& forall |i: int| 0 <= i < path.len() - 1 ==> #[trigger] committed[path[i]].deps.contains(path[i + 1])
+    }
+
+    pub open spec fn is_path_between(committed: Map<InstanceId, Command>, path: Seq<InstanceId>, from: InstanceId, to: InstanceId) -> bool {
+        &&& is_path(committed, path)
+        &&& path[0] == from
+        &&& path.last() == to
+    }
+
+    pub open spec fn reaches(committed: Map<InstanceId, Command>, from: InstanceId, to: InstanceId) -> bool {
+        exists |path: Seq<InstanceId>| #[trigger] is_path_between(committed, path, from, to)
+    }
+
+    // `id` is committed and so is everything it transitively depends on, so its place in the order
+    // can no longer change.
+    pub open spec fn closure_is_committed(committed: Map<InstanceId, Command>, id: InstanceId) -> bool {
+        &&& committed.contains_key(id)
+        &&& forall |x: InstanceId| #[trigger] reaches(committed, id, x) ==> committed[x].deps.subset_of(committed.dom())
+    }
+
+    // Tie-break inside a dependency cycle: by sequence number, then by instance id.
+    pub open spec fn ordered_before(committed: Map<InstanceId, Command>, a: InstanceId, b: InstanceId) -> bool {
+        ||| committed[a].seq < committed[b].seq
+        ||| committed[a].seq == committed[b].seq && a.owner < b.owner
+        ||| committed[a].seq == committed[b].seq && a.owner == b.owner && a.slot < b.slot
+    }
+
+    // A replica executes a command only after everything it (transitively) depends on, and breaks
+    // cycles with `ordered_before`.
+    pub open spec fn executes_before(committed: Map<InstanceId, Command>, a: InstanceId, b: InstanceId) -> bool {
+        &&& reaches(committed, b, a)
+        &&& !reaches(committed, a, b) || ordered_before(committed, a, b)
+    }
+
+    // What the commit protocol provides (`committed_commands_are_dependent`): of two interfering
+    // committed commands, at least one depends on the other.
+    pub open spec fn interfering_commands_are_dependent(committed: Map<InstanceId, Command>, interferes: spec_fn(Value, Value) -> bool) -> bool {
+        forall |a: InstanceId, b: InstanceId|
+            a != b &&
+            committed.contains_key(a) &&
+            committed.contains_key(b) &&
+            #[trigger] interferes(committed[a].value, committed[b].value) ==>
+            committed[a].deps.contains(b) || committed[b].deps.contains(a)
+    }
+
+    pub proof fn committed_instance_reaches_itself(committed: Map<InstanceId, Command>, id: InstanceId)
+    requires
+        committed.contains_key(id),
+    ensures
+        reaches(committed, id, id),
+    {
+        assert(is_path_between(committed, seq![id], id, id));
+    }
+
+    pub proof fn prefix_of_path_is_path(committed: Map<InstanceId, Command>, path: Seq<InstanceId>, len: int)
+    requires
+        is_path(committed, path),
+        0 < len <= path.len(),
+    ensures
+        is_path(committed, path.subrange(0, len)),
+        reaches(committed, path[0], path[len - 1]),
+    {
+        let prefix = path.subrange(0, len);
+        assert(forall |i: int| 0 <= i < prefix.len() ==> prefix[i] == path[i]);
+        assert(is_path_between(committed, prefix, path[0], path[len - 1]));
+    }
+
+    pub proof fn reaches_is_transitive(committed: Map<InstanceId, Command>, a: InstanceId, b: InstanceId, x: InstanceId)
+    requires
+        reaches(committed, a, b),
+        reaches(committed, b, x),
+    ensures
+        reaches(committed, a, x),
+    {
+        let first = choose |path: Seq<InstanceId>| #[trigger] is_path_between(committed, path, a, b);
+        let second = choose |path: Seq<InstanceId>| #[trigger] is_path_between(committed, path, b, x);
+        // `b` ends the first path and starts the second, so it appears once in the joined one.
+        let path = first + second.subrange(1, second.len() as int);
+        assert forall |i: int| 0 <= i < path.len() implies #[trigger] committed.contains_key(path[i]) by {
+            if (i >= first.len()) { assert(path[i] == second[i - first.len() + 1]); }
+        };
+        assert forall |i: int| 0 <= i < path.len() - 1 implies #[trigger] committed[path[i]].deps.contains(path[i + 1]) by {
+            if (i < first.len() - 1) {
+                assert(path[i] == first[i] && path[i + 1] == first[i + 1]);
+            } else if (i == first.len() - 1) {
+                assert(path[i] == b && b == second[0] && path[i + 1] == second[1]);
+            } else {
+                assert(path[i] == second[i - first.len() + 1] && path[i + 1] == second[i - first.len() + 2]);
+            }
+        };
+        assert(path.last() == x) by {
+            if (second.len() == 1) { assert(x == b); } else { assert(path.last() == second.last()); }
+        };
+        assert(is_path_between(committed, path, a, x));
+    }
+
+    // Everything reachable from a closed instance is closed too.
+    pub proof fn closure_of_reachable_is_committed(committed: Map<InstanceId, Command>, id: InstanceId, x: InstanceId)
+    requires
+        closure_is_committed(committed, id),
+        reaches(committed, id, x),
+    ensures
+        closure_is_committed(committed, x),
+    {
+        let path = choose |path: Seq<InstanceId>| #[trigger] is_path_between(committed, path, id, x);
+        assert(committed.contains_key(path[path.len() - 1]));
+        assert forall |y: InstanceId| #[trigger] reaches(committed, x, y) implies committed[y].deps.subset_of(committed.dom()) by {
+            reaches_is_transitive(committed, id, x, y);
+        };
+    }
+
+    // The first `n` instances of a path from a closed instance in `larger` are committed in `smaller`
+    // already: each is a dependency of the one before, which `smaller` reaches.
+    pub proof fn path_stays_in_closure(smaller: Map<InstanceId, Command>, larger: Map<InstanceId, Command>, id: InstanceId, path: Seq<InstanceId>, n: int)
+    requires
+        closure_is_committed(smaller, id),
+        smaller.submap_of(larger),
+        is_path_between(larger, path, id, path.last()),
+        0 < n <= path.len(),
+    ensures
+        forall |i: int| 0 <= i < n ==> #[trigger] smaller.contains_key(path[i]),
+    decreases
+        n
+    {
+        if (n > 1) {
+            path_stays_in_closure(smaller, larger, id, path, n - 1);
+            let prefix = path.subrange(0, n - 1);
+            assert(is_path(smaller, prefix)) by {
+                assert(forall |i: int| 0 <= i < prefix.len() ==> prefix[i] == path[i]);
+                assert forall |i: int| 0 <= i < prefix.len() - 1 implies #[trigger] smaller[prefix[i]].deps.contains(prefix[i + 1]) by {
+                    assert(smaller[path[i]] == larger[path[i]]);
+                };
+            };
+            assert(is_path_between(smaller, prefix, id, path[n - 2]));
+            assert(smaller[path[n - 2]] == larger[path[n - 2]]);
+            assert(reaches(smaller, id, path[n - 2]));
+            assert(smaller[path[n - 2]].deps.contains(path[n - 1]));
+        } else {
+            assert(path[0] == id);
+        }
+    }
+
+    // Committing more instances never changes what a closed instance reaches.
+    pub proof fn closure_is_stable(smaller: Map<InstanceId, Command>, larger: Map<InstanceId, Command>, id: InstanceId)
+    requires
+        closure_is_committed(smaller, id),
+        smaller.submap_of(larger),
+    ensures
+        closure_is_committed(larger, id),
+        forall |x: InstanceId| #[trigger] reaches(larger, id, x) == reaches(smaller, id, x),
+    {
+        assert forall |x: InstanceId| #[trigger] reaches(larger, id, x) == reaches(smaller, id, x) by {
+            if (reaches(smaller, id, x)) {
+                let path = choose |path: Seq<InstanceId>| #[trigger] is_path_between(smaller, path, id, x);
+                assert forall |i: int| 0 <= i < path.len() - 1 implies #[trigger] larger[path[i]].deps.contains(path[i + 1]) by {
+                    assert(smaller.contains_key(path[i]));
+                };
+                assert(is_path_between(larger, path, id, x));
+            }
+            if (reaches(larger, id, x)) {
+                let path = choose |path: Seq<InstanceId>| #[trigger] is_path_between(larger, path, id, x);
+                path_stays_in_closure(smaller, larger, id, path, path.len() as int);
+                assert forall |i: int| 0 <= i < path.len() - 1 implies #[trigger] smaller[path[i]].deps.contains(path[i + 1]) by {
+                    assert(smaller.contains_key(path[i]));
+                };
+                assert(is_path_between(smaller, path, id, x));
+            }
+        };
+        assert forall |x: InstanceId| #[trigger] reaches(larger, id, x) implies larger[x].deps.subset_of(larger.dom()) by {
+            assert(reaches(smaller, id, x));
+            let path = choose |path: Seq<InstanceId>| #[trigger] is_path_between(smaller, path, id, x);
+            assert(smaller.contains_key(path[path.len() - 1]));
+        };
+    }
+
+    // Whether anything executes before a closed instance is settled once it is closed.
+    pub proof fn executes_before_is_stable(smaller: Map<InstanceId, Command>, larger: Map<InstanceId, Command>, x: InstanceId, id: InstanceId)
+    requires
+        closure_is_committed(smaller, id),
+        smaller.submap_of(larger),
+    ensures
+        executes_before(smaller, x, id) == executes_before(larger, x, id),
+    {
+        closure_is_stable(smaller, larger, id);
+        if (reaches(smaller, id, x)) {
+            closure_of_reachable_is_committed(smaller, id, x);
+            closure_is_stable(smaller, larger, x);
+            let path = choose |path: Seq<InstanceId>| #[trigger] is_path_between(smaller, path, id, x);
+            assert(smaller.contains_key(path[path.len() - 1]));
+            assert(smaller[x] == larger[x] && smaller[id] == larger[id]);
+        }
+    }
+
+    // With the dependency invariant, any two distinct interfering commands are ordered one way or the
+    // other.
+    pub proof fn interfering_commands_are_ordered(committed: Map<InstanceId, Command>, interferes: spec_fn(Value, Value) -> bool, a: InstanceId, b: InstanceId)
+    requires
+        interfering_commands_are_dependent(committed, interferes),
+        a != b,
+        committed.contains_key(a),
+        committed.contains_key(b),
+        interferes(committed[a].value, committed[b].value),
+    ensures
+        executes_before(committed, a, b) || executes_before(committed, b, a),
+    {
+        if (committed[a].deps.contains(b)) {
+            assert(is_path_between(committed, seq![a, b], a, b));
+        } else {
+            assert(is_path_between(committed, seq![b, a], b, a));
+        }
+        // Within a cycle exactly one of the two is `ordered_before` the other.
+        if (reaches(committed, a, b) && reaches(committed, b, a)) {
+            assert(ordered_before(committed, a, b) || ordered_before(committed, b, a));
+        }
+    }
+}
@@ -1,26 +1,316 @@
-use super::{Event, Value};
+use super::{no_op, Event, Value};
 use vstd::prelude::*;
 
 verus! {
-    pub struct Constants {}
+    // `acceptors` vote until the first reconfiguration. A decided value for which `members` is `Some`
+    // is a reconfiguration command: the acceptor set it names votes from `alpha` instances later on.
+    pub struct Constants {
+        pub acceptors: Set<nat>,
+        pub alpha: nat,
+        pub members: spec_fn(Value) -> Option<Set<nat>>,
+    }
 
     pub struct Variables {
         pub decided_value: Map<nat, Value>,
+        pub submitted: Set<Value>,
+        pub proposed: Set<Value>,
+        // The acceptor set of each decided reconfiguration command, keyed by the first instance it
+        // governs.
+        pub membership: Map<nat, Set<nat>>,
     }
 
     pub open spec fn init(c: &Constants, u: &Variables) -> bool {
         &&& u.decided_value.is_empty()
+        &&& u.submitted.is_empty()
+        &&& u.proposed.is_empty()
+        &&& u.membership.is_empty()
+    }
+
+    // The membership history `decided` implies.
+    pub open spec fn membership_of(c: &Constants, decided: Map<nat, Value>) -> Map<nat, Set<nat>> {
+        Map::new(
+            |key: nat| key >= c.alpha && decided.contains_key((key - c.alpha) as nat) && (c.members)(decided[(key - c.alpha) as nat]).is_some(),
+            |key: nat| (c.members)(decided[(key - c.alpha) as nat]).unwrap(),
+        )
+    }
+
+    // Deciding `value` in `key` installs its acceptor set at `key + alpha` if it is a command.
+    pub open spec fn reconfigure(c: &Constants, membership: Map<nat, Set<nat>>, key: nat, value: Value) -> Map<nat, Set<nat>> {
+        if ((c.members)(value).is_some()) {
+            membership.insert(key + c.alpha, (c.members)(value).unwrap())
+        } else {
+            membership
+        }
+    }
+
+    pub open spec fn reconfigure_entries(c: &Constants, membership: Map<nat, Set<nat>>, entries: Seq<(nat, Value)>, n: nat) -> Map<nat, Set<nat>>
+    decreases
+        n
+    {
+        if (n == 0) {
+            membership
+        } else {
+            reconfigure(c, reconfigure_entries(c, membership, entries, (n - 1) as nat), entries[n - 1].0, entries[n - 1].1)
+        }
+    }
+
+    // The acceptors whose votes count in instance `key`.
+    pub open spec fn acceptors_at(c: &Constants, membership: Map<nat, Set<nat>>, key: nat) -> Set<nat>
+    decreases
+        key
+    {
+        if (key == 0 || key < c.alpha) {
+            c.acceptors
+        } else if (membership.contains_key(key)) {
+            membership[key]
+        } else {
+            acceptors_at(c, membership, (key - 1) as nat)
+        }
     }
 
+    pub open spec fn submit(c: &Constants, u: &Variables, v: &Variables, value: Value) -> bool {
+        &&& v.decided_value == u.decided_value
+        &&& v.submitted == u.submitted.insert(value)
+        &&& v.proposed == u.proposed
+        &&& v.membership == u.membership
+    }
+
+    pub open spec fn propose(c: &Constants, u: &Variables, v: &Variables, key: nat, value: Value) -> bool {
+        &&& u.submitted.contains(value) || value == no_op(key)
+        &&& v.decided_value == u.decided_value
+        &&& v.submitted == u.submitted
+        &&& v.proposed == u.proposed.insert(value)
+        &&& v.membership == u.membership
+    }
+
+    // Validity: only a value some leader has proposed, and hence some client has submitted unless it is
+    // the instance's no-op, can ever be decided.
     pub open spec fn decide(c: &Constants, u: &Variables, v: &Variables, key: nat, value: Value) -> bool {
+        &&& u.submitted.contains(value) || value == no_op(key)
+        &&& u.proposed.contains(value)
         &&& u.decided_value.contains_key(key) ==> u.decided_value[key] == value
         &&& v.decided_value == u.decided_value.insert(key, value)
+        &&& v.submitted == u.submitted
+        &&& v.proposed == u.proposed
+        &&& v.membership == reconfigure(c, u.membership, key, value)
+    }
+
+    pub open spec fn propose_batch(c: &Constants, u: &Variables, v: &Variables, entries: Seq<(nat, Value)>) -> bool {
+        &&& forall |j: int| #![trigger entries[j]]
+                0 <= j < entries.len() ==>
+                u.submitted.contains(entries[j].1) || entries[j].1 == no_op(entries[j].0)
+        &&& v.decided_value == u.decided_value
+        &&& v.submitted == u.submitted
+        &&& v.proposed == u.proposed.union(entries.map_values(|entry: (nat, Value)| entry.1).to_set())
+        &&& v.membership == u.membership
+    }
+
+    // `decided` with the first `n` entries decided, one after the other.
+    pub open spec fn decide_entries(decided: Map<nat, Value>, entries: Seq<(nat, Value)>, n: nat) -> Map<nat, Value>
+    decreases
+        n
+    {
+        if (n == 0) {
+            decided
+        } else {
+            decide_entries(decided, entries, (n - 1) as nat).insert(entries[n - 1].0, entries[n - 1].1)
+        }
+    }
+
+    // Every entry of the batch names its own instance and could be decided on its own in `u`.
+    pub open spec fn decide_batch(c: &Constants, u: &Variables, v: &Variables, entries: Seq<(nat, Value)>) -> bool {
+        &&& entries.map_values(|entry: (nat, Value)| entry.0).no_duplicates()
+        &&& forall |j: int| #![trigger entries[j]]
+                0 <= j < entries.len() ==> {
+                    let (key, value) = entries[j];
+                    &&& u.submitted.contains(value) || value == no_op(key)
+                    &&& u.proposed.contains(value)
+                    &&& u.decided_value.contains_key(key) ==> u.decided_value[key] == value
+                }
+        &&& v.decided_value == decide_entries(u.decided_value, entries, entries.len())
+        &&& v.submitted == u.submitted
+        &&& v.proposed == u.proposed
+        &&& v.membership == reconfigure_entries(c, u.membership, entries, entries.len())
     }
 
     pub open spec fn next(c: &Constants, u: &Variables, v: &Variables, event: Event) -> bool {
         match event {
             Event::Decide { key, value } => { decide(c, u, v, key, value) },
+            Event::Propose { key, value } => { propose(c, u, v, key, value) },
+            Event::Submit { value, .. } => { submit(c, u, v, value) },
+            Event::ProposeBatch { entries } => { propose_batch(c, u, v, entries) },
+            Event::DecideBatch { entries } => { decide_batch(c, u, v, entries) },
             Event::NoOp => { v == u },
         }
     }
+
+    // `states` goes from its first to its last element by one `next` step per element of `events`.
+    pub open spec fn is_run(c: &Constants, states: Seq<Variables>, events: Seq<Event>) -> bool {
+        &&& states.len() == events.len() + 1
+        &&& forall |j: int| #![trigger states[j]]
+                0 <= j < events.len() ==>
+                next(c, &states[j], &states[j + 1], events[j])
+    }
+
+    // Deciding the first `n` entries leaves every other instance as it was.
+    pub proof fn decide_entries_leaves_other_keys(decided: Map<nat, Value>, entries: Seq<(nat, Value)>, n: nat, key: nat)
+    requires
+        n <= entries.len(),
+        forall |i: int| #![trigger entries[i]] 0 <= i < n ==> entries[i].0 != key,
+    ensures
+        decide_entries(decided, entries, n).contains_key(key) == decided.contains_key(key),
+        decide_entries(decided, entries, n)[key] == decided[key],
+    decreases
+        n
+    {
+        if (n > 0) {
+            decide_entries_leaves_other_keys(decided, entries, (n - 1) as nat, key);
+            assert(entries[n - 1].0 != key);
+        }
+    }
+
+    // Deciding entries that agree with `decided` only adds decisions.
+    pub proof fn decide_entries_extends(decided: Map<nat, Value>, entries: Seq<(nat, Value)>, n: nat)
+    requires
+        n <= entries.len(),
+        forall |j: int| #![trigger entries[j]]
+            0 <= j < n && decided.contains_key(entries[j].0) ==> decided[entries[j].0] == entries[j].1,
+    ensures
+        decided.submap_of(decide_entries(decided, entries, n)),
+        decided.dom().finite() ==> decide_entries(decided, entries, n).dom().finite(),
+    decreases
+        n
+    {
+        if (n > 0) {
+            let (key, value) = entries[n - 1];
+            decide_entries_extends(decided, entries, (n - 1) as nat);
+            assert(decided.contains_key(key) ==> decided[key] == value);
+        }
+    }
+
+    // After `j` steps of a run of proposals, the first `j` values have joined `proposed`.
+    pub proof fn run_of_proposals_proposes_prefix(c: &Constants, states: Seq<Variables>, entries: Seq<(nat, Value)>, j: int)
+    requires
+        is_run(c, states, Event::ProposeBatch { entries }.unbatch()),
+        0 <= j < states.len(),
+    ensures
+        states[j].decided_value == states[0].decided_value,
+        states[j].submitted == states[0].submitted,
+        states[j].proposed == states[0].proposed.union(entries.map_values(|entry: (nat, Value)| entry.1).subrange(0, j).to_set()),
+        states[j].membership == states[0].membership,
+    decreases
+        j
+    {
+        let values = entries.map_values(|entry: (nat, Value)| entry.1);
+        if (j == 0) {
+            assert(values.subrange(0, 0).to_set() =~= Set::empty());
+            assert(states[0].proposed.union(Set::empty()) =~= states[0].proposed);
+        } else {
+            run_of_proposals_proposes_prefix(c, states, entries, j - 1);
+            assert(Event::ProposeBatch { entries }.unbatch()[j - 1] == Event::Propose { key: entries[j - 1].0, value: entries[j - 1].1 });
+            assert(next(c, &states[j - 1], &states[j], Event::Propose { key: entries[j - 1].0, value: entries[j - 1].1 }));
+            assert(values.subrange(0, j).to_set() =~= values.subrange(0, j - 1).to_set().insert(values[j - 1]));
+            assert(states[j].proposed =~= states[0].proposed.union(values.subrange(0, j).to_set()));
+        }
+    }
+
+    // After `j` steps of a run of decisions, the first `j` entries are decided and nothing else moved.
+    pub proof fn run_of_decisions_decides_prefix(c: &Constants, states: Seq<Variables>, entries: Seq<(nat, Value)>, j: int)
+    requires
+        is_run(c, states, Event::DecideBatch { entries }.unbatch()),
+        0 <= j < states.len(),
+    ensures
+        states[j].decided_value == decide_entries(states[0].decided_value, entries, j as nat),
+        states[j].submitted == states[0].submitted,
+        states[j].proposed == states[0].proposed,
+        states[j].membership == reconfigure_entries(c, states[0].membership, entries, j as nat),
+    decreases
+        j
+    {
+        if (j > 0) {
+            run_of_decisions_decides_prefix(c, states, entries, j - 1);
+            assert(Event::DecideBatch { entries }.unbatch()[j - 1] == Event::Decide { key: entries[j - 1].0, value: entries[j - 1].1 });
+            assert(next(c, &states[j - 1], &states[j], Event::Decide { key: entries[j - 1].0, value: entries[j - 1].1 }));
+        }
+    }
+
+    pub proof fn run_of_no_ops_stutters(c: &Constants, states: Seq<Variables>, j: int)
+    requires
+        forall |i: int| #![trigger states[i]] 0 <= i < states.len() - 1 ==> next(c, &states[i], &states[i + 1], Event::NoOp),
+        0 <= j < states.len(),
+    ensures
+        states[j] == states[0],
+    decreases
+        j
+    {
+        if (j > 0) {
+            run_of_no_ops_stutters(c, states, j - 1);
+            assert(next(c, &states[j - 1], &states[j], Event::NoOp));
+        }
+    }
+
+    // A batch step is the run of the single-key steps its entries stand for, one entry after the
+    // other, and a run of stuttering steps stutters.
+    pub proof fn run_is_batch_step(c: &Constants, states: Seq<Variables>, event: Event)
+    requires
+        event.is_batch() || event is NoOp,
+        event.is_batch() ==> is_run(c, states, event.unbatch()),
+        event is NoOp ==> states.len() > 0 && forall |j: int| #![trigger states[j]] 0 <= j < states.len() - 1 ==> next(c, &states[j], &states[j + 1], event),
+        event is DecideBatch ==> event->DecideBatch_entries.map_values(|entry: (nat, Value)| entry.0).no_duplicates(),
+    ensures
+        next(c, &states[0], &states.last(), event),
+    {
+        let n = states.len() - 1;
+        if let Event::ProposeBatch { entries } = event {
+            run_of_proposals_proposes_prefix(c, states, entries, n);
+            assert(entries.map_values(|entry: (nat, Value)| entry.1).subrange(0, n) =~= entries.map_values(|entry: (nat, Value)| entry.1));
+
+            assert forall |j: int| #![trigger entries[j]] 0 <= j < entries.len() implies
+                states[0].submitted.contains(entries[j].1) || entries[j].1 == no_op(entries[j].0)
+            by {
+                run_of_proposals_proposes_prefix(c, states, entries, j);
+                assert(event.unbatch()[j] == Event::Propose { key: entries[j].0, value: entries[j].1 });
+                assert(next(c, &states[j], &states[j + 1], event.unbatch()[j]));
+            };
+        } else if let Event::DecideBatch { entries } = event {
+            let keys = entries.map_values(|entry: (nat, Value)| entry.0);
+            run_of_decisions_decides_prefix(c, states, entries, n);
+
+            // Entry `j` names an instance no earlier entry decided, so `states[0]` agrees with it
+            // wherever `states[j]` does.
+            assert forall |j: int| #![trigger entries[j]] 0 <= j < entries.len() implies {
+                let (key, value) = entries[j];
+                &&& states[0].submitted.contains(value) || value == no_op(key)
+                &&& states[0].proposed.contains(value)
+                &&& states[0].decided_value.contains_key(key) ==> states[0].decided_value[key] == value
+            } by {
+                run_of_decisions_decides_prefix(c, states, entries, j);
+                assert(event.unbatch()[j] == Event::Decide { key: entries[j].0, value: entries[j].1 });
+                assert(next(c, &states[j], &states[j + 1], event.unbatch()[j]));
+                assert forall |i: int| #![trigger entries[i]] 0 <= i < j implies entries[i].0 != entries[j].0 by {
+                    assert(keys[i] != keys[j]);
+                };
+                decide_entries_leaves_other_keys(states[0].decided_value, entries, j as nat, entries[j].0);
+            };
+        } else {
+            run_of_no_ops_stutters(c, states, n);
+        }
+    }
+
+    // Deciding a value the instance either lacks or already has moves the implied history the way
+    // `decide` does.
+    pub proof fn decide_reconfigures(c: &Constants, decided: Map<nat, Value>, key: nat, value: Value)
+    requires
+        decided.contains_key(key) ==> decided[key] == value,
+    ensures
+        membership_of(c, decided.insert(key, value)) == reconfigure(c, membership_of(c, decided), key, value),
+    {
+        let (old, new) = (membership_of(c, decided), membership_of(c, decided.insert(key, value)));
+        assert forall |k: nat| k >= c.alpha && (k - c.alpha) as nat == key implies k == key + c.alpha by {};
+        if ((c.members)(value).is_some()) {
+            assert(new =~= old.insert(key + c.alpha, (c.members)(value).unwrap()));
+        } else {
+            assert(new =~= old);
+        }
+    }
 }
@@ -0,0 +1,376 @@
+use super::{
+    low_level::{
+        host, host_next, init, is_valid_transition, message_next, network_only_next, next_is_host_next_or_network_only_next,
+        network, reconfig, some_host_decided, Constants, Message, NetworkOperation, Transition, Variables,
+    },
+    Event, Value,
+};
+use vstd::prelude::*;
+
+verus! {
+    pub mod progress;
+    pub mod rules;
+    pub mod steps;
+
+    // An infinite run of the low-level state machine: `states(i)` steps to `states(i + 1)` on `events(i)`.
+    #[verifier::ext_equal]
+    pub struct Execution {
+        pub states: spec_fn(nat) -> Variables,
+        pub events: spec_fn(nat) -> Event,
+    }
+
+    impl Execution {
+        pub open spec fn suffix(self, start: nat) -> Execution {
+            Execution {
+                states: |i: nat| (self.states)((i + start) as nat),
+                events: |i: nat| (self.events)((i + start) as nat),
+            }
+        }
+
+        pub open spec fn head(self) -> Variables {
+            (self.states)(0)
+        }
+    }
+
+    pub type TemporalPredicate = spec_fn(Execution) -> bool;
+
+    pub type StatePredicate = spec_fn(Variables) -> bool;
+
+    pub type ActionPredicate = spec_fn(Variables, Variables, Event) -> bool;
+
+    pub open spec fn lift_state(p: StatePredicate) -> TemporalPredicate {
+        |ex: Execution| p(ex.head())
+    }
+
+    pub open spec fn lift_action(p: ActionPredicate) -> TemporalPredicate {
+        |ex: Execution| p((ex.states)(0), (ex.states)(1), (ex.events)(0))
+    }
+
+    pub open spec fn implies(p: TemporalPredicate, q: TemporalPredicate) -> TemporalPredicate {
+        |ex: Execution| p(ex) ==> q(ex)
+    }
+
+    pub open spec fn always(p: TemporalPredicate) -> TemporalPredicate {
+        |ex: Execution| forall |i: nat| #[trigger] p(ex.suffix(i))
+    }
+
+    pub open spec fn eventually(p: TemporalPredicate) -> TemporalPredicate {
+        |ex: Execution| exists |i: nat| #[trigger] p(ex.suffix(i))
+    }
+
+    pub open spec fn leads_to(p: TemporalPredicate, q: TemporalPredicate) -> TemporalPredicate {
+        always(implies(p, eventually(q)))
+    }
+
+    pub open spec fn enabled(p: ActionPredicate) -> StatePredicate {
+        |u: Variables| exists |v: Variables, event: Event| #[trigger] p(u, v, event)
+    }
+
+    // An action that stays enabled from some point on is eventually taken.
+    pub open spec fn weak_fairness(p: ActionPredicate) -> TemporalPredicate {
+        leads_to(always(lift_state(enabled(p))), lift_action(p))
+    }
+
+    pub open spec fn takes_step(c: &Constants, ex: Execution, i: nat) -> bool {
+        message_next(c, &(ex.states)(i), &(ex.states)((i + 1) as nat), (ex.events)(i))
+    }
+
+    pub open spec fn is_behavior(c: &Constants, ex: Execution) -> bool {
+        &&& init(c, &ex.head())
+        &&& forall |i: nat| #[trigger] takes_step(c, ex, i)
+    }
+
+    pub open spec fn instance_initiated(key: nat) -> StatePredicate {
+        |u: Variables| exists |i: int| #![auto] 0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key)
+    }
+
+    pub open spec fn instance_decided(key: nat) -> StatePredicate {
+        |u: Variables| some_host_decided(&u, key)
+    }
+
+    // Every host of the quorum has learned the decisions the configuration of `key` depends on, so
+    // each of them can create the instance.
+    pub open spec fn quorum_knows_configuration(c: &Constants, quorum: Set<nat>, key: nat) -> StatePredicate {
+        |u: Variables| forall |h: nat| #[trigger] quorum.contains(h) ==> c.hosts[h as int].knows_configuration(&u.hosts[h as int], key)
+    }
+
+    pub open spec fn instance_is_ready(c: &Constants, quorum: Set<nat>, key: nat) -> StatePredicate {
+        |u: Variables| instance_initiated(key)(u) && quorum_knows_configuration(c, quorum, key)(u)
+    }
+
+    // A set of acceptors that can complete both phases on its own, in the initial configuration and in
+    // every one a reconfiguration command can install.
+    pub open spec fn is_quorum(c: &Constants, quorum: Set<nat>) -> bool {
+        &&& quorum.finite()
+        &&& forall |h: nat| #[trigger] quorum.contains(h) ==> h < c.num_hosts && c.quorums.acceptors.contains(h)
+        &&& c.quorums.is_phase1_quorum(quorum)
+        &&& c.quorums.is_phase2_quorum(quorum)
+        &&& forall |value: Value| #[trigger] (c.reconfig.members)(value).is_some() ==> {
+                let members = reconfig::member_configuration((c.reconfig.members)(value).unwrap(), c.num_failures);
+                members.classic.is_phase1_quorum(quorum) && members.classic.is_phase2_quorum(quorum)
+            }
+    }
+
+    // The handlers a host runs on its own. Each is fair separately: taking one does not discharge the
+    // obligation to take another, so a learner that counts an Accepted with `observe_accepted` still
+    // counts it as a leader with `accepted`. Starting a ballot is governed by `leader_times_out`, and
+    // client submissions are not the hosts' to make.
+    pub enum Handler {
+        InitRequest,
+        RaiseToFloor,
+        Promise,
+        RejectPrepare,
+        Promised,
+        Nacked,
+        SendAccept,
+        Accept,
+        RejectAccept,
+        Accepted,
+        Learn,
+    }
+
+    pub open spec fn runs(handler: Handler, c: &host::Constants, u: &host::Variables, v: &host::Variables, key: nat, net_op: NetworkOperation, event: Event) -> bool {
+        match handler {
+            Handler::InitRequest => host::init_request(c, u, v, key, net_op),
+            Handler::RaiseToFloor => host::raise_to_floor(c, u, v, key, net_op),
+            Handler::Promise => host::promise(c, u, v, key, net_op),
+            Handler::RejectPrepare => host::reject_prepare(c, u, v, key, net_op),
+            Handler::Promised => host::promised(c, u, v, key, net_op),
+            Handler::Nacked => host::nacked(c, u, v, key, net_op),
+            Handler::SendAccept => host::send_accept(c, u, v, key, net_op),
+            Handler::Accept => host::accept(c, u, v, key, net_op),
+            Handler::RejectAccept => host::reject_accept(c, u, v, key, net_op),
+            Handler::Accepted => host::accepted(c, u, v, key, net_op),
+            Handler::Learn => if let Event::Decide { value, .. } = event { host::learn(c, u, v, key, net_op, value) } else { false },
+        }
+    }
+
+    // Host `host_id` runs `handler` for instance `key` on message `recv`.
+    pub open spec fn host_action(c: &Constants, host_id: nat, key: nat, handler: Handler, recv: Option<Message>) -> ActionPredicate {
+        |u: Variables, v: Variables, event: Event|
+            exists |net_op: NetworkOperation| #![trigger is_valid_transition(c, &u, &v, Transition::HostStep { host_id: host_id as int, instance: key, net_op }, event)]
+                net_op.recv == recv &&
+                runs(handler, &c.hosts[host_id as int], &u.hosts[host_id as int], &v.hosts[host_id as int], key, net_op, event) &&
+                is_valid_transition(c, &u, &v, Transition::HostStep { host_id: host_id as int, instance: key, net_op }, event)
+    }
+
+    pub open spec fn quorum_is_up(quorum: Set<nat>) -> StatePredicate {
+        |u: Variables| forall |h: nat| #[trigger] quorum.contains(h) ==> u.hosts[h as int].up
+    }
+
+    pub open spec fn host_action_is_fair(c: &Constants, host_id: nat, key: nat, handler: Handler, recv: Option<Message>, ex: Execution) -> bool {
+        weak_fairness(host_action(c, host_id, key, handler, recv))(ex)
+    }
+
+    pub open spec fn quorum_takes_steps_fairly(c: &Constants, quorum: Set<nat>, ex: Execution) -> bool {
+        &&& eventually(always(lift_state(quorum_is_up(quorum))))(ex)
+        &&& forall |h: nat, key: nat, handler: Handler, recv: Option<Message>|
+                quorum.contains(h) ==>
+                #[trigger] host_action_is_fair(c, h, key, handler, recv, ex)
+    }
+
+    // The leader is driving `ballot` in instance `key`: it prepared it since it last recovered, so the
+    // ballot's promises are still in its memory, and the Prepare is out.
+    pub open spec fn leads(u: Variables, leader: nat, key: nat, ballot: host::Ballot) -> bool {
+        &&& ballot.pid == leader
+        &&& u.hosts[leader as int].instances.contains_key(key)
+        &&& u.hosts[leader as int].instances[key].volatile.promised.contains_key(ballot)
+        &&& u.network.sent_messages.contains(Message::Prepare { key, ballot })
+    }
+
+    // The ballot at the leader's `max_seen_ballot` is someone else's, or one the leader lost in a crash.
+    pub open spec fn leader_is_stuck(leader: nat, key: nat) -> StatePredicate {
+        |u: Variables|
+            u.hosts[leader as int].instances.contains_key(key) &&
+            !leads(u, leader, key, u.hosts[leader as int].instances[key].max_seen_ballot)
+    }
+
+    // Partial synchrony: once timeouts are accurate only the leader starts ballots, and only in an
+    // instance it is stuck in; nobody sends a PrepareFrom.
+    pub open spec fn only_leader_prepares(c: &Constants, leader: nat) -> ActionPredicate {
+        |u: Variables, v: Variables, event: Event|
+            forall |host_id: int, key: nat, net_op: NetworkOperation|
+                #[trigger] is_valid_transition(c, &u, &v, Transition::HostStep { host_id, instance: key, net_op }, event) ==>
+                !host::send_prepare_from(&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id], key, net_op) &&
+                (host::send_prepare(&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id], key, net_op) ==> host_id == leader && leader_is_stuck(leader, key)(u))
+    }
+
+    // The leader's timeout for an instance it is stuck in.
+    pub open spec fn leader_times_out(c: &Constants, leader: nat, key: nat) -> ActionPredicate {
+        |u: Variables, v: Variables, event: Event| {
+            &&& leader_is_stuck(leader, key)(u)
+            &&& exists |net_op: NetworkOperation| #![trigger is_valid_transition(c, &u, &v, Transition::HostStep { host_id: leader as int, instance: key, net_op }, event)]
+                    host::send_prepare(&c.hosts[leader as int], &u.hosts[leader as int], &v.hosts[leader as int], key, net_op) &&
+                    is_valid_transition(c, &u, &v, Transition::HostStep { host_id: leader as int, instance: key, net_op }, event)
+        }
+    }
+
+    pub open spec fn leader_times_out_is_fair(c: &Constants, leader: nat, key: nat, ex: Execution) -> bool {
+        weak_fairness(leader_times_out(c, leader, key))(ex)
+    }
+
+    pub open spec fn leader_times_out_fairly(c: &Constants, leader: nat, ex: Execution) -> bool {
+        forall |key: nat| #[trigger] leader_times_out_is_fair(c, leader, key, ex)
+    }
+
+    // Once message delays are bounded every message ever sent is ready for delivery, retransmitted if
+    // need be, except those garbage-collected from decided instances.
+    pub open spec fn messages_are_delivered() -> StatePredicate {
+        |u: Variables|
+            forall |m: Message| #[trigger] u.network.sent_messages.contains(m) ==>
+                u.network.in_flight_messages.contains(m) ||
+                (network::message_key(m).is_some() && some_host_decided(&u, network::message_key(m).unwrap()))
+    }
+
+    pub open spec fn liveness_assumptions(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>) -> bool {
+        &&& is_behavior(c, ex)
+        &&& !c.network.consume_on_receive
+        &&& is_quorum(c, quorum)
+        &&& quorum.contains(leader)
+        &&& c.proposers.contains(leader)
+        &&& eventually(always(lift_action(only_leader_prepares(c, leader))))(ex)
+        &&& eventually(always(lift_state(messages_are_delivered())))(ex)
+        &&& quorum_takes_steps_fairly(c, quorum, ex)
+        &&& leader_times_out_fairly(c, leader, ex)
+    }
+
+    pub open spec fn initiated_instance_is_eventually_decided(c: &Constants, quorum: Set<nat>, key: nat, ex: Execution) -> bool {
+        leads_to(lift_state(instance_is_ready(c, quorum, key)), lift_state(instance_decided(key)))(ex)
+    }
+
+    pub open spec fn every_initiated_instance_is_decided(c: &Constants, quorum: Set<nat>, ex: Execution) -> bool {
+        forall |key: nat| #[trigger] initiated_instance_is_eventually_decided(c, quorum, key, ex)
+    }
+
+    pub proof fn suffix_of_suffix_is_suffix(ex: Execution, i: nat, j: nat)
+    ensures
+        ex.suffix(i).suffix(j) == ex.suffix((i + j) as nat),
+    {
+        assert(ex.suffix(i).suffix(j) =~~= ex.suffix((i + j) as nat));
+    }
+
+    pub proof fn always_holds_on_every_suffix(p: TemporalPredicate, ex: Execution, i: nat)
+    requires
+        always(p)(ex),
+    ensures
+        always(p)(ex.suffix(i)),
+    {
+        assert forall |j: nat| #[trigger] p(ex.suffix(i).suffix(j)) by {
+            suffix_of_suffix_is_suffix(ex, i, j);
+            assert(p(ex.suffix((i + j) as nat)));
+        };
+    }
+
+    pub proof fn leads_to_is_transitive(p: TemporalPredicate, q: TemporalPredicate, r: TemporalPredicate, ex: Execution)
+    requires
+        leads_to(p, q)(ex),
+        leads_to(q, r)(ex),
+    ensures
+        leads_to(p, r)(ex),
+    {
+        assert forall |i: nat| #[trigger] implies(p, eventually(r))(ex.suffix(i)) by {
+            if (p(ex.suffix(i))) {
+                assert(implies(p, eventually(q))(ex.suffix(i)));
+                let j = choose |j: nat| #[trigger] q(ex.suffix(i).suffix(j));
+                suffix_of_suffix_is_suffix(ex, i, j);
+
+                assert(implies(q, eventually(r))(ex.suffix((i + j) as nat)));
+                let k = choose |k: nat| #[trigger] r(ex.suffix((i + j) as nat).suffix(k));
+                suffix_of_suffix_is_suffix(ex, (i + j) as nat, k);
+                suffix_of_suffix_is_suffix(ex, i, (j + k) as nat);
+                assert(r(ex.suffix(i).suffix((j + k) as nat)));
+            }
+        };
+    }
+
+    pub proof fn instance_decided_is_stable(c: &Constants, u: &Variables, v: &Variables, event: Event, key: nat)
+    requires
+        message_next(c, u, v, event),
+        some_host_decided(u, key),
+    ensures
+        some_host_decided(v, key),
+    {
+        next_is_host_next_or_network_only_next(c, u, v, event);
+        let w = choose |i: int| #![auto] 0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) && u.hosts[i].instances[key].decide_value.is_some();
+
+        if (host_next(c, u, v, event)) {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, v, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            if (w != host_id) {
+                assert(v.hosts[w] == u.hosts[w]);
+            }
+        } else {
+            assert(network_only_next(c, u, v, event));
+            assert(v.hosts[w] == u.hosts[w]);
+        }
+
+        assert(v.hosts[w].instances.contains_key(key) && v.hosts[w].instances[key].decide_value.is_some());
+    }
+
+    pub proof fn instance_decided_stays_decided(c: &Constants, ex: Execution, key: nat, i: nat, j: nat)
+    requires
+        is_behavior(c, ex),
+        some_host_decided(&(ex.states)(i), key),
+    ensures
+        some_host_decided(&(ex.states)((i + j) as nat), key),
+    decreases
+        j
+    {
+        if (j > 0) {
+            let prev = (i + j - 1) as nat;
+            instance_decided_stays_decided(c, ex, key, i, (j - 1) as nat);
+            assert(takes_step(c, ex, prev));
+            instance_decided_is_stable(c, &(ex.states)(prev), &(ex.states)((prev + 1) as nat), (ex.events)(prev), key);
+        }
+    }
+
+    // Once some host decides an instance, it stays decided: the conclusion of the liveness theorem
+    // only has to be reached once per instance.
+    pub proof fn decided_instance_is_always_decided(c: &Constants, ex: Execution, key: nat)
+    requires
+        is_behavior(c, ex),
+    ensures
+        always(implies(lift_state(instance_decided(key)), always(lift_state(instance_decided(key)))))(ex),
+    {
+        assert forall |i: nat| #[trigger] implies(lift_state(instance_decided(key)), always(lift_state(instance_decided(key))))(ex.suffix(i)) by {
+            if (lift_state(instance_decided(key))(ex.suffix(i))) {
+                assert(some_host_decided(&(ex.states)(i), key));
+                assert forall |j: nat| #[trigger] lift_state(instance_decided(key))(ex.suffix(i).suffix(j)) by {
+                    suffix_of_suffix_is_suffix(ex, i, j);
+                    instance_decided_stays_decided(c, ex, key, i, j);
+                    assert(ex.suffix((i + j) as nat).head() == (ex.states)((i + j) as nat));
+                };
+            }
+        };
+    }
+
+    // Liveness: under the assumptions every instance that some host has started is eventually decided,
+    // once the quorum has learned the decisions that fix its configuration (a host only creates an
+    // instance whose configuration it knows). No client has to keep submitting: a leader with no
+    // request waiting proposes the instance's no-op.
+    // The theorem only covers networks that keep a message in flight after it is received
+    // (`!consume_on_receive`), as the transports do: a broadcast Prepare, Accept or Accepted is one
+    // message in the network, and every acceptor or learner of the quorum has to be able to read it.
+    // `progress` shows it from the step at which the assumptions have all kicked in.
+    pub proof fn liveness(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>)
+    requires
+        liveness_assumptions(c, ex, leader, quorum),
+    ensures
+        every_initiated_instance_is_decided(c, quorum, ex),
+    {
+        let n = progress::liveness_assumptions_settle(c, ex, leader, quorum);
+        assert forall |key: nat| #[trigger] initiated_instance_is_eventually_decided(c, quorum, key, ex) by {
+            assert forall |i: nat|
+                #[trigger] implies(lift_state(instance_is_ready(c, quorum, key)), eventually(lift_state(instance_decided(key))))(ex.suffix(i)) by {
+                rules::suffix_is_shifted(ex, i);
+                if (quorum_knows_configuration(c, quorum, key)((ex.states)(i))) {
+                    progress::instance_is_eventually_decided(c, ex, leader, quorum, key, n, i);
+                    let j = choose |j: nat| i <= j && #[trigger] instance_decided(key)((ex.states)(j));
+                    let d = (j - i) as nat;
+                    suffix_of_suffix_is_suffix(ex, i, d);
+                    rules::suffix_is_shifted(ex, j);
+                    assert(lift_state(instance_decided(key))(ex.suffix(i).suffix(d)));
+                }
+            };
+        };
+    }
+}
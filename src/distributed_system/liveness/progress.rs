@@ -0,0 +1,1662 @@
+use super::{
+    super::{Event, Value},
+    enabled, host_action, host_action_is_fair, is_behavior, is_quorum, leader_is_stuck, leader_times_out,
+    instance_decided, instance_decided_is_stable, leader_times_out_is_fair, leads, liveness_assumptions, messages_are_delivered, only_leader_prepares, quorum_is_up,
+    quorum_knows_configuration, runs, takes_step, Execution, Handler, StatePredicate,
+    rules::{
+        action_holds_from_some_step, all_goals, all_reach_goals, done_or, holds_until_done, nonincreasing_stabilizes, reached,
+        state_holds_from_some_step, wf1,
+    },
+    steps::{
+        accept_is_enabled, accepted_is_enabled, behavior_is_live_inductive, init_request_is_enabled, leader_times_out_is_enabled,
+        learn_is_enabled, live_inductive, nacked_is_enabled, next_is_monotone, promise_is_enabled, promised_is_enabled,
+        raise_to_floor_is_enabled, reject_prepare_is_enabled, send_accept_is_enabled,
+    },
+};
+use crate::distributed_system::low_level::{
+    host, host_next, inductive, message_next, network, network_only_next, quorum, next_is_host_next_or_network_only_next, some_host_decided,
+    Constants, Message, NetworkOperation, Transition, Variables, is_valid_transition, reconfig,
+};
+use vstd::{prelude::*, set_lib::*};
+
+verus! {
+    // The leader's newest ballot for `key`: the one it drives, or the one it prepares above next.
+    pub open spec fn top(u: Variables, leader: nat, key: nat) -> host::Ballot {
+        u.hosts[leader as int].instances[key].max_seen_ballot
+    }
+
+    pub open spec fn current(u: Variables, h: nat, key: nat) -> host::Ballot {
+        u.hosts[h as int].instances[key].current_ballot
+    }
+
+    pub open spec fn has_instance(h: nat, key: nat) -> StatePredicate {
+        |u: Variables| u.hosts[h as int].instances.contains_key(key)
+    }
+
+    // Host `h` has instance `key` at or above its floor, so its acceptor steps can run.
+    pub open spec fn at_floor(h: nat, key: nat) -> StatePredicate {
+        |u: Variables|
+            u.hosts[h as int].instances.contains_key(key) &&
+            current(u, h, key).cmp(&host::floor_for(&u.hosts[h as int], key)) >= 0
+    }
+
+    pub open spec fn caught_up(h: nat, key: nat, b: host::Ballot) -> StatePredicate {
+        |u: Variables| at_floor(h, key)(u) && current(u, h, key).cmp(&b) >= 0
+    }
+
+    // The promises the leader has counted for `b`, kept in `history` so they outlive a crash.
+    pub open spec fn promises(u: Variables, leader: nat, key: nat, b: host::Ballot) -> Set<nat> {
+        u.history[leader as int][key].promised[b].dom()
+    }
+
+    pub open spec fn acceptances(u: Variables, leader: nat, key: nat, b: host::Ballot) -> Set<nat> {
+        u.history[leader as int][key].accepted[b]
+    }
+
+    pub open spec fn proposed(u: Variables, leader: nat, key: nat, b: host::Ballot) -> bool {
+        u.hosts[leader as int].instances[key].volatile.proposed_value.contains_key(b)
+    }
+
+    // The quorums the leader counts promises and votes for `key` in.
+    pub open spec fn classic_quorums(u: Variables, leader: nat, key: nat) -> quorum::QuorumSystem {
+        u.hosts[leader as int].instances[key].configuration.classic
+    }
+
+    // The leader is working on `b`, its newest ballot, and nobody has decided yet.
+    pub open spec fn driving(leader: nat, key: nat, b: host::Ballot) -> StatePredicate {
+        |u: Variables| !some_host_decided(&u, key) && top(u, leader, key) == b && leads(u, leader, key, b)
+    }
+
+    pub open spec fn proposing(leader: nat, key: nat, b: host::Ballot) -> StatePredicate {
+        |u: Variables| driving(leader, key, b)(u) && proposed(u, leader, key, b)
+    }
+
+    // Driving `b` ends either way: the instance is decided, or the leader has heard of a higher ballot.
+    pub open spec fn moved_past(leader: nat, key: nat, b: host::Ballot) -> StatePredicate {
+        |u: Variables| some_host_decided(&u, key) || (u.hosts[leader as int].instances.contains_key(key) && top(u, leader, key).cmp(&b) > 0)
+    }
+
+    pub open spec fn beyond(leader: nat, key: nat, bound: nat) -> StatePredicate {
+        |u: Variables| some_host_decided(&u, key) || (u.hosts[leader as int].instances.contains_key(key) && top(u, leader, key).num > bound)
+    }
+
+    pub open spec fn answered_leader(leader: nat, key: nat, b: host::Ballot, h: nat) -> StatePredicate {
+        |u: Variables| promises(u, leader, key, b).contains(h) || proposed(u, leader, key, b)
+    }
+
+    pub open spec fn accepted_leader(leader: nat, key: nat, b: host::Ballot, h: nat) -> StatePredicate {
+        |u: Variables| acceptances(u, leader, key, b).contains(h)
+    }
+
+    pub open spec fn prepared_num(m: Message) -> nat {
+        match m {
+            Message::Prepare { ballot, .. } => ballot.num,
+            Message::PrepareFrom { ballot, .. } => ballot.num,
+            _ => 0,
+        }
+    }
+
+    pub open spec fn prepared_below(u: Variables, key: nat, bound: nat) -> bool {
+        forall |x: host::Ballot| #[trigger] u.ballot_was_prepared(key, x) ==> x.num <= bound
+    }
+
+    // Every ballot prepared for `key` is an old one, at most `bound`, or one the leader has already
+    // moved its `max_seen_ballot` to.
+    pub open spec fn new_ballots_are_the_leaders(leader: nat, key: nat, bound: nat) -> StatePredicate {
+        |u: Variables|
+            forall |x: host::Ballot| #[trigger] u.ballot_was_prepared(key, x) ==>
+                x.num <= bound || x.cmp(&top(u, leader, key)) <= 0
+    }
+
+    pub open spec fn raises_floor(m: Message, floor: host::Ballot) -> bool {
+        match m {
+            Message::PrepareFrom { ballot, .. } => ballot.cmp(&floor) > 0,
+            _ => false,
+        }
+    }
+
+    // The sent PrepareFroms that host `h` could still take as a new floor.
+    pub open spec fn floor_raisers(u: Variables, h: nat) -> Set<Message> {
+        u.network.sent_messages.filter(|m: Message| raises_floor(m, u.hosts[h as int].multi.promise_floor))
+    }
+
+    pub open spec fn floor_is_fixed(ex: Execution, h: nat, n: nat, j: nat) -> bool {
+        &&& (ex.states)(j).hosts[h as int].multi.promise_floor == (ex.states)(n).hosts[h as int].multi.promise_floor
+        &&& (ex.states)(j).hosts[h as int].multi.floor_from == (ex.states)(n).hosts[h as int].multi.floor_from
+    }
+
+    // From step `n` on every `eventually(always(..))` assumption holds and no floor moves any more.
+    pub open spec fn settled(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, n: nat) -> bool {
+        &&& liveness_assumptions(c, ex, leader, quorum)
+        &&& forall |j: nat| n <= j ==> #[trigger] only_leader_prepares(c, leader)((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j))
+        &&& forall |j: nat| n <= j ==> #[trigger] messages_are_delivered()((ex.states)(j))
+        &&& forall |j: nat| n <= j ==> #[trigger] quorum_is_up(quorum)((ex.states)(j))
+        &&& forall |h: nat, j: nat| h < c.num_hosts && n <= j ==> #[trigger] floor_is_fixed(ex, h, n, j)
+    }
+
+    // From step `n` on the quorum knows the configuration of `key`.
+    pub open spec fn quorum_knows_configuration_from(c: &Constants, ex: Execution, quorum: Set<nat>, key: nat, n: nat) -> bool {
+        forall |j: nat| n <= j ==> #[trigger] quorum_knows_configuration(c, quorum, key)((ex.states)(j))
+    }
+
+    pub proof fn prepared_ballots_are_bounded(s: Set<Message>) -> (bound: nat)
+    requires
+        s.finite(),
+    ensures
+        forall |m: Message| #[trigger] s.contains(m) ==> prepared_num(m) <= bound,
+    decreases
+        s.len()
+    {
+        if (s.len() == 0) {
+            s.lemma_len0_is_empty();
+            0
+        } else {
+            let m = s.choose();
+            let rest = prepared_ballots_are_bounded(s.remove(m));
+            let bound = if (prepared_num(m) > rest) { prepared_num(m) } else { rest };
+            assert forall |x: Message| #[trigger] s.contains(x) implies prepared_num(x) <= bound by {
+                if (x != m) {
+                    assert(s.remove(m).contains(x));
+                }
+            };
+            bound
+        }
+    }
+
+    pub proof fn prepared_ballots_of_state_are_bounded(u: Variables, key: nat) -> (bound: nat)
+    requires
+        u.network.sent_messages.finite(),
+    ensures
+        prepared_below(u, key, bound),
+    {
+        let bound = prepared_ballots_are_bounded(u.network.sent_messages);
+        assert forall |x: host::Ballot| #[trigger] u.ballot_was_prepared(key, x) implies x.num <= bound by {
+            if (u.network.sent_messages.contains(Message::Prepare { key, ballot: x })) {
+                assert(prepared_num(Message::Prepare { key, ballot: x }) == x.num);
+            } else {
+                let from = choose |from: nat| from <= key && #[trigger] u.network.sent_messages.contains(Message::PrepareFrom { from, ballot: x });
+                assert(prepared_num(Message::PrepareFrom { from, ballot: x }) == x.num);
+            }
+        };
+        bound
+    }
+
+    // Of the host steps only `send_prepare` sends a Prepare, and only `send_prepare_from` a PrepareFrom;
+    // `init_request` and `raise_to_floor` send a Promise built by `promise_msg`.
+    pub proof fn prepares_are_sent_by_their_handlers(c: &host::Constants, u: &host::Variables, v: &host::Variables, key: nat, net_op: NetworkOperation, event: Event)
+    requires
+        host::step(c, u, v, key, net_op, event),
+    ensures
+        net_op.send is Some && net_op.send.unwrap() is Prepare ==> host::send_prepare(c, u, v, key, net_op),
+        net_op.send is Some && net_op.send.unwrap() is PrepareFrom ==> host::send_prepare_from(c, u, v, key, net_op),
+    {
+        if (host::init_request(c, u, v, key, net_op) && net_op.send is Some) {
+            assert(net_op.send.unwrap() is Promise);
+        }
+        if (host::raise_to_floor(c, u, v, key, net_op) && net_op.send is Some) {
+            assert(net_op.send.unwrap() is Promise);
+        }
+    }
+
+    // Once only the leader prepares, a ballot newly prepared for `key` is the one the leader just
+    // moved to from an instance it was stuck in.
+    pub proof fn new_prepare_is_the_leaders(c: &Constants, u: &Variables, v: &Variables, event: Event, leader: nat, key: nat, x: host::Ballot)
+    requires
+        inductive(c, u),
+        message_next(c, u, v, event),
+        only_leader_prepares(c, leader)(*u, *v, event),
+        !u.ballot_was_prepared(key, x),
+        v.ballot_was_prepared(key, x),
+    ensures
+        leader_is_stuck(leader, key)(*u),
+        v.hosts[leader as int].instances.contains_key(key),
+        x == top(*v, leader, key),
+        leads(*v, leader, key, x),
+    {
+        next_is_host_next_or_network_only_next(c, u, v, event);
+        if (!host_next(c, u, v, event)) {
+            assert(network_only_next(c, u, v, event));
+            u.ballot_was_prepared_is_stable(v, key, x);
+        }
+        let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, v, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+        let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id]);
+        prepares_are_sent_by_their_handlers(lc, lu, lv, step_key, net_op, event);
+        assert(!host::send_prepare_from(lc, lu, lv, step_key, net_op));
+
+        if (v.network.sent_messages.contains(Message::Prepare { key, ballot: x })) {
+            assert(!u.network.sent_messages.contains(Message::Prepare { key, ballot: x }));
+            assert(net_op.send == Some(Message::Prepare { key, ballot: x }));
+        } else {
+            let from = choose |from: nat| from <= key && #[trigger] v.network.sent_messages.contains(Message::PrepareFrom { from, ballot: x });
+            assert(!u.network.sent_messages.contains(Message::PrepareFrom { from, ballot: x }));
+            assert(net_op.send == Some(Message::PrepareFrom { from, ballot: x }));
+            assert(false);
+        }
+
+        assert(host::send_prepare(lc, lu, lv, step_key, net_op));
+        assert(host_id == leader && leader_is_stuck(leader, key)(*u));
+        assert(step_key == key && lc.id == leader);
+        assert(lv.instances[key].max_seen_ballot == x);
+        assert(lv.instances[key].volatile.promised.contains_key(x));
+    }
+
+    // Only `promise_from` moves a host's floor, and it moves it to a PrepareFrom that was raising it.
+    pub proof fn floor_raisers_shrink(c: &Constants, u: &Variables, v: &Variables, event: Event, leader: nat, h: nat)
+    requires
+        inductive(c, u),
+        message_next(c, u, v, event),
+        only_leader_prepares(c, leader)(*u, *v, event),
+        h < u.hosts.len(),
+    ensures
+        floor_raisers(*v, h).subset_of(floor_raisers(*u, h)),
+        floor_raisers(*v, h).len() <= floor_raisers(*u, h).len(),
+        v.hosts[h as int].multi.promise_floor != u.hosts[h as int].multi.promise_floor ==>
+            floor_raisers(*v, h).len() < floor_raisers(*u, h).len(),
+    {
+        next_is_monotone(c, u, v, event);
+        next_is_host_next_or_network_only_next(c, u, v, event);
+        let (old, new) = (u.hosts[h as int].multi.promise_floor, v.hosts[h as int].multi.promise_floor);
+        assert(new.cmp(&old) >= 0);
+
+        if (host_next(c, u, v, event)) {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, v, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id]);
+            prepares_are_sent_by_their_handlers(lc, lu, lv, step_key, net_op, event);
+            assert(!host::send_prepare_from(lc, lu, lv, step_key, net_op));
+
+            assert forall |m: Message| #[trigger] floor_raisers(*v, h).contains(m) implies floor_raisers(*u, h).contains(m) by {
+                assert(m is PrepareFrom);
+                if (!u.network.sent_messages.contains(m)) {
+                    assert(net_op.send == Some(m));
+                }
+            };
+            lemma_len_subset(floor_raisers(*v, h), floor_raisers(*u, h));
+
+            if (host_id == h && host::promise_from(lc, lu, lv, step_key, net_op)) {
+                if let Some(Message::PrepareFrom { from, ballot }) = net_op.recv {
+                    let taken = Message::PrepareFrom { from, ballot };
+                    assert(u.network.sent_messages.contains(taken));
+                    assert(raises_floor(taken, old) && !raises_floor(taken, new));
+                    assert(floor_raisers(*v, h).subset_of(floor_raisers(*u, h).remove(taken)));
+                    lemma_len_subset(floor_raisers(*v, h), floor_raisers(*u, h).remove(taken));
+                }
+            } else if (host_id == h) {
+                assert(lv.multi.promise_floor == lu.multi.promise_floor);
+            } else {
+                assert(v.hosts[h as int] == u.hosts[h as int]);
+            }
+        } else {
+            assert(v.hosts[h as int] == u.hosts[h as int]);
+            assert(floor_raisers(*v, h) =~= floor_raisers(*u, h));
+        }
+    }
+
+    pub proof fn take_step(c: &Constants, ex: Execution, j: nat)
+    requires
+        is_behavior(c, ex),
+    ensures
+        live_inductive(c, &(ex.states)(j)),
+        live_inductive(c, &(ex.states)((j + 1) as nat)),
+        message_next(c, &(ex.states)(j), &(ex.states)((j + 1) as nat), (ex.events)(j)),
+    {
+        behavior_is_live_inductive(c, ex, j);
+        behavior_is_live_inductive(c, ex, (j + 1) as nat);
+        assert(takes_step(c, ex, j));
+    }
+
+    proof fn floor_stays_fixed(c: &Constants, ex: Execution, leader: nat, h: nat, start: nat, n: nat, j: nat)
+    requires
+        is_behavior(c, ex),
+        h < c.num_hosts,
+        start <= n <= j,
+        forall |m: nat| start <= m ==> #[trigger] only_leader_prepares(c, leader)((ex.states)(m), (ex.states)((m + 1) as nat), (ex.events)(m)),
+        forall |m: nat| n <= m ==> #[trigger] floor_raisers((ex.states)(m), h).len() == floor_raisers((ex.states)(n), h).len(),
+    ensures
+        floor_is_fixed(ex, h, n, j),
+    decreases
+        j - n
+    {
+        if (n < j) {
+            let prev = (j - 1) as nat;
+            floor_stays_fixed(c, ex, leader, h, start, n, prev);
+            take_step(c, ex, prev);
+            assert(only_leader_prepares(c, leader)((ex.states)(prev), (ex.states)(j), (ex.events)(prev)));
+            floor_raisers_shrink(c, &(ex.states)(prev), &(ex.states)(j), (ex.events)(prev), leader, h);
+            assert(floor_raisers((ex.states)(j), h).len() == floor_raisers((ex.states)(prev), h).len());
+            next_is_monotone(c, &(ex.states)(prev), &(ex.states)(j), (ex.events)(prev));
+            assert((ex.states)(prev).hosts[h as int].multi == (ex.states)(prev).hosts[h as int].multi);
+        }
+    }
+
+    // A host's floor only rises, each time to a PrepareFrom it had not taken yet, and once only the
+    // leader prepares no new PrepareFrom is sent: it can rise only finitely often.
+    pub proof fn floor_stabilizes(c: &Constants, ex: Execution, leader: nat, h: nat, start: nat) -> (n: nat)
+    requires
+        is_behavior(c, ex),
+        h < c.num_hosts,
+        forall |m: nat| start <= m ==> #[trigger] only_leader_prepares(c, leader)((ex.states)(m), (ex.states)((m + 1) as nat), (ex.events)(m)),
+    ensures
+        start <= n,
+        forall |j: nat| n <= j ==> #[trigger] floor_is_fixed(ex, h, n, j),
+    {
+        let f = |m: nat| floor_raisers((ex.states)(m), h).len();
+        assert forall |m: nat| start <= m implies #[trigger] f((m + 1) as nat) <= f(m) by {
+            take_step(c, ex, m);
+            floor_raisers_shrink(c, &(ex.states)(m), &(ex.states)((m + 1) as nat), (ex.events)(m), leader, h);
+        };
+        let n = nonincreasing_stabilizes(f, start);
+        assert forall |j: nat| n <= j implies #[trigger] floor_is_fixed(ex, h, n, j) by {
+            assert forall |m: nat| n <= m implies #[trigger] floor_raisers((ex.states)(m), h).len() == floor_raisers((ex.states)(n), h).len() by {
+                assert(f(m) == f(n));
+            };
+            floor_stays_fixed(c, ex, leader, h, start, n, j);
+        };
+        n
+    }
+
+    pub proof fn floors_stabilize(c: &Constants, ex: Execution, leader: nat, k: nat, start: nat) -> (n: nat)
+    requires
+        is_behavior(c, ex),
+        k <= c.num_hosts,
+        forall |m: nat| start <= m ==> #[trigger] only_leader_prepares(c, leader)((ex.states)(m), (ex.states)((m + 1) as nat), (ex.events)(m)),
+    ensures
+        start <= n,
+        forall |h: nat, j: nat| h < k && n <= j ==> #[trigger] floor_is_fixed(ex, h, n, j),
+    decreases
+        k
+    {
+        if (k == 0) {
+            start
+        } else {
+            let before = floors_stabilize(c, ex, leader, (k - 1) as nat, start);
+            let n = floor_stabilizes(c, ex, leader, (k - 1) as nat, before);
+            assert forall |h: nat, j: nat| h < k && n <= j implies #[trigger] floor_is_fixed(ex, h, n, j) by {
+                if (h < k - 1) {
+                    assert(floor_is_fixed(ex, h, before, j));
+                    assert(floor_is_fixed(ex, h, before, n));
+                }
+            };
+            n
+        }
+    }
+
+    // The step from which everything the liveness assumptions promise only eventually holds for good.
+    pub proof fn liveness_assumptions_settle(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>) -> (n: nat)
+    requires
+        liveness_assumptions(c, ex, leader, quorum),
+    ensures
+        settled(c, ex, leader, quorum, n),
+    {
+        let n1 = action_holds_from_some_step(only_leader_prepares(c, leader), ex);
+        let n2 = state_holds_from_some_step(messages_are_delivered(), ex);
+        let n3 = state_holds_from_some_step(quorum_is_up(quorum), ex);
+        let a = if (n1 > n2) { n1 } else { n2 };
+        let start = if (a > n3) { a } else { n3 };
+        assert forall |m: nat| start <= m implies #[trigger] only_leader_prepares(c, leader)((ex.states)(m), (ex.states)((m + 1) as nat), (ex.events)(m)) by {};
+        behavior_is_live_inductive(c, ex, 0);
+        floors_stabilize(c, ex, leader, c.num_hosts, start)
+    }
+
+    // Multi-step version of `next_is_monotone` for one instance.
+    pub proof fn instance_is_monotone(c: &Constants, ex: Execution, h: nat, key: nat, j: nat, k: nat)
+    requires
+        is_behavior(c, ex),
+        h < c.num_hosts,
+        j <= k,
+        (ex.states)(j).hosts[h as int].instances.contains_key(key),
+    ensures
+        (ex.states)(k).hosts[h as int].instances.contains_key(key),
+        current((ex.states)(k), h, key).cmp(&current((ex.states)(j), h, key)) >= 0,
+        top((ex.states)(k), h, key).cmp(&top((ex.states)(j), h, key)) >= 0,
+    decreases
+        k - j
+    {
+        if (j < k) {
+            let prev = (k - 1) as nat;
+            instance_is_monotone(c, ex, h, key, j, prev);
+            take_step(c, ex, prev);
+            next_is_monotone(c, &(ex.states)(prev), &(ex.states)(k), (ex.events)(prev));
+            assert((ex.states)(prev).hosts[h as int].instances[key] == (ex.states)(prev).hosts[h as int].instances[key]);
+        }
+    }
+
+    // Decisions are never taken back, so once the quorum knows the configuration of `key` it keeps
+    // knowing it.
+    pub proof fn configuration_knowledge_lasts(c: &Constants, ex: Execution, quorum: Set<nat>, key: nat, i: nat, j: nat)
+    requires
+        is_behavior(c, ex),
+        forall |h: nat| #[trigger] quorum.contains(h) ==> h < c.num_hosts,
+        quorum_knows_configuration(c, quorum, key)((ex.states)(i)),
+        i <= j,
+    ensures
+        quorum_knows_configuration(c, quorum, key)((ex.states)(j)),
+    decreases
+        j - i
+    {
+        if (i < j) {
+            let prev = (j - 1) as nat;
+            let (u, v) = ((ex.states)(prev), (ex.states)(j));
+            configuration_knowledge_lasts(c, ex, quorum, key, i, prev);
+            take_step(c, ex, prev);
+            next_is_monotone(c, &u, &v, (ex.events)(prev));
+            assert forall |h: nat| #[trigger] quorum.contains(h) implies c.hosts[h as int].knows_configuration(&v.hosts[h as int], key) by {
+                assert forall |k: nat| k + c.reconfig.alpha <= key implies #[trigger] v.hosts[h as int].decisions().contains_key(k) by {
+                    assert(c.hosts[h as int].knows_configuration(&u.hosts[h as int], key));
+                    assert(u.hosts[h as int].decisions().contains_key(k));
+                    assert(u.hosts[h as int].instances[k] == u.hosts[h as int].instances[k]);
+                };
+            };
+        }
+    }
+
+    pub proof fn settled_later(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, n: nat, m: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        n <= m,
+    ensures
+        settled(c, ex, leader, quorum, m),
+    {
+        assert forall |h: nat, j: nat| h < c.num_hosts && m <= j implies #[trigger] floor_is_fixed(ex, h, m, j) by {
+            assert(floor_is_fixed(ex, h, n, m));
+            assert(floor_is_fixed(ex, h, n, j));
+        };
+    }
+
+    // The quorum of `is_quorum` counts in every configuration: the initial one and that of every
+    // command.
+    pub proof fn quorum_counts_in_configuration(c: &Constants, quorum: Set<nat>, decisions: Map<nat, Value>, key: nat)
+    requires
+        is_quorum(c, quorum),
+    ensures
+        c.reconfig.configuration(c.fast_quorums(), c.num_failures, decisions, key).classic.is_phase1_quorum(quorum),
+        c.reconfig.configuration(c.fast_quorums(), c.num_failures, decisions, key).classic.is_phase2_quorum(quorum),
+    decreases
+        key
+    {
+        if (key > 0 && key >= c.reconfig.alpha) {
+            let j = (key - c.reconfig.alpha) as nat;
+
+            if (decisions.contains_key(j) && (c.reconfig.members)(decisions[j]).is_some()) {
+                assert((c.reconfig.members)(decisions[j]).is_some());
+            } else {
+                quorum_counts_in_configuration(c, quorum, decisions, (key - 1) as nat);
+            }
+        }
+    }
+
+    pub proof fn quorum_counts_in_instance(c: &Constants, u: &Variables, quorum: Set<nat>, h: nat, key: nat)
+    requires
+        inductive(c, u),
+        is_quorum(c, quorum),
+        h < u.hosts.len(),
+        u.hosts[h as int].instances.contains_key(key),
+    ensures
+        classic_quorums(*u, h, key).well_formed(c.num_hosts),
+        classic_quorums(*u, h, key).is_phase1_quorum(quorum),
+        classic_quorums(*u, h, key).is_phase2_quorum(quorum),
+    {
+        assert(u.configurations.contains_key(key) && u.hosts[h as int].instances[key].configuration == u.configurations[key]);
+        assert(reconfig::is_valid_configuration(u.configurations[key], c.num_hosts, c.num_failures));
+        let decisions = choose |decisions: Map<nat, Value>|
+            #[trigger] u.decisions_were_sent(c, decisions, key) &&
+            u.configurations[key] == c.reconfig.configuration(c.fast_quorums(), c.num_failures, decisions, key);
+        quorum_counts_in_configuration(c, quorum, decisions, key);
+    }
+
+    // What `settled` says about step `j`, spelled out for host `h`.
+    pub proof fn settled_at(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, n: nat, j: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        n <= j,
+    ensures
+        live_inductive(c, &(ex.states)(j)),
+        (ex.states)(j).hosts.len() == c.num_hosts,
+        !c.network.consume_on_receive,
+        is_quorum(c, quorum),
+        leader < c.num_hosts,
+        c.proposers.contains(leader),
+        forall |h: nat| #[trigger] quorum.contains(h) ==> h < c.num_hosts && (ex.states)(j).hosts[h as int].up,
+        forall |h: nat| #[trigger] quorum.contains(h) ==> c.hosts[h as int].is_acceptor(),
+        only_leader_prepares(c, leader)((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)),
+        forall |m: Message|
+            #[trigger] (ex.states)(j).network.sent_messages.contains(m) &&
+            network::message_key(m).is_some() &&
+            !some_host_decided(&(ex.states)(j), network::message_key(m).unwrap()) ==>
+            (ex.states)(j).network.in_flight_messages.contains(m),
+    {
+        take_step(c, ex, j);
+        assert(quorum_is_up(quorum)((ex.states)(j)));
+        assert(messages_are_delivered()((ex.states)(j)));
+        assert forall |h: nat| #[trigger] quorum.contains(h) implies c.hosts[h as int].is_acceptor() by {
+            assert(c.quorums.acceptors.contains(h));
+            assert(c.hosts[h as int].id == h && c.hosts[h as int].quorums == c.quorums);
+        };
+    }
+
+    // Between two steps from `n` on: the floor of every host is the same, and the hosts in the quorum
+    // stay up, so they keep what `next_is_monotone` says up hosts keep.
+    pub proof fn settled_step(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, n: nat, j: nat, h: nat, key: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        n <= j,
+        h < c.num_hosts,
+    ensures
+        host::floor_for(&(ex.states)((j + 1) as nat).hosts[h as int], key) == host::floor_for(&(ex.states)(j).hosts[h as int], key),
+        host::floor_for(&(ex.states)(j).hosts[h as int], key) == host::floor_for(&(ex.states)(n).hosts[h as int], key),
+    {
+        assert(floor_is_fixed(ex, h, n, j));
+        assert(floor_is_fixed(ex, h, n, (j + 1) as nat));
+    }
+
+    pub proof fn leader_is_in_quorum(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, n: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+    ensures
+        quorum.contains(leader),
+        leader < c.num_hosts,
+    {
+        settled_at(c, ex, leader, quorum, n, n);
+    }
+
+    // Driving a ballot lasts until it is over: the leader stays up, so it keeps the ballot's promises
+    // in memory, and its `max_seen_ballot` never goes down.
+    pub proof fn driving_lasts(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, n: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+    ensures
+        forall |m: nat| n <= m && #[trigger] driving(leader, key, b)((ex.states)(m)) ==>
+            driving(leader, key, b)((ex.states)((m + 1) as nat)) || moved_past(leader, key, b)((ex.states)((m + 1) as nat)),
+        forall |m: nat| n <= m && #[trigger] moved_past(leader, key, b)((ex.states)(m)) ==>
+            moved_past(leader, key, b)((ex.states)((m + 1) as nat)),
+    {
+        leader_is_in_quorum(c, ex, leader, quorum, n);
+        assert forall |m: nat| n <= m && #[trigger] driving(leader, key, b)((ex.states)(m)) implies
+            driving(leader, key, b)((ex.states)((m + 1) as nat)) || moved_past(leader, key, b)((ex.states)((m + 1) as nat)) by {
+            let (u, v) = ((ex.states)(m), (ex.states)((m + 1) as nat));
+            settled_at(c, ex, leader, quorum, n, m);
+            settled_at(c, ex, leader, quorum, n, (m + 1) as nat);
+            take_step(c, ex, m);
+            next_is_monotone(c, &u, &v, (ex.events)(m));
+            assert(u.hosts[leader as int].instances[key] == u.hosts[leader as int].instances[key]);
+            assert(u.hosts[leader as int].instances[key].volatile.promised[b] == u.hosts[leader as int].instances[key].volatile.promised[b]);
+            assert(v.network.sent_messages.contains(Message::Prepare { key, ballot: b }));
+        };
+        assert forall |m: nat| n <= m && #[trigger] moved_past(leader, key, b)((ex.states)(m)) implies
+            moved_past(leader, key, b)((ex.states)((m + 1) as nat)) by {
+            let (u, v) = ((ex.states)(m), (ex.states)((m + 1) as nat));
+            take_step(c, ex, m);
+            next_is_monotone(c, &u, &v, (ex.events)(m));
+            if (some_host_decided(&u, key)) {
+                instance_decided_is_stable(c, &u, &v, (ex.events)(m), key);
+            } else {
+                assert(u.hosts[leader as int].instances[key] == u.hosts[leader as int].instances[key]);
+            }
+        };
+    }
+
+    // `driving` holds all the way to a step at which the ballot is not over yet.
+    pub proof fn driving_holds_until(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, n: nat, i: nat, j: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        n <= i <= j,
+        driving(leader, key, b)((ex.states)(i)),
+        !moved_past(leader, key, b)((ex.states)(j)),
+    ensures
+        driving(leader, key, b)((ex.states)(j)),
+    {
+        driving_lasts(c, ex, leader, quorum, key, b, n);
+        holds_until_done(ex, driving(leader, key, b), moved_past(leader, key, b), n, i, j);
+    }
+
+    // The value the leader proposed under `b` stays proposed, as long as it stays up.
+    pub open spec fn proposing_value(leader: nat, key: nat, b: host::Ballot, value: Value) -> StatePredicate {
+        |u: Variables|
+            proposing(leader, key, b)(u) &&
+            u.hosts[leader as int].instances[key].volatile.proposed_value[b] == value
+    }
+
+    pub proof fn proposing_lasts(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, value: Value, n: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+    ensures
+        forall |m: nat| n <= m && #[trigger] proposing_value(leader, key, b, value)((ex.states)(m)) ==>
+            proposing_value(leader, key, b, value)((ex.states)((m + 1) as nat)) || moved_past(leader, key, b)((ex.states)((m + 1) as nat)),
+    {
+        driving_lasts(c, ex, leader, quorum, key, b, n);
+        leader_is_in_quorum(c, ex, leader, quorum, n);
+        assert forall |m: nat| n <= m && #[trigger] proposing_value(leader, key, b, value)((ex.states)(m)) implies
+            proposing_value(leader, key, b, value)((ex.states)((m + 1) as nat)) || moved_past(leader, key, b)((ex.states)((m + 1) as nat)) by {
+            let (u, v) = ((ex.states)(m), (ex.states)((m + 1) as nat));
+            assert(driving(leader, key, b)(u));
+            settled_at(c, ex, leader, quorum, n, m);
+            settled_at(c, ex, leader, quorum, n, (m + 1) as nat);
+            take_step(c, ex, m);
+            next_is_monotone(c, &u, &v, (ex.events)(m));
+            assert(u.hosts[leader as int].instances[key].volatile.proposed_value[b] == value);
+        };
+    }
+
+    pub proof fn proposing_holds_until(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, value: Value, n: nat, i: nat, j: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        n <= i <= j,
+        proposing_value(leader, key, b, value)((ex.states)(i)),
+        !moved_past(leader, key, b)((ex.states)(j)),
+    ensures
+        proposing_value(leader, key, b, value)((ex.states)(j)),
+    {
+        driving_lasts(c, ex, leader, quorum, key, b, n);
+        proposing_lasts(c, ex, leader, quorum, key, b, value, n);
+        holds_until_done(ex, proposing_value(leader, key, b, value), moved_past(leader, key, b), n, i, j);
+    }
+
+    // Acceptor `h` stays at or above its floor, and above any ballot it has reached: its floor no
+    // longer moves and its `current_ballot` never goes down.
+    pub proof fn caught_up_lasts(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, n: nat, h: nat, key: nat, b: host::Ballot, i: nat, j: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum.contains(h),
+        n <= i <= j,
+        caught_up(h, key, b)((ex.states)(i)),
+    ensures
+        caught_up(h, key, b)((ex.states)(j)),
+        current((ex.states)(j), h, key).cmp(&current((ex.states)(i), h, key)) >= 0,
+    {
+        settled_at(c, ex, leader, quorum, n, i);
+        instance_is_monotone(c, ex, h, key, i, j);
+        settled_step(c, ex, leader, quorum, n, i, h, key);
+        settled_step(c, ex, leader, quorum, n, j, h, key);
+    }
+
+    pub proof fn at_floor_lasts(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, n: nat, h: nat, key: nat, i: nat, j: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum.contains(h),
+        n <= i <= j,
+        at_floor(h, key)((ex.states)(i)),
+    ensures
+        at_floor(h, key)((ex.states)(j)),
+    {
+        settled_at(c, ex, leader, quorum, n, i);
+        instance_is_monotone(c, ex, h, key, i, j);
+        settled_step(c, ex, leader, quorum, n, i, h, key);
+        settled_step(c, ex, leader, quorum, n, j, h, key);
+    }
+
+    // The host step behind a `host_action`.
+    pub proof fn host_action_step(c: &Constants, u: Variables, v: Variables, event: Event, h: nat, key: nat, handler: Handler, recv: Option<Message>) -> (net_op: NetworkOperation)
+    requires
+        host_action(c, h, key, handler, recv)(u, v, event),
+    ensures
+        net_op.recv == recv,
+        runs(handler, &c.hosts[h as int], &u.hosts[h as int], &v.hosts[h as int], key, net_op, event),
+        is_valid_transition(c, &u, &v, Transition::HostStep { host_id: h as int, instance: key, net_op }, event),
+    {
+        choose |net_op: NetworkOperation| #![trigger is_valid_transition(c, &u, &v, Transition::HostStep { host_id: h as int, instance: key, net_op }, event)]
+            net_op.recv == recv &&
+            runs(handler, &c.hosts[h as int], &u.hosts[h as int], &v.hosts[h as int], key, net_op, event) &&
+            is_valid_transition(c, &u, &v, Transition::HostStep { host_id: h as int, instance: key, net_op }, event)
+    }
+
+    pub open spec fn lacks_instance(h: nat, key: nat) -> StatePredicate {
+        |u: Variables| !u.hosts[h as int].instances.contains_key(key)
+    }
+
+    pub open spec fn below_floor(h: nat, key: nat) -> StatePredicate {
+        |u: Variables|
+            u.hosts[h as int].instances.contains_key(key) &&
+            current(u, h, key).cmp(&host::floor_for(&u.hosts[h as int], key)) < 0
+    }
+
+    // Acceptor `h` has not reached the ballot the leader is driving.
+    pub open spec fn behind(leader: nat, key: nat, b: host::Ballot, h: nat) -> StatePredicate {
+        |u: Variables| driving(leader, key, b)(u) && at_floor(h, key)(u) && current(u, h, key).cmp(&b) < 0
+    }
+
+    pub open spec fn nacked_above(key: nat, b: host::Ballot) -> StatePredicate {
+        |u: Variables| exists |higher: host::Ballot| higher.cmp(&b) > 0 && #[trigger] u.network.sent_messages.contains(Message::Nack { key, ballot: b, higher })
+    }
+
+    // Acceptor `h` is past the ballot the leader is driving and has not told it so yet.
+    pub open spec fn ahead(leader: nat, key: nat, b: host::Ballot, h: nat) -> StatePredicate {
+        |u: Variables|
+            driving(leader, key, b)(u) && at_floor(h, key)(u) && current(u, h, key).cmp(&b) > 0 &&
+            !nacked_above(key, b)(u)
+    }
+
+    pub open spec fn nack_pending(leader: nat, key: nat, b: host::Ballot, higher: host::Ballot) -> StatePredicate {
+        |u: Variables| driving(leader, key, b)(u) && u.network.sent_messages.contains(Message::Nack { key, ballot: b, higher })
+    }
+
+    pub open spec fn promise_pending(leader: nat, key: nat, b: host::Ballot, h: nat, accepted: Option<(host::Ballot, Value)>) -> StatePredicate {
+        |u: Variables|
+            driving(leader, key, b)(u) &&
+            u.network.sent_messages.contains(Message::Promise { key, sender: h, ballot: b, accepted }) &&
+            !answered_leader(leader, key, b, h)(u)
+    }
+
+    pub proof fn member_gets_instance(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, n: nat, h: nat, i: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum_knows_configuration_from(c, ex, quorum, key, n),
+        quorum.contains(h),
+        n <= i,
+    ensures
+        reached(ex, i, has_instance(h, key)),
+    {
+        let (p, q) = (lacks_instance(h, key), has_instance(h, key));
+        if (p((ex.states)(i))) {
+            let a = host_action(c, h, key, Handler::InitRequest, None);
+            assert(host_action_is_fair(c, h, key, Handler::InitRequest, None, ex));
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies p((ex.states)((j + 1) as nat)) || q((ex.states)((j + 1) as nat)) by {};
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) && a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)) implies q((ex.states)((j + 1) as nat)) by {
+                host_action_step(c, (ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j), h, key, Handler::InitRequest, None);
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies enabled(a)((ex.states)(j)) by {
+                settled_at(c, ex, leader, quorum, n, j);
+                assert(quorum_knows_configuration(c, quorum, key)((ex.states)(j)));
+                init_request_is_enabled(c, &(ex.states)(j), h, key);
+            };
+            wf1(ex, a, p, q, n, i);
+        } else {
+            assert(q((ex.states)(i)));
+        }
+    }
+
+    pub proof fn member_reaches_floor(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, n: nat, h: nat, i: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum.contains(h),
+        n <= i,
+        has_instance(h, key)((ex.states)(i)),
+    ensures
+        reached(ex, i, at_floor(h, key)),
+    {
+        let (p, q) = (below_floor(h, key), at_floor(h, key));
+        if (p((ex.states)(i))) {
+            let a = host_action(c, h, key, Handler::RaiseToFloor, None);
+            assert(host_action_is_fair(c, h, key, Handler::RaiseToFloor, None, ex));
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies p((ex.states)((j + 1) as nat)) || q((ex.states)((j + 1) as nat)) by {
+                settled_at(c, ex, leader, quorum, n, j);
+                instance_is_monotone(c, ex, h, key, j, (j + 1) as nat);
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) && a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)) implies q((ex.states)((j + 1) as nat)) by {
+                settled_at(c, ex, leader, quorum, n, j);
+                settled_step(c, ex, leader, quorum, n, j, h, key);
+                host_action_step(c, (ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j), h, key, Handler::RaiseToFloor, None);
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies enabled(a)((ex.states)(j)) by {
+                settled_at(c, ex, leader, quorum, n, j);
+                raise_to_floor_is_enabled(c, &(ex.states)(j), h, key);
+            };
+            wf1(ex, a, p, q, n, i);
+        } else {
+            assert(q((ex.states)(i)));
+        }
+    }
+
+    // Every message of instance `key` that was sent is ready for delivery while `key` is undecided.
+    pub proof fn is_delivered(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, n: nat, j: nat, key: nat, m: Message)
+    requires
+        settled(c, ex, leader, quorum, n),
+        n <= j,
+        network::message_key(m) == Some(key),
+        (ex.states)(j).network.sent_messages.contains(m),
+        !some_host_decided(&(ex.states)(j), key),
+    ensures
+        (ex.states)(j).network.in_flight_messages.contains(m),
+    {
+        settled_at(c, ex, leader, quorum, n, j);
+    }
+
+    // While the leader drives `b`, `b` is a live ballot the leader is holding the promises of.
+    pub proof fn driven_ballot_is_held(c: &Constants, u: &Variables, leader: nat, key: nat, b: host::Ballot)
+    requires
+        live_inductive(c, u),
+        leads(*u, leader, key, b),
+    ensures
+        b.num > 0,
+        leader < u.hosts.len(),
+        u.history[leader as int][key].promised.contains_key(b),
+        u.history[leader as int][key].accepted.contains_key(b),
+        u.hosts[leader as int].instances[key].volatile.accepted.contains_key(b),
+        u.history[leader as int][key].promised[b] == u.hosts[leader as int].instances[key].volatile.promised[b],
+        u.history[leader as int][key].accepted[b] == u.hosts[leader as int].instances[key].volatile.accepted[b],
+        promises(*u, leader, key, b).finite(),
+        acceptances(*u, leader, key, b).finite(),
+        u.history[leader as int][key].proposed_value.contains_key(b) <==> proposed(*u, leader, key, b),
+        top(*u, leader, key) == b ==> current(*u, leader, key).cmp(&b) <= 0,
+    {
+        assert(u.network.sent_messages.contains(Message::Prepare { key, ballot: b }));
+        assert(u.hosts[leader as int].instances[key].volatile.promised.contains_key(b));
+    }
+
+    // Acceptor `h` moves up to the ballot the leader drives: the ballot's Prepare reaches it, and
+    // from at or above its floor it promises anything above where it is.
+    pub proof fn member_catches_up(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, n: nat, h: nat, i: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum.contains(h),
+        n <= i,
+        driving(leader, key, b)((ex.states)(i)),
+        at_floor(h, key)((ex.states)(i)),
+    ensures
+        reached(ex, i, done_or(moved_past(leader, key, b), driving(leader, key, b), caught_up(h, key, b))),
+    {
+        let (p, q) = (behind(leader, key, b, h), done_or(moved_past(leader, key, b), driving(leader, key, b), caught_up(h, key, b)));
+        if (p((ex.states)(i))) {
+            let recv = Some(Message::Prepare { key, ballot: b });
+            let a = host_action(c, h, key, Handler::Promise, recv);
+            assert(host_action_is_fair(c, h, key, Handler::Promise, recv, ex));
+            driving_lasts(c, ex, leader, quorum, key, b, n);
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies p((ex.states)((j + 1) as nat)) || q((ex.states)((j + 1) as nat)) by {
+                assert(driving(leader, key, b)((ex.states)(j)));
+                at_floor_lasts(c, ex, leader, quorum, n, h, key, j, (j + 1) as nat);
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) && a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)) implies q((ex.states)((j + 1) as nat)) by {
+                assert(driving(leader, key, b)((ex.states)(j)));
+                settled_at(c, ex, leader, quorum, n, j);
+                settled_step(c, ex, leader, quorum, n, j, h, key);
+                host_action_step(c, (ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j), h, key, Handler::Promise, recv);
+                assert(current((ex.states)((j + 1) as nat), h, key) == b);
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies enabled(a)((ex.states)(j)) by {
+                settled_at(c, ex, leader, quorum, n, j);
+                is_delivered(c, ex, leader, quorum, n, j, key, Message::Prepare { key, ballot: b });
+                promise_is_enabled(c, &(ex.states)(j), h, key, b);
+            };
+            wf1(ex, a, p, q, n, i);
+        } else {
+            assert(q((ex.states)(i)));
+        }
+    }
+
+    // An acceptor past `b` nacks its Prepare, and the Nack raises the leader's `max_seen_ballot`.
+    pub proof fn someone_ahead_nacks(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, n: nat, h: nat, i: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum.contains(h),
+        n <= i,
+        driving(leader, key, b)((ex.states)(i)),
+        at_floor(h, key)((ex.states)(i)),
+        current((ex.states)(i), h, key).cmp(&b) > 0,
+    ensures
+        reached(ex, i, moved_past(leader, key, b)),
+    {
+        driving_lasts(c, ex, leader, quorum, key, b, n);
+        leader_is_in_quorum(c, ex, leader, quorum, n);
+        let sent = done_or(moved_past(leader, key, b), driving(leader, key, b), nacked_above(key, b));
+
+        let j1 = if (nacked_above(key, b)((ex.states)(i))) {
+            assert(sent((ex.states)(i)));
+            i
+        } else {
+            let (p, recv) = (ahead(leader, key, b, h), Some(Message::Prepare { key, ballot: b }));
+            let a = host_action(c, h, key, Handler::RejectPrepare, recv);
+            assert(host_action_is_fair(c, h, key, Handler::RejectPrepare, recv, ex));
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies p((ex.states)((j + 1) as nat)) || sent((ex.states)((j + 1) as nat)) by {
+                assert(driving(leader, key, b)((ex.states)(j)));
+                caught_up_lasts(c, ex, leader, quorum, n, h, key, b, j, (j + 1) as nat);
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) && a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)) implies sent((ex.states)((j + 1) as nat)) by {
+                assert(driving(leader, key, b)((ex.states)(j)));
+                settled_at(c, ex, leader, quorum, n, j);
+                host_action_step(c, (ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j), h, key, Handler::RejectPrepare, recv);
+                let higher = current((ex.states)(j), h, key);
+                assert((ex.states)((j + 1) as nat).network.sent_messages.contains(Message::Nack { key, ballot: b, higher }));
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies enabled(a)((ex.states)(j)) by {
+                settled_at(c, ex, leader, quorum, n, j);
+                is_delivered(c, ex, leader, quorum, n, j, key, Message::Prepare { key, ballot: b });
+                reject_prepare_is_enabled(c, &(ex.states)(j), h, key, b);
+            };
+            wf1(ex, a, p, sent, n, i);
+            choose |j: nat| i <= j && #[trigger] sent((ex.states)(j))
+        };
+
+        if (!moved_past(leader, key, b)((ex.states)(j1))) {
+            let higher = choose |higher: host::Ballot| higher.cmp(&b) > 0 && #[trigger] (ex.states)(j1).network.sent_messages.contains(Message::Nack { key, ballot: b, higher });
+            let (p, q, recv) = (nack_pending(leader, key, b, higher), moved_past(leader, key, b), Some(Message::Nack { key, ballot: b, higher }));
+            let a = host_action(c, leader, key, Handler::Nacked, recv);
+            assert(host_action_is_fair(c, leader, key, Handler::Nacked, recv, ex));
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies p((ex.states)((j + 1) as nat)) || q((ex.states)((j + 1) as nat)) by {
+                assert(driving(leader, key, b)((ex.states)(j)));
+                take_step(c, ex, j);
+                next_is_monotone(c, &(ex.states)(j), &(ex.states)((j + 1) as nat), (ex.events)(j));
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) && a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)) implies q((ex.states)((j + 1) as nat)) by {
+                host_action_step(c, (ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j), leader, key, Handler::Nacked, recv);
+                assert(top((ex.states)((j + 1) as nat), leader, key) == higher);
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies enabled(a)((ex.states)(j)) by {
+                settled_at(c, ex, leader, quorum, n, j);
+                is_delivered(c, ex, leader, quorum, n, j, key, Message::Nack { key, ballot: b, higher });
+                nacked_is_enabled(c, &(ex.states)(j), leader, key, b, higher);
+            };
+            wf1(ex, a, p, q, n, j1);
+        } else {
+            assert(moved_past(leader, key, b)((ex.states)(j1)));
+        }
+    }
+
+    // Acceptor `h` answers the leader's Prepare for `b`: the leader counts its Promise, or has
+    // proposed already. An acceptor past `b` ends the ballot instead.
+    pub proof fn member_answers(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, n: nat, h: nat, i: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum_knows_configuration_from(c, ex, quorum, key, n),
+        quorum.contains(h),
+        n <= i,
+        driving(leader, key, b)((ex.states)(i)),
+    ensures
+        reached(ex, i, done_or(moved_past(leader, key, b), driving(leader, key, b), answered_leader(leader, key, b, h))),
+    {
+        let (done, inv, g) = (moved_past(leader, key, b), driving(leader, key, b), done_or(moved_past(leader, key, b), driving(leader, key, b), answered_leader(leader, key, b, h)));
+        driving_lasts(c, ex, leader, quorum, key, b, n);
+        leader_is_in_quorum(c, ex, leader, quorum, n);
+
+        member_gets_instance(c, ex, leader, quorum, key, n, h, i);
+        let j1 = choose |j: nat| i <= j && #[trigger] has_instance(h, key)((ex.states)(j));
+        if (done((ex.states)(j1))) {
+            assert(g((ex.states)(j1)));
+            return;
+        }
+        driving_holds_until(c, ex, leader, quorum, key, b, n, i, j1);
+
+        member_reaches_floor(c, ex, leader, quorum, key, n, h, j1);
+        let j2 = choose |j: nat| j1 <= j && #[trigger] at_floor(h, key)((ex.states)(j));
+        if (done((ex.states)(j2))) {
+            assert(g((ex.states)(j2)));
+            return;
+        }
+        driving_holds_until(c, ex, leader, quorum, key, b, n, j1, j2);
+
+        member_catches_up(c, ex, leader, quorum, key, b, n, h, j2);
+        let up = done_or(done, inv, caught_up(h, key, b));
+        let j3 = choose |j: nat| j2 <= j && #[trigger] up((ex.states)(j));
+        if (done((ex.states)(j3))) {
+            assert(g((ex.states)(j3)));
+            return;
+        }
+
+        let u = (ex.states)(j3);
+        settled_at(c, ex, leader, quorum, n, j3);
+        driven_ballot_is_held(c, &u, leader, key, b);
+        if (current(u, h, key).cmp(&b) > 0) {
+            someone_ahead_nacks(c, ex, leader, quorum, key, b, n, h, j3);
+            let j4 = choose |j: nat| j3 <= j && #[trigger] done((ex.states)(j));
+            assert(g((ex.states)(j4)));
+            return;
+        }
+
+        // `h` is at `b` itself, so it promised `b`, or the leader has moved on to phase 2 under it.
+        assert(current(u, h, key) == b);
+        assert(super::steps::answered(&u, h as int, key, current(u, h, key)));
+        if (exists |accepted: Option<(host::Ballot, Value)>| #[trigger] u.network.sent_messages.contains(Message::Promise { key, sender: h, ballot: b, accepted })) {
+            let accepted = choose |accepted: Option<(host::Ballot, Value)>| #[trigger] u.network.sent_messages.contains(Message::Promise { key, sender: h, ballot: b, accepted });
+            if (answered_leader(leader, key, b, h)(u)) {
+                assert(g(u));
+                return;
+            }
+            let (p, recv) = (promise_pending(leader, key, b, h, accepted), Some(Message::Promise { key, sender: h, ballot: b, accepted }));
+            let a = host_action(c, leader, key, Handler::Promised, recv);
+            assert(host_action_is_fair(c, leader, key, Handler::Promised, recv, ex));
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies p((ex.states)((j + 1) as nat)) || g((ex.states)((j + 1) as nat)) by {
+                assert(driving(leader, key, b)((ex.states)(j)));
+                take_step(c, ex, j);
+                next_is_monotone(c, &(ex.states)(j), &(ex.states)((j + 1) as nat), (ex.events)(j));
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) && a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)) implies g((ex.states)((j + 1) as nat)) by {
+                let v = (ex.states)((j + 1) as nat);
+                assert(driving(leader, key, b)((ex.states)(j)));
+                take_step(c, ex, j);
+                host_action_step(c, (ex.states)(j), v, (ex.events)(j), leader, key, Handler::Promised, recv);
+                assert(v.hosts[leader as int].instances[key].volatile.promised.contains_key(b));
+                assert(v.hosts[leader as int].instances[key].volatile.promised[b].contains_key(h));
+                assert(promises(v, leader, key, b).contains(h));
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies enabled(a)((ex.states)(j)) by {
+                settled_at(c, ex, leader, quorum, n, j);
+                is_delivered(c, ex, leader, quorum, n, j, key, Message::Promise { key, sender: h, ballot: b, accepted });
+                promised_is_enabled(c, &(ex.states)(j), leader, key, h, b, accepted);
+            };
+            wf1(ex, a, p, g, n, j3);
+        } else if (exists |value: Value| #[trigger] u.network.sent_messages.contains(Message::Accept { key, ballot: b, value })) {
+            let value = choose |value: Value| #[trigger] u.network.sent_messages.contains(Message::Accept { key, ballot: b, value });
+            assert(u.history[leader as int][key].proposed_value.contains_key(b));
+            assert(g(u));
+        } else {
+            let value = choose |value: Value| #[trigger] u.network.sent_messages.contains(Message::Decide { key, ballot: b, value });
+            assert(u.history[leader as int][key].proposed_value.contains_key(b));
+            assert(g(u));
+        }
+    }
+
+    // The leader's own vote counts: it is in the quorum, so it is at `b` once it has promised or
+    // accepted `b`, and while it drives `b` it is never past it.
+    pub proof fn leader_is_at_ballot(c: &Constants, u: &Variables, leader: nat, key: nat, b: host::Ballot)
+    requires
+        live_inductive(c, u),
+        driving(leader, key, b)(*u),
+        promises(*u, leader, key, b).contains(leader) || acceptances(*u, leader, key, b).contains(leader),
+    ensures
+        current(*u, leader, key) == b,
+    {
+        driven_ballot_is_held(c, u, leader, key, b);
+        if (promises(*u, leader, key, b).contains(leader)) {
+            let accepted = u.history[leader as int][key].promised[b][leader];
+            assert(u.network.sent_messages.contains(Message::Promise { key, sender: leader, ballot: b, accepted }));
+        } else {
+            let value = u.history[leader as int][key].proposed_value[b];
+            assert(u.network.sent_messages.contains(Message::Accepted { key, sender: leader, ballot: b, value }));
+        }
+        assert(current(*u, leader, key).cmp(&b) >= 0);
+    }
+
+    pub proof fn answered_leader_lasts(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, n: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+    ensures
+        forall |h: nat, m: nat|
+            quorum.contains(h) && n <= m && #[trigger] answered_leader(leader, key, b, h)((ex.states)(m)) && driving(leader, key, b)((ex.states)(m)) ==>
+            answered_leader(leader, key, b, h)((ex.states)((m + 1) as nat)) || moved_past(leader, key, b)((ex.states)((m + 1) as nat)),
+    {
+        leader_is_in_quorum(c, ex, leader, quorum, n);
+        assert forall |h: nat, m: nat|
+            quorum.contains(h) && n <= m && #[trigger] answered_leader(leader, key, b, h)((ex.states)(m)) && driving(leader, key, b)((ex.states)(m)) implies
+            answered_leader(leader, key, b, h)((ex.states)((m + 1) as nat)) || moved_past(leader, key, b)((ex.states)((m + 1) as nat)) by {
+            let (u, v) = ((ex.states)(m), (ex.states)((m + 1) as nat));
+            settled_at(c, ex, leader, quorum, n, m);
+            settled_at(c, ex, leader, quorum, n, (m + 1) as nat);
+            take_step(c, ex, m);
+            driven_ballot_is_held(c, &u, leader, key, b);
+            next_is_monotone(c, &u, &v, (ex.events)(m));
+            assert(u.history[leader as int][key].promised[b] == u.history[leader as int][key].promised[b]);
+            assert(u.hosts[leader as int].instances[key].volatile.proposed_value[b] == u.hosts[leader as int].instances[key].volatile.proposed_value[b]);
+        };
+    }
+
+    // Every member of the quorum answers the Prepare for `b`, unless the ballot ends first.
+    pub proof fn quorum_answers(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, n: nat, i: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum_knows_configuration_from(c, ex, quorum, key, n),
+        n <= i,
+        driving(leader, key, b)((ex.states)(i)),
+    ensures
+        reached(ex, i, done_or(moved_past(leader, key, b), driving(leader, key, b), all_goals(quorum, |h: nat| answered_leader(leader, key, b, h)))),
+    {
+        let goal = |h: nat| answered_leader(leader, key, b, h);
+        driving_lasts(c, ex, leader, quorum, key, b, n);
+        answered_leader_lasts(c, ex, leader, quorum, key, b, n);
+        assert forall |h: nat, m: nat| quorum.contains(h) && n <= m && driving(leader, key, b)((ex.states)(m)) implies
+            #[trigger] reached(ex, m, done_or(moved_past(leader, key, b), driving(leader, key, b), goal(h))) by {
+            member_answers(c, ex, leader, quorum, key, b, n, h, m);
+        };
+        all_reach_goals(ex, quorum, goal, driving(leader, key, b), moved_past(leader, key, b), n, i);
+    }
+
+    // The leader holds a phase 1 quorum of promises for `b` and has not proposed yet.
+    pub open spec fn ready_to_propose(c: &Constants, leader: nat, key: nat, b: host::Ballot) -> StatePredicate {
+        |u: Variables|
+            driving(leader, key, b)(u) && !proposed(u, leader, key, b) && current(u, leader, key) == b &&
+            classic_quorums(u, leader, key).is_phase1_quorum(promises(u, leader, key, b))
+    }
+
+    pub proof fn leader_proposes(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, n: nat, i: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum_knows_configuration_from(c, ex, quorum, key, n),
+        n <= i,
+        driving(leader, key, b)((ex.states)(i)),
+    ensures
+        reached(ex, i, done_or(moved_past(leader, key, b), driving(leader, key, b), |u: Variables| proposed(u, leader, key, b))),
+    {
+        let (done, q) = (moved_past(leader, key, b), done_or(moved_past(leader, key, b), driving(leader, key, b), |u: Variables| proposed(u, leader, key, b)));
+        leader_is_in_quorum(c, ex, leader, quorum, n);
+        driving_lasts(c, ex, leader, quorum, key, b, n);
+
+        quorum_answers(c, ex, leader, quorum, key, b, n, i);
+        let all = done_or(done, driving(leader, key, b), all_goals(quorum, |h: nat| answered_leader(leader, key, b, h)));
+        let j1 = choose |j: nat| i <= j && #[trigger] all((ex.states)(j));
+        let u1 = (ex.states)(j1);
+        if (done(u1) || proposed(u1, leader, key, b)) {
+            assert(q(u1));
+            return;
+        }
+
+        settled_at(c, ex, leader, quorum, n, j1);
+        driven_ballot_is_held(c, &u1, leader, key, b);
+        assert forall |h: nat| #[trigger] quorum.contains(h) implies promises(u1, leader, key, b).contains(h) by {
+            assert(answered_leader(leader, key, b, h)(u1));
+        };
+        assert(quorum.subset_of(promises(u1, leader, key, b)));
+        quorum_counts_in_instance(c, &u1, quorum, leader, key);
+        quorum::superset_of_phase1_quorum_is_phase1_quorum(classic_quorums(u1, leader, key), c.num_hosts, quorum, promises(u1, leader, key, b));
+        leader_is_at_ballot(c, &u1, leader, key, b);
+
+        let (p, a) = (ready_to_propose(c, leader, key, b), host_action(c, leader, key, Handler::SendAccept, None));
+        assert(host_action_is_fair(c, leader, key, Handler::SendAccept, None, ex));
+        assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies p((ex.states)((j + 1) as nat)) || q((ex.states)((j + 1) as nat)) by {
+            let (u, v) = ((ex.states)(j), (ex.states)((j + 1) as nat));
+            assert(driving(leader, key, b)(u));
+            if (driving(leader, key, b)(v) && !proposed(v, leader, key, b)) {
+                settled_at(c, ex, leader, quorum, n, j);
+                settled_at(c, ex, leader, quorum, n, (j + 1) as nat);
+                take_step(c, ex, j);
+                driven_ballot_is_held(c, &u, leader, key, b);
+                driven_ballot_is_held(c, &v, leader, key, b);
+                next_is_monotone(c, &u, &v, (ex.events)(j));
+                assert(u.hosts[leader as int].instances[key] == u.hosts[leader as int].instances[key]);
+                assert(u.history[leader as int][key].promised[b] == u.history[leader as int][key].promised[b]);
+                quorum_counts_in_instance(c, &u, quorum, leader, key);
+                quorum::superset_of_phase1_quorum_is_phase1_quorum(classic_quorums(u, leader, key), c.num_hosts, promises(u, leader, key, b), promises(v, leader, key, b));
+            }
+        };
+        assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) && a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)) implies q((ex.states)((j + 1) as nat)) by {
+            let v = (ex.states)((j + 1) as nat);
+            assert(driving(leader, key, b)((ex.states)(j)));
+            host_action_step(c, (ex.states)(j), v, (ex.events)(j), leader, key, Handler::SendAccept, None);
+            assert(proposed(v, leader, key, b));
+        };
+        assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies enabled(a)((ex.states)(j)) by {
+            let u = (ex.states)(j);
+            settled_at(c, ex, leader, quorum, n, j);
+            driven_ballot_is_held(c, &u, leader, key, b);
+            send_accept_is_enabled(c, &u, leader, key);
+        };
+        wf1(ex, a, p, q, n, j1);
+    }
+
+    // The leader's proposal for `b` reaches acceptor `h` and the leader counts its vote, unless the
+    // ballot ends first.
+    pub proof fn member_accepts(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, value: Value, n: nat, h: nat, i: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum_knows_configuration_from(c, ex, quorum, key, n),
+        quorum.contains(h),
+        n <= i,
+        proposing_value(leader, key, b, value)((ex.states)(i)),
+    ensures
+        reached(ex, i, done_or(moved_past(leader, key, b), proposing_value(leader, key, b, value), accepted_leader(leader, key, b, h))),
+    {
+        let (done, inv) = (moved_past(leader, key, b), proposing_value(leader, key, b, value));
+        let g = done_or(done, inv, accepted_leader(leader, key, b, h));
+        let proposal = Message::Accept { key, ballot: b, value };
+        let vote = Message::Accepted { key, sender: h, ballot: b, value };
+        driving_lasts(c, ex, leader, quorum, key, b, n);
+        proposing_lasts(c, ex, leader, quorum, key, b, value, n);
+        leader_is_in_quorum(c, ex, leader, quorum, n);
+        assert forall |j: nat| n <= j && #[trigger] inv((ex.states)(j)) implies (ex.states)(j).network.sent_messages.contains(proposal) by {
+            let u = (ex.states)(j);
+            settled_at(c, ex, leader, quorum, n, j);
+            driven_ballot_is_held(c, &u, leader, key, b);
+            assert(u.history[leader as int][key].proposed_value[b] == value);
+        };
+
+        member_gets_instance(c, ex, leader, quorum, key, n, h, i);
+        let j1 = choose |j: nat| i <= j && #[trigger] has_instance(h, key)((ex.states)(j));
+        if (done((ex.states)(j1))) {
+            assert(g((ex.states)(j1)));
+            return;
+        }
+        proposing_holds_until(c, ex, leader, quorum, key, b, value, n, i, j1);
+
+        member_reaches_floor(c, ex, leader, quorum, key, n, h, j1);
+        let j2 = choose |j: nat| j1 <= j && #[trigger] at_floor(h, key)((ex.states)(j));
+        if (done((ex.states)(j2))) {
+            assert(g((ex.states)(j2)));
+            return;
+        }
+        proposing_holds_until(c, ex, leader, quorum, key, b, value, n, j1, j2);
+
+        // `h` votes for `b`, or turns out to be past it.
+        let voted = |u: Variables| u.network.sent_messages.contains(vote) || current(u, h, key).cmp(&b) > 0;
+        let q1 = done_or(done, inv, voted);
+        let j3 = if (voted((ex.states)(j2))) {
+            assert(q1((ex.states)(j2)));
+            j2
+        } else {
+            let p = |u: Variables| inv(u) && at_floor(h, key)(u) && !voted(u);
+            let a = host_action(c, h, key, Handler::Accept, Some(proposal));
+            assert(host_action_is_fair(c, h, key, Handler::Accept, Some(proposal), ex));
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies p((ex.states)((j + 1) as nat)) || q1((ex.states)((j + 1) as nat)) by {
+                assert(inv((ex.states)(j)));
+                at_floor_lasts(c, ex, leader, quorum, n, h, key, j, (j + 1) as nat);
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) && a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)) implies q1((ex.states)((j + 1) as nat)) by {
+                assert(inv((ex.states)(j)));
+                host_action_step(c, (ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j), h, key, Handler::Accept, Some(proposal));
+                settled_at(c, ex, leader, quorum, n, j);
+                assert((ex.states)((j + 1) as nat).network.sent_messages.contains(vote));
+            };
+            assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies enabled(a)((ex.states)(j)) by {
+                assert(inv((ex.states)(j)));
+                settled_at(c, ex, leader, quorum, n, j);
+                is_delivered(c, ex, leader, quorum, n, j, key, proposal);
+                accept_is_enabled(c, &(ex.states)(j), h, key, b, value);
+            };
+            wf1(ex, a, p, q1, n, j2);
+            choose |j: nat| j2 <= j && #[trigger] q1((ex.states)(j))
+        };
+        let u3 = (ex.states)(j3);
+        if (done(u3)) {
+            assert(g(u3));
+            return;
+        }
+        if (current(u3, h, key).cmp(&b) > 0) {
+            at_floor_lasts(c, ex, leader, quorum, n, h, key, j2, j3);
+            someone_ahead_nacks(c, ex, leader, quorum, key, b, n, h, j3);
+            let j4 = choose |j: nat| j3 <= j && #[trigger] done((ex.states)(j));
+            assert(g((ex.states)(j4)));
+            return;
+        }
+        if (accepted_leader(leader, key, b, h)(u3)) {
+            assert(g(u3));
+            return;
+        }
+
+        // The leader counts the vote.
+        let p = |u: Variables| inv(u) && u.network.sent_messages.contains(vote) && !accepted_leader(leader, key, b, h)(u);
+        let a = host_action(c, leader, key, Handler::Accepted, Some(vote));
+        assert(host_action_is_fair(c, leader, key, Handler::Accepted, Some(vote), ex));
+        assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies p((ex.states)((j + 1) as nat)) || g((ex.states)((j + 1) as nat)) by {
+            assert(inv((ex.states)(j)));
+            take_step(c, ex, j);
+            next_is_monotone(c, &(ex.states)(j), &(ex.states)((j + 1) as nat), (ex.events)(j));
+        };
+        assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) && a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)) implies g((ex.states)((j + 1) as nat)) by {
+            let v = (ex.states)((j + 1) as nat);
+            assert(inv((ex.states)(j)));
+            take_step(c, ex, j);
+            host_action_step(c, (ex.states)(j), v, (ex.events)(j), leader, key, Handler::Accepted, Some(vote));
+            assert(v.hosts[leader as int].instances[key].volatile.accepted.contains_key(b));
+            assert(v.hosts[leader as int].instances[key].volatile.accepted[b].contains(h));
+            assert(acceptances(v, leader, key, b).contains(h));
+        };
+        assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies enabled(a)((ex.states)(j)) by {
+            let u = (ex.states)(j);
+            assert(inv(u));
+            settled_at(c, ex, leader, quorum, n, j);
+            driven_ballot_is_held(c, &u, leader, key, b);
+            is_delivered(c, ex, leader, quorum, n, j, key, vote);
+            accepted_is_enabled(c, &u, leader, key, h, b, value);
+        };
+        wf1(ex, a, p, g, n, j3);
+    }
+
+    pub proof fn accepted_leader_lasts(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, value: Value, n: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+    ensures
+        forall |h: nat, m: nat|
+            quorum.contains(h) && n <= m && #[trigger] accepted_leader(leader, key, b, h)((ex.states)(m)) && proposing_value(leader, key, b, value)((ex.states)(m)) ==>
+            accepted_leader(leader, key, b, h)((ex.states)((m + 1) as nat)) || moved_past(leader, key, b)((ex.states)((m + 1) as nat)),
+    {
+        assert forall |h: nat, m: nat|
+            quorum.contains(h) && n <= m && #[trigger] accepted_leader(leader, key, b, h)((ex.states)(m)) && proposing_value(leader, key, b, value)((ex.states)(m)) implies
+            accepted_leader(leader, key, b, h)((ex.states)((m + 1) as nat)) || moved_past(leader, key, b)((ex.states)((m + 1) as nat)) by {
+            let (u, v) = ((ex.states)(m), (ex.states)((m + 1) as nat));
+            settled_at(c, ex, leader, quorum, n, m);
+            take_step(c, ex, m);
+            driven_ballot_is_held(c, &u, leader, key, b);
+            next_is_monotone(c, &u, &v, (ex.events)(m));
+            assert(u.history[leader as int][key].accepted[b] == u.history[leader as int][key].accepted[b]);
+        };
+    }
+
+    // Every member of the quorum votes for the leader's proposal under `b`, unless the ballot ends first.
+    pub proof fn quorum_accepts(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, value: Value, n: nat, i: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum_knows_configuration_from(c, ex, quorum, key, n),
+        n <= i,
+        proposing_value(leader, key, b, value)((ex.states)(i)),
+    ensures
+        reached(ex, i, done_or(moved_past(leader, key, b), proposing_value(leader, key, b, value), all_goals(quorum, |h: nat| accepted_leader(leader, key, b, h)))),
+    {
+        let goal = |h: nat| accepted_leader(leader, key, b, h);
+        let inv = proposing_value(leader, key, b, value);
+        driving_lasts(c, ex, leader, quorum, key, b, n);
+        proposing_lasts(c, ex, leader, quorum, key, b, value, n);
+        accepted_leader_lasts(c, ex, leader, quorum, key, b, value, n);
+        assert forall |h: nat, m: nat| quorum.contains(h) && n <= m && inv((ex.states)(m)) implies
+            #[trigger] reached(ex, m, done_or(moved_past(leader, key, b), inv, goal(h))) by {
+            member_accepts(c, ex, leader, quorum, key, b, value, n, h, m);
+        };
+        all_reach_goals(ex, quorum, goal, inv, moved_past(leader, key, b), n, i);
+    }
+
+    // The leader holds a phase 2 quorum of votes for its proposal under `b`.
+    pub open spec fn ready_to_learn(c: &Constants, leader: nat, key: nat, b: host::Ballot, value: Value) -> StatePredicate {
+        |u: Variables|
+            proposing_value(leader, key, b, value)(u) && current(u, leader, key) == b &&
+            classic_quorums(u, leader, key).is_phase2_quorum(acceptances(u, leader, key, b))
+    }
+
+    pub proof fn leader_learns(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, value: Value, n: nat, i: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum_knows_configuration_from(c, ex, quorum, key, n),
+        n <= i,
+        proposing_value(leader, key, b, value)((ex.states)(i)),
+    ensures
+        reached(ex, i, moved_past(leader, key, b)),
+    {
+        let (done, inv) = (moved_past(leader, key, b), proposing_value(leader, key, b, value));
+        leader_is_in_quorum(c, ex, leader, quorum, n);
+        driving_lasts(c, ex, leader, quorum, key, b, n);
+        proposing_lasts(c, ex, leader, quorum, key, b, value, n);
+
+        quorum_accepts(c, ex, leader, quorum, key, b, value, n, i);
+        let all = done_or(done, inv, all_goals(quorum, |h: nat| accepted_leader(leader, key, b, h)));
+        let j1 = choose |j: nat| i <= j && #[trigger] all((ex.states)(j));
+        let u1 = (ex.states)(j1);
+        if (done(u1)) {
+            return;
+        }
+
+        settled_at(c, ex, leader, quorum, n, j1);
+        driven_ballot_is_held(c, &u1, leader, key, b);
+        assert forall |h: nat| #[trigger] quorum.contains(h) implies acceptances(u1, leader, key, b).contains(h) by {
+            assert(accepted_leader(leader, key, b, h)(u1));
+        };
+        assert(quorum.subset_of(acceptances(u1, leader, key, b)));
+        quorum_counts_in_instance(c, &u1, quorum, leader, key);
+        quorum::superset_of_phase2_quorum_is_phase2_quorum(classic_quorums(u1, leader, key), c.num_hosts, quorum, acceptances(u1, leader, key, b));
+        leader_is_at_ballot(c, &u1, leader, key, b);
+
+        let (p, a) = (ready_to_learn(c, leader, key, b, value), host_action(c, leader, key, Handler::Learn, None));
+        assert(host_action_is_fair(c, leader, key, Handler::Learn, None, ex));
+        assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies p((ex.states)((j + 1) as nat)) || done((ex.states)((j + 1) as nat)) by {
+            let (u, v) = ((ex.states)(j), (ex.states)((j + 1) as nat));
+            assert(inv(u));
+            if (inv(v)) {
+                settled_at(c, ex, leader, quorum, n, j);
+                settled_at(c, ex, leader, quorum, n, (j + 1) as nat);
+                take_step(c, ex, j);
+                driven_ballot_is_held(c, &u, leader, key, b);
+                driven_ballot_is_held(c, &v, leader, key, b);
+                next_is_monotone(c, &u, &v, (ex.events)(j));
+                assert(u.hosts[leader as int].instances[key] == u.hosts[leader as int].instances[key]);
+                assert(u.history[leader as int][key].accepted[b] == u.history[leader as int][key].accepted[b]);
+                quorum_counts_in_instance(c, &u, quorum, leader, key);
+                quorum::superset_of_phase2_quorum_is_phase2_quorum(classic_quorums(u, leader, key), c.num_hosts, acceptances(u, leader, key, b), acceptances(v, leader, key, b));
+            }
+        };
+        assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) && a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)) implies done((ex.states)((j + 1) as nat)) by {
+            let v = (ex.states)((j + 1) as nat);
+            take_step(c, ex, j);
+            host_action_step(c, (ex.states)(j), v, (ex.events)(j), leader, key, Handler::Learn, None);
+            assert(v.hosts[leader as int].instances[key].decide_value.is_some());
+            assert(some_host_decided(&v, key));
+        };
+        assert forall |j: nat| n <= j && #[trigger] p((ex.states)(j)) implies enabled(a)((ex.states)(j)) by {
+            let u = (ex.states)(j);
+            assert(inv(u));
+            settled_at(c, ex, leader, quorum, n, j);
+            driven_ballot_is_held(c, &u, leader, key, b);
+            learn_is_enabled(c, &u, leader, key);
+        };
+        wf1(ex, a, p, done, n, j1);
+    }
+
+    // A ballot the leader drives ends: both phases go through with the quorum, unless a higher ballot
+    // turns up first.
+    pub proof fn driving_ballot_moves_on(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, b: host::Ballot, n: nat, i: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum_knows_configuration_from(c, ex, quorum, key, n),
+        n <= i,
+        driving(leader, key, b)((ex.states)(i)),
+    ensures
+        reached(ex, i, moved_past(leader, key, b)),
+    {
+        let done = moved_past(leader, key, b);
+        leader_proposes(c, ex, leader, quorum, key, b, n, i);
+        let q = done_or(done, driving(leader, key, b), |u: Variables| proposed(u, leader, key, b));
+        let j1 = choose |j: nat| i <= j && #[trigger] q((ex.states)(j));
+        let u1 = (ex.states)(j1);
+        if (done(u1)) {
+            return;
+        }
+        let value = u1.hosts[leader as int].instances[key].volatile.proposed_value[b];
+        assert(proposing_value(leader, key, b, value)(u1));
+        leader_learns(c, ex, leader, quorum, key, b, value, n, j1);
+    }
+
+    // The leader's newest ballot is one it is driving.
+    pub open spec fn unstuck(leader: nat, key: nat) -> StatePredicate {
+        |u: Variables|
+            some_host_decided(&u, key) ||
+            (u.hosts[leader as int].instances.contains_key(key) && leads(u, leader, key, top(u, leader, key)))
+    }
+
+    pub open spec fn stuck_undecided(leader: nat, key: nat) -> StatePredicate {
+        |u: Variables| !some_host_decided(&u, key) && leader_is_stuck(leader, key)(u)
+    }
+
+    // A stuck leader times out and prepares a ballot above everything it has seen.
+    pub proof fn leader_gets_unstuck(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, n: nat, j: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        n <= j,
+        has_instance(leader, key)((ex.states)(j)),
+    ensures
+        reached(ex, j, unstuck(leader, key)),
+    {
+        let (p, q) = (stuck_undecided(leader, key), unstuck(leader, key));
+        leader_is_in_quorum(c, ex, leader, quorum, n);
+        if (q((ex.states)(j))) {
+            return;
+        }
+        let a = leader_times_out(c, leader, key);
+        assert(leader_times_out_is_fair(c, leader, key, ex));
+        assert forall |m: nat| n <= m && #[trigger] p((ex.states)(m)) implies p((ex.states)((m + 1) as nat)) || q((ex.states)((m + 1) as nat)) by {
+            instance_is_monotone(c, ex, leader, key, m, (m + 1) as nat);
+        };
+        assert forall |m: nat| n <= m && #[trigger] p((ex.states)(m)) && a((ex.states)(m), (ex.states)((m + 1) as nat), (ex.events)(m)) implies q((ex.states)((m + 1) as nat)) by {
+            let (u, v, event) = ((ex.states)(m), (ex.states)((m + 1) as nat), (ex.events)(m));
+            let net_op = choose |net_op: NetworkOperation| #![trigger is_valid_transition(c, &u, &v, Transition::HostStep { host_id: leader as int, instance: key, net_op }, event)]
+                host::send_prepare(&c.hosts[leader as int], &u.hosts[leader as int], &v.hosts[leader as int], key, net_op) &&
+                is_valid_transition(c, &u, &v, Transition::HostStep { host_id: leader as int, instance: key, net_op }, event);
+            settled_at(c, ex, leader, quorum, n, m);
+            assert(c.hosts[leader as int].id == leader);
+            let x = top(v, leader, key);
+            assert(x.pid == leader);
+            assert(v.hosts[leader as int].instances[key].volatile.promised.contains_key(x));
+            assert(v.network.sent_messages.contains(Message::Prepare { key, ballot: x }));
+        };
+        assert forall |m: nat| n <= m && #[trigger] p((ex.states)(m)) implies enabled(a)((ex.states)(m)) by {
+            let u = (ex.states)(m);
+            settled_at(c, ex, leader, quorum, n, m);
+            assert(u.hosts[leader as int].instances[key].decide_value.is_none());
+            leader_times_out_is_enabled(c, &u, leader, key);
+        };
+        wf1(ex, a, p, q, n, j);
+    }
+
+    // The leader's newest ballot number goes up: it drives its newest ballot until a higher one turns
+    // up, and a higher ballot with the same number is someone else's, which leaves it stuck again.
+    pub proof fn leader_raises_ballot(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, n: nat, j: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum_knows_configuration_from(c, ex, quorum, key, n),
+        n <= j,
+        has_instance(leader, key)((ex.states)(j)),
+    ensures
+        reached(ex, j, beyond(leader, key, top((ex.states)(j), leader, key).num)),
+    {
+        let bound = top((ex.states)(j), leader, key).num;
+        let done = beyond(leader, key, bound);
+        leader_is_in_quorum(c, ex, leader, quorum, n);
+
+        leader_gets_unstuck(c, ex, leader, quorum, key, n, j);
+        let j1 = choose |k: nat| j <= k && #[trigger] unstuck(leader, key)((ex.states)(k));
+        let u1 = (ex.states)(j1);
+        if (done(u1)) {
+            return;
+        }
+        instance_is_monotone(c, ex, leader, key, j, j1);
+        let b = top(u1, leader, key);
+        assert(driving(leader, key, b)(u1));
+
+        driving_ballot_moves_on(c, ex, leader, quorum, key, b, n, j1);
+        let j2 = choose |k: nat| j1 <= k && #[trigger] moved_past(leader, key, b)((ex.states)(k));
+        let u2 = (ex.states)(j2);
+        if (done(u2)) {
+            return;
+        }
+        // `b` was the leader's, and the ballot above it has the same number, so it is someone else's.
+        instance_is_monotone(c, ex, leader, key, j1, j2);
+        assert(top(u2, leader, key).num == bound && top(u2, leader, key).pid > leader);
+
+        leader_gets_unstuck(c, ex, leader, quorum, key, n, j2);
+        let j3 = choose |k: nat| j2 <= k && #[trigger] unstuck(leader, key)((ex.states)(k));
+        let u3 = (ex.states)(j3);
+        instance_is_monotone(c, ex, leader, key, j2, j3);
+        assert(done(u3));
+    }
+
+    pub proof fn leader_passes(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, bound: nat, n: nat, j: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum_knows_configuration_from(c, ex, quorum, key, n),
+        n <= j,
+        has_instance(leader, key)((ex.states)(j)),
+    ensures
+        reached(ex, j, beyond(leader, key, bound)),
+    decreases
+        bound + 1 - top((ex.states)(j), leader, key).num
+    {
+        if (beyond(leader, key, bound)((ex.states)(j))) {
+            return;
+        }
+        leader_raises_ballot(c, ex, leader, quorum, key, n, j);
+        let up = beyond(leader, key, top((ex.states)(j), leader, key).num);
+        let k = choose |k: nat| j <= k && #[trigger] up((ex.states)(k));
+        if (!beyond(leader, key, bound)((ex.states)(k))) {
+            leader_passes(c, ex, leader, quorum, key, bound, n, k);
+        }
+    }
+
+    // Once only the leader prepares, every ballot prepared after step `i` is one the leader moved its
+    // `max_seen_ballot` to.
+    pub proof fn new_ballots_hold(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, bound: nat, n: nat, i: nat, j: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        n <= i <= j,
+        prepared_below((ex.states)(i), key, bound),
+        has_instance(leader, key)((ex.states)(i)),
+    ensures
+        new_ballots_are_the_leaders(leader, key, bound)((ex.states)(j)),
+    decreases
+        j - i
+    {
+        leader_is_in_quorum(c, ex, leader, quorum, n);
+        if (i < j) {
+            let (p, u, v) = ((j - 1) as nat, (ex.states)((j - 1) as nat), (ex.states)(j));
+            new_ballots_hold(c, ex, leader, quorum, key, bound, n, i, p);
+            instance_is_monotone(c, ex, leader, key, i, p);
+            instance_is_monotone(c, ex, leader, key, p, j);
+            settled_at(c, ex, leader, quorum, n, p);
+            take_step(c, ex, p);
+            assert forall |x: host::Ballot| #[trigger] v.ballot_was_prepared(key, x) implies x.num <= bound || x.cmp(&top(v, leader, key)) <= 0 by {
+                if (u.ballot_was_prepared(key, x)) {
+                    assert(x.num <= bound || x.cmp(&top(u, leader, key)) <= 0);
+                } else {
+                    new_prepare_is_the_leaders(c, &u, &v, (ex.events)(p), leader, key, x);
+                }
+            };
+        }
+    }
+
+    // A ballot the leader drives above every ballot prepared before is never overtaken: a higher one
+    // would have to be prepared afresh, and the leader only prepares when it is stuck.
+    pub proof fn dominant_ballot_is_passed_only_by_deciding(
+        c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, bound: nat, b: host::Ballot, n: nat, i: nat, j: nat, m: nat,
+    )
+    requires
+        settled(c, ex, leader, quorum, n),
+        n <= i <= j <= m,
+        prepared_below((ex.states)(i), key, bound),
+        has_instance(leader, key)((ex.states)(i)),
+        driving(leader, key, b)((ex.states)(j)),
+        b.num > bound,
+    ensures
+        moved_past(leader, key, b)((ex.states)(m)) ==> some_host_decided(&(ex.states)(m), key),
+    decreases
+        m - j
+    {
+        if (j < m && moved_past(leader, key, b)((ex.states)(m)) && !some_host_decided(&(ex.states)(m), key)) {
+            let (p, u, v) = ((m - 1) as nat, (ex.states)((m - 1) as nat), (ex.states)(m));
+            take_step(c, ex, p);
+            settled_at(c, ex, leader, quorum, n, p);
+            dominant_ballot_is_passed_only_by_deciding(c, ex, leader, quorum, key, bound, b, n, i, j, p);
+            if (some_host_decided(&u, key)) {
+                instance_decided_is_stable(c, &u, &v, (ex.events)(p), key);
+            }
+            driving_holds_until(c, ex, leader, quorum, key, b, n, j, p);
+            new_ballots_hold(c, ex, leader, quorum, key, bound, n, i, p);
+
+            // The leader's `max_seen_ballot` above `b` was prepared, and not before this step.
+            let x = top(v, leader, key);
+            assert(v.ballot_was_prepared(key, x));
+            if (u.ballot_was_prepared(key, x)) {
+                assert(x.num <= bound || x.cmp(&top(u, leader, key)) <= 0);
+            } else {
+                new_prepare_is_the_leaders(c, &u, &v, (ex.events)(p), leader, key, x);
+            }
+            assert(false);
+        }
+    }
+
+    // Some step from `i` on has the instance decided if the quorum knows its configuration at `i`: the leader gets the instance, climbs above every
+    // ballot prepared so far, and then drives its newest ballot through with the quorum.
+    pub proof fn instance_is_eventually_decided(c: &Constants, ex: Execution, leader: nat, quorum: Set<nat>, key: nat, n: nat, i: nat)
+    requires
+        settled(c, ex, leader, quorum, n),
+        quorum_knows_configuration(c, quorum, key)((ex.states)(i)),
+    ensures
+        reached(ex, i, instance_decided(key)),
+    {
+        let decided = instance_decided(key);
+        let j0 = if (i < n) { n } else { i };
+        settled_later(c, ex, leader, quorum, n, j0);
+        settled_at(c, ex, leader, quorum, j0, j0);
+        assert forall |j: nat| j0 <= j implies #[trigger] quorum_knows_configuration(c, quorum, key)((ex.states)(j)) by {
+            configuration_knowledge_lasts(c, ex, quorum, key, i, j);
+        };
+        let n = j0;
+        leader_is_in_quorum(c, ex, leader, quorum, n);
+
+        member_gets_instance(c, ex, leader, quorum, key, n, leader, j0);
+        let j1 = choose |j: nat| j0 <= j && #[trigger] has_instance(leader, key)((ex.states)(j));
+        if (decided((ex.states)(j1))) {
+            return;
+        }
+        take_step(c, ex, j1);
+        let bound = prepared_ballots_of_state_are_bounded((ex.states)(j1), key);
+
+        leader_passes(c, ex, leader, quorum, key, bound, n, j1);
+        let j2 = choose |j: nat| j1 <= j && #[trigger] beyond(leader, key, bound)((ex.states)(j));
+        if (decided((ex.states)(j2))) {
+            return;
+        }
+
+        leader_gets_unstuck(c, ex, leader, quorum, key, n, j2);
+        let j3 = choose |j: nat| j2 <= j && #[trigger] unstuck(leader, key)((ex.states)(j));
+        if (decided((ex.states)(j3))) {
+            return;
+        }
+        instance_is_monotone(c, ex, leader, key, j2, j3);
+        let b = top((ex.states)(j3), leader, key);
+        assert(b.num > bound);
+        assert(driving(leader, key, b)((ex.states)(j3)));
+
+        driving_ballot_moves_on(c, ex, leader, quorum, key, b, n, j3);
+        let j4 = choose |j: nat| j3 <= j && #[trigger] moved_past(leader, key, b)((ex.states)(j));
+        dominant_ballot_is_passed_only_by_deciding(c, ex, leader, quorum, key, bound, b, n, j1, j3, j4);
+        assert(decided((ex.states)(j4)));
+    }
+}
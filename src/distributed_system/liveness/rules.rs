@@ -0,0 +1,250 @@
+use super::{
+    always, enabled, eventually, implies, lift_action, lift_state, suffix_of_suffix_is_suffix, weak_fairness,
+    ActionPredicate, Execution, StatePredicate,
+};
+use crate::distributed_system::low_level::Variables;
+use vstd::prelude::*;
+
+verus! {
+    // The proof works on step indices rather than suffixes: `reached(ex, i, p)` is `eventually(lift_state(p))`
+    // at `ex.suffix(i)`, and the rules below are the temporal ones restated that way.
+    pub open spec fn reached(ex: Execution, i: nat, p: StatePredicate) -> bool {
+        exists |j: nat| i <= j && #[trigger] p((ex.states)(j))
+    }
+
+    pub proof fn suffix_is_shifted(ex: Execution, i: nat)
+    ensures
+        ex.suffix(i).head() == (ex.states)(i),
+        (ex.suffix(i).states)(1) == (ex.states)((i + 1) as nat),
+        (ex.suffix(i).events)(0) == (ex.events)(i),
+    {
+    }
+
+    // The step from which an `eventually(always(..))` assumption holds for good.
+    pub proof fn state_holds_from_some_step(p: StatePredicate, ex: Execution) -> (n: nat)
+    requires
+        eventually(always(lift_state(p)))(ex),
+    ensures
+        forall |j: nat| n <= j ==> #[trigger] p((ex.states)(j)),
+    {
+        let n = choose |n: nat| #[trigger] always(lift_state(p))(ex.suffix(n));
+        assert forall |j: nat| n <= j implies #[trigger] p((ex.states)(j)) by {
+            let d = (j - n) as nat;
+            assert(lift_state(p)(ex.suffix(n).suffix(d)));
+            suffix_of_suffix_is_suffix(ex, n, d);
+            suffix_is_shifted(ex, j);
+        };
+        n
+    }
+
+    pub proof fn action_holds_from_some_step(a: ActionPredicate, ex: Execution) -> (n: nat)
+    requires
+        eventually(always(lift_action(a)))(ex),
+    ensures
+        forall |j: nat| n <= j ==> #[trigger] a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)),
+    {
+        let n = choose |n: nat| #[trigger] always(lift_action(a))(ex.suffix(n));
+        assert forall |j: nat| n <= j implies #[trigger] a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)) by {
+            let d = (j - n) as nat;
+            assert(lift_action(a)(ex.suffix(n).suffix(d)));
+            suffix_of_suffix_is_suffix(ex, n, d);
+            suffix_is_shifted(ex, j);
+        };
+        n
+    }
+
+    proof fn holds_until_reached(ex: Execution, p: StatePredicate, q: StatePredicate, start: nat, i: nat, d: nat)
+    requires
+        start <= i,
+        p((ex.states)(i)),
+        !reached(ex, i, q),
+        forall |j: nat| start <= j && #[trigger] p((ex.states)(j)) ==> p((ex.states)((j + 1) as nat)) || q((ex.states)((j + 1) as nat)),
+    ensures
+        p((ex.states)((i + d) as nat)),
+    decreases
+        d
+    {
+        if (d > 0) {
+            let j = (i + d - 1) as nat;
+            holds_until_reached(ex, p, q, start, i, (d - 1) as nat);
+            assert(p((ex.states)(j)));
+            assert(!q((ex.states)((j + 1) as nat)));
+        }
+    }
+
+    // WF1: `p` lasts until `q`, the action takes `p` to `q`, and `p` keeps the action enabled, so the
+    // action's fairness makes `q` happen.
+    pub proof fn wf1(ex: Execution, a: ActionPredicate, p: StatePredicate, q: StatePredicate, start: nat, i: nat)
+    requires
+        weak_fairness(a)(ex),
+        start <= i,
+        p((ex.states)(i)),
+        forall |j: nat| start <= j && #[trigger] p((ex.states)(j)) ==> p((ex.states)((j + 1) as nat)) || q((ex.states)((j + 1) as nat)),
+        forall |j: nat|
+            start <= j && #[trigger] p((ex.states)(j)) && a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)) ==>
+            q((ex.states)((j + 1) as nat)),
+        forall |j: nat| start <= j && #[trigger] p((ex.states)(j)) ==> enabled(a)((ex.states)(j)),
+    ensures
+        reached(ex, i, q),
+    {
+        if (!reached(ex, i, q)) {
+            assert(always(lift_state(enabled(a)))(ex.suffix(i))) by {
+                assert forall |d: nat| #[trigger] lift_state(enabled(a))(ex.suffix(i).suffix(d)) by {
+                    holds_until_reached(ex, p, q, start, i, d);
+                    suffix_of_suffix_is_suffix(ex, i, d);
+                    suffix_is_shifted(ex, (i + d) as nat);
+                };
+            };
+            assert(implies(always(lift_state(enabled(a))), eventually(lift_action(a)))(ex.suffix(i)));
+            let d = choose |d: nat| #[trigger] lift_action(a)(ex.suffix(i).suffix(d));
+            let j = (i + d) as nat;
+            suffix_of_suffix_is_suffix(ex, i, d);
+            suffix_is_shifted(ex, j);
+            holds_until_reached(ex, p, q, start, i, d);
+            assert(a((ex.states)(j), (ex.states)((j + 1) as nat), (ex.events)(j)));
+            assert(q((ex.states)((j + 1) as nat)));
+            assert(i <= j + 1);
+        }
+    }
+
+    // `p` holds from `i` to `j` if each step keeps it, as long as `done` (which is stable) has not
+    // happened by `j`.
+    pub proof fn holds_until_done(ex: Execution, p: StatePredicate, done: StatePredicate, start: nat, i: nat, j: nat)
+    requires
+        start <= i <= j,
+        p((ex.states)(i)),
+        !done((ex.states)(j)),
+        forall |m: nat| start <= m && #[trigger] p((ex.states)(m)) ==> p((ex.states)((m + 1) as nat)) || done((ex.states)((m + 1) as nat)),
+        forall |m: nat| start <= m && #[trigger] done((ex.states)(m)) ==> done((ex.states)((m + 1) as nat)),
+    ensures
+        p((ex.states)(j)),
+    decreases
+        j - i
+    {
+        if (i < j) {
+            let m = (i + 1) as nat;
+            if (done((ex.states)(m))) {
+                stays_done(ex, done, start, m, j);
+            } else {
+                holds_until_done(ex, p, done, start, m, j);
+            }
+        }
+    }
+
+    pub proof fn stays_done(ex: Execution, done: StatePredicate, start: nat, i: nat, j: nat)
+    requires
+        start <= i <= j,
+        done((ex.states)(i)),
+        forall |m: nat| start <= m && #[trigger] done((ex.states)(m)) ==> done((ex.states)((m + 1) as nat)),
+    ensures
+        done((ex.states)(j)),
+    decreases
+        j - i
+    {
+        if (i < j) {
+            stays_done(ex, done, start, (i + 1) as nat, j);
+        }
+    }
+
+    // `p` has happened unless `done` already has; `inv` is what the proof carries along meanwhile.
+    pub open spec fn done_or(done: StatePredicate, inv: StatePredicate, p: StatePredicate) -> StatePredicate {
+        |u: Variables| done(u) || (inv(u) && p(u))
+    }
+
+    pub open spec fn all_goals(hosts: Set<nat>, goal: spec_fn(nat) -> StatePredicate) -> StatePredicate {
+        |u: Variables| forall |h: nat| hosts.contains(h) ==> #[trigger] goal(h)(u)
+    }
+
+    // Each member of a finite set reaches its own goal while `inv` lasts, and keeps it; then all of them
+    // hold their goals at once, unless `done` happens first.
+    pub proof fn all_reach_goals(
+        ex: Execution, hosts: Set<nat>, goal: spec_fn(nat) -> StatePredicate,
+        inv: StatePredicate, done: StatePredicate, start: nat, i: nat,
+    )
+    requires
+        hosts.finite(),
+        start <= i,
+        inv((ex.states)(i)),
+        forall |m: nat| start <= m && #[trigger] inv((ex.states)(m)) ==> inv((ex.states)((m + 1) as nat)) || done((ex.states)((m + 1) as nat)),
+        forall |m: nat| start <= m && #[trigger] done((ex.states)(m)) ==> done((ex.states)((m + 1) as nat)),
+        forall |h: nat, m: nat|
+            hosts.contains(h) && start <= m && #[trigger] goal(h)((ex.states)(m)) && inv((ex.states)(m)) ==>
+            goal(h)((ex.states)((m + 1) as nat)) || done((ex.states)((m + 1) as nat)),
+        forall |h: nat, m: nat|
+            hosts.contains(h) && start <= m && inv((ex.states)(m)) ==>
+            #[trigger] reached(ex, m, done_or(done, inv, goal(h))),
+    ensures
+        reached(ex, i, done_or(done, inv, all_goals(hosts, goal))),
+    decreases
+        hosts.len()
+    {
+        let all = done_or(done, inv, all_goals(hosts, goal));
+        if (hosts.len() == 0) {
+            hosts.lemma_len0_is_empty();
+            assert(all((ex.states)(i)));
+        } else {
+            let x = hosts.choose();
+            let rest = hosts.remove(x);
+            let some = done_or(done, inv, all_goals(rest, goal));
+            all_reach_goals(ex, rest, goal, inv, done, start, i);
+            let j1 = choose |j: nat| i <= j && #[trigger] some((ex.states)(j));
+            if (done((ex.states)(j1))) {
+                assert(all((ex.states)(j1)));
+            } else {
+                let one = done_or(done, inv, goal(x));
+                assert(reached(ex, j1, one));
+                let j2 = choose |j: nat| j1 <= j && #[trigger] one((ex.states)(j));
+                if (!done((ex.states)(j2))) {
+                    assert forall |h: nat| hosts.contains(h) implies #[trigger] goal(h)((ex.states)(j2)) by {
+                        if (h != x) {
+                            assert(rest.contains(h));
+                            assert(goal(h)((ex.states)(j1)));
+                            let both = |u: Variables| inv(u) && goal(h)(u);
+                            assert forall |m: nat| start <= m && #[trigger] both((ex.states)(m)) implies
+                                both((ex.states)((m + 1) as nat)) || done((ex.states)((m + 1) as nat)) by {
+                                assert(inv((ex.states)(m)));
+                                assert(goal(h)((ex.states)(m)));
+                            };
+                            holds_until_done(ex, both, done, start, j1, j2);
+                        }
+                    };
+                }
+                assert(all((ex.states)(j2)));
+            }
+        }
+    }
+
+    proof fn nonincreasing_is_bounded(f: spec_fn(nat) -> nat, start: nat, j: nat)
+    requires
+        start <= j,
+        forall |m: nat| start <= m ==> #[trigger] f((m + 1) as nat) <= f(m),
+    ensures
+        f(j) <= f(start),
+    decreases
+        j - start
+    {
+        if (start < j) {
+            nonincreasing_is_bounded(f, start, (j - 1) as nat);
+            assert(f(((j - 1) as nat + 1) as nat) <= f((j - 1) as nat));
+        }
+    }
+
+    // A count that never goes up stops going down.
+    pub proof fn nonincreasing_stabilizes(f: spec_fn(nat) -> nat, start: nat) -> (n: nat)
+    requires
+        forall |m: nat| start <= m ==> #[trigger] f((m + 1) as nat) <= f(m),
+    ensures
+        start <= n,
+        forall |j: nat| n <= j ==> #[trigger] f(j) == f(n),
+    decreases
+        f(start)
+    {
+        if (exists |j: nat| start <= j && #[trigger] f(j) != f(start)) {
+            let j = choose |j: nat| start <= j && #[trigger] f(j) != f(start);
+            nonincreasing_is_bounded(f, start, j);
+            nonincreasing_stabilizes(f, j)
+        } else {
+            start
+        }
+    }
+}
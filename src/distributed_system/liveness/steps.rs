@@ -0,0 +1,852 @@
+use super::{
+    super::{no_op, refinement_init, refinement_next, Event, Value},
+    enabled, host_action, is_behavior, leader_is_stuck, leader_times_out, runs, takes_step, Execution, Handler,
+};
+use crate::distributed_system::low_level::{
+    fast, host, host_next, inductive, message_next, network, next_is_host_next_or_network_only_next, record, Constants, Message,
+    NetworkOperation, Transition, Variables, is_valid_transition,
+};
+use vstd::prelude::*;
+
+verus! {
+    pub proof fn behavior_is_inductive(c: &Constants, ex: Execution, i: nat)
+    requires
+        is_behavior(c, ex),
+    ensures
+        inductive(c, &(ex.states)(i)),
+    decreases
+        i
+    {
+        if (i == 0) {
+            refinement_init(c, &ex.head());
+        } else {
+            let prev = (i - 1) as nat;
+            behavior_is_inductive(c, ex, prev);
+            assert(takes_step(c, ex, prev));
+            refinement_next(c, &(ex.states)(prev), &(ex.states)(i), (ex.events)(prev));
+        }
+    }
+
+    // Host `i` has told the leader of `ballot` that it is at it: it promised it, or the ballot's leader
+    // is past phase 1 and has sent an Accept or Decide for it.
+    pub open spec fn answered(u: &Variables, i: int, key: nat, ballot: host::Ballot) -> bool {
+        ||| exists |accepted: Option<(host::Ballot, Value)>| #[trigger] u.network.sent_messages.contains(Message::Promise { key, sender: i as nat, ballot, accepted })
+        ||| exists |value: Value| #[trigger] u.network.sent_messages.contains(Message::Accept { key, ballot, value })
+        ||| exists |value: Value| #[trigger] u.network.sent_messages.contains(Message::Decide { key, ballot, value })
+    }
+
+    // An acceptor only ever moves to a ballot it promises, accepts or learns was decided, so it never
+    // sits at a ballot whose leader is still waiting for its promise.
+    pub open spec fn current_ballot_was_answered(c: &Constants, u: &Variables) -> bool {
+        forall |i: int, key: nat|
+            0 <= i < u.hosts.len() &&
+            u.hosts[i].instances.contains_key(key) &&
+            c.hosts[i].is_acceptor() &&
+            u.hosts[i].instances[key].current_ballot.num > 0 ==>
+            #[trigger] answered(u, i, key, u.hosts[i].instances[key].current_ballot)
+    }
+
+    // A host only ever raises `max_seen_ballot` to a ballot it prepared or heard of, so once only the
+    // leader prepares, nobody's `max_seen_ballot` can get ahead of the leader's newest ballot.
+    pub open spec fn max_seen_ballot_was_prepared(c: &Constants, u: &Variables) -> bool {
+        forall |i: int, key: nat|
+            0 <= i < u.hosts.len() &&
+            u.hosts[i].instances.contains_key(key) &&
+            u.hosts[i].instances[key].max_seen_ballot.num > 0 ==>
+            #[trigger] u.ballot_was_prepared(key, u.hosts[i].instances[key].max_seen_ballot)
+    }
+
+    // `inductive` and the invariants only the liveness proof needs.
+    pub open spec fn live_inductive(c: &Constants, u: &Variables) -> bool {
+        &&& inductive(c, u)
+        &&& current_ballot_was_answered(c, u)
+        &&& max_seen_ballot_was_prepared(c, u)
+    }
+
+    pub proof fn answered_is_stable(u: &Variables, v: &Variables, i: int, key: nat, ballot: host::Ballot)
+    requires
+        u.network.sent_messages.subset_of(v.network.sent_messages),
+        answered(u, i, key, ballot),
+    ensures
+        answered(v, i, key, ballot),
+    {
+        if (exists |accepted: Option<(host::Ballot, Value)>| #[trigger] u.network.sent_messages.contains(Message::Promise { key, sender: i as nat, ballot, accepted })) {
+            let accepted = choose |accepted: Option<(host::Ballot, Value)>| #[trigger] u.network.sent_messages.contains(Message::Promise { key, sender: i as nat, ballot, accepted });
+            assert(v.network.sent_messages.contains(Message::Promise { key, sender: i as nat, ballot, accepted }));
+        } else if (exists |value: Value| #[trigger] u.network.sent_messages.contains(Message::Accept { key, ballot, value })) {
+            let value = choose |value: Value| #[trigger] u.network.sent_messages.contains(Message::Accept { key, ballot, value });
+            assert(v.network.sent_messages.contains(Message::Accept { key, ballot, value }));
+        } else {
+            let value = choose |value: Value| #[trigger] u.network.sent_messages.contains(Message::Decide { key, ballot, value });
+            assert(v.network.sent_messages.contains(Message::Decide { key, ballot, value }));
+        }
+    }
+
+    // A Promise built by `promise_msg` answers the ballot it promises.
+    proof fn promise_msg_answers(u: &Variables, lc: &host::Constants, instance: host::Instance, i: int, key: nat, ballot: host::Ballot)
+    requires
+        lc.id == i,
+        u.network.sent_messages.contains(host::promise_msg(lc, instance, key, ballot)),
+    ensures
+        answered(u, i, key, ballot),
+    {
+        if let Message::Promise { accepted, .. } = host::promise_msg(lc, instance, key, ballot) {
+            assert(u.network.sent_messages.contains(Message::Promise { key, sender: i as nat, ballot, accepted }));
+        }
+    }
+
+    pub proof fn current_ballot_was_answered_is_inductive(c: &Constants, u: &Variables, v: &Variables, event: Event)
+    requires
+        inductive(c, u),
+        current_ballot_was_answered(c, u),
+        message_next(c, u, v, event),
+    ensures
+        current_ballot_was_answered(c, v),
+    {
+        next_is_host_next_or_network_only_next(c, u, v, event);
+        if (host_next(c, u, v, event)) {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, v, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id]);
+            assert(u.network.sent_messages.subset_of(v.network.sent_messages));
+
+            assert forall |i: int, key: nat|
+                0 <= i < v.hosts.len() &&
+                v.hosts[i].instances.contains_key(key) &&
+                c.hosts[i].is_acceptor() &&
+                v.hosts[i].instances[key].current_ballot.num > 0 implies
+                #[trigger] answered(v, i, key, v.hosts[i].instances[key].current_ballot)
+            by {
+                let ballot = v.hosts[i].instances[key].current_ballot;
+                assert(c.hosts[i].id == i);
+
+                // A new or raised instance promises its floor in the same step.
+                if ((i == host_id) && (key == step_key) && (host::init_request(lc, lu, lv, step_key, net_op) || host::raise_to_floor(lc, lu, lv, step_key, net_op))) {
+                    assert(ballot == host::floor_for(lu, key));
+                    if (host::init_request(lc, lu, lv, step_key, net_op)) {
+                        assert(net_op.send == Some(host::promise_msg(lc, lv.instances[key], key, ballot)));
+                        promise_msg_answers(v, lc, lv.instances[key], i, key, ballot);
+                    } else {
+                        assert(net_op.send == Some(host::promise_msg(lc, lu.instances[key], key, ballot)));
+                        promise_msg_answers(v, lc, lu.instances[key], i, key, ballot);
+                    }
+                } else {
+                    match (net_op.recv) {
+                        Some(Message::Prepare { key: recv_key, ballot: recv_ballot })
+                        if (host::promise(lc, lu, lv, step_key, net_op) && (i == host_id) && (key == step_key)) => {
+                            assert(ballot == recv_ballot);
+                            let accepted = if (lu.instances[key].accept_ballot.is_some()) {
+                                Some((lu.instances[key].accept_ballot.unwrap(), lu.instances[key].accept_value.unwrap()))
+                            } else {
+                                None
+                            };
+                            assert(v.network.sent_messages.contains(Message::Promise { key, sender: i as nat, ballot, accepted }));
+                        },
+                        Some(Message::Accept { key: recv_key, ballot: recv_ballot, value })
+                        if (host::accept(lc, lu, lv, step_key, net_op) && (i == host_id) && (key == step_key)) => {
+                            assert(v.network.sent_messages.contains(Message::Accept { key, ballot, value }));
+                        },
+                        Some(Message::Decide { key: recv_key, ballot: recv_ballot, value })
+                        if (event is Decide && host::decide(lc, lu, lv, step_key, net_op, value) && (i == host_id) && (key == step_key)) => {
+                            assert(v.network.sent_messages.contains(Message::Decide { key, ballot, value }));
+                        },
+                        _ => {
+                            assert(u.hosts[i].instances.contains_key(key) && u.hosts[i].instances[key].current_ballot == ballot);
+                            assert(answered(u, i, key, ballot));
+                            answered_is_stable(u, v, i, key, ballot);
+                        },
+                    }
+                }
+            };
+        } else {
+            assert forall |i: int, key: nat|
+                0 <= i < v.hosts.len() &&
+                v.hosts[i].instances.contains_key(key) &&
+                c.hosts[i].is_acceptor() &&
+                v.hosts[i].instances[key].current_ballot.num > 0 implies
+                #[trigger] answered(v, i, key, v.hosts[i].instances[key].current_ballot)
+            by {
+                assert(answered(u, i, key, u.hosts[i].instances[key].current_ballot));
+                answered_is_stable(u, v, i, key, u.hosts[i].instances[key].current_ballot);
+            };
+        }
+    }
+
+    pub proof fn max_seen_ballot_was_prepared_is_inductive(c: &Constants, u: &Variables, v: &Variables, event: Event)
+    requires
+        inductive(c, u),
+        max_seen_ballot_was_prepared(c, u),
+        message_next(c, u, v, event),
+    ensures
+        max_seen_ballot_was_prepared(c, v),
+    {
+        next_is_host_next_or_network_only_next(c, u, v, event);
+        if (host_next(c, u, v, event)) {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, v, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id]);
+            v.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+            assert(u.network.sent_messages.subset_of(v.network.sent_messages));
+
+            assert forall |i: int, key: nat|
+                0 <= i < v.hosts.len() &&
+                v.hosts[i].instances.contains_key(key) &&
+                v.hosts[i].instances[key].max_seen_ballot.num > 0 implies
+                #[trigger] v.ballot_was_prepared(key, v.hosts[i].instances[key].max_seen_ballot)
+            by {
+                let ballot = v.hosts[i].instances[key].max_seen_ballot;
+                if (i != host_id || key != step_key) {
+                    if (i == host_id) {
+                        assert(lv.instances[key] == lu.instances[key] || lv.instances[key] == host::durable_part(lu.instances[key]));
+                    } else {
+                        assert(v.hosts[i] == u.hosts[i]);
+                    }
+                    assert(u.ballot_was_prepared(key, ballot));
+                    v.ballot_was_prepared_is_stable(u, key, ballot);
+                } else if (!lu.instances.contains_key(key)) {
+                    // A new instance starts at its floor, which some PrepareFrom covering it prepared.
+                    assert(host::init_request(lc, lu, lv, key, net_op));
+                    assert(ballot == host::floor_for(lu, key));
+                    assert(key >= lu.multi.floor_from && ballot == lu.multi.promise_floor);
+                    assert(v.network.sent_messages.contains(Message::PrepareFrom { from: lu.multi.floor_from, ballot }));
+                } else if (host::send_prepare(lc, lu, lv, key, net_op)) {
+                    assert(net_op.send == Some(Message::Prepare { key, ballot }));
+                    assert(v.network.sent_messages.contains(Message::Prepare { key, ballot }));
+                } else if (ballot == lu.instances[key].max_seen_ballot) {
+                    assert(u.ballot_was_prepared(key, ballot));
+                    v.ballot_was_prepared_is_stable(u, key, ballot);
+                } else {
+                    // Every other step that moves `max_seen_ballot` takes the ballot of a floor, a
+                    // PrepareFrom it leads, or a message, all of which were prepared.
+                    if (host::raise_to_floor(lc, lu, lv, key, net_op)) {
+                        assert(key >= lu.multi.floor_from && ballot == lu.multi.promise_floor);
+                        assert(u.network.sent_messages.contains(Message::PrepareFrom { from: lu.multi.floor_from, ballot }));
+                    } else if (host::join_prepare_from(lc, lu, lv, key, net_op)) {
+                        let (from, leading) = lu.multi.leading.unwrap();
+                        assert(ballot == leading && from <= key);
+                        assert(u.network.sent_messages.contains(Message::PrepareFrom { from, ballot }));
+                    } else {
+                        match (net_op.recv) {
+                            Some(Message::Prepare { key: recv_key, ballot: recv_ballot }) => {
+                                assert(ballot == recv_ballot);
+                                assert(u.network.sent_messages.contains(Message::Prepare { key, ballot }));
+                            },
+                            Some(Message::Nack { key: recv_key, ballot: nacked_ballot, higher }) => {
+                                assert(ballot == higher);
+                                assert(u.ballot_was_prepared(key, higher));
+                            },
+                            Some(Message::Accept { key: recv_key, ballot: recv_ballot, value }) => {
+                                assert(ballot == recv_ballot);
+                                assert(u.history[ballot.pid as int][key].proposed_value.contains_key(ballot));
+                            },
+                            Some(Message::Accepted { key: recv_key, sender, ballot: recv_ballot, value }) => {
+                                assert(ballot == recv_ballot);
+                                assert(u.network.sent_messages.contains(Message::Accept { key, ballot, value }));
+                                assert(u.history[ballot.pid as int][key].proposed_value.contains_key(ballot));
+                            },
+                            Some(Message::Decide { key: recv_key, ballot: recv_ballot, value }) => {
+                                assert(ballot == recv_ballot);
+                                assert(u.history[ballot.pid as int][key].proposed_value.contains_key(ballot));
+                            },
+                            _ => {},
+                        }
+                        assert(u.ballot_was_prepared(key, ballot));
+                    }
+                    v.ballot_was_prepared_is_stable(u, key, ballot);
+                }
+            };
+        } else {
+            assert forall |i: int, key: nat|
+                0 <= i < v.hosts.len() &&
+                v.hosts[i].instances.contains_key(key) &&
+                v.hosts[i].instances[key].max_seen_ballot.num > 0 implies
+                #[trigger] v.ballot_was_prepared(key, v.hosts[i].instances[key].max_seen_ballot)
+            by {
+                v.ballot_was_prepared_is_stable(u, key, u.hosts[i].instances[key].max_seen_ballot);
+            };
+        }
+    }
+
+    pub proof fn behavior_is_live_inductive(c: &Constants, ex: Execution, i: nat)
+    requires
+        is_behavior(c, ex),
+    ensures
+        live_inductive(c, &(ex.states)(i)),
+    decreases
+        i
+    {
+        behavior_is_inductive(c, ex, i);
+        if (i > 0) {
+            let prev = (i - 1) as nat;
+            behavior_is_live_inductive(c, ex, prev);
+            assert(takes_step(c, ex, prev));
+            current_ballot_was_answered_is_inductive(c, &(ex.states)(prev), &(ex.states)(i), (ex.events)(prev));
+            max_seen_ballot_was_prepared_is_inductive(c, &(ex.states)(prev), &(ex.states)(i), (ex.events)(prev));
+        }
+    }
+
+    // What no step undoes. Durable ballots only go up, instances are never dropped, and a host that
+    // stays up keeps every ballot, proposal and vote in its memory; `history` keeps them regardless.
+    pub proof fn next_is_monotone(c: &Constants, u: &Variables, v: &Variables, event: Event)
+    requires
+        inductive(c, u),
+        message_next(c, u, v, event),
+    ensures
+        v.hosts.len() == u.hosts.len(),
+        u.network.sent_messages.subset_of(v.network.sent_messages),
+        forall |i: int, key: nat| #![trigger u.hosts[i].instances[key]]
+            0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) ==>
+            v.hosts[i].instances.contains_key(key) &&
+            v.hosts[i].instances[key].current_ballot.cmp(&u.hosts[i].instances[key].current_ballot) >= 0 &&
+            v.hosts[i].instances[key].max_seen_ballot.cmp(&u.hosts[i].instances[key].max_seen_ballot) >= 0 &&
+            v.hosts[i].instances[key].configuration == u.hosts[i].instances[key].configuration &&
+            (u.hosts[i].instances[key].decide_value.is_some() ==> v.hosts[i].instances[key].decide_value.is_some()),
+        forall |i: int, key: nat, ballot: host::Ballot| #![trigger u.hosts[i].instances[key].volatile.promised[ballot]]
+            0 <= i < u.hosts.len() && u.hosts[i].up && v.hosts[i].up &&
+            u.hosts[i].instances.contains_key(key) &&
+            u.hosts[i].instances[key].volatile.promised.contains_key(ballot) ==>
+            v.hosts[i].instances[key].volatile.promised.contains_key(ballot),
+        forall |i: int, key: nat, ballot: host::Ballot| #![trigger u.hosts[i].instances[key].volatile.proposed_value[ballot]]
+            0 <= i < u.hosts.len() && u.hosts[i].up && v.hosts[i].up &&
+            u.hosts[i].instances.contains_key(key) &&
+            u.hosts[i].instances[key].volatile.proposed_value.contains_key(ballot) ==>
+            v.hosts[i].instances[key].volatile.proposed_value.contains_key(ballot) &&
+            v.hosts[i].instances[key].volatile.proposed_value[ballot] == u.hosts[i].instances[key].volatile.proposed_value[ballot],
+        forall |i: int, key: nat, ballot: host::Ballot| #![trigger u.history[i][key].promised[ballot]]
+            0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) &&
+            u.history[i][key].promised.contains_key(ballot) ==>
+            v.history[i][key].promised.contains_key(ballot) &&
+            u.history[i][key].promised[ballot].dom().subset_of(v.history[i][key].promised[ballot].dom()),
+        forall |i: int, key: nat, ballot: host::Ballot| #![trigger u.history[i][key].accepted[ballot]]
+            0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) &&
+            u.history[i][key].accepted.contains_key(ballot) ==>
+            v.history[i][key].accepted.contains_key(ballot) &&
+            u.history[i][key].accepted[ballot].subset_of(v.history[i][key].accepted[ballot]),
+        forall |i: int| #![trigger u.hosts[i].multi]
+            0 <= i < u.hosts.len() ==>
+            v.hosts[i].multi.promise_floor.cmp(&u.hosts[i].multi.promise_floor) >= 0 &&
+            (v.hosts[i].multi.promise_floor == u.hosts[i].multi.promise_floor ==> v.hosts[i].multi.floor_from == u.hosts[i].multi.floor_from),
+    {
+        next_is_host_next_or_network_only_next(c, u, v, event);
+        if (host_next(c, u, v, event)) {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, v, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id]);
+            v.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+            v.host_step_keeps_configurations(c, u, event, host_id, step_key, net_op);
+            assert(u.network.sent_messages.subset_of(v.network.sent_messages));
+
+            assert forall |i: int, key: nat| #![trigger u.hosts[i].instances[key]]
+                0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) implies
+                v.hosts[i].instances.contains_key(key) &&
+                v.hosts[i].instances[key].current_ballot.cmp(&u.hosts[i].instances[key].current_ballot) >= 0 &&
+                v.hosts[i].instances[key].max_seen_ballot.cmp(&u.hosts[i].instances[key].max_seen_ballot) >= 0 &&
+                v.hosts[i].instances[key].configuration == u.hosts[i].instances[key].configuration &&
+                (u.hosts[i].instances[key].decide_value.is_some() ==> v.hosts[i].instances[key].decide_value.is_some())
+            by {
+                if (i == host_id) {
+                    if (key != step_key) {
+                        assert(lv.instances[key] == lu.instances[key] || lv.instances[key] == host::durable_part(lu.instances[key]));
+                    } else if (host::send_prepare(lc, lu, lv, key, net_op)) {
+                        assert(lv.instances[key].max_seen_ballot == host::next_ballot(lc, lu.instances[key]));
+                    }
+                } else {
+                    assert(v.hosts[i] == u.hosts[i]);
+                }
+            };
+
+            assert forall |i: int, key: nat, ballot: host::Ballot| #![trigger u.hosts[i].instances[key].volatile.promised[ballot]]
+                0 <= i < u.hosts.len() && u.hosts[i].up && v.hosts[i].up &&
+                u.hosts[i].instances.contains_key(key) &&
+                u.hosts[i].instances[key].volatile.promised.contains_key(ballot) implies
+                v.hosts[i].instances[key].volatile.promised.contains_key(ballot)
+            by {
+                if (i == host_id) {
+                    assert(!host::recover(lc, lu, lv, net_op) && !host::crash(lc, lu, lv, net_op));
+                    if (key != step_key) {
+                        assert(lv.instances[key] == lu.instances[key]);
+                    }
+                } else {
+                    assert(v.hosts[i] == u.hosts[i]);
+                }
+            };
+
+            assert forall |i: int, key: nat, ballot: host::Ballot| #![trigger u.hosts[i].instances[key].volatile.proposed_value[ballot]]
+                0 <= i < u.hosts.len() && u.hosts[i].up && v.hosts[i].up &&
+                u.hosts[i].instances.contains_key(key) &&
+                u.hosts[i].instances[key].volatile.proposed_value.contains_key(ballot) implies
+                v.hosts[i].instances[key].volatile.proposed_value.contains_key(ballot) &&
+                v.hosts[i].instances[key].volatile.proposed_value[ballot] == u.hosts[i].instances[key].volatile.proposed_value[ballot]
+            by {
+                if (i == host_id) {
+                    assert(!host::recover(lc, lu, lv, net_op) && !host::crash(lc, lu, lv, net_op));
+                    if (key != step_key) {
+                        assert(lv.instances[key] == lu.instances[key]);
+                    } else if (host::send_accept(lc, lu, lv, key, net_op)) {
+                        assert(ballot != lu.instances[key].current_ballot);
+                    }
+                } else {
+                    assert(v.hosts[i] == u.hosts[i]);
+                }
+            };
+
+            assert forall |i: int, key: nat, ballot: host::Ballot| #![trigger u.history[i][key].promised[ballot]]
+                0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) &&
+                u.history[i][key].promised.contains_key(ballot) implies
+                v.history[i][key].promised.contains_key(ballot) &&
+                u.history[i][key].promised[ballot].dom().subset_of(v.history[i][key].promised[ballot].dom())
+            by {
+                if (i == host_id && key == step_key) {
+                    let (old, new) = (u.history[i][key], v.history[i][key]);
+                    if (host::send_prepare(lc, lu, lv, key, net_op) || host::join_prepare_from(lc, lu, lv, key, net_op)) {
+                        assert(new.promised[ballot] == old.promised[ballot]);
+                    } else if (host::promised(lc, lu, lv, key, net_op)) {
+                        if let Some(Message::Promise { sender, ballot: recv_ballot, accepted, .. }) = net_op.recv {
+                            if (ballot == recv_ballot) {
+                                assert(new.promised[ballot] == old.promised[ballot].insert(sender, accepted));
+                            } else {
+                                assert(new.promised[ballot] == old.promised[ballot]);
+                            }
+                        }
+                    } else {
+                        assert(new.promised == old.promised);
+                    }
+                }
+            };
+
+            assert forall |i: int, key: nat, ballot: host::Ballot| #![trigger u.history[i][key].accepted[ballot]]
+                0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) &&
+                u.history[i][key].accepted.contains_key(ballot) implies
+                v.history[i][key].accepted.contains_key(ballot) &&
+                u.history[i][key].accepted[ballot].subset_of(v.history[i][key].accepted[ballot])
+            by {
+                if (i == host_id && key == step_key) {
+                    let (old, new) = (u.history[i][key], v.history[i][key]);
+                    if (host::send_prepare(lc, lu, lv, key, net_op) || host::join_prepare_from(lc, lu, lv, key, net_op)) {
+                        assert(new.accepted[ballot] == old.accepted[ballot]);
+                    } else if (host::accepted(lc, lu, lv, key, net_op)) {
+                        if let Some(Message::Accepted { sender, ballot: recv_ballot, .. }) = net_op.recv {
+                            if (ballot == recv_ballot) {
+                                assert(new.accepted[ballot] == old.accepted[ballot].insert(sender));
+                            } else {
+                                assert(new.accepted[ballot] == old.accepted[ballot]);
+                            }
+                        }
+                    } else {
+                        assert(new.accepted == old.accepted);
+                    }
+                }
+            };
+
+            assert forall |i: int| #![trigger u.hosts[i].multi]
+                0 <= i < u.hosts.len() implies
+                v.hosts[i].multi.promise_floor.cmp(&u.hosts[i].multi.promise_floor) >= 0 &&
+                (v.hosts[i].multi.promise_floor == u.hosts[i].multi.promise_floor ==> v.hosts[i].multi.floor_from == u.hosts[i].multi.floor_from)
+            by {
+                if (i == host_id && host::promise_from(lc, lu, lv, step_key, net_op)) {
+                    assert(lv.multi.promise_floor.cmp(&lu.multi.promise_floor) > 0);
+                } else if (i == host_id) {
+                    assert(lv.multi.promise_floor == lu.multi.promise_floor && lv.multi.floor_from == lu.multi.floor_from);
+                } else {
+                    assert(v.hosts[i] == u.hosts[i]);
+                }
+            };
+        }
+    }
+
+    // Running a host step from `u`, with the network handing over `net_op.recv` and keeping it in flight.
+    pub open spec fn successor(u: Variables, host_id: int, host: host::Variables, send: Option<Message>) -> Variables {
+        Variables {
+            hosts: u.hosts.update(host_id, host),
+            network: network::Variables {
+                sent_messages: if let Some(m) = send { u.network.sent_messages.insert(m) } else { u.network.sent_messages },
+                in_flight_messages: if let Some(m) = send { u.network.in_flight_messages.insert(m) } else { u.network.in_flight_messages },
+            },
+            history: u.history.update(host_id, record(u.history[host_id], host)),
+        }
+    }
+
+    pub proof fn successor_is_valid(c: &Constants, u: &Variables, host_id: int, key: nat, net_op: NetworkOperation, event: Event, host: host::Variables)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        0 <= host_id < u.hosts.len(),
+        host::step(&c.hosts[host_id], &u.hosts[host_id], &host, key, net_op, event),
+        if let Some(m) = net_op.recv { u.network.in_flight_messages.contains(m) } else { true },
+        !(event is Submit),
+    ensures
+        is_valid_transition(c, u, &successor(*u, host_id, host, net_op.send), Transition::HostStep { host_id, instance: key, net_op }, event),
+    {
+        let v = successor(*u, host_id, host, net_op.send);
+        assert(v.hosts[host_id] == host);
+        assert forall |idx: nat| #![auto] 0 <= idx < v.hosts.len() implies v.hosts[idx as int].well_formed(&c.hosts[idx as int]) by {
+            if (idx != host_id) {
+                assert(v.hosts[idx as int] == u.hosts[idx as int]);
+            }
+        };
+        assert forall |idx: int| #![auto] 0 <= idx < v.hosts.len() implies v.history[idx].dom() == v.hosts[idx].instances.dom() by {
+            if (idx == host_id) {
+                assert(v.history[idx].dom() =~= host.instances.dom());
+            }
+        };
+        assert(v.well_formed(c));
+        assert forall |i: int| #![auto] 0 <= i < v.hosts.len() && i != host_id implies u.hosts[i] == v.hosts[i] by {};
+    }
+
+    // Host `host_id` can run `handler` in `u` by stepping to `host`.
+    pub proof fn handler_is_enabled(c: &Constants, u: &Variables, host_id: nat, key: nat, handler: Handler, net_op: NetworkOperation, event: Event, host: host::Variables)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        host_id < u.hosts.len(),
+        host::step(&c.hosts[host_id as int], &u.hosts[host_id as int], &host, key, net_op, event),
+        runs(handler, &c.hosts[host_id as int], &u.hosts[host_id as int], &host, key, net_op, event),
+        if let Some(m) = net_op.recv { u.network.in_flight_messages.contains(m) } else { true },
+        !(event is Submit),
+    ensures
+        enabled(host_action(c, host_id, key, handler, net_op.recv))(*u),
+    {
+        let v = successor(*u, host_id as int, host, net_op.send);
+        successor_is_valid(c, u, host_id as int, key, net_op, event, host);
+        assert(v.hosts[host_id as int] == host);
+        assert(host_action(c, host_id, key, handler, net_op.recv)(*u, v, event));
+    }
+
+    pub open spec fn with_instance(u: host::Variables, key: nat, instance: host::Instance) -> host::Variables {
+        host::Variables { instances: u.instances.insert(key, instance), pending: u.pending, assigned: u.assigned, up: u.up, multi: u.multi }
+    }
+
+    pub proof fn init_request_is_enabled(c: &Constants, u: &Variables, h: nat, key: nat)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        h < u.hosts.len(),
+        u.hosts[h as int].up,
+        !u.hosts[h as int].instances.contains_key(key),
+        c.hosts[h as int].knows_configuration(&u.hosts[h as int], key),
+    ensures
+        enabled(host_action(c, h, key, Handler::InitRequest, None))(*u),
+    {
+        let (lc, lu) = (&c.hosts[h as int], u.hosts[h as int]);
+        let floor = host::floor_for(&lu, key);
+        let instance = host::Instance {
+            current_ballot: floor, max_seen_ballot: floor, accept_ballot: None, accept_value: None, decide_value: None,
+            configuration: lc.configuration(&lu, key), volatile: host::Volatile::empty(),
+        };
+        let lv = with_instance(lu, key, instance);
+        let send = if (lc.is_acceptor() && floor.num > 0) { Some(host::promise_msg(lc, instance, key, floor)) } else { None };
+        let net_op = NetworkOperation { send, recv: None };
+        assert(lv.instances[key] == instance);
+        assert(host::init_request(lc, &lu, &lv, key, net_op));
+        handler_is_enabled(c, u, h, key, Handler::InitRequest, net_op, Event::NoOp, lv);
+    }
+
+    pub proof fn raise_to_floor_is_enabled(c: &Constants, u: &Variables, h: nat, key: nat)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        h < u.hosts.len(),
+        u.hosts[h as int].up,
+        u.hosts[h as int].instances.contains_key(key),
+        u.hosts[h as int].instances[key].current_ballot.cmp(&host::floor_for(&u.hosts[h as int], key)) < 0,
+    ensures
+        enabled(host_action(c, h, key, Handler::RaiseToFloor, None))(*u),
+    {
+        let (lc, lu) = (&c.hosts[h as int], u.hosts[h as int]);
+        let floor = host::floor_for(&lu, key);
+        let old = lu.instances[key];
+        let lv = with_instance(lu, key, host::Instance {
+            current_ballot: floor, max_seen_ballot: host::max_ballot(old.max_seen_ballot, floor),
+            accept_ballot: old.accept_ballot, accept_value: old.accept_value, decide_value: old.decide_value,
+            configuration: old.configuration, volatile: old.volatile,
+        });
+        let send = if (lc.is_acceptor()) { Some(host::promise_msg(lc, old, key, floor)) } else { None };
+        let net_op = NetworkOperation { send, recv: None };
+        assert(host::raise_to_floor(lc, &lu, &lv, key, net_op));
+        handler_is_enabled(c, u, h, key, Handler::RaiseToFloor, net_op, Event::NoOp, lv);
+    }
+
+    pub proof fn promise_is_enabled(c: &Constants, u: &Variables, h: nat, key: nat, ballot: host::Ballot)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        h < u.hosts.len(),
+        u.hosts[h as int].up,
+        c.hosts[h as int].is_acceptor(),
+        u.hosts[h as int].instances.contains_key(key),
+        u.hosts[h as int].instances[key].current_ballot.cmp(&host::floor_for(&u.hosts[h as int], key)) >= 0,
+        ballot.cmp(&u.hosts[h as int].instances[key].current_ballot) == 1,
+        u.network.in_flight_messages.contains(Message::Prepare { key, ballot }),
+    ensures
+        enabled(host_action(c, h, key, Handler::Promise, Some(Message::Prepare { key, ballot })))(*u),
+    {
+        let (lc, lu) = (&c.hosts[h as int], u.hosts[h as int]);
+        let old = lu.instances[key];
+        let lv = with_instance(lu, key, host::Instance {
+            current_ballot: ballot, max_seen_ballot: host::max_ballot(old.max_seen_ballot, ballot),
+            accept_ballot: old.accept_ballot, accept_value: old.accept_value, decide_value: old.decide_value,
+            configuration: old.configuration, volatile: old.volatile,
+        });
+        let send = if (old.accept_ballot.is_some()) {
+            Some(Message::Promise { key, sender: lc.id, ballot, accepted: Some((old.accept_ballot.unwrap(), old.accept_value.unwrap())) })
+        } else {
+            Some(Message::Promise { key, sender: lc.id, ballot, accepted: None })
+        };
+        let net_op = NetworkOperation { send, recv: Some(Message::Prepare { key, ballot }) };
+        assert(host::promise(lc, &lu, &lv, key, net_op));
+        handler_is_enabled(c, u, h, key, Handler::Promise, net_op, Event::NoOp, lv);
+    }
+
+    pub proof fn reject_prepare_is_enabled(c: &Constants, u: &Variables, h: nat, key: nat, ballot: host::Ballot)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        h < u.hosts.len(),
+        u.hosts[h as int].up,
+        c.hosts[h as int].is_acceptor(),
+        u.hosts[h as int].instances.contains_key(key),
+        u.hosts[h as int].instances[key].current_ballot.cmp(&host::floor_for(&u.hosts[h as int], key)) >= 0,
+        ballot.cmp(&u.hosts[h as int].instances[key].current_ballot) < 0,
+        u.network.in_flight_messages.contains(Message::Prepare { key, ballot }),
+    ensures
+        enabled(host_action(c, h, key, Handler::RejectPrepare, Some(Message::Prepare { key, ballot })))(*u),
+    {
+        let (lc, lu) = (&c.hosts[h as int], u.hosts[h as int]);
+        let send = Some(Message::Nack { key, ballot, higher: lu.instances[key].current_ballot });
+        let net_op = NetworkOperation { send, recv: Some(Message::Prepare { key, ballot }) };
+        assert(host::reject_prepare(lc, &lu, &lu, key, net_op));
+        handler_is_enabled(c, u, h, key, Handler::RejectPrepare, net_op, Event::NoOp, lu);
+    }
+
+    pub proof fn promised_is_enabled(c: &Constants, u: &Variables, h: nat, key: nat, sender: nat, ballot: host::Ballot, accepted: Option<(host::Ballot, Value)>)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        h < u.hosts.len(),
+        u.hosts[h as int].up,
+        u.hosts[h as int].instances.contains_key(key),
+        u.hosts[h as int].instances[key].volatile.promised.contains_key(ballot),
+        !u.hosts[h as int].instances[key].volatile.proposed_value.contains_key(ballot),
+        u.network.in_flight_messages.contains(Message::Promise { key, sender, ballot, accepted }),
+    ensures
+        enabled(host_action(c, h, key, Handler::Promised, Some(Message::Promise { key, sender, ballot, accepted })))(*u),
+    {
+        let (lc, lu) = (&c.hosts[h as int], u.hosts[h as int]);
+        let old = lu.instances[key];
+        let lv = with_instance(lu, key, host::Instance {
+            current_ballot: old.current_ballot, max_seen_ballot: old.max_seen_ballot,
+            accept_ballot: old.accept_ballot, accept_value: old.accept_value, decide_value: old.decide_value, configuration: old.configuration,
+            volatile: host::Volatile {
+                promised: old.volatile.promised.insert(ballot, old.volatile.promised[ballot].insert(sender, accepted)),
+                proposed_value: old.volatile.proposed_value,
+                accepted: old.volatile.accepted,
+                learned: old.volatile.learned,
+            },
+        });
+        let net_op = NetworkOperation { send: None, recv: Some(Message::Promise { key, sender, ballot, accepted }) };
+        assert(host::promised(lc, &lu, &lv, key, net_op));
+        handler_is_enabled(c, u, h, key, Handler::Promised, net_op, Event::NoOp, lv);
+    }
+
+    pub proof fn nacked_is_enabled(c: &Constants, u: &Variables, h: nat, key: nat, ballot: host::Ballot, higher: host::Ballot)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        h < u.hosts.len(),
+        u.hosts[h as int].up,
+        ballot.pid == h,
+        u.hosts[h as int].instances.contains_key(key),
+        higher.cmp(&u.hosts[h as int].instances[key].max_seen_ballot) > 0,
+        u.network.in_flight_messages.contains(Message::Nack { key, ballot, higher }),
+    ensures
+        enabled(host_action(c, h, key, Handler::Nacked, Some(Message::Nack { key, ballot, higher })))(*u),
+    {
+        let (lc, lu) = (&c.hosts[h as int], u.hosts[h as int]);
+        let old = lu.instances[key];
+        assert(lc.id == h);
+        let lv = with_instance(lu, key, host::Instance {
+            current_ballot: old.current_ballot, max_seen_ballot: higher,
+            accept_ballot: old.accept_ballot, accept_value: old.accept_value, decide_value: old.decide_value,
+            configuration: old.configuration, volatile: old.volatile,
+        });
+        let net_op = NetworkOperation { send: None, recv: Some(Message::Nack { key, ballot, higher }) };
+        assert(host::nacked(lc, &lu, &lv, key, net_op));
+        handler_is_enabled(c, u, h, key, Handler::Nacked, net_op, Event::NoOp, lv);
+    }
+
+    pub proof fn send_accept_is_enabled(c: &Constants, u: &Variables, h: nat, key: nat)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        h < u.hosts.len(),
+        u.hosts[h as int].up,
+        u.hosts[h as int].instances.contains_key(key),
+        u.hosts[h as int].instances[key].volatile.promised.contains_key(u.hosts[h as int].instances[key].current_ballot),
+        u.hosts[h as int].instances[key].configuration.classic.is_phase1_quorum(u.hosts[h as int].instances[key].volatile.promised[u.hosts[h as int].instances[key].current_ballot].dom()),
+        !u.hosts[h as int].instances[key].volatile.proposed_value.contains_key(u.hosts[h as int].instances[key].current_ballot),
+    ensures
+        enabled(host_action(c, h, key, Handler::SendAccept, None))(*u),
+    {
+        let (lc, lu) = (&c.hosts[h as int], u.hosts[h as int]);
+        let old = lu.instances[key];
+        let ballot = old.current_ballot;
+        let max_accepted_value = fast::select_value(old.configuration, lc.num_hosts, old.volatile.promised[ballot]);
+        let takes_request = max_accepted_value.is_none() && lu.pending.len() > 0;
+        let value = if let Some((_, value)) = max_accepted_value { value } else if (lu.pending.len() > 0) { lu.pending[0] } else { no_op(key) };
+        let instance = host::Instance {
+            current_ballot: ballot, max_seen_ballot: old.max_seen_ballot,
+            accept_ballot: old.accept_ballot, accept_value: old.accept_value, decide_value: old.decide_value, configuration: old.configuration,
+            volatile: host::Volatile {
+                promised: old.volatile.promised,
+                proposed_value: old.volatile.proposed_value.insert(ballot, value),
+                accepted: old.volatile.accepted,
+                learned: old.volatile.learned,
+            },
+        };
+        let lv = host::Variables {
+            instances: lu.instances.insert(key, instance),
+            pending: if (takes_request) { lu.pending.drop_first() } else { lu.pending },
+            assigned: if (takes_request) { lu.assigned.insert(lu.pending[0].request()) } else { lu.assigned },
+            up: lu.up,
+            multi: lu.multi,
+        };
+        let net_op = NetworkOperation { send: Some(Message::Accept { key, ballot, value }), recv: None };
+        assert(lv.instances[key] == instance);
+        assert(host::send_accept(lc, &lu, &lv, key, net_op));
+        handler_is_enabled(c, u, h, key, Handler::SendAccept, net_op, Event::Propose { key, value }, lv);
+    }
+
+    pub proof fn accept_is_enabled(c: &Constants, u: &Variables, h: nat, key: nat, ballot: host::Ballot, value: Value)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        h < u.hosts.len(),
+        u.hosts[h as int].up,
+        c.hosts[h as int].is_acceptor(),
+        u.hosts[h as int].instances.contains_key(key),
+        u.hosts[h as int].instances[key].current_ballot.cmp(&host::floor_for(&u.hosts[h as int], key)) >= 0,
+        ballot.cmp(&u.hosts[h as int].instances[key].current_ballot) >= 0,
+        u.network.in_flight_messages.contains(Message::Accept { key, ballot, value }),
+    ensures
+        enabled(host_action(c, h, key, Handler::Accept, Some(Message::Accept { key, ballot, value })))(*u),
+    {
+        let (lc, lu) = (&c.hosts[h as int], u.hosts[h as int]);
+        let old = lu.instances[key];
+        let lv = with_instance(lu, key, host::Instance {
+            current_ballot: ballot, max_seen_ballot: host::max_ballot(old.max_seen_ballot, ballot),
+            accept_ballot: Some(ballot), accept_value: Some(value), decide_value: old.decide_value,
+            configuration: old.configuration, volatile: old.volatile,
+        });
+        let net_op = NetworkOperation { send: Some(Message::Accepted { key, sender: lc.id, ballot, value }), recv: Some(Message::Accept { key, ballot, value }) };
+        assert(host::accept(lc, &lu, &lv, key, net_op));
+        handler_is_enabled(c, u, h, key, Handler::Accept, net_op, Event::NoOp, lv);
+    }
+
+    pub proof fn reject_accept_is_enabled(c: &Constants, u: &Variables, h: nat, key: nat, ballot: host::Ballot, value: Value)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        h < u.hosts.len(),
+        u.hosts[h as int].up,
+        c.hosts[h as int].is_acceptor(),
+        u.hosts[h as int].instances.contains_key(key),
+        u.hosts[h as int].instances[key].current_ballot.cmp(&host::floor_for(&u.hosts[h as int], key)) >= 0,
+        ballot.cmp(&u.hosts[h as int].instances[key].current_ballot) < 0,
+        u.network.in_flight_messages.contains(Message::Accept { key, ballot, value }),
+    ensures
+        enabled(host_action(c, h, key, Handler::RejectAccept, Some(Message::Accept { key, ballot, value })))(*u),
+    {
+        let (lc, lu) = (&c.hosts[h as int], u.hosts[h as int]);
+        let send = Some(Message::Nack { key, ballot, higher: lu.instances[key].current_ballot });
+        let net_op = NetworkOperation { send, recv: Some(Message::Accept { key, ballot, value }) };
+        assert(host::reject_accept(lc, &lu, &lu, key, net_op));
+        handler_is_enabled(c, u, h, key, Handler::RejectAccept, net_op, Event::NoOp, lu);
+    }
+
+    pub proof fn accepted_is_enabled(c: &Constants, u: &Variables, h: nat, key: nat, sender: nat, ballot: host::Ballot, value: Value)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        h < u.hosts.len(),
+        u.hosts[h as int].up,
+        u.hosts[h as int].instances.contains_key(key),
+        u.hosts[h as int].instances[key].volatile.accepted.contains_key(ballot),
+        u.network.in_flight_messages.contains(Message::Accepted { key, sender, ballot, value }),
+    ensures
+        enabled(host_action(c, h, key, Handler::Accepted, Some(Message::Accepted { key, sender, ballot, value })))(*u),
+    {
+        let (lc, lu) = (&c.hosts[h as int], u.hosts[h as int]);
+        let old = lu.instances[key];
+        let lv = with_instance(lu, key, host::Instance {
+            current_ballot: old.current_ballot, max_seen_ballot: old.max_seen_ballot,
+            accept_ballot: old.accept_ballot, accept_value: old.accept_value, decide_value: old.decide_value, configuration: old.configuration,
+            volatile: host::Volatile {
+                promised: old.volatile.promised,
+                proposed_value: old.volatile.proposed_value,
+                accepted: old.volatile.accepted.insert(ballot, old.volatile.accepted[ballot].insert(sender)),
+                learned: old.volatile.learned,
+            },
+        });
+        let net_op = NetworkOperation { send: None, recv: Some(Message::Accepted { key, sender, ballot, value }) };
+        assert(host::accepted(lc, &lu, &lv, key, net_op));
+        handler_is_enabled(c, u, h, key, Handler::Accepted, net_op, Event::NoOp, lv);
+    }
+
+    pub proof fn learn_is_enabled(c: &Constants, u: &Variables, h: nat, key: nat)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        h < u.hosts.len(),
+        u.hosts[h as int].up,
+        u.hosts[h as int].instances.contains_key(key),
+        u.hosts[h as int].instances[key].volatile.proposed_value.contains_key(u.hosts[h as int].instances[key].current_ballot),
+        u.hosts[h as int].instances[key].volatile.accepted.contains_key(u.hosts[h as int].instances[key].current_ballot),
+        u.hosts[h as int].instances[key].configuration.classic.is_phase2_quorum(u.hosts[h as int].instances[key].volatile.accepted[u.hosts[h as int].instances[key].current_ballot]),
+    ensures
+        enabled(host_action(c, h, key, Handler::Learn, None))(*u),
+    {
+        let (lc, lu) = (&c.hosts[h as int], u.hosts[h as int]);
+        let old = lu.instances[key];
+        let ballot = old.current_ballot;
+        let value = old.volatile.proposed_value[ballot];
+        let lv = with_instance(lu, key, host::Instance {
+            current_ballot: ballot, max_seen_ballot: old.max_seen_ballot,
+            accept_ballot: old.accept_ballot, accept_value: old.accept_value, decide_value: Some(value),
+            configuration: old.configuration, volatile: old.volatile,
+        });
+        let net_op = NetworkOperation { send: Some(Message::Decide { key, ballot, value }), recv: None };
+        assert(host::learn(lc, &lu, &lv, key, net_op, value));
+        handler_is_enabled(c, u, h, key, Handler::Learn, net_op, Event::Decide { key, value }, lv);
+    }
+
+    pub proof fn leader_times_out_is_enabled(c: &Constants, u: &Variables, leader: nat, key: nat)
+    requires
+        u.well_formed(c),
+        !c.network.consume_on_receive,
+        leader < u.hosts.len(),
+        c.proposers.contains(leader),
+        u.hosts[leader as int].up,
+        leader_is_stuck(leader, key)(*u),
+        u.hosts[leader as int].instances[key].decide_value.is_none(),
+    ensures
+        enabled(leader_times_out(c, leader, key))(*u),
+    {
+        let (lc, lu) = (&c.hosts[leader as int], u.hosts[leader as int]);
+        let old = lu.instances[key];
+        let ballot = host::next_ballot(lc, old);
+        let lv = with_instance(lu, key, host::Instance {
+            current_ballot: old.current_ballot, max_seen_ballot: ballot,
+            accept_ballot: old.accept_ballot, accept_value: old.accept_value, decide_value: old.decide_value, configuration: old.configuration,
+            volatile: host::Volatile {
+                promised: old.volatile.promised.insert(ballot, Map::empty()),
+                proposed_value: old.volatile.proposed_value,
+                accepted: old.volatile.accepted.insert(ballot, Set::empty()),
+                learned: old.volatile.learned,
+            },
+        });
+        let net_op = NetworkOperation { send: Some(Message::Prepare { key, ballot }), recv: None };
+        assert(lc.id == leader && lc.proposers == c.proposers);
+        assert(host::send_prepare(lc, &lu, &lv, key, net_op));
+        let v = successor(*u, leader as int, lv, net_op.send);
+        successor_is_valid(c, u, leader as int, key, net_op, Event::NoOp, lv);
+        assert(v.hosts[leader as int] == lv);
+        assert(leader_times_out(c, leader, key)(*u, v, Event::NoOp));
+    }
+}
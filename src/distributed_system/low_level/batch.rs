@@ -0,0 +1,827 @@
+use super::{
+    fast::select_value,
+    host::{self, Ballot, Constants, Instance, Variables, Volatile},
+    Message, NetworkOperation,
+};
+use crate::distributed_system::{no_op, Event, Value};
+use vstd::prelude::*;
+
+verus! {
+    // Batched phase 2. An AcceptBatch, AcceptedBatch or DecideBatch carries the single-key messages
+    // `unbatch` lists, one per entry and all at the same ballot. A leader proposes in several instances
+    // at once and sends their Accepts only as one AcceptBatch, or announces the decisions it has an
+    // Accepted quorum for as one DecideBatch; an acceptor takes an AcceptBatch whole and answers with
+    // one AcceptedBatch, which the leader counts whole; and a host takes every decision of a DecideBatch
+    // in one step. Each batch step is a run of single-key `host::step`s (`step_is_run`) on messages the
+    // batch carries, with the events `Event::unbatch` lists, and `network::step` records every carried
+    // message in the sent history, so the invariants in `low_level/mod.rs`, which only talk about
+    // single-key messages, carry over (`batch_next_is_run_of_host_steps`).
+    pub open spec fn is_batch(message: Message) -> bool {
+        ||| message is AcceptBatch
+        ||| message is AcceptedBatch
+        ||| message is DecideBatch
+    }
+
+    pub open spec fn entries(message: Message) -> Seq<(nat, Value)> {
+        match message {
+            Message::AcceptBatch { entries, .. } => entries,
+            Message::AcceptedBatch { entries, .. } => entries,
+            Message::DecideBatch { entries, .. } => entries,
+            _ => Seq::empty(),
+        }
+    }
+
+    pub open spec fn unbatch(message: Message) -> Seq<Message> {
+        match message {
+            Message::AcceptBatch { ballot, entries } =>
+                entries.map_values(|entry: (nat, Value)| Message::Accept { key: entry.0, ballot, value: entry.1 }),
+            Message::AcceptedBatch { sender, ballot, entries } =>
+                entries.map_values(|entry: (nat, Value)| Message::Accepted { key: entry.0, sender, ballot, value: entry.1 }),
+            Message::DecideBatch { ballot, entries } =>
+                entries.map_values(|entry: (nat, Value)| Message::Decide { key: entry.0, ballot, value: entry.1 }),
+            _ => Seq::empty(),
+        }
+    }
+
+    // Every instance appears at most once, so the entries of a batch touch disjoint instances.
+    pub open spec fn has_distinct_keys(entries: Seq<(nat, Value)>) -> bool {
+        forall |i: int, j: int| #![trigger entries[i], entries[j]]
+            0 <= i < entries.len() &&
+            0 <= j < entries.len() &&
+            i != j ==>
+            entries[i].0 != entries[j].0
+    }
+
+    // Step `j` of a batch: instance `entries[j].0` becomes `update(instance, j)` and nothing else changes.
+    pub open spec fn apply_entry(s: Variables, entries: Seq<(nat, Value)>, update: spec_fn(Instance, int) -> Instance, j: int) -> Variables {
+        Variables {
+            instances: s.instances.insert(entries[j].0, update(s.instances[entries[j].0], j)),
+            pending: s.pending,
+            assigned: s.assigned,
+            up: s.up,
+            multi: s.multi,
+        }
+    }
+
+    // The host after the first `n` steps of a batch.
+    pub open spec fn apply_entries(u: Variables, entries: Seq<(nat, Value)>, update: spec_fn(Instance, int) -> Instance, n: nat) -> Variables
+    decreases
+        n
+    {
+        if (n == 0) {
+            u
+        } else {
+            apply_entry(apply_entries(u, entries, update, (n - 1) as nat), entries, update, n - 1)
+        }
+    }
+
+    // `states` goes from its first to its last element by one single-key host step per element of
+    // `steps`: step `j` is on instance `steps[j].0`, with network operation `steps[j].1` and event
+    // `steps[j].2`.
+    pub open spec fn is_run(c: &Constants, states: Seq<Variables>, steps: Seq<(nat, NetworkOperation, Event)>) -> bool {
+        &&& states.len() == steps.len() + 1
+        &&& forall |j: int| #![trigger states[j]]
+                0 <= j < steps.len() ==>
+                host::step(c, &states[j], &states[j + 1], steps[j].0, steps[j].1, steps[j].2)
+    }
+
+    // `later` has the instances of `earlier` and still holds every ballot and vote it held, so
+    // recording `earlier` and then `later` in the history is recording `later`.
+    pub open spec fn keeps_memory(earlier: Variables, later: Variables) -> bool {
+        &&& later.instances.dom() == earlier.instances.dom()
+        &&& forall |key: nat| #[trigger] earlier.instances.contains_key(key) ==> {
+                let (a, b) = (earlier.instances[key].volatile, later.instances[key].volatile);
+                &&& a.promised.dom().subset_of(b.promised.dom())
+                &&& a.proposed_value.dom().subset_of(b.proposed_value.dom())
+                &&& a.accepted.dom().subset_of(b.accepted.dom())
+                &&& a.learned.dom().subset_of(b.learned.dom())
+            }
+    }
+
+    // `message` is absent exactly when `batch` is, and otherwise one of the messages it carries.
+    pub open spec fn is_carried_by(message: Option<Message>, batch: Option<Message>) -> bool {
+        match (message, batch) {
+            (None, None) => true,
+            (Some(message), Some(batch)) => unbatch(batch).contains(message),
+            _ => false,
+        }
+    }
+
+    // How a run stands for the batch step with `net_op` and `event`. Every step receives a distinct
+    // message that `net_op.recv` carries and replies with one that `net_op.send` carries; no reply is
+    // a message another step receives; every message `net_op.send` carries is a reply; and the events
+    // of the steps are those `event` stands for, or all stuttering.
+    pub open spec fn runs_on(steps: Seq<(nat, NetworkOperation, Event)>, net_op: NetworkOperation, event: Event) -> bool {
+        &&& forall |j: int| #![trigger steps[j]]
+                0 <= j < steps.len() ==>
+                is_carried_by(steps[j].1.recv, net_op.recv) &&
+                is_carried_by(steps[j].1.send, net_op.send)
+        &&& forall |i: int, j: int| #![trigger steps[i], steps[j]]
+                0 <= i < steps.len() &&
+                0 <= j < steps.len() ==>
+                (i != j && steps[i].1.recv.is_some() ==> steps[i].1.recv != steps[j].1.recv) &&
+                (steps[i].1.send.is_some() ==> steps[i].1.send != steps[j].1.recv)
+        &&& forall |m: Message| #![trigger unbatch(net_op.send.unwrap()).contains(m)]
+                net_op.send.is_some() && unbatch(net_op.send.unwrap()).contains(m) ==>
+                exists |j: int| 0 <= j < steps.len() && #[trigger] steps[j].1.send == Some(m)
+        &&& if event.is_batch() {
+                events_of(steps) == event.unbatch()
+            } else {
+                forall |j: int| #![trigger steps[j]] 0 <= j < steps.len() ==> steps[j].2 is NoOp
+            }
+    }
+
+    pub open spec fn events_of(steps: Seq<(nat, NetworkOperation, Event)>) -> Seq<Event> {
+        steps.map_values(|step: (nat, NetworkOperation, Event)| step.2)
+    }
+
+    pub open spec fn accept_update(ballot: Ballot, entries: Seq<(nat, Value)>) -> spec_fn(Instance, int) -> Instance {
+        |instance: Instance, j: int| Instance {
+            current_ballot: ballot,
+            max_seen_ballot: host::max_ballot(instance.max_seen_ballot, ballot),
+            accept_ballot: Some(ballot),
+            accept_value: Some(entries[j].1),
+            decide_value: instance.decide_value,
+            configuration: instance.configuration,
+            volatile: instance.volatile,
+        }
+    }
+
+    pub open spec fn accepted_update(sender: nat, ballot: Ballot) -> spec_fn(Instance, int) -> Instance {
+        |instance: Instance, j: int| Instance {
+            current_ballot: instance.current_ballot,
+            max_seen_ballot: instance.max_seen_ballot,
+            accept_ballot: instance.accept_ballot,
+            accept_value: instance.accept_value,
+            decide_value: instance.decide_value,
+            configuration: instance.configuration,
+            volatile: Volatile {
+                promised: instance.volatile.promised,
+                proposed_value: instance.volatile.proposed_value,
+                accepted: instance.volatile.accepted.insert(ballot, instance.volatile.accepted[ballot].insert(sender)),
+                learned: instance.volatile.learned,
+            },
+        }
+    }
+
+    pub open spec fn decide_update(ballot: Ballot, entries: Seq<(nat, Value)>) -> spec_fn(Instance, int) -> Instance {
+        |instance: Instance, j: int| Instance {
+            current_ballot: ballot,
+            max_seen_ballot: host::max_ballot(instance.max_seen_ballot, ballot),
+            accept_ballot: instance.accept_ballot,
+            accept_value: instance.accept_value,
+            decide_value: Some(entries[j].1),
+            configuration: instance.configuration,
+            volatile: instance.volatile,
+        }
+    }
+
+    // What `send_accept` needs to propose in instance `key` of `s`.
+    pub open spec fn can_propose(c: &Constants, s: Variables, key: nat) -> bool {
+        &&& s.instances.contains_key(key)
+        &&& s.instances[key].volatile.promised.contains_key(s.instances[key].current_ballot)
+        &&& s.instances[key].configuration.classic.is_phase1_quorum(s.instances[key].volatile.promised[s.instances[key].current_ballot].dom())
+        &&& !s.instances[key].volatile.proposed_value.contains_key(s.instances[key].current_ballot)
+    }
+
+    // Whether `send_accept` in instance `key` of `s` takes the oldest pending request.
+    pub open spec fn takes_request(c: &Constants, s: Variables, key: nat) -> bool {
+        &&& select_value(s.instances[key].configuration, c.num_hosts, s.instances[key].volatile.promised[s.instances[key].current_ballot]).is_none()
+        &&& s.pending.len() > 0
+    }
+
+    // The value `send_accept` proposes in instance `key` of `s`.
+    pub open spec fn proposal(c: &Constants, s: Variables, key: nat) -> Value {
+        if let Some((_, value)) = select_value(s.instances[key].configuration, c.num_hosts, s.instances[key].volatile.promised[s.instances[key].current_ballot]) {
+            value
+        } else if (s.pending.len() > 0) {
+            s.pending[0]
+        } else {
+            no_op(key)
+        }
+    }
+
+    // `s` after `send_accept` proposes in instance `key`.
+    pub open spec fn propose_entry(c: &Constants, s: Variables, key: nat) -> Variables {
+        let instance = s.instances[key];
+
+        Variables {
+            instances: s.instances.insert(key, Instance {
+                current_ballot: instance.current_ballot,
+                max_seen_ballot: instance.max_seen_ballot,
+                accept_ballot: instance.accept_ballot,
+                accept_value: instance.accept_value,
+                decide_value: instance.decide_value,
+                configuration: instance.configuration,
+                volatile: Volatile {
+                    promised: instance.volatile.promised,
+                    proposed_value: instance.volatile.proposed_value.insert(instance.current_ballot, proposal(c, s, key)),
+                    accepted: instance.volatile.accepted,
+                    learned: instance.volatile.learned,
+                },
+            }),
+            pending: if takes_request(c, s, key) { s.pending.drop_first() } else { s.pending },
+            assigned: if takes_request(c, s, key) { s.assigned.insert(s.pending[0].request()) } else { s.assigned },
+            up: s.up,
+            multi: s.multi,
+        }
+    }
+
+    // The leader after proposing in the instances of the first `n` entries, one after the other. Each
+    // proposal may take a pending request, so it depends on the ones before it.
+    pub open spec fn propose_entries(c: &Constants, u: Variables, entries: Seq<(nat, Value)>, n: nat) -> Variables
+    decreases
+        n
+    {
+        if (n == 0) {
+            u
+        } else {
+            propose_entry(c, propose_entries(c, u, entries, (n - 1) as nat), entries[n - 1].0)
+        }
+    }
+
+    // A leader proposes in every instance of the batch at `ballot` and sends their Accepts as one
+    // AcceptBatch, instead of one Accept each.
+    pub open spec fn propose_batch(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::AcceptBatch { ballot, entries }) = net_op.send {
+            &&& net_op.recv.is_none()
+            &&& entries.len() > 0
+            &&& has_distinct_keys(entries)
+            &&& forall |j: int| #![trigger entries[j]]
+                    0 <= j < entries.len() ==> {
+                        let s = propose_entries(c, *u, entries, j as nat);
+                        &&& can_propose(c, s, entries[j].0)
+                        &&& s.instances[entries[j].0].current_ballot == ballot
+                        &&& entries[j].1 == proposal(c, s, entries[j].0)
+                    }
+            &&& *v == propose_entries(c, *u, entries, entries.len())
+        } else {
+            &&& false
+        }
+    }
+
+    // An acceptor takes a batch only if it would take every Accept in it, and answers with one AcceptedBatch.
+    pub open spec fn accept_batch(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::AcceptBatch { ballot, entries }) = net_op.recv {
+            &&& entries.len() > 0
+            &&& has_distinct_keys(entries)
+            &&& forall |j: int| #![trigger entries[j]]
+                    0 <= j < entries.len() ==>
+                    c.is_acceptor() &&
+                    u.instances.contains_key(entries[j].0) &&
+                    u.instances[entries[j].0].current_ballot.cmp(&host::floor_for(u, entries[j].0)) >= 0 &&
+                    ballot.cmp(&u.instances[entries[j].0].current_ballot) >= 0
+            &&& *v == apply_entries(*u, entries, accept_update(ballot, entries), entries.len())
+            &&& net_op.send == Some(Message::AcceptedBatch { sender: c.id, ballot, entries })
+        } else {
+            &&& false
+        }
+    }
+
+    pub open spec fn accepted_batch(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::AcceptedBatch { sender, ballot, entries }) = net_op.recv {
+            &&& entries.len() > 0
+            &&& has_distinct_keys(entries)
+            &&& forall |j: int| #![trigger entries[j]]
+                    0 <= j < entries.len() ==>
+                    u.instances.contains_key(entries[j].0) &&
+                    u.instances[entries[j].0].volatile.accepted.contains_key(ballot)
+            &&& *v == apply_entries(*u, entries, accepted_update(sender, ballot), entries.len())
+            &&& net_op.send.is_none()
+        } else {
+            &&& false
+        }
+    }
+
+    // A leader announces every decision `send_decide` would announce at `ballot`, as one batch.
+    pub open spec fn send_decide_batch(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::DecideBatch { ballot, entries }) = net_op.send {
+            &&& net_op.recv.is_none()
+            &&& entries.len() > 0
+            &&& has_distinct_keys(entries)
+            &&& forall |j: int| #![trigger entries[j]]
+                    0 <= j < entries.len() ==>
+                    u.instances.contains_key(entries[j].0) &&
+                    u.instances[entries[j].0].current_ballot == ballot &&
+                    host::send_decide(c, u, u, entries[j].0, NetworkOperation {
+                        recv: None,
+                        send: Some(Message::Decide { key: entries[j].0, ballot, value: entries[j].1 }),
+                    })
+            &&& *v == *u
+        } else {
+            &&& false
+        }
+    }
+
+    // A host takes every decision of a DecideBatch at once, checking each as it would a Decide.
+    pub open spec fn decide_batch(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::DecideBatch { ballot, entries }) = net_op.recv {
+            &&& entries.len() > 0
+            &&& has_distinct_keys(entries)
+            &&& forall |j: int| #![trigger entries[j]]
+                    0 <= j < entries.len() ==>
+                    u.instances.contains_key(entries[j].0) &&
+                    ballot.cmp(&u.instances[entries[j].0].current_ballot) >= 0
+            &&& *v == apply_entries(*u, entries, decide_update(ballot, entries), entries.len())
+            &&& net_op.send.is_none()
+        } else {
+            &&& false
+        }
+    }
+
+    pub open spec fn step(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation, event: Event) -> bool {
+        &&& u.well_formed(c)
+        &&& v.well_formed(c)
+        &&& u.up && v.up
+        &&& match event {
+                Event::ProposeBatch { entries } => {
+                    &&& net_op.send matches Some(Message::AcceptBatch { .. })
+                    &&& entries == net_op.send.unwrap()->AcceptBatch_entries
+                    &&& propose_batch(c, u, v, net_op)
+                },
+                Event::DecideBatch { entries } => {
+                    &&& net_op.recv matches Some(Message::DecideBatch { .. })
+                    &&& entries == net_op.recv.unwrap()->DecideBatch_entries
+                    &&& decide_batch(c, u, v, net_op)
+                },
+                Event::NoOp => {
+                    ||| accept_batch(c, u, v, net_op)
+                    ||| accepted_batch(c, u, v, net_op)
+                    ||| send_decide_batch(c, u, v, net_op)
+                },
+                _ => false,
+            }
+    }
+
+    // The first `n` steps of a batch leave instances of later entries, and everything but
+    // `instances`, as they were.
+    pub proof fn apply_entries_preserves_untouched_instances(u: Variables, entries: Seq<(nat, Value)>, update: spec_fn(Instance, int) -> Instance, n: nat, key: nat)
+    requires
+        n <= entries.len(),
+        forall |i: int| #![trigger entries[i]] 0 <= i < n ==> entries[i].0 != key,
+    ensures
+        apply_entries(u, entries, update, n).instances.contains_key(key) == u.instances.contains_key(key),
+        apply_entries(u, entries, update, n).instances[key] == u.instances[key],
+        apply_entries(u, entries, update, n).pending == u.pending,
+        apply_entries(u, entries, update, n).up == u.up,
+        apply_entries(u, entries, update, n).multi == u.multi,
+    decreases
+        n
+    {
+        if (n > 0) {
+            apply_entries_preserves_untouched_instances(u, entries, update, (n - 1) as nat, key);
+            assert(entries[n - 1].0 != key);
+        }
+    }
+
+    // The states a batch goes through, with step `j` acting on an instance that is still as it was in `u`.
+    pub proof fn apply_entries_steps(u: Variables, entries: Seq<(nat, Value)>, update: spec_fn(Instance, int) -> Instance) -> (states: Seq<Variables>)
+    requires
+        has_distinct_keys(entries),
+    ensures
+        states.len() == entries.len() + 1,
+        states[0] == u,
+        states[entries.len() as int] == apply_entries(u, entries, update, entries.len()),
+        forall |j: int| #![trigger states[j]]
+            0 <= j < entries.len() ==>
+            states[j + 1] == apply_entry(states[j], entries, update, j) &&
+            states[j].instances.contains_key(entries[j].0) == u.instances.contains_key(entries[j].0) &&
+            states[j].instances[entries[j].0] == u.instances[entries[j].0] &&
+            states[j].pending == u.pending &&
+            states[j].up == u.up &&
+            states[j].multi == u.multi,
+    {
+        let states = Seq::new((entries.len() + 1) as nat, |j: int| apply_entries(u, entries, update, j as nat));
+
+        assert forall |j: int| #![trigger states[j]]
+                0 <= j < entries.len() implies
+                states[j + 1] == apply_entry(states[j], entries, update, j) &&
+                states[j].instances.contains_key(entries[j].0) == u.instances.contains_key(entries[j].0) &&
+                states[j].instances[entries[j].0] == u.instances[entries[j].0] &&
+                states[j].pending == u.pending &&
+                states[j].up == u.up &&
+                    states[j].multi == u.multi
+        by {
+            assert forall |i: int| #![trigger entries[i]] 0 <= i < j implies entries[i].0 != entries[j].0 by {
+                assert(entries[i].0 != entries[j].0);
+            };
+            apply_entries_preserves_untouched_instances(u, entries, update, j as nat, entries[j].0);
+            assert(apply_entries(u, entries, update, (j + 1) as nat) == apply_entry(apply_entries(u, entries, update, j as nat), entries, update, j));
+        };
+
+        states
+    }
+
+    // The single-key steps of a proposed batch: the `send_accept` of each entry.
+    pub open spec fn propose_steps(ballot: Ballot, entries: Seq<(nat, Value)>) -> Seq<(nat, NetworkOperation, Event)> {
+        entries.map_values(|entry: (nat, Value)| (entry.0, NetworkOperation {
+            recv: None,
+            send: Some(Message::Accept { key: entry.0, ballot, value: entry.1 }),
+        }, Event::Propose { key: entry.0, value: entry.1 }))
+    }
+
+    // The single-key steps of an AcceptBatch: the Accept of each entry, answered by its Accepted.
+    pub open spec fn accept_steps(sender: nat, ballot: Ballot, entries: Seq<(nat, Value)>) -> Seq<(nat, NetworkOperation, Event)> {
+        entries.map_values(|entry: (nat, Value)| (entry.0, NetworkOperation {
+            recv: Some(Message::Accept { key: entry.0, ballot, value: entry.1 }),
+            send: Some(Message::Accepted { key: entry.0, sender, ballot, value: entry.1 }),
+        }, Event::NoOp))
+    }
+
+    pub open spec fn accepted_steps(sender: nat, ballot: Ballot, entries: Seq<(nat, Value)>) -> Seq<(nat, NetworkOperation, Event)> {
+        entries.map_values(|entry: (nat, Value)| (entry.0, NetworkOperation {
+            recv: Some(Message::Accepted { key: entry.0, sender, ballot, value: entry.1 }),
+            send: None,
+        }, Event::NoOp))
+    }
+
+    pub open spec fn send_decide_steps(ballot: Ballot, entries: Seq<(nat, Value)>) -> Seq<(nat, NetworkOperation, Event)> {
+        entries.map_values(|entry: (nat, Value)| (entry.0, NetworkOperation {
+            recv: None,
+            send: Some(Message::Decide { key: entry.0, ballot, value: entry.1 }),
+        }, Event::NoOp))
+    }
+
+    pub open spec fn decide_steps(ballot: Ballot, entries: Seq<(nat, Value)>) -> Seq<(nat, NetworkOperation, Event)> {
+        entries.map_values(|entry: (nat, Value)| (entry.0, NetworkOperation {
+            recv: Some(Message::Decide { key: entry.0, ballot, value: entry.1 }),
+            send: None,
+        }, Event::Decide { key: entry.0, value: entry.1 }))
+    }
+
+    pub proof fn propose_entries_keeps_up(c: &Constants, u: Variables, entries: Seq<(nat, Value)>, n: nat)
+    ensures
+        propose_entries(c, u, entries, n).up == u.up,
+    decreases
+        n
+    {
+        if (n > 0) {
+            propose_entries_keeps_up(c, u, entries, (n - 1) as nat);
+        }
+    }
+
+    // `send_accept` is deterministic: it proposes `proposal` and leaves the host as `propose_entry` says.
+    pub proof fn send_accept_is_propose_entry(c: &Constants, s: Variables, t: Variables, key: nat, net_op: NetworkOperation)
+    requires
+        host::send_accept(c, &s, &t, key, net_op),
+        t.up == s.up,
+    ensures
+        can_propose(c, s, key),
+        t == propose_entry(c, s, key),
+        net_op.send == Some(Message::Accept { key, ballot: s.instances[key].current_ballot, value: proposal(c, s, key) }),
+    {
+        assert(t.instances == propose_entry(c, s, key).instances);
+    }
+
+    // The proposals of the first `n` entries only depend on those entries.
+    pub proof fn propose_entries_depends_on_prefix(c: &Constants, u: Variables, a: Seq<(nat, Value)>, b: Seq<(nat, Value)>, n: nat)
+    requires
+        n <= a.len(),
+        n <= b.len(),
+        a.subrange(0, n as int) == b.subrange(0, n as int),
+    ensures
+        propose_entries(c, u, a, n) == propose_entries(c, u, b, n),
+    decreases
+        n
+    {
+        if (n > 0) {
+            assert(a.subrange(0, n - 1) =~= a.subrange(0, n as int).subrange(0, n - 1));
+            assert(b.subrange(0, n - 1) =~= b.subrange(0, n as int).subrange(0, n - 1));
+            propose_entries_depends_on_prefix(c, u, a, b, (n - 1) as nat);
+            assert(a[n - 1] == a.subrange(0, n as int)[n - 1]);
+            assert(b[n - 1] == b.subrange(0, n as int)[n - 1]);
+        }
+    }
+
+    pub proof fn propose_batch_is_sequence_of_send_accepts(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> (states: Seq<Variables>)
+    requires
+        u.well_formed(c),
+        u.up,
+        propose_batch(c, u, v, net_op),
+    ensures
+        states.len() > 0,
+        states[0] == *u,
+        states.last() == *v,
+        is_run(c, states, propose_steps(net_op.send.unwrap()->AcceptBatch_ballot, net_op.send.unwrap()->AcceptBatch_entries)),
+        forall |j: int| #![trigger states[j]]
+            0 <= j < net_op.send.unwrap()->AcceptBatch_entries.len() ==>
+            states[j + 1] == propose_entry(c, states[j], net_op.send.unwrap()->AcceptBatch_entries[j].0) &&
+            can_propose(c, states[j], net_op.send.unwrap()->AcceptBatch_entries[j].0),
+    {
+        let (ballot, entries) = (net_op.send.unwrap()->AcceptBatch_ballot, net_op.send.unwrap()->AcceptBatch_entries);
+        let states = Seq::new((entries.len() + 1) as nat, |j: int| propose_entries(c, *u, entries, j as nat));
+        let steps = propose_steps(ballot, entries);
+
+        assert forall |j: int| #![trigger states[j]]
+                0 <= j < steps.len() implies
+                host::step(c, &states[j], &states[j + 1], steps[j].0, steps[j].1, steps[j].2)
+        by {
+            let (key, value) = entries[j];
+            let (s, t) = (states[j], states[j + 1]);
+            assert(t == propose_entry(c, s, key));
+            assert(can_propose(c, s, key) && s.instances[key].current_ballot == ballot && value == proposal(c, s, key));
+            propose_entries_keeps_up(c, *u, entries, j as nat);
+            propose_entries_keeps_up(c, *u, entries, (j + 1) as nat);
+            assert(steps[j].1 == NetworkOperation { recv: None, send: Some(Message::Accept { key, ballot, value }) });
+            assert(t.instances[key].current_ballot == ballot);
+            assert(t.instances[key].volatile.proposed_value[ballot] == value);
+            assert(host::send_accept(c, &s, &t, key, steps[j].1));
+        };
+
+        assert(states.last() == propose_entries(c, *u, entries, entries.len()));
+        assert(is_run(c, states, steps));
+        states
+    }
+
+    pub proof fn decide_batch_is_sequence_of_decides(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> (states: Seq<Variables>)
+    requires
+        u.well_formed(c),
+        u.up,
+        decide_batch(c, u, v, net_op),
+    ensures
+        states.len() > 0,
+        states[0] == *u,
+        states.last() == *v,
+        is_run(c, states, decide_steps(net_op.recv.unwrap()->DecideBatch_ballot, net_op.recv.unwrap()->DecideBatch_entries)),
+        forall |j: int| #![trigger states[j]]
+            0 <= j < net_op.recv.unwrap()->DecideBatch_entries.len() ==>
+            states[j + 1] == apply_entry(states[j], net_op.recv.unwrap()->DecideBatch_entries, decide_update(net_op.recv.unwrap()->DecideBatch_ballot, net_op.recv.unwrap()->DecideBatch_entries), j) &&
+            states[j].instances.contains_key(net_op.recv.unwrap()->DecideBatch_entries[j].0),
+    {
+        let (ballot, entries) = (net_op.recv.unwrap()->DecideBatch_ballot, net_op.recv.unwrap()->DecideBatch_entries);
+        let update = decide_update(ballot, entries);
+        let states = apply_entries_steps(*u, entries, update);
+        let steps = decide_steps(ballot, entries);
+
+        assert forall |j: int| #![trigger states[j]]
+                0 <= j < steps.len() implies
+                host::step(c, &states[j], &states[j + 1], steps[j].0, steps[j].1, steps[j].2)
+        by {
+            let (key, value) = entries[j];
+            assert(steps[j].1 == NetworkOperation { recv: Some(Message::Decide { key, ballot, value }), send: None });
+            assert(states[j + 1].instances == states[j].instances.insert(key, update(states[j].instances[key], j)));
+            assert(host::decide(c, &states[j], &states[j + 1], key, steps[j].1, value));
+        };
+
+        assert(is_run(c, states, steps));
+        states
+    }
+
+    pub proof fn accept_batch_is_sequence_of_accepts(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> (states: Seq<Variables>)
+    requires
+        u.well_formed(c),
+        u.up,
+        accept_batch(c, u, v, net_op),
+    ensures
+        states.len() > 0,
+        states[0] == *u,
+        states.last() == *v,
+        is_run(c, states, accept_steps(c.id, net_op.recv.unwrap()->AcceptBatch_ballot, net_op.recv.unwrap()->AcceptBatch_entries)),
+    {
+        let (ballot, entries) = (net_op.recv.unwrap()->AcceptBatch_ballot, net_op.recv.unwrap()->AcceptBatch_entries);
+        let update = accept_update(ballot, entries);
+        let states = apply_entries_steps(*u, entries, update);
+        let steps = accept_steps(c.id, ballot, entries);
+
+        assert forall |j: int| #![trigger states[j]]
+                0 <= j < steps.len() implies
+                host::step(c, &states[j], &states[j + 1], steps[j].0, steps[j].1, steps[j].2)
+        by {
+            let key = entries[j].0;
+            assert(steps[j].0 == key);
+            assert(steps[j].1.recv == Some(Message::Accept { key, ballot, value: entries[j].1 }));
+            assert(steps[j].1.send == Some(Message::Accepted { key, sender: c.id, ballot, value: entries[j].1 }));
+            assert(states[j + 1].instances == states[j].instances.insert(key, update(states[j].instances[key], j)));
+            assert(host::floor_for(&states[j], key) == host::floor_for(u, key));
+            assert(host::accept(c, &states[j], &states[j + 1], key, steps[j].1));
+        };
+
+        assert(is_run(c, states, steps));
+        states
+    }
+
+    pub proof fn accepted_batch_is_sequence_of_accepteds(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> (states: Seq<Variables>)
+    requires
+        u.well_formed(c),
+        u.up,
+        accepted_batch(c, u, v, net_op),
+    ensures
+        states.len() > 0,
+        states[0] == *u,
+        states.last() == *v,
+        is_run(c, states, accepted_steps(net_op.recv.unwrap()->AcceptedBatch_sender, net_op.recv.unwrap()->AcceptedBatch_ballot, net_op.recv.unwrap()->AcceptedBatch_entries)),
+    {
+        let (sender, ballot, entries) = (net_op.recv.unwrap()->AcceptedBatch_sender, net_op.recv.unwrap()->AcceptedBatch_ballot, net_op.recv.unwrap()->AcceptedBatch_entries);
+        let update = accepted_update(sender, ballot);
+        let states = apply_entries_steps(*u, entries, update);
+        let steps = accepted_steps(sender, ballot, entries);
+
+        assert forall |j: int| #![trigger states[j]]
+                0 <= j < steps.len() implies
+                host::step(c, &states[j], &states[j + 1], steps[j].0, steps[j].1, steps[j].2)
+        by {
+            let key = entries[j].0;
+            assert(steps[j].1.recv == Some(Message::Accepted { key, sender, ballot, value: entries[j].1 }));
+            assert(states[j + 1].instances == states[j].instances.insert(key, update(states[j].instances[key], j)));
+            assert(host::accepted(c, &states[j], &states[j + 1], key, steps[j].1));
+        };
+
+        assert(is_run(c, states, steps));
+        states
+    }
+
+    // `send_decide` leaves the host as it is, so every state of the run is `u`.
+    pub proof fn send_decide_batch_is_sequence_of_send_decides(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> (states: Seq<Variables>)
+    requires
+        u.well_formed(c),
+        u.up,
+        send_decide_batch(c, u, v, net_op),
+    ensures
+        states.len() > 0,
+        states[0] == *u,
+        states.last() == *v,
+        is_run(c, states, send_decide_steps(net_op.send.unwrap()->DecideBatch_ballot, net_op.send.unwrap()->DecideBatch_entries)),
+    {
+        let (ballot, entries) = (net_op.send.unwrap()->DecideBatch_ballot, net_op.send.unwrap()->DecideBatch_entries);
+        let steps = send_decide_steps(ballot, entries);
+        let states = Seq::new((entries.len() + 1) as nat, |j: int| *u);
+
+        assert forall |j: int| #![trigger states[j]]
+                0 <= j < steps.len() implies
+                host::step(c, &states[j], &states[j + 1], steps[j].0, steps[j].1, steps[j].2)
+        by {
+            assert(steps[j].1 == NetworkOperation { recv: None, send: Some(Message::Decide { key: entries[j].0, ballot, value: entries[j].1 }) });
+            assert(host::send_decide(c, &states[j], &states[j + 1], steps[j].0, steps[j].1));
+        };
+
+        assert(is_run(c, states, steps));
+        states
+    }
+
+    // Every batch step is a run of single-key host steps that `runs_on` its network operation.
+    pub proof fn step_is_run(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation, event: Event) -> (run: (Seq<Variables>, Seq<(nat, NetworkOperation, Event)>))
+    requires
+        step(c, u, v, net_op, event),
+    ensures
+        run.0.len() > 0,
+        run.0[0] == *u,
+        run.0.last() == *v,
+        is_run(c, run.0, run.1),
+        runs_on(run.1, net_op, event),
+        forall |j: int| #![trigger run.0[j]] 0 <= j < run.1.len() ==> keeps_memory(run.0[j], run.0[j + 1]),
+    {
+        if let Event::ProposeBatch { .. } = event {
+            let (ballot, entries) = (net_op.send.unwrap()->AcceptBatch_ballot, net_op.send.unwrap()->AcceptBatch_entries);
+            let states = propose_batch_is_sequence_of_send_accepts(c, u, v, net_op);
+            let steps = propose_steps(ballot, entries);
+
+            // Each proposal records a value under a ballot the instance had no proposal for, and
+            // takes nothing away.
+            assert forall |j: int| #![trigger states[j]] 0 <= j < steps.len() implies keeps_memory(states[j], states[j + 1]) by {
+                let key = entries[j].0;
+                assert(states[j + 1].instances.dom() =~= states[j].instances.dom());
+                assert(states[j + 1].instances[key].volatile.proposed_value.dom() =~= states[j].instances[key].volatile.proposed_value.dom().insert(states[j].instances[key].current_ballot));
+            };
+
+            assert forall |j: int| #![trigger steps[j]] 0 <= j < steps.len() implies
+                is_carried_by(steps[j].1.recv, net_op.recv) && is_carried_by(steps[j].1.send, net_op.send)
+            by {
+                assert(unbatch(net_op.send.unwrap())[j] == steps[j].1.send.unwrap());
+            };
+            assert forall |m: Message| #![trigger unbatch(net_op.send.unwrap()).contains(m)]
+                net_op.send.is_some() && unbatch(net_op.send.unwrap()).contains(m) implies
+                exists |j: int| 0 <= j < steps.len() && #[trigger] steps[j].1.send == Some(m)
+            by {
+                let j = choose |j: int| 0 <= j < entries.len() && unbatch(net_op.send.unwrap())[j] == m;
+                assert(steps[j].1.send == Some(m));
+            };
+            assert(events_of(steps) =~= event.unbatch());
+            assert(runs_on(steps, net_op, event));
+            (states, steps)
+        } else if let Event::DecideBatch { .. } = event {
+            let (ballot, entries) = (net_op.recv.unwrap()->DecideBatch_ballot, net_op.recv.unwrap()->DecideBatch_entries);
+            let states = decide_batch_is_sequence_of_decides(c, u, v, net_op);
+            let steps = decide_steps(ballot, entries);
+
+            // Each decision raises the ballot and records the value of an instance the host has, and
+            // leaves its memory alone.
+            assert forall |j: int| #![trigger states[j]] 0 <= j < steps.len() implies keeps_memory(states[j], states[j + 1]) by {
+                assert(states[j + 1].instances.dom() =~= states[j].instances.dom());
+            };
+
+            assert forall |j: int| #![trigger steps[j]] 0 <= j < steps.len() implies
+                is_carried_by(steps[j].1.recv, net_op.recv) && is_carried_by(steps[j].1.send, net_op.send)
+            by {
+                assert(unbatch(net_op.recv.unwrap())[j] == steps[j].1.recv.unwrap());
+            };
+            // Distinct keys make the carried Decides, and so the receives, distinct.
+            assert forall |i: int, j: int| #![trigger steps[i], steps[j]]
+                0 <= i < steps.len() && 0 <= j < steps.len() && i != j implies
+                steps[i].1.recv != steps[j].1.recv
+            by {
+                assert(entries[i].0 != entries[j].0);
+            };
+            assert(events_of(steps) =~= event.unbatch());
+            assert(runs_on(steps, net_op, event));
+            (states, steps)
+        } else if (accept_batch(c, u, v, net_op)) {
+            let (ballot, entries) = (net_op.recv.unwrap()->AcceptBatch_ballot, net_op.recv.unwrap()->AcceptBatch_entries);
+            let states = accept_batch_is_sequence_of_accepts(c, u, v, net_op);
+            let steps = accept_steps(c.id, ballot, entries);
+
+            // Each Accept overwrites the acceptor's vote of an instance it already has and leaves its
+            // memory alone.
+            assert forall |j: int| #![trigger states[j]] 0 <= j < steps.len() implies keeps_memory(states[j], states[j + 1]) by {
+                assert(states[j + 1].instances.dom() =~= states[j].instances.dom());
+            };
+
+            assert forall |j: int| #![trigger steps[j]] 0 <= j < steps.len() implies
+                is_carried_by(steps[j].1.recv, net_op.recv) && is_carried_by(steps[j].1.send, net_op.send)
+            by {
+                assert(unbatch(net_op.recv.unwrap())[j] == steps[j].1.recv.unwrap());
+                assert(unbatch(net_op.send.unwrap())[j] == steps[j].1.send.unwrap());
+            };
+            assert forall |i: int, j: int| #![trigger steps[i], steps[j]]
+                0 <= i < steps.len() && 0 <= j < steps.len() && i != j implies
+                steps[i].1.recv != steps[j].1.recv
+            by {
+                assert(entries[i].0 != entries[j].0);
+            };
+            assert forall |m: Message| #![trigger unbatch(net_op.send.unwrap()).contains(m)]
+                net_op.send.is_some() && unbatch(net_op.send.unwrap()).contains(m) implies
+                exists |j: int| 0 <= j < steps.len() && #[trigger] steps[j].1.send == Some(m)
+            by {
+                let j = choose |j: int| 0 <= j < entries.len() && unbatch(net_op.send.unwrap())[j] == m;
+                assert(steps[j].1.send == Some(m));
+            };
+            assert(runs_on(steps, net_op, event));
+            (states, steps)
+        } else if (accepted_batch(c, u, v, net_op)) {
+            let (sender, ballot, entries) = (net_op.recv.unwrap()->AcceptedBatch_sender, net_op.recv.unwrap()->AcceptedBatch_ballot, net_op.recv.unwrap()->AcceptedBatch_entries);
+            let states = accepted_batch_is_sequence_of_accepteds(c, u, v, net_op);
+            let steps = accepted_steps(sender, ballot, entries);
+
+            // Each Accepted adds a sender under a ballot the leader already counts.
+            assert forall |j: int| #![trigger states[j]] 0 <= j < steps.len() implies keeps_memory(states[j], states[j + 1]) by {
+                assert(states[j + 1].instances.dom() =~= states[j].instances.dom());
+                assert(states[j + 1].instances[entries[j].0].volatile.accepted.dom() =~= states[j].instances[entries[j].0].volatile.accepted.dom());
+            };
+
+            assert forall |j: int| #![trigger steps[j]] 0 <= j < steps.len() implies
+                is_carried_by(steps[j].1.recv, net_op.recv) && is_carried_by(steps[j].1.send, net_op.send)
+            by {
+                assert(unbatch(net_op.recv.unwrap())[j] == steps[j].1.recv.unwrap());
+            };
+            assert forall |i: int, j: int| #![trigger steps[i], steps[j]]
+                0 <= i < steps.len() && 0 <= j < steps.len() && i != j implies
+                steps[i].1.recv != steps[j].1.recv
+            by {
+                assert(entries[i].0 != entries[j].0);
+            };
+            assert(runs_on(steps, net_op, event));
+            (states, steps)
+        } else {
+            let (ballot, entries) = (net_op.send.unwrap()->DecideBatch_ballot, net_op.send.unwrap()->DecideBatch_entries);
+            let states = send_decide_batch_is_sequence_of_send_decides(c, u, v, net_op);
+            let steps = send_decide_steps(ballot, entries);
+            assert forall |j: int| #![trigger states[j]] 0 <= j < steps.len() implies keeps_memory(states[j], states[j + 1]) by {
+                assert(states[j] == *u && states[j + 1] == *u);
+            };
+
+            assert forall |j: int| #![trigger steps[j]] 0 <= j < steps.len() implies
+                is_carried_by(steps[j].1.recv, net_op.recv) && is_carried_by(steps[j].1.send, net_op.send)
+            by {
+                assert(unbatch(net_op.send.unwrap())[j] == steps[j].1.send.unwrap());
+            };
+            assert forall |m: Message| #![trigger unbatch(net_op.send.unwrap()).contains(m)]
+                net_op.send.is_some() && unbatch(net_op.send.unwrap()).contains(m) implies
+                exists |j: int| 0 <= j < steps.len() && #[trigger] steps[j].1.send == Some(m)
+            by {
+                let j = choose |j: int| 0 <= j < entries.len() && unbatch(net_op.send.unwrap())[j] == m;
+                assert(steps[j].1.send == Some(m));
+            };
+            assert(runs_on(steps, net_op, event));
+            (states, steps)
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use super::{
+    continuous_set_size_bounds, full_set_size,
+    host::{
+        get_max_accepted_value, get_max_accepted_value_is_some_implies_accepted_map_has_corresponding_sender,
+        map_has_key_with_some_value_same_as_get_max_accepted_value, Ballot,
+    },
+    overlapping_sets_have_common_element,
+    quorum::{all_hosts, are_valid_hosts, quorums_by_size, quorums_by_size_are_well_formed, QuorumSystem},
+};
+use crate::distributed_system::Value;
+use vstd::{arithmetic::div_mod::lemma_fundamental_div_mod, prelude::*, set_lib::*};
+
+verus! {
+    // Fast Paxos. In a fast ballot acceptors take a value straight from a client, so different
+    // acceptors can accept different values at the same ballot. A value is chosen once a fast quorum
+    // accepts it, and a coordinator recovering from a collision must be able to tell from one phase-1
+    // quorum which value, if any, that could have been: every phase-1 quorum has to meet any two
+    // fast quorums in a common host.
+    pub struct FastQuorumSystem {
+        pub classic: QuorumSystem,
+        pub fast: spec_fn(Set<nat>) -> bool,
+        pub is_fast_ballot: spec_fn(Ballot) -> bool,
+    }
+
+    impl FastQuorumSystem {
+        pub open spec fn is_fast_quorum(&self, hosts: Set<nat>) -> bool {
+            (self.fast)(hosts)
+        }
+
+        pub open spec fn fast_quorums_intersect(&self, num_hosts: nat) -> bool {
+            forall |q: Set<nat>, r1: Set<nat>, r2: Set<nat>|
+                #![trigger self.classic.is_phase1_quorum(q), self.is_fast_quorum(r1), self.is_fast_quorum(r2)]
+                are_valid_hosts(q, num_hosts) &&
+                are_valid_hosts(r1, num_hosts) &&
+                are_valid_hosts(r2, num_hosts) &&
+                self.classic.is_phase1_quorum(q) &&
+                self.is_fast_quorum(r1) &&
+                self.is_fast_quorum(r2) ==>
+                exists |h: nat| #![auto] q.contains(h) && r1.contains(h) && r2.contains(h)
+        }
+
+        pub open spec fn well_formed(&self, num_hosts: nat) -> bool {
+            &&& self.classic.well_formed(num_hosts)
+            &&& self.fast_quorums_intersect(num_hosts)
+        }
+    }
+
+    // Every host that is in `fast_quorum` and reported to the coordinator reported `(ballot, value)`,
+    // so the hosts of `fast_quorum` may all have accepted `value` at `ballot`.
+    pub open spec fn fast_quorum_may_have_accepted(promises: Map<nat, Option<(Ballot, Value)>>, fast_quorum: Set<nat>, ballot: Ballot, value: Value) -> bool {
+        forall |sender: nat| #[trigger] fast_quorum.contains(sender) && promises.contains_key(sender) ==> promises[sender] == Some((ballot, value))
+    }
+
+    pub open spec fn value_may_have_been_chosen(fq: FastQuorumSystem, num_hosts: nat, promises: Map<nat, Option<(Ballot, Value)>>, ballot: Ballot, value: Value) -> bool {
+        exists |fast_quorum: Set<nat>|
+            are_valid_hosts(fast_quorum, num_hosts) &&
+            #[trigger] fq.is_fast_quorum(fast_quorum) &&
+            fast_quorum_may_have_accepted(promises, fast_quorum, ballot, value)
+    }
+
+    pub open spec fn reports_ballot(promises: Map<nat, Option<(Ballot, Value)>>, sender: nat, ballot: Ballot) -> bool {
+        &&& promises.contains_key(sender)
+        &&& promises[sender].is_some()
+        &&& promises[sender].unwrap().0 == ballot
+    }
+
+    pub open spec fn is_lowest_reporting_sender(promises: Map<nat, Option<(Ballot, Value)>>, sender: nat, ballot: Ballot) -> bool {
+        &&& reports_ballot(promises, sender, ballot)
+        &&& forall |s: nat| s < sender ==> !#[trigger] reports_ballot(promises, s, ballot)
+    }
+
+    // The coordinator's value selection, generalising `get_max_accepted_value`: after a collision at
+    // the highest reported ballot, a value some fast quorum may have accepted takes precedence. If
+    // none can have been, any reported value is safe, and the coordinator takes the one the
+    // lowest-numbered host reported so that an implementation can compute the same choice.
+    pub open spec fn select_value(fq: FastQuorumSystem, num_hosts: nat, promises: Map<nat, Option<(Ballot, Value)>>) -> Option<(Ballot, Value)> {
+        let max_accepted_value = get_max_accepted_value(promises);
+
+        if (max_accepted_value.is_none()) {
+            None
+        } else {
+            let ballot = max_accepted_value.unwrap().0;
+
+            if (!(fq.is_fast_ballot)(ballot)) {
+                max_accepted_value
+            } else if (exists |value: Value| #[trigger] value_may_have_been_chosen(fq, num_hosts, promises, ballot, value)) {
+                Some((ballot, choose |value: Value| #[trigger] value_may_have_been_chosen(fq, num_hosts, promises, ballot, value)))
+            } else {
+                promises[choose |sender: nat| #[trigger] is_lowest_reporting_sender(promises, sender, ballot)]
+            }
+        }
+    }
+
+    pub proof fn lowest_reporting_sender_exists(promises: Map<nat, Option<(Ballot, Value)>>, sender: nat, ballot: Ballot)
+    requires
+        reports_ballot(promises, sender, ballot),
+    ensures
+        exists |lowest: nat| #[trigger] is_lowest_reporting_sender(promises, lowest, ballot),
+    decreases
+        sender
+    {
+        if (exists |s: nat| s < sender && #[trigger] reports_ballot(promises, s, ballot)) {
+            let s = choose |s: nat| s < sender && #[trigger] reports_ballot(promises, s, ballot);
+            lowest_reporting_sender_exists(promises, s, ballot);
+        } else {
+            assert(is_lowest_reporting_sender(promises, sender, ballot));
+        }
+    }
+
+    pub proof fn at_most_one_value_may_have_been_chosen(fq: FastQuorumSystem, num_hosts: nat, promises: Map<nat, Option<(Ballot, Value)>>, ballot: Ballot, value1: Value, value2: Value)
+    requires
+        fq.well_formed(num_hosts),
+        are_valid_hosts(promises.dom(), num_hosts),
+        fq.classic.is_phase1_quorum(promises.dom()),
+        value_may_have_been_chosen(fq, num_hosts, promises, ballot, value1),
+        value_may_have_been_chosen(fq, num_hosts, promises, ballot, value2),
+    ensures
+        value1 == value2,
+    {
+        let fast_quorum1 = choose |fast_quorum: Set<nat>| are_valid_hosts(fast_quorum, num_hosts) && #[trigger] fq.is_fast_quorum(fast_quorum) && fast_quorum_may_have_accepted(promises, fast_quorum, ballot, value1);
+        let fast_quorum2 = choose |fast_quorum: Set<nat>| are_valid_hosts(fast_quorum, num_hosts) && #[trigger] fq.is_fast_quorum(fast_quorum) && fast_quorum_may_have_accepted(promises, fast_quorum, ballot, value2);
+
+        assert(fq.classic.is_phase1_quorum(promises.dom()) && fq.is_fast_quorum(fast_quorum1) && fq.is_fast_quorum(fast_quorum2));
+        let common_sender = choose |h: nat| #![auto] promises.dom().contains(h) && fast_quorum1.contains(h) && fast_quorum2.contains(h);
+        assert(promises[common_sender] == Some((ballot, value1)));
+        assert(promises[common_sender] == Some((ballot, value2)));
+    }
+
+    // The full acceptor set is a phase-1 quorum, so it meets any two fast quorums.
+    pub proof fn fast_quorums_have_common_host(fq: FastQuorumSystem, num_hosts: nat, num_failures: nat, r1: Set<nat>, r2: Set<nat>)
+    requires
+        fq.well_formed(num_hosts),
+        fq.classic.tolerates_failures(num_failures),
+        are_valid_hosts(r1, num_hosts),
+        are_valid_hosts(r2, num_hosts),
+        fq.is_fast_quorum(r1),
+        fq.is_fast_quorum(r2),
+    ensures
+        exists |h: nat| #![auto] r1.contains(h) && r2.contains(h),
+    {
+        let acceptors = fq.classic.acceptors;
+
+        assert(acceptors.subset_of(acceptors));
+        assert(acceptors.len() >= acceptors.len() - num_failures);
+        assert(fq.classic.is_phase1_quorum(acceptors));
+        let h = choose |h: nat| #![auto] acceptors.contains(h) && r1.contains(h) && r2.contains(h);
+        assert(r1.contains(h) && r2.contains(h));
+    }
+
+    // The value `select_value` picks was reported by some host: the highest one, or one of the fast
+    // quorum the value may have been chosen by, which meets the phase-1 quorum.
+    pub proof fn selected_value_was_reported(fq: FastQuorumSystem, num_hosts: nat, promises: Map<nat, Option<(Ballot, Value)>>)
+    requires
+        fq.well_formed(num_hosts),
+        are_valid_hosts(promises.dom(), num_hosts),
+        fq.classic.is_phase1_quorum(promises.dom()),
+        get_max_accepted_value(promises).is_some(),
+    ensures
+        exists |sender: nat| #[trigger] promises.contains_key(sender) && promises[sender] == select_value(fq, num_hosts, promises),
+    {
+        let max_accepted_value = get_max_accepted_value(promises);
+        let ballot = max_accepted_value.unwrap().0;
+
+        if ((fq.is_fast_ballot)(ballot) && exists |value: Value| #[trigger] value_may_have_been_chosen(fq, num_hosts, promises, ballot, value)) {
+            let value = choose |value: Value| #[trigger] value_may_have_been_chosen(fq, num_hosts, promises, ballot, value);
+            let fast_quorum = choose |fast_quorum: Set<nat>| are_valid_hosts(fast_quorum, num_hosts) && #[trigger] fq.is_fast_quorum(fast_quorum) && fast_quorum_may_have_accepted(promises, fast_quorum, ballot, value);
+
+            assert(fq.classic.is_phase1_quorum(promises.dom()) && fq.is_fast_quorum(fast_quorum));
+            let sender = choose |h: nat| #![auto] promises.dom().contains(h) && fast_quorum.contains(h) && fast_quorum.contains(h);
+            assert(promises.contains_key(sender) && promises[sender] == Some((ballot, value)));
+            assert(select_value(fq, num_hosts, promises) == Some((ballot, value)));
+        } else if ((fq.is_fast_ballot)(ballot)) {
+            get_max_accepted_value_is_some_implies_accepted_map_has_corresponding_sender(promises);
+            let sender = choose |s: nat| #[trigger] map_has_key_with_some_value_same_as_get_max_accepted_value(promises, s);
+            assert(reports_ballot(promises, sender, ballot));
+            lowest_reporting_sender_exists(promises, sender, ballot);
+            let lowest = choose |lowest: nat| #[trigger] is_lowest_reporting_sender(promises, lowest, ballot);
+            assert(promises.contains_key(lowest) && promises[lowest] == select_value(fq, num_hosts, promises));
+        } else {
+            get_max_accepted_value_is_some_implies_accepted_map_has_corresponding_sender(promises);
+            let sender = choose |s: nat| #[trigger] map_has_key_with_some_value_same_as_get_max_accepted_value(promises, s);
+            assert(promises.contains_key(sender) && promises[sender] == select_value(fq, num_hosts, promises));
+        }
+    }
+
+    // After a collision at the highest reported ballot, the value a fast quorum may have accepted is
+    // the one selected.
+    pub proof fn value_that_may_have_been_chosen_is_selected(fq: FastQuorumSystem, num_hosts: nat, promises: Map<nat, Option<(Ballot, Value)>>, value: Value)
+    requires
+        fq.well_formed(num_hosts),
+        are_valid_hosts(promises.dom(), num_hosts),
+        fq.classic.is_phase1_quorum(promises.dom()),
+        get_max_accepted_value(promises).is_some(),
+        (fq.is_fast_ballot)(get_max_accepted_value(promises).unwrap().0),
+        value_may_have_been_chosen(fq, num_hosts, promises, get_max_accepted_value(promises).unwrap().0, value),
+    ensures
+        select_value(fq, num_hosts, promises) == Some((get_max_accepted_value(promises).unwrap().0, value)),
+    {
+        let ballot = get_max_accepted_value(promises).unwrap().0;
+        let chosen = choose |value: Value| #[trigger] value_may_have_been_chosen(fq, num_hosts, promises, ballot, value);
+
+        at_most_one_value_may_have_been_chosen(fq, num_hosts, promises, ballot, value, chosen);
+    }
+
+    // When the highest reported ballot is classic there was no collision to recover from.
+    pub proof fn select_value_at_classic_ballot(fq: FastQuorumSystem, num_hosts: nat, promises: Map<nat, Option<(Ballot, Value)>>)
+    requires
+        get_max_accepted_value(promises).is_some(),
+        !(fq.is_fast_ballot)(get_max_accepted_value(promises).unwrap().0),
+    ensures
+        select_value(fq, num_hosts, promises) == get_max_accepted_value(promises),
+    {
+    }
+
+    pub open spec fn classic_quorum_size(num_hosts: nat) -> nat {
+        (num_hosts / 2) + 1
+    }
+
+    // ⌈3n/4⌉
+    pub open spec fn fast_quorum_size(num_hosts: nat) -> nat {
+        ((3 * num_hosts) + 3) / 4
+    }
+
+    pub open spec fn fast_quorums_by_size(num_hosts: nat, is_fast_ballot: spec_fn(Ballot) -> bool) -> FastQuorumSystem {
+        FastQuorumSystem {
+            classic: quorums_by_size(all_hosts(num_hosts), classic_quorum_size(num_hosts), classic_quorum_size(num_hosts)),
+            fast: |hosts: Set<nat>| hosts.finite() && hosts.intersect(all_hosts(num_hosts)).len() >= fast_quorum_size(num_hosts),
+            is_fast_ballot,
+        }
+    }
+
+    pub proof fn fast_quorums_by_size_are_well_formed(num_hosts: nat, is_fast_ballot: spec_fn(Ballot) -> bool)
+    ensures
+        fast_quorums_by_size(num_hosts, is_fast_ballot).well_formed(num_hosts),
+    {
+        let fq = fast_quorums_by_size(num_hosts, is_fast_ballot);
+        let acceptors = all_hosts(num_hosts);
+        let (classic_size, fast_size) = (classic_quorum_size(num_hosts), fast_quorum_size(num_hosts));
+
+        assert(2 * classic_size >= num_hosts + 1) by { lemma_fundamental_div_mod(num_hosts as int, 2); };
+        assert(4 * fast_size >= 3 * num_hosts) by { lemma_fundamental_div_mod(((3 * num_hosts) + 3) as int, 4); };
+        assert(classic_size + (2 * fast_size) > 2 * num_hosts);
+
+        full_set_size(acceptors, num_hosts);
+        assert(are_valid_hosts(acceptors, num_hosts));
+        quorums_by_size_are_well_formed(num_hosts, acceptors, classic_size, classic_size);
+
+        assert forall |q: Set<nat>, r1: Set<nat>, r2: Set<nat>|
+            #![trigger fq.classic.is_phase1_quorum(q), fq.is_fast_quorum(r1), fq.is_fast_quorum(r2)]
+            are_valid_hosts(q, num_hosts) &&
+            are_valid_hosts(r1, num_hosts) &&
+            are_valid_hosts(r2, num_hosts) &&
+            fq.classic.is_phase1_quorum(q) &&
+            fq.is_fast_quorum(r1) &&
+            fq.is_fast_quorum(r2) implies
+            exists |h: nat| #![auto] q.contains(h) && r1.contains(h) && r2.contains(h)
+        by {
+            assert(q.intersect(acceptors) =~= q);
+            assert(r1.intersect(acceptors) =~= r1);
+            assert(r2.intersect(acceptors) =~= r2);
+            three_large_sets_have_common_element(q, r1, r2, num_hosts);
+        };
+    }
+
+    pub proof fn three_large_sets_have_common_element(set1: Set<nat>, set2: Set<nat>, set3: Set<nat>, full_size: nat)
+    requires
+        set1.finite(),
+        set2.finite(),
+        set3.finite(),
+        forall |x: nat| #![auto] set1.contains(x) ==> 0 <= x < full_size,
+        forall |x: nat| #![auto] set2.contains(x) ==> 0 <= x < full_size,
+        forall |x: nat| #![auto] set3.contains(x) ==> 0 <= x < full_size,
+        set1.len() + set2.len() + set3.len() > 2 * full_size,
+    ensures
+        exists |x: nat| #![auto] set1.contains(x) && set2.contains(x) && set3.contains(x)
+    {
+        let common12 = set1.intersect(set2);
+
+        assert(forall |x: nat| #[trigger] set1.union(set2).contains(x) ==> 0 <= x < full_size);
+        assert(set1.union(set2).len() <= full_size) by { continuous_set_size_bounds(set1.union(set2), full_size); };
+        assert(set1.len() + set2.len() == set1.union(set2).len() + common12.len()) by { lemma_set_intersect_union_lens(set1, set2); };
+        assert(common12.len() + set3.len() > full_size);
+
+        overlapping_sets_have_common_element(common12, set3, full_size);
+        let common_val = choose |x: nat| #![auto] common12.contains(x) && set3.contains(x);
+        assert(set1.contains(common_val) && set2.contains(common_val) && set3.contains(common_val));
+    }
+}
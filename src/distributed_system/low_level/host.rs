@@ -1,5 +1,10 @@
-use super::{Message, NetworkOperation};
-use crate::distributed_system::{Event, Value};
+use super::{
+    fast::{select_value, FastQuorumSystem},
+    quorum::QuorumSystem,
+    reconfig::Reconfiguration,
+    Message, NetworkOperation,
+};
+use crate::distributed_system::{no_op, Event, Value};
 use vstd::{calc, prelude::*};
 
 verus! {
@@ -24,32 +29,166 @@ verus! {
         }
     }
 
+    pub open spec fn max_ballot(a: Ballot, b: Ballot) -> Ballot {
+        if (a.cmp(&b) >= 0) {
+            a
+        } else {
+            b
+        }
+    }
+
+    // The fast round of every instance. Each instance starts at it, so an acceptor that has promised
+    // no classic ballot yet can accept a value straight from a client (`fast_accept`). Every ballot
+    // a host prepares has `num > 0`.
+    pub open spec fn fast_ballot() -> Ballot {
+        Ballot { num: 0, pid: 0 }
+    }
+
+    pub open spec fn is_fast_ballot(ballot: Ballot) -> bool {
+        ballot.num == 0
+    }
+
+    pub open spec fn fast_quorums(quorums: QuorumSystem, fast: spec_fn(Set<nat>) -> bool) -> FastQuorumSystem {
+        FastQuorumSystem { classic: quorums, fast, is_fast_ballot: |ballot: Ballot| is_fast_ballot(ballot) }
+    }
+
+    // A fast client numbers its requests by the instance they are for, so `seq` is the slot whose
+    // fast round a request goes to. A no-op for `key` has `seq == key` as well.
+    pub open spec fn fast_slot(value: Value) -> nat {
+        value.seq
+    }
+
+    // A host prepares ballots only if it is in `proposers`, votes only if it is one of
+    // `quorums.acceptors`, and counts Accepted messages itself only if it is in `learners`.
+    // `request_owner` maps every client to the proposer that queues its requests. `fast` are the
+    // quorums a learner needs in the fast round (`fast_quorums`), and the clients in `fast_clients`
+    // send their requests to the acceptors directly instead of to their owner. `quorums` and `fast`
+    // are the initial configuration: decided values can replace the acceptors whose votes count with
+    // a subset of `quorums.acceptors` (`reconfig`).
     pub struct Constants {
         pub id: nat,
         pub num_hosts: nat,
         pub num_failures: nat,
+        pub quorums: QuorumSystem,
+        pub fast: spec_fn(Set<nat>) -> bool,
+        pub proposers: Set<nat>,
+        pub learners: Set<nat>,
+        pub request_owner: spec_fn(nat) -> nat,
+        pub fast_clients: Set<nat>,
+        pub reconfig: Reconfiguration,
     }
 
+    // Durable: every field but `volatile` survives a crash, and `promise`/`accept`/`decide` update
+    // them in the same step that sends Promise/Accepted, i.e. they must reach stable storage before
+    // the message leaves the host.
+    //
+    // `max_seen_ballot` is the highest ballot this host has prepared or received for the instance, and
+    // `send_prepare` picks the next ballot above it (`next_ballot`). Promise and Accepted are only
+    // taken for ballots this host prepared and a rejected ballot is below `current_ballot`, so
+    // `promised`, `accepted`, `reject_prepare` and `reject_accept` have nothing to raise it by.
+    //
+    // `configuration` is fixed when the instance is created: the quorums that promises and votes for
+    // it are counted in (`Constants::configuration`).
     pub struct Instance {
         pub current_ballot: Ballot,
-        pub promised: Map<Ballot, Map<nat, Option<(Ballot, Value)>>>,
-        pub proposed_value: Map<Ballot, Value>,
-        pub accepted: Map<Ballot, Set<nat>>,
+        pub max_seen_ballot: Ballot,
         pub accept_ballot: Option<Ballot>,
         pub accept_value: Option<Value>,
         pub decide_value: Option<Value>,
+        pub configuration: FastQuorumSystem,
+        pub volatile: Volatile,
     }
 
+    // Volatile: `promised`, `proposed_value` and `accepted` are the leader's in-memory bookkeeping
+    // for the ballots it prepared since it last recovered, and `learned` is a learner's record of who
+    // sent Accepted for which ballot and value. `recover` empties all four.
+    pub struct Volatile {
+        pub promised: Map<Ballot, Map<nat, Option<(Ballot, Value)>>>,
+        pub proposed_value: Map<Ballot, Value>,
+        pub accepted: Map<Ballot, Set<nat>>,
+        pub learned: Map<(Ballot, Value), Set<nat>>,
+    }
+
+    impl Volatile {
+        pub open spec fn empty() -> Volatile {
+            Volatile { promised: Map::empty(), proposed_value: Map::empty(), accepted: Map::empty(), learned: Map::empty() }
+        }
+    }
+
+    // `pending` is volatile and comes back empty from `recover`.
+    //
+    // `assigned` is durable: it holds the `(client_id, seq)` of every request `send_accept` has taken
+    // from `pending` into a slot, and must reach stable storage before that Accept leaves the host.
     pub struct Variables {
         pub instances: Map<nat, Instance>,
+        pub pending: Seq<Value>,
+        pub assigned: Set<(nat, nat)>,
+        pub up: bool,
+        pub multi: MultiPaxos,
+    }
+
+    // Multi-Paxos phase 1, where one PrepareFrom covers instance `from` and every instance above it.
+    //
+    // Durable: `promise_floor` is the ballot of the last PrepareFrom this host took and `floor_from`
+    // its `from`. The floor covers instances at or above `floor_from` that this host has not created
+    // yet: `init_request` starts them at it. Acceptor steps only act on an instance that is at or
+    // above its floor, and `raise_to_floor` brings instances created before the PrepareFrom up to it.
+    // Either way the instance promises the floor with an ordinary Promise, so every promise an
+    // acceptor makes is still per instance.
+    //
+    // Volatile: `leading` is the PrepareFrom this host sent and can still take instances into with
+    // `join_prepare_from`; it comes back empty from `recover`.
+    pub struct MultiPaxos {
+        pub promise_floor: Ballot,
+        pub floor_from: nat,
+        pub leading: Option<(nat, Ballot)>,
     }
 
     impl Constants {
         pub open spec fn well_formed(&self) -> bool {
             &&& 0 <= self.id < self.num_hosts
             &&& self.num_hosts > 0
-            &&& self.num_failures > 0
-            &&& self.num_hosts == ((2 * self.num_failures) + 1)
+            &&& self.num_hosts >= ((2 * self.num_failures) + 1)
+            &&& self.quorums.well_formed(self.num_hosts)
+            &&& self.quorums.tolerates_failures(self.num_failures)
+            &&& self.fast_quorums().well_formed(self.num_hosts)
+            &&& self.reconfig.well_formed(self.quorums.acceptors, self.num_failures)
+            &&& forall |client_id: nat| #![trigger (self.request_owner)(client_id)]
+                    (self.request_owner)(client_id) < self.num_hosts &&
+                    self.proposers.contains((self.request_owner)(client_id))
+        }
+
+        pub open spec fn fast_quorums(&self) -> FastQuorumSystem {
+            fast_quorums(self.quorums, self.fast)
+        }
+
+        // The quorums of instance `key`, given the decisions `u` has learned.
+        pub open spec fn configuration(&self, u: &Variables, key: nat) -> FastQuorumSystem {
+            self.reconfig.configuration(self.fast_quorums(), self.num_failures, u.decisions(), key)
+        }
+
+        pub open spec fn knows_configuration(&self, u: &Variables, key: nat) -> bool {
+            self.reconfig.knows_configuration(u.decisions(), key)
+        }
+
+        pub open spec fn is_proposer(&self) -> bool {
+            self.proposers.contains(self.id)
+        }
+
+        pub open spec fn is_fast_request(&self, value: Value) -> bool {
+            self.fast_clients.contains(value.client_id)
+        }
+
+        pub open spec fn is_acceptor(&self) -> bool {
+            self.quorums.acceptors.contains(self.id)
+        }
+
+        pub open spec fn is_learner(&self) -> bool {
+            self.learners.contains(self.id)
+        }
+
+        pub open spec fn owns_request(&self, client_id: nat) -> bool {
+            (self.request_owner)(client_id) == self.id
         }
     }
 
@@ -57,6 +196,13 @@ verus! {
         pub open spec fn well_formed(&self, c: &Constants) -> bool {
             &&& c.well_formed()
         }
+
+        pub open spec fn decisions(&self) -> Map<nat, Value> {
+            Map::new(
+                |key: nat| self.instances.contains_key(key) && self.instances[key].decide_value.is_some(),
+                |key: nat| self.instances[key].decide_value.unwrap(),
+            )
+        }
     }
 
     pub open spec fn init(c: &Constants, u: &Variables, host_id: nat, num_hosts: nat) -> bool {
@@ -64,50 +210,185 @@ verus! {
         &&& c.id == host_id
         &&& c.num_hosts == num_hosts
         &&& u.instances.is_empty()
+        &&& u.pending.len() == 0
+        &&& u.assigned.is_empty()
+        &&& u.up
+        &&& u.multi.promise_floor == Ballot { num: 0, pid: 0 }
+        &&& u.multi.floor_from == 0
+        &&& u.multi.leading.is_none()
+    }
+
+    pub open spec fn crash(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& u.up
+        &&& !v.up
+        &&& v.instances == u.instances
+        &&& v.pending == u.pending
+        &&& v.assigned == u.assigned
+        &&& v.multi == u.multi
+        &&& net_op.recv.is_none()
+        &&& net_op.send.is_none()
+    }
+
+    // What `recover` reads back of an instance: its durable fields, with `volatile` empty.
+    pub open spec fn durable_part(instance: Instance) -> Instance {
+        Instance {
+            current_ballot: instance.current_ballot,
+            max_seen_ballot: instance.max_seen_ballot,
+            accept_ballot: instance.accept_ballot,
+            accept_value: instance.accept_value,
+            decide_value: instance.decide_value,
+            configuration: instance.configuration,
+            volatile: Volatile::empty(),
+        }
+    }
+
+    // Only durable state is read back; the leader's view of every earlier ballot is lost.
+    pub open spec fn recover(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& !u.up
+        &&& v.up
+        &&& v.instances == u.instances.map_values(|instance: Instance| durable_part(instance))
+        &&& v.pending.len() == 0
+        &&& v.assigned == u.assigned
+        &&& v.multi == MultiPaxos { promise_floor: u.multi.promise_floor, floor_from: u.multi.floor_from, leading: None }
+        &&& net_op.recv.is_none()
+        &&& net_op.send.is_none()
+    }
+
+    // A client hands its request to this host, which forwards it to the request's owner.
+    pub open spec fn submit_request(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation, value: Value) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& net_op.recv.is_none()
+        &&& !value.is_no_op()
+        &&& v == u
+        &&& net_op.send == Some(Message::ClientRequest { value })
+    }
+
+    pub open spec fn is_queued(pending: Seq<Value>, client_id: nat, seq: nat) -> bool {
+        exists |j: int| #![trigger pending[j]] 0 <= j < pending.len() && pending[j].client_id == client_id && pending[j].seq == seq
+    }
+
+    // Only the owner queues a request, and only once: a retransmission of a request that is already
+    // queued or already assigned to a slot is consumed without effect. A fast client's request is for
+    // the acceptors (`fast_accept`), so no owner queues it.
+    pub open spec fn receive_request(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::ClientRequest { value }) = net_op.recv {
+            &&& !c.is_fast_request(value)
+            &&& c.owns_request(value.client_id)
+            &&& v.instances == u.instances
+            &&& v.pending == if (u.assigned.contains(value.request()) || is_queued(u.pending, value.client_id, value.seq)) {
+                    u.pending
+                } else {
+                    u.pending.push(value)
+                }
+            &&& v.assigned == u.assigned
+            &&& v.multi == u.multi
+            &&& net_op.send.is_none()
+        } else {
+            &&& false
+        }
     }
 
+    // The ballot below which instance `key` may not act: the promise floor if the last PrepareFrom
+    // covers `key`, and no bound otherwise.
+    pub open spec fn floor_for(u: &Variables, key: nat) -> Ballot {
+        if (key >= u.multi.floor_from) {
+            u.multi.promise_floor
+        } else {
+            Ballot { num: 0, pid: 0 }
+        }
+    }
+
+    // The Promise an acceptor of instance `key` sends for `ballot`, as in `promise`.
+    pub open spec fn promise_msg(c: &Constants, instance: Instance, key: nat, ballot: Ballot) -> Message {
+        if (instance.accept_ballot.is_some()) {
+            Message::Promise { key, sender: c.id, ballot, accepted: Some((instance.accept_ballot.unwrap(), instance.accept_value.unwrap())) }
+        } else {
+            Message::Promise { key, sender: c.id, ballot, accepted: None }
+        }
+    }
+
+    // A new instance starts at its floor: an acceptor promises the floor for it right away, as if the
+    // PrepareFrom had reached it as a Prepare for `key`. The host must have learned the decisions that
+    // fix the configuration of `key` first.
     pub open spec fn init_request(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
     recommends
         u.well_formed(c),
         v.well_formed(c),
     {
         &&& !u.instances.contains_key(key)
+        &&& c.knows_configuration(u, key)
         &&& net_op.recv.is_none()
         &&& v.instances == u.instances.insert(key, Instance {
-            current_ballot: Ballot { num: 0, pid: 0 },
-            promised: Map::empty(),
-            proposed_value: Map::empty(),
-            accepted: Map::empty(),
+            current_ballot: floor_for(u, key),
+            max_seen_ballot: floor_for(u, key),
             accept_ballot: None,
             accept_value: None,
             decide_value: None,
+            configuration: c.configuration(u, key),
+            volatile: Volatile::empty(),
         })
-        &&& net_op.send.is_none()
+        &&& v.pending == u.pending
+        &&& v.assigned == u.assigned
+        &&& v.multi == u.multi
+        &&& net_op.send == if (c.is_acceptor() && floor_for(u, key).num > 0) {
+                Some(promise_msg(c, v.instances[key], key, floor_for(u, key)))
+            } else {
+                None
+            }
+    }
+
+    // The lowest ballot owned by this host that is above everything it has seen for `instance`.
+    pub open spec fn next_ballot(c: &Constants, instance: Instance) -> Ballot {
+        Ballot { num: instance.max_seen_ballot.num + 1, pid: c.id }
     }
 
+    // `new_ballot` is above `max_seen_ballot`, which is durable and at least every ballot this host has
+    // ever led for the instance, so a recovered host never re-leads one of its own ballots
+    // (`next_ballot_is_fresh`). Nothing lost in a crash is needed to pick it.
     pub open spec fn send_prepare(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
     recommends
         u.well_formed(c),
         v.well_formed(c),
     {
+        &&& c.is_proposer()
         &&& u.instances.contains_key(key)
         &&& net_op.recv.is_none()
         &&& {
-            let new_ballot = Ballot { num: u.instances[key].current_ballot.num + 1, pid: c.id };
+            let new_ballot = next_ballot(c, u.instances[key]);
 
-            &&& !u.instances[key].promised.contains_key(new_ballot)
-            &&& !u.instances[key].proposed_value.contains_key(new_ballot)
-            &&& !u.instances[key].accepted.contains_key(new_ballot)
             &&& u.instances[key].decide_value.is_none()
             &&& v.instances == u.instances.insert(key, Instance {
                     current_ballot: u.instances[key].current_ballot,
-                    promised: u.instances[key].promised.insert(new_ballot, Map::empty()),
-                    proposed_value: u.instances[key].proposed_value,
-                    accepted: u.instances[key].accepted.insert(new_ballot, Set::empty()),
+                    max_seen_ballot: new_ballot,
                     accept_ballot: u.instances[key].accept_ballot,
                     accept_value: u.instances[key].accept_value,
                     decide_value: u.instances[key].decide_value,
+                    configuration: u.instances[key].configuration,
+                    volatile: Volatile {
+                        promised: u.instances[key].volatile.promised.insert(new_ballot, Map::empty()),
+                        proposed_value: u.instances[key].volatile.proposed_value,
+                        accepted: u.instances[key].volatile.accepted.insert(new_ballot, Set::empty()),
+                        learned: u.instances[key].volatile.learned,
+                    },
                 })
+            &&& v.pending == u.pending
+            &&& v.assigned == u.assigned
+            &&& v.multi == u.multi
             &&& net_op.send == Some(Message::Prepare { key, ballot: new_ballot })
         }
     }
@@ -119,18 +400,23 @@ verus! {
     {
         if let Some(Message::Prepare { key: instance, ballot }) = net_op.recv {
             &&& instance == key
+            &&& c.is_acceptor()
             &&& u.instances.contains_key(instance)
+            &&& u.instances[instance].current_ballot.cmp(&floor_for(u, instance)) >= 0
             &&& ballot.cmp(&u.instances[instance].current_ballot) == 1
             &&& v.instances[instance].current_ballot == ballot
             &&& v.instances == u.instances.insert(instance, Instance {
                     current_ballot: ballot,
-                    promised: u.instances[instance].promised,
-                    proposed_value: u.instances[instance].proposed_value,
-                    accepted: u.instances[instance].accepted,
+                    max_seen_ballot: max_ballot(u.instances[instance].max_seen_ballot, ballot),
                     accept_ballot: u.instances[instance].accept_ballot,
                     accept_value: u.instances[instance].accept_value,
                     decide_value: u.instances[instance].decide_value,
+                    configuration: u.instances[instance].configuration,
+                    volatile: u.instances[instance].volatile,
                 })
+            &&& v.pending == u.pending
+            &&& v.assigned == u.assigned
+            &&& v.multi == u.multi
             &&& net_op.send == if (u.instances[instance].accept_ballot.is_some()) {
                     Some(Message::Promise { key, sender: c.id, ballot, accepted: Some((u.instances[instance].accept_ballot.unwrap(), u.instances[instance].accept_value.unwrap())) })
                 } else {
@@ -141,6 +427,26 @@ verus! {
         }
     }
 
+    // An acceptor that has moved past `ballot` tells its owner which ballot it is at instead of
+    // staying silent, so the owner can prepare above it (see `nacked`).
+    pub open spec fn reject_prepare(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::Prepare { key: instance, ballot }) = net_op.recv {
+            &&& instance == key
+            &&& c.is_acceptor()
+            &&& u.instances.contains_key(instance)
+            &&& u.instances[instance].current_ballot.cmp(&floor_for(u, instance)) >= 0
+            &&& ballot.cmp(&u.instances[instance].current_ballot) < 0
+            &&& v == u
+            &&& net_op.send == Some(Message::Nack { key, ballot, higher: u.instances[instance].current_ballot })
+        } else {
+            &&& false
+        }
+    }
+
     pub open spec fn promised(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
     recommends
         u.well_formed(c),
@@ -149,23 +455,150 @@ verus! {
         if let Some(Message::Promise { key: instance, sender, ballot, accepted }) = net_op.recv {
             &&& instance == key
             &&& u.instances.contains_key(instance)
-            &&& u.instances[instance].promised.contains_key(ballot)
-            &&& !u.instances[instance].proposed_value.contains_key(ballot)
+            &&& u.instances[instance].volatile.promised.contains_key(ballot)
+            &&& !u.instances[instance].volatile.proposed_value.contains_key(ballot)
             &&& v.instances == u.instances.insert(instance, Instance {
                     current_ballot: u.instances[instance].current_ballot,
-                    promised: u.instances[instance].promised.insert(ballot, u.instances[instance].promised[ballot].insert(sender, accepted)),
-                    proposed_value: u.instances[instance].proposed_value,
-                    accepted: u.instances[instance].accepted,
+                    max_seen_ballot: u.instances[instance].max_seen_ballot,
                     accept_ballot: u.instances[instance].accept_ballot,
                     accept_value: u.instances[instance].accept_value,
                     decide_value: u.instances[instance].decide_value,
+                    configuration: u.instances[instance].configuration,
+                    volatile: Volatile {
+                        promised: u.instances[instance].volatile.promised.insert(ballot, u.instances[instance].volatile.promised[ballot].insert(sender, accepted)),
+                        proposed_value: u.instances[instance].volatile.proposed_value,
+                        accepted: u.instances[instance].volatile.accepted,
+                        learned: u.instances[instance].volatile.learned,
+                    },
                 })
+            &&& v.pending == u.pending
+            &&& v.assigned == u.assigned
+            &&& v.multi == u.multi
+            &&& net_op.send.is_none()
+        } else {
+            &&& false
+        }
+    }
+
+    // Multi-Paxos phase 1: one PrepareFrom asks every acceptor to promise `ballot` for instance `key`
+    // and every instance above it. The ballot only has to be above this host's own floor; acceptors
+    // that have moved past it simply do not promise it. `join_prepare_from` later takes each covered
+    // instance into the ballot on this host's side. Like `next_ballot`, the ballot is above the
+    // durable `max_seen_ballot` of every covered instance the host has, so none of them has led it
+    // before; instances created later start empty.
+    pub open spec fn send_prepare_from(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& c.is_proposer()
+        &&& net_op.recv.is_none()
+        &&& match net_op.send {
+                Some(Message::PrepareFrom { from, ballot }) => {
+                    &&& from == key
+                    &&& ballot.num > 0
+                    &&& ballot.pid == c.id
+                    &&& ballot.cmp(&u.multi.promise_floor) > 0
+                    &&& forall |k: nat| #![trigger u.instances[k]]
+                            k >= from && u.instances.contains_key(k) ==>
+                            ballot.cmp(&u.instances[k].max_seen_ballot) > 0
+                    &&& v.instances == u.instances
+                    &&& v.pending == u.pending
+                    &&& v.assigned == u.assigned
+                    &&& v.multi == MultiPaxos { promise_floor: u.multi.promise_floor, floor_from: u.multi.floor_from, leading: Some((from, ballot)) }
+                },
+                _ => false,
+            }
+    }
+
+    // The acceptor takes `ballot` as its new floor from `from` on. Nothing is promised yet: instances
+    // it already has promise it in `raise_to_floor`, and new ones in `init_request`.
+    pub open spec fn promise_from(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::PrepareFrom { from, ballot }) = net_op.recv {
+            &&& from == key
+            &&& ballot.cmp(&u.multi.promise_floor) > 0
+            &&& v.instances == u.instances
+            &&& v.pending == u.pending
+            &&& v.assigned == u.assigned
+            &&& v.multi == MultiPaxos { promise_floor: ballot, floor_from: from, leading: u.multi.leading }
             &&& net_op.send.is_none()
         } else {
             &&& false
         }
     }
 
+    // Moves an instance that is below its floor up to it, promising the floor the way `promise` would
+    // have promised a Prepare for it.
+    pub open spec fn raise_to_floor(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        let floor = floor_for(u, key);
+
+        &&& u.instances.contains_key(key)
+        &&& net_op.recv.is_none()
+        &&& u.instances[key].current_ballot.cmp(&floor) < 0
+        &&& v.instances == u.instances.insert(key, Instance {
+                current_ballot: floor,
+                max_seen_ballot: max_ballot(u.instances[key].max_seen_ballot, floor),
+                accept_ballot: u.instances[key].accept_ballot,
+                accept_value: u.instances[key].accept_value,
+                decide_value: u.instances[key].decide_value,
+                configuration: u.instances[key].configuration,
+                volatile: u.instances[key].volatile,
+            })
+        &&& v.pending == u.pending
+        &&& v.assigned == u.assigned
+        &&& v.multi == u.multi
+        &&& net_op.send == if (c.is_acceptor()) { Some(promise_msg(c, u.instances[key], key, floor)) } else { None }
+    }
+
+    // The leader of a PrepareFrom starts collecting promises for instance `key` under its ballot,
+    // exactly as `send_prepare` would have, without sending a Prepare of its own. `leading` is
+    // volatile, so an instance that has not joined it yet since it was sent has never led it
+    // (`leading_ballot_is_only_in_live_instances`), and the check against `volatile` suffices.
+    pub open spec fn join_prepare_from(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& u.instances.contains_key(key)
+        &&& net_op.recv.is_none()
+        &&& u.multi.leading.is_some()
+        &&& {
+            let (from, ballot) = u.multi.leading.unwrap();
+
+            &&& from <= key
+            &&& !u.instances[key].volatile.promised.contains_key(ballot)
+            &&& !u.instances[key].volatile.proposed_value.contains_key(ballot)
+            &&& !u.instances[key].volatile.accepted.contains_key(ballot)
+            &&& u.instances[key].decide_value.is_none()
+            &&& v.instances == u.instances.insert(key, Instance {
+                    current_ballot: u.instances[key].current_ballot,
+                    max_seen_ballot: max_ballot(u.instances[key].max_seen_ballot, ballot),
+                    accept_ballot: u.instances[key].accept_ballot,
+                    accept_value: u.instances[key].accept_value,
+                    decide_value: u.instances[key].decide_value,
+                    configuration: u.instances[key].configuration,
+                    volatile: Volatile {
+                        promised: u.instances[key].volatile.promised.insert(ballot, Map::empty()),
+                        proposed_value: u.instances[key].volatile.proposed_value,
+                        accepted: u.instances[key].volatile.accepted.insert(ballot, Set::empty()),
+                        learned: u.instances[key].volatile.learned,
+                    },
+                })
+            &&& v.pending == u.pending
+            &&& v.assigned == u.assigned
+            &&& v.multi == u.multi
+            &&& net_op.send.is_none()
+        }
+    }
+
     pub open spec fn max_accepted_value_by_ballot(a: Option<(Ballot, Value)>, b: Option<(Ballot, Value)>) -> Option<(Ballot, Value)> {
         if (a.is_none() && b.is_none()) {
             None
@@ -204,25 +637,36 @@ verus! {
         v.well_formed(c),
     {
         let instance = key;
+        let max_accepted_value = select_value(u.instances[instance].configuration, c.num_hosts, u.instances[instance].volatile.promised[u.instances[instance].current_ballot]);
 
         &&& u.instances.contains_key(instance)
         &&& net_op.recv.is_none()
-        &&& u.instances[instance].promised.contains_key(u.instances[instance].current_ballot)
-        &&& u.instances[instance].promised[u.instances[instance].current_ballot].len() > c.num_failures
-        &&& !u.instances[instance].proposed_value.contains_key(u.instances[instance].current_ballot)
+        &&& u.instances[instance].volatile.promised.contains_key(u.instances[instance].current_ballot)
+        &&& u.instances[instance].configuration.classic.is_phase1_quorum(u.instances[instance].volatile.promised[u.instances[instance].current_ballot].dom())
+        &&& !u.instances[instance].volatile.proposed_value.contains_key(u.instances[instance].current_ballot)
+        // With no previously accepted value to re-propose, the leader takes the oldest pending client
+        // request, or the instance's no-op if no request is waiting.
         &&& v.instances == u.instances.insert(instance, Instance {
                 current_ballot: u.instances[instance].current_ballot,
-                promised: u.instances[instance].promised,
-                proposed_value: u.instances[instance].proposed_value.insert(
-                    u.instances[instance].current_ballot,
-                    if let Some((_, value)) = get_max_accepted_value(u.instances[instance].promised[u.instances[instance].current_ballot]) { value } else { c.id as Value }
-                ),
-                accepted: u.instances[instance].accepted,
+                max_seen_ballot: u.instances[instance].max_seen_ballot,
                 accept_ballot: u.instances[instance].accept_ballot,
                 accept_value: u.instances[instance].accept_value,
                 decide_value: u.instances[instance].decide_value,
+                configuration: u.instances[instance].configuration,
+                volatile: Volatile {
+                    promised: u.instances[instance].volatile.promised,
+                    proposed_value: u.instances[instance].volatile.proposed_value.insert(
+                        u.instances[instance].current_ballot,
+                        if let Some((_, value)) = max_accepted_value { value } else if (u.pending.len() > 0) { u.pending[0] } else { no_op(key) }
+                    ),
+                    accepted: u.instances[instance].volatile.accepted,
+                    learned: u.instances[instance].volatile.learned,
+                },
             })
-        &&& net_op.send == Some(Message::Accept { key, ballot: v.instances[instance].current_ballot, value: v.instances[instance].proposed_value[v.instances[instance].current_ballot] })
+        &&& v.pending == if (max_accepted_value.is_none() && u.pending.len() > 0) { u.pending.drop_first() } else { u.pending }
+        &&& v.assigned == if (max_accepted_value.is_none() && u.pending.len() > 0) { u.assigned.insert(u.pending[0].request()) } else { u.assigned }
+        &&& v.multi == u.multi
+        &&& net_op.send == Some(Message::Accept { key, ballot: v.instances[instance].current_ballot, value: v.instances[instance].volatile.proposed_value[v.instances[instance].current_ballot] })
     }
 
     pub open spec fn accept(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
@@ -232,18 +676,108 @@ verus! {
     {
         if let Some(Message::Accept { key: instance, ballot, value }) = net_op.recv {
             &&& instance == key
+            &&& c.is_acceptor()
             &&& u.instances.contains_key(instance)
+            &&& u.instances[instance].current_ballot.cmp(&floor_for(u, instance)) >= 0
             &&& ballot.cmp(&u.instances[instance].current_ballot) >= 0
             &&& v.instances == u.instances.insert(instance, Instance {
                     current_ballot: ballot,
-                    promised: u.instances[instance].promised,
-                    proposed_value: u.instances[instance].proposed_value,
-                    accepted: u.instances[instance].accepted,
+                    max_seen_ballot: max_ballot(u.instances[instance].max_seen_ballot, ballot),
                     accept_ballot: Some(ballot),
                     accept_value: Some(value),
                     decide_value: u.instances[instance].decide_value,
+                    configuration: u.instances[instance].configuration,
+                    volatile: u.instances[instance].volatile,
                 })
-            &&& net_op.send == Some(Message::Accepted { key, sender: c.id, ballot })
+            &&& v.pending == u.pending
+            &&& v.assigned == u.assigned
+            &&& v.multi == u.multi
+            &&& net_op.send == Some(Message::Accepted { key, sender: c.id, ballot, value })
+        } else {
+            &&& false
+        }
+    }
+
+    // The fast round: an acceptor takes a fast client's request straight off the network into the
+    // request's `fast_slot`, with no proposer in between. It takes at most one value in the fast
+    // round, and only while it has promised no classic ballot for the instance.
+    pub open spec fn fast_accept(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::ClientRequest { value }) = net_op.recv {
+            let instance = fast_slot(value);
+            &&& instance == key
+            &&& c.is_fast_request(value)
+            &&& c.is_acceptor()
+            &&& u.instances.contains_key(instance)
+            &&& u.instances[instance].current_ballot.cmp(&floor_for(u, instance)) >= 0
+            &&& u.instances[instance].current_ballot == fast_ballot()
+            &&& u.instances[instance].accept_ballot.is_none()
+            &&& v.instances == u.instances.insert(instance, Instance {
+                    current_ballot: u.instances[instance].current_ballot,
+                    max_seen_ballot: u.instances[instance].max_seen_ballot,
+                    accept_ballot: Some(fast_ballot()),
+                    accept_value: Some(value),
+                    decide_value: u.instances[instance].decide_value,
+                    configuration: u.instances[instance].configuration,
+                    volatile: u.instances[instance].volatile,
+                })
+            &&& v.pending == u.pending
+            &&& v.assigned == u.assigned
+            &&& v.multi == u.multi
+            &&& net_op.send == Some(Message::Accepted { key, sender: c.id, ballot: fast_ballot(), value })
+        } else {
+            &&& false
+        }
+    }
+
+    pub open spec fn reject_accept(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::Accept { key: instance, ballot, .. }) = net_op.recv {
+            &&& instance == key
+            &&& c.is_acceptor()
+            &&& u.instances.contains_key(instance)
+            &&& u.instances[instance].current_ballot.cmp(&floor_for(u, instance)) >= 0
+            &&& ballot.cmp(&u.instances[instance].current_ballot) < 0
+            &&& v == u
+            &&& net_op.send == Some(Message::Nack { key, ballot, higher: u.instances[instance].current_ballot })
+        } else {
+            &&& false
+        }
+    }
+
+    // The owner of a rejected ballot raises its `max_seen_ballot` to the acceptor's, so its next
+    // `send_prepare` picks a ballot above `higher`. Its `current_ballot` stays put: a host moved to a
+    // ballot it never promised would ignore that ballot's Prepare (`promise` wants a strictly higher
+    // one), and a leader counting on its promise would wait for it forever.
+    pub open spec fn nacked(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::Nack { key: instance, ballot, higher }) = net_op.recv {
+            &&& instance == key
+            &&& ballot.pid == c.id
+            &&& u.instances.contains_key(instance)
+            &&& higher.cmp(&u.instances[instance].max_seen_ballot) > 0
+            &&& v.instances == u.instances.insert(instance, Instance {
+                    current_ballot: u.instances[instance].current_ballot,
+                    max_seen_ballot: higher,
+                    accept_ballot: u.instances[instance].accept_ballot,
+                    accept_value: u.instances[instance].accept_value,
+                    decide_value: u.instances[instance].decide_value,
+                    configuration: u.instances[instance].configuration,
+                    volatile: u.instances[instance].volatile,
+                })
+            &&& v.pending == u.pending
+            &&& v.assigned == u.assigned
+            &&& v.multi == u.multi
+            &&& net_op.send.is_none()
         } else {
             &&& false
         }
@@ -254,25 +788,107 @@ verus! {
         u.well_formed(c),
         v.well_formed(c),
     {
-        if let Some(Message::Accepted { key: instance, sender, ballot }) = net_op.recv {
+        if let Some(Message::Accepted { key: instance, sender, ballot, .. }) = net_op.recv {
             &&& instance == key
             &&& u.instances.contains_key(instance)
-            &&& u.instances[instance].accepted.contains_key(ballot)
+            &&& u.instances[instance].volatile.accepted.contains_key(ballot)
             &&& v.instances == u.instances.insert(instance, Instance {
                     current_ballot: u.instances[instance].current_ballot,
-                    promised: u.instances[instance].promised,
-                    proposed_value: u.instances[instance].proposed_value,
-                    accepted: u.instances[instance].accepted.insert(ballot, u.instances[instance].accepted[ballot].insert(sender)),
+                    max_seen_ballot: u.instances[instance].max_seen_ballot,
                     accept_ballot: u.instances[instance].accept_ballot,
                     accept_value: u.instances[instance].accept_value,
                     decide_value: u.instances[instance].decide_value,
+                    configuration: u.instances[instance].configuration,
+                    volatile: Volatile {
+                        promised: u.instances[instance].volatile.promised,
+                        proposed_value: u.instances[instance].volatile.proposed_value,
+                        accepted: u.instances[instance].volatile.accepted.insert(ballot, u.instances[instance].volatile.accepted[ballot].insert(sender)),
+                        learned: u.instances[instance].volatile.learned,
+                    },
                 })
+            &&& v.pending == u.pending
+            &&& v.assigned == u.assigned
+            &&& v.multi == u.multi
             &&& net_op.send.is_none()
         } else {
             &&& false
         }
     }
 
+    // Acceptors send Accepted to the ballot owner and to every learner; a learner counts matching
+    // ones per ballot and value, whichever host led the ballot.
+    pub open spec fn observe_accepted(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::Accepted { key: instance, sender, ballot, value }) = net_op.recv {
+            let senders = if (u.instances[instance].volatile.learned.contains_key((ballot, value))) {
+                u.instances[instance].volatile.learned[(ballot, value)]
+            } else {
+                Set::empty()
+            };
+
+            &&& instance == key
+            &&& c.is_learner()
+            &&& u.instances.contains_key(instance)
+            &&& v.instances == u.instances.insert(instance, Instance {
+                    current_ballot: u.instances[instance].current_ballot,
+                    max_seen_ballot: max_ballot(u.instances[instance].max_seen_ballot, ballot),
+                    accept_ballot: u.instances[instance].accept_ballot,
+                    accept_value: u.instances[instance].accept_value,
+                    decide_value: u.instances[instance].decide_value,
+                    configuration: u.instances[instance].configuration,
+                    volatile: Volatile {
+                        promised: u.instances[instance].volatile.promised,
+                        proposed_value: u.instances[instance].volatile.proposed_value,
+                        accepted: u.instances[instance].volatile.accepted,
+                        learned: u.instances[instance].volatile.learned.insert((ballot, value), senders.insert(sender)),
+                    },
+                })
+            &&& v.pending == u.pending
+            &&& v.assigned == u.assigned
+            &&& v.multi == u.multi
+            &&& net_op.send.is_none()
+        } else {
+            &&& false
+        }
+    }
+
+    // A learner that has counted a phase-2 quorum of Accepted for `(ballot, value)` decides without
+    // waiting for the leader's Decide, and announces it to the hosts that are not learners. The fast
+    // round has no leader, so there the learner is the only one who decides and it needs a fast quorum.
+    pub open spec fn learner_decide(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation, ballot: Ballot, expected_value: Value) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        let instance = key;
+
+        &&& c.is_learner()
+        &&& u.instances.contains_key(instance)
+        &&& net_op.recv.is_none()
+        &&& u.instances[instance].volatile.learned.contains_key((ballot, expected_value))
+        &&& if (is_fast_ballot(ballot)) {
+                u.instances[instance].configuration.is_fast_quorum(u.instances[instance].volatile.learned[(ballot, expected_value)])
+            } else {
+                u.instances[instance].configuration.classic.is_phase2_quorum(u.instances[instance].volatile.learned[(ballot, expected_value)])
+            }
+        &&& v.instances == u.instances.insert(instance, Instance {
+                current_ballot: u.instances[instance].current_ballot,
+                max_seen_ballot: u.instances[instance].max_seen_ballot,
+                accept_ballot: u.instances[instance].accept_ballot,
+                accept_value: u.instances[instance].accept_value,
+                decide_value: Some(expected_value),
+                configuration: u.instances[instance].configuration,
+                volatile: u.instances[instance].volatile,
+            })
+        &&& v.pending == u.pending
+        &&& v.assigned == u.assigned
+        &&& v.multi == u.multi
+        &&& net_op.send == Some(Message::Decide { key, ballot, value: expected_value })
+    }
+
     pub open spec fn send_decide(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation) -> bool
     recommends
         u.well_formed(c),
@@ -282,11 +898,41 @@ verus! {
 
         &&& u.instances.contains_key(instance)
         &&& net_op.recv.is_none()
-        &&& u.instances[instance].proposed_value.contains_key(u.instances[instance].current_ballot)
-        &&& u.instances[instance].accepted.contains_key(u.instances[instance].current_ballot)
-        &&& u.instances[instance].accepted[u.instances[instance].current_ballot].len() > c.num_failures
+        &&& u.instances[instance].volatile.proposed_value.contains_key(u.instances[instance].current_ballot)
+        &&& u.instances[instance].volatile.accepted.contains_key(u.instances[instance].current_ballot)
+        &&& u.instances[instance].configuration.classic.is_phase2_quorum(u.instances[instance].volatile.accepted[u.instances[instance].current_ballot])
         &&& v == u
-        &&& net_op.send == Some(Message::Decide { key, ballot: u.instances[instance].current_ballot, value: u.instances[instance].proposed_value[u.instances[instance].current_ballot] })
+        &&& net_op.send == Some(Message::Decide { key, ballot: u.instances[instance].current_ballot, value: u.instances[instance].volatile.proposed_value[u.instances[instance].current_ballot] })
+    }
+
+    // The leader that gathered an Accepted quorum for its current ballot learns the decision itself and announces it.
+    pub open spec fn learn(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation, expected_value: Value) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        let instance = key;
+        let ballot = u.instances[instance].current_ballot;
+
+        &&& u.instances.contains_key(instance)
+        &&& net_op.recv.is_none()
+        &&& u.instances[instance].volatile.proposed_value.contains_key(ballot)
+        &&& u.instances[instance].volatile.accepted.contains_key(ballot)
+        &&& u.instances[instance].configuration.classic.is_phase2_quorum(u.instances[instance].volatile.accepted[ballot])
+        &&& expected_value == u.instances[instance].volatile.proposed_value[ballot]
+        &&& v.instances == u.instances.insert(instance, Instance {
+                current_ballot: ballot,
+                max_seen_ballot: u.instances[instance].max_seen_ballot,
+                accept_ballot: u.instances[instance].accept_ballot,
+                accept_value: u.instances[instance].accept_value,
+                decide_value: Some(expected_value),
+                configuration: u.instances[instance].configuration,
+                volatile: u.instances[instance].volatile,
+            })
+        &&& v.pending == u.pending
+        &&& v.assigned == u.assigned
+        &&& v.multi == u.multi
+        &&& net_op.send == Some(Message::Decide { key, ballot, value: expected_value })
     }
 
     pub open spec fn decide(c: &Constants, u: &Variables, v: &Variables, key: nat, net_op: NetworkOperation, expected_value: Value) -> bool
@@ -297,17 +943,20 @@ verus! {
         if let Some(Message::Decide { key: instance, ballot, value }) = net_op.recv {
             &&& instance == key
             &&& u.instances.contains_key(instance)
-            &&& ballot.cmp(&u.instances[instance].current_ballot) == 1
+            &&& ballot.cmp(&u.instances[instance].current_ballot) >= 0
             &&& value == expected_value
             &&& v.instances == u.instances.insert(instance, Instance {
                     current_ballot: ballot,
-                    promised: u.instances[instance].promised,
-                    proposed_value: u.instances[instance].proposed_value,
-                    accepted: u.instances[instance].accepted,
+                    max_seen_ballot: max_ballot(u.instances[instance].max_seen_ballot, ballot),
                     accept_ballot: u.instances[instance].accept_ballot,
                     accept_value: u.instances[instance].accept_value,
                     decide_value: Some(value),
+                    configuration: u.instances[instance].configuration,
+                    volatile: u.instances[instance].volatile,
                 })
+            &&& v.pending == u.pending
+            &&& v.assigned == u.assigned
+            &&& v.multi == u.multi
             &&& net_op.send.is_none()
         } else {
             &&& false
@@ -320,17 +969,56 @@ verus! {
         &&& match event {
                 Event::Decide { key: event_key, value } => {
                     &&& event_key == key
-                    &&& decide(c, u, v, key, net_op, value)
+                    &&& u.up && v.up
+                    &&& {
+                        ||| decide(c, u, v, key, net_op, value)
+                        ||| learn(c, u, v, key, net_op, value)
+                        ||| exists |ballot: Ballot| #[trigger] learner_decide(c, u, v, key, net_op, ballot, value)
+                    }
                 },
+                Event::Propose { key: event_key, value } => {
+                    &&& event_key == key
+                    &&& u.up && v.up
+                    &&& {
+                        ||| {
+                            &&& send_accept(c, u, v, key, net_op)
+                            &&& value == v.instances[key].volatile.proposed_value[v.instances[key].current_ballot]
+                        }
+                        ||| {
+                            &&& fast_accept(c, u, v, key, net_op)
+                            &&& value == v.instances[key].accept_value.unwrap()
+                        }
+                    }
+                },
+                Event::Submit { value } => {
+                    &&& u.up
+                    &&& submit_request(c, u, v, net_op, value)
+                },
+                Event::ProposeBatch { .. } | Event::DecideBatch { .. } => false,
                 Event::NoOp => {
-                    ||| init_request(c, u, v, key, net_op)
-                    ||| send_prepare(c, u, v, key, net_op)
-                    ||| promise(c, u, v, key, net_op)
-                    ||| promised(c, u, v, key, net_op)
-                    ||| send_accept(c, u, v, key, net_op)
-                    ||| accept(c, u, v, key, net_op)
-                    ||| accepted(c, u, v, key, net_op)
-                    ||| send_decide(c, u, v, key, net_op)
+                    ||| crash(c, u, v, net_op)
+                    ||| recover(c, u, v, net_op)
+                    ||| {
+                        &&& u.up && v.up
+                        &&& {
+                            ||| init_request(c, u, v, key, net_op)
+                            ||| send_prepare(c, u, v, key, net_op)
+                            ||| send_prepare_from(c, u, v, key, net_op)
+                            ||| promise_from(c, u, v, key, net_op)
+                            ||| raise_to_floor(c, u, v, key, net_op)
+                            ||| join_prepare_from(c, u, v, key, net_op)
+                            ||| promise(c, u, v, key, net_op)
+                            ||| reject_prepare(c, u, v, key, net_op)
+                            ||| promised(c, u, v, key, net_op)
+                            ||| accept(c, u, v, key, net_op)
+                            ||| reject_accept(c, u, v, key, net_op)
+                            ||| nacked(c, u, v, key, net_op)
+                            ||| accepted(c, u, v, key, net_op)
+                            ||| observe_accepted(c, u, v, key, net_op)
+                            ||| send_decide(c, u, v, key, net_op)
+                            ||| receive_request(c, u, v, key, net_op)
+                        }
+                    }
                 },
             }
     }
@@ -357,6 +1045,19 @@ verus! {
             accepted_map[s1].unwrap().1 == accepted_map[s2].unwrap().1
     }
 
+    // Acceptors that collided in the fast round report different values for the same fast ballot, so
+    // only classic ballots are known to carry one value each.
+    pub open spec fn same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(accepted_map: Map<nat, Option<(Ballot, Value)>>) -> bool {
+        forall |s1: nat, s2: nat|
+            accepted_map.contains_key(s1) &&
+            accepted_map.contains_key(s2) &&
+            accepted_map[s1].is_some() &&
+            accepted_map[s2].is_some() &&
+            !is_fast_ballot(accepted_map[s1].unwrap().0) &&
+            #[trigger] accepted_map_ballots_are_same(accepted_map[s1].unwrap().0, accepted_map[s2].unwrap().0) ==>
+            accepted_map[s1].unwrap().1 == accepted_map[s2].unwrap().1
+    }
+
     pub open spec fn is_largest_accepted_ballot_sender(accepted_map: Map<nat, Option<(Ballot, Value)>>, largest_accepted_ballot_sender: nat) -> bool {
         &&& accepted_map.contains_key(largest_accepted_ballot_sender)
         &&& accepted_map[largest_accepted_ballot_sender].is_some()
@@ -1,16 +1,27 @@
-use super::{Event, Value};
+use super::{no_op, Event, Value};
 use vstd::{prelude::*, set_lib::*};
 
 verus! {
+    pub mod batch;
+    pub mod fast;
     pub mod host;
     pub mod network;
+    pub mod quorum;
+    pub mod reconfig;
 
     pub enum Message {
         Prepare { key: nat, ballot: host::Ballot },
         Promise { key: nat, sender: nat, ballot: host::Ballot, accepted: Option<(host::Ballot, Value)> },
         Accept { key: nat, ballot: host::Ballot, value: Value },
-        Accepted { key: nat, sender: nat, ballot: host::Ballot },
+        Accepted { key: nat, sender: nat, ballot: host::Ballot, value: Value },
+        Nack { key: nat, ballot: host::Ballot, higher: host::Ballot },
         Decide { key: nat, ballot: host::Ballot, value: Value },
+        PrepareFrom { from: nat, ballot: host::Ballot },
+        ClientRequest { value: Value },
+        // Batched phase 2 (`batch`): the Accept, Accepted or Decide of every entry, all at `ballot`.
+        AcceptBatch { ballot: host::Ballot, entries: Seq<(nat, Value)> },
+        AcceptedBatch { sender: nat, ballot: host::Ballot, entries: Seq<(nat, Value)> },
+        DecideBatch { ballot: host::Ballot, entries: Seq<(nat, Value)> },
     }
 
     pub struct NetworkOperation {
@@ -23,23 +34,62 @@ verus! {
         pub num_hosts: nat,
         pub hosts: Seq<host::Constants>,
         pub network: network::Constants,
+        pub quorums: quorum::QuorumSystem,
+        pub fast: spec_fn(Set<nat>) -> bool,
+        pub proposers: Set<nat>,
+        pub learners: Set<nat>,
+        pub request_owner: spec_fn(nat) -> nat,
+        pub fast_clients: Set<nat>,
+        pub reconfig: reconfig::Reconfiguration,
     }
 
+    // `history[i][key]` is ghost state: everything host `i` has ever held in the volatile part of
+    // instance `key`, merged across crashes. `recover` empties the host's own copy but not this, so the
+    // invariants about a leader's promise quorums, proposals and Accepted quorums keep talking about
+    // every ballot the host ever led, while the host itself only acts on what it still has in memory.
+    //
+    // `configurations[key]` is ghost state as well: the quorums of instance `key`, recorded when the
+    // first host creates it. Every host that creates it later computes the same ones, so the
+    // invariants count promises and votes for `key` in them whichever host holds the instance.
     pub struct Variables {
         pub hosts: Seq<host::Variables>,
         pub network: network::Variables,
+        pub history: Seq<Map<nat, host::Volatile>>,
+        pub configurations: Map<nat, fast::FastQuorumSystem>,
     }
 
     impl Constants {
         pub open spec fn well_formed(&self) -> bool {
             &&& self.num_hosts > 0
-            &&& self.num_failures > 0
-            &&& self.num_hosts == ((2 * self.num_failures) + 1)
+            &&& self.num_hosts >= ((2 * self.num_failures) + 1)
             &&& self.hosts.len() == self.num_hosts
+            &&& self.quorums.well_formed(self.num_hosts)
+            &&& self.quorums.tolerates_failures(self.num_failures)
+            &&& self.fast_quorums().well_formed(self.num_hosts)
+            &&& self.reconfig.well_formed(self.quorums.acceptors, self.num_failures)
+            &&& forall |client_id: nat| #![trigger (self.request_owner)(client_id)]
+                    (self.request_owner)(client_id) < self.num_hosts &&
+                    self.proposers.contains((self.request_owner)(client_id))
             &&& forall |i: nat| #![auto]
                     0 <= i < self.num_hosts ==>
                     self.hosts[i as int].id == i &&
-                    self.hosts[i as int].num_failures == self.num_failures
+                    self.hosts[i as int].num_hosts == self.num_hosts &&
+                    self.hosts[i as int].num_failures == self.num_failures &&
+                    self.hosts[i as int].quorums == self.quorums &&
+                    self.hosts[i as int].fast == self.fast &&
+                    self.hosts[i as int].proposers == self.proposers &&
+                    self.hosts[i as int].learners == self.learners &&
+                    self.hosts[i as int].request_owner == self.request_owner &&
+                    self.hosts[i as int].fast_clients == self.fast_clients &&
+                    self.hosts[i as int].reconfig == self.reconfig
+        }
+
+        pub open spec fn is_fast_request(&self, value: Value) -> bool {
+            self.fast_clients.contains(value.client_id)
+        }
+
+        pub open spec fn fast_quorums(&self) -> fast::FastQuorumSystem {
+            host::fast_quorums(self.quorums, self.fast)
         }
     }
 
@@ -49,19 +99,53 @@ verus! {
             &&& self.hosts.len() == c.hosts.len()
             &&& forall |idx: nat| #![auto] 0 <= idx < self.hosts.len() ==> self.hosts[idx as int].well_formed(&c.hosts[idx as int])
             &&& self.network.well_formed(&c.network)
+            &&& self.history.len() == self.hosts.len()
+            &&& forall |idx: int| #![auto] 0 <= idx < self.hosts.len() ==> self.history[idx].dom() == self.hosts[idx].instances.dom()
         }
     }
 
+    pub open spec fn merge(history: host::Volatile, volatile: host::Volatile) -> host::Volatile {
+        host::Volatile {
+            promised: history.promised.union_prefer_right(volatile.promised),
+            proposed_value: history.proposed_value.union_prefer_right(volatile.proposed_value),
+            accepted: history.accepted.union_prefer_right(volatile.accepted),
+            learned: history.learned.union_prefer_right(volatile.learned),
+        }
+    }
+
+    // The history of a host after one of its steps: whatever it now holds in memory, on top of what it
+    // held before. Nothing is ever taken out, so a crash and `recover` leave it as it was.
+    pub open spec fn record(history: Map<nat, host::Volatile>, host: host::Variables) -> Map<nat, host::Volatile> {
+        Map::new(
+            |key: nat| host.instances.contains_key(key),
+            |key: nat| merge(if history.contains_key(key) { history[key] } else { host::Volatile::empty() }, host.instances[key].volatile),
+        )
+    }
+
+    // The configurations after a host step: the one the host fixed for each instance it has that no
+    // host had before. Recorded ones are never replaced.
+    pub open spec fn record_configurations(configurations: Map<nat, fast::FastQuorumSystem>, host: host::Variables) -> Map<nat, fast::FastQuorumSystem> {
+        Map::new(
+            |key: nat| configurations.contains_key(key) || host.instances.contains_key(key),
+            |key: nat| if configurations.contains_key(key) { configurations[key] } else { host.instances[key].configuration },
+        )
+    }
+
     pub open spec fn init(c: &Constants, u: &Variables) -> bool {
         &&& u.well_formed(c)
         &&& forall |idx: nat| #![auto]
                 0 <= idx < u.hosts.len() ==>
                 host::init(&c.hosts[idx as int], &u.hosts[idx as int], idx, u.hosts.len())
         &&& network::init(&c.network, &u.network)
+        &&& forall |idx: int| #![auto] 0 <= idx < u.hosts.len() ==> u.history[idx] == Map::<nat, host::Volatile>::empty()
+        &&& u.configurations == Map::<nat, fast::FastQuorumSystem>::empty()
     }
 
     pub enum Transition {
-        HostStep { host_id: int, instance: nat, net_op: NetworkOperation }
+        HostStep { host_id: int, instance: nat, net_op: NetworkOperation },
+        DropStep { message: Message },
+        DuplicateStep { message: Message },
+        GarbageCollectStep { key: nat },
     }
 
     pub open spec fn host_step(c: &Constants, u: &Variables, v: &Variables, host_id: int, instance: nat, net_op: NetworkOperation, event: Event) -> bool
@@ -74,21 +158,109 @@ verus! {
             &&& host::step(&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id], instance, net_op, event)
             &&& forall |i: int| #![auto] 0 <= i < v.hosts.len() && i != host_id ==> u.hosts[i] == v.hosts[i]
         }
+        &&& v.history == u.history.update(host_id, record(u.history[host_id], v.hosts[host_id]))
+        &&& v.configurations == record_configurations(u.configurations, v.hosts[host_id])
         &&& network::step(&c.network, &u.network, &v.network, net_op)
     }
 
+    pub open spec fn some_host_decided(u: &Variables, key: nat) -> bool {
+        exists |i: int| #![auto] 0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) && u.hosts[i].instances[key].decide_value.is_some()
+    }
+
     pub open spec fn is_valid_transition(c: &Constants, u: &Variables, v: &Variables, transition: Transition, event: Event) -> bool {
         &&& u.well_formed(c)
         &&& v.well_formed(c)
         &&& match transition {
-            Transition::HostStep { host_id, instance, net_op } => host_step(c, u, v, host_id, instance, net_op, event)
+            Transition::HostStep { host_id, instance, net_op } => host_step(c, u, v, host_id, instance, net_op, event),
+            Transition::DropStep { message } => {
+                &&& event is NoOp
+                &&& v.hosts == u.hosts
+                &&& v.history == u.history
+                &&& v.configurations == u.configurations
+                &&& network::drop(&c.network, &u.network, &v.network, message)
+            },
+            Transition::DuplicateStep { message } => {
+                &&& event is NoOp
+                &&& v.hosts == u.hosts
+                &&& v.history == u.history
+                &&& v.configurations == u.configurations
+                &&& network::duplicate(&c.network, &u.network, &v.network, message)
+            },
+            Transition::GarbageCollectStep { key } => {
+                &&& event is NoOp
+                &&& some_host_decided(u, key)
+                &&& v.hosts == u.hosts
+                &&& v.history == u.history
+                &&& v.configurations == u.configurations
+                &&& network::garbage_collect(&c.network, &u.network, &v.network, key)
+            },
         }
     }
 
-    pub open spec fn next(c: &Constants, u: &Variables, v: &Variables, event: Event) -> bool {
+    // A step of one host on a batch (`batch::step`), which the network delivers and records like any
+    // other message. A batch only touches instances the host already has, so it records no
+    // configuration.
+    pub open spec fn batch_host_step(c: &Constants, u: &Variables, v: &Variables, host_id: int, net_op: NetworkOperation, event: Event) -> bool {
+        &&& u.well_formed(c)
+        &&& v.well_formed(c)
+        &&& 0 <= host_id < u.hosts.len()
+        &&& batch::step(&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id], net_op, event)
+        &&& forall |i: int| #![auto] 0 <= i < v.hosts.len() && i != host_id ==> u.hosts[i] == v.hosts[i]
+        &&& v.history == u.history.update(host_id, record(u.history[host_id], v.hosts[host_id]))
+        &&& v.configurations == u.configurations
+        &&& network::step(&c.network, &u.network, &v.network, net_op)
+    }
+
+    // Every step on single-key messages. The invariants below are proved one such step at a time;
+    // a batch step is a run of them (`batch_next_is_run_of_host_steps`).
+    pub open spec fn message_next(c: &Constants, u: &Variables, v: &Variables, event: Event) -> bool {
         exists |transition: Transition| #[trigger] is_valid_transition(c, u, v, transition, event)
     }
 
+    pub open spec fn batch_next(c: &Constants, u: &Variables, v: &Variables, event: Event) -> bool {
+        exists |host_id: int, net_op: NetworkOperation| #[trigger] batch_host_step(c, u, v, host_id, net_op, event)
+    }
+
+    pub open spec fn next(c: &Constants, u: &Variables, v: &Variables, event: Event) -> bool {
+        ||| message_next(c, u, v, event)
+        ||| batch_next(c, u, v, event)
+    }
+
+    pub open spec fn host_next(c: &Constants, u: &Variables, v: &Variables, event: Event) -> bool {
+        exists |host_id: int, instance: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, v, Transition::HostStep { host_id, instance, net_op }, event)
+    }
+
+    // Drop, duplicate and garbage-collect steps touch neither the hosts nor the sent history.
+    pub open spec fn network_only_next(c: &Constants, u: &Variables, v: &Variables, event: Event) -> bool {
+        &&& u.well_formed(c)
+        &&& v.well_formed(c)
+        &&& event is NoOp
+        &&& v.hosts == u.hosts
+        &&& v.history == u.history
+        &&& v.configurations == u.configurations
+        &&& v.network.sent_messages == u.network.sent_messages
+        &&& v.network.in_flight_messages.subset_of(u.network.in_flight_messages.union(u.network.sent_messages))
+    }
+
+    pub proof fn next_is_host_next_or_network_only_next(c: &Constants, u: &Variables, v: &Variables, event: Event)
+    requires
+        message_next(c, u, v, event),
+    ensures
+        host_next(c, u, v, event) || network_only_next(c, u, v, event),
+    {
+        let transition = choose |transition: Transition| #[trigger] is_valid_transition(c, u, v, transition, event);
+        match transition {
+            Transition::HostStep { host_id, instance, net_op } => {
+                assert(is_valid_transition(c, u, v, Transition::HostStep { host_id, instance, net_op }, event));
+            },
+            _ => {
+                assert(v.network.sent_messages =~= u.network.sent_messages);
+            },
+        }
+    }
+
+    // Holds across `host::crash`/`host::recover`: neither touches a durable field or the network, and
+    // the invariants about a leader's bookkeeping are stated over `history`, which `recover` keeps.
     pub open spec fn safety(c: &Constants, u: &Variables) -> bool {
         &&& u.well_formed(c)
         &&& forall |i: int, j: int, instance: nat| #![auto]
@@ -100,39 +272,74 @@ verus! {
                 u.hosts[i].instances[instance].decide_value == u.hosts[j].instances[instance].decide_value
     }
 
+    pub open spec fn validity(c: &Constants, u: &Variables) -> bool {
+        &&& u.well_formed(c)
+        &&& forall |i: int, instance: nat| #![auto]
+                0 <= i < u.hosts.len() &&
+                u.hosts[i].instances.contains_key(instance) &&
+                u.hosts[i].instances[instance].decide_value.is_some() ==>
+                u.value_was_proposed(u.hosts[i].instances[instance].decide_value.unwrap())
+    }
+
+    // A request `(client_id, seq)` is decided in at most one instance, whatever operations clients
+    // attach to it, so no client request is executed twice by the replicated log.
+    pub open spec fn requests_are_decided_at_most_once(c: &Constants, u: &Variables) -> bool {
+        &&& u.well_formed(c)
+        &&& forall |i: int, k1: nat, j: int, k2: nat| #![auto]
+                0 <= i < u.hosts.len() &&
+                0 <= j < u.hosts.len() &&
+                u.hosts[i].instances.contains_key(k1) &&
+                u.hosts[j].instances.contains_key(k2) &&
+                u.hosts[i].instances[k1].decide_value.is_some() &&
+                u.hosts[j].instances[k2].decide_value.is_some() &&
+                u.hosts[i].instances[k1].decide_value.unwrap().request() == u.hosts[j].instances[k2].decide_value.unwrap().request() ==>
+                k1 == k2
+    }
+
     impl Variables {
         pub open spec fn all_maps_and_sets_are_finite(&self, c: &Constants) -> bool {
             &&& forall |i: int| #![auto] 0 <= i < self.hosts.len() ==> self.hosts[i].instances.dom().finite()
             &&& forall |i: int, instance: nat| #![auto]
                     0 <= i < self.hosts.len() &&
                     self.hosts[i].instances.contains_key(instance) ==>
-                    self.hosts[i].instances[instance].promised.dom().finite() &&
-                    self.hosts[i].instances[instance].proposed_value.dom().finite() &&
-                    self.hosts[i].instances[instance].accepted.dom().finite()
+                    self.history[i][instance].promised.dom().finite() &&
+                    self.history[i][instance].proposed_value.dom().finite() &&
+                    self.history[i][instance].accepted.dom().finite()
             &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
                     0 <= i < self.hosts.len() &&
                     self.hosts[i].instances.contains_key(instance) &&
-                    self.hosts[i].instances[instance].promised.contains_key(ballot) ==>
-                    self.hosts[i].instances[instance].promised[ballot].dom().finite()
+                    self.history[i][instance].promised.contains_key(ballot) ==>
+                    self.history[i][instance].promised[ballot].dom().finite()
             &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
                     0 <= i < self.hosts.len() &&
                     self.hosts[i].instances.contains_key(instance) &&
-                    self.hosts[i].instances[instance].accepted.contains_key(ballot) ==>
-                    self.hosts[i].instances[instance].accepted[ballot].finite()
+                    self.history[i][instance].accepted.contains_key(ballot) ==>
+                    self.history[i][instance].accepted[ballot].finite()
+            &&& forall |i: int, instance: nat, ballot: host::Ballot, value: Value| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.hosts[i].instances[instance].volatile.learned.contains_key((ballot, value)) ==>
+                    self.hosts[i].instances[instance].volatile.learned[(ballot, value)].finite()
         }
 
         pub open spec fn all_map_keys_and_set_values_are_valid(&self, c: &Constants) -> bool {
             &&& forall |i: int, instance: nat, ballot: host::Ballot, sender: nat| #![auto]
                     0 <= i < self.hosts.len() &&
                     self.hosts[i].instances.contains_key(instance) &&
-                    self.hosts[i].instances[instance].promised.contains_key(ballot) &&
-                    self.hosts[i].instances[instance].promised[ballot].contains_key(sender) ==>
+                    self.history[i][instance].promised.contains_key(ballot) &&
+                    self.history[i][instance].promised[ballot].contains_key(sender) ==>
                     0 <= sender < c.num_hosts
             &&& forall |i: int, instance: nat, ballot: host::Ballot, sender: nat| #![auto]
                     0 <= i < self.hosts.len() &&
                     self.hosts[i].instances.contains_key(instance) &&
-                    self.hosts[i].instances[instance].accepted.contains_key(ballot) &&
-                    self.hosts[i].instances[instance].accepted[ballot].contains(sender) ==>
+                    self.history[i][instance].accepted.contains_key(ballot) &&
+                    self.history[i][instance].accepted[ballot].contains(sender) ==>
+                    0 <= sender < c.num_hosts
+            &&& forall |i: int, instance: nat, ballot: host::Ballot, value: Value, sender: nat| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.hosts[i].instances[instance].volatile.learned.contains_key((ballot, value)) &&
+                    self.hosts[i].instances[instance].volatile.learned[(ballot, value)].contains(sender) ==>
                     0 <= sender < c.num_hosts
         }
 
@@ -140,68 +347,78 @@ verus! {
             &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
                     0 <= i < self.hosts.len() &&
                     self.hosts[i].instances.contains_key(instance) &&
-                    self.hosts[i].instances[instance].promised.contains_key(ballot) ==>
-                    0 <= self.hosts[i].instances[instance].promised[ballot].len() <= c.num_hosts
+                    self.history[i][instance].promised.contains_key(ballot) ==>
+                    0 <= self.history[i][instance].promised[ballot].len() <= c.num_hosts
             &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
                     0 <= i < self.hosts.len() &&
                     self.hosts[i].instances.contains_key(instance) &&
-                    self.hosts[i].instances[instance].accepted.contains_key(ballot) ==>
-                    0 <= self.hosts[i].instances[instance].accepted[ballot].len() <= c.num_hosts
+                    self.history[i][instance].accepted.contains_key(ballot) ==>
+                    0 <= self.history[i][instance].accepted[ballot].len() <= c.num_hosts
         }
 
         pub open spec fn all_ballot_pids_in_all_maps_correspond_to_respective_host_id(&self, c: &Constants) -> bool {
             &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
                     0 <= i < self.hosts.len() &&
                     self.hosts[i].instances.contains_key(instance) &&
-                    self.hosts[i].instances[instance].promised.contains_key(ballot) ==>
+                    self.history[i][instance].promised.contains_key(ballot) ==>
                     ballot.pid == c.hosts[i].id
             &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
                     0 <= i < self.hosts.len() &&
                     self.hosts[i].instances.contains_key(instance) &&
-                    self.hosts[i].instances[instance].proposed_value.contains_key(ballot) ==>
+                    self.history[i][instance].proposed_value.contains_key(ballot) ==>
                     ballot.pid == c.hosts[i].id
             &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
                     0 <= i < self.hosts.len() &&
                     self.hosts[i].instances.contains_key(instance) &&
-                    self.hosts[i].instances[instance].accepted.contains_key(ballot) ==>
+                    self.history[i][instance].accepted.contains_key(ballot) ==>
                     ballot.pid == c.hosts[i].id
         }
 
         pub proof fn all_map_and_set_sizes_are_bounded_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
+            self.all_maps_and_sets_are_finite(c),
+            self.all_map_keys_and_set_values_are_valid(c),
             self.all_map_and_set_sizes_are_bounded(c),
+            self.all_ballot_pids_in_all_maps_correspond_to_respective_host_id(c),
         {
             assert(u.all_maps_and_sets_are_finite(c));
-            assert(self.network.in_flight_messages.finite());
+            assert(self.network.sent_messages.finite());
 
-            let Transition::HostStep { host_id, instance, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
+            let (host_id, instance, net_op) = choose |host_id: int, instance: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance, net_op }, event);
             let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, instance, net_op);
+
+            // The only new ballot is the stepping host's own: a fresh one from `send_prepare`, or the
+            // PrepareFrom it leads (`floors_and_leads_were_prepared_from`).
+            assert(self.all_maps_and_sets_are_finite(c));
+            assert(self.all_map_keys_and_set_values_are_valid(c));
+            assert(self.all_ballot_pids_in_all_maps_correspond_to_respective_host_id(c));
 
             assert forall |i: int, key: nat, ballot: host::Ballot| #![auto]
                     0 <= i < self.hosts.len() &&
                     self.hosts[i].instances.contains_key(key) &&
-                    self.hosts[i].instances[key].promised.contains_key(ballot) implies
-                    0 <= self.hosts[i].instances[key].promised[ballot].len() <= c.num_hosts
+                    self.history[i][key].promised.contains_key(ballot) implies
+                    0 <= self.history[i][key].promised[ballot].len() <= c.num_hosts
             by {
-                assert(forall |sender: nat| #[trigger] self.hosts[i].instances[key].promised[ballot].contains_key(sender) ==> 0 <= sender < c.num_hosts);
+                assert(forall |sender: nat| #[trigger] self.history[i][key].promised[ballot].contains_key(sender) ==> 0 <= sender < c.num_hosts);
                 let full_set = Set::new(|x: nat| 0 <= x < c.num_hosts);
                 assert(full_set.finite() && full_set.len() == c.num_hosts) by { full_set_size(full_set, c.num_hosts); };
-                assert(self.hosts[i].instances[key].promised[ballot].len() <= c.num_hosts) by { lemma_len_subset(self.hosts[i].instances[key].promised[ballot].dom(), full_set); };
+                assert(self.history[i][key].promised[ballot].len() <= c.num_hosts) by { lemma_len_subset(self.history[i][key].promised[ballot].dom(), full_set); };
             };
 
             assert forall |i: int, key: nat, ballot: host::Ballot| #![auto]
                     0 <= i < self.hosts.len() &&
                     self.hosts[i].instances.contains_key(key) &&
-                    self.hosts[i].instances[key].accepted.contains_key(ballot) implies
-                    0 <= self.hosts[i].instances[key].accepted[ballot].len() <= c.num_hosts
+                    self.history[i][key].accepted.contains_key(ballot) implies
+                    0 <= self.history[i][key].accepted[ballot].len() <= c.num_hosts
             by {
-                assert(forall |sender: nat| #[trigger] self.hosts[i].instances[key].accepted[ballot].contains(sender) ==> 0 <= sender < c.num_hosts);
+                assert(forall |sender: nat| #[trigger] self.history[i][key].accepted[ballot].contains(sender) ==> 0 <= sender < c.num_hosts);
                 let full_set = Set::new(|x: nat| 0 <= x < c.num_hosts);
                 assert(full_set.finite() && full_set.len() == c.num_hosts) by { full_set_size(full_set, c.num_hosts); };
-                assert(self.hosts[i].instances[key].accepted[ballot].len() <= c.num_hosts) by { lemma_len_subset(self.hosts[i].instances[key].accepted[ballot], full_set); };
+                assert(self.history[i][key].accepted[ballot].len() <= c.num_hosts) by { lemma_len_subset(self.history[i][key].accepted[ballot], full_set); };
             };
         }
     }
@@ -213,665 +430,2902 @@ verus! {
         &&& u.all_ballot_pids_in_all_maps_correspond_to_respective_host_id(c)
     }
 
-    impl Variables {
-        pub open spec fn prepare_msg_in_network_implies_sender_map_has_ballot_key(&self, c: &Constants) -> bool {
-            forall |key: nat, ballot: host::Ballot| #![auto]
-                self.network.in_flight_messages.contains(Message::Prepare { key, ballot }) ==>
-                {
-                    let leader = ballot.pid as int;
-
-                    &&& 0 <= leader < self.hosts.len()
-                    &&& self.hosts[leader].instances.contains_key(key)
-                    &&& self.hosts[leader].instances[key].promised.contains_key(ballot)
-                    &&& self.hosts[leader].instances[key].accepted.contains_key(ballot)
-                }
-        }
-
-        pub open spec fn promise_msg_in_network_implies_sender_has_promised(&self, c: &Constants) -> bool {
-            forall |key: nat, sender: nat, ballot: host::Ballot, accepted: Option<(host::Ballot, Value)>| #![auto]
-                self.network.in_flight_messages.contains(Message::Promise { key, sender, ballot, accepted }) ==>
-                self.hosts[sender as int].instances.contains_key(key) &&
-                self.hosts[sender as int].instances[key].current_ballot.cmp(&ballot) >= 0
-        }
-
-        pub open spec fn accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender(&self, c: &Constants) -> bool {
-            forall |key: nat, ballot: host::Ballot, value: Value| #![auto]
-                self.network.in_flight_messages.contains(Message::Accept { key, ballot, value }) ==>
-                {
-                    let leader = ballot.pid as int;
-
-                    &&& 0 <= leader < self.hosts.len()
-                    &&& self.hosts[leader].instances.contains_key(key)
-                    &&& self.hosts[leader].instances[key].promised.contains_key(ballot)
-                    &&& self.hosts[leader].instances[key].promised[ballot].len() > c.num_failures
-                    &&& self.hosts[leader].instances[key].proposed_value.contains_key(ballot)
-                    &&& self.hosts[leader].instances[key].proposed_value[ballot] == value
-                }
-        }
-
-        pub open spec fn accepted_msg_in_network_implies_sender_has_accepted_some_value(&self, c: &Constants) -> bool {
-            forall |key: nat, sender: nat, ballot: host::Ballot| #![auto]
-                self.network.in_flight_messages.contains(Message::Accepted { key, sender, ballot }) ==>
-                self.hosts[sender as int].instances.contains_key(key) &&
-                self.hosts[sender as int].instances[key].current_ballot.cmp(&ballot) >= 0 &&
-                self.hosts[sender as int].instances[key].accept_ballot.is_some() &&
-                self.hosts[sender as int].instances[key].accept_ballot.unwrap().cmp(&ballot) >= 0 &&
-                self.hosts[sender as int].instances[key].accept_value.is_some()
-        }
-
-        pub open spec fn decide_msg_in_network_implies_quorum_has_accepted_some_value(&self, c: &Constants) -> bool {
-            forall |key: nat, ballot: host::Ballot, value: Value|
-                #[trigger] self.network.in_flight_messages.contains(Message::Decide { key, ballot, value }) ==>
-                {
-                    let leader = ballot.pid as int;
-
-                    &&& 0 <= leader < self.hosts.len()
-                    &&& self.hosts[leader].instances.contains_key(key)
-                    &&& self.hosts[leader].instances[key].accepted.contains_key(ballot)
-                    &&& self.hosts[leader].instances[key].accepted[ballot].len() > c.num_failures
-                    &&& self.hosts[leader].instances[key].proposed_value.contains_key(ballot)
-                    &&& value == self.hosts[leader].instances[key].proposed_value[ballot]
-                }
+    // How one step of a host moves its history of the instance it steps: `send_prepare` and
+    // `join_prepare_from` add a fresh ballot, `promised` and `accepted` count one more sender for a
+    // ballot already there, `send_accept` proposes under a ballot that has no proposal yet, and every
+    // other step, `crash` and `recover` included, leaves it alone.
+    pub open spec fn history_step(lc: &host::Constants, lu: &host::Variables, lv: &host::Variables, key: nat, net_op: NetworkOperation, old: host::Volatile, new: host::Volatile) -> bool {
+        if (host::send_prepare(lc, lu, lv, key, net_op) || host::join_prepare_from(lc, lu, lv, key, net_op)) {
+            let ballot = if (host::send_prepare(lc, lu, lv, key, net_op)) { host::next_ballot(lc, lu.instances[key]) } else { lu.multi.leading.unwrap().1 };
+
+            &&& !old.promised.contains_key(ballot)
+            &&& !old.proposed_value.contains_key(ballot)
+            &&& !old.accepted.contains_key(ballot)
+            &&& new.promised == old.promised.insert(ballot, Map::empty())
+            &&& new.proposed_value == old.proposed_value
+            &&& new.accepted == old.accepted.insert(ballot, Set::empty())
+        } else if (host::promised(lc, lu, lv, key, net_op)) {
+            if let Some(Message::Promise { sender, ballot, accepted, .. }) = net_op.recv {
+                &&& old.promised.contains_key(ballot)
+                &&& !old.proposed_value.contains_key(ballot)
+                &&& new.promised == old.promised.insert(ballot, old.promised[ballot].insert(sender, accepted))
+                &&& new.proposed_value == old.proposed_value
+                &&& new.accepted == old.accepted
+            } else {
+                false
+            }
+        } else if (host::send_accept(lc, lu, lv, key, net_op)) {
+            let ballot = lu.instances[key].current_ballot;
+
+            &&& old.promised.contains_key(ballot)
+            &&& old.promised[ballot] == lu.instances[key].volatile.promised[ballot]
+            &&& !old.proposed_value.contains_key(ballot)
+            &&& new.promised == old.promised
+            &&& new.proposed_value == old.proposed_value.insert(ballot, lv.instances[key].volatile.proposed_value[ballot])
+            &&& new.accepted == old.accepted
+        } else if (host::accepted(lc, lu, lv, key, net_op)) {
+            if let Some(Message::Accepted { sender, ballot, .. }) = net_op.recv {
+                &&& old.accepted.contains_key(ballot)
+                &&& new.promised == old.promised
+                &&& new.proposed_value == old.proposed_value
+                &&& new.accepted == old.accepted.insert(ballot, old.accepted[ballot].insert(sender))
+            } else {
+                false
+            }
+        } else {
+            &&& new.promised == old.promised
+            &&& new.proposed_value == old.proposed_value
+            &&& new.accepted == old.accepted
         }
     }
 
-    pub open spec fn messages_in_network_implies_first_degree_properties(c: &Constants, u: &Variables) -> bool {
-        &&& u.prepare_msg_in_network_implies_sender_map_has_ballot_key(c)
-        &&& u.promise_msg_in_network_implies_sender_has_promised(c)
-        &&& u.accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender(c)
-        &&& u.accepted_msg_in_network_implies_sender_has_accepted_some_value(c)
-        &&& u.decide_msg_in_network_implies_quorum_has_accepted_some_value(c)
-    }
-
     impl Variables {
-        pub open spec fn promised_state_implies_network_has_prepare_msg(&self, c: &Constants) -> bool {
-            forall |i: int, instance: nat|
-                0 <= i < self.hosts.len() &&
-                self.hosts[i].instances.contains_key(instance) &&
-                self.hosts[i].instances[instance].current_ballot.num > 0 ==>
-                #[trigger] self.network.in_flight_messages.contains(Message::Prepare { key: instance, ballot: self.hosts[i].instances[instance].current_ballot })
+        // What a host still holds in memory for a ballot is exactly what `history` has for it. Its
+        // volatile maps only hold ballots it prepared since it last recovered, and a ballot it prepares
+        // is one it has never led before, so the two cannot have drifted apart.
+        pub open spec fn volatile_state_is_recorded_in_history(&self, c: &Constants) -> bool {
+            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.hosts[i].instances[instance].volatile.promised.contains_key(ballot) ==>
+                    self.history[i][instance].promised.contains_key(ballot) &&
+                    self.history[i][instance].promised[ballot] == self.hosts[i].instances[instance].volatile.promised[ballot] &&
+                    (self.history[i][instance].proposed_value.contains_key(ballot) <==> self.hosts[i].instances[instance].volatile.proposed_value.contains_key(ballot)) &&
+                    (self.history[i][instance].accepted.contains_key(ballot) <==> self.hosts[i].instances[instance].volatile.accepted.contains_key(ballot))
+            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.hosts[i].instances[instance].volatile.proposed_value.contains_key(ballot) ==>
+                    self.hosts[i].instances[instance].volatile.promised.contains_key(ballot) &&
+                    self.history[i][instance].proposed_value[ballot] == self.hosts[i].instances[instance].volatile.proposed_value[ballot]
+            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.hosts[i].instances[instance].volatile.accepted.contains_key(ballot) ==>
+                    self.hosts[i].instances[instance].volatile.promised.contains_key(ballot) &&
+                    self.history[i][instance].accepted[ballot] == self.hosts[i].instances[instance].volatile.accepted[ballot]
         }
 
-        pub open spec fn someone_promised_implies_network_has_their_promise_msg(&self, c: &Constants) -> bool {
-            forall |i: int, instance: nat, ballot: host::Ballot, sender: nat|
+        // The ballot of the PrepareFrom a host is leading is above everything it had led in the covered
+        // instances when it sent it, and `leading` does not survive a crash, so the ballot is only in the
+        // history of instances that have joined it since, which still hold it in memory.
+        pub open spec fn leading_ballot_is_only_in_live_instances(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
                 0 <= i < self.hosts.len() &&
+                self.hosts[i].multi.leading.is_some() &&
+                self.hosts[i].multi.leading.unwrap().0 <= instance &&
+                self.hosts[i].multi.leading.unwrap().1 == ballot &&
                 self.hosts[i].instances.contains_key(instance) &&
-                self.hosts[i].instances[instance].promised.dom().contains(ballot) &&
-                self.hosts[i].instances[instance].promised[ballot].dom().contains(sender) ==>
-                #[trigger] self.network.in_flight_messages.contains(Message::Promise { key: instance, sender, ballot, accepted: self.hosts[i].instances[instance].promised[ballot][sender] })
-        }
-
-        pub open spec fn either_of_accept_ballot_or_value_is_some(&self, i: int, instance: nat) -> bool {
-            ||| self.hosts[i].instances[instance].accept_ballot.is_some()
-            ||| self.hosts[i].instances[instance].accept_value.is_some()
+                (self.history[i][instance].promised.contains_key(ballot) ||
+                 self.history[i][instance].proposed_value.contains_key(ballot) ||
+                 self.history[i][instance].accepted.contains_key(ballot)) ==>
+                self.hosts[i].instances[instance].volatile.promised.contains_key(ballot)
         }
 
-        pub open spec fn accept_ballot_some_eq_accept_value_some(&self, i: int, instance: nat) -> bool {
-            self.hosts[i].instances[instance].accept_ballot.is_some() == self.hosts[i].instances[instance].accept_value.is_some()
-        }
-
-        pub open spec fn if_accept_ballot_is_some_then_accept_value_is_some(&self, c: &Constants) -> bool {
-            forall |i: int, instance: nat|
-                0 <= i < self.hosts.len() &&
-                self.hosts[i].instances.contains_key(instance) ==>
-                #[trigger] self.accept_ballot_some_eq_accept_value_some(i, instance)
-        }
+        // How one host step moves `history`: only the stepping host's entry for the stepped instance
+        // changes, and only by the ballot, promise, proposal or Accepted the step records. In particular
+        // `crash` and `recover` leave it alone.
+        pub proof fn history_follows_host_step(&self, c: &Constants, u: &Variables, event: Event, host_id: int, key: nat, net_op: NetworkOperation)
+        requires
+            inductive(c, u),
+            is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: key, net_op }, event),
+        ensures
+            forall |i: int| #![auto] 0 <= i < self.hosts.len() && i != host_id ==> self.history[i] == u.history[i],
+            forall |k: nat| #![auto]
+                u.hosts[host_id].instances.contains_key(k) && k != key ==>
+                self.history[host_id][k].promised == u.history[host_id][k].promised &&
+                self.history[host_id][k].proposed_value == u.history[host_id][k].proposed_value &&
+                self.history[host_id][k].accepted == u.history[host_id][k].accepted,
+            !u.hosts[host_id].instances.contains_key(key) && self.hosts[host_id].instances.contains_key(key) ==>
+                self.history[host_id][key].promised == Map::<host::Ballot, Map<nat, Option<(host::Ballot, Value)>>>::empty() &&
+                self.history[host_id][key].proposed_value == Map::<host::Ballot, Value>::empty() &&
+                self.history[host_id][key].accepted == Map::<host::Ballot, Set<nat>>::empty(),
+            u.hosts[host_id].instances.contains_key(key) ==>
+                history_step(&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id], key, net_op, u.history[host_id][key], self.history[host_id][key]),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            let (history, new_history) = (u.history[host_id], self.history[host_id]);
+            assert(new_history == record(history, *lv));
+
+            // Every step but `recover` leaves the volatile part of each instance it does not step as it was,
+            // and `recover` empties it; either way `merge` gives back the recorded history.
+            assert forall |k: nat| #![auto]
+                lu.instances.contains_key(k) && k != key implies
+                new_history[k].promised == history[k].promised &&
+                new_history[k].proposed_value == history[k].proposed_value &&
+                new_history[k].accepted == history[k].accepted
+            by {
+                assert(lv.instances.contains_key(k));
+                let (volatile, new_volatile) = (lu.instances[k].volatile, lv.instances[k].volatile);
+                assert(new_volatile == volatile || new_volatile == host::Volatile::empty());
+                assert(new_history[k].promised =~= history[k].promised);
+                assert(new_history[k].proposed_value =~= history[k].proposed_value);
+                assert(new_history[k].accepted =~= history[k].accepted);
+            };
 
-        pub open spec fn accepted_state_implies_network_has_accept_message(&self, c: &Constants) -> bool {
-            forall |i: int, instance: nat|
-                0 <= i < self.hosts.len() &&
-                self.hosts[i].instances.contains_key(instance) &&
-                #[trigger] self.either_of_accept_ballot_or_value_is_some(i, instance) ==>
-                self.hosts[i].instances[instance].accept_ballot.is_some() &&
-                self.hosts[i].instances[instance].accept_value.is_some() &&
-                self.network.in_flight_messages.contains(Message::Accept { key: instance, ballot: self.hosts[i].instances[instance].accept_ballot.unwrap(), value: self.hosts[i].instances[instance].accept_value.unwrap() })
-        }
+            if (!lu.instances.contains_key(key) && lv.instances.contains_key(key)) {
+                assert(host::init_request(lc, lu, lv, key, net_op));
+                assert(!history.contains_key(key));
+                assert(new_history[key].promised =~= Map::empty());
+                assert(new_history[key].proposed_value =~= Map::empty());
+                assert(new_history[key].accepted =~= Map::empty());
+            }
 
-        pub open spec fn accepted_state_implies_network_has_accepted_message(&self, c: &Constants) -> bool {
-            forall |sender: nat, instance: nat, ballot: host::Ballot| #![auto]
-                0 <= sender < self.hosts.len() &&
-                self.hosts[sender as int].instances.contains_key(instance) &&
-                self.hosts[sender as int].instances[instance].accept_ballot == Some(ballot) ==>
-                self.network.in_flight_messages.contains(Message::Accepted { key: instance, sender , ballot })
-        }
+            if (lu.instances.contains_key(key)) {
+                let (old, new) = (history[key], new_history[key]);
+                let (volatile, new_volatile) = (lu.instances[key].volatile, lv.instances[key].volatile);
+                assert(new == merge(old, new_volatile));
 
-        pub open spec fn someone_accepted_implies_network_has_their_accepted_msg(&self, c: &Constants) -> bool {
-            forall |i: int, instance: nat, ballot: host::Ballot, sender: nat| #![auto]
-                0 <= i < self.hosts.len() &&
-                self.hosts[i].instances.contains_key(instance) &&
-                self.hosts[i].instances[instance].accepted.contains_key(ballot) &&
-                self.hosts[i].instances[instance].accepted[ballot].contains(sender) ==>
-                self.network.in_flight_messages.contains(Message::Accepted { key: instance, sender, ballot })
+                if (host::send_prepare(lc, lu, lv, key, net_op) || host::join_prepare_from(lc, lu, lv, key, net_op)) {
+                    let ballot = if (host::send_prepare(lc, lu, lv, key, net_op)) { host::next_ballot(lc, lu.instances[key]) } else { lu.multi.leading.unwrap().1 };
+                    if (host::send_prepare(lc, lu, lv, key, net_op)) {
+                        u.next_ballot_is_fresh(c, host_id, key);
+                    } else {
+                        assert(!volatile.promised.contains_key(ballot));
+                        assert(lu.multi.leading.unwrap().0 <= key);
+                    }
+                    assert(new.promised =~= old.promised.insert(ballot, Map::empty()));
+                    assert(new.proposed_value =~= old.proposed_value);
+                    assert(new.accepted =~= old.accepted.insert(ballot, Set::empty()));
+                } else if (host::promised(lc, lu, lv, key, net_op)) {
+                    if let Some(Message::Promise { sender, ballot, accepted, .. }) = net_op.recv {
+                        assert(volatile.promised.contains_key(ballot));
+                        assert(new.promised =~= old.promised.insert(ballot, old.promised[ballot].insert(sender, accepted)));
+                        assert(new.proposed_value =~= old.proposed_value);
+                        assert(new.accepted =~= old.accepted);
+                    }
+                } else if (host::send_accept(lc, lu, lv, key, net_op)) {
+                    let ballot = lu.instances[key].current_ballot;
+                    assert(volatile.promised.contains_key(ballot));
+                    assert(new.promised =~= old.promised);
+                    assert(new.proposed_value =~= old.proposed_value.insert(ballot, new_volatile.proposed_value[ballot]));
+                    assert(new.accepted =~= old.accepted);
+                } else if (host::accepted(lc, lu, lv, key, net_op)) {
+                    if let Some(Message::Accepted { sender, ballot, .. }) = net_op.recv {
+                        assert(volatile.accepted.contains_key(ballot));
+                        assert(new.promised =~= old.promised);
+                        assert(new.proposed_value =~= old.proposed_value);
+                        assert(new.accepted =~= old.accepted.insert(ballot, old.accepted[ballot].insert(sender)));
+                    }
+                } else {
+                    assert(new_volatile.promised == volatile.promised || new_volatile == host::Volatile::empty());
+                    assert(new_volatile.proposed_value == volatile.proposed_value || new_volatile == host::Volatile::empty());
+                    assert(new_volatile.accepted == volatile.accepted || new_volatile == host::Volatile::empty());
+                    assert(new.promised =~= old.promised);
+                    assert(new.proposed_value =~= old.proposed_value);
+                    assert(new.accepted =~= old.accepted);
+                }
+            }
         }
 
-        pub open spec fn decided_state_implies_network_has_decide_message(&self, c: &Constants) -> bool {
-            forall |i: int, instance: nat|
+        // `record` merges a host's whole memory into its history after every step, so whatever it has
+        // learned is there too.
+        pub open spec fn learned_is_recorded_in_history(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat| #![auto]
                 0 <= i < self.hosts.len() &&
-                self.hosts[i].instances.contains_key(instance) &&
-                #[trigger] self.hosts[i].instances[instance].decide_value.is_some() ==>
-                exists |ballot: host::Ballot| #[trigger] self.network.in_flight_messages.contains(Message::Decide { key: instance, ballot, value: self.hosts[i].instances[instance].decide_value.unwrap() })
+                self.hosts[i].instances.contains_key(instance) ==>
+                self.hosts[i].instances[instance].volatile.learned.submap_of(self.history[i][instance].learned)
         }
 
-        pub proof fn if_accept_ballot_is_some_then_accept_value_is_some_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        pub proof fn volatile_state_is_recorded_in_history_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
-            self.if_accept_ballot_is_some_then_accept_value_is_some(c)
+            self.volatile_state_is_recorded_in_history(c),
         {
-            self.all_maps_and_sets_are_finite(c);
-
-            let Transition::HostStep { host_id, instance: key, net_op } = choose |transition: Transition| #[trigger] is_valid_transition(c, u, self, transition, event);
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
             let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
 
-            assert forall |i: int, instance: nat|
+            // A ballot the step adds to `promised` is fresh in `history`, so its other maps are empty
+            // there as well; every other ballot kept in memory was already recorded, and only grows in
+            // step with its record.
+            assert forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
                 0 <= i < self.hosts.len() &&
-                self.hosts[i].instances.contains_key(instance) implies
-                #[trigger] self.accept_ballot_some_eq_accept_value_some(i, instance)
+                self.hosts[i].instances.contains_key(instance) &&
+                self.hosts[i].instances[instance].volatile.promised.contains_key(ballot) implies
+                self.history[i][instance].promised.contains_key(ballot) &&
+                self.history[i][instance].promised[ballot] == self.hosts[i].instances[instance].volatile.promised[ballot] &&
+                (self.history[i][instance].proposed_value.contains_key(ballot) <==> self.hosts[i].instances[instance].volatile.proposed_value.contains_key(ballot)) &&
+                (self.history[i][instance].accepted.contains_key(ballot) <==> self.hosts[i].instances[instance].volatile.accepted.contains_key(ballot))
             by {
-                match (event) {
-                    Event::NoOp if ((host::init_request(lc, lu, lv, key, net_op) || host::accept(lc, lu, lv, key, net_op)) && (i == host_id) && (key == instance)) => {
-                        assert(self.accept_ballot_some_eq_accept_value_some(i, instance));
-                    },
-                    _ => { assert(u.accept_ballot_some_eq_accept_value_some(i, instance)); },
+                if (i == host_id && instance == step_key && u.hosts[i].instances.contains_key(instance) && !u.hosts[i].instances[instance].volatile.promised.contains_key(ballot)) {
+                    assert(host::send_prepare(lc, lu, lv, step_key, net_op) || host::join_prepare_from(lc, lu, lv, step_key, net_op));
                 }
             };
         }
 
-        pub proof fn accepted_state_implies_network_has_accept_message_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        pub proof fn leading_ballot_is_only_in_live_instances_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
-            self.accepted_state_implies_network_has_accept_message(c),
+            self.leading_ballot_is_only_in_live_instances(c),
         {
-            assert(self.all_maps_and_sets_are_finite(c));
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
 
-            assert forall |i: int, instance: nat|
+            assert forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
                 0 <= i < self.hosts.len() &&
+                self.hosts[i].multi.leading.is_some() &&
+                self.hosts[i].multi.leading.unwrap().0 <= instance &&
+                self.hosts[i].multi.leading.unwrap().1 == ballot &&
                 self.hosts[i].instances.contains_key(instance) &&
-                #[trigger] self.either_of_accept_ballot_or_value_is_some(i, instance) implies
-                self.hosts[i].instances[instance].accept_ballot.is_some() &&
-                self.hosts[i].instances[instance].accept_value.is_some() &&
-                self.network.in_flight_messages.contains(Message::Accept { key: instance, ballot: self.hosts[i].instances[instance].accept_ballot.unwrap(), value: self.hosts[i].instances[instance].accept_value.unwrap() })
+                (self.history[i][instance].promised.contains_key(ballot) ||
+                 self.history[i][instance].proposed_value.contains_key(ballot) ||
+                 self.history[i][instance].accepted.contains_key(ballot)) implies
+                self.hosts[i].instances[instance].volatile.promised.contains_key(ballot)
             by {
-                if (self.hosts[i].instances[instance].accept_ballot == u.hosts[i].instances[instance].accept_ballot) { assert(u.either_of_accept_ballot_or_value_is_some(i, instance)); }
+                if (i == host_id && host::send_prepare_from(lc, lu, lv, step_key, net_op)) {
+                    // The new ballot is above `max_seen_ballot`, and so above every ballot of this host's
+                    // history, in every instance it covers.
+                    assert(ballot.cmp(&lu.instances[instance].max_seen_ballot) > 0);
+                    assert(u.history[i][instance].promised.contains_key(ballot) ==> ballot.cmp(&lu.instances[instance].max_seen_ballot) <= 0);
+                    assert(u.history[i][instance].proposed_value.contains_key(ballot) ==> ballot.cmp(&lu.instances[instance].max_seen_ballot) <= 0);
+                    assert(u.history[i][instance].accepted.contains_key(ballot) ==> ballot.cmp(&lu.instances[instance].max_seen_ballot) <= 0);
+                } else if (i == host_id && instance == step_key && !u.hosts[i].instances.contains_key(instance)) {
+                    assert(host::init_request(lc, lu, lv, step_key, net_op));
+                } else if (i == host_id) {
+                    // `leading` is kept, so neither `crash` nor `recover` ran, and the volatile maps only grew.
+                    assert(lv.multi.leading == lu.multi.leading);
+                    assert(u.hosts[i].instances[instance].volatile.promised.contains_key(ballot) ||
+                           host::send_prepare(lc, lu, lv, step_key, net_op) ||
+                           host::join_prepare_from(lc, lu, lv, step_key, net_op));
+                }
             };
         }
+    }
 
-        pub proof fn decided_state_implies_network_has_decide_message_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        pub proof fn learned_is_recorded_in_history_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
-            self.decided_state_implies_network_has_decide_message(c),
+            self.learned_is_recorded_in_history(c),
         {
-            assert(self.all_maps_and_sets_are_finite(c));
-
-            let Transition::HostStep { host_id, instance: step_key, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
-            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
 
             assert forall |i: int, instance: nat| #![auto]
                 0 <= i < self.hosts.len() &&
-                self.hosts[i].instances.contains_key(instance) &&
-                self.hosts[i].instances[instance].decide_value.is_some() implies
-                exists |ballot: host::Ballot| #![auto] self.network.in_flight_messages.contains(Message::Decide { key: instance, ballot, value: self.hosts[i].instances[instance].decide_value.unwrap() })
+                self.hosts[i].instances.contains_key(instance) implies
+                self.hosts[i].instances[instance].volatile.learned.submap_of(self.history[i][instance].learned)
             by {
-                match ((event, net_op.recv)) {
-                    (Event::Decide { key: decide_key, value }, Some(Message::Decide { key: instance, ballot: recv_bal, value: recv_val }))
-                    if (i == host_id) => {
-                        assert(decide_key == step_key);
-                        assert(host::decide(lc, lu, lv, step_key, net_op, value));
-                        assert(step_key == instance);
-                        assert(recv_val == self.hosts[i].instances[instance].decide_value.unwrap());
-                        assert(self.network.in_flight_messages.contains(Message::Decide { key: instance, ballot: recv_bal, value: recv_val }));
-                        assert(exists |ballot: host::Ballot| #![auto] self.network.in_flight_messages.contains(Message::Decide { key: instance, ballot, value: self.hosts[i].instances[instance].decide_value.unwrap() }));
-                    },
-                    _ => { }
+                if (i == host_id) {
+                    assert(self.history[i][instance].learned == merge(
+                        if u.history[i].contains_key(instance) { u.history[i][instance] } else { host::Volatile::empty() },
+                        self.hosts[i].instances[instance].volatile,
+                    ).learned);
+                } else {
+                    assert(self.history[i] == u.history[i]);
                 }
             };
         }
     }
 
-    pub open spec fn properties_imply_first_degree_messages_in_network(c: &Constants, u: &Variables) -> bool {
-        &&& u.promised_state_implies_network_has_prepare_msg(c)
-        &&& u.someone_promised_implies_network_has_their_promise_msg(c)
-        &&& u.if_accept_ballot_is_some_then_accept_value_is_some(c)
-        &&& u.accepted_state_implies_network_has_accept_message(c)
-        &&& u.accepted_state_implies_network_has_accepted_message(c)
-        &&& u.someone_accepted_implies_network_has_their_accepted_msg(c)
-        &&& u.decided_state_implies_network_has_decide_message(c)
+    pub open spec fn history_properties(c: &Constants, u: &Variables) -> bool {
+        &&& u.volatile_state_is_recorded_in_history(c)
+        &&& u.leading_ballot_is_only_in_live_instances(c)
+        &&& u.learned_is_recorded_in_history(c)
+    }
+
+    // A host whose memory is already all in its history records nothing new, as in a batch step that
+    // only re-sends what the host has.
+    pub proof fn record_of_recorded_host_is_identity(c: &Constants, u: &Variables, i: int)
+    requires
+        inductive(c, u),
+        0 <= i < u.hosts.len(),
+    ensures
+        record(u.history[i], u.hosts[i]) == u.history[i],
+    {
+        assert forall |key: nat| #[trigger] u.history[i].contains_key(key) implies record(u.history[i], u.hosts[i])[key] == u.history[i][key] by {
+            let (old, volatile) = (u.history[i][key], u.hosts[i].instances[key].volatile);
+            assert(old.promised.union_prefer_right(volatile.promised) =~= old.promised);
+            assert(old.proposed_value.union_prefer_right(volatile.proposed_value) =~= old.proposed_value);
+            assert(old.accepted.union_prefer_right(volatile.accepted) =~= old.accepted);
+            assert(old.learned.union_prefer_right(volatile.learned) =~= old.learned);
+        };
+        assert(record(u.history[i], u.hosts[i]) =~= u.history[i]);
+    }
+
+    pub proof fn record_of_grown_host_absorbs_earlier_record(history: Map<nat, host::Volatile>, earlier: host::Variables, later: host::Variables)
+    requires
+        batch::keeps_memory(earlier, later),
+    ensures
+        record(record(history, earlier), later) == record(history, later),
+    {
+        assert forall |key: nat| #[trigger] later.instances.contains_key(key) implies record(record(history, earlier), later)[key] == record(history, later)[key] by {
+            let old = if history.contains_key(key) { history[key] } else { host::Volatile::empty() };
+            let (a, b) = (earlier.instances[key].volatile, later.instances[key].volatile);
+            assert(old.promised.union_prefer_right(a.promised).union_prefer_right(b.promised) =~= old.promised.union_prefer_right(b.promised));
+            assert(old.proposed_value.union_prefer_right(a.proposed_value).union_prefer_right(b.proposed_value) =~= old.proposed_value.union_prefer_right(b.proposed_value));
+            assert(old.accepted.union_prefer_right(a.accepted).union_prefer_right(b.accepted) =~= old.accepted.union_prefer_right(b.accepted));
+            assert(old.learned.union_prefer_right(a.learned).union_prefer_right(b.learned) =~= old.learned.union_prefer_right(b.learned));
+        };
+        assert(record(record(history, earlier), later) =~= record(history, later));
     }
 
     impl Variables {
-        pub open spec fn network_msgs_have_valid_sender_and_ballot_pid(&self, c: &Constants) -> bool {
-            &&& forall |key: nat, ballot: host::Ballot| #![auto]
-                    self.network.in_flight_messages.contains(Message::Prepare { key, ballot }) ==>
-                    ballot.num > 0 && 0 <= ballot.pid < self.hosts.len()
-            &&& forall |key:nat, sender: nat, ballot: host::Ballot, accepted: Option<(host::Ballot, Value)>| #![auto]
-                    self.network.in_flight_messages.contains(Message::Promise { key, sender, ballot, accepted }) ==>
-                    ballot.num > 0 && 0 <= sender < self.hosts.len() && ballot.num > 0 && 0 <= ballot.pid < self.hosts.len()
-            &&& forall |key:nat, sender: nat, ballot: host::Ballot, accepted_ballot: host::Ballot, accepted_value: Value| #![auto]
-                    self.network.in_flight_messages.contains(Message::Promise { key, sender, ballot, accepted: Some((accepted_ballot, accepted_value)) }) ==>
-                    ballot.num > 0 && 0 <= accepted_ballot.pid < self.hosts.len()
-            &&& forall |key:nat, ballot: host::Ballot, value: Value| #![auto]
-                    self.network.in_flight_messages.contains(Message::Accept { key, ballot, value }) ==>
-                    ballot.num > 0 && 0 <= ballot.pid < self.hosts.len()
-            &&& forall |key:nat, sender: nat, ballot: host::Ballot| #![auto]
-                    self.network.in_flight_messages.contains(Message::Accepted { key, sender, ballot }) ==>
-                    ballot.num > 0 && 0 <= sender < self.hosts.len() && ballot.num > 0 && 0 <= ballot.pid < self.hosts.len()
-            &&& forall |key:nat, ballot: host::Ballot, value: Value| #![auto]
-                    ballot.num > 0 && self.network.in_flight_messages.contains(Message::Decide { key, ballot, value }) ==>
-                    0 <= ballot.pid < self.hosts.len()
+        pub open spec fn configuration(&self, key: nat) -> fast::FastQuorumSystem {
+            self.configurations[key]
         }
 
-        pub open spec fn promise_msgs_from_same_sender_for_same_ballot_have_same_accepted(&self, c: &Constants) -> bool {
-            forall |sender: nat, instance: nat, b1: host::Ballot, a1: Option<(host::Ballot, Value)>, b2: host::Ballot, a2: Option<(host::Ballot, Value)>| #![auto]
-                self.network.in_flight_messages.contains(Message::Promise { key: instance, sender, ballot: b1, accepted: a1 }) &&
-                self.network.in_flight_messages.contains(Message::Promise { key: instance, sender, ballot: b2, accepted: a2 }) &&
-                b1 == b2 ==>
-                a1 == a2
+        pub open spec fn configurations_are_valid(&self, c: &Constants) -> bool {
+            forall |key: nat| #[trigger] self.configurations.contains_key(key) ==>
+                reconfig::is_valid_configuration(self.configurations[key], c.num_hosts, c.num_failures)
         }
 
-        pub open spec fn ballot_in_accepted_is_smaller_than_promise_message_ballot(&self, c: &Constants) -> bool {
-            forall |sender: nat, instance: nat, promise_ballot: host::Ballot, accepted_ballot: host::Ballot, accepted_value: Value| #![auto]
-                self.network.in_flight_messages.contains(Message::Promise { key: instance, sender, ballot: promise_ballot, accepted: Some((accepted_ballot, accepted_value)) }) ==>
-                accepted_ballot.cmp(&promise_ballot) < 0
+        // `decisions` covers what the configuration of `key` depends on, and each of them is the value of
+        // some Decide message.
+        pub open spec fn decisions_were_sent(&self, c: &Constants, decisions: Map<nat, Value>, key: nat) -> bool {
+            &&& c.reconfig.knows_configuration(decisions, key)
+            &&& forall |j: nat| #![trigger decisions[j]]
+                    j + c.reconfig.alpha <= key ==>
+                    exists |ballot: host::Ballot| #[trigger] self.network.sent_messages.contains(Message::Decide { key: j, ballot, value: decisions[j] })
         }
 
-        pub open spec fn value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot(&self, c: &Constants) -> bool {
-            forall |sender: nat, instance: nat, ballot: host::Ballot, accepted_ballot: host::Ballot, accepted_value: Value| #![auto]
-                self.network.in_flight_messages.contains(Message::Promise { key: instance, sender, ballot, accepted: Some((accepted_ballot, accepted_value)) }) ==>
+        // The host that recorded a configuration computed it from decisions it had learned.
+        pub open spec fn configurations_were_decided(&self, c: &Constants) -> bool {
+            forall |key: nat| #[trigger] self.configurations.contains_key(key) ==>
+                exists |decisions: Map<nat, Value>|
+                    #[trigger] self.decisions_were_sent(c, decisions, key) &&
+                    self.configurations[key] == c.reconfig.configuration(c.fast_quorums(), c.num_failures, decisions, key)
+        }
+
+        pub open spec fn instances_use_recorded_configurations(&self, c: &Constants) -> bool {
+            forall |i: int, key: nat| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(key) ==>
+                self.configurations.contains_key(key) &&
+                self.hosts[i].instances[key].configuration == self.configurations[key]
+        }
+
+        // Decisions are never taken back, so a host keeps knowing the configuration of every instance
+        // it created.
+        pub open spec fn instances_know_their_configuration(&self, c: &Constants) -> bool {
+            forall |i: int, key: nat| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(key) ==>
+                c.hosts[i].knows_configuration(&self.hosts[i], key)
+        }
+
+        pub open spec fn message_keys_have_configurations(&self, c: &Constants) -> bool {
+            forall |message: Message| #![trigger self.network.sent_messages.contains(message)]
+                self.network.sent_messages.contains(message) &&
+                network::message_key(message).is_some() ==>
+                self.configurations.contains_key(network::message_key(message).unwrap())
+        }
+
+        // A recorded configuration is never replaced, so the invariants of `u` that count in it keep
+        // counting in the same quorums. Every instance a host holds is counted in its own
+        // configuration, which is the recorded one.
+        pub proof fn configurations_only_grow(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            u.configurations.submap_of(self.configurations),
+            forall |key: nat| #[trigger] u.configurations.contains_key(key) ==> self.configuration(key) == u.configuration(key),
+            configuration_properties(c, self),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            assert(self.configurations == record_configurations(u.configurations, self.hosts[host_id]));
+            self.configuration_properties_are_inductive(c, u, event);
+        }
+
+        // A host step keeps the configuration of every instance the host had and fixes the one of the
+        // instance it creates from what it has learned.
+        pub proof fn host_step_keeps_configurations(&self, c: &Constants, u: &Variables, event: Event, host_id: int, step_key: nat, net_op: NetworkOperation)
+        requires
+            inductive(c, u),
+            is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event),
+        ensures
+            forall |key: nat| #![auto]
+                u.hosts[host_id].instances.contains_key(key) ==>
+                self.hosts[host_id].instances.contains_key(key) &&
+                self.hosts[host_id].instances[key].configuration == u.hosts[host_id].instances[key].configuration,
+            forall |key: nat| #![auto]
+                !u.hosts[host_id].instances.contains_key(key) && self.hosts[host_id].instances.contains_key(key) ==>
+                key == step_key &&
+                c.hosts[host_id].knows_configuration(&u.hosts[host_id], key) &&
+                self.hosts[host_id].instances[key].configuration == c.hosts[host_id].configuration(&u.hosts[host_id], key),
+            forall |key: nat| #![auto]
+                u.hosts[host_id].decisions().contains_key(key) ==> self.hosts[host_id].decisions().contains_key(key),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            if (!lu.instances.contains_key(step_key) && lv.instances.contains_key(step_key)) {
+                assert(host::init_request(lc, lu, lv, step_key, net_op));
+            }
+        }
+
+        pub proof fn configuration_properties_are_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            configuration_properties(c, self),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.host_step_keeps_configurations(c, u, event, host_id, step_key, net_op);
+            assert(u.network.sent_messages.subset_of(self.network.sent_messages));
+            assert(lc.fast_quorums() == c.fast_quorums());
+
+            // The only configuration the step can record is that of the instance it creates.
+            let created = !lu.instances.contains_key(step_key) && lv.instances.contains_key(step_key);
+            let computed = lc.configuration(lu, step_key);
+            assert forall |key: nat| #[trigger] self.configurations.contains_key(key) && !u.configurations.contains_key(key) implies
+                created && key == step_key && self.configurations[key] == computed
+            by {
+                assert(lv.instances.contains_key(key));
+                if (!lu.instances.contains_key(key)) {
+                } else {
+                    assert(u.configurations.contains_key(key));
+                }
+            };
+
+            // What the creating host learned was decided, so it is what every Decide says.
+            if (created) {
+                assert(u.decisions_were_sent(c, lu.decisions(), step_key)) by {
+                    assert forall |j: nat| #![trigger lu.decisions()[j]]
+                        j + c.reconfig.alpha <= step_key implies
+                        exists |ballot: host::Ballot| #[trigger] u.network.sent_messages.contains(Message::Decide { key: j, ballot, value: lu.decisions()[j] })
+                    by {
+                        assert(lu.decisions().contains_key(j));
+                        assert(lu.instances[j].decide_value.is_some());
+                    };
+                };
+                assert(u.configurations.contains_key(step_key) ==> u.configurations[step_key] == computed) by {
+                    if (u.configurations.contains_key(step_key)) {
+                        let decisions = choose |decisions: Map<nat, Value>|
+                            #[trigger] u.decisions_were_sent(c, decisions, step_key) &&
+                            u.configurations[step_key] == c.reconfig.configuration(c.fast_quorums(), c.num_failures, decisions, step_key);
+                        assert forall |j: nat| j + c.reconfig.alpha <= step_key implies #[trigger] decisions[j] == lu.decisions()[j] by {
+                            assert(decisions.contains_key(j) && lu.decisions().contains_key(j));
+                            let b1 = choose |ballot: host::Ballot| #[trigger] u.network.sent_messages.contains(Message::Decide { key: j, ballot, value: decisions[j] });
+                            let b2 = choose |ballot: host::Ballot| #[trigger] u.network.sent_messages.contains(Message::Decide { key: j, ballot, value: lu.decisions()[j] });
+                        };
+                        c.reconfig.configuration_depends_on_known_decisions(c.fast_quorums(), c.num_failures, decisions, lu.decisions(), step_key);
+                    }
+                };
+                c.reconfig.configuration_is_valid(c.fast_quorums(), c.num_hosts, c.num_failures, c.quorums.acceptors, lu.decisions(), step_key);
+            }
+
+            assert(self.configurations_are_valid(c));
+
+            assert forall |key: nat| #[trigger] self.configurations.contains_key(key) implies
+                exists |decisions: Map<nat, Value>|
+                    #[trigger] self.decisions_were_sent(c, decisions, key) &&
+                    self.configurations[key] == c.reconfig.configuration(c.fast_quorums(), c.num_failures, decisions, key)
+            by {
+                let decisions = if (u.configurations.contains_key(key)) {
+                    choose |decisions: Map<nat, Value>|
+                        #[trigger] u.decisions_were_sent(c, decisions, key) &&
+                        u.configurations[key] == c.reconfig.configuration(c.fast_quorums(), c.num_failures, decisions, key)
+                } else {
+                    lu.decisions()
+                };
+                assert forall |j: nat| #![trigger decisions[j]]
+                    j + c.reconfig.alpha <= key implies
+                    exists |ballot: host::Ballot| #[trigger] self.network.sent_messages.contains(Message::Decide { key: j, ballot, value: decisions[j] })
+                by {
+                    let ballot = choose |ballot: host::Ballot| #[trigger] u.network.sent_messages.contains(Message::Decide { key: j, ballot, value: decisions[j] });
+                };
+                assert(self.decisions_were_sent(c, decisions, key));
+            };
+
+            assert forall |i: int, key: nat| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(key) implies
+                self.configurations.contains_key(key) &&
+                self.hosts[i].instances[key].configuration == self.configurations[key] &&
+                c.hosts[i].knows_configuration(&self.hosts[i], key)
+            by {
+                if (i != host_id) {
+                    assert(self.hosts[i] == u.hosts[i]);
+                } else if (lu.instances.contains_key(key)) {
+                    assert(lc.knows_configuration(lu, key));
+                } else {
+                    assert(lc.knows_configuration(lu, key));
+                }
+            };
+
+            assert forall |message: Message| #![trigger self.network.sent_messages.contains(message)]
+                self.network.sent_messages.contains(message) &&
+                network::message_key(message).is_some() implies
+                self.configurations.contains_key(network::message_key(message).unwrap())
+            by {
+                if (!u.network.sent_messages.contains(message)) {
+                    // A host sends messages about the instance it steps, which it has.
+                    assert(net_op.send == Some(message));
+                    assert(network::message_key(message) == Some(step_key));
+                    assert(lv.instances.contains_key(step_key));
+                }
+            };
+        }
+    }
+
+    pub open spec fn configuration_properties(c: &Constants, u: &Variables) -> bool {
+        &&& u.configurations_are_valid(c)
+        &&& u.configurations_were_decided(c)
+        &&& u.instances_use_recorded_configurations(c)
+        &&& u.instances_know_their_configuration(c)
+        &&& u.message_keys_have_configurations(c)
+    }
+
+    impl Variables {
+        // `ballot` was prepared for instance `key`, by a Prepare for it or by a PrepareFrom whose range
+        // covers it.
+        pub open spec fn ballot_was_prepared(&self, key: nat, ballot: host::Ballot) -> bool {
+            ||| self.network.sent_messages.contains(Message::Prepare { key, ballot })
+            ||| exists |from: nat| from <= key && #[trigger] self.network.sent_messages.contains(Message::PrepareFrom { from, ballot })
+        }
+
+        pub proof fn ballot_was_prepared_is_stable(&self, u: &Variables, key: nat, ballot: host::Ballot)
+        requires
+            u.network.sent_messages.subset_of(self.network.sent_messages),
+            u.ballot_was_prepared(key, ballot),
+        ensures
+            self.ballot_was_prepared(key, ballot),
+        {
+            if (!u.network.sent_messages.contains(Message::Prepare { key, ballot })) {
+                let from = choose |from: nat| from <= key && #[trigger] u.network.sent_messages.contains(Message::PrepareFrom { from, ballot });
+                assert(self.network.sent_messages.contains(Message::PrepareFrom { from, ballot }));
+            }
+        }
+
+        pub proof fn prepared_ballot_is_valid(&self, c: &Constants, key: nat, ballot: host::Ballot)
+        requires
+            self.network_msgs_have_valid_sender_and_ballot_pid(c),
+            self.ballot_was_prepared(key, ballot),
+        ensures
+            ballot.num > 0 && 0 <= ballot.pid < self.hosts.len(),
+        {
+            if (!self.network.sent_messages.contains(Message::Prepare { key, ballot })) {
+                let from = choose |from: nat| from <= key && #[trigger] self.network.sent_messages.contains(Message::PrepareFrom { from, ballot });
+                assert(self.network.sent_messages.contains(Message::PrepareFrom { from, ballot }));
+            }
+        }
+
+        pub open spec fn prepare_msg_in_network_implies_sender_map_has_ballot_key(&self, c: &Constants) -> bool {
+            forall |key: nat, ballot: host::Ballot| #![auto]
+                self.network.sent_messages.contains(Message::Prepare { key, ballot }) ==>
                 {
-                    let leader = accepted_ballot.pid as int;
+                    let leader = ballot.pid as int;
 
                     &&& 0 <= leader < self.hosts.len()
-                    &&& self.hosts[leader].instances.contains_key(instance)
-                    &&& self.hosts[leader].instances[instance].proposed_value.contains_key(accepted_ballot)
-                    &&& accepted_value == self.hosts[leader].instances[instance].proposed_value[accepted_ballot]
+                    &&& self.hosts[leader].instances.contains_key(key)
+                    &&& self.history[leader][key].promised.contains_key(ballot)
+                    &&& self.history[leader][key].accepted.contains_key(ballot)
+                }
+        }
+
+        pub open spec fn promise_msg_in_network_implies_sender_has_promised(&self, c: &Constants) -> bool {
+            forall |key: nat, sender: nat, ballot: host::Ballot, accepted: Option<(host::Ballot, Value)>| #![auto]
+                self.network.sent_messages.contains(Message::Promise { key, sender, ballot, accepted }) ==>
+                self.hosts[sender as int].instances.contains_key(key) &&
+                self.hosts[sender as int].instances[key].current_ballot.cmp(&ballot) >= 0
+        }
+
+        pub open spec fn accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender(&self, c: &Constants) -> bool {
+            forall |key: nat, ballot: host::Ballot, value: Value| #![auto]
+                self.network.sent_messages.contains(Message::Accept { key, ballot, value }) ==>
+                {
+                    let leader = ballot.pid as int;
+
+                    &&& 0 <= leader < self.hosts.len()
+                    &&& self.hosts[leader].instances.contains_key(key)
+                    &&& map_contains_key_with_phase1_quorum(self.history[leader][key].promised, ballot, self.configuration(key).classic)
+                    &&& self.history[leader][key].proposed_value.contains_key(ballot)
+                    &&& self.history[leader][key].proposed_value[ballot] == value
+                }
+        }
+
+        pub open spec fn accepted_msg_in_network_implies_sender_has_accepted_some_value(&self, c: &Constants) -> bool {
+            forall |key: nat, sender: nat, ballot: host::Ballot, value: Value| #![auto]
+                self.network.sent_messages.contains(Message::Accepted { key, sender, ballot, value }) ==>
+                self.hosts[sender as int].instances.contains_key(key) &&
+                self.hosts[sender as int].instances[key].current_ballot.cmp(&ballot) >= 0 &&
+                self.hosts[sender as int].instances[key].accept_ballot.is_some() &&
+                self.hosts[sender as int].instances[key].accept_ballot.unwrap().cmp(&ballot) >= 0 &&
+                self.hosts[sender as int].instances[key].accept_value.is_some()
+        }
+
+        pub open spec fn accepted_by(&self, c: &Constants, key: nat, ballot: host::Ballot, value: Value, senders: Set<nat>) -> bool {
+            &&& quorum::are_valid_hosts(senders, c.num_hosts)
+            &&& self.configuration(key).classic.is_phase2_quorum(senders)
+            &&& forall |sender: nat| #[trigger] senders.contains(sender) ==> self.network.sent_messages.contains(Message::Accepted { key, sender, ballot, value })
+        }
+
+        // Whoever counted them, the ballot owner or a learner, a phase-2 quorum of Accepted messages
+        // for `(ballot, value)` is in the network.
+        pub open spec fn quorum_has_accepted(&self, c: &Constants, key: nat, ballot: host::Ballot, value: Value) -> bool {
+            exists |senders: Set<nat>| #[trigger] self.accepted_by(c, key, ballot, value, senders)
+        }
+
+        pub open spec fn fast_accepted_by(&self, c: &Constants, key: nat, value: Value, senders: Set<nat>) -> bool {
+            &&& quorum::are_valid_hosts(senders, c.num_hosts)
+            &&& self.configuration(key).is_fast_quorum(senders)
+            &&& forall |sender: nat| #[trigger] senders.contains(sender) ==> self.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value })
+        }
+
+        // The fast round has no leader whose proposal a Decide could point at: a learner decides it on
+        // a fast quorum of Accepted messages for `value`.
+        pub open spec fn fast_quorum_has_accepted(&self, c: &Constants, key: nat, value: Value) -> bool {
+            exists |senders: Set<nat>| #[trigger] self.fast_accepted_by(c, key, value, senders)
+        }
+
+        // The Accept an acceptor took `(ballot, value)` from, or in the fast round the client's request
+        // for slot `key`.
+        pub open spec fn accept_was_sent(&self, key: nat, ballot: host::Ballot, value: Value) -> bool {
+            if (host::is_fast_ballot(ballot)) {
+                &&& ballot == host::fast_ballot()
+                &&& self.network.sent_messages.contains(Message::ClientRequest { value })
+                &&& key == host::fast_slot(value)
+            } else {
+                self.network.sent_messages.contains(Message::Accept { key, ballot, value })
+            }
+        }
+
+        pub open spec fn decide_msg_in_network_implies_quorum_has_accepted_some_value(&self, c: &Constants) -> bool {
+            forall |key: nat, ballot: host::Ballot, value: Value|
+                #[trigger] self.network.sent_messages.contains(Message::Decide { key, ballot, value }) ==>
+                if (host::is_fast_ballot(ballot)) {
+                    &&& ballot == host::fast_ballot()
+                    &&& self.fast_quorum_has_accepted(c, key, value)
+                } else {
+                    let leader = ballot.pid as int;
+
+                    &&& 0 <= leader < self.hosts.len()
+                    &&& self.hosts[leader].instances.contains_key(key)
+                    &&& self.history[leader][key].proposed_value.contains_key(ballot)
+                    &&& value == self.history[leader][key].proposed_value[ballot]
+                    &&& self.quorum_has_accepted(c, key, ballot, value)
+                }
+        }
+
+        pub open spec fn nack_msg_in_network_implies_higher_ballot_was_prepared(&self, c: &Constants) -> bool {
+            forall |key: nat, ballot: host::Ballot, higher: host::Ballot| #![auto]
+                self.network.sent_messages.contains(Message::Nack { key, ballot, higher }) ==>
+                higher.cmp(&ballot) > 0 &&
+                self.ballot_was_prepared(key, higher)
+        }
+    }
+
+    pub open spec fn messages_in_network_implies_first_degree_properties(c: &Constants, u: &Variables) -> bool {
+        &&& u.prepare_msg_in_network_implies_sender_map_has_ballot_key(c)
+        &&& u.promise_msg_in_network_implies_sender_has_promised(c)
+        &&& u.accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender(c)
+        &&& u.accepted_msg_in_network_implies_sender_has_accepted_some_value(c)
+        &&& u.decide_msg_in_network_implies_quorum_has_accepted_some_value(c)
+        &&& u.nack_msg_in_network_implies_higher_ballot_was_prepared(c)
+    }
+
+    impl Variables {
+        pub proof fn decide_msg_in_network_implies_quorum_has_accepted_some_value_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.decide_msg_in_network_implies_quorum_has_accepted_some_value(c),
+        {
+            assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
+            self.someone_accepted_implies_network_has_their_accepted_msg_is_inductive(c, u, event);
+            self.learned_senders_have_sent_accepted_is_inductive(c, u, event);
+            self.accepted_msg_in_network_implies_network_has_corresponding_accept_msg_is_inductive(c, u, event);
+            self.network_msgs_have_valid_sender_and_ballot_pid_is_inductive(c, u, event);
+            self.phase1_and_phase2_quorums_in_host_maps_are_preserved(c, u, event);
+            assert(self.accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender(c));
+
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+            self.host_step_keeps_configurations(c, u, event, host_id, step_key, net_op);
+            self.configurations_only_grow(c, u, event);
+
+            assert forall |key: nat, ballot: host::Ballot, value: Value|
+                #[trigger] self.network.sent_messages.contains(Message::Decide { key, ballot, value }) implies
+                if (host::is_fast_ballot(ballot)) {
+                    &&& ballot == host::fast_ballot()
+                    &&& self.fast_quorum_has_accepted(c, key, value)
+                } else {
+                    let leader = ballot.pid as int;
+
+                    &&& 0 <= leader < self.hosts.len()
+                    &&& self.hosts[leader].instances.contains_key(key)
+                    &&& self.history[leader][key].proposed_value.contains_key(ballot)
+                    &&& value == self.history[leader][key].proposed_value[ballot]
+                    &&& self.quorum_has_accepted(c, key, ballot, value)
+                }
+            by {
+                if (u.network.sent_messages.contains(Message::Decide { key, ballot, value })) {
+                    assert(u.configurations.contains_key(key));
+                    if (host::is_fast_ballot(ballot)) {
+                        let senders = choose |senders: Set<nat>| #[trigger] u.fast_accepted_by(c, key, value, senders);
+                        assert(self.fast_accepted_by(c, key, value, senders));
+                    } else {
+                        let senders = choose |senders: Set<nat>| #[trigger] u.accepted_by(c, key, ballot, value, senders);
+                        assert(self.accepted_by(c, key, ballot, value, senders));
+                    }
+                } else if (host::send_decide(lc, lu, lv, step_key, net_op) || host::learn(lc, lu, lv, step_key, net_op, value)) {
+                    // The leader decides a ballot it proposed in, which it prepared.
+                    assert(u.history[host_id][key].proposed_value.contains_key(ballot));
+                    assert(u.ballot_was_prepared(key, ballot));
+                    u.prepared_ballot_is_valid(c, key, ballot);
+
+                    let senders = self.history[host_id][key].accepted[ballot];
+                    assert(ballot.pid == c.hosts[host_id].id);
+                    assert(lu.instances[key].configuration == self.configuration(key));
+                    assert(self.accepted_by(c, key, ballot, value, senders));
+                } else {
+                    assert(host::learner_decide(lc, lu, lv, step_key, net_op, ballot, value));
+                    let senders = lv.instances[key].volatile.learned[(ballot, value)];
+                    assert(lu.instances[key].configuration == self.configuration(key));
+                    assert(self.configurations.contains_key(key));
+
+                    if (host::is_fast_ballot(ballot)) {
+                        // A fast quorum is not empty, and the Accepted of any member has the fast ballot.
+                        assert(quorum::are_valid_hosts(senders, c.num_hosts));
+                        fast::fast_quorums_have_common_host(self.configuration(key), c.num_hosts, c.num_failures, senders, senders);
+                        let sender = choose |h: nat| #![auto] senders.contains(h) && senders.contains(h);
+                        assert(self.network.sent_messages.contains(Message::Accepted { key, sender, ballot, value }));
+                        assert(ballot == host::fast_ballot());
+                        assert(self.fast_accepted_by(c, key, value, senders));
+                    } else {
+                        assert(self.accepted_by(c, key, ballot, value, senders));
+
+                        quorum::phase2_quorum_has_some_host(self.configuration(key).classic, c.num_hosts, c.num_failures, senders);
+                        let sender = choose |h: nat| #![auto] senders.contains(h);
+                        assert(self.network.sent_messages.contains(Message::Accepted { key, sender, ballot, value }));
+                        assert(self.accept_was_sent(key, ballot, value));
+                        assert(self.network.sent_messages.contains(Message::Accept { key, ballot, value }));
+                    }
+                }
+            };
+        }
+
+        pub proof fn nack_msg_in_network_implies_higher_ballot_was_prepared_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.nack_msg_in_network_implies_higher_ballot_was_prepared(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            assert(u.network.sent_messages.subset_of(self.network.sent_messages));
+
+            assert forall |key: nat, ballot: host::Ballot, higher: host::Ballot| #![auto]
+                self.network.sent_messages.contains(Message::Nack { key, ballot, higher }) implies
+                higher.cmp(&ballot) > 0 &&
+                self.ballot_was_prepared(key, higher)
+            by {
+                if (!u.network.sent_messages.contains(Message::Nack { key, ballot, higher })) {
+                    assert(net_op.send == Some(Message::Nack { key, ballot, higher }));
+                    assert(host::reject_prepare(lc, lu, lv, step_key, net_op) || host::reject_accept(lc, lu, lv, step_key, net_op));
+                    match (net_op.recv) {
+                        Some(Message::Prepare { key: recv_key, ballot: recv_ballot }) => {
+                            assert(u.network.sent_messages.contains(Message::Prepare { key: recv_key, ballot: recv_ballot }));
+                        },
+                        Some(Message::Accept { key: recv_key, ballot: recv_ballot, value }) => {
+                            assert(u.network.sent_messages.contains(Message::Accept { key: recv_key, ballot: recv_ballot, value }));
+                        },
+                        _ => {},
+                    }
+                    assert(ballot.num > 0);
+                    assert(higher == lu.instances[key].current_ballot && higher.num > 0);
+                    assert(u.ballot_was_prepared(key, higher));
                 }
+                self.ballot_was_prepared_is_stable(u, key, higher);
+            };
+        }
+    }
+
+    impl Variables {
+        pub open spec fn promised_state_implies_network_has_prepare_msg(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat|
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.hosts[i].instances[instance].current_ballot.num > 0 ==>
+                #[trigger] self.ballot_was_prepared(instance, self.hosts[i].instances[instance].current_ballot)
+        }
+
+        pub open spec fn someone_promised_implies_network_has_their_promise_msg(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat, ballot: host::Ballot, sender: nat|
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.history[i][instance].promised.dom().contains(ballot) &&
+                self.history[i][instance].promised[ballot].dom().contains(sender) ==>
+                #[trigger] self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot, accepted: self.history[i][instance].promised[ballot][sender] })
+        }
+
+        pub open spec fn either_of_accept_ballot_or_value_is_some(&self, i: int, instance: nat) -> bool {
+            ||| self.hosts[i].instances[instance].accept_ballot.is_some()
+            ||| self.hosts[i].instances[instance].accept_value.is_some()
+        }
+
+        pub open spec fn accept_ballot_some_eq_accept_value_some(&self, i: int, instance: nat) -> bool {
+            self.hosts[i].instances[instance].accept_ballot.is_some() == self.hosts[i].instances[instance].accept_value.is_some()
+        }
+
+        pub open spec fn if_accept_ballot_is_some_then_accept_value_is_some(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat|
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) ==>
+                #[trigger] self.accept_ballot_some_eq_accept_value_some(i, instance)
+        }
+
+        pub open spec fn accepted_state_implies_network_has_accept_message(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat|
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                #[trigger] self.either_of_accept_ballot_or_value_is_some(i, instance) ==>
+                self.hosts[i].instances[instance].accept_ballot.is_some() &&
+                self.hosts[i].instances[instance].accept_value.is_some() &&
+                self.accept_was_sent(instance, self.hosts[i].instances[instance].accept_ballot.unwrap(), self.hosts[i].instances[instance].accept_value.unwrap())
+        }
+
+        pub open spec fn accepted_state_implies_network_has_accepted_message(&self, c: &Constants) -> bool {
+            forall |sender: nat, instance: nat, ballot: host::Ballot| #![auto]
+                0 <= sender < self.hosts.len() &&
+                self.hosts[sender as int].instances.contains_key(instance) &&
+                self.hosts[sender as int].instances[instance].accept_ballot == Some(ballot) ==>
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot, value: self.hosts[sender as int].instances[instance].accept_value.unwrap() })
+        }
+
+        pub open spec fn someone_accepted_implies_network_has_their_accepted_msg(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat, ballot: host::Ballot, sender: nat| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.history[i][instance].accepted.contains_key(ballot) &&
+                self.history[i][instance].accepted[ballot].contains(sender) ==>
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot, value: self.history[i][instance].proposed_value[ballot] })
+        }
+
+        pub open spec fn learned_senders_have_sent_accepted(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat, ballot: host::Ballot, value: Value, sender: nat| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.hosts[i].instances[instance].volatile.learned.contains_key((ballot, value)) &&
+                self.hosts[i].instances[instance].volatile.learned[(ballot, value)].contains(sender) ==>
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot, value })
+        }
+
+        // Every value a host has proposed under a ballot went out in that ballot's Accept, on its own or
+        // in an AcceptBatch.
+        pub open spec fn proposals_were_sent(&self, c: &Constants) -> bool {
+            forall |i: int, key: nat, ballot: host::Ballot| #![trigger self.history[i][key].proposed_value[ballot]]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(key) &&
+                self.history[i][key].proposed_value.contains_key(ballot) ==>
+                self.network.sent_messages.contains(Message::Accept { key, ballot, value: self.history[i][key].proposed_value[ballot] })
+        }
+
+        pub open spec fn decided_state_implies_network_has_decide_message(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat|
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                #[trigger] self.hosts[i].instances[instance].decide_value.is_some() ==>
+                exists |ballot: host::Ballot| #[trigger] self.network.sent_messages.contains(Message::Decide { key: instance, ballot, value: self.hosts[i].instances[instance].decide_value.unwrap() })
+        }
+
+        pub proof fn promised_state_implies_network_has_prepare_msg_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.promised_state_implies_network_has_prepare_msg(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+
+            assert(u.network.sent_messages.subset_of(self.network.sent_messages));
+
+            assert forall |i: int, instance: nat|
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.hosts[i].instances[instance].current_ballot.num > 0 implies
+                #[trigger] self.ballot_was_prepared(instance, self.hosts[i].instances[instance].current_ballot)
+            by {
+                let ballot = self.hosts[i].instances[instance].current_ballot;
+
+                // A new or raised instance is at the floor, which some PrepareFrom covering it prepared.
+                if ((i == host_id) && (instance == step_key) && (host::init_request(lc, lu, lv, step_key, net_op) || host::raise_to_floor(lc, lu, lv, step_key, net_op))) {
+                    assert(ballot == host::floor_for(lu, instance));
+                    assert(instance >= lu.multi.floor_from && ballot == lu.multi.promise_floor);
+                    assert(self.network.sent_messages.contains(Message::PrepareFrom { from: lu.multi.floor_from, ballot }));
+                } else {
+                    match (net_op.recv) {
+                        Some(Message::Prepare { key: recv_key, ballot: recv_ballot })
+                        if (host::promise(lc, lu, lv, step_key, net_op) && (i == host_id) && (instance == step_key)) => {
+                            assert(ballot == recv_ballot);
+                            assert(self.network.sent_messages.contains(Message::Prepare { key: instance, ballot }));
+                        },
+                        Some(Message::Accept { key: recv_key, ballot: recv_ballot, value })
+                        if (host::accept(lc, lu, lv, step_key, net_op) && (i == host_id) && (instance == step_key)) => {
+                            assert(u.network.sent_messages.contains(Message::Accept { key: instance, ballot, value }));
+                            assert(u.history[ballot.pid as int][instance].proposed_value.contains_key(ballot));
+                            assert(u.ballot_was_prepared(instance, ballot));
+                        },
+                        Some(Message::Decide { key: recv_key, ballot: recv_ballot, value })
+                        if (event is Decide && host::decide(lc, lu, lv, step_key, net_op, value) && (i == host_id) && (instance == step_key)) => {
+                            assert(u.network.sent_messages.contains(Message::Decide { key: instance, ballot, value }));
+                            assert(!host::is_fast_ballot(ballot));
+                            assert(u.history[ballot.pid as int][instance].proposed_value.contains_key(ballot));
+                            assert(u.ballot_was_prepared(instance, ballot));
+                        },
+                        // `nacked` only raises `max_seen_ballot`, so like every other step it leaves
+                        // `current_ballot` where it was.
+                        _ => {
+                            assert(u.hosts[i].instances.contains_key(instance) && u.hosts[i].instances[instance].current_ballot == ballot);
+                            assert(u.ballot_was_prepared(instance, ballot));
+                        },
+                    }
+                    self.ballot_was_prepared_is_stable(u, instance, ballot);
+                }
+            };
+        }
+
+        pub proof fn if_accept_ballot_is_some_then_accept_value_is_some_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.if_accept_ballot_is_some_then_accept_value_is_some(c)
+        {
+            self.all_maps_and_sets_are_finite(c);
+
+            let (host_id, key, net_op) = choose |host_id: int, key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+
+            assert forall |i: int, instance: nat|
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) implies
+                #[trigger] self.accept_ballot_some_eq_accept_value_some(i, instance)
+            by {
+                match (event) {
+                    _ if ((host::init_request(lc, lu, lv, key, net_op) || host::accept(lc, lu, lv, key, net_op) || host::fast_accept(lc, lu, lv, key, net_op)) && (i == host_id) && (key == instance)) => {
+                        assert(self.accept_ballot_some_eq_accept_value_some(i, instance));
+                    },
+                    _ => { assert(u.accept_ballot_some_eq_accept_value_some(i, instance)); },
+                }
+            };
+        }
+
+        pub proof fn accepted_state_implies_network_has_accept_message_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.accepted_state_implies_network_has_accept_message(c),
+        {
+            assert(self.all_maps_and_sets_are_finite(c));
+
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+
+            assert forall |i: int, instance: nat|
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                #[trigger] self.either_of_accept_ballot_or_value_is_some(i, instance) implies
+                self.hosts[i].instances[instance].accept_ballot.is_some() &&
+                self.hosts[i].instances[instance].accept_value.is_some() &&
+                self.accept_was_sent(instance, self.hosts[i].instances[instance].accept_ballot.unwrap(), self.hosts[i].instances[instance].accept_value.unwrap())
+            by {
+                match (net_op.recv) {
+                    Some(Message::Accept { key: recv_key, ballot, value })
+                    if (host::accept(lc, lu, lv, step_key, net_op) && (i == host_id) && (instance == step_key)) => {
+                        // Accept messages carry prepared, so classic, ballots.
+                        assert(u.network.sent_messages.contains(Message::Accept { key: instance, ballot, value }));
+                        assert(!host::is_fast_ballot(ballot));
+                    },
+                    Some(Message::ClientRequest { value })
+                    if (host::fast_accept(lc, lu, lv, step_key, net_op) && (i == host_id) && (instance == step_key)) => {
+                        assert(lv.instances[instance].accept_ballot == Some(host::fast_ballot()));
+                        assert(u.network.sent_messages.contains(Message::ClientRequest { value }));
+                        assert(instance == host::fast_slot(value));
+                    },
+                    _ => {
+                        if (u.hosts[i].instances.contains_key(instance)) {
+                            assert(u.either_of_accept_ballot_or_value_is_some(i, instance));
+                        }
+                    },
+                }
+            };
+        }
+
+        pub proof fn decided_state_implies_network_has_decide_message_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.decided_state_implies_network_has_decide_message(c),
+        {
+            assert(self.all_maps_and_sets_are_finite(c));
+
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+
+            assert forall |i: int, instance: nat| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.hosts[i].instances[instance].decide_value.is_some() implies
+                exists |ballot: host::Ballot| #![auto] self.network.sent_messages.contains(Message::Decide { key: instance, ballot, value: self.hosts[i].instances[instance].decide_value.unwrap() })
+            by {
+                match ((event, net_op.recv)) {
+                    (Event::Decide { key: decide_key, value }, Some(Message::Decide { key: instance, ballot: recv_bal, value: recv_val }))
+                    if (i == host_id) => {
+                        assert(decide_key == step_key);
+                        assert(host::decide(lc, lu, lv, step_key, net_op, value));
+                        assert(step_key == instance);
+                        assert(recv_val == self.hosts[i].instances[instance].decide_value.unwrap());
+                        assert(self.network.sent_messages.contains(Message::Decide { key: instance, ballot: recv_bal, value: recv_val }));
+                        assert(exists |ballot: host::Ballot| #![auto] self.network.sent_messages.contains(Message::Decide { key: instance, ballot, value: self.hosts[i].instances[instance].decide_value.unwrap() }));
+                    },
+                    (Event::Decide { key: decide_key, value }, None)
+                    if (i == host_id && instance == step_key) => {
+                        let ballot = if (host::learn(lc, lu, lv, step_key, net_op, value)) {
+                            lu.instances[step_key].current_ballot
+                        } else {
+                            choose |ballot: host::Ballot| #[trigger] host::learner_decide(lc, lu, lv, step_key, net_op, ballot, value)
+                        };
+                        assert(value == self.hosts[i].instances[instance].decide_value.unwrap());
+                        assert(self.network.sent_messages.contains(Message::Decide { key: instance, ballot, value }));
+                    },
+                    _ => { }
+                }
+            };
+        }
+
+        pub proof fn someone_accepted_implies_network_has_their_accepted_msg_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.someone_accepted_implies_network_has_their_accepted_msg(c),
+        {
+            assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
+
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+
+            assert forall |i: int, instance: nat, ballot: host::Ballot, sender: nat| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.history[i][instance].accepted.contains_key(ballot) &&
+                self.history[i][instance].accepted[ballot].contains(sender) implies
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot, value: self.history[i][instance].proposed_value[ballot] })
+            by {
+                match (net_op.recv) {
+                    Some(Message::Accepted { key: recv_key, sender: recv_sender, ballot: recv_ballot, value: recv_value })
+                    if (host::accepted(lc, lu, lv, step_key, net_op) && (i == host_id) && (instance == step_key) && (ballot == recv_ballot) && (sender == recv_sender)) => {
+                        assert(u.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot, value: recv_value }));
+                        // The leader counts Accepted only for ballots it prepared, which are classic.
+                        assert(u.history[host_id][instance].accepted.contains_key(ballot));
+                        assert(u.ballot_was_prepared(instance, ballot));
+                        u.prepared_ballot_is_valid(c, instance, ballot);
+                        assert(u.accept_was_sent(instance, ballot, recv_value));
+                        assert(u.network.sent_messages.contains(Message::Accept { key: instance, ballot, value: recv_value }));
+                        assert(ballot.pid == c.hosts[i].id);
+                        assert(recv_value == u.history[host_id][instance].proposed_value[ballot]);
+                    },
+                    _ => {
+                        let old_accepted = u.history[i][instance].accepted[ballot];
+                        assert(old_accepted.contains(sender));
+                        assert(old_accepted.remove(sender).len() + 1 == old_accepted.len());
+                        assert(u.history[i][instance].proposed_value.contains_key(ballot));
+                        assert(self.history[i][instance].proposed_value[ballot] == u.history[i][instance].proposed_value[ballot]);
+                    },
+                }
+            };
+        }
+
+        pub proof fn learned_senders_have_sent_accepted_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.learned_senders_have_sent_accepted(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+
+            assert forall |i: int, instance: nat, ballot: host::Ballot, value: Value, sender: nat| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.hosts[i].instances[instance].volatile.learned.contains_key((ballot, value)) &&
+                self.hosts[i].instances[instance].volatile.learned[(ballot, value)].contains(sender) implies
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot, value })
+            by {
+                match (net_op.recv) {
+                    Some(Message::Accepted { key: recv_key, sender: recv_sender, ballot: recv_ballot, value: recv_value })
+                    if (host::observe_accepted(lc, lu, lv, step_key, net_op) && (i == host_id) && (instance == step_key) && (ballot == recv_ballot) && (value == recv_value) && (sender == recv_sender)) => {
+                        assert(u.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot, value }));
+                    },
+                    _ => {
+                        assert(u.hosts[i].instances[instance].volatile.learned[(ballot, value)].contains(sender));
+                    },
+                }
+            };
+        }
+
+        pub proof fn proposals_were_sent_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.proposals_were_sent(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+            assert(u.network.sent_messages.subset_of(self.network.sent_messages));
+
+            assert forall |i: int, key: nat, ballot: host::Ballot|
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(key) &&
+                self.history[i][key].proposed_value.contains_key(ballot) implies
+                self.network.sent_messages.contains(Message::Accept { key, ballot, value: #[trigger] self.history[i][key].proposed_value[ballot] })
+            by {
+                if (i != host_id) {
+                    assert(self.history[i] == u.history[i]);
+                    assert(u.hosts[i] == self.hosts[i]);
+                } else if (!lu.instances.contains_key(key)) {
+                    assert(key == step_key);
+                    assert(self.history[i][key].proposed_value == Map::<host::Ballot, Value>::empty());
+                } else if (key != step_key) {
+                    assert(self.history[i][key].proposed_value == u.history[i][key].proposed_value);
+                } else if (host::send_accept(lc, lu, lv, key, net_op) && ballot == lu.instances[key].current_ballot) {
+                    assert(self.history[i][key].proposed_value[ballot] == lv.instances[key].volatile.proposed_value[ballot]);
+                    assert(lv.instances[key].current_ballot == ballot);
+                    assert(net_op.send == Some(Message::Accept { key, ballot, value: lv.instances[key].volatile.proposed_value[ballot] }));
+                } else {
+                    assert(self.history[i][key].proposed_value.contains_key(ballot) ==> u.history[i][key].proposed_value.contains_key(ballot));
+                    assert(self.history[i][key].proposed_value[ballot] == u.history[i][key].proposed_value[ballot]);
+                    assert(u.network.sent_messages.contains(Message::Accept { key, ballot, value: u.history[i][key].proposed_value[ballot] }));
+                }
+            };
+        }
+    }
+
+    pub open spec fn properties_imply_first_degree_messages_in_network(c: &Constants, u: &Variables) -> bool {
+        &&& u.promised_state_implies_network_has_prepare_msg(c)
+        &&& u.someone_promised_implies_network_has_their_promise_msg(c)
+        &&& u.if_accept_ballot_is_some_then_accept_value_is_some(c)
+        &&& u.accepted_state_implies_network_has_accept_message(c)
+        &&& u.accepted_state_implies_network_has_accepted_message(c)
+        &&& u.someone_accepted_implies_network_has_their_accepted_msg(c)
+        &&& u.learned_senders_have_sent_accepted(c)
+        &&& u.proposals_were_sent(c)
+        &&& u.decided_state_implies_network_has_decide_message(c)
+    }
+
+    impl Variables {
+        pub open spec fn network_msgs_have_valid_sender_and_ballot_pid(&self, c: &Constants) -> bool {
+            &&& forall |key: nat, ballot: host::Ballot| #![auto]
+                    self.network.sent_messages.contains(Message::Prepare { key, ballot }) ==>
+                    ballot.num > 0 && 0 <= ballot.pid < self.hosts.len()
+            &&& forall |from: nat, ballot: host::Ballot| #![auto]
+                    self.network.sent_messages.contains(Message::PrepareFrom { from, ballot }) ==>
+                    ballot.num > 0 && 0 <= ballot.pid < self.hosts.len()
+            &&& forall |key:nat, sender: nat, ballot: host::Ballot, accepted: Option<(host::Ballot, Value)>| #![auto]
+                    self.network.sent_messages.contains(Message::Promise { key, sender, ballot, accepted }) ==>
+                    ballot.num > 0 && 0 <= sender < self.hosts.len() && ballot.num > 0 && 0 <= ballot.pid < self.hosts.len()
+            &&& forall |key:nat, sender: nat, ballot: host::Ballot, accepted_ballot: host::Ballot, accepted_value: Value| #![auto]
+                    self.network.sent_messages.contains(Message::Promise { key, sender, ballot, accepted: Some((accepted_ballot, accepted_value)) }) ==>
+                    ballot.num > 0 && 0 <= accepted_ballot.pid < self.hosts.len()
+            &&& forall |key:nat, ballot: host::Ballot, value: Value| #![auto]
+                    self.network.sent_messages.contains(Message::Accept { key, ballot, value }) ==>
+                    ballot.num > 0 && 0 <= ballot.pid < self.hosts.len()
+            &&& forall |key:nat, sender: nat, ballot: host::Ballot, value: Value| #![auto]
+                    self.network.sent_messages.contains(Message::Accepted { key, sender, ballot, value }) ==>
+                    (ballot.num > 0 || ballot == host::fast_ballot()) && 0 <= sender < self.hosts.len() && 0 <= ballot.pid < self.hosts.len()
+            &&& forall |key:nat, ballot: host::Ballot, value: Value| #![auto]
+                    ballot.num > 0 && self.network.sent_messages.contains(Message::Decide { key, ballot, value }) ==>
+                    0 <= ballot.pid < self.hosts.len()
+            &&& forall |key: nat, ballot: host::Ballot, higher: host::Ballot| #![auto]
+                    self.network.sent_messages.contains(Message::Nack { key, ballot, higher }) ==>
+                    ballot.num > 0 && 0 <= ballot.pid < self.hosts.len() && higher.num > 0 && 0 <= higher.pid < self.hosts.len()
+        }
+
+        pub open spec fn promise_msgs_from_same_sender_for_same_ballot_have_same_accepted(&self, c: &Constants) -> bool {
+            forall |sender: nat, instance: nat, b1: host::Ballot, a1: Option<(host::Ballot, Value)>, b2: host::Ballot, a2: Option<(host::Ballot, Value)>| #![auto]
+                self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot: b1, accepted: a1 }) &&
+                self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot: b2, accepted: a2 }) &&
+                b1 == b2 ==>
+                a1 == a2
+        }
+
+        pub open spec fn ballot_in_accepted_is_smaller_than_promise_message_ballot(&self, c: &Constants) -> bool {
+            forall |sender: nat, instance: nat, promise_ballot: host::Ballot, accepted_ballot: host::Ballot, accepted_value: Value| #![auto]
+                self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot: promise_ballot, accepted: Some((accepted_ballot, accepted_value)) }) ==>
+                accepted_ballot.cmp(&promise_ballot) < 0
+        }
+
+        // Only a classic ballot has a leader whose proposal the reported value is; a value accepted in the
+        // fast round came straight from a client (`accept_was_sent`).
+        pub open spec fn value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot(&self, c: &Constants) -> bool {
+            forall |sender: nat, instance: nat, ballot: host::Ballot, accepted_ballot: host::Ballot, accepted_value: Value| #![auto]
+                self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot, accepted: Some((accepted_ballot, accepted_value)) }) &&
+                !host::is_fast_ballot(accepted_ballot) ==>
+                {
+                    let leader = accepted_ballot.pid as int;
+
+                    &&& 0 <= leader < self.hosts.len()
+                    &&& self.hosts[leader].instances.contains_key(instance)
+                    &&& self.history[leader][instance].proposed_value.contains_key(accepted_ballot)
+                    &&& accepted_value == self.history[leader][instance].proposed_value[accepted_ballot]
+                }
+        }
+
+        pub open spec fn if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message(&self, c: &Constants) -> bool {
+            forall |sender: nat, instance: nat, ballot: host::Ballot, accepted_ballot: host::Ballot, accepted_value: Value| #![auto]
+                self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot, accepted: Some((accepted_ballot, accepted_value)) }) ==>
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot: accepted_ballot, value: accepted_value })
+        }
+
+        pub open spec fn network_has_at_most_one_accept_message_for_any_ballot(&self, c: &Constants) -> bool {
+            forall |instance: nat, ballot: host::Ballot, v1: Value, v2: Value| #![auto]
+                self.network.sent_messages.contains(Message::Accept { key: instance, ballot, value: v1 }) &&
+                self.network.sent_messages.contains(Message::Accept { key: instance, ballot, value: v2 }) ==>
+                v1 == v2
+        }
+
+        pub open spec fn accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted(&self, c: &Constants) -> bool {
+            forall |sender: nat, instance: nat, accepted_ballot: host::Ballot, value: Value, future_ballot: host::Ballot, accepted: Option<(host::Ballot, Value)>| #![auto]
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot: accepted_ballot, value }) &&
+                future_ballot.cmp(&accepted_ballot) > 0 &&
+                self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot: future_ballot, accepted }) ==>
+                {
+                    &&& accepted.is_some()
+                    &&& accepted.unwrap().0.cmp(&accepted_ballot) >= 0
+                }
+        }
+
+        pub open spec fn accepted_msg_in_network_implies_network_has_corresponding_accept_msg(&self, c: &Constants) -> bool {
+            forall |sender: nat, instance: nat, ballot: host::Ballot, value: Value| #![auto]
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot, value }) ==>
+                self.accept_was_sent(instance, ballot, value)
+        }
+
+        // An acceptor takes one value in the fast round, so it sends at most one Accepted for it.
+        pub open spec fn fast_accepted_msgs_from_same_sender_have_same_value(&self, c: &Constants) -> bool {
+            forall |instance: nat, sender: nat, v1: Value, v2: Value| #![auto]
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot: host::fast_ballot(), value: v1 }) &&
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot: host::fast_ballot(), value: v2 }) ==>
+                v1 == v2
+        }
+
+        pub open spec fn all_decide_messages_hold_same_value(&self, c: &Constants) -> bool {
+            forall |key: nat, b1: host::Ballot, v1: Value, b2: host::Ballot, v2: Value| #![auto]
+                self.network.sent_messages.contains(Message::Decide { key, ballot: b1, value: v1 }) &&
+                self.network.sent_messages.contains(Message::Decide { key, ballot: b2, value: v2 }) ==>
+                v1 == v2
+        }
+
+        // A batch only ever goes out together with the messages it carries (`network::step`).
+        pub open spec fn batch_msgs_in_network_carry_sent_msgs(&self, c: &Constants) -> bool {
+            forall |batch: Message| #![trigger self.network.sent_messages.contains(batch)]
+                self.network.sent_messages.contains(batch) && batch::is_batch(batch) ==>
+                batch::unbatch(batch).to_set().subset_of(self.network.sent_messages)
+        }
+
+        pub proof fn network_msgs_have_valid_sender_and_ballot_pid_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.network_msgs_have_valid_sender_and_ballot_pid(c),
+        {
+            assert(self.all_maps_and_sets_are_finite(c));
+            assert(self.network.sent_messages.finite());
+
+            let (host_id, instance, net_op) = choose |host_id: int, instance: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+
+            assert forall |key:nat, sender: nat, ballot: host::Ballot, accepted_ballot: host::Ballot, accepted_value: Value| #![auto]
+                    self.network.sent_messages.contains(Message::Promise { key, sender, ballot, accepted: Some((accepted_ballot, accepted_value)) }) implies
+                    ballot.num > 0 && 0 <= accepted_ballot.pid < self.hosts.len()
+            by {
+                assert(self.network.sent_messages.contains(Message::Accepted { key, sender, ballot: accepted_ballot, value: accepted_value }));
+                assert(accepted_ballot.pid < self.hosts.len());
+            };
+
+            // The rejected ballot came in on a Prepare or Accept; `higher` was prepared before this step.
+            self.nack_msg_in_network_implies_higher_ballot_was_prepared_is_inductive(c, u, event);
+            assert forall |key: nat, ballot: host::Ballot, higher: host::Ballot| #![auto]
+                    self.network.sent_messages.contains(Message::Nack { key, ballot, higher }) implies
+                    ballot.num > 0 && 0 <= ballot.pid < self.hosts.len() && higher.num > 0 && 0 <= higher.pid < self.hosts.len()
+            by {
+                if (!u.network.sent_messages.contains(Message::Nack { key, ballot, higher })) {
+                    match (net_op.recv) {
+                        Some(Message::Prepare { key: recv_key, ballot: recv_ballot }) => {
+                            assert(u.network.sent_messages.contains(Message::Prepare { key: recv_key, ballot: recv_ballot }));
+                        },
+                        Some(Message::Accept { key: recv_key, ballot: recv_ballot, value }) => {
+                            assert(u.network.sent_messages.contains(Message::Accept { key: recv_key, ballot: recv_ballot, value }));
+                        },
+                        _ => {},
+                    }
+                }
+                assert(u.ballot_was_prepared(key, higher));
+                u.prepared_ballot_is_valid(c, key, higher);
+            };
+
+            // A Promise sent by `init_request` or `raise_to_floor` is for the floor, which came in on a PrepareFrom.
+            assert forall |key:nat, sender: nat, ballot: host::Ballot, accepted: Option<(host::Ballot, Value)>| #![auto]
+                    self.network.sent_messages.contains(Message::Promise { key, sender, ballot, accepted }) implies
+                    ballot.num > 0 && 0 <= sender < self.hosts.len() && ballot.num > 0 && 0 <= ballot.pid < self.hosts.len()
+            by {
+                if (!u.network.sent_messages.contains(Message::Promise { key, sender, ballot, accepted })) {
+                    if (host::init_request(lc, lu, lv, instance, net_op) || host::raise_to_floor(lc, lu, lv, instance, net_op)) {
+                        assert(ballot == host::floor_for(lu, instance) && ballot == lu.multi.promise_floor);
+                        assert(u.network.sent_messages.contains(Message::PrepareFrom { from: lu.multi.floor_from, ballot }));
+                    } else {
+                        assert(host::promise(lc, lu, lv, instance, net_op));
+                    }
+                }
+            };
+        }
+
+        pub proof fn value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot(c),
+        {
+            assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
+            assert(self.if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message(c));
+
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+
+            assert forall |sender: nat, instance: nat, ballot: host::Ballot, accepted_ballot: host::Ballot, accepted_value: Value| #![auto]
+                self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot, accepted: Some((accepted_ballot, accepted_value)) }) &&
+                !host::is_fast_ballot(accepted_ballot) implies
+                {
+                    let leader = accepted_ballot.pid as int;
+
+                    &&& 0 <= leader < self.hosts.len()
+                    &&& self.hosts[leader].instances.contains_key(instance)
+                    &&& self.history[leader][instance].proposed_value.contains_key(accepted_ballot)
+                    &&& accepted_value == self.history[leader][instance].proposed_value[accepted_ballot]
+                }
+            by {
+                if (instance == step_key) {
+                    let leader = accepted_ballot.pid as int;
+                    assert(0 <= leader < self.hosts.len());
+                    assert(self.history[leader][instance].proposed_value.contains_key(accepted_ballot));
+
+                    match ((event, net_op.recv, net_op.send)) {
+                        (Event::NoOp, None, Some(Message::Promise { key: send_instance, sender, ballot: promise_ballot, accepted }))
+                        if (host::raise_to_floor(lc, lu, lv, step_key, net_op) && (sender == host_id) && accepted.is_some() && (accepted == Some((accepted_ballot, accepted_value)))) => {
+                            assert(send_instance == instance);
+                            assert(self.either_of_accept_ballot_or_value_is_some(sender as int, instance));
+                            assert(self.accept_was_sent(instance, lv.instances[instance].accept_ballot.unwrap(), lv.instances[instance].accept_value.unwrap())) by {
+                                self.accepted_state_implies_network_has_accept_message_is_inductive(c, u, event);
+                            };
+                            assert(self.network.sent_messages.contains(Message::Accept { key: instance, ballot: accepted_ballot, value: accepted_value }));
+                            assert(accepted_value == self.history[leader][instance].proposed_value[accepted_ballot]);
+                        },
+                        (Event::NoOp, Some(Message::Prepare { key: recv_instance, ballot: prepare_ballot }), Some(Message::Promise { key: send_instance, sender, ballot: promise_ballot, accepted }))
+                        if (host::promise(lc, lu, lv, step_key, net_op) && (sender == host_id) && (promise_ballot == prepare_ballot) && accepted.is_some() && (accepted == Some((accepted_ballot, accepted_value)))) => {
+                            assert(recv_instance == send_instance);
+                            assert(self.either_of_accept_ballot_or_value_is_some(sender as int, instance));
+                            assert(self.accept_was_sent(instance, lv.instances[instance].accept_ballot.unwrap(), lv.instances[instance].accept_value.unwrap())) by {
+                                self.accepted_state_implies_network_has_accept_message_is_inductive(c, u, event);
+                            };
+                            assert(self.network.sent_messages.contains(Message::Accept { key: instance, ballot: accepted_ballot, value: accepted_value }));
+                            assert(accepted_value == self.history[leader][instance].proposed_value[accepted_ballot]);
+                        },
+                        _ => {},
+                    }
+                }
+            };
+        }
+
+        // A Promise is sent only by an acceptor moving its instance above the Promise's ballot (or creating
+        // it there), and an earlier Promise for that ballot would already have it at or above it.
+        pub proof fn promise_msgs_from_same_sender_for_same_ballot_have_same_accepted_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.promise_msgs_from_same_sender_for_same_ballot_have_same_accepted(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+
+            assert forall |sender: nat, instance: nat, b1: host::Ballot, a1: Option<(host::Ballot, Value)>, b2: host::Ballot, a2: Option<(host::Ballot, Value)>| #![auto]
+                self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot: b1, accepted: a1 }) &&
+                self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot: b2, accepted: a2 }) &&
+                b1 == b2 implies
+                a1 == a2
+            by {
+                let (m1, m2) = (Message::Promise { key: instance, sender, ballot: b1, accepted: a1 }, Message::Promise { key: instance, sender, ballot: b2, accepted: a2 });
+
+                if (u.network.sent_messages.contains(m1) != u.network.sent_messages.contains(m2)) {
+                    assert(net_op.send == Some(if (u.network.sent_messages.contains(m1)) { m2 } else { m1 }));
+                    assert(sender == host_id);
+                    if (host::init_request(lc, lu, lv, step_key, net_op)) {
+                        assert(!lu.instances.contains_key(instance));
+                        assert(lu.instances.contains_key(instance));
+                    } else {
+                        assert(host::promise(lc, lu, lv, step_key, net_op) || host::raise_to_floor(lc, lu, lv, step_key, net_op));
+                        assert(lu.instances[instance].current_ballot.cmp(&b1) < 0);
+                        assert(lu.instances[instance].current_ballot.cmp(&b1) >= 0);
+                    }
+                }
+            };
+        }
+
+        // The acceptor reports its own accepted pair in every Promise, and that pair is at least as
+        // high as anything it has sent Accepted for.
+        pub proof fn accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+
+            assert forall |sender: nat, instance: nat, accepted_ballot: host::Ballot, value: Value, future_ballot: host::Ballot, accepted: Option<(host::Ballot, Value)>| #![auto]
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot: accepted_ballot, value }) &&
+                future_ballot.cmp(&accepted_ballot) > 0 &&
+                self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot: future_ballot, accepted }) implies
+                {
+                    &&& accepted.is_some()
+                    &&& accepted.unwrap().0.cmp(&accepted_ballot) >= 0
+                }
+            by {
+                let (accepted_msg, promise_msg) = (Message::Accepted { key: instance, sender, ballot: accepted_ballot, value }, Message::Promise { key: instance, sender, ballot: future_ballot, accepted });
+
+                if (!u.network.sent_messages.contains(promise_msg)) {
+                    assert(net_op.send == Some(promise_msg) && sender == host_id);
+                    assert(u.network.sent_messages.contains(accepted_msg));
+                    assert(lu.instances.contains_key(instance));
+                    assert(lu.instances[instance].accept_ballot.is_some());
+                    assert(lu.instances[instance].accept_ballot.unwrap().cmp(&accepted_ballot) >= 0);
+                    assert(host::promise(lc, lu, lv, step_key, net_op) || host::raise_to_floor(lc, lu, lv, step_key, net_op));
+                    assert(accepted == Some((lu.instances[instance].accept_ballot.unwrap(), lu.instances[instance].accept_value.unwrap())));
+                } else if (!u.network.sent_messages.contains(accepted_msg)) {
+                    assert(net_op.send == Some(accepted_msg) && sender == host_id);
+                    assert(lu.instances[instance].current_ballot.cmp(&future_ballot) >= 0);
+                    if (host::accept(lc, lu, lv, step_key, net_op)) {
+                        assert(accepted_ballot.cmp(&lu.instances[instance].current_ballot) >= 0);
+                    } else {
+                        // `fast_accept` runs at the fast ballot, so the instance has promised nothing above it.
+                        assert(host::fast_accept(lc, lu, lv, step_key, net_op));
+                        assert(accepted_ballot == lu.instances[instance].current_ballot);
+                    }
+                }
+            };
+        }
+
+        pub proof fn accepted_msg_in_network_implies_network_has_corresponding_accept_msg_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.accepted_msg_in_network_implies_network_has_corresponding_accept_msg(c),
+        {
+            assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
+
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+
+            assert forall |sender: nat, instance: nat, ballot: host::Ballot, value: Value| #![auto]
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot, value }) implies
+                self.accept_was_sent(instance, ballot, value)
+            by {
+                match ((net_op.recv, net_op.send)) {
+                    (Some(Message::Accept { key: recv_key, ballot: recv_ballot, value: recv_value }), Some(Message::Accepted { .. }))
+                    if (host::accept(lc, lu, lv, step_key, net_op) && (net_op.send == Some(Message::Accepted { key: instance, sender, ballot, value }))) => {
+                        assert(recv_ballot == ballot && recv_value == value);
+                        assert(u.network.sent_messages.contains(Message::Accept { key: instance, ballot, value }));
+                        assert(!host::is_fast_ballot(ballot));
+                    },
+                    (Some(Message::ClientRequest { value: recv_value }), Some(Message::Accepted { .. }))
+                    if (host::fast_accept(lc, lu, lv, step_key, net_op) && (net_op.send == Some(Message::Accepted { key: instance, sender, ballot, value }))) => {
+                        assert(ballot == host::fast_ballot() && recv_value == value);
+                        assert(u.network.sent_messages.contains(Message::ClientRequest { value }));
+                        assert(instance == host::fast_slot(value));
+                    },
+                    _ => {
+                        assert(u.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot, value }));
+                    },
+                }
+            };
+        }
+
+        // `fast_accept` needs an instance that has accepted nothing, and an acceptor that has sent an
+        // Accepted for an instance has accepted something there for good.
+        pub proof fn fast_accepted_msgs_from_same_sender_have_same_value_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.fast_accepted_msgs_from_same_sender_have_same_value(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+
+            assert forall |instance: nat, sender: nat, v1: Value, v2: Value| #![auto]
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot: host::fast_ballot(), value: v1 }) &&
+                self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot: host::fast_ballot(), value: v2 }) implies
+                v1 == v2
+            by {
+                let (m1, m2) = (Message::Accepted { key: instance, sender, ballot: host::fast_ballot(), value: v1 }, Message::Accepted { key: instance, sender, ballot: host::fast_ballot(), value: v2 });
+
+                if (u.network.sent_messages.contains(m1) != u.network.sent_messages.contains(m2)) {
+                    let old = if (u.network.sent_messages.contains(m1)) { m1 } else { m2 };
+                    assert(net_op.send == Some(if (u.network.sent_messages.contains(m1)) { m2 } else { m1 }));
+                    assert(sender == host_id);
+                    assert(u.network.sent_messages.contains(old));
+                    assert(lu.instances[instance].accept_ballot.is_some());
+                    if (host::accept(lc, lu, lv, step_key, net_op)) {
+                        // `accept` sends the ballot of an Accept, which is classic.
+                        if let Some(Message::Accept { key: recv_key, ballot, value }) = net_op.recv {
+                            assert(u.network.sent_messages.contains(Message::Accept { key: recv_key, ballot, value }));
+                            assert(ballot.num > 0);
+                        }
+                    } else {
+                        assert(host::fast_accept(lc, lu, lv, step_key, net_op));
+                        assert(lu.instances[instance].accept_ballot.is_none());
+                    }
+                }
+            };
+        }
+
+        pub proof fn all_decide_messages_hold_same_value_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.all_decide_messages_hold_same_value(c)
+        {
+            assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
+            self.decide_msg_in_network_implies_quorum_has_accepted_some_value_is_inductive(c, u, event);
+            self.network_msgs_have_valid_sender_and_ballot_pid_is_inductive(c, u, event);
+            self.value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot_is_inductive(c, u, event);
+            self.accepted_msg_in_network_implies_network_has_corresponding_accept_msg_is_inductive(c, u, event);
+            self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some_is_inductive(c, u, event);
+            self.same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts_is_inductive(c, u, event);
+            self.if_host_proposed_then_quorum_has_promised_is_inductive(c, u, event);
+            self.phase1_and_phase2_quorums_in_host_maps_are_preserved(c, u, event);
+            assert(self.accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender(c));
+            assert(self.someone_promised_implies_network_has_their_promise_msg(c));
+            assert(self.ballot_in_accepted_is_smaller_than_promise_message_ballot(c));
+            assert(self.accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted(c));
+            assert(self.if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message(c));
+            self.fast_accepted_msgs_from_same_sender_have_same_value_is_inductive(c, u, event);
+            self.configurations_only_grow(c, u, event);
+
+            assert forall |key: nat, b1: host::Ballot, v1: Value, b2: host::Ballot, v2: Value| #![auto]
+                self.network.sent_messages.contains(Message::Decide { key, ballot: b1, value: v1 }) &&
+                self.network.sent_messages.contains(Message::Decide { key, ballot: b2, value: v2 }) implies
+                v1 == v2
+            by {
+                let (past_ballot, past_value, future_ballot, future_value) = if (b1.cmp(&b2) > 0) {
+                    (b2, v2, b1, v1)
+                } else {
+                    (b1, v1, b2, v2)
+                };
+                assert(self.configurations.contains_key(key));
+
+                if (host::is_fast_ballot(future_ballot)) {
+                    // Both were decided in the fast round. The two fast quorums share an acceptor, which
+                    // took one value there.
+                    let past_senders = choose |senders: Set<nat>| #[trigger] self.fast_accepted_by(c, key, past_value, senders);
+                    let future_senders = choose |senders: Set<nat>| #[trigger] self.fast_accepted_by(c, key, future_value, senders);
+                    fast::fast_quorums_have_common_host(self.configuration(key), c.num_hosts, c.num_failures, past_senders, future_senders);
+                    let common_sender = choose |h: nat| #![auto] past_senders.contains(h) && future_senders.contains(h);
+                    assert(self.network.sent_messages.contains(Message::Accepted { key, sender: common_sender, ballot: host::fast_ballot(), value: past_value }));
+                    assert(self.network.sent_messages.contains(Message::Accepted { key, sender: common_sender, ballot: host::fast_ballot(), value: future_value }));
+                } else if (host::is_fast_ballot(past_ballot)) {
+                    assert(self.fast_quorum_has_accepted(c, key, past_value));
+                    assert(self.host_proposed(future_ballot.pid as int, key, future_ballot));
+                    self.fast_quorum_accepted_value_is_proposed_in_future(c, key, past_value, future_ballot);
+                } else {
+                    assert(self.quorum_has_accepted(c, key, past_ballot, past_value));
+                    assert(self.host_proposed(future_ballot.pid as int, key, future_ballot));
+                    self.quorum_accepted_value_is_proposed_in_future(c, key, past_ballot, past_value, future_ballot);
+                }
+            };
+        }
+
+        pub proof fn batch_msgs_in_network_carry_sent_msgs_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.batch_msgs_in_network_carry_sent_msgs(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+
+            // No single-key host step sends a batch.
+            assert(net_op.send.is_some() ==> !batch::is_batch(net_op.send.unwrap()));
+            assert(u.network.sent_messages.subset_of(self.network.sent_messages));
+        }
+    }
+
+    pub open spec fn properties_of_valid_messages_in_network(c: &Constants, u: &Variables) -> bool {
+        &&& u.network_msgs_have_valid_sender_and_ballot_pid(c)
+        &&& u.promise_msgs_from_same_sender_for_same_ballot_have_same_accepted(c)
+        &&& u.ballot_in_accepted_is_smaller_than_promise_message_ballot(c)
+        &&& u.value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot(c)
+        &&& u.if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message(c)
+        &&& u.network_has_at_most_one_accept_message_for_any_ballot(c)
+        &&& u.accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted(c)
+        &&& u.accepted_msg_in_network_implies_network_has_corresponding_accept_msg(c)
+        &&& u.fast_accepted_msgs_from_same_sender_have_same_value(c)
+        &&& u.all_decide_messages_hold_same_value(c)
+        &&& u.batch_msgs_in_network_carry_sent_msgs(c)
+    }
+
+    impl Variables {
+        pub open spec fn if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot(&self, c: &Constants) -> bool {
+            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.history[i][instance].promised.contains_key(ballot) ==>
+                    self.ballot_was_prepared(instance, ballot)
+            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.history[i][instance].proposed_value.contains_key(ballot) ==>
+                    self.ballot_was_prepared(instance, ballot)
+            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.history[i][instance].accepted.contains_key(ballot) ==>
+                    self.ballot_was_prepared(instance, ballot)
+        }
+
+        pub open spec fn proposed_some_value_and_get_max_accepted_value_is_some(&self, i: int, instance: nat, ballot: host::Ballot) -> bool {
+            &&& self.hosts[i].instances.contains_key(instance)
+            &&& self.history[i][instance].proposed_value.contains_key(ballot)
+            &&& host::get_max_accepted_value(self.history[i][instance].promised[ballot]).is_some()
+        }
+
+        // What `select_value` picks is the highest reported value unless the highest reported ballot is
+        // the fast one (`fast::select_value_at_classic_ballot`).
+        pub open spec fn if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < self.hosts.len() &&
+                #[trigger] self.proposed_some_value_and_get_max_accepted_value_is_some(i, instance, ballot) ==>
+                self.history[i][instance].proposed_value[ballot] == fast::select_value(self.configuration(instance), c.num_hosts, self.history[i][instance].promised[ballot]).unwrap().1
+        }
+
+        pub open spec fn host_accept_ballot_is_none_or_leq_to_current_ballot(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.hosts[i].instances[instance].accept_ballot.is_some() ==>
+                self.hosts[i].instances[instance].accept_ballot.unwrap().cmp(&self.hosts[i].instances[instance].current_ballot) <= 0
+        }
+
+        // Nothing a host is at, has prepared, has proposed in or has counted acceptances for is above
+        // its `max_seen_ballot`, so `host::next_ballot` is never already in its maps.
+        pub open spec fn host_ballots_are_at_most_max_seen_ballot(&self, c: &Constants) -> bool {
+            &&& forall |i: int, instance: nat| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) ==>
+                    self.hosts[i].instances[instance].current_ballot.cmp(&self.hosts[i].instances[instance].max_seen_ballot) <= 0
+            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.history[i][instance].promised.contains_key(ballot) ==>
+                    ballot.cmp(&self.hosts[i].instances[instance].max_seen_ballot) <= 0
+            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.history[i][instance].proposed_value.contains_key(ballot) ==>
+                    ballot.cmp(&self.hosts[i].instances[instance].max_seen_ballot) <= 0
+            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.history[i][instance].accepted.contains_key(ballot) ==>
+                    ballot.cmp(&self.hosts[i].instances[instance].max_seen_ballot) <= 0
+        }
+
+        pub open spec fn hosts_have_same_some_accept_ballot(&self, h1: int, h2: int, instance: nat) -> bool {
+            &&& self.hosts[h1].instances[instance].accept_ballot.is_some()
+            &&& self.hosts[h1].instances[instance].accept_ballot == self.hosts[h2].instances[instance].accept_ballot
+        }
+
+        pub open spec fn hosts_have_same_some_accept_value(&self, h1: int, h2: int, instance: nat) -> bool {
+            &&& self.hosts[h1].instances[instance].accept_value.is_some()
+            &&& self.hosts[h1].instances[instance].accept_value == self.hosts[h2].instances[instance].accept_value
+        }
+
+        // Acceptors that collided in the fast round hold different values at the fast ballot.
+        pub open spec fn any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value(&self, c: &Constants) -> bool {
+            forall |h1: int, h2: int, instance: nat|
+                0 <= h1 < self.hosts.len() &&
+                0 <= h2 < self.hosts.len() &&
+                self.hosts[h1].instances.contains_key(instance) &&
+                self.hosts[h2].instances.contains_key(instance) &&
+                #[trigger] self.hosts_have_same_some_accept_ballot(h1, h2, instance) &&
+                !host::is_fast_ballot(self.hosts[h1].instances[instance].accept_ballot.unwrap()) ==>
+                self.hosts_have_same_some_accept_value(h1, h2, instance)
+        }
+
+        pub open spec fn if_someone_has_accepted_then_someone_has_proposed(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.history[i][instance].accepted.contains_key(ballot) &&
+                self.history[i][instance].accepted[ballot].len() > 0 ==>
+                self.history[i][instance].proposed_value.contains_key(ballot)
+        }
+
+        pub open spec fn same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(&self, c: &Constants) -> bool {
+            forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.history[i][instance].promised.contains_key(ballot) ==>
+                #[trigger] host::same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(self.history[i][instance].promised[ballot])
+        }
+
+        pub proof fn if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(c),
+        {
+            assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
+
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.host_step_keeps_configurations(c, u, event, host_id, step_key, net_op);
+            self.configurations_only_grow(c, u, event);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+
+            assert forall |i: int, instance: nat, ballot: host::Ballot|
+            0 <= i < self.hosts.len() &&
+            self.hosts[i].instances.contains_key(instance) &&
+            #[trigger] self.proposed_some_value_and_get_max_accepted_value_is_some(i, instance, ballot) implies
+            self.history[i][instance].proposed_value[ballot] == fast::select_value(self.configuration(instance), c.num_hosts, self.history[i][instance].promised[ballot]).unwrap().1
+            by {
+                if ((i != host_id) || u.history[i][instance].proposed_value.contains_key(ballot)) {
+                    assert(u.proposed_some_value_and_get_max_accepted_value_is_some(i, instance, ballot));
+                }
+            };
+        }
+
+        pub proof fn any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value(c),
+        {
+            assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
+
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+
+            assert forall |h1: int, h2: int, instance: nat|
+                0 <= h1 < self.hosts.len() &&
+                0 <= h2 < self.hosts.len() &&
+                self.hosts[h1].instances.contains_key(instance) &&
+                self.hosts[h2].instances.contains_key(instance) &&
+                #[trigger] self.hosts_have_same_some_accept_ballot(h1, h2, instance) &&
+                !host::is_fast_ballot(self.hosts[h1].instances[instance].accept_ballot.unwrap()) implies
+                self.hosts_have_same_some_accept_value(h1, h2, instance)
+            by {
+                assert(self.hosts[h1].instances[instance].accept_ballot.is_some() && self.hosts[h2].instances[instance].accept_ballot.is_some());
+                assert(self.hosts[h1].instances[instance].accept_ballot == self.hosts[h2].instances[instance].accept_ballot);
+
+                self.accepted_state_implies_network_has_accept_message_is_inductive(c, u, event);
+
+                assert(self.either_of_accept_ballot_or_value_is_some(h1, instance));
+                assert(self.accept_was_sent(instance, self.hosts[h1].instances[instance].accept_ballot.unwrap(), self.hosts[h1].instances[instance].accept_value.unwrap()));
+                assert(self.network.sent_messages.contains(Message::Accept { key: instance, ballot: self.hosts[h1].instances[instance].accept_ballot.unwrap(), value: self.hosts[h1].instances[instance].accept_value.unwrap() }));
+                assert(self.either_of_accept_ballot_or_value_is_some(h2, instance));
+                assert(self.accept_was_sent(instance, self.hosts[h2].instances[instance].accept_ballot.unwrap(), self.hosts[h2].instances[instance].accept_value.unwrap()));
+                assert(self.network.sent_messages.contains(Message::Accept { key: instance, ballot: self.hosts[h2].instances[instance].accept_ballot.unwrap(), value: self.hosts[h2].instances[instance].accept_value.unwrap() }));
+            };
+        }
+
+        pub proof fn same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts_is_inductive(&self, c: &Constants, u:&Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(c)
+        {
+            assert(self.all_maps_and_sets_are_finite(c));
+
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+
+            assert forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.history[i][instance].promised.contains_key(ballot) implies
+                #[trigger] host::same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(self.history[i][instance].promised[ballot])
+            by {
+                match ((event, net_op.recv, net_op.send)) {
+                    (Event::NoOp, Some(Message::Promise { key, sender, ballot: recv_ballot, accepted }), _)
+                    if (host::promised(lc, lu, lv, step_key, net_op) && (i == host_id) && (instance == step_key) && (ballot == recv_ballot)) => {
+                        let old_accepted_map = u.history[host_id][instance].promised[ballot];
+                        let accepted_map = self.history[host_id][instance].promised[ballot];
+
+                        assert forall |s1: nat, s2: nat|
+                            accepted_map.contains_key(s1) &&
+                            accepted_map.contains_key(s2) &&
+                            accepted_map[s1].is_some() &&
+                            accepted_map[s2].is_some() &&
+                            !host::is_fast_ballot(accepted_map[s1].unwrap().0) &&
+                            #[trigger] host::accepted_map_ballots_are_same(accepted_map[s1].unwrap().0, accepted_map[s2].unwrap().0) implies
+                            accepted_map[s1].unwrap().1 == accepted_map[s2].unwrap().1
+                        by {
+                            if (accepted.is_some() && (s1 == sender || s2 == sender)) {
+                                if (s1 != s2) {
+                                    let old_sender = if (s1 != sender) { s1 } else { s2 };
+                                    assert(self.network.sent_messages.contains(Message::Promise { key: instance, sender: old_sender, ballot, accepted: accepted_map[old_sender] }));
+                                    assert(accepted_map[s1].unwrap().1 == accepted_map[s2].unwrap().1);
+                                }
+                            } else {
+                                assert(host::same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(u.history[host_id][instance].promised[ballot]));
+                                assert(host::accepted_map_ballots_are_same(old_accepted_map[s1].unwrap().0, old_accepted_map[s2].unwrap().0));
+                                assert(old_accepted_map[s1].unwrap().1 == old_accepted_map[s2].unwrap().1);
+                            }
+                        };
+                    },
+                    _ => {},
+                }
+            };
+        }
+
+        pub open spec fn host_proposed(&self, i: int, instance: nat, ballot: host::Ballot) -> bool {
+            &&& self.hosts[i].instances.contains_key(instance)
+            &&& self.history[i][instance].proposed_value.contains_key(ballot)
+        }
+
+        // A value some acceptor took straight from its client in the fast round.
+        pub open spec fn value_was_fast_proposed(&self, value: Value) -> bool {
+            exists |key: nat, sender: nat| #[trigger] self.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value })
+        }
+
+        pub open spec fn value_was_proposed(&self, value: Value) -> bool {
+            ||| exists |i: int, instance: nat, ballot: host::Ballot|
+                    0 <= i < self.hosts.len() &&
+                    #[trigger] self.host_proposed(i, instance, ballot) &&
+                    self.history[i][instance].proposed_value[ballot] == value
+            ||| self.value_was_fast_proposed(value)
+        }
+
+        pub open spec fn value_was_submitted(&self, value: Value) -> bool {
+            self.network.sent_messages.contains(Message::ClientRequest { value })
+        }
+
+        pub open spec fn submitted_values_are_not_no_ops(&self, c: &Constants) -> bool {
+            forall |value: Value| #[trigger] self.value_was_submitted(value) ==> !value.is_no_op()
+        }
+
+        pub open spec fn pending_requests_are_in_network(&self, c: &Constants) -> bool {
+            forall |i: int, j: int| #![auto]
+                0 <= i < self.hosts.len() &&
+                0 <= j < self.hosts[i].pending.len() ==>
+                self.network.sent_messages.contains(Message::ClientRequest { value: self.hosts[i].pending[j] })
         }
 
-        pub open spec fn if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message(&self, c: &Constants) -> bool {
-            forall |sender: nat, instance: nat, ballot: host::Ballot, accepted_ballot: host::Ballot, accepted_value: Value| #![auto]
-                self.network.in_flight_messages.contains(Message::Promise { key: instance, sender, ballot, accepted: Some((accepted_ballot, accepted_value)) }) ==>
-                self.network.in_flight_messages.contains(Message::Accepted { key: instance, sender, ballot: accepted_ballot })
+        // A leader proposes a request some client submitted, or the no-op of the instance. An acceptor
+        // only takes a fast client's request in the fast round.
+        pub open spec fn proposed_values_were_submitted(&self, c: &Constants) -> bool {
+            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.history[i][instance].proposed_value.contains_key(ballot) ==>
+                    self.value_was_submitted(self.history[i][instance].proposed_value[ballot]) ||
+                    self.history[i][instance].proposed_value[ballot] == no_op(instance)
+            &&& forall |key: nat, sender: nat, value: Value| #![auto]
+                    self.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value }) ==>
+                    self.value_was_submitted(value) && c.is_fast_request(value)
         }
 
-        pub open spec fn network_has_at_most_one_accept_message_for_any_ballot(&self, c: &Constants) -> bool {
-            forall |instance: nat, ballot: host::Ballot, v1: Value, v2: Value| #![auto]
-                self.network.in_flight_messages.contains(Message::Accept { key: instance, ballot, value: v1 }) &&
-                self.network.in_flight_messages.contains(Message::Accept { key: instance, ballot, value: v2 }) ==>
-                v1 == v2
+        // Fast clients skip the owner, so no queue holds their requests.
+        pub open spec fn pending_requests_are_owned_and_distinct(&self, c: &Constants) -> bool {
+            &&& forall |i: int, j: int| #![trigger self.hosts[i].pending[j]]
+                    0 <= i < self.hosts.len() &&
+                    0 <= j < self.hosts[i].pending.len() ==>
+                    (c.request_owner)(self.hosts[i].pending[j].client_id) == i &&
+                    !c.is_fast_request(self.hosts[i].pending[j])
+            &&& forall |i: int, j1: int, j2: int| #![trigger self.hosts[i].pending[j1], self.hosts[i].pending[j2]]
+                    0 <= i < self.hosts.len() &&
+                    0 <= j1 < self.hosts[i].pending.len() &&
+                    0 <= j2 < self.hosts[i].pending.len() &&
+                    j1 != j2 ==>
+                    self.hosts[i].pending[j1].request() != self.hosts[i].pending[j2].request()
         }
 
-        pub open spec fn accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted(&self, c: &Constants) -> bool {
-            forall |sender: nat, instance: nat, accepted_ballot: host::Ballot, future_ballot: host::Ballot, accepted: Option<(host::Ballot, Value)>| #![auto]
-                self.network.in_flight_messages.contains(Message::Accepted { key: instance, sender, ballot: accepted_ballot }) &&
-                future_ballot.cmp(&accepted_ballot) > 0 &&
-                self.network.in_flight_messages.contains(Message::Promise { key: instance, sender, ballot: future_ballot, accepted }) ==>
-                {
-                    &&& accepted.is_some()
-                    &&& accepted.unwrap().0.cmp(&accepted_ballot) >= 0
-                }
+        pub open spec fn pending_requests_were_not_proposed(&self, c: &Constants) -> bool {
+            forall |i: int, j: int| #![trigger self.hosts[i].pending[j]]
+                0 <= i < self.hosts.len() &&
+                0 <= j < self.hosts[i].pending.len() ==>
+                !self.value_was_proposed(self.hosts[i].pending[j])
         }
 
-        pub open spec fn accepted_msg_in_network_implies_network_has_corresponding_accept_msg(&self, c: &Constants) -> bool {
-            forall |sender: nat, instance: nat, ballot: host::Ballot| #![auto]
-                self.network.in_flight_messages.contains(Message::Accepted { key: instance, sender, ballot }) ==>
-                (exists |value: Value| #![auto] self.network.in_flight_messages.contains(Message::Accept { key: instance, ballot, value }))
+        // `receive_request` only queues a request its owner has not assigned, and `send_accept` assigns
+        // the head of the queue as it takes it out.
+        pub open spec fn pending_requests_are_not_assigned(&self, c: &Constants) -> bool {
+            forall |i: int, j: int| #![trigger self.hosts[i].pending[j]]
+                0 <= i < self.hosts.len() &&
+                0 <= j < self.hosts[i].pending.len() ==>
+                !self.hosts[i].assigned.contains(self.hosts[i].pending[j].request())
         }
 
-        pub open spec fn all_decide_messages_hold_same_value(&self, c: &Constants) -> bool {
-            forall |key: nat, b1: host::Ballot, v1: Value, b2: host::Ballot, v2: Value| #![auto]
-                self.network.in_flight_messages.contains(Message::Decide { key, ballot: b1, value: v1 }) &&
-                self.network.in_flight_messages.contains(Message::Decide { key, ballot: b2, value: v2 }) ==>
+        // The owner of every proposed request has recorded it in `assigned` and will not queue it again.
+        // No-ops are never queued and fast requests never reach their owner, so neither is recorded.
+        pub open spec fn proposed_requests_are_assigned(&self, c: &Constants) -> bool {
+            forall |value: Value| #[trigger] self.value_was_proposed(value) && !value.is_no_op() && !c.is_fast_request(value) ==>
+                self.hosts[(c.request_owner)(value.client_id) as int].assigned.contains(value.request())
+        }
+
+        // A request is proposed with one operation only, even if its client sent it with several:
+        // its owner takes it from `pending` once. Acceptors may take different operations for a fast
+        // request, but all of them for its `fast_slot`.
+        pub open spec fn proposed_requests_have_one_value(&self, c: &Constants) -> bool {
+            forall |v1: Value, v2: Value|
+                #[trigger] self.value_was_proposed(v1) &&
+                #[trigger] self.value_was_proposed(v2) &&
+                !c.is_fast_request(v1) &&
+                v1.request() == v2.request() ==>
                 v1 == v2
         }
 
-        pub proof fn network_msgs_have_valid_sender_and_ballot_pid_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        // A fast request is accepted in its `fast_slot` only (`accept_was_sent`), and a leader only
+        // re-proposes it there, when it finds it reported in its promise quorum.
+        pub open spec fn proposed_values_are_proposed_in_one_slot(&self, c: &Constants) -> bool {
+            &&& forall |i: int, k1: nat, b1: host::Ballot, j: int, k2: nat, b2: host::Ballot|
+                    0 <= i < self.hosts.len() &&
+                    0 <= j < self.hosts.len() &&
+                    #[trigger] self.host_proposed(i, k1, b1) &&
+                    #[trigger] self.host_proposed(j, k2, b2) &&
+                    self.history[i][k1].proposed_value[b1] == self.history[j][k2].proposed_value[b2] ==>
+                    k1 == k2
+            &&& forall |j: int, k: nat, b: host::Ballot|
+                    0 <= j < self.hosts.len() &&
+                    #[trigger] self.host_proposed(j, k, b) &&
+                    c.is_fast_request(self.history[j][k].proposed_value[b]) ==>
+                    k == host::fast_slot(self.history[j][k].proposed_value[b])
+        }
+
+        // A host step keeps every proposal and adds at most the one `send_accept` makes for the
+        // stepping host's current ballot.
+        pub proof fn host_step_only_adds_the_send_accept_proposal(&self, c: &Constants, u: &Variables, event: Event, host_id: int, step_key: nat, net_op: NetworkOperation)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event),
         ensures
-            self.network_msgs_have_valid_sender_and_ballot_pid(c),
+            forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < u.hosts.len() &&
+                #[trigger] u.host_proposed(i, instance, ballot) ==>
+                self.host_proposed(i, instance, ballot) &&
+                self.history[i][instance].proposed_value[ballot] == u.history[i][instance].proposed_value[ballot],
+            forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < u.hosts.len() &&
+                #[trigger] self.host_proposed(i, instance, ballot) &&
+                !u.host_proposed(i, instance, ballot) ==>
+                i == host_id &&
+                instance == step_key &&
+                host::send_accept(&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id], step_key, net_op) &&
+                ballot == u.hosts[host_id].instances[step_key].current_ballot,
         {
-            assert(self.all_maps_and_sets_are_finite(c));
-            assert(self.network.in_flight_messages.finite());
-
-            let Transition::HostStep { host_id, instance, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
             let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
 
-            assert forall |key:nat, sender: nat, ballot: host::Ballot, accepted_ballot: host::Ballot, accepted_value: Value| #![auto]
-                    self.network.in_flight_messages.contains(Message::Promise { key, sender, ballot, accepted: Some((accepted_ballot, accepted_value)) }) implies
-                    ballot.num > 0 && 0 <= accepted_ballot.pid < self.hosts.len()
+            assert forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < u.hosts.len() &&
+                #[trigger] u.host_proposed(i, instance, ballot) implies
+                self.host_proposed(i, instance, ballot) &&
+                self.history[i][instance].proposed_value[ballot] == u.history[i][instance].proposed_value[ballot]
             by {
-                assert(self.network.in_flight_messages.contains(Message::Accepted { key, sender, ballot: accepted_ballot }));
-                assert(accepted_ballot.pid < self.hosts.len());
+                if (i == host_id && instance == step_key && host::send_accept(lc, lu, lv, step_key, net_op)) {
+                    assert(ballot != lu.instances[instance].current_ballot);
+                }
+            };
+
+            assert forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < u.hosts.len() &&
+                #[trigger] self.host_proposed(i, instance, ballot) &&
+                !u.host_proposed(i, instance, ballot) implies
+                i == host_id &&
+                instance == step_key &&
+                host::send_accept(lc, lu, lv, step_key, net_op) &&
+                ballot == lu.instances[step_key].current_ballot
+            by {
+                if (host::init_request(lc, lu, lv, step_key, net_op) && i == host_id && instance == step_key) {
+                    assert(self.history[host_id][step_key].proposed_value == Map::<host::Ballot, Value>::empty());
+                }
             };
         }
 
-        pub proof fn value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        // The value `select_value` takes from a leader's promise quorum was proposed before: by the
+        // leader of the classic ballot it was reported at, or in the fast round, by a fast client for
+        // this very instance.
+        pub proof fn selected_value_was_proposed(&self, c: &Constants, i: int, instance: nat, ballot: host::Ballot)
         requires
-            inductive(c, u),
-            next(c, u, self, event),
+            inductive(c, self),
+            0 <= i < self.hosts.len(),
+            self.hosts[i].instances.contains_key(instance),
+            map_contains_key_with_phase1_quorum(self.history[i][instance].promised, ballot, self.configuration(instance).classic),
+            host::get_max_accepted_value(self.history[i][instance].promised[ballot]).is_some(),
         ensures
-            self.value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot(c),
-        {
-            assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
-            assert(self.if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message(c));
+            ({
+                let (selected_ballot, selected_value) = fast::select_value(self.configuration(instance), c.num_hosts, self.history[i][instance].promised[ballot]).unwrap();
 
-            let Transition::HostStep { host_id, instance: step_key, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
-            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+                &&& self.value_was_proposed(selected_value)
+                &&& if (host::is_fast_ballot(selected_ballot)) {
+                        &&& self.value_was_submitted(selected_value)
+                        &&& c.is_fast_request(selected_value)
+                        &&& instance == host::fast_slot(selected_value)
+                    } else {
+                        &&& self.host_proposed(selected_ballot.pid as int, instance, selected_ballot)
+                        &&& self.history[selected_ballot.pid as int][instance].proposed_value[selected_ballot] == selected_value
+                    }
+            }),
+        {
+            let accepted_map = self.history[i][instance].promised[ballot];
+            let fq = self.configuration(instance);
+            assert(quorum::are_valid_hosts(accepted_map.dom(), c.num_hosts));
+            assert(self.configurations.contains_key(instance));
+            fast::selected_value_was_reported(fq, c.num_hosts, accepted_map);
+            let sender = choose |sender: nat| #[trigger] accepted_map.contains_key(sender) && accepted_map[sender] == fast::select_value(fq, c.num_hosts, accepted_map);
+            let (selected_ballot, selected_value) = accepted_map[sender].unwrap();
+
+            assert(self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot, accepted: accepted_map[sender] }));
+            if (host::is_fast_ballot(selected_ballot)) {
+                assert(self.network.sent_messages.contains(Message::Accepted { key: instance, sender, ballot: selected_ballot, value: selected_value }));
+                assert(self.accept_was_sent(instance, selected_ballot, selected_value));
+                assert(selected_ballot == host::fast_ballot());
+                assert(self.value_was_fast_proposed(selected_value));
+            } else {
+                assert(self.host_proposed(selected_ballot.pid as int, instance, selected_ballot));
+                assert(self.history[selected_ballot.pid as int][instance].proposed_value[selected_ballot] == selected_value);
+            }
+        }
 
-            assert forall |sender: nat, instance: nat, ballot: host::Ballot, accepted_ballot: host::Ballot, accepted_value: Value| #![auto]
-                self.network.in_flight_messages.contains(Message::Promise { key: instance, sender, ballot, accepted: Some((accepted_ballot, accepted_value)) }) implies
-                {
-                    let leader = accepted_ballot.pid as int;
+        // A proposed no-op is the no-op of the instance it was proposed in: clients never submit one.
+        pub proof fn proposed_no_op_is_its_instances(&self, c: &Constants, value: Value)
+        requires
+            inductive(c, self),
+            self.value_was_proposed(value),
+            value.is_no_op(),
+        ensures
+            value == no_op(value.seq),
+            forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < self.hosts.len() &&
+                #[trigger] self.host_proposed(i, instance, ballot) &&
+                self.history[i][instance].proposed_value[ballot] == value ==>
+                instance == value.seq,
+        {
+            assert(!self.value_was_submitted(value));
+            if (self.value_was_fast_proposed(value)) {
+                let (key, sender) = choose |key: nat, sender: nat| #[trigger] self.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value });
+                assert(self.value_was_submitted(value));
+            }
+            assert forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < self.hosts.len() &&
+                #[trigger] self.host_proposed(i, instance, ballot) &&
+                self.history[i][instance].proposed_value[ballot] == value implies
+                instance == value.seq
+            by {
+                assert(value == no_op(instance));
+            };
+            let (i, instance, ballot) = choose |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < self.hosts.len() &&
+                #[trigger] self.host_proposed(i, instance, ballot) &&
+                self.history[i][instance].proposed_value[ballot] == value;
+            assert(value == no_op(instance));
+        }
 
-                    &&& 0 <= leader < self.hosts.len()
-                    &&& self.hosts[leader].instances.contains_key(instance)
-                    &&& self.hosts[leader].instances[instance].proposed_value.contains_key(accepted_ballot)
-                    &&& accepted_value == self.hosts[leader].instances[instance].proposed_value[accepted_ballot]
+        // A value that is proposed for the first time is a fast client's request an acceptor takes in
+        // its `fast_slot`, the head of the stepping host's queue, which `send_accept` moves into
+        // `assigned`, or, with the queue empty, the no-op `send_accept` proposes for its instance:
+        // re-proposals of an accepted value reported in a Promise repeat a value proposed before.
+        pub proof fn new_proposed_values_come_from_pending(&self, c: &Constants, u: &Variables, event: Event, host_id: int, step_key: nat, net_op: NetworkOperation)
+        requires
+            inductive(c, u),
+            is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event),
+        ensures
+            forall |value: Value| #[trigger] u.value_was_proposed(value) ==> self.value_was_proposed(value),
+            forall |value: Value| #[trigger] self.value_was_proposed(value) && !u.value_was_proposed(value) ==> {
+                if (host::fast_accept(&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id], step_key, net_op)) {
+                    &&& net_op.recv == Some(Message::ClientRequest { value })
+                    &&& c.is_fast_request(value)
+                    &&& step_key == host::fast_slot(value)
+                    &&& self.hosts[host_id].pending == u.hosts[host_id].pending
+                    &&& self.hosts[host_id].assigned == u.hosts[host_id].assigned
+                } else {
+                    &&& host::send_accept(&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id], step_key, net_op)
+                    &&& if (u.hosts[host_id].pending.len() > 0) {
+                            &&& value == u.hosts[host_id].pending[0]
+                            &&& self.hosts[host_id].pending == u.hosts[host_id].pending.drop_first()
+                            &&& self.hosts[host_id].assigned == u.hosts[host_id].assigned.insert(u.hosts[host_id].pending[0].request())
+                        } else {
+                            &&& value == no_op(step_key)
+                            &&& self.hosts[host_id].pending == u.hosts[host_id].pending
+                            &&& self.hosts[host_id].assigned == u.hosts[host_id].assigned
+                        }
                 }
-            by {
-                if (instance == step_key) {
-                    let leader = accepted_ballot.pid as int;
-                    assert(0 <= leader < self.hosts.len());
-                    assert(self.hosts[leader].instances[instance].proposed_value.contains_key(accepted_ballot));
+            },
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+            self.host_step_only_adds_the_send_accept_proposal(c, u, event, host_id, step_key, net_op);
+            assert(u.network.sent_messages.subset_of(self.network.sent_messages));
+            assert(lc.fast_clients == c.fast_clients);
+
+            assert forall |value: Value| #[trigger] u.value_was_proposed(value) implies self.value_was_proposed(value) by {
+                if (u.value_was_fast_proposed(value)) {
+                    let (key, sender) = choose |key: nat, sender: nat| #[trigger] u.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value });
+                    assert(self.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value }));
+                } else {
+                    let (i, instance, ballot) = choose |i: int, instance: nat, ballot: host::Ballot|
+                        0 <= i < u.hosts.len() &&
+                        #[trigger] u.host_proposed(i, instance, ballot) &&
+                        u.history[i][instance].proposed_value[ballot] == value;
+                    assert(self.host_proposed(i, instance, ballot));
+                }
+            };
 
-                    match ((event, net_op.recv, net_op.send)) {
-                        (Event::NoOp, Some(Message::Prepare { key: recv_instance, ballot: prepare_ballot }), Some(Message::Promise { key: send_instance, sender, ballot: promise_ballot, accepted }))
-                        if (host::promise(lc, lu, lv, step_key, net_op) && (sender == host_id) && (promise_ballot == prepare_ballot) && accepted.is_some() && (accepted == Some((accepted_ballot, accepted_value)))) => {
-                            assert(recv_instance == send_instance);
-                            assert(self.either_of_accept_ballot_or_value_is_some(sender as int, instance));
-                            assert(self.network.in_flight_messages.contains(Message::Accept { key: instance, ballot: lv.instances[instance].accept_ballot.unwrap(), value: lv.instances[instance].accept_value.unwrap() })) by {
-                                self.accepted_state_implies_network_has_accept_message_is_inductive(c, u, event);
-                            };
-                            assert(accepted_value == self.hosts[leader].instances[instance].proposed_value[accepted_ballot]);
-                        },
-                        _ => {},
+            assert forall |value: Value| #[trigger] self.value_was_proposed(value) && !u.value_was_proposed(value) implies {
+                if (host::fast_accept(lc, lu, lv, step_key, net_op)) {
+                    &&& net_op.recv == Some(Message::ClientRequest { value })
+                    &&& c.is_fast_request(value)
+                    &&& step_key == host::fast_slot(value)
+                    &&& lv.pending == lu.pending
+                    &&& lv.assigned == lu.assigned
+                } else {
+                    &&& host::send_accept(lc, lu, lv, step_key, net_op)
+                    &&& if (lu.pending.len() > 0) {
+                            &&& value == lu.pending[0]
+                            &&& lv.pending == lu.pending.drop_first()
+                            &&& lv.assigned == lu.assigned.insert(lu.pending[0].request())
+                        } else {
+                            &&& value == no_op(step_key)
+                            &&& lv.pending == lu.pending
+                            &&& lv.assigned == lu.assigned
+                        }
+                }
+            } by {
+                if (self.value_was_fast_proposed(value)) {
+                    // Only `fast_accept` sends an Accepted for the fast ballot: every Accept carries a
+                    // prepared one. It sends the request it took off the network.
+                    let (key, sender) = choose |key: nat, sender: nat| #[trigger] self.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value });
+                    if (u.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value })) {
+                        assert(u.value_was_fast_proposed(value));
+                    }
+                    assert(net_op.send == Some(Message::Accepted { key, sender, ballot: host::fast_ballot(), value }));
+                    if let Some(Message::Accept { key: recv_key, ballot, value: recv_value }) = net_op.recv {
+                        assert(u.network.sent_messages.contains(Message::Accept { key: recv_key, ballot, value: recv_value }));
+                        assert(ballot.num > 0);
+                    }
+                    assert(host::fast_accept(lc, lu, lv, step_key, net_op));
+                    assert(net_op.recv == Some(Message::ClientRequest { value }));
+                } else {
+                    let (i, instance, ballot) = choose |i: int, instance: nat, ballot: host::Ballot|
+                        0 <= i < self.hosts.len() &&
+                        #[trigger] self.host_proposed(i, instance, ballot) &&
+                        self.history[i][instance].proposed_value[ballot] == value;
+
+                    if (u.host_proposed(i, instance, ballot)) {
+                        assert(u.history[i][instance].proposed_value[ballot] == value);
+                        assert(u.value_was_proposed(value));
+                    }
+                    assert(i == host_id && instance == step_key && host::send_accept(lc, lu, lv, step_key, net_op));
+                    assert(!host::fast_accept(lc, lu, lv, step_key, net_op));
+                    assert(lu.instances[instance].configuration == u.configuration(instance));
+
+                    if (host::get_max_accepted_value(u.history[host_id][instance].promised[ballot]).is_some()) {
+                        assert(map_contains_key_with_phase1_quorum(u.history[host_id][instance].promised, ballot, u.configuration(instance).classic));
+                        u.selected_value_was_proposed(c, host_id, instance, ballot);
+                        assert(value == fast::select_value(u.configuration(instance), c.num_hosts, u.history[host_id][instance].promised[ballot]).unwrap().1);
+                        assert(u.value_was_proposed(value));
                     }
                 }
             };
         }
 
-        pub proof fn accepted_msg_in_network_implies_network_has_corresponding_accept_msg_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        pub proof fn pending_requests_are_in_network_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
-            self.accepted_msg_in_network_implies_network_has_corresponding_accept_msg(c),
+            self.pending_requests_are_in_network(c),
         {
-            assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
-
-            let Transition::HostStep { host_id, instance: step_key, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
             let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
 
-            assert forall |sender: nat, instance: nat, ballot: host::Ballot| #![auto]
-                self.network.in_flight_messages.contains(Message::Accepted { key: instance, sender, ballot }) implies
-                exists |value: Value| #![auto] self.network.in_flight_messages.contains(Message::Accept { key: instance, ballot, value })
+            assert forall |i: int, j: int| #![auto]
+                0 <= i < self.hosts.len() &&
+                0 <= j < self.hosts[i].pending.len() implies
+                self.network.sent_messages.contains(Message::ClientRequest { value: self.hosts[i].pending[j] })
             by {
-                match (event) {
-                    Event::NoOp => {
-                        let condition = host::send_prepare(lc, lu, lv, step_key, net_op) ||
-                            host::send_decide(lc, lu, lv, step_key, net_op) ||
-                            host::promise(lc, lu, lv, step_key, net_op) ||
-                            host::accept(lc, lu, lv, step_key, net_op) ||
-                            host::send_accept(lc, lu, lv, step_key, net_op);
-
-                        if (condition) {
-                            assert(exists |value: Value| #![auto] self.network.in_flight_messages.contains(Message::Accept { key: instance, ballot, value })) by {
-                                assert(exists |value: Value| #![auto] u.network.in_flight_messages.contains(Message::Accept { key: instance, ballot, value }));
-                                let existing_value = choose |value: Value| #![auto] u.network.in_flight_messages.contains(Message::Accept { key: instance, ballot, value });
-                                assert(self.network.in_flight_messages.contains(Message::Accept { key: instance, ballot, value: existing_value }));
-                            };
-                        }
-                    },
-                    _ => {}
+                if (i != host_id) {
+                    assert(self.hosts[i].pending == u.hosts[i].pending);
+                } else if (host::receive_request(lc, lu, lv, step_key, net_op)) {
+                    if (j < lu.pending.len()) {
+                        assert(lv.pending[j] == lu.pending[j]);
+                    }
+                } else if (host::send_accept(lc, lu, lv, step_key, net_op) && lv.pending.len() < lu.pending.len()) {
+                    assert(lv.pending[j] == lu.pending[j + 1]);
+                } else if (host::recover(lc, lu, lv, net_op)) {
+                    assert(lv.pending.len() == 0);
+                } else {
+                    assert(lv.pending == lu.pending);
                 }
             };
         }
 
-        pub proof fn all_decide_messages_hold_same_value_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        pub proof fn proposed_values_were_submitted_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
-            self.all_decide_messages_hold_same_value(c)
+            self.proposed_values_were_submitted(c),
         {
-            assert(self.all_maps_and_sets_are_finite(c));
-            assert(self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(c)) by { self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some_is_inductive(c, u, event); };
-
-            let Transition::HostStep { host_id, instance, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
             let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.host_step_keeps_configurations(c, u, event, host_id, step_key, net_op);
+            self.configurations_only_grow(c, u, event);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
 
-            assert forall |key: nat, b1: host::Ballot, v1: Value, b2: host::Ballot, v2: Value| #![auto]
-                self.network.in_flight_messages.contains(Message::Decide { key, ballot: b1, value: v1 }) &&
-                self.network.in_flight_messages.contains(Message::Decide { key, ballot: b2, value: v2 }) implies
-                v1 == v2
+            assert forall |key: nat, sender: nat, value: Value| #![auto]
+                self.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value }) implies
+                self.value_was_submitted(value) && c.is_fast_request(value)
             by {
-                let (h1, h2) = (b1.pid as int, b2.pid as int);
-                assert(0 <= h1 < self.hosts.len() && 0 <= h2 < self.hosts.len());
-                assert(map_contains_key_with_min_len(self.hosts[h1].instances[key].accepted, b1, c.num_failures));
-                assert(map_contains_key_with_min_len(self.hosts[h2].instances[key].accepted, b2, c.num_failures));
-
-                assert(self.hosts[h1].instances[key].proposed_value.contains_key(b1) && self.hosts[h2].instances[key].proposed_value.contains_key(b2));
-                assert(v1 == self.hosts[h1].instances[key].proposed_value[b1] && v2 == self.hosts[h2].instances[key].proposed_value[b2]);
+                if (!u.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value })) {
+                    // `accept` sends the ballot of an Accept, which is classic, so this is `fast_accept`
+                    // taking a fast client's request.
+                    if let Some(Message::Accept { key: recv_key, ballot, value: recv_value }) = net_op.recv {
+                        assert(u.network.sent_messages.contains(Message::Accept { key: recv_key, ballot, value: recv_value }));
+                        assert(ballot.num > 0);
+                    }
+                    assert(host::fast_accept(lc, lu, lv, step_key, net_op));
+                    assert(u.network.sent_messages.contains(Message::ClientRequest { value }));
+                    assert(lc.fast_clients == c.fast_clients);
+                } else {
+                    assert(u.value_was_submitted(value));
+                }
+            };
 
-                if (b1 != b2) {
-                    let (future_leader, future_ballot, future_value) = if (b1.cmp(&b2) > 0) {
-                        (h1, b1, v1)
-                    } else {
-                        (h2, b2, v2)
-                    };
-                    let (past_leader, past_ballot, past_value) = if (b1.cmp(&b2) > 0) {
-                        (h2, b2, v2)
+            assert forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                0 <= i < self.hosts.len() &&
+                self.hosts[i].instances.contains_key(instance) &&
+                self.history[i][instance].proposed_value.contains_key(ballot) implies
+                self.value_was_submitted(self.history[i][instance].proposed_value[ballot]) ||
+                self.history[i][instance].proposed_value[ballot] == no_op(instance)
+            by {
+                let value = self.history[i][instance].proposed_value[ballot];
+
+                if ((i == host_id) && (instance == step_key) && host::send_accept(lc, lu, lv, step_key, net_op) && (ballot == lu.instances[instance].current_ballot)) {
+                    let accepted_map = u.history[host_id][instance].promised[ballot];
+
+                    if (host::get_max_accepted_value(accepted_map).is_some()) {
+                        // A re-proposal repeats a value proposed in the same instance.
+                        assert(lu.instances[instance].configuration == u.configuration(instance));
+                        assert(map_contains_key_with_phase1_quorum(u.history[host_id][instance].promised, ballot, u.configuration(instance).classic));
+                        u.selected_value_was_proposed(c, host_id, instance, ballot);
+                        let (selected_ballot, selected_value) = fast::select_value(u.configuration(instance), c.num_hosts, accepted_map).unwrap();
+                        assert(value == selected_value);
+                        if (host::is_fast_ballot(selected_ballot)) {
+                            assert(u.value_was_submitted(value));
+                        } else {
+                            assert(u.history[selected_ballot.pid as int][instance].proposed_value[selected_ballot] == value);
+                        }
+                    } else if (lu.pending.len() > 0) {
+                        assert(u.network.sent_messages.contains(Message::ClientRequest { value: lu.pending[0] }));
                     } else {
-                        (h1, b1, v1)
-                    };
-
-                    assert(future_ballot.cmp(&past_ballot) > 0);
-                    self.accepted_system_always_proposes_same_value_in_future_is_inductive(c, u, event);
-                    assert(map_contains_key_with_min_len_and_map_contains_key(self.hosts[past_leader].instances[key].accepted, self.hosts[future_leader].instances[key].proposed_value, past_ballot, future_ballot, c.num_failures));
+                        assert(value == no_op(instance));
+                    }
+                } else {
+                    assert(u.hosts[i].instances.contains_key(instance));
+                    assert(u.history[i][instance].proposed_value.contains_key(ballot));
+                    assert(u.history[i][instance].proposed_value[ballot] == value);
                 }
 
-                assert (v1 == v2);
+                if (u.value_was_submitted(value)) {
+                    assert(self.network.sent_messages.contains(Message::ClientRequest { value }));
+                }
             };
         }
-    }
 
-    pub open spec fn properties_of_valid_messages_in_network(c: &Constants, u: &Variables) -> bool {
-        &&& u.network_msgs_have_valid_sender_and_ballot_pid(c)
-        &&& u.promise_msgs_from_same_sender_for_same_ballot_have_same_accepted(c)
-        &&& u.ballot_in_accepted_is_smaller_than_promise_message_ballot(c)
-        &&& u.value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot(c)
-        &&& u.if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message(c)
-        &&& u.network_has_at_most_one_accept_message_for_any_ballot(c)
-        &&& u.accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted(c)
-        &&& u.accepted_msg_in_network_implies_network_has_corresponding_accept_msg(c)
-        &&& u.all_decide_messages_hold_same_value(c)
-    }
+        pub proof fn submitted_values_are_not_no_ops_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.submitted_values_are_not_no_ops(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
 
-    impl Variables {
-        pub open spec fn if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot(&self, c: &Constants) -> bool {
-            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
-                    0 <= i < self.hosts.len() &&
-                    self.hosts[i].instances.contains_key(instance) &&
-                    self.hosts[i].instances[instance].promised.contains_key(ballot) ==>
-                    self.network.in_flight_messages.contains(Message::Prepare { key: instance, ballot })
-            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
-                    0 <= i < self.hosts.len() &&
-                    self.hosts[i].instances.contains_key(instance) &&
-                    self.hosts[i].instances[instance].proposed_value.contains_key(ballot) ==>
-                    self.network.in_flight_messages.contains(Message::Prepare { key: instance, ballot })
-            &&& forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
-                    0 <= i < self.hosts.len() &&
-                    self.hosts[i].instances.contains_key(instance) &&
-                    self.hosts[i].instances[instance].accepted.contains_key(ballot) ==>
-                    self.network.in_flight_messages.contains(Message::Prepare { key: instance, ballot })
+            // Only `submit_request` sends a ClientRequest, and clients do not submit no-ops.
+            assert forall |value: Value| #[trigger] self.value_was_submitted(value) implies !value.is_no_op() by {
+                if (!u.value_was_submitted(value)) {
+                    assert(net_op.send == Some(Message::ClientRequest { value }));
+                    assert(host::submit_request(lc, lu, lv, net_op, value));
+                }
+            };
         }
 
-        pub open spec fn proposed_some_value_and_get_max_accepted_value_is_some(&self, i: int, instance: nat, ballot: host::Ballot) -> bool {
-            &&& self.hosts[i].instances.contains_key(instance)
-            &&& self.hosts[i].instances[instance].proposed_value.contains_key(ballot)
-            &&& host::get_max_accepted_value(self.hosts[i].instances[instance].promised[ballot]).is_some()
-        }
+        pub proof fn pending_requests_are_owned_and_distinct_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.pending_requests_are_owned_and_distinct(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            assert(lc.id == host_id && lc.request_owner == c.request_owner && lc.fast_clients == c.fast_clients);
 
-        pub open spec fn if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(&self, c: &Constants) -> bool {
-            forall |i: int, instance: nat, ballot: host::Ballot|
+            assert forall |i: int, j: int| #![trigger self.hosts[i].pending[j]]
                 0 <= i < self.hosts.len() &&
-                #[trigger] self.proposed_some_value_and_get_max_accepted_value_is_some(i, instance, ballot) ==>
-                self.hosts[i].instances[instance].proposed_value[ballot] == host::get_max_accepted_value(self.hosts[i].instances[instance].promised[ballot]).unwrap().1
-        }
+                0 <= j < self.hosts[i].pending.len() implies
+                (c.request_owner)(self.hosts[i].pending[j].client_id) == i &&
+                !c.is_fast_request(self.hosts[i].pending[j])
+            by {
+                if (i != host_id) {
+                    assert(self.hosts[i].pending == u.hosts[i].pending);
+                } else if (host::receive_request(lc, lu, lv, step_key, net_op)) {
+                    if (j < lu.pending.len()) {
+                        assert(lv.pending[j] == lu.pending[j]);
+                    }
+                } else if (host::send_accept(lc, lu, lv, step_key, net_op) && lv.pending.len() < lu.pending.len()) {
+                    assert(lv.pending[j] == lu.pending[j + 1]);
+                } else if (host::recover(lc, lu, lv, net_op)) {
+                    assert(lv.pending.len() == 0);
+                } else {
+                    assert(lv.pending == lu.pending);
+                }
+            };
 
-        pub open spec fn host_accept_ballot_is_none_or_leq_to_current_ballot(&self, c: &Constants) -> bool {
-            forall |i: int, instance: nat| #![auto]
+            assert forall |i: int, j1: int, j2: int| #![trigger self.hosts[i].pending[j1], self.hosts[i].pending[j2]]
                 0 <= i < self.hosts.len() &&
-                self.hosts[i].instances.contains_key(instance) &&
-                self.hosts[i].instances[instance].accept_ballot.is_some() ==>
-                self.hosts[i].instances[instance].accept_ballot.unwrap().cmp(&self.hosts[i].instances[instance].current_ballot) <= 0
+                0 <= j1 < self.hosts[i].pending.len() &&
+                0 <= j2 < self.hosts[i].pending.len() &&
+                j1 != j2 implies
+                self.hosts[i].pending[j1].request() != self.hosts[i].pending[j2].request()
+            by {
+                if (i != host_id) {
+                    assert(self.hosts[i].pending == u.hosts[i].pending);
+                } else if (host::receive_request(lc, lu, lv, step_key, net_op)) {
+                    // A request is only appended when no queued entry has its `(client_id, seq)`.
+                    if (j1 < lu.pending.len()) {
+                        assert(lv.pending[j1] == lu.pending[j1]);
+                    }
+                    if (j2 < lu.pending.len()) {
+                        assert(lv.pending[j2] == lu.pending[j2]);
+                    }
+                } else if (host::send_accept(lc, lu, lv, step_key, net_op) && lv.pending.len() < lu.pending.len()) {
+                    assert(lv.pending[j1] == lu.pending[j1 + 1]);
+                    assert(lv.pending[j2] == lu.pending[j2 + 1]);
+                } else if (host::recover(lc, lu, lv, net_op)) {
+                    assert(lv.pending.len() == 0);
+                } else {
+                    assert(lv.pending == lu.pending);
+                }
+            };
         }
 
-        pub open spec fn hosts_have_same_some_accept_ballot(&self, h1: int, h2: int, instance: nat) -> bool {
-            &&& self.hosts[h1].instances[instance].accept_ballot.is_some()
-            &&& self.hosts[h1].instances[instance].accept_ballot == self.hosts[h2].instances[instance].accept_ballot
+        pub proof fn pending_requests_were_not_proposed_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.pending_requests_were_not_proposed(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            assert(lc.id == host_id && lc.request_owner == c.request_owner);
+            self.new_proposed_values_come_from_pending(c, u, event, host_id, step_key, net_op);
+
+            assert forall |i: int, j: int| #![trigger self.hosts[i].pending[j]]
+                0 <= i < self.hosts.len() &&
+                0 <= j < self.hosts[i].pending.len() implies
+                !self.value_was_proposed(self.hosts[i].pending[j])
+            by {
+                let request = self.hosts[i].pending[j];
+
+                if (i == host_id && host::receive_request(lc, lu, lv, step_key, net_op) && j == lu.pending.len()) {
+                    // Just queued: its owner, this host, had not assigned it, so nobody has proposed it.
+                    assert(lv.pending == lu.pending.push(request));
+                    assert(!lu.assigned.contains(request.request()));
+                    assert((c.request_owner)(request.client_id) == host_id);
+                    assert(!request.is_no_op() && !c.is_fast_request(request));
+                    assert(!u.value_was_proposed(request));
+                    assert(!(host::send_accept(lc, lu, lv, step_key, net_op)) && !(host::fast_accept(lc, lu, lv, step_key, net_op)));
+                } else {
+                    // Already queued, so not proposed before this step. The one value this step may
+                    // propose for the first time is a fast request, or the head of the stepping host's
+                    // queue, which is a different request: it is no longer queued, and no other host
+                    // queues it. It is not a no-op either, since every queued request was submitted.
+                    let old_index = if (i == host_id && lv.pending.len() < lu.pending.len()) { j + 1 } else { j };
+                    if (i != host_id) {
+                        assert(self.hosts[i].pending == u.hosts[i].pending);
+                    } else if (host::receive_request(lc, lu, lv, step_key, net_op)) {
+                        assert(lv.pending[j] == lu.pending[j]);
+                    } else if (lv.pending.len() < lu.pending.len()) {
+                        assert(lv.pending[j] == lu.pending[j + 1]);
+                    } else {
+                        assert(lv.pending == lu.pending);
+                    }
+                    assert(u.hosts[i].pending[old_index] == request);
+                    assert(!u.value_was_proposed(request));
+
+                    if (self.value_was_proposed(request) && host::fast_accept(lc, lu, lv, step_key, net_op)) {
+                        // The fast round only takes fast requests, and no queue holds one.
+                        assert(c.is_fast_request(request));
+                        assert(!c.is_fast_request(u.hosts[i].pending[old_index]));
+                    } else if (self.value_was_proposed(request)) {
+                        assert(u.value_was_submitted(request));
+                        assert(!request.is_no_op());
+                        assert(lu.pending.len() > 0);
+                        let head = lu.pending[0];
+                        assert(request == head);
+                        assert((c.request_owner)(head.client_id) == host_id);
+                        assert((c.request_owner)(request.client_id) == i);
+                        assert(old_index != 0);
+                        assert(lu.pending[old_index].request() == lu.pending[0].request());
+                    }
+                }
+            };
         }
 
-        pub open spec fn hosts_have_same_some_accept_value(&self, h1: int, h2: int, instance: nat) -> bool {
-            &&& self.hosts[h1].instances[instance].accept_value.is_some()
-            &&& self.hosts[h1].instances[instance].accept_value == self.hosts[h2].instances[instance].accept_value
+        pub proof fn pending_requests_are_not_assigned_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.pending_requests_are_not_assigned(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+
+            assert forall |i: int, j: int| #![trigger self.hosts[i].pending[j]]
+                0 <= i < self.hosts.len() &&
+                0 <= j < self.hosts[i].pending.len() implies
+                !self.hosts[i].assigned.contains(self.hosts[i].pending[j].request())
+            by {
+                let request = self.hosts[i].pending[j];
+
+                if (i != host_id) {
+                    assert(self.hosts[i] == u.hosts[i]);
+                } else if (host::receive_request(lc, lu, lv, step_key, net_op)) {
+                    // A request is only appended when its owner has not assigned it.
+                    if (j < lu.pending.len()) {
+                        assert(lv.pending[j] == lu.pending[j]);
+                    } else {
+                        assert(lv.pending == lu.pending.push(request));
+                    }
+                } else if (host::send_accept(lc, lu, lv, step_key, net_op) && lv.pending.len() < lu.pending.len()) {
+                    // The head moves into `assigned`; every other queued request has another identity.
+                    assert(lv.pending[j] == lu.pending[j + 1]);
+                    assert(lu.pending[j + 1].request() != lu.pending[0].request());
+                } else if (host::recover(lc, lu, lv, net_op)) {
+                    assert(lv.pending.len() == 0);
+                } else {
+                    assert(lv.pending == lu.pending);
+                    assert(lv.assigned == lu.assigned);
+                }
+            };
         }
 
-        pub open spec fn any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value(&self, c: &Constants) -> bool {
-            forall |h1: int, h2: int, instance: nat|
-                0 <= h1 < self.hosts.len() &&
-                0 <= h2 < self.hosts.len() &&
-                self.hosts[h1].instances.contains_key(instance) &&
-                self.hosts[h2].instances.contains_key(instance) &&
-                #[trigger] self.hosts_have_same_some_accept_ballot(h1, h2, instance) ==>
-                self.hosts_have_same_some_accept_value(h1, h2, instance)
+        pub proof fn proposed_requests_are_assigned_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.proposed_requests_are_assigned(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.new_proposed_values_come_from_pending(c, u, event, host_id, step_key, net_op);
+
+            // `assigned` only grows.
+            assert(forall |i: int| #![auto] 0 <= i < u.hosts.len() ==> u.hosts[i].assigned.subset_of(self.hosts[i].assigned));
+
+            assert forall |value: Value| #[trigger] self.value_was_proposed(value) && !value.is_no_op() && !c.is_fast_request(value) implies
+                self.hosts[(c.request_owner)(value.client_id) as int].assigned.contains(value.request())
+            by {
+                let owner = (c.request_owner)(value.client_id) as int;
+                assert(0 <= owner < self.hosts.len());
+
+                if (u.value_was_proposed(value)) {
+                    assert(u.hosts[owner].assigned.contains(value.request()));
+                } else {
+                    // Proposed for the first time and neither a no-op nor a fast request: the head of
+                    // this host's queue, which it owns.
+                    assert(!host::fast_accept(lc, lu, lv, step_key, net_op));
+                    assert(lu.pending.len() > 0);
+                    let head = lu.pending[0];
+                    assert(value == head);
+                    assert((c.request_owner)(head.client_id) == host_id);
+                    assert(lv.assigned.contains(value.request()));
+                }
+            };
         }
 
-        pub open spec fn if_someone_has_accepted_then_someone_has_proposed(&self, c: &Constants) -> bool {
-            forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
-                0 <= i < self.hosts.len() &&
-                self.hosts[i].instances.contains_key(instance) &&
-                self.hosts[i].instances[instance].accepted.contains_key(ballot) &&
-                self.hosts[i].instances[instance].accepted[ballot].len() > 0 ==>
-                self.hosts[i].instances[instance].proposed_value.contains_key(ballot)
+        pub proof fn proposed_requests_have_one_value_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.proposed_requests_have_one_value(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.new_proposed_values_come_from_pending(c, u, event, host_id, step_key, net_op);
+
+            assert forall |v1: Value, v2: Value|
+                #[trigger] self.value_was_proposed(v1) &&
+                #[trigger] self.value_was_proposed(v2) &&
+                !c.is_fast_request(v1) &&
+                v1.request() == v2.request() implies
+                v1 == v2
+            by {
+                let (old1, old2) = (u.value_was_proposed(v1), u.value_was_proposed(v2));
+                // Both have the same client, so neither is a fast request, and `fast_accept` takes
+                // nothing else.
+                assert(!c.is_fast_request(v2));
+
+                if (old1 != old2) {
+                    let (fresh, old) = if (old1) { (v2, v1) } else { (v1, v2) };
+                    assert(!host::fast_accept(lc, lu, lv, step_key, net_op));
+                    if (fresh.is_no_op()) {
+                        // Both are no-ops of the same instance.
+                        assert(old.is_no_op());
+                        u.proposed_no_op_is_its_instances(c, old);
+                        assert(fresh == no_op(step_key));
+                    } else {
+                        // The fresh one is the head of this host's queue. The other one was proposed
+                        // before, so its owner, this host, had already assigned the request, and
+                        // `pending_requests_are_not_assigned` says the head is not assigned.
+                        assert(lu.pending.len() > 0);
+                        let head = lu.pending[0];
+                        assert(fresh == head);
+                        assert(!old.is_no_op());
+                        assert((c.request_owner)(head.client_id) == host_id);
+                        assert(u.hosts[(c.request_owner)(old.client_id) as int].assigned.contains(old.request()));
+                        assert(lu.assigned.contains(head.request()));
+                        assert(false);
+                    }
+                } else if (!old1) {
+                    assert(!host::fast_accept(lc, lu, lv, step_key, net_op));
+                    if (lu.pending.len() > 0) {
+                        assert(v1 == lu.pending[0] && v2 == lu.pending[0]);
+                    } else {
+                        assert(v1 == no_op(step_key) && v2 == no_op(step_key));
+                    }
+                }
+            };
         }
 
-        pub open spec fn same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(&self, c: &Constants) -> bool {
-            forall |i: int, instance: nat, ballot: host::Ballot|
-                0 <= i < self.hosts.len() &&
-                self.hosts[i].instances.contains_key(instance) &&
-                self.hosts[i].instances[instance].promised.contains_key(ballot) ==>
-                #[trigger] host::same_accepted_ballots_in_accepted_map_have_same_accepted_value(self.hosts[i].instances[instance].promised[ballot])
+        // The value `send_accept` proposes is either new or was proposed before in the stepped instance
+        // only, and if it is a fast request the stepped instance is its `fast_slot`.
+        pub proof fn send_accept_proposal_was_only_proposed_in_same_slot(&self, c: &Constants, u: &Variables, event: Event, host_id: int, step_key: nat, net_op: NetworkOperation)
+        requires
+            inductive(c, u),
+            is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event),
+            host::send_accept(&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id], step_key, net_op),
+        ensures
+            ({
+                let value = self.history[host_id][step_key].proposed_value[u.hosts[host_id].instances[step_key].current_ballot];
+
+                &&& forall |p: int, k: nat, b: host::Ballot|
+                        0 <= p < u.hosts.len() &&
+                        #[trigger] u.host_proposed(p, k, b) &&
+                        u.history[p][k].proposed_value[b] == value ==>
+                        k == step_key
+                &&& c.is_fast_request(value) ==> step_key == host::fast_slot(value)
+            }),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+
+            let ballot = lu.instances[step_key].current_ballot;
+            let value = self.history[host_id][step_key].proposed_value[ballot];
+            let accepted_map = u.history[host_id][step_key].promised[ballot];
+
+            if (host::get_max_accepted_value(accepted_map).is_some()) {
+                assert(map_contains_key_with_phase1_quorum(u.history[host_id][step_key].promised, ballot, u.configuration(step_key).classic));
+                u.selected_value_was_proposed(c, host_id, step_key, ballot);
+                assert(lu.instances[step_key].configuration == u.configuration(step_key));
+                let (selected_ballot, selected_value) = fast::select_value(u.configuration(step_key), c.num_hosts, accepted_map).unwrap();
+                assert(value == selected_value);
+
+                if (host::is_fast_ballot(selected_ballot)) {
+                    // A fast request, whose every classic proposal is in its `fast_slot` as well.
+                    assert(c.is_fast_request(value) && step_key == host::fast_slot(value));
+                    assert forall |p: int, k: nat, b: host::Ballot|
+                        0 <= p < u.hosts.len() &&
+                        #[trigger] u.host_proposed(p, k, b) &&
+                        u.history[p][k].proposed_value[b] == value implies
+                        k == step_key
+                    by {
+                        assert(k == host::fast_slot(u.history[p][k].proposed_value[b]));
+                    };
+                } else {
+                    let leader = selected_ballot.pid as int;
+                    assert(u.host_proposed(leader, step_key, selected_ballot));
+                    assert(u.history[leader][step_key].proposed_value[selected_ballot] == value);
+                    if (c.is_fast_request(value)) {
+                        assert(step_key == host::fast_slot(u.history[leader][step_key].proposed_value[selected_ballot]));
+                    }
+                }
+            } else if (lu.pending.len() == 0) {
+                // The no-op of `step_key`, which only a leader of `step_key` proposes.
+                assert(value == no_op(step_key));
+                assert(host::fast_slot(value) == step_key);
+                assert(!u.value_was_submitted(value));
+                assert forall |p: int, k: nat, b: host::Ballot|
+                    0 <= p < u.hosts.len() &&
+                    #[trigger] u.host_proposed(p, k, b) &&
+                    u.history[p][k].proposed_value[b] == value implies
+                    k == step_key
+                by {
+                    assert(value == no_op(k));
+                };
+            } else {
+                // A new request, which nobody has proposed, and a queued one, so not a fast request.
+                assert(value == lu.pending[0]);
+                assert(!c.is_fast_request(u.hosts[host_id].pending[0]));
+                assert(!u.value_was_proposed(u.hosts[host_id].pending[0]));
+                assert forall |p: int, k: nat, b: host::Ballot|
+                    0 <= p < u.hosts.len() &&
+                    #[trigger] u.host_proposed(p, k, b) &&
+                    u.history[p][k].proposed_value[b] == value implies
+                    k == step_key
+                by {
+                    assert(u.value_was_proposed(value));
+                };
+            }
         }
 
-        pub proof fn if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        pub proof fn proposed_values_are_proposed_in_one_slot_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
-            self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(c),
+            self.proposed_values_are_proposed_in_one_slot(c),
         {
-            assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
-
-            let Transition::HostStep { host_id, instance: step_key, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
             let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+            self.host_step_only_adds_the_send_accept_proposal(c, u, event, host_id, step_key, net_op);
+            if (host::send_accept(lc, lu, lv, step_key, net_op)) {
+                self.send_accept_proposal_was_only_proposed_in_same_slot(c, u, event, host_id, step_key, net_op);
+            }
 
-            assert forall |i: int, instance: nat, ballot: host::Ballot|
-            0 <= i < self.hosts.len() &&
-            self.hosts[i].instances.contains_key(instance) &&
-            #[trigger] self.proposed_some_value_and_get_max_accepted_value_is_some(i, instance, ballot) implies
-            self.hosts[i].instances[instance].proposed_value[ballot] == host::get_max_accepted_value(self.hosts[i].instances[instance].promised[ballot]).unwrap().1
+            assert forall |i: int, k1: nat, b1: host::Ballot, j: int, k2: nat, b2: host::Ballot|
+                0 <= i < self.hosts.len() &&
+                0 <= j < self.hosts.len() &&
+                #[trigger] self.host_proposed(i, k1, b1) &&
+                #[trigger] self.host_proposed(j, k2, b2) &&
+                self.history[i][k1].proposed_value[b1] == self.history[j][k2].proposed_value[b2] implies
+                k1 == k2
             by {
-                if ((i != host_id) || u.hosts[i].instances[instance].proposed_value.contains_key(ballot)) {
-                    assert(u.proposed_some_value_and_get_max_accepted_value_is_some(i, instance, ballot));
+                let value = self.history[i][k1].proposed_value[b1];
+                let (old1, old2) = (u.host_proposed(i, k1, b1), u.host_proposed(j, k2, b2));
+
+                if (old1 && old2) {
+                    assert(u.history[i][k1].proposed_value[b1] == u.history[j][k2].proposed_value[b2]);
+                } else if (old1 || old2) {
+                    // One of them is the proposal `send_accept` just made in `step_key`; the other one
+                    // was there before.
+                    let (p, k, b) = if (old1) { (i, k1, b1) } else { (j, k2, b2) };
+                    let ballot = lu.instances[step_key].current_ballot;
+                    assert(u.host_proposed(p, k, b) && u.history[p][k].proposed_value[b] == value);
+                    assert(host::send_accept(lc, lu, lv, step_key, net_op));
+                    assert(self.history[host_id][step_key].proposed_value[ballot] == value);
+                    assert(k == step_key);
+                }
+            };
+
+            assert forall |j: int, k: nat, b: host::Ballot|
+                0 <= j < self.hosts.len() &&
+                #[trigger] self.host_proposed(j, k, b) &&
+                c.is_fast_request(self.history[j][k].proposed_value[b]) implies
+                k == host::fast_slot(self.history[j][k].proposed_value[b])
+            by {
+                if (u.host_proposed(j, k, b)) {
+                    assert(self.history[j][k].proposed_value[b] == u.history[j][k].proposed_value[b]);
+                } else {
+                    assert(host::send_accept(lc, lu, lv, step_key, net_op) && j == host_id && k == step_key);
+                    assert(b == lu.instances[step_key].current_ballot);
                 }
             };
         }
+    }
 
-        pub proof fn any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+    impl Variables {
+        pub proof fn host_ballots_are_at_most_max_seen_ballot_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
-            self.any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value(c),
+            self.host_ballots_are_at_most_max_seen_ballot(c),
         {
-            assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
-
-            let Transition::HostStep { host_id, instance: step_key, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
             let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+
+            // Every step keeps `max_seen_ballot` or raises it to `max_ballot` of it and the received,
+            // floor or led ballot; `send_prepare` raises it to a ballot above the old one.
+            if (lu.instances.contains_key(step_key) && lv.instances.contains_key(step_key)) {
+                let (old_max, new_max) = (lu.instances[step_key].max_seen_ballot, lv.instances[step_key].max_seen_ballot);
+                assert(old_max.cmp(&new_max) <= 0);
+                assert(lv.instances[step_key].current_ballot.cmp(&new_max) <= 0);
+
+                if (host::send_prepare(lc, lu, lv, step_key, net_op)) {
+                    assert(new_max == host::next_ballot(lc, lu.instances[step_key]));
+                    assert(old_max.cmp(&new_max) < 0);
+                } else if (host::join_prepare_from(lc, lu, lv, step_key, net_op)) {
+                    assert(new_max == host::max_ballot(old_max, lu.multi.leading.unwrap().1));
+                    assert(lu.multi.leading.unwrap().1.cmp(&new_max) <= 0);
+                }
+            }
 
-            assert forall |h1: int, h2: int, instance: nat|
-                0 <= h1 < self.hosts.len() &&
-                0 <= h2 < self.hosts.len() &&
-                self.hosts[h1].instances.contains_key(instance) &&
-                self.hosts[h2].instances.contains_key(instance) &&
-                #[trigger] self.hosts_have_same_some_accept_ballot(h1, h2, instance) implies
-                self.hosts_have_same_some_accept_value(h1, h2, instance)
+            assert forall |i: int, instance: nat, ballot: host::Ballot| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    self.history[i][instance].proposed_value.contains_key(ballot) implies
+                    ballot.cmp(&self.hosts[i].instances[instance].max_seen_ballot) <= 0
             by {
-                assert(self.hosts[h1].instances[instance].accept_ballot.is_some() && self.hosts[h2].instances[instance].accept_ballot.is_some());
-                assert(self.hosts[h1].instances[instance].accept_ballot == self.hosts[h2].instances[instance].accept_ballot);
+                if (host::send_accept(lc, lu, lv, step_key, net_op) && i == host_id && instance == step_key && ballot == lu.instances[instance].current_ballot) {
+                    assert(ballot.cmp(&lu.instances[instance].max_seen_ballot) <= 0);
+                }
+            };
+        }
 
-                self.accepted_state_implies_network_has_accept_message_is_inductive(c, u, event);
+        // The ballot `send_prepare` picks is owned by the host and is in none of its maps, not even those
+        // lost in a crash: it is above the durable `max_seen_ballot`, and so above everything in `history`.
+        pub proof fn next_ballot_is_fresh(&self, c: &Constants, i: int, instance: nat)
+        requires
+            self.well_formed(c),
+            self.host_ballots_are_at_most_max_seen_ballot(c),
+            0 <= i < self.hosts.len(),
+            self.hosts[i].instances.contains_key(instance),
+        ensures
+            ({
+                let current = self.hosts[i].instances[instance];
+                let history = self.history[i][instance];
+                let new_ballot = host::next_ballot(&c.hosts[i], current);
+
+                &&& new_ballot.pid == c.hosts[i].id
+                &&& new_ballot.cmp(&current.max_seen_ballot) > 0
+                &&& !history.promised.contains_key(new_ballot)
+                &&& !history.proposed_value.contains_key(new_ballot)
+                &&& !history.accepted.contains_key(new_ballot)
+            }),
+        {
+            let current = self.hosts[i].instances[instance];
+            let history = self.history[i][instance];
+            let new_ballot = host::next_ballot(&c.hosts[i], current);
 
-                assert(self.either_of_accept_ballot_or_value_is_some(h1, instance));
-                assert(self.network.in_flight_messages.contains(Message::Accept { key: instance, ballot: self.hosts[h1].instances[instance].accept_ballot.unwrap(), value: self.hosts[h1].instances[instance].accept_value.unwrap() }));
-                assert(self.either_of_accept_ballot_or_value_is_some(h2, instance));
-                assert(self.network.in_flight_messages.contains(Message::Accept { key: instance, ballot: self.hosts[h2].instances[instance].accept_ballot.unwrap(), value: self.hosts[h2].instances[instance].accept_value.unwrap() }));
-            };
+            assert(history.promised.contains_key(new_ballot) ==> new_ballot.cmp(&current.max_seen_ballot) <= 0);
+            assert(history.proposed_value.contains_key(new_ballot) ==> new_ballot.cmp(&current.max_seen_ballot) <= 0);
+            assert(history.accepted.contains_key(new_ballot) ==> new_ballot.cmp(&current.max_seen_ballot) <= 0);
         }
 
-        pub proof fn same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts_is_inductive(&self, c: &Constants, u:&Variables, event: Event)
+        // The floor a host has promised, and the PrepareFrom it is leading, both came from a sent
+        // PrepareFrom; the one it leads is its own.
+        pub open spec fn floors_and_leads_were_prepared_from(&self, c: &Constants) -> bool {
+            &&& forall |i: int| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].multi.promise_floor != (host::Ballot { num: 0, pid: 0 }) ==>
+                    self.network.sent_messages.contains(Message::PrepareFrom { from: self.hosts[i].multi.floor_from, ballot: self.hosts[i].multi.promise_floor })
+            &&& forall |i: int| #![auto]
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].multi.leading.is_some() ==>
+                    self.hosts[i].multi.leading.unwrap().1.pid == c.hosts[i].id &&
+                    self.network.sent_messages.contains(Message::PrepareFrom { from: self.hosts[i].multi.leading.unwrap().0, ballot: self.hosts[i].multi.leading.unwrap().1 })
+        }
+
+        pub proof fn floors_and_leads_were_prepared_from_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
-            self.same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(c)
+            self.floors_and_leads_were_prepared_from(c),
         {
-            assert(self.all_maps_and_sets_are_finite(c));
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            assert(u.network.sent_messages.subset_of(self.network.sent_messages));
+
+            // Only `promise_from` moves the floor, to a PrepareFrom it received; only `send_prepare_from`
+            // starts leading, with the PrepareFrom it sends.
+            if (host::promise_from(lc, lu, lv, step_key, net_op)) {
+                if let Some(Message::PrepareFrom { from, ballot }) = net_op.recv {
+                    assert(u.network.in_flight_messages.contains(Message::PrepareFrom { from, ballot }));
+                    assert(self.network.sent_messages.contains(Message::PrepareFrom { from, ballot }));
+                }
+            } else if (host::send_prepare_from(lc, lu, lv, step_key, net_op)) {
+                assert(self.network.sent_messages.contains(net_op.send.unwrap()));
+            } else {
+                assert(lv.multi.promise_floor == lu.multi.promise_floor && lv.multi.floor_from == lu.multi.floor_from);
+                assert(lv.multi.leading.is_none() || lv.multi.leading == lu.multi.leading);
+            }
+        }
 
-            let Transition::HostStep { host_id, instance: step_key, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
+        pub proof fn if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            self.if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot(c),
+        {
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
             let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.host_step_keeps_configurations(c, u, event, host_id, step_key, net_op);
+            self.configurations_only_grow(c, u, event);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+            assert(u.network.sent_messages.subset_of(self.network.sent_messages));
 
+            // The only ballots that enter the maps are a new Prepare's, the PrepareFrom being led, and
+            // (in `proposed_value`) the current ballot, which is already in `promised`.
             assert forall |i: int, instance: nat, ballot: host::Ballot|
-                0 <= i < self.hosts.len() &&
-                self.hosts[i].instances.contains_key(instance) &&
-                self.hosts[i].instances[instance].promised.contains_key(ballot) implies
-                #[trigger] host::same_accepted_ballots_in_accepted_map_have_same_accepted_value(self.hosts[i].instances[instance].promised[ballot])
+                    0 <= i < self.hosts.len() &&
+                    self.hosts[i].instances.contains_key(instance) &&
+                    (self.history[i][instance].promised.contains_key(ballot) ||
+                     self.history[i][instance].proposed_value.contains_key(ballot) ||
+                     self.history[i][instance].accepted.contains_key(ballot)) implies
+                    #[trigger] self.ballot_was_prepared(instance, ballot)
             by {
-                match ((event, net_op.recv, net_op.send)) {
-                    (Event::NoOp, Some(Message::Promise { key, sender, ballot: recv_ballot, accepted }), _)
-                    if (host::promised(lc, lu, lv, step_key, net_op) && (i == host_id) && (instance == step_key) && (ballot == recv_ballot)) => {
-                        let old_accepted_map = lu.instances[instance].promised[ballot];
-                        let accepted_map = lv.instances[instance].promised[ballot];
-
-                        assert forall |s1: nat, s2: nat|
-                            accepted_map.contains_key(s1) &&
-                            accepted_map.contains_key(s2) &&
-                            accepted_map[s1].is_some() &&
-                            accepted_map[s2].is_some() &&
-                            #[trigger] host::accepted_map_ballots_are_same(accepted_map[s1].unwrap().0, accepted_map[s2].unwrap().0) implies
-                            accepted_map[s1].unwrap().1 == accepted_map[s2].unwrap().1
-                        by {
-                            if (accepted.is_some() && (s1 == sender || s2 == sender)) {
-                                if (s1 != s2) {
-                                    let old_sender = if (s1 != sender) { s1 } else { s2 };
-                                    assert(self.network.in_flight_messages.contains(Message::Promise { key: instance, sender: old_sender, ballot, accepted: accepted_map[old_sender] }));
-                                    assert(accepted_map[s1].unwrap().1 == accepted_map[s2].unwrap().1);
-                                }
-                            } else {
-                                assert(host::same_accepted_ballots_in_accepted_map_have_same_accepted_value(lu.instances[instance].promised[ballot]));
-                                assert(host::accepted_map_ballots_are_same(old_accepted_map[s1].unwrap().0, old_accepted_map[s2].unwrap().0));
-                                assert(old_accepted_map[s1].unwrap().1 == old_accepted_map[s2].unwrap().1);
-                            }
-                        };
-                    },
-                    _ => {},
+                if (u.hosts[i].instances.contains_key(instance) && u.history[i][instance].promised.contains_key(ballot)) {
+                    assert(u.ballot_was_prepared(instance, ballot));
+                } else if (u.hosts[i].instances.contains_key(instance) && u.history[i][instance].accepted.contains_key(ballot)) {
+                    assert(u.ballot_was_prepared(instance, ballot));
+                } else if (u.hosts[i].instances.contains_key(instance) && u.history[i][instance].proposed_value.contains_key(ballot)) {
+                    assert(u.ballot_was_prepared(instance, ballot));
+                } else if (host::send_prepare(lc, lu, lv, step_key, net_op)) {
+                    assert(self.network.sent_messages.contains(Message::Prepare { key: instance, ballot }));
+                } else if (host::join_prepare_from(lc, lu, lv, step_key, net_op)) {
+                    let (from, lead_ballot) = lu.multi.leading.unwrap();
+                    assert(ballot == lead_ballot && from <= instance);
+                    assert(self.network.sent_messages.contains(Message::PrepareFrom { from, ballot }));
+                } else {
+                    assert(host::send_accept(lc, lu, lv, step_key, net_op));
+                    assert(u.history[i][instance].promised.contains_key(ballot));
+                    assert(u.ballot_was_prepared(instance, ballot));
+                }
+                if (u.ballot_was_prepared(instance, ballot)) {
+                    self.ballot_was_prepared_is_stable(u, instance, ballot);
                 }
             };
         }
@@ -879,42 +3333,59 @@ verus! {
 
     pub open spec fn properties_of_valid_host_states(c: &Constants, u: &Variables) -> bool {
         &&& u.if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot(c)
+        &&& u.floors_and_leads_were_prepared_from(c)
         &&& u.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(c)
         &&& u.host_accept_ballot_is_none_or_leq_to_current_ballot(c)
+        &&& u.host_ballots_are_at_most_max_seen_ballot(c)
         &&& u.any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value(c)
         &&& u.if_someone_has_accepted_then_someone_has_proposed(c)
         &&& u.same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(c)
+        &&& u.pending_requests_are_in_network(c)
+        &&& u.submitted_values_are_not_no_ops(c)
+        &&& u.proposed_values_were_submitted(c)
+        &&& u.pending_requests_are_owned_and_distinct(c)
+        &&& u.pending_requests_were_not_proposed(c)
+        &&& u.pending_requests_are_not_assigned(c)
+        &&& u.proposed_requests_are_assigned(c)
+        &&& u.proposed_requests_have_one_value(c)
+        &&& u.proposed_values_are_proposed_in_one_slot(c)
     }
 
-    pub trait HasLen {
-        spec fn get_len(&self) -> nat;
+    // The hosts a leader has heard from: senders of promises (map keys) or of acceptances (set members).
+    pub trait HasMembers {
+        spec fn members(&self) -> Set<nat>;
     }
 
-    impl<K, V> HasLen for Map<K, V> {
-        open spec fn get_len(&self) -> nat {
-            self.len()
+    impl<V> HasMembers for Map<nat, V> {
+        open spec fn members(&self) -> Set<nat> {
+            self.dom()
         }
     }
 
-    impl<V> HasLen for Set<V> {
-        open spec fn get_len(&self) -> nat {
-            self.len()
+    impl HasMembers for Set<nat> {
+        open spec fn members(&self) -> Set<nat> {
+            *self
         }
     }
 
-    pub open spec fn map_contains_key_with_min_len<K, V: HasLen>(map: Map<K, V>, key: K, min_val: nat) -> bool {
+    pub open spec fn map_contains_key_with_phase1_quorum<K, V: HasMembers>(map: Map<K, V>, key: K, quorums: quorum::QuorumSystem) -> bool {
+        &&& map.contains_key(key)
+        &&& quorums.is_phase1_quorum(map[key].members())
+    }
+
+    pub open spec fn map_contains_key_with_phase2_quorum<K, V: HasMembers>(map: Map<K, V>, key: K, quorums: quorum::QuorumSystem) -> bool {
         &&& map.contains_key(key)
-        &&& map[key].get_len() > min_val
+        &&& quorums.is_phase2_quorum(map[key].members())
     }
 
-    pub open spec fn map_contains_key_with_min_len_and_map_contains_key<K1, V1: HasLen, K2, V2>(map1: Map<K1, V1>, map2: Map<K2, V2>, key1: K1, key2: K2, min_val: nat) -> bool {
-        &&& map_contains_key_with_min_len(map1, key1, min_val)
+    pub open spec fn map_contains_key_with_phase2_quorum_and_map_contains_key<K1, V1: HasMembers, K2, V2>(map1: Map<K1, V1>, map2: Map<K2, V2>, key1: K1, key2: K2, quorums: quorum::QuorumSystem) -> bool {
+        &&& map_contains_key_with_phase2_quorum(map1, key1, quorums)
         &&& map2.contains_key(key2)
     }
 
-    pub open spec fn two_maps_contain_values_with_min_len<K1, V1: HasLen, K2, V2: HasLen>(map1: Map<K1, V1>, map2: Map<K2, V2>, key1: K1, key2: K2, min_val: nat) -> bool {
-        &&& map_contains_key_with_min_len(map1, key1, min_val)
-        &&& map_contains_key_with_min_len(map2, key2, min_val)
+    pub open spec fn two_maps_contain_phase2_and_phase1_quorums<K1, V1: HasMembers, K2, V2: HasMembers>(map1: Map<K1, V1>, map2: Map<K2, V2>, key1: K1, key2: K2, quorums: quorum::QuorumSystem) -> bool {
+        &&& map_contains_key_with_phase2_quorum(map1, key1, quorums)
+        &&& map_contains_key_with_phase1_quorum(map2, key2, quorums)
     }
 
     impl Variables {
@@ -922,8 +3393,8 @@ verus! {
             forall |i: int, instance: nat, ballot: host::Ballot|
                 0 <= i < self.hosts.len() &&
                 self.hosts[i].instances.contains_key(instance) &&
-                self.hosts[i].instances[instance].proposed_value.contains_key(ballot) ==>
-                #[trigger] map_contains_key_with_min_len(self.hosts[i].instances[instance].promised, ballot, c.num_failures)
+                self.history[i][instance].proposed_value.contains_key(ballot) ==>
+                #[trigger] map_contains_key_with_phase1_quorum(self.history[i][instance].promised, ballot, self.configuration(instance).classic)
         }
 
         pub open spec fn if_system_accepted_exists_some_accept_value_in_future_promise_quorum(&self, c: &Constants) -> bool {
@@ -932,9 +3403,9 @@ verus! {
                 0 <= h2 < self.hosts.len() &&
                 self.hosts[h1].instances.contains_key(instance) &&
                 self.hosts[h2].instances.contains_key(instance) &&
-                #[trigger] two_maps_contain_values_with_min_len(self.hosts[h1].instances[instance].accepted, self.hosts[h2].instances[instance].promised, accepted_ballot, future_ballot, c.num_failures) &&
+                #[trigger] two_maps_contain_phase2_and_phase1_quorums(self.history[h1][instance].accepted, self.history[h2][instance].promised, accepted_ballot, future_ballot, self.configuration(instance).classic) &&
                 future_ballot.cmp(&accepted_ballot) > 0 ==>
-                exists |sender: nat| #[trigger] host::map_has_key_with_some_value(self.hosts[h2].instances[instance].promised[future_ballot], sender) && self.hosts[h1].instances[instance].accepted[accepted_ballot].contains(sender)
+                exists |sender: nat| #[trigger] host::map_has_key_with_some_value(self.history[h2][instance].promised[future_ballot], sender) && self.history[h1][instance].accepted[accepted_ballot].contains(sender)
         }
 
         pub open spec fn accepted_system_calculates_same_proposed_value_in_future(self, c: &Constants) -> bool {
@@ -943,11 +3414,11 @@ verus! {
                 0 <= h2 < self.hosts.len() &&
                 self.hosts[h1].instances.contains_key(instance) &&
                 self.hosts[h2].instances.contains_key(instance) &&
-                #[trigger] two_maps_contain_values_with_min_len(self.hosts[h1].instances[instance].accepted, self.hosts[h2].instances[instance].promised, accepted_ballot, future_ballot, c.num_failures) &&
+                #[trigger] two_maps_contain_phase2_and_phase1_quorums(self.history[h1][instance].accepted, self.history[h2][instance].promised, accepted_ballot, future_ballot, self.configuration(instance).classic) &&
                 future_ballot.cmp(&accepted_ballot) > 0 ==>
                 {
-                    let old_accepted_value = self.hosts[h1].instances[instance].proposed_value[accepted_ballot];
-                    let calculated_new_proposed = host::get_max_accepted_value(self.hosts[h2].instances[instance].promised[future_ballot]);
+                    let old_accepted_value = self.history[h1][instance].proposed_value[accepted_ballot];
+                    let calculated_new_proposed = host::get_max_accepted_value(self.history[h2][instance].promised[future_ballot]);
 
                     &&& calculated_new_proposed.is_some()
                     &&& calculated_new_proposed.unwrap().1 == old_accepted_value
@@ -960,33 +3431,87 @@ verus! {
                 self.hosts[i].instances.contains_key(instance) &&
                 0 <= future_ballot.pid < self.hosts.len() &&
                 self.hosts[future_ballot.pid as int].instances.contains_key(instance) &&
-                #[trigger] map_contains_key_with_min_len_and_map_contains_key(self.hosts[i].instances[instance].accepted, self.hosts[future_ballot.pid as int].instances[instance].proposed_value, accepted_ballot, future_ballot, c.num_failures) &&
+                #[trigger] map_contains_key_with_phase2_quorum_and_map_contains_key(self.history[i][instance].accepted, self.history[future_ballot.pid as int][instance].proposed_value, accepted_ballot, future_ballot, self.configuration(instance).classic) &&
                 future_ballot.cmp(&accepted_ballot) >= 0 ==>
-                self.hosts[future_ballot.pid as int].instances[instance].proposed_value[future_ballot] == self.hosts[i].instances[instance].proposed_value[accepted_ballot]
+                self.history[future_ballot.pid as int][instance].proposed_value[future_ballot] == self.history[i][instance].proposed_value[accepted_ballot]
+        }
+
+        pub proof fn phase1_and_phase2_quorums_in_host_maps_are_preserved(&self, c: &Constants, u: &Variables, event: Event)
+        requires
+            inductive(c, u),
+            host_next(c, u, self, event),
+        ensures
+            forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < u.hosts.len() &&
+                u.hosts[i].instances.contains_key(instance) &&
+                #[trigger] map_contains_key_with_phase1_quorum(u.history[i][instance].promised, ballot, u.configuration(instance).classic) ==>
+                map_contains_key_with_phase1_quorum(self.history[i][instance].promised, ballot, self.configuration(instance).classic),
+            forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < u.hosts.len() &&
+                u.hosts[i].instances.contains_key(instance) &&
+                #[trigger] map_contains_key_with_phase2_quorum(u.history[i][instance].accepted, ballot, u.configuration(instance).classic) ==>
+                map_contains_key_with_phase2_quorum(self.history[i][instance].accepted, ballot, self.configuration(instance).classic),
+        {
+            assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
+            self.configurations_only_grow(c, u, event);
+
+            assert forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < u.hosts.len() &&
+                u.hosts[i].instances.contains_key(instance) &&
+                #[trigger] map_contains_key_with_phase1_quorum(u.history[i][instance].promised, ballot, u.configuration(instance).classic) implies
+                map_contains_key_with_phase1_quorum(self.history[i][instance].promised, ballot, self.configuration(instance).classic)
+            by {
+                let old_promises = u.history[i][instance].promised[ballot].dom();
+                let new_promises = self.history[i][instance].promised[ballot].dom();
+                assert(self.history[i][instance].promised.contains_key(ballot));
+                assert(old_promises.subset_of(new_promises));
+                assert(new_promises.finite());
+                assert(u.configurations.contains_key(instance));
+                quorum::superset_of_phase1_quorum_is_phase1_quorum(u.configuration(instance).classic, c.num_hosts, old_promises, new_promises);
+            };
+
+            assert forall |i: int, instance: nat, ballot: host::Ballot|
+                0 <= i < u.hosts.len() &&
+                u.hosts[i].instances.contains_key(instance) &&
+                #[trigger] map_contains_key_with_phase2_quorum(u.history[i][instance].accepted, ballot, u.configuration(instance).classic) implies
+                map_contains_key_with_phase2_quorum(self.history[i][instance].accepted, ballot, self.configuration(instance).classic)
+            by {
+                let old_accepted = u.history[i][instance].accepted[ballot];
+                let new_accepted = self.history[i][instance].accepted[ballot];
+                assert(self.history[i][instance].accepted.contains_key(ballot));
+                assert(old_accepted.subset_of(new_accepted));
+                assert(new_accepted.finite());
+                assert(u.configurations.contains_key(instance));
+                quorum::superset_of_phase2_quorum_is_phase2_quorum(u.configuration(instance).classic, c.num_hosts, old_accepted, new_accepted);
+            };
         }
 
         pub proof fn if_host_proposed_then_quorum_has_promised_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
             self.if_host_proposed_then_quorum_has_promised(c),
         {
             assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
+            self.phase1_and_phase2_quorums_in_host_maps_are_preserved(c, u, event);
 
-            let Transition::HostStep { host_id, instance, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
+            let (host_id, instance, net_op) = choose |host_id: int, instance: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance, net_op }, event);
             let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.host_step_keeps_configurations(c, u, event, host_id, instance, net_op);
+            self.configurations_only_grow(c, u, event);
+            self.history_follows_host_step(c, u, event, host_id, instance, net_op);
 
             assert forall |i: int, instance: nat, ballot: host::Ballot|
                 0 <= i < self.hosts.len() &&
                 self.hosts[i].instances.contains_key(instance) &&
-                self.hosts[i].instances[instance].proposed_value.contains_key(ballot) implies
-                #[trigger] map_contains_key_with_min_len(self.hosts[i].instances[instance].promised, ballot, c.num_failures)
+                self.history[i][instance].proposed_value.contains_key(ballot) implies
+                #[trigger] map_contains_key_with_phase1_quorum(self.history[i][instance].promised, ballot, self.configuration(instance).classic)
             by {
-                if (u.hosts[i].instances[instance].proposed_value.contains_key(ballot)) {
-                    assert(self.hosts[i].instances[instance].proposed_value[ballot] == u.hosts[i].instances[instance].proposed_value[ballot]);
-                    assert(map_contains_key_with_min_len(u.hosts[i].instances[instance].promised, ballot, c.num_failures));
-                    assert(map_contains_key_with_min_len(self.hosts[i].instances[instance].promised, ballot, c.num_failures));
+                if (u.history[i][instance].proposed_value.contains_key(ballot)) {
+                    assert(self.history[i][instance].proposed_value[ballot] == u.history[i][instance].proposed_value[ballot]);
+                    assert(map_contains_key_with_phase1_quorum(u.history[i][instance].promised, ballot, u.configuration(instance).classic));
+                    assert(map_contains_key_with_phase1_quorum(self.history[i][instance].promised, ballot, self.configuration(instance).classic));
                 }
             }
         }
@@ -994,100 +3519,115 @@ verus! {
         pub proof fn if_system_accepted_exists_some_accept_value_in_future_promise_quorum_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
             self.if_system_accepted_exists_some_accept_value_in_future_promise_quorum(c),
         {
-            assert(u.network.in_flight_messages.finite());
-            assert(self.network.in_flight_messages.finite());
+            assert(u.network.sent_messages.finite());
+            assert(self.network.sent_messages.finite());
             assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
 
-            let Transition::HostStep { host_id, instance, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
+            let (host_id, instance, net_op) = choose |host_id: int, instance: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance, net_op }, event);
             let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.host_step_keeps_configurations(c, u, event, host_id, instance, net_op);
+            self.configurations_only_grow(c, u, event);
+            self.history_follows_host_step(c, u, event, host_id, instance, net_op);
 
             assert forall |h1: int, h2: int, instance: nat, accepted_ballot: host::Ballot, future_ballot: host::Ballot|
                 0 <= h1 < self.hosts.len() &&
                 0 <= h2 < self.hosts.len() &&
                 self.hosts[h1].instances.contains_key(instance) &&
                 self.hosts[h2].instances.contains_key(instance) &&
-                #[trigger] two_maps_contain_values_with_min_len(self.hosts[h1].instances[instance].accepted, self.hosts[h2].instances[instance].promised, accepted_ballot, future_ballot, c.num_failures) &&
+                #[trigger] two_maps_contain_phase2_and_phase1_quorums(self.history[h1][instance].accepted, self.history[h2][instance].promised, accepted_ballot, future_ballot, self.configuration(instance).classic) &&
                 future_ballot.cmp(&accepted_ballot) > 0 implies
-                exists |sender: nat| #[trigger] host::map_has_key_with_some_value(self.hosts[h2].instances[instance].promised[future_ballot], sender) && self.hosts[h1].instances[instance].accepted[accepted_ballot].contains(sender)
+                exists |sender: nat| #[trigger] host::map_has_key_with_some_value(self.history[h2][instance].promised[future_ballot], sender) && self.history[h1][instance].accepted[accepted_ballot].contains(sender)
             by {
                 assert(self.hosts.len() == c.num_hosts);
-                assert(c.num_hosts == ((2 * c.num_failures) + 1));
-                assert(forall |x: nat| #![auto] self.hosts[h1].instances[instance].accepted[accepted_ballot].contains(x) ==> 0 <= x < c.num_hosts);
-                assert(forall |x: nat| #![auto] self.hosts[h2].instances[instance].promised[future_ballot].contains_key(x) ==> 0 <= x < c.num_hosts);
-                assert(exists |sender: nat| #![auto] self.hosts[h1].instances[instance].accepted[accepted_ballot].contains(sender) && self.hosts[h2].instances[instance].promised[future_ballot].contains_key(sender)) by {
-                    overlapping_sets_have_common_element(self.hosts[h1].instances[instance].accepted[accepted_ballot], self.hosts[h2].instances[instance].promised[future_ballot].dom(), c.num_failures, c.num_hosts);
+                assert(forall |x: nat| #![auto] self.history[h1][instance].accepted[accepted_ballot].contains(x) ==> 0 <= x < c.num_hosts);
+                assert(forall |x: nat| #![auto] self.history[h2][instance].promised[future_ballot].contains_key(x) ==> 0 <= x < c.num_hosts);
+                assert(quorum::are_valid_hosts(self.history[h1][instance].accepted[accepted_ballot], c.num_hosts));
+                assert(quorum::are_valid_hosts(self.history[h2][instance].promised[future_ballot].dom(), c.num_hosts));
+                assert(exists |sender: nat| #![auto] self.history[h1][instance].accepted[accepted_ballot].contains(sender) && self.history[h2][instance].promised[future_ballot].contains_key(sender)) by {
+                    quorum::phase1_and_phase2_quorums_have_common_host(self.configuration(instance).classic, c.num_hosts, self.history[h2][instance].promised[future_ballot].dom(), self.history[h1][instance].accepted[accepted_ballot]);
                 };
 
-                let common_sender = choose |sender: nat| #![auto] self.hosts[h1].instances[instance].accepted[accepted_ballot].contains(sender) && self.hosts[h2].instances[instance].promised[future_ballot].contains_key(sender);
-                assert(self.hosts[h1].instances[instance].accepted[accepted_ballot].contains(common_sender) && self.hosts[h2].instances[instance].promised[future_ballot].contains_key(common_sender));
+                let common_sender = choose |sender: nat| #![auto] self.history[h1][instance].accepted[accepted_ballot].contains(sender) && self.history[h2][instance].promised[future_ballot].contains_key(sender);
+                assert(self.history[h1][instance].accepted[accepted_ballot].contains(common_sender) && self.history[h2][instance].promised[future_ballot].contains_key(common_sender));
                 assert(self.hosts[common_sender as int].instances[instance].accept_value.is_some());
                 assert(
                     forall |ballot: host::Ballot, accepted: Option<(host::Ballot, Value)>| #![auto]
-                        self.network.in_flight_messages.contains(Message::Promise { key: instance, sender: common_sender, ballot, accepted }) &&
+                        self.network.sent_messages.contains(Message::Promise { key: instance, sender: common_sender, ballot, accepted }) &&
                         ballot.cmp(&accepted_ballot) > 0 ==>
                         accepted.is_some()
                 );
-                assert(self.network.in_flight_messages.contains(Message::Promise { key: instance, sender: common_sender, ballot: future_ballot, accepted: self.hosts[h2].instances[instance].promised[future_ballot][common_sender] }));
-                assert(host::map_has_key_with_some_value(self.hosts[h2].instances[instance].promised[future_ballot], common_sender));
+                assert(self.network.sent_messages.contains(Message::Promise { key: instance, sender: common_sender, ballot: future_ballot, accepted: self.history[h2][instance].promised[future_ballot][common_sender] }));
+                assert(host::map_has_key_with_some_value(self.history[h2][instance].promised[future_ballot], common_sender));
             }
         }
 
-        pub proof fn accepted_system_calculates_same_proposed_value_in_future_is_inductive_for_accepted_host_step(&self, c: &Constants, u: &Variables, h1: int, key: nat, sender: nat, accepted_ballot: host::Ballot, future_ballot: host::Ballot)
+        pub proof fn accepted_system_calculates_same_proposed_value_in_future_is_inductive_for_accepted_host_step(&self, c: &Constants, u: &Variables, h1: int, key: nat, sender: nat, accepted_ballot: host::Ballot, accepted_value: Value, future_ballot: host::Ballot)
         requires
             inductive(c, u),
-            next(c, u, self, Event::NoOp),
-            host_step(c, u, self, h1, key, NetworkOperation { recv: Some(Message::Accepted { key, sender, ballot: accepted_ballot }), send: None }, Event::NoOp),
+            host_next(c, u, self, Event::NoOp),
+            host_step(c, u, self, h1, key, NetworkOperation { recv: Some(Message::Accepted { key, sender, ballot: accepted_ballot, value: accepted_value }), send: None }, Event::NoOp),
             host::accepted(
                 &c.hosts[h1],
                 &u.hosts[h1],
                 &self.hosts[h1],
                 key,
-                NetworkOperation { recv: Some(Message::Accepted { key, sender, ballot: accepted_ballot }), send: None }
+                NetworkOperation { recv: Some(Message::Accepted { key, sender, ballot: accepted_ballot, value: accepted_value }), send: None }
             ),
-            network::step(&c.network, &u.network, &self.network, NetworkOperation { recv: Some(Message::Accepted { key, sender, ballot: accepted_ballot }), send: None }),
+            network::step(&c.network, &u.network, &self.network, NetworkOperation { recv: Some(Message::Accepted { key, sender, ballot: accepted_ballot, value: accepted_value }), send: None }),
             0 <= future_ballot.pid < u.hosts.len(),
             future_ballot.cmp(&accepted_ballot) > 0,
             u.hosts[future_ballot.pid as int].instances.contains_key(key),
-            u.hosts[future_ballot.pid as int].instances[key].promised.contains_key(future_ballot),
-            u.hosts[future_ballot.pid as int].instances[key].promised[future_ballot].len() > c.num_failures,
-            self.hosts[h1].instances[key].proposed_value.contains_key(accepted_ballot),
-            self.hosts[h1].instances[key].accepted.contains_key(accepted_ballot),
-            self.hosts[h1].instances[key].accepted[accepted_ballot].len() > c.num_failures,
+            u.history[future_ballot.pid as int][key].promised.contains_key(future_ballot),
+            u.configuration(key).classic.is_phase1_quorum(u.history[future_ballot.pid as int][key].promised[future_ballot].dom()),
+            self.history[h1][key].proposed_value.contains_key(accepted_ballot),
+            self.history[h1][key].accepted.contains_key(accepted_ballot),
+            self.configuration(key).classic.is_phase2_quorum(self.history[h1][key].accepted[accepted_ballot]),
         ensures
             ({
-                let calculated_result = host::get_max_accepted_value(u.hosts[future_ballot.pid as int].instances[key].promised[future_ballot]);
+                let calculated_result = host::get_max_accepted_value(u.history[future_ballot.pid as int][key].promised[future_ballot]);
 
                 &&& calculated_result.is_some()
-                &&& calculated_result.unwrap().1 == self.hosts[h1].instances[key].proposed_value[accepted_ballot]
+                &&& calculated_result.unwrap().1 == self.history[h1][key].proposed_value[accepted_ballot]
+                &&& !host::is_fast_ballot(calculated_result.unwrap().0)
             })
         decreases
             future_ballot.num, future_ballot.pid
         {
             self.all_maps_and_sets_are_finite(c);
+            self.if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot_is_inductive(c, u, Event::NoOp);
+            self.network_msgs_have_valid_sender_and_ballot_pid_is_inductive(c, u, Event::NoOp);
+            self.configurations_only_grow(c, u, Event::NoOp);
+            assert(u.configurations.contains_key(key));
+
+            let net_op = NetworkOperation { recv: Some(Message::Accepted { key, sender, ballot: accepted_ballot, value: accepted_value }), send: None };
+            assert(is_valid_transition(c, u, self, Transition::HostStep { host_id: h1, instance: key, net_op }, Event::NoOp)) by {
+                let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, Event::NoOp);
+            };
+            self.history_follows_host_step(c, u, Event::NoOp, h1, key, net_op);
 
             assert(host::accepted(
                 &c.hosts[h1],
                 &u.hosts[h1],
                 &self.hosts[h1],
                 key,
-                NetworkOperation { recv: Some(Message::Accepted { key, sender, ballot: accepted_ballot }), send: None }
+                NetworkOperation { recv: Some(Message::Accepted { key, sender, ballot: accepted_ballot, value: accepted_value }), send: None }
             ));
 
             let instance = key;
             let h2 = future_ballot.pid as int;
-            let accepted_map = u.hosts[h2].instances[instance].promised[future_ballot];
+            let accepted_map = u.history[h2][instance].promised[future_ballot];
 
             assert(self.if_system_accepted_exists_some_accept_value_in_future_promise_quorum(c)) by {
                 self.if_system_accepted_exists_some_accept_value_in_future_promise_quorum_is_inductive(c, u, Event::NoOp);
             };
-            assert(two_maps_contain_values_with_min_len(self.hosts[h1].instances[instance].accepted, self.hosts[h2].instances[instance].promised, accepted_ballot, future_ballot, c.num_failures));
-            let common_sender = choose |s: nat| #[trigger] host::map_has_key_with_some_value(accepted_map, s) && self.hosts[h1].instances[instance].accepted[accepted_ballot].contains(s);
+            assert(two_maps_contain_phase2_and_phase1_quorums(self.history[h1][instance].accepted, self.history[h2][instance].promised, accepted_ballot, future_ballot, self.configuration(instance).classic));
+            let common_sender = choose |s: nat| #[trigger] host::map_has_key_with_some_value(accepted_map, s) && self.history[h1][instance].accepted[accepted_ballot].contains(s);
             let (common_sender_ballot, common_sender_value) = accepted_map[common_sender].unwrap();
-            assert(self.network.in_flight_messages.contains(Message::Promise { key: instance, sender: common_sender, ballot: future_ballot, accepted: self.hosts[h2].instances[instance].promised[future_ballot][common_sender] }));
+            assert(self.network.sent_messages.contains(Message::Promise { key: instance, sender: common_sender, ballot: future_ballot, accepted: self.history[h2][instance].promised[future_ballot][common_sender] }));
             assert(common_sender_ballot.cmp(&accepted_ballot) >= 0);
 
             host::get_max_accepted_value_is_some_if_accepted_map_has_sender_with_value_as_some_value(accepted_map);
@@ -1102,32 +3642,38 @@ verus! {
             host::get_max_accepted_ballot_corresponds_to_largest_ballot(accepted_map);
             assert(calculated_ballot == largest_sender_ballot);
 
+            assert(self.hosts[h1].instances.contains_key(key));
+            self.accepted_ballots_are_classic(c, h1, key, accepted_ballot);
+            assert(largest_sender_ballot.cmp(&common_sender_ballot) >= 0);
+            assert(largest_sender_ballot.cmp(&accepted_ballot) >= 0);
+            assert(!host::is_fast_ballot(largest_sender_ballot));
+
             host::get_max_accepted_value_is_some_implies_accepted_map_has_corresponding_sender(accepted_map);
             let calculated_sender = choose |s: nat| #[trigger] host::map_has_key_with_some_value_same_as_get_max_accepted_value(accepted_map, s);
             assert(self.same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(c));
-            assert(host::same_accepted_ballots_in_accepted_map_have_same_accepted_value(accepted_map));
+            assert(host::same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(accepted_map));
             assert(host::accepted_map_ballots_are_same(accepted_map[calculated_sender].unwrap().0, accepted_map[largest_sender].unwrap().0));
             assert(accepted_map[calculated_sender] == accepted_map[largest_sender]);
             assert(calculated_value == largest_sender_value);
 
-            assert(largest_sender_ballot.cmp(&accepted_ballot) >= 0);
-
             if (largest_sender_ballot == accepted_ballot) {
-                assert(calculated_value == self.hosts[h1].instances[instance].proposed_value[accepted_ballot]);
+                assert(calculated_value == self.history[h1][instance].proposed_value[accepted_ballot]);
             } else {
-                assert(self.network.in_flight_messages.contains(Message::Promise { key, sender: largest_sender, ballot: future_ballot, accepted: Some((largest_sender_ballot, largest_sender_value)) }));
-                assert(largest_sender_value == self.hosts[largest_sender_ballot.pid as int].instances[instance].proposed_value[largest_sender_ballot]);
+                assert(self.network.sent_messages.contains(Message::Promise { key, sender: largest_sender, ballot: future_ballot, accepted: Some((largest_sender_ballot, largest_sender_value)) }));
+                assert(largest_sender_value == self.history[largest_sender_ballot.pid as int][instance].proposed_value[largest_sender_ballot]);
 
                 assert(decreases_to!(future_ballot.num, future_ballot.pid => largest_sender_ballot.num, largest_sender_ballot.pid));
-                self.accepted_system_calculates_same_proposed_value_in_future_is_inductive_for_accepted_host_step(c, u, h1, key, sender, accepted_ballot, largest_sender_ballot);
-                let old_calculated_result = host::get_max_accepted_value(u.hosts[largest_sender_ballot.pid as int].instances[instance].promised[largest_sender_ballot]);
+                self.accepted_system_calculates_same_proposed_value_in_future_is_inductive_for_accepted_host_step(c, u, h1, key, sender, accepted_ballot, accepted_value, largest_sender_ballot);
+                let old_calculated_result = host::get_max_accepted_value(u.history[largest_sender_ballot.pid as int][instance].promised[largest_sender_ballot]);
                 assert(old_calculated_result.is_some());
                 let (old_result_ballot, old_result_value) = old_calculated_result.unwrap();
-                assert(old_result_value == self.hosts[h1].instances[instance].proposed_value[accepted_ballot]);
+                assert(old_result_value == self.history[h1][instance].proposed_value[accepted_ballot]);
 
+                assert(self.history[largest_sender_ballot.pid as int][instance].promised == u.history[largest_sender_ballot.pid as int][instance].promised);
+                fast::select_value_at_classic_ballot(u.configuration(instance), c.num_hosts, u.history[largest_sender_ballot.pid as int][instance].promised[largest_sender_ballot]);
                 self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some_is_inductive(c, u, Event::NoOp);
                 assert(self.proposed_some_value_and_get_max_accepted_value_is_some(largest_sender_ballot.pid as int, instance, largest_sender_ballot));
-                assert(self.hosts[largest_sender_ballot.pid as int].instances[instance].proposed_value[largest_sender_ballot] == old_result_value);
+                assert(self.history[largest_sender_ballot.pid as int][instance].proposed_value[largest_sender_ballot] == old_result_value);
 
                 assert(largest_sender_value == old_result_value);
             }
@@ -1136,72 +3682,83 @@ verus! {
         pub proof fn accepted_system_calculates_same_proposed_value_in_future_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
             self.accepted_system_calculates_same_proposed_value_in_future(c),
         {
             assert(self.all_maps_and_sets_are_finite(c));
             assert(self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(c)) by { self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some_is_inductive(c, u, event); };
+            self.if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot_is_inductive(c, u, event);
+            self.network_msgs_have_valid_sender_and_ballot_pid_is_inductive(c, u, event);
 
-            let Transition::HostStep { host_id, instance: step_key, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
+            let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance: step_key, net_op }, event);
             let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.host_step_keeps_configurations(c, u, event, host_id, step_key, net_op);
+            self.configurations_only_grow(c, u, event);
+            self.history_follows_host_step(c, u, event, host_id, step_key, net_op);
 
             assert forall |h1: int, h2: int, instance: nat, accepted_ballot: host::Ballot, future_ballot: host::Ballot|
                 0 <= h1 < self.hosts.len() &&
                 0 <= h2 < self.hosts.len() &&
                 self.hosts[h1].instances.contains_key(instance) &&
                 self.hosts[h2].instances.contains_key(instance) &&
-                #[trigger] two_maps_contain_values_with_min_len(self.hosts[h1].instances[instance].accepted, self.hosts[h2].instances[instance].promised, accepted_ballot, future_ballot, c.num_failures) &&
+                #[trigger] two_maps_contain_phase2_and_phase1_quorums(self.history[h1][instance].accepted, self.history[h2][instance].promised, accepted_ballot, future_ballot, self.configuration(instance).classic) &&
                 future_ballot.cmp(&accepted_ballot) > 0 implies
                 {
-                    let old_accepted_value = self.hosts[h1].instances[instance].proposed_value[accepted_ballot];
-                    let calculated_new_proposed = host::get_max_accepted_value(self.hosts[h2].instances[instance].promised[future_ballot]);
+                    let old_accepted_value = self.history[h1][instance].proposed_value[accepted_ballot];
+                    let calculated_new_proposed = host::get_max_accepted_value(self.history[h2][instance].promised[future_ballot]);
 
                     &&& calculated_new_proposed.is_some()
                     &&& calculated_new_proposed.unwrap().1 == old_accepted_value
                 }
             by {
-                let old_accepted_value = self.hosts[h1].instances[instance].proposed_value[accepted_ballot];
-                assert(old_accepted_value == u.hosts[h1].instances[instance].proposed_value[accepted_ballot]);
-                let calculated_new_proposed = host::get_max_accepted_value(self.hosts[h2].instances[instance].promised[future_ballot]);
+                let old_accepted_value = self.history[h1][instance].proposed_value[accepted_ballot];
+                assert(old_accepted_value == u.history[h1][instance].proposed_value[accepted_ballot]);
+                let calculated_new_proposed = host::get_max_accepted_value(self.history[h2][instance].promised[future_ballot]);
+                self.accepted_ballots_are_classic(c, h1, instance, accepted_ballot);
 
                 self.if_system_accepted_exists_some_accept_value_in_future_promise_quorum_is_inductive(c, u, event);
-                let common_sender = choose |s: nat| #[trigger] host::map_has_key_with_some_value(self.hosts[h2].instances[instance].promised[future_ballot], s) && self.hosts[h1].instances[instance].accepted[accepted_ballot].contains(s);
-                let (common_sender_ballot, common_sender_value) = self.hosts[h2].instances[instance].promised[future_ballot][common_sender].unwrap();
-                assert(self.network.in_flight_messages.contains(Message::Promise { key: instance, sender: common_sender, ballot: future_ballot, accepted: self.hosts[h2].instances[instance].promised[future_ballot][common_sender] }));
+                let common_sender = choose |s: nat| #[trigger] host::map_has_key_with_some_value(self.history[h2][instance].promised[future_ballot], s) && self.history[h1][instance].accepted[accepted_ballot].contains(s);
+                let (common_sender_ballot, common_sender_value) = self.history[h2][instance].promised[future_ballot][common_sender].unwrap();
+                assert(self.network.sent_messages.contains(Message::Promise { key: instance, sender: common_sender, ballot: future_ballot, accepted: self.history[h2][instance].promised[future_ballot][common_sender] }));
                 assert(common_sender_ballot.cmp(&accepted_ballot) >= 0);
 
-                host::get_max_accepted_value_is_some_if_accepted_map_has_sender_with_value_as_some_value(self.hosts[h2].instances[instance].promised[future_ballot]);
+                host::get_max_accepted_value_is_some_if_accepted_map_has_sender_with_value_as_some_value(self.history[h2][instance].promised[future_ballot]);
                 assert(calculated_new_proposed.is_some());
                 let (calculated_ballot, calculated_value) = calculated_new_proposed.unwrap();
 
                 match ((event, net_op.recv, net_op.send)) {
                     (Event::NoOp, _, Some(Message::Prepare { key: send_key, ballot })) if (host::send_prepare(lc, lu, lv, step_key, net_op) && (instance == step_key)) => {
                         assert(ballot != accepted_ballot);
-                        assert(two_maps_contain_values_with_min_len(u.hosts[h1].instances[instance].accepted, u.hosts[h2].instances[instance].promised, accepted_ballot, future_ballot, c.num_failures));
+                        assert(two_maps_contain_phase2_and_phase1_quorums(u.history[h1][instance].accepted, u.history[h2][instance].promised, accepted_ballot, future_ballot, u.configuration(instance).classic));
+                        assert(calculated_value == old_accepted_value);
+                    },
+                    (Event::NoOp, None, None) if (host::join_prepare_from(lc, lu, lv, step_key, net_op) && (instance == step_key)) => {
+                        let ballot = lu.multi.leading.unwrap().1;
+                        assert(ballot != accepted_ballot);
+                        assert(two_maps_contain_phase2_and_phase1_quorums(u.history[h1][instance].accepted, u.history[h2][instance].promised, accepted_ballot, future_ballot, u.configuration(instance).classic));
                         assert(calculated_value == old_accepted_value);
                     },
                     (Event::NoOp, Some(Message::Promise { key:recv_key, sender, ballot, accepted }), _) if (host::promised(lc, lu, lv, step_key, net_op) && (h2 == host_id) && (instance == step_key)) => {
                         if (ballot == future_ballot) {
-                            let old_accepted_map = lu.instances[instance].promised[future_ballot];
-                            let new_accepted_map = lu.instances[instance].promised[future_ballot].insert(sender, accepted);
-                            assert(lv.instances[instance].promised[future_ballot] == new_accepted_map);
-                            assert(old_accepted_map.len() >= c.num_failures);
+                            let old_accepted_map = u.history[host_id][instance].promised[future_ballot];
+                            let new_accepted_map = u.history[host_id][instance].promised[future_ballot].insert(sender, accepted);
+                            assert(self.history[host_id][instance].promised[future_ballot] == new_accepted_map);
 
-                            assert(host::same_accepted_ballots_in_accepted_map_have_same_accepted_value(new_accepted_map)) by {
+                            assert(host::same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(new_accepted_map)) by {
                                 self.same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts_is_inductive(c, u, event);
                             };
 
                             if (old_accepted_map.contains_key(sender)) {
-                                assert(self.network.in_flight_messages.contains(Message::Promise { key: instance, sender, ballot: future_ballot, accepted: old_accepted_map[sender] }));
+                                assert(self.network.sent_messages.contains(Message::Promise { key: instance, sender, ballot: future_ballot, accepted: old_accepted_map[sender] }));
                                 assert(new_accepted_map == old_accepted_map);
-                                assert(two_maps_contain_values_with_min_len(self.hosts[h1].instances[instance].accepted, u.hosts[h2].instances[instance].promised, accepted_ballot, future_ballot, c.num_failures));
+                                assert(two_maps_contain_phase2_and_phase1_quorums(self.history[h1][instance].accepted, u.history[h2][instance].promised, accepted_ballot, future_ballot, self.configuration(instance).classic));
                                 assert(calculated_value == old_accepted_value);
                             } else {
                                 host::if_accepted_map_has_sender_with_value_as_some_then_larget_accepted_ballot_sender_exists(new_accepted_map);
                                 let largest_sender = choose |largest_sender: nat| #[trigger] host::is_largest_accepted_ballot_sender(new_accepted_map, largest_sender);
                                 let (largest_sender_ballot, largest_sender_value) = new_accepted_map[largest_sender].unwrap();
-                                assert(self.network.in_flight_messages.contains(Message::Promise { key: instance, sender: largest_sender, ballot: future_ballot, accepted: new_accepted_map[largest_sender] }));
+                                assert(self.network.sent_messages.contains(Message::Promise { key: instance, sender: largest_sender, ballot: future_ballot, accepted: new_accepted_map[largest_sender] }));
                                 assert(largest_sender_ballot != ballot);
 
                                 host::get_max_accepted_ballot_corresponds_to_largest_ballot(new_accepted_map);
@@ -1209,45 +3766,48 @@ verus! {
 
                                 host::get_max_accepted_value_is_some_implies_accepted_map_has_corresponding_sender(new_accepted_map);
                                 let calculated_sender = choose |s: nat| #[trigger] host::map_has_key_with_some_value_same_as_get_max_accepted_value(new_accepted_map, s);
+                                assert(largest_sender_ballot.cmp(&common_sender_ballot) >= 0);
+                                assert(!host::is_fast_ballot(largest_sender_ballot));
                                 assert(self.same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(c));
-                                assert(host::same_accepted_ballots_in_accepted_map_have_same_accepted_value(new_accepted_map));
+                                assert(host::same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(new_accepted_map));
                                 assert(host::accepted_map_ballots_are_same(new_accepted_map[calculated_sender].unwrap().0, new_accepted_map[largest_sender].unwrap().0));
                                 assert(calculated_value == largest_sender_value);
                                 assert(largest_sender_ballot.cmp(&accepted_ballot) >= 0);
 
                                 let largest_sender_ballot_leader = largest_sender_ballot.pid as int;
-                                assert(largest_sender_value == self.hosts[largest_sender_ballot_leader].instances[instance].proposed_value[largest_sender_ballot]);
+                                assert(largest_sender_value == self.history[largest_sender_ballot_leader][instance].proposed_value[largest_sender_ballot]);
 
                                 if (largest_sender_ballot == accepted_ballot) {
                                     assert(calculated_value == old_accepted_value);
                                 } else {
                                     assert(largest_sender_ballot.cmp(&accepted_ballot) > 0);
-                                    assert(two_maps_contain_values_with_min_len(u.hosts[h1].instances[instance].accepted, u.hosts[largest_sender_ballot_leader].instances[instance].promised, accepted_ballot, largest_sender_ballot, c.num_failures));
-                                    assert(host::get_max_accepted_value(u.hosts[largest_sender_ballot_leader].instances[instance].promised[largest_sender_ballot]).unwrap().1 == old_accepted_value);
+                                    assert(two_maps_contain_phase2_and_phase1_quorums(u.history[h1][instance].accepted, u.history[largest_sender_ballot_leader][instance].promised, accepted_ballot, largest_sender_ballot, u.configuration(instance).classic));
+                                    assert(host::get_max_accepted_value(u.history[largest_sender_ballot_leader][instance].promised[largest_sender_ballot]).unwrap().1 == old_accepted_value);
+                                    u.get_max_after_accepted_ballot_is_classic(c, h1, largest_sender_ballot_leader, instance, accepted_ballot, largest_sender_ballot);
                                     assert(u.proposed_some_value_and_get_max_accepted_value_is_some(largest_sender_ballot_leader, instance, largest_sender_ballot));
-                                    assert(u.hosts[largest_sender_ballot_leader].instances[instance].proposed_value[largest_sender_ballot] == old_accepted_value);
+                                    assert(u.history[largest_sender_ballot_leader][instance].proposed_value[largest_sender_ballot] == old_accepted_value);
                                     assert(calculated_value == old_accepted_value);
                                 }
                             }
                         } else {
-                            assert(two_maps_contain_values_with_min_len(u.hosts[h1].instances[instance].accepted, u.hosts[h2].instances[instance].promised, accepted_ballot, future_ballot, c.num_failures));
+                            assert(two_maps_contain_phase2_and_phase1_quorums(u.history[h1][instance].accepted, u.history[h2][instance].promised, accepted_ballot, future_ballot, u.configuration(instance).classic));
                             assert(calculated_value == old_accepted_value);
                         }
                     },
-                    (Event::NoOp, Some(Message::Accepted { key:recv_key, sender, ballot }), _) if (host::accepted(lc, lu, lv, step_key, net_op) && (h1 == host_id) && (instance == step_key)) => {
-                        assert(self.hosts[h1].instances[instance].proposed_value == u.hosts[h1].instances[instance].proposed_value && self.hosts[h2].instances[instance].proposed_value == u.hosts[h2].instances[instance].proposed_value);
-                        assert(self.hosts[h1].instances[instance].promised == u.hosts[h1].instances[instance].promised && self.hosts[h2].instances[instance].promised == u.hosts[h2].instances[instance].promised);
+                    (Event::NoOp, Some(Message::Accepted { key:recv_key, sender, ballot, value: recv_value }), _) if (host::accepted(lc, lu, lv, step_key, net_op) && (h1 == host_id) && (instance == step_key)) => {
+                        assert(self.history[h1][instance].proposed_value == u.history[h1][instance].proposed_value && self.history[h2][instance].proposed_value == u.history[h2][instance].proposed_value);
+                        assert(self.history[h1][instance].promised == u.history[h1][instance].promised && self.history[h2][instance].promised == u.history[h2][instance].promised);
 
-                        if ((ballot == accepted_ballot) && (lu.instances[instance].accepted[ballot].len() == c.num_failures)) {
+                        if ((ballot == accepted_ballot) && !map_contains_key_with_phase2_quorum(u.history[host_id][instance].accepted, ballot, u.configuration(instance).classic)) {
                             assert(ballot != future_ballot);
 
-                            let old_accepted_hosts = lu.instances[instance].accepted[ballot];
-                            let new_accepted_hosts = lu.instances[instance].accepted[ballot].insert(sender);
-                            assert(lv.instances[instance].accepted[ballot] == new_accepted_hosts);
+                            let old_accepted_hosts = u.history[host_id][instance].accepted[ballot];
+                            let new_accepted_hosts = u.history[host_id][instance].accepted[ballot].insert(sender);
+                            assert(self.history[host_id][instance].accepted[ballot] == new_accepted_hosts);
 
-                            let accepted_map = self.hosts[h2].instances[instance].promised[future_ballot];
+                            let accepted_map = self.history[h2][instance].promised[future_ballot];
 
-                            assert(host::same_accepted_ballots_in_accepted_map_have_same_accepted_value(accepted_map));
+                            assert(host::same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(accepted_map));
                             host::if_accepted_map_has_sender_with_value_as_some_then_larget_accepted_ballot_sender_exists(accepted_map);
                             let largest_sender = choose |largest_sender: nat| #[trigger] host::is_largest_accepted_ballot_sender(accepted_map, largest_sender);
                             let (largest_sender_ballot, largest_sender_value) = accepted_map[largest_sender].unwrap();
@@ -1257,16 +3817,18 @@ verus! {
 
                             host::get_max_accepted_value_is_some_implies_accepted_map_has_corresponding_sender(accepted_map);
                             let calculated_sender = choose |s: nat| #[trigger] host::map_has_key_with_some_value_same_as_get_max_accepted_value(accepted_map, s);
+                            assert(largest_sender_ballot.cmp(&common_sender_ballot) >= 0);
+                            assert(!host::is_fast_ballot(largest_sender_ballot));
                             assert(self.same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(c));
-                            assert(host::same_accepted_ballots_in_accepted_map_have_same_accepted_value(accepted_map));
+                            assert(host::same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(accepted_map));
                             assert(host::accepted_map_ballots_are_same(accepted_map[calculated_sender].unwrap().0, accepted_map[largest_sender].unwrap().0));
                             assert(accepted_map[calculated_sender] == accepted_map[largest_sender]);
                             assert(calculated_value == largest_sender_value);
 
-                            self.accepted_system_calculates_same_proposed_value_in_future_is_inductive_for_accepted_host_step(c, u, h1, step_key, sender, accepted_ballot, future_ballot);
+                            self.accepted_system_calculates_same_proposed_value_in_future_is_inductive_for_accepted_host_step(c, u, h1, step_key, sender, accepted_ballot, recv_value, future_ballot);
                             assert(largest_sender_value == old_accepted_value);
                         } else {
-                            assert(two_maps_contain_values_with_min_len(lu.instances[instance].accepted, u.hosts[h2].instances[instance].promised, accepted_ballot, future_ballot, c.num_failures));
+                            assert(two_maps_contain_phase2_and_phase1_quorums(u.history[host_id][instance].accepted, u.history[h2][instance].promised, accepted_ballot, future_ballot, u.configuration(instance).classic));
                             assert(calculated_value == old_accepted_value);
                         }
                     },
@@ -1278,46 +3840,290 @@ verus! {
         pub proof fn accepted_system_always_proposes_same_value_in_future_is_inductive(&self, c: &Constants, u: &Variables, event: Event)
         requires
             inductive(c, u),
-            next(c, u, self, event),
+            host_next(c, u, self, event),
         ensures
             self.accepted_system_always_proposes_same_value_in_future(c),
         {
             assert(host_map_properties(c, self)) by { self.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
             assert(self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(c)) by { self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some_is_inductive(c, u, event); };
 
-            let Transition::HostStep { host_id, instance, net_op } = choose |transition: Transition| is_valid_transition(c, u, self, transition, event);
+            let (host_id, instance, net_op) = choose |host_id: int, instance: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, self, Transition::HostStep { host_id, instance, net_op }, event);
             let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            self.host_step_keeps_configurations(c, u, event, host_id, instance, net_op);
+            self.configurations_only_grow(c, u, event);
+            self.history_follows_host_step(c, u, event, host_id, instance, net_op);
 
             assert forall |i: int, instance: nat, accepted_ballot: host::Ballot, future_ballot: host::Ballot|
                 0 <= i < self.hosts.len() &&
                 self.hosts[i].instances.contains_key(instance) &&
                 0 <= future_ballot.pid < self.hosts.len() &&
                 self.hosts[future_ballot.pid as int].instances.contains_key(instance) &&
-                #[trigger] map_contains_key_with_min_len_and_map_contains_key(self.hosts[i].instances[instance].accepted, self.hosts[future_ballot.pid as int].instances[instance].proposed_value, accepted_ballot, future_ballot, c.num_failures) &&
+                #[trigger] map_contains_key_with_phase2_quorum_and_map_contains_key(self.history[i][instance].accepted, self.history[future_ballot.pid as int][instance].proposed_value, accepted_ballot, future_ballot, self.configuration(instance).classic) &&
                 future_ballot.cmp(&accepted_ballot) >= 0 implies
-                self.hosts[future_ballot.pid as int].instances[instance].proposed_value[future_ballot] == self.hosts[i].instances[instance].proposed_value[accepted_ballot]
+                self.history[future_ballot.pid as int][instance].proposed_value[future_ballot] == self.history[i][instance].proposed_value[accepted_ballot]
             by {
                 if (future_ballot.cmp(&accepted_ballot) > 0) {
                     let h2 = future_ballot.pid as int;
                     self.if_host_proposed_then_quorum_has_promised_is_inductive(c, u, event);
-                    assert(two_maps_contain_values_with_min_len(self.hosts[i].instances[instance].accepted, self.hosts[h2].instances[instance].promised, accepted_ballot, future_ballot, c.num_failures));
-                    let calculated_result = host::get_max_accepted_value(self.hosts[h2].instances[instance].promised[future_ballot]);
+                    assert(two_maps_contain_phase2_and_phase1_quorums(self.history[i][instance].accepted, self.history[h2][instance].promised, accepted_ballot, future_ballot, self.configuration(instance).classic));
+                    let calculated_result = host::get_max_accepted_value(self.history[h2][instance].promised[future_ballot]);
                     self.if_system_accepted_exists_some_accept_value_in_future_promise_quorum_is_inductive(c, u, event);
-                    host::get_max_accepted_value_is_some_if_accepted_map_has_sender_with_value_as_some_value(self.hosts[h2].instances[instance].promised[future_ballot]);
+                    host::get_max_accepted_value_is_some_if_accepted_map_has_sender_with_value_as_some_value(self.history[h2][instance].promised[future_ballot]);
                     assert(calculated_result.is_some());
                     let (calculated_ballot, calculated_value) = calculated_result.unwrap();
 
 
                     self.accepted_system_calculates_same_proposed_value_in_future_is_inductive(c, u, event);
-                    assert(two_maps_contain_values_with_min_len(self.hosts[i].instances[instance].accepted, self.hosts[h2].instances[instance].promised, accepted_ballot, future_ballot, c.num_failures));
-                    assert(calculated_value == self.hosts[i].instances[instance].proposed_value[accepted_ballot]);
+                    assert(two_maps_contain_phase2_and_phase1_quorums(self.history[i][instance].accepted, self.history[h2][instance].promised, accepted_ballot, future_ballot, self.configuration(instance).classic));
+                    assert(calculated_value == self.history[i][instance].proposed_value[accepted_ballot]);
+
+                    self.if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot_is_inductive(c, u, event);
+                    self.network_msgs_have_valid_sender_and_ballot_pid_is_inductive(c, u, event);
+                    self.someone_accepted_implies_network_has_their_accepted_msg_is_inductive(c, u, event);
+                    self.accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted_is_inductive(c, u, event);
+                    assert(self.someone_promised_implies_network_has_their_promise_msg(c));
+                    self.get_max_after_accepted_ballot_is_classic(c, i, h2, instance, accepted_ballot, future_ballot);
 
                     self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some_is_inductive(c, u, event);
                     assert(self.proposed_some_value_and_get_max_accepted_value_is_some(h2, instance, future_ballot));
-                    assert(self.hosts[h2].instances[instance].proposed_value[future_ballot] == calculated_value);
+                    assert(self.history[h2][instance].proposed_value[future_ballot] == calculated_value);
                 }
             };
         }
+
+        // The single-state counterpart of `accepted_system_always_proposes_same_value_in_future` for
+        // quorums counted from the network rather than in the leader's `accepted` map, which is what a
+        // learner's Decide rests on. The later leader's promise quorum meets the accepting quorum, so one
+        // promise reports a ballot at or above `accepted_ballot`; the largest reported ballot carries the
+        // value proposed at `future_ballot`, and by induction on ballots it is `value`.
+        pub proof fn quorum_accepted_value_is_proposed_in_future(&self, c: &Constants, key: nat, accepted_ballot: host::Ballot, value: Value, future_ballot: host::Ballot)
+        requires
+            self.well_formed(c),
+            host_map_properties(c, self),
+            configuration_properties(c, self),
+            self.accept_msg_in_network_implies_quorum_promised_and_value_proposed_by_sender(c),
+            self.someone_promised_implies_network_has_their_promise_msg(c),
+            self.network_msgs_have_valid_sender_and_ballot_pid(c),
+            self.ballot_in_accepted_is_smaller_than_promise_message_ballot(c),
+            self.value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot(c),
+            self.accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted(c),
+            self.accepted_msg_in_network_implies_network_has_corresponding_accept_msg(c),
+            self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(c),
+            self.same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(c),
+            self.if_host_proposed_then_quorum_has_promised(c),
+            self.quorum_has_accepted(c, key, accepted_ballot, value),
+            !host::is_fast_ballot(accepted_ballot),
+            0 <= future_ballot.pid < self.hosts.len(),
+            self.host_proposed(future_ballot.pid as int, key, future_ballot),
+            future_ballot.cmp(&accepted_ballot) >= 0,
+        ensures
+            self.history[future_ballot.pid as int][key].proposed_value[future_ballot] == value,
+        decreases
+            future_ballot.num, future_ballot.pid
+        {
+            let fq = self.configuration(key);
+            let senders = choose |senders: Set<nat>| #[trigger] self.accepted_by(c, key, accepted_ballot, value, senders);
+            let leader = future_ballot.pid as int;
+            assert(self.configurations.contains_key(key) && reconfig::is_valid_configuration(fq, c.num_hosts, c.num_failures));
+
+            if (future_ballot == accepted_ballot) {
+                quorum::phase2_quorum_has_some_host(fq.classic, c.num_hosts, c.num_failures, senders);
+                let sender = choose |h: nat| #![auto] senders.contains(h);
+                assert(self.network.sent_messages.contains(Message::Accepted { key, sender, ballot: accepted_ballot, value }));
+                assert(self.accept_was_sent(key, accepted_ballot, value));
+                assert(self.network.sent_messages.contains(Message::Accept { key, ballot: accepted_ballot, value }));
+            } else {
+                let accepted_map = self.history[leader][key].promised[future_ballot];
+                assert(map_contains_key_with_phase1_quorum(self.history[leader][key].promised, future_ballot, self.configuration(key).classic));
+                assert(quorum::are_valid_hosts(accepted_map.dom(), c.num_hosts));
+                quorum::phase1_and_phase2_quorums_have_common_host(fq.classic, c.num_hosts, accepted_map.dom(), senders);
+                let common_sender = choose |s: nat| #![auto] accepted_map.dom().contains(s) && senders.contains(s);
+
+                assert(self.network.sent_messages.contains(Message::Promise { key, sender: common_sender, ballot: future_ballot, accepted: accepted_map[common_sender] }));
+                assert(self.network.sent_messages.contains(Message::Accepted { key, sender: common_sender, ballot: accepted_ballot, value }));
+                assert(host::map_has_key_with_some_value(accepted_map, common_sender));
+                let (common_sender_ballot, common_sender_value) = accepted_map[common_sender].unwrap();
+                assert(common_sender_ballot.cmp(&accepted_ballot) >= 0);
+
+                host::get_max_accepted_value_is_some_if_accepted_map_has_sender_with_value_as_some_value(accepted_map);
+                let (calculated_ballot, calculated_value) = host::get_max_accepted_value(accepted_map).unwrap();
+
+                host::if_accepted_map_has_sender_with_value_as_some_then_larget_accepted_ballot_sender_exists(accepted_map);
+                let largest_sender = choose |largest_sender: nat| #[trigger] host::is_largest_accepted_ballot_sender(accepted_map, largest_sender);
+                let (largest_sender_ballot, largest_sender_value) = accepted_map[largest_sender].unwrap();
+
+                host::get_max_accepted_ballot_corresponds_to_largest_ballot(accepted_map);
+                assert(calculated_ballot == largest_sender_ballot);
+
+                host::get_max_accepted_value_is_some_implies_accepted_map_has_corresponding_sender(accepted_map);
+                let calculated_sender = choose |s: nat| #[trigger] host::map_has_key_with_some_value_same_as_get_max_accepted_value(accepted_map, s);
+                assert(largest_sender_ballot.cmp(&common_sender_ballot) >= 0);
+                assert(!host::is_fast_ballot(largest_sender_ballot));
+                assert(host::same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(accepted_map));
+                assert(host::accepted_map_ballots_are_same(accepted_map[calculated_sender].unwrap().0, accepted_map[largest_sender].unwrap().0));
+                assert(calculated_value == largest_sender_value);
+
+                fast::select_value_at_classic_ballot(fq, c.num_hosts, accepted_map);
+                assert(self.proposed_some_value_and_get_max_accepted_value_is_some(leader, key, future_ballot));
+                assert(self.history[leader][key].proposed_value[future_ballot] == largest_sender_value);
+
+                assert(largest_sender_ballot.cmp(&accepted_ballot) >= 0);
+                assert(self.network.sent_messages.contains(Message::Promise { key, sender: largest_sender, ballot: future_ballot, accepted: accepted_map[largest_sender] }));
+                assert(largest_sender_ballot.cmp(&future_ballot) < 0);
+
+                let previous_leader = largest_sender_ballot.pid as int;
+                assert(self.host_proposed(previous_leader, key, largest_sender_ballot));
+                assert(largest_sender_value == self.history[previous_leader][key].proposed_value[largest_sender_ballot]);
+
+                assert(decreases_to!(future_ballot.num, future_ballot.pid => largest_sender_ballot.num, largest_sender_ballot.pid));
+                self.quorum_accepted_value_is_proposed_in_future(c, key, accepted_ballot, value, largest_sender_ballot);
+            }
+        }
+
+        // The fast-round counterpart of `quorum_accepted_value_is_proposed_in_future`. The later leader's
+        // promise quorum meets the fast quorum that accepted `value`. If the highest ballot reported to it
+        // is the fast one, everyone from that fast quorum it heard from reported `value`, which
+        // `select_value` recovers; otherwise it is a classic ballot in between and induction applies.
+        pub proof fn fast_quorum_accepted_value_is_proposed_in_future(&self, c: &Constants, key: nat, value: Value, future_ballot: host::Ballot)
+        requires
+            self.well_formed(c),
+            host_map_properties(c, self),
+            configuration_properties(c, self),
+            self.someone_promised_implies_network_has_their_promise_msg(c),
+            self.network_msgs_have_valid_sender_and_ballot_pid(c),
+            self.ballot_in_accepted_is_smaller_than_promise_message_ballot(c),
+            self.value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot(c),
+            self.if_accepted_is_some_in_promise_message_then_network_has_corresponding_old_accepted_message(c),
+            self.accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted(c),
+            self.fast_accepted_msgs_from_same_sender_have_same_value(c),
+            self.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some(c),
+            self.same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts(c),
+            self.if_host_proposed_then_quorum_has_promised(c),
+            self.fast_quorum_has_accepted(c, key, value),
+            0 <= future_ballot.pid < self.hosts.len(),
+            self.host_proposed(future_ballot.pid as int, key, future_ballot),
+            !host::is_fast_ballot(future_ballot),
+        ensures
+            self.history[future_ballot.pid as int][key].proposed_value[future_ballot] == value,
+        decreases
+            future_ballot.num, future_ballot.pid
+        {
+            let fq = self.configuration(key);
+            let senders = choose |senders: Set<nat>| #[trigger] self.fast_accepted_by(c, key, value, senders);
+            let leader = future_ballot.pid as int;
+            let accepted_map = self.history[leader][key].promised[future_ballot];
+            assert(self.configurations.contains_key(key) && reconfig::is_valid_configuration(fq, c.num_hosts, c.num_failures));
+
+            assert(map_contains_key_with_phase1_quorum(self.history[leader][key].promised, future_ballot, self.configuration(key).classic));
+            assert(quorum::are_valid_hosts(accepted_map.dom(), c.num_hosts));
+            assert(fq.classic.is_phase1_quorum(accepted_map.dom()) && fq.is_fast_quorum(senders));
+            let common_sender = choose |h: nat| #![auto] accepted_map.dom().contains(h) && senders.contains(h) && senders.contains(h);
+
+            assert(self.network.sent_messages.contains(Message::Promise { key, sender: common_sender, ballot: future_ballot, accepted: accepted_map[common_sender] }));
+            assert(self.network.sent_messages.contains(Message::Accepted { key, sender: common_sender, ballot: host::fast_ballot(), value }));
+            assert(host::map_has_key_with_some_value(accepted_map, common_sender));
+
+            host::get_max_accepted_value_is_some_if_accepted_map_has_sender_with_value_as_some_value(accepted_map);
+            let (calculated_ballot, calculated_value) = host::get_max_accepted_value(accepted_map).unwrap();
+
+            host::if_accepted_map_has_sender_with_value_as_some_then_larget_accepted_ballot_sender_exists(accepted_map);
+            let largest_sender = choose |largest_sender: nat| #[trigger] host::is_largest_accepted_ballot_sender(accepted_map, largest_sender);
+            let (largest_sender_ballot, largest_sender_value) = accepted_map[largest_sender].unwrap();
+
+            host::get_max_accepted_ballot_corresponds_to_largest_ballot(accepted_map);
+            assert(calculated_ballot == largest_sender_ballot);
+            assert(self.proposed_some_value_and_get_max_accepted_value_is_some(leader, key, future_ballot));
+
+            assert(self.network.sent_messages.contains(Message::Promise { key, sender: largest_sender, ballot: future_ballot, accepted: accepted_map[largest_sender] }));
+            assert(self.network.sent_messages.contains(Message::Accepted { key, sender: largest_sender, ballot: largest_sender_ballot, value: largest_sender_value }));
+            assert(largest_sender_ballot.cmp(&future_ballot) < 0);
+
+            if (host::is_fast_ballot(largest_sender_ballot)) {
+                assert(largest_sender_ballot == host::fast_ballot());
+                assert forall |sender: nat| #[trigger] senders.contains(sender) && accepted_map.contains_key(sender) implies accepted_map[sender] == Some((largest_sender_ballot, value)) by {
+                    assert(self.network.sent_messages.contains(Message::Promise { key, sender, ballot: future_ballot, accepted: accepted_map[sender] }));
+                    assert(self.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value }));
+                    assert(host::map_has_key_with_some_value(accepted_map, sender));
+                    let (sender_ballot, sender_value) = accepted_map[sender].unwrap();
+                    assert(self.network.sent_messages.contains(Message::Accepted { key, sender, ballot: sender_ballot, value: sender_value }));
+                    assert(sender_ballot == host::fast_ballot());
+                    assert(sender_value == value);
+                };
+                assert(fast::fast_quorum_may_have_accepted(accepted_map, senders, largest_sender_ballot, value));
+                assert(fast::value_may_have_been_chosen(fq, c.num_hosts, accepted_map, largest_sender_ballot, value));
+                fast::value_that_may_have_been_chosen_is_selected(fq, c.num_hosts, accepted_map, value);
+            } else {
+                host::get_max_accepted_value_is_some_implies_accepted_map_has_corresponding_sender(accepted_map);
+                let calculated_sender = choose |s: nat| #[trigger] host::map_has_key_with_some_value_same_as_get_max_accepted_value(accepted_map, s);
+                assert(host::same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(accepted_map));
+                assert(host::accepted_map_ballots_are_same(accepted_map[calculated_sender].unwrap().0, accepted_map[largest_sender].unwrap().0));
+                assert(calculated_value == largest_sender_value);
+
+                fast::select_value_at_classic_ballot(fq, c.num_hosts, accepted_map);
+                assert(self.history[leader][key].proposed_value[future_ballot] == largest_sender_value);
+
+                let previous_leader = largest_sender_ballot.pid as int;
+                assert(self.host_proposed(previous_leader, key, largest_sender_ballot));
+                assert(largest_sender_value == self.history[previous_leader][key].proposed_value[largest_sender_ballot]);
+
+                assert(decreases_to!(future_ballot.num, future_ballot.pid => largest_sender_ballot.num, largest_sender_ballot.pid));
+                self.fast_quorum_accepted_value_is_proposed_in_future(c, key, value, largest_sender_ballot);
+            }
+        }
+
+        // Every ballot in a host's `accepted` map was prepared, so none of them is the fast one.
+        pub proof fn accepted_ballots_are_classic(&self, c: &Constants, i: int, instance: nat, ballot: host::Ballot)
+        requires
+            self.if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot(c),
+            self.network_msgs_have_valid_sender_and_ballot_pid(c),
+            0 <= i < self.hosts.len(),
+            self.hosts[i].instances.contains_key(instance),
+            self.history[i][instance].accepted.contains_key(ballot),
+        ensures
+            !host::is_fast_ballot(ballot),
+        {
+            self.prepared_ballot_is_valid(c, instance, ballot);
+        }
+
+        // A promise quorum for a ballot above one a quorum accepted reports a ballot at least as high, so
+        // the highest reported ballot is classic and `select_value` takes the highest reported value.
+        pub proof fn get_max_after_accepted_ballot_is_classic(&self, c: &Constants, h1: int, h2: int, instance: nat, accepted_ballot: host::Ballot, future_ballot: host::Ballot)
+        requires
+            host_map_properties(c, self),
+            configuration_properties(c, self),
+            self.if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot(c),
+            self.network_msgs_have_valid_sender_and_ballot_pid(c),
+            self.if_system_accepted_exists_some_accept_value_in_future_promise_quorum(c),
+            self.someone_promised_implies_network_has_their_promise_msg(c),
+            self.someone_accepted_implies_network_has_their_accepted_msg(c),
+            self.accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted(c),
+            0 <= h1 < self.hosts.len(),
+            0 <= h2 < self.hosts.len(),
+            self.hosts[h1].instances.contains_key(instance),
+            self.hosts[h2].instances.contains_key(instance),
+            two_maps_contain_phase2_and_phase1_quorums(self.history[h1][instance].accepted, self.history[h2][instance].promised, accepted_ballot, future_ballot, self.configuration(instance).classic),
+            future_ballot.cmp(&accepted_ballot) > 0,
+        ensures
+            host::get_max_accepted_value(self.history[h2][instance].promised[future_ballot]).is_some(),
+            fast::select_value(self.configuration(instance), c.num_hosts, self.history[h2][instance].promised[future_ballot]) == host::get_max_accepted_value(self.history[h2][instance].promised[future_ballot]),
+        {
+            let accepted_map = self.history[h2][instance].promised[future_ballot];
+            self.accepted_ballots_are_classic(c, h1, instance, accepted_ballot);
+
+            let common_sender = choose |s: nat| #[trigger] host::map_has_key_with_some_value(accepted_map, s) && self.history[h1][instance].accepted[accepted_ballot].contains(s);
+            assert(self.network.sent_messages.contains(Message::Promise { key: instance, sender: common_sender, ballot: future_ballot, accepted: accepted_map[common_sender] }));
+            assert(self.network.sent_messages.contains(Message::Accepted { key: instance, sender: common_sender, ballot: accepted_ballot, value: self.history[h1][instance].proposed_value[accepted_ballot] }));
+            assert(accepted_map[common_sender].unwrap().0.cmp(&accepted_ballot) >= 0);
+
+            host::get_max_accepted_value_is_some_if_accepted_map_has_sender_with_value_as_some_value(accepted_map);
+            host::if_accepted_map_has_sender_with_value_as_some_then_larget_accepted_ballot_sender_exists(accepted_map);
+            let largest_sender = choose |largest_sender: nat| #[trigger] host::is_largest_accepted_ballot_sender(accepted_map, largest_sender);
+            host::get_max_accepted_ballot_corresponds_to_largest_ballot(accepted_map);
+            assert(accepted_map[largest_sender].unwrap().0.cmp(&accepted_map[common_sender].unwrap().0) >= 0);
+
+            assert(self.configurations.contains_key(instance));
+            fast::select_value_at_classic_ballot(self.configuration(instance), c.num_hosts, accepted_map);
+        }
     }
 
     pub open spec fn system_quorum_properties(c: &Constants, u: &Variables) -> bool {
@@ -1329,8 +4135,11 @@ verus! {
 
     pub open spec fn inductive(c: &Constants, u: &Variables) -> bool {
         &&& u.well_formed(c)
-        &&& u.network.in_flight_messages.finite()
+        &&& u.network.sent_messages.finite()
+        &&& u.network.in_flight_messages.subset_of(u.network.sent_messages)
         &&& host_map_properties(c, u)
+        &&& history_properties(c, u)
+        &&& configuration_properties(c, u)
         &&& messages_in_network_implies_first_degree_properties(c, u)
         &&& properties_imply_first_degree_messages_in_network(c, u)
         &&& properties_of_valid_messages_in_network(c, u)
@@ -1338,6 +4147,173 @@ verus! {
         &&& system_quorum_properties(c, u)
     }
 
+    // What the network holds after the first `n` steps of a batch run: everything ever sent plus the
+    // replies so far, and, if receiving consumes, without the carried messages taken so far.
+    pub open spec fn run_sends(steps: Seq<(nat, NetworkOperation, Event)>, n: int) -> Set<Message> {
+        Set::new(|message: Message| exists |j: int| 0 <= j < n && #[trigger] steps[j].1.send == Some(message))
+    }
+
+    pub open spec fn run_recvs(steps: Seq<(nat, NetworkOperation, Event)>, n: int) -> Set<Message> {
+        Set::new(|message: Message| exists |j: int| 0 <= j < n && #[trigger] steps[j].1.recv == Some(message))
+    }
+
+    pub open spec fn run_state(c: &Constants, u: &Variables, host_id: int, states: Seq<host::Variables>, steps: Seq<(nat, NetworkOperation, Event)>, n: int) -> Variables {
+        let sent_messages = u.network.sent_messages.union(run_sends(steps, n));
+
+        Variables {
+            hosts: u.hosts.update(host_id, states[n]),
+            network: network::Variables {
+                sent_messages,
+                in_flight_messages: if c.network.consume_on_receive {
+                    sent_messages.difference(run_recvs(steps, n)).union(run_sends(steps, n))
+                } else {
+                    sent_messages
+                },
+            },
+            history: if n == 0 { u.history } else { u.history.update(host_id, record(u.history[host_id], states[n])) },
+            configurations: u.configurations,
+        }
+    }
+
+    pub proof fn run_keeps_instances(states: Seq<host::Variables>, n: int)
+    requires
+        0 <= n < states.len(),
+        forall |j: int| #![trigger states[j]] 0 <= j < states.len() - 1 ==> batch::keeps_memory(states[j], states[j + 1]),
+    ensures
+        states[n].instances.dom() == states[0].instances.dom(),
+    decreases
+        n
+    {
+        if (n > 0) {
+            run_keeps_instances(states, n - 1);
+            assert(batch::keeps_memory(states[n - 1], states[n]));
+        }
+    }
+
+    // `v` is `w` with some batches added to the sent history, each carrying messages `w` has sent.
+    pub open spec fn adds_only_batches(c: &Constants, w: &Variables, v: &Variables) -> bool {
+        &&& w.well_formed(c)
+        &&& v.well_formed(c)
+        &&& v.hosts == w.hosts
+        &&& v.history == w.history
+        &&& v.configurations == w.configurations
+        &&& v.network.sent_messages.finite()
+        &&& w.network.sent_messages.subset_of(v.network.sent_messages)
+        &&& forall |message: Message| #![trigger v.network.sent_messages.contains(message)]
+                v.network.sent_messages.contains(message) && !w.network.sent_messages.contains(message) ==>
+                batch::is_batch(message) && batch::unbatch(message).to_set().subset_of(w.network.sent_messages)
+        &&& v.network.in_flight_messages.subset_of(v.network.sent_messages)
+    }
+
+    // A batch step is a run of single-key steps of the same host between states the protocol can
+    // reach: the network first re-delivers every carried message (a `network_only_next` step), the
+    // host then takes them one at a time (`message_next` steps), and finally the batch itself joins
+    // the sent history.
+    pub proof fn batch_next_is_run_of_host_steps(c: &Constants, u: &Variables, v: &Variables, event: Event) -> (run: (Seq<Variables>, Seq<Event>))
+    requires
+        inductive(c, u),
+        batch_next(c, u, v, event),
+    ensures
+        run.0.len() == run.1.len() + 1,
+        network_only_next(c, u, &run.0[0], Event::NoOp),
+        forall |j: int| #![trigger run.0[j]] 0 <= j < run.1.len() ==> message_next(c, &run.0[j], &run.0[j + 1], run.1[j]),
+        if event.is_batch() { run.1 == event.unbatch() } else { forall |j: int| #![trigger run.1[j]] 0 <= j < run.1.len() ==> run.1[j] is NoOp },
+        event is DecideBatch ==> batch::has_distinct_keys(event->DecideBatch_entries),
+        adds_only_batches(c, &run.0.last(), v),
+    {
+        let (host_id, net_op) = choose |host_id: int, net_op: NetworkOperation| #[trigger] batch_host_step(c, u, v, host_id, net_op, event);
+        let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id]);
+        let (states, steps) = batch::step_is_run(lc, lu, lv, net_op, event);
+        let n = steps.len() as int;
+        let ws = Seq::new((n + 1) as nat, |j: int| run_state(c, u, host_id, states, steps, j));
+        let events = steps.map_values(|step: (nat, NetworkOperation, Event)| step.2);
+
+        // Every carried message the run receives was sent along with the batch carrying it.
+        assert forall |j: int| #![trigger steps[j]] 0 <= j < n && steps[j].1.recv.is_some() implies
+            u.network.sent_messages.contains(steps[j].1.recv.unwrap()) && !batch::is_batch(steps[j].1.recv.unwrap())
+        by {
+            let batch = net_op.recv.unwrap();
+            assert(u.network.sent_messages.contains(batch));
+            assert(batch::unbatch(batch).to_set().contains(steps[j].1.recv.unwrap()));
+        };
+        assert forall |j: int| #![trigger steps[j]] 0 <= j < n && steps[j].1.send.is_some() implies
+            !batch::is_batch(steps[j].1.send.unwrap())
+        by {
+            let batch = net_op.send.unwrap();
+            let k = choose |k: int| 0 <= k < batch::unbatch(batch).len() && batch::unbatch(batch)[k] == steps[j].1.send.unwrap();
+        };
+
+        assert forall |j: int| #![trigger ws[j]] 0 <= j < n implies
+            is_valid_transition(c, &ws[j], &ws[j + 1], Transition::HostStep { host_id, instance: steps[j].0, net_op: steps[j].1 }, steps[j].2)
+        by {
+            let (w, w_next) = (ws[j], ws[j + 1]);
+            assert(w_next.hosts =~= w.hosts.update(host_id, states[j + 1]));
+            if (j > 0) {
+                record_of_grown_host_absorbs_earlier_record(u.history[host_id], states[j], states[j + 1]);
+            }
+            assert(w_next.history =~= w.history.update(host_id, record(w.history[host_id], w_next.hosts[host_id])));
+
+            // The run creates no instance, and every instance the host had is recorded already.
+            run_keeps_instances(states, j + 1);
+            assert(record_configurations(w.configurations, w_next.hosts[host_id]) =~= w.configurations);
+
+            let step_net_op = steps[j].1;
+            assert(run_sends(steps, j + 1) =~= if let Some(send) = step_net_op.send { run_sends(steps, j).insert(send) } else { run_sends(steps, j) });
+            assert(run_recvs(steps, j + 1) =~= if let Some(recv) = step_net_op.recv { run_recvs(steps, j).insert(recv) } else { run_recvs(steps, j) });
+            if let Some(recv) = step_net_op.recv {
+                // Receives are distinct and never one of the replies, so `recv` is still in flight.
+                assert(!run_recvs(steps, j).contains(recv));
+                assert(!run_sends(steps, j).contains(recv));
+                assert(w.network.in_flight_messages.contains(recv));
+            }
+            assert(network::step(&c.network, &w.network, &w_next.network, step_net_op));
+        };
+
+        assert forall |j: int| #![trigger ws[j]] 0 <= j < n implies message_next(c, &ws[j], &ws[j + 1], events[j]) by {
+            assert(is_valid_transition(c, &ws[j], &ws[j + 1], Transition::HostStep { host_id, instance: steps[j].0, net_op: steps[j].1 }, steps[j].2));
+        };
+
+        assert(network_only_next(c, u, &ws[0], Event::NoOp)) by {
+            assert(ws[0].hosts =~= u.hosts);
+            assert(run_sends(steps, 0) =~= Set::empty());
+            assert(run_recvs(steps, 0) =~= Set::empty());
+            assert(ws[0].network.sent_messages =~= u.network.sent_messages);
+        };
+
+        if (event.is_batch()) {
+            assert(events =~= batch::events_of(steps));
+        }
+
+        assert(adds_only_batches(c, &ws[n], v)) by {
+            let w = ws[n];
+            assert(w.hosts =~= v.hosts);
+            if (n == 0) {
+                record_of_recorded_host_is_identity(c, u, host_id);
+            }
+            assert(w.history =~= v.history);
+
+            // Everything the batch carries is a reply of the run.
+            if let Some(batch) = net_op.send {
+                assert forall |message: Message| #[trigger] batch::unbatch(batch).to_set().contains(message) implies
+                    w.network.sent_messages.contains(message)
+                by {
+                    let j = choose |j: int| 0 <= j < n && #[trigger] steps[j].1.send == Some(message);
+                    assert(run_sends(steps, n).contains(message));
+                };
+                assert forall |message: Message| #[trigger] run_sends(steps, n).contains(message) implies
+                    batch::unbatch(batch).to_set().contains(message)
+                by {
+                    let j = choose |j: int| 0 <= j < n && #[trigger] steps[j].1.send == Some(message);
+                };
+            } else {
+                assert(run_sends(steps, n) =~= Set::empty());
+            }
+            assert(w.network.sent_messages.subset_of(v.network.sent_messages));
+        };
+
+        (ws, events)
+    }
+
 /*  Redundant with `set_lib::lemma_set_disjoint_lens`
     pub proof fn union_of_disjoint_sets_implies_sum_of_lengths<T>(set1: Set<T>, set2: Set<T>)
     requires
@@ -1490,7 +4466,6 @@ verus! {
 
     pub proof fn full_set_size(full_set: Set<nat>, max_val: nat)
     requires
-        max_val > 0,
         full_set =~= Set::new(|x: nat| 0 <= x < max_val),
     ensures
         full_set.finite(),
@@ -1498,13 +4473,13 @@ verus! {
     decreases
         max_val
     {
-        if (max_val == 1) {
-            assert(full_set =~= set![0]);
-            assert(full_set.finite());
+        if (max_val == 0) {
+            assert(full_set =~= Set::empty());
         } else {
             let largest_val = (max_val - 1) as nat;
             let sub_full_set = full_set.remove(largest_val);
             full_set_size(sub_full_set, largest_val);
+            assert(full_set =~= sub_full_set.insert(largest_val));
         }
     }
 
@@ -1535,20 +4510,19 @@ verus! {
         }
     }
 
-    pub proof fn overlapping_sets_have_common_element(set1: Set<nat>, set2: Set<nat>, floor_half_size: nat, full_size: nat)
+    // Two sets of hosts out of `full_size` whose sizes add up to more than `full_size` must share a
+    // host; with `num_hosts >= 2f + 1`, any two sets of `num_hosts - f` hosts qualify.
+    pub proof fn overlapping_sets_have_common_element(set1: Set<nat>, set2: Set<nat>, full_size: nat)
     requires
         set1.finite(),
         set2.finite(),
-        floor_half_size > 0,
-        full_size == ((2 * floor_half_size) + 1),
         forall |x: nat| #![auto] set1.contains(x) ==> 0 <= x < full_size,
         forall |x: nat| #![auto] set2.contains(x) ==> 0 <= x < full_size,
-        set1.len() > floor_half_size,
-        set2.len() > floor_half_size,
+        set1.len() + set2.len() > full_size,
     ensures
         exists |x: nat| #![auto] set1.contains(x) && set2.contains(x)
     {
-        assert(set1.len() + set2.len() > full_size);
+        assert(forall |x: nat| #[trigger] set1.union(set2).contains(x) ==> 0 <= x < full_size);
         assert(set1.union(set2).len() <= full_size) by { continuous_set_size_bounds(set1.union(set2), full_size); };
 
         let (set1_size, set2_size) = (set1.len(), set2.len());
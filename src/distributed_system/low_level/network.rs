@@ -1,11 +1,18 @@
-use super::{Message, NetworkOperation};
+use super::{batch, Message, NetworkOperation};
 use vstd::prelude::*;
 
 verus! {
-    pub struct Constants {}
+    pub struct Constants {
+        pub consume_on_receive: bool,
+    }
 
+    // `sent_messages` is the (ghost) history of everything ever sent, batches together with every
+    // message they carry; it only grows and is what the protocol invariants talk about.
+    // `in_flight_messages` is what can still be delivered: a subset of the history that the network
+    // may drop from, duplicate into, or garbage-collect.
     #[verifier::ext_equal]
     pub struct Variables {
+        pub sent_messages: Set<Message>,
         pub in_flight_messages: Set<Message>,
     }
 
@@ -21,15 +28,76 @@ verus! {
         }
     }
 
+    pub open spec fn message_key(message: Message) -> Option<nat> {
+        match message {
+            Message::Prepare { key, .. } => Some(key),
+            Message::Promise { key, .. } => Some(key),
+            Message::Accept { key, .. } => Some(key),
+            Message::Accepted { key, .. } => Some(key),
+            Message::Nack { key, .. } => Some(key),
+            Message::Decide { key, .. } => Some(key),
+            Message::PrepareFrom { .. } => None,
+            Message::ClientRequest { .. } => None,
+            Message::AcceptBatch { .. } => None,
+            Message::AcceptedBatch { .. } => None,
+            Message::DecideBatch { .. } => None,
+        }
+    }
+
+    // Decide messages are kept so that hosts lagging behind can still learn the value. A batch spans
+    // several instances, so it is never collected by key either.
+    pub open spec fn is_collectable(message: Message, key: nat) -> bool {
+        &&& message_key(message) == Some(key)
+        &&& !(message is Decide)
+    }
+
     pub open spec fn init(c: &Constants, u: &Variables) -> bool {
         &&& u.well_formed(c)
+        &&& u.sent_messages.is_empty()
         &&& u.in_flight_messages.is_empty()
     }
 
     pub open spec fn step(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool {
+        let remaining = match net_op.recv {
+            Some(message) if c.consume_on_receive => u.in_flight_messages.remove(message),
+            _ => u.in_flight_messages,
+        };
+
         &&& u.well_formed(c)
         &&& v.well_formed(c)
         &&& if let Some(message) = net_op.recv { u.in_flight_messages.contains(message) } else { true }
-        &&& if let Some(message) = net_op.send { v.in_flight_messages =~= u.in_flight_messages.insert(message) } else { v =~= u }
+        &&& if let Some(message) = net_op.send {
+                &&& v.sent_messages =~= if batch::is_batch(message) {
+                        u.sent_messages.insert(message).union(batch::unbatch(message).to_set())
+                    } else {
+                        u.sent_messages.insert(message)
+                    }
+                &&& v.in_flight_messages =~= remaining.insert(message)
+            } else {
+                &&& v.sent_messages =~= u.sent_messages
+                &&& v.in_flight_messages =~= remaining
+            }
+    }
+
+    pub open spec fn drop(c: &Constants, u: &Variables, v: &Variables, message: Message) -> bool {
+        &&& u.well_formed(c)
+        &&& v.well_formed(c)
+        &&& v.sent_messages =~= u.sent_messages
+        &&& v.in_flight_messages =~= u.in_flight_messages.remove(message)
+    }
+
+    pub open spec fn duplicate(c: &Constants, u: &Variables, v: &Variables, message: Message) -> bool {
+        &&& u.well_formed(c)
+        &&& v.well_formed(c)
+        &&& u.sent_messages.contains(message)
+        &&& v.sent_messages =~= u.sent_messages
+        &&& v.in_flight_messages =~= u.in_flight_messages.insert(message)
+    }
+
+    pub open spec fn garbage_collect(c: &Constants, u: &Variables, v: &Variables, key: nat) -> bool {
+        &&& u.well_formed(c)
+        &&& v.well_formed(c)
+        &&& v.sent_messages =~= u.sent_messages
+        &&& v.in_flight_messages =~= u.in_flight_messages.filter(|message: Message| !is_collectable(message, key))
     }
 }
@@ -0,0 +1,191 @@
+use vstd::{prelude::*, set_lib::*};
+
+verus! {
+    // Phase 1 waits for a phase-1 quorum of promises and phase 2 for a phase-2 quorum of acceptances.
+    // Safety only needs every phase-1 quorum to meet every phase-2 quorum; neither family has to
+    // intersect itself, so phase-2 quorums can be made small at the cost of larger phase-1 quorums.
+    //
+    // `acceptors` is the membership the quorums are drawn from; votes from other hosts do not count.
+    pub struct QuorumSystem {
+        pub acceptors: Set<nat>,
+        pub phase1: spec_fn(Set<nat>) -> bool,
+        pub phase2: spec_fn(Set<nat>) -> bool,
+    }
+
+    pub open spec fn are_valid_hosts(hosts: Set<nat>, num_hosts: nat) -> bool {
+        &&& hosts.finite()
+        &&& forall |h: nat| #[trigger] hosts.contains(h) ==> h < num_hosts
+    }
+
+    impl QuorumSystem {
+        pub open spec fn is_phase1_quorum(&self, hosts: Set<nat>) -> bool {
+            (self.phase1)(hosts)
+        }
+
+        pub open spec fn is_phase2_quorum(&self, hosts: Set<nat>) -> bool {
+            (self.phase2)(hosts)
+        }
+
+        pub open spec fn quorums_intersect(&self, num_hosts: nat) -> bool {
+            forall |q1: Set<nat>, q2: Set<nat>|
+                are_valid_hosts(q1, num_hosts) &&
+                are_valid_hosts(q2, num_hosts) &&
+                #[trigger] self.is_phase1_quorum(q1) &&
+                #[trigger] self.is_phase2_quorum(q2) ==>
+                exists |h: nat| #![auto] q1.contains(h) && q2.contains(h)
+        }
+
+        // Promises and acceptances are only ever added, so a quorum once reached must stay one.
+        pub open spec fn quorums_are_upward_closed(&self) -> bool {
+            &&& forall |q: Set<nat>, s: Set<nat>| #![trigger self.is_phase1_quorum(q), self.is_phase1_quorum(s)]
+                    self.is_phase1_quorum(q) && q.subset_of(s) && s.finite() ==> self.is_phase1_quorum(s)
+            &&& forall |q: Set<nat>, s: Set<nat>| #![trigger self.is_phase2_quorum(q), self.is_phase2_quorum(s)]
+                    self.is_phase2_quorum(q) && q.subset_of(s) && s.finite() ==> self.is_phase2_quorum(s)
+        }
+
+        // With at most `num_failures` acceptors down, the remaining ones must still be able to complete
+        // both phases.
+        pub open spec fn tolerates_failures(&self, num_failures: nat) -> bool {
+            forall |q: Set<nat>|
+                q.subset_of(self.acceptors) &&
+                #[trigger] q.len() >= self.acceptors.len() - num_failures ==>
+                self.is_phase1_quorum(q) && self.is_phase2_quorum(q)
+        }
+
+        pub open spec fn well_formed(&self, num_hosts: nat) -> bool {
+            &&& are_valid_hosts(self.acceptors, num_hosts)
+            &&& self.quorums_intersect(num_hosts)
+            &&& self.quorums_are_upward_closed()
+        }
+    }
+
+    pub proof fn phase1_and_phase2_quorums_have_common_host(quorums: QuorumSystem, num_hosts: nat, q1: Set<nat>, q2: Set<nat>)
+    requires
+        quorums.well_formed(num_hosts),
+        are_valid_hosts(q1, num_hosts),
+        are_valid_hosts(q2, num_hosts),
+        quorums.is_phase1_quorum(q1),
+        quorums.is_phase2_quorum(q2),
+    ensures
+        exists |h: nat| #![auto] q1.contains(h) && q2.contains(h),
+    {
+    }
+
+    // The full acceptor set is a phase-1 quorum, and it meets every phase-2 quorum.
+    pub proof fn phase2_quorum_has_some_host(quorums: QuorumSystem, num_hosts: nat, num_failures: nat, q: Set<nat>)
+    requires
+        quorums.well_formed(num_hosts),
+        quorums.tolerates_failures(num_failures),
+        are_valid_hosts(q, num_hosts),
+        quorums.is_phase2_quorum(q),
+    ensures
+        exists |h: nat| #![auto] q.contains(h),
+    {
+        assert(quorums.acceptors.subset_of(quorums.acceptors));
+        assert(quorums.acceptors.len() >= quorums.acceptors.len() - num_failures);
+        assert(quorums.is_phase1_quorum(quorums.acceptors));
+        phase1_and_phase2_quorums_have_common_host(quorums, num_hosts, quorums.acceptors, q);
+    }
+
+    pub proof fn superset_of_phase1_quorum_is_phase1_quorum(quorums: QuorumSystem, num_hosts: nat, q: Set<nat>, s: Set<nat>)
+    requires
+        quorums.well_formed(num_hosts),
+        quorums.is_phase1_quorum(q),
+        q.subset_of(s),
+        s.finite(),
+    ensures
+        quorums.is_phase1_quorum(s),
+    {
+    }
+
+    pub proof fn superset_of_phase2_quorum_is_phase2_quorum(quorums: QuorumSystem, num_hosts: nat, q: Set<nat>, s: Set<nat>)
+    requires
+        quorums.well_formed(num_hosts),
+        quorums.is_phase2_quorum(q),
+        q.subset_of(s),
+        s.finite(),
+    ensures
+        quorums.is_phase2_quorum(s),
+    {
+    }
+
+    pub open spec fn all_hosts(num_hosts: nat) -> Set<nat> {
+        Set::new(|h: nat| h < num_hosts)
+    }
+
+    // Every set containing at least `phase1_size` (resp. `phase2_size`) of the acceptors; votes from
+    // hosts outside `acceptors` do not count.
+    pub open spec fn quorums_by_size(acceptors: Set<nat>, phase1_size: nat, phase2_size: nat) -> QuorumSystem {
+        QuorumSystem {
+            acceptors,
+            phase1: |hosts: Set<nat>| hosts.finite() && hosts.intersect(acceptors).len() >= phase1_size,
+            phase2: |hosts: Set<nat>| hosts.finite() && hosts.intersect(acceptors).len() >= phase2_size,
+        }
+    }
+
+    pub proof fn quorums_by_size_are_well_formed(num_hosts: nat, acceptors: Set<nat>, phase1_size: nat, phase2_size: nat)
+    requires
+        are_valid_hosts(acceptors, num_hosts),
+        phase1_size + phase2_size > acceptors.len(),
+    ensures
+        quorums_by_size(acceptors, phase1_size, phase2_size).well_formed(num_hosts),
+    {
+        let quorums = quorums_by_size(acceptors, phase1_size, phase2_size);
+
+        assert forall |q1: Set<nat>, q2: Set<nat>|
+            are_valid_hosts(q1, num_hosts) &&
+            are_valid_hosts(q2, num_hosts) &&
+            #[trigger] quorums.is_phase1_quorum(q1) &&
+            #[trigger] quorums.is_phase2_quorum(q2) implies
+            exists |h: nat| #![auto] q1.contains(h) && q2.contains(h)
+        by {
+            let (members1, members2) = (q1.intersect(acceptors), q2.intersect(acceptors));
+            assert(members1.union(members2).subset_of(acceptors));
+            assert(members1.union(members2).len() <= acceptors.len()) by { lemma_len_subset(members1.union(members2), acceptors); };
+            assert(members1.len() + members2.len() == members1.union(members2).len() + members1.intersect(members2).len()) by { lemma_set_intersect_union_lens(members1, members2); };
+
+            assert(members1.intersect(members2).len() > 0);
+            let common_host = members1.intersect(members2).choose();
+            assert(q1.contains(common_host) && q2.contains(common_host));
+        };
+
+        assert forall |q: Set<nat>, s: Set<nat>| #![trigger quorums.is_phase1_quorum(q), quorums.is_phase1_quorum(s)]
+            quorums.is_phase1_quorum(q) && q.subset_of(s) && s.finite() implies quorums.is_phase1_quorum(s)
+        by {
+            lemma_len_subset(q.intersect(acceptors), s.intersect(acceptors));
+        };
+
+        assert forall |q: Set<nat>, s: Set<nat>| #![trigger quorums.is_phase2_quorum(q), quorums.is_phase2_quorum(s)]
+            quorums.is_phase2_quorum(q) && q.subset_of(s) && s.finite() implies quorums.is_phase2_quorum(s)
+        by {
+            lemma_len_subset(q.intersect(acceptors), s.intersect(acceptors));
+        };
+    }
+
+    // Simple majorities, generalised to any `acceptors.len() >= 2 * num_failures + 1`: both phases wait
+    // for all but `num_failures` acceptors, so even cluster sizes work too.
+    pub open spec fn majority_quorums(acceptors: Set<nat>, num_failures: nat) -> QuorumSystem {
+        quorums_by_size(acceptors, (acceptors.len() - num_failures) as nat, (acceptors.len() - num_failures) as nat)
+    }
+
+    pub proof fn majority_quorums_are_well_formed(num_hosts: nat, acceptors: Set<nat>, num_failures: nat)
+    requires
+        are_valid_hosts(acceptors, num_hosts),
+        acceptors.len() >= ((2 * num_failures) + 1),
+    ensures
+        majority_quorums(acceptors, num_failures).well_formed(num_hosts),
+        majority_quorums(acceptors, num_failures).tolerates_failures(num_failures),
+    {
+        let quorum_size = (acceptors.len() - num_failures) as nat;
+        quorums_by_size_are_well_formed(num_hosts, acceptors, quorum_size, quorum_size);
+
+        assert forall |q: Set<nat>|
+            q.subset_of(acceptors) &&
+            #[trigger] q.len() >= acceptors.len() - num_failures implies
+            majority_quorums(acceptors, num_failures).is_phase1_quorum(q) && majority_quorums(acceptors, num_failures).is_phase2_quorum(q)
+        by {
+            assert(q.intersect(acceptors) =~= q);
+            lemma_len_subset(q, acceptors);
+        };
+    }
+}
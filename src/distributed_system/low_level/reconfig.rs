@@ -0,0 +1,182 @@
+use super::{
+    fast::{fast_quorum_size, FastQuorumSystem},
+    host::{fast_quorums, is_fast_ballot, Ballot},
+    quorum::{are_valid_hosts, majority_quorums, majority_quorums_are_well_formed},
+};
+use crate::distributed_system::Value;
+use vstd::{arithmetic::div_mod::lemma_fundamental_div_mod, prelude::*, set_lib::*};
+
+verus! {
+    // Reconfiguration: a decided value can change the acceptors whose votes count. If the value
+    // decided in instance `j` is a reconfiguration command (`members` is `Some`), the acceptor set it
+    // names counts from instance `j + alpha` on, until a later command replaces it. The `alpha`
+    // instances in between can run while `j` is still open; instance `key` only starts on a host that
+    // has learned every decision its configuration depends on (`knows_configuration`).
+    pub struct Reconfiguration {
+        pub alpha: nat,
+        pub members: spec_fn(Value) -> Option<Set<nat>>,
+    }
+
+    impl Reconfiguration {
+        // A command names at least `2 * num_failures + 1` of the hosts that run the acceptor role.
+        pub open spec fn well_formed(&self, acceptors: Set<nat>, num_failures: nat) -> bool {
+            &&& self.alpha > 0
+            &&& forall |value: Value| #[trigger] (self.members)(value).is_some() ==>
+                    (self.members)(value).unwrap().subset_of(acceptors) &&
+                    (self.members)(value).unwrap().len() >= ((2 * num_failures) + 1)
+        }
+
+        pub open spec fn knows_configuration(&self, decisions: Map<nat, Value>, key: nat) -> bool {
+            forall |j: nat| j + self.alpha <= key ==> #[trigger] decisions.contains_key(j)
+        }
+
+        // The quorums of instance `key`: those of the last command decided at or below `key - alpha`,
+        // or `initial` if there is none.
+        pub open spec fn configuration(&self, initial: FastQuorumSystem, num_failures: nat, decisions: Map<nat, Value>, key: nat) -> FastQuorumSystem
+        decreases
+            key
+        {
+            if (key == 0 || key < self.alpha) {
+                initial
+            } else {
+                let j = (key - self.alpha) as nat;
+
+                if (decisions.contains_key(j) && (self.members)(decisions[j]).is_some()) {
+                    member_configuration((self.members)(decisions[j]).unwrap(), num_failures)
+                } else {
+                    self.configuration(initial, num_failures, decisions, (key - 1) as nat)
+                }
+            }
+        }
+    }
+
+    // `majority_quorums` over `members`, with fast quorums of ⌈3n/4⌉ of them as in `fast_quorums_by_size`.
+    pub open spec fn member_configuration(members: Set<nat>, num_failures: nat) -> FastQuorumSystem {
+        fast_quorums(
+            majority_quorums(members, num_failures),
+            |hosts: Set<nat>| hosts.finite() && hosts.intersect(members).len() >= fast_quorum_size(members.len()),
+        )
+    }
+
+    // What the invariants need of every configuration an instance uses.
+    pub open spec fn is_valid_configuration(fq: FastQuorumSystem, num_hosts: nat, num_failures: nat) -> bool {
+        &&& fq.well_formed(num_hosts)
+        &&& fq.classic.tolerates_failures(num_failures)
+        &&& forall |ballot: Ballot| #[trigger] (fq.is_fast_ballot)(ballot) == is_fast_ballot(ballot)
+    }
+
+    pub proof fn member_configuration_is_valid(num_hosts: nat, members: Set<nat>, num_failures: nat)
+    requires
+        are_valid_hosts(members, num_hosts),
+        members.len() >= ((2 * num_failures) + 1),
+    ensures
+        is_valid_configuration(member_configuration(members, num_failures), num_hosts, num_failures),
+    {
+        let fq = member_configuration(members, num_failures);
+        let (classic_size, fast_size) = ((members.len() - num_failures) as nat, fast_quorum_size(members.len()));
+
+        majority_quorums_are_well_formed(num_hosts, members, num_failures);
+        assert(4 * fast_size >= 3 * members.len()) by { lemma_fundamental_div_mod(((3 * members.len()) + 3) as int, 4); };
+        assert(classic_size + (2 * fast_size) > 2 * members.len());
+
+        assert forall |q: Set<nat>, r1: Set<nat>, r2: Set<nat>|
+            #![trigger fq.classic.is_phase1_quorum(q), fq.is_fast_quorum(r1), fq.is_fast_quorum(r2)]
+            are_valid_hosts(q, num_hosts) &&
+            are_valid_hosts(r1, num_hosts) &&
+            are_valid_hosts(r2, num_hosts) &&
+            fq.classic.is_phase1_quorum(q) &&
+            fq.is_fast_quorum(r1) &&
+            fq.is_fast_quorum(r2) implies
+            exists |h: nat| #![auto] q.contains(h) && r1.contains(h) && r2.contains(h)
+        by {
+            three_large_subsets_have_common_element(q.intersect(members), r1.intersect(members), r2.intersect(members), members);
+            let h = choose |h: nat| #![auto] q.intersect(members).contains(h) && r1.intersect(members).contains(h) && r2.intersect(members).contains(h);
+            assert(q.contains(h) && r1.contains(h) && r2.contains(h));
+        };
+    }
+
+    // `three_large_sets_have_common_element` for subsets of any finite set of hosts rather than of the
+    // first `full_size` ones.
+    pub proof fn three_large_subsets_have_common_element(set1: Set<nat>, set2: Set<nat>, set3: Set<nat>, universe: Set<nat>)
+    requires
+        universe.finite(),
+        set1.subset_of(universe),
+        set2.subset_of(universe),
+        set3.subset_of(universe),
+        set1.len() + set2.len() + set3.len() > 2 * universe.len(),
+    ensures
+        exists |x: nat| #![auto] set1.contains(x) && set2.contains(x) && set3.contains(x),
+    {
+        lemma_len_subset(set1, universe);
+        lemma_len_subset(set2, universe);
+        lemma_len_subset(set3, universe);
+
+        let common12 = set1.intersect(set2);
+        lemma_len_subset(set1.union(set2), universe);
+        lemma_set_intersect_union_lens(set1, set2);
+        assert(common12.len() + set3.len() > universe.len());
+
+        let common = common12.intersect(set3);
+        lemma_len_subset(common12, universe);
+        lemma_len_subset(common12.union(set3), universe);
+        lemma_set_intersect_union_lens(common12, set3);
+        assert(common.len() > 0);
+
+        let x = common.choose();
+        assert(set1.contains(x) && set2.contains(x) && set3.contains(x));
+    }
+
+    impl Reconfiguration {
+        pub proof fn configuration_is_valid(&self, initial: FastQuorumSystem, num_hosts: nat, num_failures: nat, acceptors: Set<nat>, decisions: Map<nat, Value>, key: nat)
+        requires
+            self.well_formed(acceptors, num_failures),
+            are_valid_hosts(acceptors, num_hosts),
+            is_valid_configuration(initial, num_hosts, num_failures),
+        ensures
+            is_valid_configuration(self.configuration(initial, num_failures, decisions, key), num_hosts, num_failures),
+        decreases
+            key
+        {
+            if (key > 0 && key >= self.alpha) {
+                let j = (key - self.alpha) as nat;
+
+                if (decisions.contains_key(j) && (self.members)(decisions[j]).is_some()) {
+                    let members = (self.members)(decisions[j]).unwrap();
+                    lemma_len_subset(members, acceptors);
+                    member_configuration_is_valid(num_hosts, members, num_failures);
+                } else {
+                    self.configuration_is_valid(initial, num_hosts, num_failures, acceptors, decisions, (key - 1) as nat);
+                }
+            }
+        }
+
+        // The configuration of `key` only reads the decisions below `key - alpha`, so two hosts that
+        // agree on those compute the same one.
+        pub proof fn configuration_depends_on_known_decisions(&self, initial: FastQuorumSystem, num_failures: nat, d1: Map<nat, Value>, d2: Map<nat, Value>, key: nat)
+        requires
+            self.knows_configuration(d1, key),
+            self.knows_configuration(d2, key),
+            forall |j: nat| j + self.alpha <= key ==> #[trigger] d1[j] == d2[j],
+        ensures
+            self.configuration(initial, num_failures, d1, key) == self.configuration(initial, num_failures, d2, key),
+        decreases
+            key
+        {
+            if (key > 0 && key >= self.alpha) {
+                let j = (key - self.alpha) as nat;
+                assert(d1.contains_key(j) && d2.contains_key(j) && d1[j] == d2[j]);
+                self.configuration_depends_on_known_decisions(initial, num_failures, d1, d2, (key - 1) as nat);
+            }
+        }
+
+        // Knowing the configuration of `key` means knowing that of every instance below it.
+        pub proof fn knows_configuration_below(&self, decisions: Map<nat, Value>, key: nat, lower: nat)
+        requires
+            self.knows_configuration(decisions, key),
+            lower <= key,
+        ensures
+            self.knows_configuration(decisions, lower),
+        {
+        }
+    }
+}
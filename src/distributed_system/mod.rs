@@ -4,38 +4,98 @@ use high_level::{
 use low_level::{
     init as low_init, next as low_next, Constants as LowConstants, Variables as LowVariables, *,
 };
-use vstd::prelude::*;
+use vstd::{prelude::*, set_lib::*};
 
 verus! {
+    pub mod epaxos;
     pub mod high_level;
+    pub mod liveness;
     pub mod low_level;
+    pub mod raft;
+    pub mod replicated_log;
 
-    pub type Value = int;
+    // A client command: the operation `op` tagged with the `(client_id, seq)` of the request that
+    // submitted it. A retry carries the same triple, and two requests never share an identity even
+    // when their operations are equal, so deduplication keys on the identity, never on `op`.
+    pub struct Value {
+        pub client_id: nat,
+        pub seq: nat,
+        pub op: int,
+    }
+
+    impl Value {
+        pub open spec fn request(&self) -> (nat, nat) {
+            (self.client_id, self.seq)
+        }
+
+        pub open spec fn is_no_op(&self) -> bool {
+            self.client_id == NO_OP_CLIENT
+        }
+    }
+
+    // The client id of no-ops, which no client uses.
+    pub spec const NO_OP_CLIENT: nat = 0xffff_ffff_ffff_ffff;
+
+    // What a leader proposes in instance `key` when no request is waiting, so an instance never has to
+    // wait for clients. Its `seq` is the instance, so no two instances share a no-op.
+    pub open spec fn no_op(key: nat) -> Value {
+        Value { client_id: NO_OP_CLIENT, seq: key, op: 0 }
+    }
 
     pub enum Event {
         Decide { key: nat, value: Value },
+        Propose { key: nat, value: Value },
+        Submit { value: Value },
+        // A leader proposes, or a host decides, every entry of a batch in one step.
+        ProposeBatch { entries: Seq<(nat, Value)> },
+        DecideBatch { entries: Seq<(nat, Value)> },
         NoOp,
     }
 
+    impl Event {
+        pub open spec fn is_batch(&self) -> bool {
+            ||| self is ProposeBatch
+            ||| self is DecideBatch
+        }
+
+        // The single-key events a batch event stands for, one per entry and in order.
+        pub open spec fn unbatch(&self) -> Seq<Event> {
+            match *self {
+                Event::ProposeBatch { entries } => entries.map_values(|entry: (nat, Value)| Event::Propose { key: entry.0, value: entry.1 }),
+                Event::DecideBatch { entries } => entries.map_values(|entry: (nat, Value)| Event::Decide { key: entry.0, value: entry.1 }),
+                _ => Seq::empty(),
+            }
+        }
+    }
+
     pub open spec fn constants_abstraction(lc: &LowConstants) -> HighConstants
     recommends
         lc.well_formed()
     {
-        HighConstants { }
+        HighConstants {
+            acceptors: lc.quorums.acceptors,
+            alpha: lc.reconfig.alpha,
+            members: lc.reconfig.members,
+        }
     }
 
     pub open spec fn variables_abstraction(lc: &LowConstants, lv: &LowVariables) -> HighVariables
     recommends
         lv.well_formed(lc)
     {
+        let decided_value = Map::new(
+            |key: nat| (exists |i: int| #![auto] 0 <= i < lv.hosts.len() && lv.hosts[i].instances.contains_key(key) && lv.hosts[i].instances[key].decide_value.is_some()),
+            |key: nat| {
+                let host = choose |i: int| #![auto] 0 <= i < lv.hosts.len() && lv.hosts[i].instances.contains_key(key) && lv.hosts[i].instances[key].decide_value.is_some();
+                lv.hosts[host].instances[key].decide_value.unwrap()
+            },
+        );
+
         HighVariables {
-            decided_value: Map::new(
-                |key: nat| (exists |i: int| #![auto] 0 <= i < lv.hosts.len() && lv.hosts[i].instances.contains_key(key) && lv.hosts[i].instances[key].decide_value.is_some()),
-                |key: nat| {
-                    let host = choose |i: int| #![auto] 0 <= i < lv.hosts.len() && lv.hosts[i].instances.contains_key(key) && lv.hosts[i].instances[key].decide_value.is_some();
-                    lv.hosts[host].instances[key].decide_value.unwrap()
-                },
-            )
+            decided_value,
+            submitted: Set::new(|value: Value| lv.value_was_submitted(value)),
+            proposed: Set::new(|value: Value| lv.value_was_proposed(value)),
+            membership: high_level::membership_of(&constants_abstraction(lc), decided_value),
         }
     }
 
@@ -46,7 +106,11 @@ verus! {
     ensures
         inductive(c, u),
         high_init(&constants_abstraction(c), &variables_abstraction(c, u)),
-    { }
+    {
+        assert(variables_abstraction(c, u).submitted =~= Set::empty());
+        assert(variables_abstraction(c, u).proposed =~= Set::empty());
+        assert(variables_abstraction(c, u).membership =~= Map::empty());
+    }
 
     // Corresponds to `inductive(c, u) && next(c, u, v) ==> inductive(c, v)`
     proof fn refinement_next(c: &LowConstants, u: &LowVariables, v: &LowVariables, event: Event)
@@ -57,106 +121,643 @@ verus! {
         inductive(c, v),
         high_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event),
     {
-        assert(inductive(c, v)) by {
-            assert(v.network.in_flight_messages.finite());
-            assert(host_map_properties(c, v)) by { v.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
-            assert(messages_in_network_implies_first_degree_properties(c, v));
-            assert(properties_imply_first_degree_messages_in_network(c, v)) by {
-                v.if_accept_ballot_is_some_then_accept_value_is_some_is_inductive(c, u, event);
-                v.accepted_state_implies_network_has_accept_message_is_inductive(c, u, event);
-                v.decided_state_implies_network_has_decide_message_is_inductive(c, u, event);
-            };
-            assert(properties_of_valid_messages_in_network(c, v)) by {
-                v.network_msgs_have_valid_sender_and_ballot_pid_is_inductive(c, u, event);
-                v.value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot_is_inductive(c, u, event);
-                v.accepted_msg_in_network_implies_network_has_corresponding_accept_msg_is_inductive(c, u, event);
-                v.all_decide_messages_hold_same_value_is_inductive(c, u, event);
+        if (message_next(c, u, v, event)) {
+            message_refinement_next(c, u, v, event);
+        } else {
+            batch_refinement_next(c, u, v, event);
+        }
+    }
+
+    // A batch step is a run of single-key steps, each of which refines, followed by a step that only
+    // adds the batch to the sent history. The run's events are the ones the batch event stands for, so
+    // its high-level steps make up the batch's high-level step (`high_level::run_is_batch_step`).
+    proof fn batch_refinement_next(c: &LowConstants, u: &LowVariables, v: &LowVariables, event: Event)
+    requires
+        inductive(c, u),
+        batch_next(c, u, v, event),
+    ensures
+        inductive(c, v),
+        high_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event),
+    {
+        let hc = constants_abstraction(c);
+        let (ws, events) = batch_next_is_run_of_host_steps(c, u, v, event);
+        let n = events.len() as int;
+        network_only_next_is_stuttering(c, u, &ws[0], Event::NoOp);
+
+        message_run_refines(c, ws, events, n);
+        let hs = Seq::new((n + 1) as nat, |j: int| variables_abstraction(c, &ws[j]));
+        assert forall |j: int| #![trigger hs[j]] 0 <= j < n implies high_next(&hc, &hs[j], &hs[j + 1], events[j]) by {
+            message_run_refines(c, ws, events, j + 1);
+        };
+        if (event.is_batch()) {
+            if let Event::DecideBatch { entries } = event {
+                let keys = entries.map_values(|entry: (nat, Value)| entry.0);
+                assert(keys.no_duplicates()) by {
+                    assert forall |i: int, j: int| 0 <= i < keys.len() && 0 <= j < keys.len() && i != j implies keys[i] != keys[j] by {
+                        assert(entries[i].0 != entries[j].0);
+                    };
+                };
+            }
+            assert(high_level::is_run(&hc, hs, event.unbatch()));
+        } else {
+            assert forall |j: int| #![trigger events[j]] 0 <= j < n implies events[j] == Event::NoOp by {
+                assert(events[j] is NoOp);
             };
-            assert(properties_of_valid_host_states(c, v)) by {
-                v.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some_is_inductive(c, u, event);
-                v.any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value_is_inductive(c, u, event);
-                v.same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts_is_inductive(c, u, event);
+            assert(event is NoOp) by {
+                let (host_id, net_op) = choose |host_id: int, net_op: NetworkOperation| #[trigger] batch_host_step(c, u, v, host_id, net_op, event);
             };
-            assert(system_quorum_properties(c, v)) by {
-                v.if_host_proposed_then_quorum_has_promised_is_inductive(c, u, event);
-                v.if_system_accepted_exists_some_accept_value_in_future_promise_quorum_is_inductive(c, u, event);
-                v.accepted_system_calculates_same_proposed_value_in_future_is_inductive(c, u, event);
-                v.accepted_system_always_proposes_same_value_in_future_is_inductive(c, u, event);
+        }
+        high_level::run_is_batch_step(&hc, hs, event);
+
+        // The network step before the run and the one after it leave the abstraction alone.
+        assert(hs[0] == variables_abstraction(c, u));
+        adding_batches_preserves_inductive(c, &ws[n], v);
+        assert(hs.last() == variables_abstraction(c, v));
+    }
+
+    // Each step of a run of message steps from a reachable state keeps the invariants and refines a
+    // high-level step on the event it took.
+    proof fn message_run_refines(c: &LowConstants, ws: Seq<LowVariables>, events: Seq<Event>, j: int)
+    requires
+        inductive(c, &ws[0]),
+        ws.len() == events.len() + 1,
+        forall |k: int| #![trigger ws[k]] 0 <= k < events.len() ==> message_next(c, &ws[k], &ws[k + 1], events[k]),
+        0 <= j < ws.len(),
+    ensures
+        inductive(c, &ws[j]),
+        j > 0 ==> high_next(&constants_abstraction(c), &variables_abstraction(c, &ws[j - 1]), &variables_abstraction(c, &ws[j]), events[j - 1]),
+    decreases
+        j
+    {
+        if (j > 0) {
+            message_run_refines(c, ws, events, j - 1);
+            assert(message_next(c, &ws[j - 1], &ws[j], events[j - 1]));
+            message_refinement_next(c, &ws[j - 1], &ws[j], events[j - 1]);
+        }
+    }
+
+    // Adding batches whose messages were already sent changes no invariant: each is stated over
+    // single-key messages, which stay the same, or over batches, whose messages are there.
+    proof fn adding_batches_preserves_inductive(c: &LowConstants, w: &LowVariables, v: &LowVariables)
+    requires
+        inductive(c, w),
+        adds_only_batches(c, w, v),
+    ensures
+        inductive(c, v),
+        variables_abstraction(c, v) == variables_abstraction(c, w),
+    {
+        assert forall |message: Message| #[trigger] v.network.sent_messages.contains(message) && !batch::is_batch(message) implies
+            w.network.sent_messages.contains(message) by {};
+
+        assert(host_map_properties(c, v));
+        assert(history_properties(c, v));
+        assert(configuration_properties(c, v));
+        assert(messages_in_network_implies_first_degree_properties(c, v));
+        assert(properties_imply_first_degree_messages_in_network(c, v));
+        assert(properties_of_valid_messages_in_network(c, v));
+        assert(properties_of_valid_host_states(c, v));
+        assert(system_quorum_properties(c, v));
+
+        assert(variables_abstraction(c, v).decided_value =~= variables_abstraction(c, w).decided_value);
+        assert(variables_abstraction(c, v).submitted =~= variables_abstraction(c, w).submitted);
+        assert(variables_abstraction(c, v).proposed =~= variables_abstraction(c, w).proposed);
+        assert(variables_abstraction(c, v).membership == variables_abstraction(c, w).membership);
+    }
+
+    // The single-key steps, one host step or network step at a time.
+    proof fn message_refinement_next(c: &LowConstants, u: &LowVariables, v: &LowVariables, event: Event)
+    requires
+        inductive(c, u),
+        message_next(c, u, v, event),
+    ensures
+        inductive(c, v),
+        high_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event),
+    {
+        next_is_host_next_or_network_only_next(c, u, v, event);
+
+        if (!host_next(c, u, v, event)) {
+            network_only_next_is_stuttering(c, u, v, event);
+        } else {
+            assert(inductive(c, v)) by {
+                assert(v.network.sent_messages.finite());
+                assert(v.network.in_flight_messages.subset_of(v.network.sent_messages));
+                assert(host_map_properties(c, v)) by { v.all_map_and_set_sizes_are_bounded_is_inductive(c, u, event); };
+                assert(history_properties(c, v)) by {
+                    v.volatile_state_is_recorded_in_history_is_inductive(c, u, event);
+                    v.leading_ballot_is_only_in_live_instances_is_inductive(c, u, event);
+                    v.learned_is_recorded_in_history_is_inductive(c, u, event);
+                };
+                assert(configuration_properties(c, v)) by { v.configuration_properties_are_inductive(c, u, event); };
+                assert(messages_in_network_implies_first_degree_properties(c, v)) by {
+                    v.phase1_and_phase2_quorums_in_host_maps_are_preserved(c, u, event);
+                    v.decide_msg_in_network_implies_quorum_has_accepted_some_value_is_inductive(c, u, event);
+                    v.nack_msg_in_network_implies_higher_ballot_was_prepared_is_inductive(c, u, event);
+                };
+                assert(properties_imply_first_degree_messages_in_network(c, v)) by {
+                    v.promised_state_implies_network_has_prepare_msg_is_inductive(c, u, event);
+                    v.if_accept_ballot_is_some_then_accept_value_is_some_is_inductive(c, u, event);
+                    v.accepted_state_implies_network_has_accept_message_is_inductive(c, u, event);
+                    v.someone_accepted_implies_network_has_their_accepted_msg_is_inductive(c, u, event);
+                    v.learned_senders_have_sent_accepted_is_inductive(c, u, event);
+                    v.proposals_were_sent_is_inductive(c, u, event);
+                    v.decided_state_implies_network_has_decide_message_is_inductive(c, u, event);
+                };
+                assert(properties_of_valid_messages_in_network(c, v)) by {
+                    v.network_msgs_have_valid_sender_and_ballot_pid_is_inductive(c, u, event);
+                    v.promise_msgs_from_same_sender_for_same_ballot_have_same_accepted_is_inductive(c, u, event);
+                    v.accepted_msg_in_network_implies_future_promises_of_same_sender_have_some_accepted_is_inductive(c, u, event);
+                    v.value_in_accepted_of_promise_is_same_as_proposed_value_for_corresponding_ballot_is_inductive(c, u, event);
+                    v.accepted_msg_in_network_implies_network_has_corresponding_accept_msg_is_inductive(c, u, event);
+                    v.fast_accepted_msgs_from_same_sender_have_same_value_is_inductive(c, u, event);
+                    v.all_decide_messages_hold_same_value_is_inductive(c, u, event);
+                    v.batch_msgs_in_network_carry_sent_msgs_is_inductive(c, u, event);
+                };
+                assert(properties_of_valid_host_states(c, v)) by {
+                    v.if_host_maps_have_ballot_then_network_has_prepare_msg_with_same_ballot_is_inductive(c, u, event);
+                    v.floors_and_leads_were_prepared_from_is_inductive(c, u, event);
+                    v.if_host_proposed_some_value_it_is_always_same_as_get_max_accepted_value_if_some_is_inductive(c, u, event);
+                    v.host_ballots_are_at_most_max_seen_ballot_is_inductive(c, u, event);
+                    v.any_two_hosts_with_some_same_accept_ballot_have_some_same_accept_value_is_inductive(c, u, event);
+                    v.same_accepted_ballots_have_same_value_in_accepted_map_in_promised_of_all_hosts_is_inductive(c, u, event);
+                    v.pending_requests_are_in_network_is_inductive(c, u, event);
+                    v.submitted_values_are_not_no_ops_is_inductive(c, u, event);
+                    v.proposed_values_were_submitted_is_inductive(c, u, event);
+                    v.pending_requests_are_owned_and_distinct_is_inductive(c, u, event);
+                    v.pending_requests_were_not_proposed_is_inductive(c, u, event);
+                    v.pending_requests_are_not_assigned_is_inductive(c, u, event);
+                    v.proposed_requests_are_assigned_is_inductive(c, u, event);
+                    v.proposed_requests_have_one_value_is_inductive(c, u, event);
+                    v.proposed_values_are_proposed_in_one_slot_is_inductive(c, u, event);
+                };
+                assert(system_quorum_properties(c, v)) by {
+                    v.if_host_proposed_then_quorum_has_promised_is_inductive(c, u, event);
+                    v.if_system_accepted_exists_some_accept_value_in_future_promise_quorum_is_inductive(c, u, event);
+                    v.accepted_system_calculates_same_proposed_value_in_future_is_inductive(c, u, event);
+                    v.accepted_system_always_proposes_same_value_in_future_is_inductive(c, u, event);
+                };
             };
-        };
 
-        assert(high_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event)) by {
-            assert(host_map_properties(c, v));
-            inductive_is_safe(c, v);
-
-            let Transition::HostStep { host_id, instance: step_key, net_op } = choose |transition: Transition| is_valid_transition(c, u, v, transition, event);
-            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id]);
-
-            match (event) {
-                Event::Decide { key, value } => {
-                    assert(forall |i: int| #![auto] 0 <= i < u.hosts.len() ==> v.hosts[i].instances.dom() == u.hosts[i].instances.dom());
-
-                    let old_calculated_map = Map::new(
-                        |key: nat| (exists |i: int| #![auto] 0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) && u.hosts[i].instances[key].decide_value.is_some()),
-                        |key: nat| {
-                            let host = choose |i: int| #![auto] 0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) && u.hosts[i].instances[key].decide_value.is_some();
-                            u.hosts[host].instances[key].decide_value.unwrap()
-                        },
-                    );
-
-                    let new_calculated_map = Map::new(
-                        |key: nat| (exists |i: int| #![auto] 0 <= i < v.hosts.len() && v.hosts[i].instances.contains_key(key) && v.hosts[i].instances[key].decide_value.is_some()),
-                        |key: nat| {
-                            let host = choose |i: int| #![auto] 0 <= i < v.hosts.len() && v.hosts[i].instances.contains_key(key) && v.hosts[i].instances[key].decide_value.is_some();
-                            v.hosts[host].instances[key].decide_value.unwrap()
-                        },
-                    );
-
-                    assert(new_calculated_map.dom() =~= old_calculated_map.dom().insert(key));
-
-                    if (old_calculated_map.contains_key(key)) {
-                        assert(new_calculated_map[key] == old_calculated_map[key]);
-                    }
-
-                    assert(new_calculated_map =~= old_calculated_map.insert(key, value));
-                    assert(high_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event));
-                },
-                Event::NoOp => {
-                    if (host::init_request(lc, lu, lv, step_key, net_op)) {
-                        assert(forall |i: int| #![auto] 0 <= i < u.hosts.len() && i != host_id ==> v.hosts[i].instances.dom() == u.hosts[i].instances.dom());
-                    } else {
+            assert(high_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event)) by {
+                assert(host_map_properties(c, v));
+                inductive_is_safe(c, v);
+
+                let (host_id, step_key, net_op) = choose |host_id: int, step_key: nat, net_op: NetworkOperation| #[trigger] is_valid_transition(c, u, v, Transition::HostStep { host_id, instance: step_key, net_op }, event);
+                let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id]);
+                v.history_follows_host_step(c, u, event, host_id, step_key, net_op);
+
+                match (event) {
+                    // No single-key step takes a batch event.
+                    Event::ProposeBatch { .. } | Event::DecideBatch { .. } => {
+                        assert(!host::step(lc, lu, lv, step_key, net_op, event));
+                    },
+                    Event::Decide { key, value } => {
                         assert(forall |i: int| #![auto] 0 <= i < u.hosts.len() ==> v.hosts[i].instances.dom() == u.hosts[i].instances.dom());
-                    }
-
-                    assert(forall |i: int, key: nat| #![auto]
-                        0 <= i < u.hosts.len() &&
-                        u.hosts[i].instances.contains_key(key) ==>
-                        v.hosts[i].instances[key].decide_value == u.hosts[i].instances[key].decide_value);
-
-                    let old_calculated_map = Map::new(
-                        |key: nat| (exists |i: int| #![auto] 0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) && u.hosts[i].instances[key].decide_value.is_some()),
-                        |key: nat| {
-                            let host = choose |i: int| #![auto] 0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) && u.hosts[i].instances[key].decide_value.is_some();
-                            u.hosts[host].instances[key].decide_value.unwrap()
-                        },
-                    );
-
-                    let new_calculated_map = Map::new(
-                        |key: nat| (exists |i: int| #![auto] 0 <= i < v.hosts.len() && v.hosts[i].instances.contains_key(key) && v.hosts[i].instances[key].decide_value.is_some()),
-                        |key: nat| {
-                            let host = choose |i: int| #![auto] 0 <= i < v.hosts.len() && v.hosts[i].instances.contains_key(key) && v.hosts[i].instances[key].decide_value.is_some();
-                            v.hosts[host].instances[key].decide_value.unwrap()
-                        },
-                    );
-
-                    assert(variables_abstraction(c, u).decided_value =~= old_calculated_map);
-                    assert(variables_abstraction(c, v).decided_value =~= new_calculated_map);
-
-                    assert(new_calculated_map =~= old_calculated_map);
-                    assert(high_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event));
-                },
+
+                        let old_calculated_map = Map::new(
+                            |key: nat| (exists |i: int| #![auto] 0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) && u.hosts[i].instances[key].decide_value.is_some()),
+                            |key: nat| {
+                                let host = choose |i: int| #![auto] 0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) && u.hosts[i].instances[key].decide_value.is_some();
+                                u.hosts[host].instances[key].decide_value.unwrap()
+                            },
+                        );
+
+                        let new_calculated_map = Map::new(
+                            |key: nat| (exists |i: int| #![auto] 0 <= i < v.hosts.len() && v.hosts[i].instances.contains_key(key) && v.hosts[i].instances[key].decide_value.is_some()),
+                            |key: nat| {
+                                let host = choose |i: int| #![auto] 0 <= i < v.hosts.len() && v.hosts[i].instances.contains_key(key) && v.hosts[i].instances[key].decide_value.is_some();
+                                v.hosts[host].instances[key].decide_value.unwrap()
+                            },
+                        );
+
+                        assert(new_calculated_map.dom() =~= old_calculated_map.dom().insert(key));
+
+                        if (old_calculated_map.contains_key(key)) {
+                            assert(new_calculated_map[key] == old_calculated_map[key]);
+                        }
+
+                        assert(new_calculated_map =~= old_calculated_map.insert(key, value));
+                        high_level::decide_reconfigures(&constants_abstraction(c), old_calculated_map, key, value);
+
+                        if let Some(Message::Decide { key: instance, ballot, value: recv_value }) = net_op.recv {
+                            let leader = ballot.pid as int;
+                            assert(u.network.sent_messages.contains(Message::Decide { key: instance, ballot, value: recv_value }));
+                            if (host::is_fast_ballot(ballot)) {
+                                assert(u.configurations.contains_key(instance));
+                                fast_quorum_accepted_value_was_fast_proposed(c, u, instance, recv_value);
+                            } else {
+                                assert(u.host_proposed(leader, instance, ballot));
+                                let proposal = u.history[leader][instance].proposed_value[ballot];
+                                assert(u.value_was_submitted(proposal) || proposal == no_op(instance));
+                            }
+                            assert(variables_abstraction(c, u).submitted.contains(value) || value == no_op(key));
+                            assert(variables_abstraction(c, u).proposed.contains(value));
+                        } else if (host::learn(lc, lu, lv, step_key, net_op, value)) {
+                            let ballot = lu.instances[step_key].current_ballot;
+                            assert(u.host_proposed(host_id, step_key, ballot));
+                            let proposal = u.history[host_id][step_key].proposed_value[ballot];
+                            assert(u.value_was_submitted(proposal) || proposal == no_op(step_key));
+                            assert(variables_abstraction(c, u).submitted.contains(value) || value == no_op(key));
+                            assert(variables_abstraction(c, u).proposed.contains(value));
+                        } else {
+                            let ballot = choose |ballot: host::Ballot| #[trigger] host::learner_decide(lc, lu, lv, step_key, net_op, ballot, value);
+                            if (host::is_fast_ballot(ballot)) {
+                                // The learner counted a fast quorum; the Decide it sent says so, and the
+                                // Accepted messages it counted were there before.
+                                assert(v.network.sent_messages.contains(Message::Decide { key: step_key, ballot, value }));
+                                assert(v.configurations.contains_key(step_key) && lv.instances.contains_key(step_key));
+                                fast_quorum_accepted_value_was_fast_proposed(c, v, step_key, value);
+                                let (k, s) = choose |k: nat, s: nat| #[trigger] v.network.sent_messages.contains(Message::Accepted { key: k, sender: s, ballot: host::fast_ballot(), value });
+                                assert(u.network.sent_messages.contains(Message::Accepted { key: k, sender: s, ballot: host::fast_ballot(), value }));
+                                assert(u.value_was_fast_proposed(value));
+                                assert(u.value_was_submitted(value));
+                            } else {
+                                let senders = lu.instances[step_key].volatile.learned[(ballot, value)];
+                                assert(u.configurations.contains_key(step_key) && lu.instances[step_key].configuration == u.configuration(step_key));
+                                quorum::phase2_quorum_has_some_host(u.configuration(step_key).classic, c.num_hosts, c.num_failures, senders);
+                                let sender = choose |h: nat| #![auto] senders.contains(h);
+                                assert(u.network.sent_messages.contains(Message::Accepted { key: step_key, sender, ballot, value }));
+                                assert(u.accept_was_sent(step_key, ballot, value));
+                                assert(u.network.sent_messages.contains(Message::Accept { key: step_key, ballot, value }));
+
+                                let leader = ballot.pid as int;
+                                assert(u.host_proposed(leader, step_key, ballot));
+                                let proposal = u.history[leader][step_key].proposed_value[ballot];
+                                assert(u.value_was_submitted(proposal) || proposal == no_op(step_key));
+                            }
+                            assert(variables_abstraction(c, u).submitted.contains(value) || value == no_op(key));
+                            assert(variables_abstraction(c, u).proposed.contains(value));
+                        }
+                        assert(variables_abstraction(c, v).submitted =~= variables_abstraction(c, u).submitted);
+
+                        v.host_step_only_adds_the_send_accept_proposal(c, u, event, host_id, step_key, net_op);
+                        assert(!(net_op.send matches Some(Message::Accepted { .. })));
+                        proposed_values_are_same_if_proposals_are_same(u, v);
+                        assert(variables_abstraction(c, v).proposed =~= variables_abstraction(c, u).proposed);
+                        assert(high_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event));
+                    },
+                    Event::Submit { value } => {
+                        assert(v.hosts =~= u.hosts);
+                        assert(v.network.sent_messages.contains(Message::ClientRequest { value }));
+
+                        assert(variables_abstraction(c, v).decided_value =~= variables_abstraction(c, u).decided_value);
+                        assert(variables_abstraction(c, v).submitted =~= variables_abstraction(c, u).submitted.insert(value));
+                        assert(variables_abstraction(c, v).proposed =~= variables_abstraction(c, u).proposed);
+                        assert(high_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event));
+                    },
+                    Event::Propose { .. } | Event::NoOp => {
+                        if (host::init_request(lc, lu, lv, step_key, net_op)) {
+                            assert(forall |i: int| #![auto] 0 <= i < u.hosts.len() && i != host_id ==> v.hosts[i].instances.dom() == u.hosts[i].instances.dom());
+                        } else {
+                            assert(forall |i: int| #![auto] 0 <= i < u.hosts.len() ==> v.hosts[i].instances.dom() == u.hosts[i].instances.dom());
+                        }
+
+                        assert(forall |i: int, key: nat| #![auto]
+                            0 <= i < u.hosts.len() &&
+                            u.hosts[i].instances.contains_key(key) ==>
+                            v.hosts[i].instances[key].decide_value == u.hosts[i].instances[key].decide_value);
+
+                        let old_calculated_map = Map::new(
+                            |key: nat| (exists |i: int| #![auto] 0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) && u.hosts[i].instances[key].decide_value.is_some()),
+                            |key: nat| {
+                                let host = choose |i: int| #![auto] 0 <= i < u.hosts.len() && u.hosts[i].instances.contains_key(key) && u.hosts[i].instances[key].decide_value.is_some();
+                                u.hosts[host].instances[key].decide_value.unwrap()
+                            },
+                        );
+
+                        let new_calculated_map = Map::new(
+                            |key: nat| (exists |i: int| #![auto] 0 <= i < v.hosts.len() && v.hosts[i].instances.contains_key(key) && v.hosts[i].instances[key].decide_value.is_some()),
+                            |key: nat| {
+                                let host = choose |i: int| #![auto] 0 <= i < v.hosts.len() && v.hosts[i].instances.contains_key(key) && v.hosts[i].instances[key].decide_value.is_some();
+                                v.hosts[host].instances[key].decide_value.unwrap()
+                            },
+                        );
+
+                        assert(variables_abstraction(c, u).decided_value =~= old_calculated_map);
+                        assert(variables_abstraction(c, v).decided_value =~= new_calculated_map);
+
+                        assert(new_calculated_map =~= old_calculated_map);
+
+                        assert(!(net_op.send matches Some(Message::ClientRequest { .. })));
+                        assert(variables_abstraction(c, v).submitted =~= variables_abstraction(c, u).submitted);
+
+                        v.host_step_only_adds_the_send_accept_proposal(c, u, event, host_id, step_key, net_op);
+
+                        if let Event::Propose { key, value } = event {
+                            let ballot = lv.instances[step_key].current_ballot;
+                            let fast = host::fast_accept(lc, lu, lv, step_key, net_op) && value == lv.instances[step_key].accept_value.unwrap();
+
+                            if (fast) {
+                                // An acceptor takes a client's request in the fast round without any
+                                // leader. Another acceptor may have taken it already, which proposes it
+                                // again.
+                                assert(net_op.recv == Some(Message::ClientRequest { value }));
+                                assert(v.network.sent_messages.contains(Message::Accepted { key: step_key, sender: lc.id, ballot: host::fast_ballot(), value }));
+                                assert(v.value_was_fast_proposed(value));
+                                assert(u.value_was_submitted(value));
+                            } else {
+                                // A request some client submitted, or the no-op of `key`.
+                                assert(v.host_proposed(host_id, step_key, ballot));
+                                assert(v.value_was_submitted(value) || value == no_op(key));
+                            }
+                            assert(variables_abstraction(c, v).proposed.contains(value));
+                            assert(variables_abstraction(c, u).submitted.contains(value) || value == no_op(key));
+
+                            assert forall |other: Value| #[trigger] v.value_was_proposed(other) && !u.value_was_proposed(other) implies other == value by {
+                                if (v.value_was_fast_proposed(other)) {
+                                    // `send_accept` sends an Accept, so a new Accepted is `fast_accept`'s.
+                                    let (k, s) = choose |k: nat, s: nat| #[trigger] v.network.sent_messages.contains(Message::Accepted { key: k, sender: s, ballot: host::fast_ballot(), value: other });
+                                    assert(!u.network.sent_messages.contains(Message::Accepted { key: k, sender: s, ballot: host::fast_ballot(), value: other }));
+                                    assert(net_op.send == Some(Message::Accepted { key: k, sender: s, ballot: host::fast_ballot(), value: other }));
+                                    assert(fast);
+                                } else {
+                                    let (i, instance, proposal) = choose |i: int, instance: nat, proposal: host::Ballot|
+                                        0 <= i < v.hosts.len() &&
+                                        #[trigger] v.host_proposed(i, instance, proposal) &&
+                                        v.history[i][instance].proposed_value[proposal] == other;
+                                    if (!u.host_proposed(i, instance, proposal)) {
+                                        assert(host::send_accept(lc, lu, lv, step_key, net_op));
+                                        assert(!fast);
+                                    } else {
+                                        assert(u.history[i][instance].proposed_value[proposal] == other);
+                                    }
+                                }
+                            };
+                            assert forall |other: Value| #[trigger] u.value_was_proposed(other) implies v.value_was_proposed(other) by {
+                                if (u.value_was_fast_proposed(other)) {
+                                    let (k, s) = choose |k: nat, s: nat| #[trigger] u.network.sent_messages.contains(Message::Accepted { key: k, sender: s, ballot: host::fast_ballot(), value: other });
+                                    assert(v.network.sent_messages.contains(Message::Accepted { key: k, sender: s, ballot: host::fast_ballot(), value: other }));
+                                } else {
+                                    let (i, instance, proposal) = choose |i: int, instance: nat, proposal: host::Ballot|
+                                        0 <= i < u.hosts.len() &&
+                                        #[trigger] u.host_proposed(i, instance, proposal) &&
+                                        u.history[i][instance].proposed_value[proposal] == other;
+                                    assert(v.host_proposed(i, instance, proposal));
+                                }
+                            };
+                            assert(variables_abstraction(c, v).proposed =~= variables_abstraction(c, u).proposed.insert(value));
+                        } else {
+                            // The only Accepted a NoOp step sends is `accept`'s, for the classic ballot
+                            // of an Accept.
+                            if let Some(Message::Accept { key: recv_key, ballot, value: recv_value }) = net_op.recv {
+                                assert(u.network.sent_messages.contains(Message::Accept { key: recv_key, ballot, value: recv_value }));
+                                assert(ballot.num > 0);
+                            }
+                            assert(!host::fast_accept(lc, lu, lv, step_key, net_op));
+                            proposed_values_are_same_if_proposals_are_same(u, v);
+                            assert(variables_abstraction(c, v).proposed =~= variables_abstraction(c, u).proposed);
+                        }
+
+                        assert(high_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event));
+                    },
+                };
             };
+        }
+    }
+
+    // Corresponds to `inductive(c, u) && next(c, u, v) ==> inductive(c, v) && abstraction(u) == abstraction(v)` for
+    // drop, duplicate and garbage-collect steps: every invariant is stated over the sent history.
+    proof fn network_only_next_is_stuttering(c: &LowConstants, u: &LowVariables, v: &LowVariables, event: Event)
+    requires
+        inductive(c, u),
+        network_only_next(c, u, v, event),
+    ensures
+        inductive(c, v),
+        high_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event),
+    {
+        assert(v.network.in_flight_messages.subset_of(v.network.sent_messages));
+        assert(host_map_properties(c, v));
+        assert(history_properties(c, v));
+        assert(configuration_properties(c, v));
+        assert(messages_in_network_implies_first_degree_properties(c, v));
+        assert(properties_imply_first_degree_messages_in_network(c, v));
+        assert(properties_of_valid_messages_in_network(c, v));
+        assert(properties_of_valid_host_states(c, v));
+        assert(system_quorum_properties(c, v));
+
+        assert(variables_abstraction(c, v).decided_value =~= variables_abstraction(c, u).decided_value);
+        assert(variables_abstraction(c, v).submitted =~= variables_abstraction(c, u).submitted);
+        assert(variables_abstraction(c, v).proposed =~= variables_abstraction(c, u).proposed);
+        assert(variables_abstraction(c, v).membership == variables_abstraction(c, u).membership);
+    }
+
+    // Steps that leave every `proposed_value` map untouched (newly created instances start empty)
+    // and send no Accepted for the fast ballot leave the set of proposed values untouched as well.
+    proof fn proposed_values_are_same_if_proposals_are_same(u: &LowVariables, v: &LowVariables)
+    requires
+        u.hosts.len() == v.hosts.len(),
+        forall |i: int, instance: nat, ballot: host::Ballot|
+            0 <= i < u.hosts.len() ==>
+            (#[trigger] v.host_proposed(i, instance, ballot) <==> u.host_proposed(i, instance, ballot)),
+        forall |i: int, instance: nat, ballot: host::Ballot|
+            0 <= i < u.hosts.len() &&
+            #[trigger] u.host_proposed(i, instance, ballot) ==>
+            v.history[i][instance].proposed_value[ballot] == u.history[i][instance].proposed_value[ballot],
+        forall |key: nat, sender: nat, value: Value|
+            #[trigger] v.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value }) <==>
+            u.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value }),
+    ensures
+        forall |value: Value| #[trigger] v.value_was_proposed(value) <==> u.value_was_proposed(value),
+    {
+        assert forall |value: Value| #[trigger] v.value_was_proposed(value) implies u.value_was_proposed(value) by {
+            if (v.value_was_fast_proposed(value)) {
+                let (key, sender) = choose |key: nat, sender: nat| #[trigger] v.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value });
+                assert(u.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value }));
+            } else {
+                let (i, instance, ballot) = choose |i: int, instance: nat, ballot: host::Ballot|
+                    0 <= i < v.hosts.len() &&
+                    #[trigger] v.host_proposed(i, instance, ballot) &&
+                    v.history[i][instance].proposed_value[ballot] == value;
+                assert(u.host_proposed(i, instance, ballot));
+            }
+        };
+        assert forall |value: Value| #[trigger] u.value_was_proposed(value) implies v.value_was_proposed(value) by {
+            if (u.value_was_fast_proposed(value)) {
+                let (key, sender) = choose |key: nat, sender: nat| #[trigger] u.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value });
+                assert(v.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value }));
+            } else {
+                let (i, instance, ballot) = choose |i: int, instance: nat, ballot: host::Ballot|
+                    0 <= i < u.hosts.len() &&
+                    #[trigger] u.host_proposed(i, instance, ballot) &&
+                    u.history[i][instance].proposed_value[ballot] == value;
+                assert(v.host_proposed(i, instance, ballot));
+            }
+        };
+    }
+
+    // A value a fast quorum accepted is a fast client's request some acceptor took for its
+    // `fast_slot`, so it was proposed in the fast round and submitted by that client.
+    proof fn fast_quorum_accepted_value_was_fast_proposed(c: &LowConstants, u: &LowVariables, key: nat, value: Value)
+    requires
+        inductive(c, u),
+        u.configurations.contains_key(key),
+        u.fast_quorum_has_accepted(c, key, value),
+    ensures
+        key == host::fast_slot(value),
+        c.is_fast_request(value),
+        u.value_was_fast_proposed(value),
+        u.value_was_proposed(value),
+        u.value_was_submitted(value),
+    {
+        let senders = choose |senders: Set<nat>| #[trigger] u.fast_accepted_by(c, key, value, senders);
+        fast::fast_quorums_have_common_host(u.configuration(key), c.num_hosts, c.num_failures, senders, senders);
+        let sender = choose |h: nat| #![auto] senders.contains(h) && senders.contains(h);
+        assert(u.network.sent_messages.contains(Message::Accepted { key, sender, ballot: host::fast_ballot(), value }));
+        assert(u.accept_was_sent(key, host::fast_ballot(), value));
+        assert(u.value_was_fast_proposed(value));
+    }
+
+    pub open spec fn instance_keys_of_hosts(hosts: Seq<host::Variables>, count: nat) -> Set<nat>
+    decreases
+        count
+    {
+        if (count == 0) {
+            Set::empty()
+        } else {
+            instance_keys_of_hosts(hosts, (count - 1) as nat) + hosts[count - 1].instances.dom()
+        }
+    }
+
+    proof fn instance_keys_of_hosts_is_finite_union(hosts: Seq<host::Variables>, count: nat)
+    requires
+        count <= hosts.len(),
+        forall |i: int| #![auto] 0 <= i < count ==> hosts[i].instances.dom().finite(),
+    ensures
+        instance_keys_of_hosts(hosts, count).finite(),
+        forall |i: int, key: nat| #![auto] 0 <= i < count && hosts[i].instances.contains_key(key) ==> instance_keys_of_hosts(hosts, count).contains(key),
+    decreases
+        count
+    {
+        if (count > 0) {
+            instance_keys_of_hosts_is_finite_union(hosts, (count - 1) as nat);
+        }
+    }
+
+    proof fn decided_value_domain_is_finite(c: &LowConstants, u: &LowVariables)
+    requires
+        inductive(c, u),
+    ensures
+        variables_abstraction(c, u).decided_value.dom().finite(),
+    {
+        let keys = instance_keys_of_hosts(u.hosts, u.hosts.len());
+        instance_keys_of_hosts_is_finite_union(u.hosts, u.hosts.len());
+        assert(variables_abstraction(c, u).decided_value.dom().subset_of(keys));
+        lemma_len_subset(variables_abstraction(c, u).decided_value.dom(), keys);
+    }
+
+    pub open spec fn host_decided_value(lv: &host::Variables) -> Map<nat, Value> {
+        Map::new(
+            |key: nat| lv.instances.contains_key(key) && lv.instances[key].decide_value.is_some(),
+            |key: nat| lv.instances[key].decide_value.unwrap(),
+        )
+    }
+
+    // Corresponds to `inductive(c, u) && next(c, u, v) ==> next(log(abstraction(c)), log(abstraction(u)), log(abstraction(v)))`
+    proof fn log_refinement_next(c: &LowConstants, u: &LowVariables, v: &LowVariables, event: Event)
+    requires
+        inductive(c, u),
+        low_next(c, u, v, event),
+    ensures
+        replicated_log::next(
+            &replicated_log::constants_abstraction(&constants_abstraction(c)),
+            &replicated_log::variables_abstraction(&constants_abstraction(c), &variables_abstraction(c, u)),
+            &replicated_log::variables_abstraction(&constants_abstraction(c), &variables_abstraction(c, v)),
+            event,
+        ),
+    {
+        refinement_next(c, u, v, event);
+        decided_value_domain_is_finite(c, u);
+        replicated_log::refinement_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event);
+    }
+
+    // Every replica's locally applicable log (its own contiguous run of decided slots) is a prefix of
+    // the system log, so all replicas apply the same commands in the same order.
+    proof fn replica_log_is_prefix_of_system_log(c: &LowConstants, u: &LowVariables, i: int)
+    requires
+        inductive(c, u),
+        0 <= i < u.hosts.len(),
+    ensures
+        replicated_log::log_of(host_decided_value(&u.hosts[i])).is_prefix_of(replicated_log::log_of(variables_abstraction(c, u).decided_value)),
+    {
+        decided_value_domain_is_finite(c, u);
+        host_decisions_are_decided(c, u, i);
+
+        let (local, global) = (host_decided_value(&u.hosts[i]), variables_abstraction(c, u).decided_value);
+        lemma_len_subset(local.dom(), global.dom());
+
+        replicated_log::contiguous_prefix_of_submap_is_prefix(local, global, local.dom().len(), global.dom().len());
+    }
+
+    // What a host has decided is what the system has decided.
+    proof fn host_decisions_are_decided(c: &LowConstants, u: &LowVariables, i: int)
+    requires
+        inductive(c, u),
+        0 <= i < u.hosts.len(),
+    ensures
+        host_decided_value(&u.hosts[i]).submap_of(variables_abstraction(c, u).decided_value),
+    {
+        inductive_is_safe(c, u);
+
+        let (local, global) = (host_decided_value(&u.hosts[i]), variables_abstraction(c, u).decided_value);
+
+        assert forall |key: nat| #[trigger] local.contains_key(key) implies global.contains_key(key) && global[key] == local[key] by {
+            let j = choose |j: int| #![auto] 0 <= j < u.hosts.len() && u.hosts[j].instances.contains_key(key) && u.hosts[j].instances[key].decide_value.is_some();
+            if (i < j) {
+                assert(u.hosts[i].instances[key].decide_value == u.hosts[j].instances[key].decide_value);
+            } else if (j < i) {
+                assert(u.hosts[j].instances[key].decide_value == u.hosts[i].instances[key].decide_value);
+            }
+        };
+    }
+
+    // The acceptors an instance counts votes from are the ones the high-level membership history
+    // names for it: the host fixed them from decisions it had learned, which are the system's.
+    proof fn instance_acceptors_are_membership(c: &LowConstants, u: &LowVariables, i: int, key: nat)
+    requires
+        inductive(c, u),
+        0 <= i < u.hosts.len(),
+        u.hosts[i].instances.contains_key(key),
+    ensures
+        u.hosts[i].instances[key].configuration.classic.acceptors == high_level::acceptors_at(&constants_abstraction(c), variables_abstraction(c, u).membership, key),
+    {
+        let lu = &u.hosts[i];
+        let (local, global) = (host_decided_value(lu), variables_abstraction(c, u).decided_value);
+        host_decisions_are_decided(c, u, i);
+
+        assert(u.configurations.contains_key(key) && lu.instances[key].configuration == u.configuration(key));
+        let decisions = choose |decisions: Map<nat, Value>|
+            #[trigger] u.decisions_were_sent(c, decisions, key) &&
+            u.configurations[key] == c.reconfig.configuration(c.fast_quorums(), c.num_failures, decisions, key);
+
+        assert(c.hosts[i].reconfig == c.reconfig && c.hosts[i].knows_configuration(lu, key));
+        assert forall |j: nat| j + c.reconfig.alpha <= key implies #[trigger] global.contains_key(j) && decisions[j] == global[j] by {
+            assert(lu.decisions().contains_key(j) && local.contains_key(j));
+            assert(decisions.contains_key(j));
+            let b1 = choose |ballot: host::Ballot| #[trigger] u.network.sent_messages.contains(Message::Decide { key: j, ballot, value: decisions[j] });
+            let b2 = choose |ballot: host::Ballot| #[trigger] u.network.sent_messages.contains(Message::Decide { key: j, ballot, value: local[j] });
+        };
+        assert(c.reconfig.knows_configuration(global, key));
+        assert forall |j: nat| j + c.reconfig.alpha <= key implies #[trigger] decisions[j] == global[j] by {
+            assert(global.contains_key(j) && decisions[j] == global[j]);
         };
+
+        c.reconfig.configuration_depends_on_known_decisions(c.fast_quorums(), c.num_failures, decisions, global, key);
+        configuration_acceptors_are_membership(c, global, key);
+    }
+
+    // `configuration` and `acceptors_at` walk back to the same command, or to the initial acceptors.
+    proof fn configuration_acceptors_are_membership(c: &LowConstants, decided: Map<nat, Value>, key: nat)
+    ensures
+        c.reconfig.configuration(c.fast_quorums(), c.num_failures, decided, key).classic.acceptors ==
+            high_level::acceptors_at(&constants_abstraction(c), high_level::membership_of(&constants_abstraction(c), decided), key),
+    decreases
+        key
+    {
+        if (key > 0 && key >= c.reconfig.alpha) {
+            let j = (key - c.reconfig.alpha) as nat;
+            if (!(decided.contains_key(j) && (c.reconfig.members)(decided[j]).is_some())) {
+                configuration_acceptors_are_membership(c, decided, (key - 1) as nat);
+            }
+        }
     }
 
     // Corresponds to `inductive(c, u) ==> safety(c, u)`
@@ -166,4 +767,84 @@ verus! {
     ensures
         safety(c, u)
     { }
+
+    // Corresponds to `inductive(c, u) ==> validity(c, u)`
+    proof fn inductive_is_valid(c: &LowConstants, u: &LowVariables)
+    requires
+        inductive(c, u)
+    ensures
+        validity(c, u)
+    {
+        assert forall |i: int, instance: nat| #![auto]
+            0 <= i < u.hosts.len() &&
+            u.hosts[i].instances.contains_key(instance) &&
+            u.hosts[i].instances[instance].decide_value.is_some() implies
+            u.value_was_proposed(u.hosts[i].instances[instance].decide_value.unwrap())
+        by {
+            let value = u.hosts[i].instances[instance].decide_value.unwrap();
+            let ballot = choose |ballot: host::Ballot| #![auto] u.network.sent_messages.contains(Message::Decide { key: instance, ballot, value });
+            let leader = ballot.pid as int;
+
+            if (host::is_fast_ballot(ballot)) {
+                fast_quorum_accepted_value_was_fast_proposed(c, u, instance, value);
+            } else {
+                assert(u.host_proposed(leader, instance, ballot));
+                assert(u.history[leader][instance].proposed_value[ballot] == value);
+            }
+        };
+    }
+
+    // Corresponds to `inductive(c, u) ==> requests_are_decided_at_most_once(c, u)`
+    proof fn inductive_decides_requests_at_most_once(c: &LowConstants, u: &LowVariables)
+    requires
+        inductive(c, u)
+    ensures
+        requests_are_decided_at_most_once(c, u)
+    {
+        assert forall |i: int, k1: nat, j: int, k2: nat| #![auto]
+            0 <= i < u.hosts.len() &&
+            0 <= j < u.hosts.len() &&
+            u.hosts[i].instances.contains_key(k1) &&
+            u.hosts[j].instances.contains_key(k2) &&
+            u.hosts[i].instances[k1].decide_value.is_some() &&
+            u.hosts[j].instances[k2].decide_value.is_some() &&
+            u.hosts[i].instances[k1].decide_value.unwrap().request() == u.hosts[j].instances[k2].decide_value.unwrap().request() implies
+            k1 == k2
+        by {
+            // Each decision is the value its Decide's leader proposed in that instance, or in the fast
+            // round a fast request for that instance.
+            let (v1, v2) = (u.hosts[i].instances[k1].decide_value.unwrap(), u.hosts[j].instances[k2].decide_value.unwrap());
+            let b1 = choose |ballot: host::Ballot| #![auto] u.network.sent_messages.contains(Message::Decide { key: k1, ballot, value: v1 });
+            let b2 = choose |ballot: host::Ballot| #![auto] u.network.sent_messages.contains(Message::Decide { key: k2, ballot, value: v2 });
+            let (l1, l2) = (b1.pid as int, b2.pid as int);
+
+            if (host::is_fast_ballot(b1)) {
+                fast_quorum_accepted_value_was_fast_proposed(c, u, k1, v1);
+            } else {
+                assert(u.host_proposed(l1, k1, b1));
+                assert(u.history[l1][k1].proposed_value[b1] == v1);
+            }
+            if (host::is_fast_ballot(b2)) {
+                fast_quorum_accepted_value_was_fast_proposed(c, u, k2, v2);
+            } else {
+                assert(u.host_proposed(l2, k2, b2));
+                assert(u.history[l2][k2].proposed_value[b2] == v2);
+            }
+
+            assert(u.value_was_proposed(v1) && u.value_was_proposed(v2));
+            assert(v1.client_id == v2.client_id && v1.seq == v2.seq);
+
+            if (c.is_fast_request(v1)) {
+                // A fast request is only ever accepted or proposed in its `fast_slot`, its `seq`.
+                assert(c.is_fast_request(v2));
+                assert(k1 == host::fast_slot(v1));
+                assert(k2 == host::fast_slot(v2));
+            } else {
+                // Both proposals are of the same request, so they are the same value, which leaders
+                // propose in one slot only: the fast round takes nothing but fast requests.
+                assert(v1 == v2);
+                assert(!host::is_fast_ballot(b1) && !host::is_fast_ballot(b2));
+            }
+        };
+    }
 }
@@ -0,0 +1,416 @@
+use super::{Message, NetworkOperation};
+use crate::distributed_system::{low_level::quorum::are_valid_hosts, Event, Value};
+use vstd::prelude::*;
+
+verus! {
+    pub struct Entry {
+        pub term: nat,
+        pub value: Value,
+    }
+
+    pub struct Constants {
+        pub id: nat,
+        pub num_hosts: nat,
+    }
+
+    pub enum Role {
+        Follower,
+        Candidate,
+        Leader,
+    }
+
+    // `log[0..commit_index]` is committed. `votes` is only meaningful for a candidate and
+    // `match_index` only for a leader, both for `current_term`.
+    pub struct Variables {
+        pub current_term: nat,
+        pub voted_for: Option<nat>,
+        pub log: Seq<Entry>,
+        pub commit_index: nat,
+        pub role: Role,
+        pub votes: Set<nat>,
+        pub match_index: Map<nat, nat>,
+    }
+
+    impl Constants {
+        pub open spec fn well_formed(&self) -> bool {
+            &&& 0 <= self.id < self.num_hosts
+        }
+    }
+
+    impl Variables {
+        pub open spec fn well_formed(&self, c: &Constants) -> bool {
+            &&& c.well_formed()
+            &&& self.commit_index <= self.log.len()
+        }
+    }
+
+    pub open spec fn is_majority(c: &Constants, hosts: Set<nat>) -> bool {
+        &&& are_valid_hosts(hosts, c.num_hosts)
+        &&& 2 * hosts.len() > c.num_hosts
+    }
+
+    // `log` starts with `prefix`.
+    pub open spec fn has_prefix(log: Seq<Entry>, prefix: Seq<Entry>) -> bool {
+        &&& prefix.len() <= log.len()
+        &&& log.subrange(0, prefix.len() as int) == prefix
+    }
+
+    // Term of the entry at 1-based `index`; index 0 is the empty prefix.
+    pub open spec fn term_at(log: Seq<Entry>, index: nat) -> nat {
+        if (index == 0 || index > log.len()) {
+            0
+        } else {
+            log[index - 1].term
+        }
+    }
+
+    pub open spec fn last_log_term(log: Seq<Entry>) -> nat {
+        term_at(log, log.len())
+    }
+
+    // The candidate's log, summarised by its last term and length, is at least as up to date as `log`.
+    pub open spec fn log_is_up_to_date(last_term: nat, last_index: nat, log: Seq<Entry>) -> bool {
+        ||| last_term > last_log_term(log)
+        ||| last_term == last_log_term(log) && last_index >= log.len()
+    }
+
+    // Keep `log` if it already holds `entries` right after `prev_index`; otherwise cut it at the first
+    // conflict and append `entries`.
+    pub open spec fn merge_entries(log: Seq<Entry>, prev_index: nat, entries: Seq<Entry>) -> Seq<Entry> {
+        if (prev_index + entries.len() <= log.len() && log.subrange(prev_index as int, prev_index + entries.len()) == entries) {
+            log
+        } else {
+            log.subrange(0, prev_index as int) + entries
+        }
+    }
+
+    pub open spec fn init(c: &Constants, u: &Variables, host_id: nat, num_hosts: nat) -> bool {
+        &&& u.well_formed(c)
+        &&& c.id == host_id
+        &&& c.num_hosts == num_hosts
+        &&& u.current_term == 0
+        &&& u.voted_for.is_none()
+        &&& u.log.len() == 0
+        &&& u.commit_index == 0
+        &&& u.role is Follower
+        &&& u.votes.is_empty()
+        &&& u.match_index.is_empty()
+    }
+
+    pub open spec fn submit_request(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation, value: Value) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& net_op.recv.is_none()
+        &&& v == u
+        &&& net_op.send == Some(Message::ClientRequest { value })
+    }
+
+    // The candidate votes for itself by answering its own RequestVote, like any other voter.
+    pub open spec fn start_election(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& !(u.role is Leader)
+        &&& net_op.recv.is_none()
+        &&& v.current_term == u.current_term + 1
+        &&& v.voted_for == Some(c.id)
+        &&& v.log == u.log
+        &&& v.commit_index == u.commit_index
+        &&& v.role is Candidate
+        &&& v.votes.is_empty()
+        &&& v.match_index.is_empty()
+        &&& net_op.send == Some(Message::RequestVote { term: v.current_term, candidate: c.id, last_log_index: u.log.len(), last_log_term: last_log_term(u.log) })
+    }
+
+    // A higher term in the request first turns the host into a follower of that term with no vote cast.
+    pub open spec fn vote(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::RequestVote { term, candidate, last_log_index, last_log_term }) = net_op.recv {
+            let newer_term = term > u.current_term;
+            let voted_for = if (newer_term) { None } else { u.voted_for };
+
+            &&& term >= u.current_term
+            &&& voted_for.is_none() || voted_for == Some(candidate)
+            &&& log_is_up_to_date(last_log_term, last_log_index, u.log)
+            &&& v.current_term == term
+            &&& v.voted_for == Some(candidate)
+            &&& v.log == u.log
+            &&& v.commit_index == u.commit_index
+            &&& if (newer_term) {
+                    &&& v.role is Follower
+                    &&& v.votes.is_empty()
+                    &&& v.match_index.is_empty()
+                } else {
+                    &&& v.role == u.role
+                    &&& v.votes == u.votes
+                    &&& v.match_index == u.match_index
+                }
+            &&& net_op.send == Some(Message::Vote { term, voter: c.id, candidate })
+        } else {
+            &&& false
+        }
+    }
+
+    pub open spec fn receive_vote(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::Vote { term, voter, candidate }) = net_op.recv {
+            &&& candidate == c.id
+            &&& term == u.current_term
+            &&& u.role is Candidate
+            &&& v.current_term == u.current_term
+            &&& v.voted_for == u.voted_for
+            &&& v.log == u.log
+            &&& v.commit_index == u.commit_index
+            &&& v.role == u.role
+            &&& v.votes == u.votes.insert(voter)
+            &&& v.match_index == u.match_index
+            &&& net_op.send.is_none()
+        } else {
+            &&& false
+        }
+    }
+
+    pub open spec fn become_leader(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& u.role is Candidate
+        &&& is_majority(c, u.votes)
+        &&& v.current_term == u.current_term
+        &&& v.voted_for == u.voted_for
+        &&& v.log == u.log
+        &&& v.commit_index == u.commit_index
+        &&& v.role is Leader
+        &&& v.votes == u.votes
+        &&& v.match_index == Map::<nat, nat>::empty()
+        &&& net_op.recv.is_none()
+        &&& net_op.send.is_none()
+    }
+
+    pub open spec fn propose(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation, value: Value) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::ClientRequest { value: request_value }) = net_op.recv {
+            &&& request_value == value
+            &&& u.role is Leader
+            &&& v.current_term == u.current_term
+            &&& v.voted_for == u.voted_for
+            &&& v.log == u.log.push(Entry { term: u.current_term, value })
+            &&& v.commit_index == u.commit_index
+            &&& v.role == u.role
+            &&& v.votes == u.votes
+            &&& v.match_index == u.match_index
+            &&& net_op.send.is_none()
+        } else {
+            &&& false
+        }
+    }
+
+    pub open spec fn send_append_entries(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::AppendEntries { term, leader, prev_index, prev_term, entries, leader_commit }) = net_op.send {
+            &&& u.role is Leader
+            &&& term == u.current_term
+            &&& leader == c.id
+            &&& prev_index <= u.log.len()
+            &&& prev_term == term_at(u.log, prev_index)
+            &&& entries == u.log.subrange(prev_index as int, u.log.len() as int)
+            &&& leader_commit == u.commit_index
+            &&& v == u
+            &&& net_op.recv.is_none()
+        } else {
+            &&& false
+        }
+    }
+
+    // Also how followers learn about commits: `commit_index` only moves up to what the leader vouched for.
+    pub open spec fn append_entries(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::AppendEntries { term, leader, prev_index, prev_term, entries, leader_commit }) = net_op.recv {
+            let match_index = prev_index + entries.len();
+            let learned_commit = if (leader_commit < match_index) { leader_commit } else { match_index };
+
+            &&& leader != c.id
+            &&& term >= u.current_term
+            &&& prev_index <= u.log.len()
+            &&& term_at(u.log, prev_index) == prev_term
+            &&& v.current_term == term
+            &&& v.voted_for == if (term > u.current_term) { None } else { u.voted_for }
+            &&& v.log == merge_entries(u.log, prev_index, entries)
+            &&& v.commit_index == if (learned_commit > u.commit_index) { learned_commit } else { u.commit_index }
+            &&& v.role is Follower
+            &&& v.votes.is_empty()
+            &&& v.match_index.is_empty()
+            &&& net_op.send == Some(Message::AppendEntriesReply { term, sender: c.id, leader, match_index })
+        } else {
+            &&& false
+        }
+    }
+
+    pub open spec fn append_entries_reply(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        if let Some(Message::AppendEntriesReply { term, sender, leader, match_index }) = net_op.recv {
+            let old_match = if (u.match_index.contains_key(sender)) { u.match_index[sender] } else { 0 };
+
+            &&& leader == c.id
+            &&& term == u.current_term
+            &&& u.role is Leader
+            &&& v.current_term == u.current_term
+            &&& v.voted_for == u.voted_for
+            &&& v.log == u.log
+            &&& v.commit_index == u.commit_index
+            &&& v.role == u.role
+            &&& v.votes == u.votes
+            &&& v.match_index == u.match_index.insert(sender, if (match_index > old_match) { match_index } else { old_match })
+            &&& net_op.send.is_none()
+        } else {
+            &&& false
+        }
+    }
+
+    // Hosts (the leader included) whose log is known to hold the first `index` entries.
+    pub open spec fn replicated_on(c: &Constants, u: &Variables, index: nat) -> Set<nat> {
+        Set::new(|h: nat| h < c.num_hosts && (h == c.id || (u.match_index.contains_key(h) && u.match_index[h] >= index)))
+    }
+
+    // The leader commits one entry per step. Entries of earlier terms are only committed by way of a
+    // later entry of the current term that a majority holds.
+    pub open spec fn advance_commit(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool
+    recommends
+        u.well_formed(c),
+        v.well_formed(c),
+    {
+        &&& u.role is Leader
+        &&& u.commit_index < u.log.len()
+        &&& exists |index: nat|
+                u.commit_index < index <= u.log.len() &&
+                term_at(u.log, index) == u.current_term &&
+                #[trigger] is_majority(c, replicated_on(c, u, index))
+        &&& v.current_term == u.current_term
+        &&& v.voted_for == u.voted_for
+        &&& v.log == u.log
+        &&& v.commit_index == u.commit_index + 1
+        &&& v.role == u.role
+        &&& v.votes == u.votes
+        &&& v.match_index == u.match_index
+        &&& net_op.recv.is_none()
+        &&& net_op.send.is_none()
+    }
+
+    pub open spec fn step(c: &Constants, u: &Variables, v: &Variables, net_op: NetworkOperation, event: Event) -> bool {
+        &&& u.well_formed(c)
+        &&& v.well_formed(c)
+        &&& match event {
+                Event::Decide { key, value } => {
+                    &&& advance_commit(c, u, v, net_op)
+                    &&& key == u.commit_index
+                    &&& value == u.log[key as int].value
+                },
+                Event::Propose { key, value } => {
+                    &&& propose(c, u, v, net_op, value)
+                    &&& key == u.log.len()
+                },
+                Event::Submit { value } => {
+                    &&& submit_request(c, u, v, net_op, value)
+                },
+                Event::ProposeBatch { .. } | Event::DecideBatch { .. } => {
+                    &&& false
+                },
+                Event::NoOp => {
+                    ||| start_election(c, u, v, net_op)
+                    ||| vote(c, u, v, net_op)
+                    ||| receive_vote(c, u, v, net_op)
+                    ||| become_leader(c, u, v, net_op)
+                    ||| send_append_entries(c, u, v, net_op)
+                    ||| append_entries(c, u, v, net_op)
+                    ||| append_entries_reply(c, u, v, net_op)
+                },
+            }
+    }
+
+    // `merge_entries` never loses a prefix that both the log and the leader's log start with, when
+    // `entries` is the leader's log from `prev_index` on.
+    pub proof fn merge_keeps_common_prefix(log: Seq<Entry>, prev_index: nat, entries: Seq<Entry>, leader_log: Seq<Entry>, prefix: Seq<Entry>)
+    requires
+        prev_index <= log.len(),
+        prev_index + entries.len() <= leader_log.len(),
+        entries == leader_log.subrange(prev_index as int, prev_index + entries.len()),
+        has_prefix(log, prefix),
+        has_prefix(leader_log, prefix),
+    ensures
+        has_prefix(merge_entries(log, prev_index, entries), prefix),
+    {
+        let end = prev_index + entries.len();
+        let merged = merge_entries(log, prev_index, entries);
+        if (merged != log) {
+            if (end <= prefix.len()) {
+                // Then `log` already holds `entries` after `prev_index`, and nothing was cut.
+                assert(log.subrange(prev_index as int, end as int) =~= entries) by {
+                    assert forall |i: int| 0 <= i < entries.len() implies log[prev_index + i] == #[trigger] entries[i] by {
+                        assert(log[prev_index + i] == prefix[prev_index + i]);
+                        assert(leader_log[prev_index + i] == prefix[prev_index + i]);
+                    };
+                };
+                assert(false);
+            }
+            assert(merged.len() == end);
+            assert(merged.subrange(0, prefix.len() as int) =~= prefix) by {
+                assert forall |i: int| 0 <= i < prefix.len() implies #[trigger] merged[i] == prefix[i] by {
+                    if (i < prev_index) {
+                        assert(merged[i] == log[i] && log[i] == prefix[i]);
+                    } else {
+                        assert(merged[i] == entries[i - prev_index] && entries[i - prev_index] == leader_log[i]);
+                        assert(leader_log[i] == prefix[i]);
+                    }
+                };
+            };
+        }
+    }
+
+    // After `merge_entries`, the log holds the leader's log up to the end of `entries`, provided the
+    // two already agree up to `prev_index`.
+    pub proof fn merge_matches_leader(log: Seq<Entry>, prev_index: nat, entries: Seq<Entry>, leader_log: Seq<Entry>)
+    requires
+        prev_index <= log.len(),
+        prev_index + entries.len() <= leader_log.len(),
+        entries == leader_log.subrange(prev_index as int, prev_index + entries.len()),
+        log.subrange(0, prev_index as int) == leader_log.subrange(0, prev_index as int),
+    ensures
+        has_prefix(merge_entries(log, prev_index, entries), leader_log.subrange(0, prev_index + entries.len())),
+    {
+        let end = prev_index + entries.len();
+        let merged = merge_entries(log, prev_index, entries);
+        assert(merged.subrange(0, end as int) =~= leader_log.subrange(0, end as int)) by {
+            assert forall |i: int| 0 <= i < end implies #[trigger] merged[i] == leader_log[i] by {
+                if (i < prev_index) {
+                    assert(log.subrange(0, prev_index as int)[i] == leader_log.subrange(0, prev_index as int)[i]);
+                } else if (merged == log) {
+                    assert(log.subrange(prev_index as int, end as int)[i - prev_index] == entries[i - prev_index]);
+                } else {
+                    assert(merged[i] == entries[i - prev_index]);
+                }
+            };
+        };
+    }
+}
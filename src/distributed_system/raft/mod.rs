@@ -0,0 +1,1707 @@
+use super::{
+    high_level::{init as high_init, next as high_next, reconfigure, Constants as HighConstants, Variables as HighVariables},
+    low_level::{overlapping_sets_have_common_element, quorum::{all_hosts, are_valid_hosts}},
+    Event, Value,
+};
+use host::{has_prefix, last_log_term, term_at, Entry};
+use vstd::prelude::*;
+
+verus! {
+    pub mod host;
+
+    // A second low-level protocol under the same `high_level` spec as the Paxos machine in `low_level`:
+    // log index `i` plays the role of instance key `i`, and an entry is decided once it is committed.
+    pub enum Message {
+        RequestVote { term: nat, candidate: nat, last_log_index: nat, last_log_term: nat },
+        Vote { term: nat, voter: nat, candidate: nat },
+        AppendEntries { term: nat, leader: nat, prev_index: nat, prev_term: nat, entries: Seq<Entry>, leader_commit: nat },
+        AppendEntriesReply { term: nat, sender: nat, leader: nat, match_index: nat },
+        ClientRequest { value: Value },
+    }
+
+    pub struct NetworkOperation {
+        pub send: Option<Message>,
+        pub recv: Option<Message>,
+    }
+
+    pub struct Constants {
+        pub num_hosts: nat,
+        pub hosts: Seq<host::Constants>,
+    }
+
+    // Sent messages are never removed, so the network may deliver any of them any number of times and
+    // in any order; a lost message is one that is never delivered.
+    //
+    // `leaders[t]` and `leader_logs[t]` are ghost state: the host that won term `t`, and its log as
+    // leader of `t` the last time it was one. A leader only ever appends in its own term, so each
+    // `leader_logs[t]` only grows, and log matching and leader completeness are stated against them.
+    pub struct Variables {
+        pub hosts: Seq<host::Variables>,
+        pub sent_messages: Set<Message>,
+        pub leaders: Map<nat, nat>,
+        pub leader_logs: Map<nat, Seq<Entry>>,
+    }
+
+    impl Constants {
+        pub open spec fn well_formed(&self) -> bool {
+            &&& self.num_hosts > 0
+            &&& self.hosts.len() == self.num_hosts
+            &&& forall |i: nat| #![auto]
+                    0 <= i < self.num_hosts ==>
+                    self.hosts[i as int].id == i &&
+                    self.hosts[i as int].num_hosts == self.num_hosts
+        }
+
+        pub open spec fn is_majority(&self, hosts: Set<nat>) -> bool {
+            &&& are_valid_hosts(hosts, self.num_hosts)
+            &&& 2 * hosts.len() > self.num_hosts
+        }
+    }
+
+    impl Variables {
+        pub open spec fn well_formed(&self, c: &Constants) -> bool {
+            &&& c.well_formed()
+            &&& self.hosts.len() == c.hosts.len()
+            &&& forall |idx: nat| #![auto] 0 <= idx < self.hosts.len() ==> self.hosts[idx as int].well_formed(&c.hosts[idx as int])
+        }
+    }
+
+    // The history after a step of host `host_id`: a leader's log, recorded under its term.
+    pub open spec fn record_leaders(leaders: Map<nat, nat>, host_id: nat, host: host::Variables) -> Map<nat, nat> {
+        if (host.role is Leader) {
+            leaders.insert(host.current_term, host_id)
+        } else {
+            leaders
+        }
+    }
+
+    pub open spec fn record_leader_logs(leader_logs: Map<nat, Seq<Entry>>, host: host::Variables) -> Map<nat, Seq<Entry>> {
+        if (host.role is Leader) {
+            leader_logs.insert(host.current_term, host.log)
+        } else {
+            leader_logs
+        }
+    }
+
+    pub open spec fn init(c: &Constants, u: &Variables) -> bool {
+        &&& u.well_formed(c)
+        &&& forall |idx: nat| #![auto]
+                0 <= idx < u.hosts.len() ==>
+                host::init(&c.hosts[idx as int], &u.hosts[idx as int], idx, u.hosts.len())
+        &&& u.sent_messages.is_empty()
+        &&& u.leaders == Map::<nat, nat>::empty()
+        &&& u.leader_logs == Map::<nat, Seq<Entry>>::empty()
+    }
+
+    pub open spec fn network_step(u: &Variables, v: &Variables, net_op: NetworkOperation) -> bool {
+        &&& if let Some(message) = net_op.recv { u.sent_messages.contains(message) } else { true }
+        &&& if let Some(message) = net_op.send { v.sent_messages == u.sent_messages.insert(message) } else { v.sent_messages == u.sent_messages }
+    }
+
+    pub open spec fn host_step(c: &Constants, u: &Variables, v: &Variables, host_id: int, net_op: NetworkOperation, event: Event) -> bool {
+        &&& u.well_formed(c)
+        &&& v.well_formed(c)
+        &&& 0 <= host_id < u.hosts.len()
+        &&& host::step(&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id], net_op, event)
+        &&& forall |i: int| #![auto] 0 <= i < v.hosts.len() && i != host_id ==> u.hosts[i] == v.hosts[i]
+        &&& network_step(u, v, net_op)
+        &&& v.leaders == record_leaders(u.leaders, host_id as nat, v.hosts[host_id])
+        &&& v.leader_logs == record_leader_logs(u.leader_logs, v.hosts[host_id])
+    }
+
+    pub open spec fn next(c: &Constants, u: &Variables, v: &Variables, event: Event) -> bool {
+        exists |host_id: int, net_op: NetworkOperation| #[trigger] host_step(c, u, v, host_id, net_op, event)
+    }
+
+    impl Variables {
+        pub open spec fn elected_by(&self, c: &Constants, term: nat, leader: nat, voters: Set<nat>) -> bool {
+            &&& c.is_majority(voters)
+            &&& forall |voter: nat| #[trigger] voters.contains(voter) ==> self.sent_messages.contains(Message::Vote { term, voter, candidate: leader })
+        }
+
+        pub open spec fn prefix(&self, term: nat, index: nat) -> Seq<Entry> {
+            self.leader_logs[term].subrange(0, index as int)
+        }
+
+        // `host` has held the first `index` entries of the leader's log in `term`: it is that leader,
+        // or it said so in an AppendEntriesReply.
+        pub open spec fn acked(&self, host: nat, term: nat, index: nat) -> bool {
+            ||| self.leaders.contains_key(term) && self.leaders[term] == host && index <= self.leader_logs[term].len()
+            ||| exists |leader: nat, match_index: nat|
+                    index <= match_index &&
+                    #[trigger] self.sent_messages.contains(Message::AppendEntriesReply { term, sender: host, leader, match_index })
+        }
+
+        // The leader of `term` may commit its first `index` entries once a majority holds them: the
+        // last of them is from `term` itself.
+        pub open spec fn committable(&self, term: nat, index: nat) -> bool {
+            &&& self.leader_logs.contains_key(term)
+            &&& 0 < index <= self.leader_logs[term].len()
+            &&& self.leader_logs[term][index - 1].term == term
+        }
+
+        pub open spec fn acked_by(&self, term: nat, index: nat, hosts: Set<nat>) -> bool {
+            forall |h: nat| #[trigger] hosts.contains(h) ==> self.acked(h, term, index)
+        }
+
+        pub open spec fn committed(&self, c: &Constants, term: nat, index: nat) -> bool {
+            &&& self.committable(term, index)
+            &&& exists |hosts: Set<nat>| c.is_majority(hosts) && #[trigger] self.acked_by(term, index, hosts)
+        }
+
+        // Every host in `hosts` has moved past `term` without acknowledging the entries, and never will.
+        pub open spec fn abandoned_by(&self, term: nat, index: nat, hosts: Set<nat>) -> bool {
+            forall |h: nat| #[trigger] hosts.contains(h) ==> self.hosts[h as int].current_term > term && !self.acked(h, term, index)
+        }
+
+        // No majority can acknowledge the first `index` entries of `term` any more, so they are never
+        // committed in `term`.
+        pub open spec fn abandoned(&self, c: &Constants, term: nat, index: nat) -> bool {
+            exists |hosts: Set<nat>| c.is_majority(hosts) && #[trigger] self.abandoned_by(term, index, hosts)
+        }
+
+        // The first `commit_index` entries of `log` are a committed prefix from a term up to `term`.
+        pub open spec fn committed_prefix(&self, c: &Constants, log: Seq<Entry>, commit_index: nat, term: nat) -> bool {
+            ||| commit_index == 0
+            ||| exists |t: nat, n: nat|
+                    #[trigger] self.committed(c, t, n) &&
+                    t <= term &&
+                    commit_index <= n &&
+                    has_prefix(log, self.prefix(t, commit_index))
+        }
+
+        pub open spec fn value_was_proposed(&self, value: Value) -> bool {
+            exists |term: nat, i: int| #![auto]
+                self.leader_logs.contains_key(term) &&
+                0 <= i < self.leader_logs[term].len() &&
+                self.leader_logs[term][i].value == value
+        }
+
+        // A host votes at most once per term, and remembers the vote while it stays in that term.
+        pub open spec fn votes_are_unique(&self, c: &Constants) -> bool {
+            &&& forall |term: nat, voter: nat, candidate: nat|
+                    #[trigger] self.sent_messages.contains(Message::Vote { term, voter, candidate }) ==>
+                    voter < c.num_hosts &&
+                    (self.hosts[voter as int].current_term > term ||
+                        self.hosts[voter as int].current_term == term && self.hosts[voter as int].voted_for == Some(candidate))
+            &&& forall |term: nat, voter: nat, c1: nat, c2: nat|
+                    #[trigger] self.sent_messages.contains(Message::Vote { term, voter, candidate: c1 }) &&
+                    #[trigger] self.sent_messages.contains(Message::Vote { term, voter, candidate: c2 }) ==>
+                    c1 == c2
+        }
+
+        // A candidate's votes were sent to it, and its RequestVote describes the log it still has.
+        pub open spec fn candidates_match_their_requests(&self, c: &Constants) -> bool {
+            &&& forall |term: nat, candidate: nat, last_log_index: nat, last_log_term: nat|
+                    #[trigger] self.sent_messages.contains(Message::RequestVote { term, candidate, last_log_index, last_log_term }) ==>
+                    candidate < c.num_hosts && self.hosts[candidate as int].current_term >= term
+            &&& forall |i: int| #![auto]
+                    0 <= i < self.hosts.len() && self.hosts[i].role is Candidate ==>
+                    are_valid_hosts(self.hosts[i].votes, c.num_hosts) &&
+                    (forall |voter: nat| #[trigger] self.hosts[i].votes.contains(voter) ==>
+                        self.sent_messages.contains(Message::Vote { term: self.hosts[i].current_term, voter, candidate: i as nat })) &&
+                    (forall |last_log_index: nat, last_log_term: nat|
+                        #[trigger] self.sent_messages.contains(Message::RequestVote { term: self.hosts[i].current_term, candidate: i as nat, last_log_index, last_log_term }) ==>
+                        last_log_index == self.hosts[i].log.len() && last_log_term == last_log_term(self.hosts[i].log))
+        }
+
+        // Every recorded leader won its term and never stands for it again; a current leader's log is
+        // the recorded one, and it only counts replies that were sent.
+        pub open spec fn leaders_won_elections(&self, c: &Constants) -> bool {
+            &&& self.leaders.dom() == self.leader_logs.dom()
+            &&& forall |term: nat| #[trigger] self.leaders.contains_key(term) ==>
+                    self.leaders[term] < c.num_hosts &&
+                    self.hosts[self.leaders[term] as int].current_term >= term &&
+                    (self.hosts[self.leaders[term] as int].current_term == term ==> !(self.hosts[self.leaders[term] as int].role is Candidate)) &&
+                    exists |voters: Set<nat>| #[trigger] self.elected_by(c, term, self.leaders[term], voters)
+            &&& forall |i: int| #![auto]
+                    0 <= i < self.hosts.len() && self.hosts[i].role is Leader ==>
+                    self.leaders.contains_key(self.hosts[i].current_term) &&
+                    self.leaders[self.hosts[i].current_term] == i &&
+                    self.leader_logs[self.hosts[i].current_term] == self.hosts[i].log &&
+                    are_valid_hosts(self.hosts[i].match_index.dom(), c.num_hosts) &&
+                    (forall |h: nat| #[trigger] self.hosts[i].match_index.contains_key(h) ==>
+                        self.acked(h, self.hosts[i].current_term, self.hosts[i].match_index[h]))
+        }
+
+        // Log matching: a log that ends in an entry of term `s` is a prefix of the log of the leader of
+        // `s`, whether it is another leader's log or a host's.
+        pub open spec fn logs_match_leader_logs(&self, c: &Constants) -> bool {
+            &&& forall |term: nat, i: int| #![trigger self.leader_logs[term][i]]
+                    self.leader_logs.contains_key(term) &&
+                    0 <= i < self.leader_logs[term].len() ==> {
+                        let s = self.leader_logs[term][i].term;
+                        &&& s <= term
+                        &&& self.leader_logs.contains_key(s)
+                        &&& i < self.leader_logs[s].len()
+                        &&& self.leader_logs[term].subrange(0, i + 1) == self.leader_logs[s].subrange(0, i + 1)
+                    }
+            &&& forall |h: int, i: int| #![trigger self.hosts[h].log[i]]
+                    0 <= h < self.hosts.len() &&
+                    0 <= i < self.hosts[h].log.len() ==> {
+                        let s = self.hosts[h].log[i].term;
+                        &&& s <= self.hosts[h].current_term
+                        &&& self.leader_logs.contains_key(s)
+                        &&& i < self.leader_logs[s].len()
+                        &&& self.hosts[h].log.subrange(0, i + 1) == self.leader_logs[s].subrange(0, i + 1)
+                    }
+        }
+
+        // AppendEntries carries a slice of its leader's log and a commit index it had committed;
+        // replies acknowledge entries that leader had; entries hold submitted values.
+        pub open spec fn messages_match_leader_logs(&self, c: &Constants) -> bool {
+            &&& forall |term: nat, leader: nat, prev_index: nat, prev_term: nat, entries: Seq<Entry>, leader_commit: nat|
+                    #[trigger] self.sent_messages.contains(Message::AppendEntries { term, leader, prev_index, prev_term, entries, leader_commit }) ==>
+                    self.leaders.contains_key(term) &&
+                    self.leaders[term] == leader &&
+                    prev_index + entries.len() <= self.leader_logs[term].len() &&
+                    prev_term == term_at(self.leader_logs[term], prev_index) &&
+                    entries == self.leader_logs[term].subrange(prev_index as int, prev_index + entries.len()) &&
+                    leader_commit <= self.hosts[leader as int].commit_index &&
+                    self.committed_prefix(c, self.leader_logs[term], leader_commit, term)
+            &&& forall |term: nat, sender: nat, leader: nat, match_index: nat|
+                    #[trigger] self.sent_messages.contains(Message::AppendEntriesReply { term, sender, leader, match_index }) ==>
+                    sender < c.num_hosts &&
+                    self.leader_logs.contains_key(term) &&
+                    match_index <= self.leader_logs[term].len() &&
+                    self.hosts[sender as int].current_term >= term
+            &&& forall |term: nat, i: int| #![trigger self.leader_logs[term][i]]
+                    self.leader_logs.contains_key(term) &&
+                    0 <= i < self.leader_logs[term].len() ==>
+                    self.sent_messages.contains(Message::ClientRequest { value: self.leader_logs[term][i].value })
+        }
+
+        // Until its entries are abandoned, a host keeps what it acknowledged, and it only votes for
+        // candidates whose logs are at least as up to date.
+        pub open spec fn acknowledgements_are_kept(&self, c: &Constants) -> bool {
+            &&& forall |h: nat, term: nat, index: nat|
+                    h < c.num_hosts &&
+                    #[trigger] self.committable(term, index) &&
+                    #[trigger] self.acked(h, term, index) &&
+                    !self.abandoned(c, term, index) ==>
+                    has_prefix(self.hosts[h as int].log, self.prefix(term, index))
+            &&& forall |h: nat, term: nat, index: nat, later: nat, candidate: nat|
+                    #![trigger self.acked(h, term, index), self.sent_messages.contains(Message::Vote { term: later, voter: h, candidate })]
+                    self.committable(term, index) &&
+                    self.acked(h, term, index) &&
+                    self.sent_messages.contains(Message::Vote { term: later, voter: h, candidate }) &&
+                    term < later &&
+                    !self.abandoned(c, term, index) ==>
+                    exists |last_log_index: nat, last_log_term: nat|
+                        #[trigger] self.sent_messages.contains(Message::RequestVote { term: later, candidate, last_log_index, last_log_term }) &&
+                        (last_log_term > term || last_log_term == term && last_log_index >= index)
+        }
+
+        // Leader completeness: every later leader starts with entries a majority may still commit.
+        pub open spec fn leaders_are_complete(&self, c: &Constants) -> bool {
+            forall |term: nat, index: nat, later: nat|
+                #[trigger] self.committable(term, index) &&
+                #[trigger] self.leader_logs.contains_key(later) &&
+                term < later ==>
+                has_prefix(self.leader_logs[later], self.prefix(term, index)) || self.abandoned(c, term, index)
+        }
+
+        pub open spec fn commits_are_safe(&self, c: &Constants) -> bool {
+            forall |i: int| #![auto]
+                0 <= i < self.hosts.len() ==>
+                self.committed_prefix(c, self.hosts[i].log, self.hosts[i].commit_index, self.hosts[i].current_term)
+        }
+    }
+
+    pub open spec fn inductive(c: &Constants, u: &Variables) -> bool {
+        &&& u.well_formed(c)
+        &&& u.votes_are_unique(c)
+        &&& u.candidates_match_their_requests(c)
+        &&& u.leaders_won_elections(c)
+        &&& u.logs_match_leader_logs(c)
+        &&& u.messages_match_leader_logs(c)
+        &&& u.acknowledgements_are_kept(c)
+        &&& u.leaders_are_complete(c)
+        &&& u.commits_are_safe(c)
+    }
+
+    pub proof fn majorities_intersect(c: &Constants, q1: Set<nat>, q2: Set<nat>)
+    requires
+        c.is_majority(q1),
+        c.is_majority(q2),
+    ensures
+        exists |h: nat| #![auto] q1.contains(h) && q2.contains(h),
+    {
+        overlapping_sets_have_common_element(q1, q2, c.num_hosts);
+    }
+
+    // Entries of a leader's log, and of a host's, never go down in term.
+    pub proof fn leader_log_terms_are_sorted(c: &Constants, u: &Variables, term: nat, i: int, j: int)
+    requires
+        inductive(c, u),
+        u.leader_logs.contains_key(term),
+        0 <= i <= j < u.leader_logs[term].len(),
+    ensures
+        u.leader_logs[term][i].term <= u.leader_logs[term][j].term,
+    {
+        let s = u.leader_logs[term][j].term;
+        assert(u.leader_logs[term].subrange(0, j + 1) == u.leader_logs[s].subrange(0, j + 1));
+        assert(u.leader_logs[term][i] == u.leader_logs[term].subrange(0, j + 1)[i]);
+        assert(u.leader_logs[s][i] == u.leader_logs[s].subrange(0, j + 1)[i]);
+        assert(u.leader_logs[s][i].term <= s);
+    }
+
+    pub proof fn host_log_terms_are_sorted(c: &Constants, u: &Variables, h: int, i: int, j: int)
+    requires
+        inductive(c, u),
+        0 <= h < u.hosts.len(),
+        0 <= i <= j < u.hosts[h].log.len(),
+    ensures
+        u.hosts[h].log[i].term <= u.hosts[h].log[j].term,
+    {
+        let s = u.hosts[h].log[j].term;
+        assert(u.hosts[h].log.subrange(0, j + 1) == u.leader_logs[s].subrange(0, j + 1));
+        assert(u.hosts[h].log[i] == u.hosts[h].log.subrange(0, j + 1)[i]);
+        assert(u.leader_logs[s][i] == u.leader_logs[s].subrange(0, j + 1)[i]);
+        assert(u.leader_logs[s][i].term <= s);
+    }
+
+    pub proof fn acked_host_reached_term(c: &Constants, u: &Variables, h: nat, term: nat, index: nat)
+    requires
+        inductive(c, u),
+        h < c.num_hosts,
+        u.acked(h, term, index),
+    ensures
+        u.hosts[h as int].current_term >= term,
+    {
+        if (!(u.leaders.contains_key(term) && u.leaders[term] == h && index <= u.leader_logs[term].len())) {
+            let (leader, match_index) = choose |leader: nat, match_index: nat|
+                index <= match_index &&
+                #[trigger] u.sent_messages.contains(Message::AppendEntriesReply { term, sender: h, leader, match_index });
+            assert(u.sent_messages.contains(Message::AppendEntriesReply { term, sender: h, leader, match_index }));
+        }
+    }
+
+    pub proof fn committed_is_not_abandoned(c: &Constants, u: &Variables, term: nat, index: nat)
+    requires
+        u.committed(c, term, index),
+    ensures
+        !u.abandoned(c, term, index),
+    {
+        if (u.abandoned(c, term, index)) {
+            let ackers = choose |hosts: Set<nat>| c.is_majority(hosts) && #[trigger] u.acked_by(term, index, hosts);
+            let movers = choose |hosts: Set<nat>| c.is_majority(hosts) && #[trigger] u.abandoned_by(term, index, hosts);
+            majorities_intersect(c, ackers, movers);
+            let h = choose |h: nat| #![auto] ackers.contains(h) && movers.contains(h);
+            assert(u.acked(h, term, index) && !u.acked(h, term, index));
+        }
+    }
+
+    // Two committed prefixes agree wherever both have entries.
+    pub proof fn committed_prefixes_agree(c: &Constants, u: &Variables, t1: nat, n1: nat, t2: nat, n2: nat)
+    requires
+        inductive(c, u),
+        u.committed(c, t1, n1),
+        u.committed(c, t2, n2),
+    ensures
+        forall |k: int| 0 <= k < n1 && k < n2 ==> #[trigger] u.leader_logs[t1][k] == u.leader_logs[t2][k],
+    {
+        if (t1 < t2) {
+            committed_is_not_abandoned(c, u, t1, n1);
+            assert(has_prefix(u.leader_logs[t2], u.prefix(t1, n1)));
+            assert forall |k: int| 0 <= k < n1 && k < n2 implies #[trigger] u.leader_logs[t1][k] == u.leader_logs[t2][k] by {
+                assert(u.prefix(t1, n1)[k] == u.leader_logs[t1][k]);
+                assert(u.leader_logs[t2].subrange(0, n1 as int)[k] == u.leader_logs[t2][k]);
+            };
+        } else if (t2 < t1) {
+            committed_is_not_abandoned(c, u, t2, n2);
+            assert(has_prefix(u.leader_logs[t1], u.prefix(t2, n2)));
+            assert forall |k: int| 0 <= k < n1 && k < n2 implies #[trigger] u.leader_logs[t1][k] == u.leader_logs[t2][k] by {
+                assert(u.prefix(t2, n2)[k] == u.leader_logs[t2][k]);
+                assert(u.leader_logs[t1].subrange(0, n2 as int)[k] == u.leader_logs[t1][k]);
+            };
+        }
+    }
+
+    // State machine safety: hosts agree on every entry both have committed.
+    pub proof fn committed_entries_agree(c: &Constants, u: &Variables, i: int, j: int, index: int)
+    requires
+        inductive(c, u),
+        0 <= i < u.hosts.len(),
+        0 <= j < u.hosts.len(),
+        0 <= index < u.hosts[i].commit_index,
+        index < u.hosts[j].commit_index,
+    ensures
+        u.hosts[i].log[index] == u.hosts[j].log[index],
+    {
+        let (hi, hj) = (u.hosts[i], u.hosts[j]);
+        assert(u.committed_prefix(c, hi.log, hi.commit_index, hi.current_term));
+        assert(u.committed_prefix(c, hj.log, hj.commit_index, hj.current_term));
+        let (t1, n1) = choose |t: nat, n: nat| #[trigger] u.committed(c, t, n) && t <= hi.current_term && hi.commit_index <= n && has_prefix(hi.log, u.prefix(t, hi.commit_index));
+        let (t2, n2) = choose |t: nat, n: nat| #[trigger] u.committed(c, t, n) && t <= hj.current_term && hj.commit_index <= n && has_prefix(hj.log, u.prefix(t, hj.commit_index));
+        committed_prefixes_agree(c, u, t1, n1, t2, n2);
+        assert(hi.log[index] == hi.log.subrange(0, hi.commit_index as int)[index]);
+        assert(hj.log[index] == hj.log.subrange(0, hj.commit_index as int)[index]);
+        assert(u.leader_logs[t1][index] == u.leader_logs[t2][index]);
+    }
+
+    // What every host step does: the network only gains what it sends, other hosts stay put, and
+    // terms and commit indices never go down.
+    pub proof fn host_step_facts(c: &Constants, u: &Variables, v: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+    requires
+        host_step(c, u, v, host_id, net_op, event),
+    ensures
+        c.hosts[host_id].id == host_id,
+        c.hosts[host_id].num_hosts == c.num_hosts,
+        u.sent_messages.subset_of(v.sent_messages),
+        forall |message: Message| #[trigger] v.sent_messages.contains(message) && !u.sent_messages.contains(message) ==> net_op.send == Some(message),
+        net_op.recv.is_some() ==> u.sent_messages.contains(net_op.recv.unwrap()),
+        forall |i: int| #![auto] 0 <= i < v.hosts.len() && i != host_id ==> v.hosts[i] == u.hosts[i],
+        forall |i: int| #![auto]
+            0 <= i < v.hosts.len() ==>
+            u.hosts[i].current_term <= v.hosts[i].current_term &&
+            u.hosts[i].commit_index <= v.hosts[i].commit_index,
+        forall |hosts: Set<nat>| #[trigger] host::is_majority(&c.hosts[host_id], hosts) == c.is_majority(hosts),
+    {
+        assert(c.hosts[host_id as nat as int].id == host_id);
+    }
+
+    // The history only changes at the stepping host's term: a new leader records its log under a term
+    // nobody won before, and a leader that appends grows its own record.
+    pub proof fn leader_history_facts(c: &Constants, u: &Variables, v: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+    requires
+        inductive(c, u),
+        host_step(c, u, v, host_id, net_op, event),
+    ensures
+        u.leaders.submap_of(v.leaders),
+        u.leader_logs.dom().subset_of(v.leader_logs.dom()),
+        forall |term: nat| #[trigger] u.leader_logs.contains_key(term) ==> has_prefix(v.leader_logs[term], u.leader_logs[term]),
+        forall |term: nat, index: nat| #![trigger v.prefix(term, index)]
+            u.leader_logs.contains_key(term) && index <= u.leader_logs[term].len() ==>
+            v.prefix(term, index) == u.prefix(term, index),
+        forall |term: nat| #![auto]
+            v.leader_logs.contains_key(term) && !u.leader_logs.contains_key(term) ==>
+            term == u.hosts[host_id].current_term &&
+            host::become_leader(&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id], net_op) &&
+            v.leaders[term] == host_id &&
+            v.leader_logs[term] == u.hosts[host_id].log,
+        forall |term: nat| #![auto]
+            u.leader_logs.contains_key(term) && v.leader_logs[term] != u.leader_logs[term] ==>
+            term == u.hosts[host_id].current_term &&
+            u.hosts[host_id].role is Leader &&
+            v.leader_logs[term] == v.hosts[host_id].log &&
+            v.leader_logs[term] == u.leader_logs[term].push(Entry { term, value: v.hosts[host_id].log.last().value }) &&
+            net_op.send.is_none() &&
+            exists |value: Value| #[trigger] host::propose(&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id], net_op, value),
+    {
+        let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id]);
+        host_step_facts(c, u, v, host_id, net_op, event);
+        if (lv.role is Leader) {
+            let term = lv.current_term;
+            if (lu.role is Leader) {
+                // A leader stays one only within its term, and only `propose` changes its log.
+                assert(lu.current_term == term);
+                assert(u.leaders[term] == host_id && u.leader_logs[term] == lu.log);
+                if (lv.log != lu.log) {
+                    let value = choose |value: Value| host::step(lc, lu, lv, net_op, Event::Propose { key: lu.log.len(), value }) && event == Event::Propose { key: lu.log.len(), value };
+                    assert(host::propose(lc, lu, lv, net_op, value));
+                    assert(lv.log == lu.log.push(Entry { term, value }));
+                    assert(lv.log.last().value == value);
+                }
+                assert(v.leader_logs.dom() == u.leader_logs.dom());
+            } else {
+                assert(host::become_leader(lc, lu, lv, net_op));
+                // Election safety: the winner of `term` would share a voter with this candidate, so it
+                // would be this candidate, which never won.
+                if (u.leader_logs.contains_key(term)) {
+                    let winner = u.leaders[term];
+                    let voters = choose |voters: Set<nat>| #[trigger] u.elected_by(c, term, winner, voters);
+                    majorities_intersect(c, voters, lu.votes);
+                    let voter = choose |h: nat| #![auto] voters.contains(h) && lu.votes.contains(h);
+                    assert(u.sent_messages.contains(Message::Vote { term, voter, candidate: winner }));
+                    assert(u.sent_messages.contains(Message::Vote { term, voter, candidate: host_id as nat }));
+                    assert(winner == host_id);
+                    assert(false);
+                }
+            }
+        }
+        assert forall |term: nat| #[trigger] u.leader_logs.contains_key(term) implies has_prefix(v.leader_logs[term], u.leader_logs[term]) by {
+            assert(v.leader_logs[term].subrange(0, u.leader_logs[term].len() as int) =~= u.leader_logs[term]);
+        };
+        assert forall |term: nat, index: nat| #![trigger v.prefix(term, index)]
+            u.leader_logs.contains_key(term) && index <= u.leader_logs[term].len() implies
+            v.prefix(term, index) == u.prefix(term, index)
+        by {
+            assert(v.prefix(term, index) =~= u.prefix(term, index)) by {
+                assert forall |k: int| 0 <= k < index implies v.leader_logs[term][k] == u.leader_logs[term][k] by {
+                    assert(v.leader_logs[term].subrange(0, u.leader_logs[term].len() as int)[k] == v.leader_logs[term][k]);
+                };
+            };
+        };
+    }
+
+    // What a host learns from an AppendEntries it accepts: the message is a slice of its leader's
+    // log, the host already agrees with that log up to the slice, and holds it up to the slice's end
+    // afterwards.
+    pub proof fn append_entries_matches_leader(c: &Constants, u: &Variables, h: int, term: nat, leader: nat, prev_index: nat, prev_term: nat, entries: Seq<Entry>, leader_commit: nat)
+    requires
+        inductive(c, u),
+        0 <= h < u.hosts.len(),
+        u.sent_messages.contains(Message::AppendEntries { term, leader, prev_index, prev_term, entries, leader_commit }),
+        prev_index <= u.hosts[h].log.len(),
+        term_at(u.hosts[h].log, prev_index) == prev_term,
+    ensures
+        u.leader_logs.contains_key(term),
+        u.leaders[term] == leader,
+        prev_index + entries.len() <= u.leader_logs[term].len(),
+        entries == u.leader_logs[term].subrange(prev_index as int, prev_index + entries.len()),
+        u.hosts[h].log.subrange(0, prev_index as int) == u.leader_logs[term].subrange(0, prev_index as int),
+        has_prefix(host::merge_entries(u.hosts[h].log, prev_index, entries), u.prefix(term, prev_index + entries.len())),
+    {
+        let (log, leader_log) = (u.hosts[h].log, u.leader_logs[term]);
+        if (prev_index > 0) {
+            // Both logs end their first `prev_index` entries in `prev_term`, so both start like the log
+            // of the leader of `prev_term`.
+            let p = prev_index - 1;
+            let s = log[p].term;
+            assert(log.subrange(0, p + 1) == u.leader_logs[s].subrange(0, p + 1));
+            assert(leader_log[p].term == s);
+            assert(leader_log.subrange(0, p + 1) == u.leader_logs[s].subrange(0, p + 1));
+        } else {
+            assert(log.subrange(0, 0) =~= leader_log.subrange(0, 0));
+        }
+        host::merge_matches_leader(log, prev_index, entries, leader_log);
+    }
+
+    impl Variables {
+        pub proof fn acked_is_monotonic(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event, h: nat, term: nat, index: nat)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+            u.acked(h, term, index),
+        ensures
+            self.acked(h, term, index),
+        {
+            host_step_facts(c, u, self, host_id, net_op, event);
+            leader_history_facts(c, u, self, host_id, net_op, event);
+            if (u.leaders.contains_key(term) && u.leaders[term] == h && index <= u.leader_logs[term].len()) {
+                assert(self.leaders[term] == h && index <= self.leader_logs[term].len());
+            } else {
+                let (leader, match_index) = choose |leader: nat, match_index: nat|
+                    index <= match_index &&
+                    #[trigger] u.sent_messages.contains(Message::AppendEntriesReply { term, sender: h, leader, match_index });
+                assert(self.sent_messages.contains(Message::AppendEntriesReply { term, sender: h, leader, match_index }));
+            }
+        }
+
+        pub proof fn committed_is_monotonic(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event, term: nat, index: nat)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+            u.committed(c, term, index),
+        ensures
+            self.committed(c, term, index),
+            forall |k: nat| k <= index ==> #[trigger] self.prefix(term, k) == u.prefix(term, k),
+        {
+            leader_history_facts(c, u, self, host_id, net_op, event);
+            assert(self.leader_logs[term][index - 1] == u.leader_logs[term][index - 1]) by {
+                assert(self.prefix(term, index)[index - 1] == u.prefix(term, index)[index - 1]);
+            };
+            let hosts = choose |hosts: Set<nat>| c.is_majority(hosts) && #[trigger] u.acked_by(term, index, hosts);
+            assert forall |h: nat| #[trigger] hosts.contains(h) implies self.acked(h, term, index) by {
+                self.acked_is_monotonic(c, u, host_id, net_op, event, h, term, index);
+            };
+            assert(self.acked_by(term, index, hosts));
+        }
+
+        pub proof fn committed_prefix_is_monotonic(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event, log: Seq<Entry>, new_log: Seq<Entry>, commit_index: nat, term: nat, new_term: nat)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+            u.committed_prefix(c, log, commit_index, term),
+            commit_index <= log.len(),
+            commit_index <= new_log.len(),
+            new_log.subrange(0, commit_index as int) == log.subrange(0, commit_index as int),
+            term <= new_term,
+        ensures
+            self.committed_prefix(c, new_log, commit_index, new_term),
+        {
+            if (commit_index > 0) {
+                let (t, n) = choose |t: nat, n: nat| #[trigger] u.committed(c, t, n) && t <= term && commit_index <= n && has_prefix(log, u.prefix(t, commit_index));
+                self.committed_is_monotonic(c, u, host_id, net_op, event, t, n);
+                assert(self.prefix(t, commit_index) == u.prefix(t, commit_index));
+                assert(self.committed(c, t, n) && has_prefix(new_log, self.prefix(t, commit_index)));
+            }
+        }
+
+        // A majority that abandoned entries keeps them abandoned: its members stay past the term, and
+        // nobody acknowledges anything in a term it has left.
+        pub proof fn abandoned_is_monotonic(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event, term: nat, index: nat)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+            u.abandoned(c, term, index),
+        ensures
+            self.abandoned(c, term, index),
+        {
+            host_step_facts(c, u, self, host_id, net_op, event);
+            leader_history_facts(c, u, self, host_id, net_op, event);
+            let hosts = choose |hosts: Set<nat>| c.is_majority(hosts) && #[trigger] u.abandoned_by(term, index, hosts);
+            assert forall |h: nat| #[trigger] hosts.contains(h) implies self.hosts[h as int].current_term > term && !self.acked(h, term, index) by {
+                if (self.acked(h, term, index)) {
+                    self.new_ack_is_in_current_term(c, u, host_id, net_op, event, h, term, index);
+                }
+            };
+            assert(self.abandoned_by(term, index, hosts));
+        }
+
+        // An acknowledgement `u` lacks comes from the stepping host, in the term it now has.
+        pub proof fn new_ack_is_in_current_term(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event, h: nat, term: nat, index: nat)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+            h < c.num_hosts,
+            self.acked(h, term, index),
+            !u.acked(h, term, index),
+        ensures
+            h == host_id,
+            u.hosts[host_id].current_term <= term,
+            self.hosts[host_id].current_term == term,
+            forall |later: nat, candidate: nat| #[trigger] self.sent_messages.contains(Message::Vote { term: later, voter: h, candidate }) ==>
+                u.sent_messages.contains(Message::Vote { term: later, voter: h, candidate }),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, net_op, event);
+            leader_history_facts(c, u, self, host_id, net_op, event);
+            if (self.leaders.contains_key(term) && self.leaders[term] == h && index <= self.leader_logs[term].len()) {
+                // It just won `term`, or appended the entry as its leader.
+                if (u.leader_logs.contains_key(term)) {
+                    assert(u.leaders[term] == h);
+                    assert(self.leader_logs[term] != u.leader_logs[term]);
+                }
+            } else {
+                let (leader, match_index) = choose |leader: nat, match_index: nat|
+                    index <= match_index &&
+                    #[trigger] self.sent_messages.contains(Message::AppendEntriesReply { term, sender: h, leader, match_index });
+                assert(!u.sent_messages.contains(Message::AppendEntriesReply { term, sender: h, leader, match_index }));
+                assert(host::append_entries(lc, lu, lv, net_op));
+            }
+        }
+
+        pub proof fn votes_are_unique_is_inductive(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+        ensures
+            self.votes_are_unique(c),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, net_op, event);
+
+            assert forall |term: nat, voter: nat, candidate: nat| #[trigger] self.sent_messages.contains(Message::Vote { term, voter, candidate }) implies
+                voter < c.num_hosts &&
+                (self.hosts[voter as int].current_term > term ||
+                    self.hosts[voter as int].current_term == term && self.hosts[voter as int].voted_for == Some(candidate))
+            by {
+                if (!u.sent_messages.contains(Message::Vote { term, voter, candidate })) {
+                    assert(host::vote(lc, lu, lv, net_op) && voter == host_id);
+                } else if (voter == host_id && lu.current_term == term && lv.current_term == term) {
+                    // Staying in `term`, the host keeps its vote or casts the same one again.
+                    assert(lu.voted_for == Some(candidate));
+                    assert(lv.voted_for == Some(candidate));
+                }
+            };
+
+            assert forall |term: nat, voter: nat, c1: nat, c2: nat|
+                #[trigger] self.sent_messages.contains(Message::Vote { term, voter, candidate: c1 }) &&
+                #[trigger] self.sent_messages.contains(Message::Vote { term, voter, candidate: c2 }) implies
+                c1 == c2
+            by {
+                // A new vote is in the voter's current term, where it already voted for the same host.
+                if (!u.sent_messages.contains(Message::Vote { term, voter, candidate: c1 })) {
+                    assert(host::vote(lc, lu, lv, net_op) && voter == host_id);
+                    if (u.sent_messages.contains(Message::Vote { term, voter, candidate: c2 })) {
+                        assert(lu.current_term == term && lu.voted_for == Some(c2));
+                    }
+                } else if (!u.sent_messages.contains(Message::Vote { term, voter, candidate: c2 })) {
+                    assert(host::vote(lc, lu, lv, net_op) && voter == host_id);
+                    assert(lu.current_term == term && lu.voted_for == Some(c1));
+                }
+            };
+        }
+
+        pub proof fn candidates_match_their_requests_is_inductive(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+        ensures
+            self.candidates_match_their_requests(c),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, net_op, event);
+
+            assert forall |term: nat, candidate: nat, last_log_index: nat, last_log_term: nat|
+                #[trigger] self.sent_messages.contains(Message::RequestVote { term, candidate, last_log_index, last_log_term }) implies
+                candidate < c.num_hosts && self.hosts[candidate as int].current_term >= term
+            by {
+                if (!u.sent_messages.contains(Message::RequestVote { term, candidate, last_log_index, last_log_term })) {
+                    assert(host::start_election(lc, lu, lv, net_op) && candidate == host_id);
+                }
+            };
+
+            assert forall |i: int| #![auto] 0 <= i < self.hosts.len() && self.hosts[i].role is Candidate implies
+                are_valid_hosts(self.hosts[i].votes, c.num_hosts) &&
+                (forall |voter: nat| #[trigger] self.hosts[i].votes.contains(voter) ==>
+                    self.sent_messages.contains(Message::Vote { term: self.hosts[i].current_term, voter, candidate: i as nat })) &&
+                (forall |last_log_index: nat, last_log_term: nat|
+                    #[trigger] self.sent_messages.contains(Message::RequestVote { term: self.hosts[i].current_term, candidate: i as nat, last_log_index, last_log_term }) ==>
+                    last_log_index == self.hosts[i].log.len() && last_log_term == last_log_term(self.hosts[i].log))
+            by {
+                let term = self.hosts[i].current_term;
+                if (i != host_id) {
+                    // A new RequestVote names the stepping host.
+                    assert forall |last_log_index: nat, last_log_term: nat|
+                        #[trigger] self.sent_messages.contains(Message::RequestVote { term, candidate: i as nat, last_log_index, last_log_term }) implies
+                        u.sent_messages.contains(Message::RequestVote { term, candidate: i as nat, last_log_index, last_log_term })
+                    by {};
+                } else if (host::start_election(lc, lu, lv, net_op)) {
+                    // Its earlier requests were for earlier terms.
+                    assert(lv.votes =~= Set::<nat>::empty());
+                    assert forall |last_log_index: nat, last_log_term: nat|
+                        #[trigger] self.sent_messages.contains(Message::RequestVote { term, candidate: i as nat, last_log_index, last_log_term }) implies
+                        last_log_index == lv.log.len() && last_log_term == last_log_term(lv.log)
+                    by {
+                        assert(!u.sent_messages.contains(Message::RequestVote { term, candidate: i as nat, last_log_index, last_log_term }));
+                    };
+                } else {
+                    // Otherwise it was a candidate in the same term with the same log already.
+                    assert(lu.role is Candidate && lu.current_term == term && lu.log == lv.log);
+                    if (host::receive_vote(lc, lu, lv, net_op)) {
+                        let Message::Vote { term: _, voter: new_voter, candidate: _ } = net_op.recv.unwrap() else { assert(false); return; };
+                        assert(u.sent_messages.contains(Message::Vote { term, voter: new_voter, candidate: i as nat }));
+                        assert(lv.votes =~= lu.votes.insert(new_voter));
+                    }
+                }
+            };
+        }
+
+        pub proof fn leaders_won_elections_is_inductive(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+        ensures
+            self.leaders_won_elections(c),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, net_op, event);
+            leader_history_facts(c, u, self, host_id, net_op, event);
+
+            assert forall |term: nat| #[trigger] self.leaders.contains_key(term) implies
+                self.leaders[term] < c.num_hosts &&
+                self.hosts[self.leaders[term] as int].current_term >= term &&
+                (self.hosts[self.leaders[term] as int].current_term == term ==> !(self.hosts[self.leaders[term] as int].role is Candidate)) &&
+                exists |voters: Set<nat>| #[trigger] self.elected_by(c, term, self.leaders[term], voters)
+            by {
+                if (!u.leaders.contains_key(term)) {
+                    // The new leader's votes elected it.
+                    assert(host::become_leader(lc, lu, lv, net_op) && self.leaders[term] == host_id);
+                    assert(self.elected_by(c, term, host_id as nat, lu.votes));
+                } else {
+                    let winner = u.leaders[term];
+                    assert(self.leaders[term] == winner);
+                    let voters = choose |voters: Set<nat>| #[trigger] u.elected_by(c, term, winner, voters);
+                    assert(self.elected_by(c, term, winner, voters));
+                    if (winner == host_id && lv.current_term == term && lv.role is Candidate) {
+                        // Becoming a candidate moves to a new term, past every term it won.
+                        assert(lu.current_term == term && lu.role is Candidate);
+                    }
+                }
+            };
+
+            assert forall |i: int| #![auto] 0 <= i < self.hosts.len() && self.hosts[i].role is Leader implies
+                self.leaders.contains_key(self.hosts[i].current_term) &&
+                self.leaders[self.hosts[i].current_term] == i &&
+                self.leader_logs[self.hosts[i].current_term] == self.hosts[i].log &&
+                are_valid_hosts(self.hosts[i].match_index.dom(), c.num_hosts) &&
+                (forall |h: nat| #[trigger] self.hosts[i].match_index.contains_key(h) ==>
+                    self.acked(h, self.hosts[i].current_term, self.hosts[i].match_index[h]))
+            by {
+                let term = self.hosts[i].current_term;
+                if (i != host_id) {
+                    // Only one host leads `term`, so the stepping host recorded another term, if any.
+                    if (lv.role is Leader && lv.current_term == term) {
+                        assert(u.leaders[term] == i);
+                        if (lu.role is Leader) { assert(u.leaders[term] == host_id); } else { assert(!u.leader_logs.contains_key(term)); }
+                    }
+                    assert(self.leaders[term] == i && self.leader_logs[term] == self.hosts[i].log);
+                    assert forall |h: nat| #[trigger] self.hosts[i].match_index.contains_key(h) implies self.acked(h, term, self.hosts[i].match_index[h]) by {
+                        self.acked_is_monotonic(c, u, host_id, net_op, event, h, term, u.hosts[i].match_index[h]);
+                    };
+                } else if (host::become_leader(lc, lu, lv, net_op)) {
+                    assert(lv.match_index.dom() =~= Set::<nat>::empty());
+                } else {
+                    assert(lu.role is Leader && lu.current_term == term);
+                    if (host::append_entries_reply(lc, lu, lv, net_op)) {
+                        let Message::AppendEntriesReply { term: _, sender, leader, match_index } = net_op.recv.unwrap() else { assert(false); return; };
+                        assert(u.sent_messages.contains(Message::AppendEntriesReply { term, sender, leader, match_index }));
+                        assert(lv.match_index.dom() =~= lu.match_index.dom().insert(sender));
+                        assert forall |h: nat| #[trigger] lv.match_index.contains_key(h) implies self.acked(h, term, lv.match_index[h]) by {
+                            if (h == sender && match_index > (if lu.match_index.contains_key(sender) { lu.match_index[sender] } else { 0 })) {
+                                assert(self.sent_messages.contains(Message::AppendEntriesReply { term, sender, leader, match_index }));
+                            } else if (h == sender && !lu.match_index.contains_key(sender)) {
+                                assert(lv.match_index[h] == 0);
+                                assert(self.sent_messages.contains(Message::AppendEntriesReply { term, sender, leader, match_index }));
+                            } else {
+                                self.acked_is_monotonic(c, u, host_id, net_op, event, h, term, lu.match_index[h]);
+                            }
+                        };
+                    } else {
+                        assert(lv.match_index == lu.match_index);
+                        assert forall |h: nat| #[trigger] lv.match_index.contains_key(h) implies self.acked(h, term, lv.match_index[h]) by {
+                            self.acked_is_monotonic(c, u, host_id, net_op, event, h, term, lu.match_index[h]);
+                        };
+                    }
+                }
+            };
+        }
+
+        pub proof fn logs_match_leader_logs_is_inductive(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+        ensures
+            self.logs_match_leader_logs(c),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, net_op, event);
+            leader_history_facts(c, u, self, host_id, net_op, event);
+
+            // A prefix `u` matched against the log of the leader of `s` still matches it.
+            assert forall |s: nat, log: Seq<Entry>, i: int|
+                u.leader_logs.contains_key(s) &&
+                0 <= i < u.leader_logs[s].len() &&
+                #[trigger] log.subrange(0, i + 1) == u.leader_logs[s].subrange(0, i + 1) implies
+                log.subrange(0, i + 1) == self.leader_logs[s].subrange(0, i + 1) && i < self.leader_logs[s].len()
+            by {
+                assert(self.prefix(s, (i + 1) as nat) == u.prefix(s, (i + 1) as nat));
+            };
+
+            assert forall |term: nat, i: int| #![trigger self.leader_logs[term][i]]
+                self.leader_logs.contains_key(term) &&
+                0 <= i < self.leader_logs[term].len() implies {
+                    let s = self.leader_logs[term][i].term;
+                    &&& s <= term
+                    &&& self.leader_logs.contains_key(s)
+                    &&& i < self.leader_logs[s].len()
+                    &&& self.leader_logs[term].subrange(0, i + 1) == self.leader_logs[s].subrange(0, i + 1)
+                }
+            by {
+                let log = self.leader_logs[term];
+                if (!u.leader_logs.contains_key(term)) {
+                    // A new leader's log matched the history as its own log.
+                    assert(log == lu.log && term == lu.current_term);
+                    let s = lu.log[i].term;
+                    assert(lu.log.subrange(0, i + 1) == u.leader_logs[s].subrange(0, i + 1));
+                } else if (i < u.leader_logs[term].len()) {
+                    let s = u.leader_logs[term][i].term;
+                    assert(log.subrange(0, i + 1) == u.leader_logs[term].subrange(0, i + 1)) by {
+                        assert(self.prefix(term, (i + 1) as nat) == u.prefix(term, (i + 1) as nat));
+                    };
+                    assert(log[i] == u.leader_logs[term][i]);
+                    assert(u.leader_logs[term].subrange(0, i + 1) == u.leader_logs[s].subrange(0, i + 1));
+                } else {
+                    // The entry a leader just appended is of its own term.
+                    assert(log[i].term == term);
+                }
+            };
+
+            assert forall |h: int, i: int| #![trigger self.hosts[h].log[i]]
+                0 <= h < self.hosts.len() &&
+                0 <= i < self.hosts[h].log.len() implies {
+                    let s = self.hosts[h].log[i].term;
+                    &&& s <= self.hosts[h].current_term
+                    &&& self.leader_logs.contains_key(s)
+                    &&& i < self.leader_logs[s].len()
+                    &&& self.hosts[h].log.subrange(0, i + 1) == self.leader_logs[s].subrange(0, i + 1)
+                }
+            by {
+                let log = self.hosts[h].log;
+                if (h != host_id || lv.log == lu.log) {
+                    let s = u.hosts[h].log[i].term;
+                    assert(u.hosts[h].log.subrange(0, i + 1) == u.leader_logs[s].subrange(0, i + 1));
+                } else if (host::append_entries(lc, lu, lv, net_op)) {
+                    let Message::AppendEntries { term, leader, prev_index, prev_term, entries, leader_commit } = net_op.recv.unwrap() else { assert(false); return; };
+                    append_entries_matches_leader(c, u, host_id, term, leader, prev_index, prev_term, entries, leader_commit);
+                    let leader_log = u.leader_logs[term];
+                    if (i < prev_index) {
+                        // Below the slice the log is what it was.
+                        let s = lu.log[i].term;
+                        assert(log.subrange(0, i + 1) == lu.log.subrange(0, i + 1));
+                        assert(lu.log.subrange(0, i + 1) == u.leader_logs[s].subrange(0, i + 1));
+                    } else {
+                        // From the slice on it is the leader's log.
+                        assert(log.subrange(0, i + 1) == leader_log.subrange(0, i + 1)) by {
+                            assert(log.subrange(0, (prev_index + entries.len()) as int) == leader_log.subrange(0, (prev_index + entries.len()) as int));
+                        };
+                        let s = leader_log[i].term;
+                        assert(log[i] == leader_log[i]);
+                        assert(leader_log.subrange(0, i + 1) == u.leader_logs[s].subrange(0, i + 1));
+                    }
+                } else {
+                    // A leader appends an entry of its current term to its recorded log.
+                    let value = choose |value: Value| #[trigger] host::propose(lc, lu, lv, net_op, value);
+                    assert(host::propose(lc, lu, lv, net_op, value));
+                    assert(lv.log == self.leader_logs[lv.current_term]);
+                    if (i < lu.log.len()) {
+                        let s = lu.log[i].term;
+                        assert(log.subrange(0, i + 1) == lu.log.subrange(0, i + 1));
+                        assert(lu.log.subrange(0, i + 1) == u.leader_logs[s].subrange(0, i + 1));
+                    } else {
+                        assert(log.subrange(0, i + 1) =~= log);
+                    }
+                }
+            };
+        }
+
+        pub proof fn messages_match_leader_logs_is_inductive(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+        ensures
+            self.messages_match_leader_logs(c),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, net_op, event);
+            leader_history_facts(c, u, self, host_id, net_op, event);
+
+            assert forall |term: nat, leader: nat, prev_index: nat, prev_term: nat, entries: Seq<Entry>, leader_commit: nat|
+                #[trigger] self.sent_messages.contains(Message::AppendEntries { term, leader, prev_index, prev_term, entries, leader_commit }) implies
+                self.leaders.contains_key(term) &&
+                self.leaders[term] == leader &&
+                prev_index + entries.len() <= self.leader_logs[term].len() &&
+                prev_term == term_at(self.leader_logs[term], prev_index) &&
+                entries == self.leader_logs[term].subrange(prev_index as int, prev_index + entries.len()) &&
+                leader_commit <= self.hosts[leader as int].commit_index &&
+                self.committed_prefix(c, self.leader_logs[term], leader_commit, term)
+            by {
+                let end = prev_index + entries.len();
+                if (!u.sent_messages.contains(Message::AppendEntries { term, leader, prev_index, prev_term, entries, leader_commit })) {
+                    // A leader sends a slice of its own log, which is the recorded one.
+                    assert(host::send_append_entries(lc, lu, lv, net_op) && lv == lu);
+                    assert(u.leaders[term] == host_id && u.leader_logs[term] == lu.log);
+                    assert(self.leader_logs[term] == lu.log);
+                    self.committed_prefix_is_monotonic(c, u, host_id, net_op, event, lu.log, lu.log, lu.commit_index, term, term);
+                } else {
+                    // The leader's log has only grown since.
+                    let old_log = u.leader_logs[term];
+                    let new_log = self.leader_logs[term];
+                    assert(new_log.subrange(0, old_log.len() as int) == old_log);
+                    assert(new_log.subrange(prev_index as int, end as int) =~= old_log.subrange(prev_index as int, end as int)) by {
+                        assert forall |k: int| prev_index <= k < end implies new_log[k] == old_log[k] by {
+                            assert(new_log.subrange(0, old_log.len() as int)[k] == new_log[k]);
+                        };
+                    };
+                    if (prev_index > 0) {
+                        assert(new_log.subrange(0, old_log.len() as int)[prev_index - 1] == new_log[prev_index - 1]);
+                    }
+                    if (leader_commit > 0) {
+                        let (t, n) = choose |t: nat, n: nat| #[trigger] u.committed(c, t, n) && t <= term && leader_commit <= n && has_prefix(old_log, u.prefix(t, leader_commit));
+                        assert(old_log.subrange(0, leader_commit as int) == new_log.subrange(0, leader_commit as int)) by {
+                            assert forall |k: int| 0 <= k < leader_commit implies new_log[k] == old_log[k] by {
+                                assert(new_log.subrange(0, old_log.len() as int)[k] == new_log[k]);
+                            };
+                            assert(old_log.subrange(0, leader_commit as int) =~= new_log.subrange(0, leader_commit as int));
+                        };
+                    }
+                    self.committed_prefix_is_monotonic(c, u, host_id, net_op, event, old_log, new_log, leader_commit, term, term);
+                }
+            };
+
+            assert forall |term: nat, sender: nat, leader: nat, match_index: nat|
+                #[trigger] self.sent_messages.contains(Message::AppendEntriesReply { term, sender, leader, match_index }) implies
+                sender < c.num_hosts &&
+                self.leader_logs.contains_key(term) &&
+                match_index <= self.leader_logs[term].len() &&
+                self.hosts[sender as int].current_term >= term
+            by {
+                if (!u.sent_messages.contains(Message::AppendEntriesReply { term, sender, leader, match_index })) {
+                    assert(host::append_entries(lc, lu, lv, net_op) && sender == host_id);
+                    let Message::AppendEntries { term: _, leader: _, prev_index, prev_term, entries, leader_commit } = net_op.recv.unwrap() else { assert(false); return; };
+                    assert(u.sent_messages.contains(Message::AppendEntries { term, leader, prev_index, prev_term, entries, leader_commit }));
+                } else {
+                    assert(u.leader_logs[term].len() <= self.leader_logs[term].len());
+                }
+            };
+
+            assert forall |term: nat, i: int| #![trigger self.leader_logs[term][i]]
+                self.leader_logs.contains_key(term) &&
+                0 <= i < self.leader_logs[term].len() implies
+                self.sent_messages.contains(Message::ClientRequest { value: self.leader_logs[term][i].value })
+            by {
+                if (!u.leader_logs.contains_key(term)) {
+                    let s = lu.log[i].term;
+                    assert(lu.log.subrange(0, i + 1) == u.leader_logs[s].subrange(0, i + 1));
+                    assert(lu.log[i] == u.leader_logs[s][i]);
+                } else if (i < u.leader_logs[term].len()) {
+                    assert(self.leader_logs[term][i] == u.leader_logs[term][i]) by {
+                        assert(self.leader_logs[term].subrange(0, u.leader_logs[term].len() as int)[i] == self.leader_logs[term][i]);
+                    };
+                } else {
+                    // The request a leader appends was sent to it.
+                    let value = choose |value: Value| #[trigger] host::propose(lc, lu, lv, net_op, value);
+                    assert(u.sent_messages.contains(Message::ClientRequest { value }));
+                    assert(self.leader_logs[term][i].value == value);
+                }
+            };
+        }
+
+        pub proof fn acknowledgements_are_kept_is_inductive(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+        ensures
+            self.acknowledgements_are_kept(c),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, net_op, event);
+            leader_history_facts(c, u, self, host_id, net_op, event);
+
+            assert forall |h: nat, term: nat, index: nat|
+                h < c.num_hosts &&
+                #[trigger] self.committable(term, index) &&
+                #[trigger] self.acked(h, term, index) &&
+                !self.abandoned(c, term, index) implies
+                has_prefix(self.hosts[h as int].log, self.prefix(term, index))
+            by {
+                if (u.abandoned(c, term, index)) { self.abandoned_is_monotonic(c, u, host_id, net_op, event, term, index); }
+                if (u.committable(term, index) && u.acked(h, term, index)) {
+                    let prefix = u.prefix(term, index);
+                    assert(has_prefix(u.hosts[h as int].log, prefix));
+                    assert(self.prefix(term, index) == prefix);
+                    if (h == host_id && lv.log != lu.log) {
+                        if (host::append_entries(lc, lu, lv, net_op)) {
+                            // The sender leads a term at or after the acknowledged one, and its log
+                            // starts with the acknowledged entries.
+                            let Message::AppendEntries { term: later, leader, prev_index, prev_term, entries, leader_commit } = net_op.recv.unwrap() else { assert(false); return; };
+                            append_entries_matches_leader(c, u, host_id, later, leader, prev_index, prev_term, entries, leader_commit);
+                            acked_host_reached_term(c, u, h, term, index);
+                            if (term < later) {
+                                assert(u.leader_logs.contains_key(later));
+                                assert(has_prefix(u.leader_logs[later], prefix));
+                            } else {
+                                assert(u.leader_logs[later].subrange(0, index as int) == prefix);
+                            }
+                            host::merge_keeps_common_prefix(lu.log, prev_index, entries, u.leader_logs[later], prefix);
+                        } else {
+                            let value = choose |value: Value| #[trigger] host::propose(lc, lu, lv, net_op, value);
+                            assert(lv.log.subrange(0, index as int) =~= lu.log.subrange(0, index as int));
+                        }
+                    }
+                } else if (u.committable(term, index)) {
+                    // A new acknowledgement: the host just matched the leader's log up to the reply.
+                    self.new_ack_is_in_current_term(c, u, host_id, net_op, event, h, term, index);
+                    if (host::append_entries(lc, lu, lv, net_op)) {
+                        let Message::AppendEntries { term: _, leader, prev_index, prev_term, entries, leader_commit } = net_op.recv.unwrap() else { assert(false); return; };
+                        append_entries_matches_leader(c, u, host_id, term, leader, prev_index, prev_term, entries, leader_commit);
+                        assert(index <= prev_index + entries.len());
+                        assert(lv.log.subrange(0, index as int) =~= u.prefix(term, prev_index + entries.len()).subrange(0, index as int));
+                    } else {
+                        assert(self.leader_logs[term] == lv.log);
+                        assert(lv.log.subrange(0, index as int) == self.prefix(term, index));
+                    }
+                } else {
+                    // The entries were just appended by their leader, which is the only one to hold them.
+                    self.new_committable_is_only_leaders(c, u, host_id, net_op, event, h, term, index);
+                }
+            };
+
+            assert forall |h: nat, term: nat, index: nat, later: nat, candidate: nat|
+                #![trigger self.acked(h, term, index), self.sent_messages.contains(Message::Vote { term: later, voter: h, candidate })]
+                self.committable(term, index) &&
+                self.acked(h, term, index) &&
+                self.sent_messages.contains(Message::Vote { term: later, voter: h, candidate }) &&
+                term < later &&
+                !self.abandoned(c, term, index) implies
+                exists |last_log_index: nat, last_log_term: nat|
+                    #[trigger] self.sent_messages.contains(Message::RequestVote { term: later, candidate, last_log_index, last_log_term }) &&
+                    (last_log_term > term || last_log_term == term && last_log_index >= index)
+            by {
+                if (u.abandoned(c, term, index)) { self.abandoned_is_monotonic(c, u, host_id, net_op, event, term, index); }
+                assert(h < c.num_hosts);
+                if (!(u.committable(term, index) && u.acked(h, term, index))) {
+                    // Acknowledged in the host's current term, which is before any term it voted in.
+                    if (u.committable(term, index)) {
+                        self.new_ack_is_in_current_term(c, u, host_id, net_op, event, h, term, index);
+                    } else {
+                        self.new_committable_is_only_leaders(c, u, host_id, net_op, event, h, term, index);
+                    }
+                    assert(u.sent_messages.contains(Message::Vote { term: later, voter: h, candidate }));
+                    assert(u.hosts[h as int].current_term >= later);
+                    assert(false);
+                } else if (u.sent_messages.contains(Message::Vote { term: later, voter: h, candidate })) {
+                    let (last_log_index, last_log_term) = choose |last_log_index: nat, last_log_term: nat|
+                        #[trigger] u.sent_messages.contains(Message::RequestVote { term: later, candidate, last_log_index, last_log_term }) &&
+                        (last_log_term > term || last_log_term == term && last_log_index >= index);
+                    assert(self.sent_messages.contains(Message::RequestVote { term: later, candidate, last_log_index, last_log_term }));
+                } else {
+                    // A new vote: the voter still holds the entries, so the up-to-date check covers them.
+                    assert(host::vote(lc, lu, lv, net_op) && h == host_id);
+                    let Message::RequestVote { term: _, candidate: _, last_log_index, last_log_term } = net_op.recv.unwrap() else { assert(false); return; };
+                    assert(u.sent_messages.contains(Message::RequestVote { term: later, candidate, last_log_index, last_log_term }));
+                    assert(has_prefix(lu.log, u.prefix(term, index)));
+                    assert(lu.log[index - 1] == u.leader_logs[term][index - 1]) by {
+                        assert(lu.log.subrange(0, index as int)[index - 1] == lu.log[index - 1]);
+                    };
+                    host_log_terms_are_sorted(c, u, host_id, index - 1, lu.log.len() - 1);
+                    assert(last_log_term(lu.log) >= term);
+                    assert(self.sent_messages.contains(Message::RequestVote { term: later, candidate, last_log_index, last_log_term }));
+                }
+            };
+        }
+
+        // Entries that became committable in this step were just appended by the leader of their term,
+        // which is the only host to acknowledge them, and a majority that voted in a later term has
+        // already left their term.
+        pub proof fn new_committable_is_only_leaders(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event, h: nat, term: nat, index: nat)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+            self.committable(term, index),
+            !u.committable(term, index),
+        ensures
+            u.leader_logs.contains_key(term),
+            index == u.leader_logs[term].len() + 1,
+            self.acked(h, term, index) ==> h == host_id,
+            self.acked(h, term, index) ==> has_prefix(self.hosts[h as int].log, self.prefix(term, index)),
+            u.hosts[host_id].current_term == term,
+            self.hosts[host_id].current_term == term,
+            self.sent_messages == u.sent_messages,
+            forall |later: nat| #[trigger] u.leader_logs.contains_key(later) && term < later ==> self.abandoned(c, term, index),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, net_op, event);
+            leader_history_facts(c, u, self, host_id, net_op, event);
+            if (!u.leader_logs.contains_key(term)) {
+                // A new leader's entries all come from earlier terms.
+                let s = lu.log[index - 1].term;
+                assert(u.leader_logs.contains_key(s));
+                assert(false);
+            }
+            if (index <= u.leader_logs[term].len()) {
+                assert(self.leader_logs[term][index - 1] == u.leader_logs[term][index - 1]) by {
+                    assert(self.leader_logs[term].subrange(0, u.leader_logs[term].len() as int)[index - 1] == self.leader_logs[term][index - 1]);
+                };
+                assert(false);
+            }
+            assert(self.leader_logs[term] != u.leader_logs[term]);
+            assert(self.sent_messages == u.sent_messages);
+            if (self.acked(h, term, index) && !(self.leaders.contains_key(term) && self.leaders[term] == h && index <= self.leader_logs[term].len())) {
+                let (leader, match_index) = choose |leader: nat, match_index: nat|
+                    index <= match_index &&
+                    #[trigger] self.sent_messages.contains(Message::AppendEntriesReply { term, sender: h, leader, match_index });
+                assert(u.sent_messages.contains(Message::AppendEntriesReply { term, sender: h, leader, match_index }));
+                assert(false);
+            }
+            if (self.acked(h, term, index)) {
+                assert(self.leaders[term] == u.leaders[term] && u.leaders[term] == host_id);
+                assert(self.prefix(term, index) =~= lv.log);
+            }
+
+            // The voters of a later term left `term` before this leader appended to it.
+            assert forall |later: nat| #[trigger] u.leader_logs.contains_key(later) && term < later implies self.abandoned(c, term, index) by {
+                let voters = choose |voters: Set<nat>| #[trigger] u.elected_by(c, later, u.leaders[later], voters);
+                assert forall |q: nat| #[trigger] voters.contains(q) implies self.hosts[q as int].current_term > term && !self.acked(q, term, index) by {
+                    assert(u.sent_messages.contains(Message::Vote { term: later, voter: q, candidate: u.leaders[later] }));
+                    assert(u.hosts[q as int].current_term >= later);
+                    if (self.acked(q, term, index)) { assert(q == host_id); }
+                };
+                assert(self.abandoned_by(term, index, voters));
+            };
+        }
+
+        pub proof fn leaders_are_complete_is_inductive(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+        ensures
+            self.leaders_are_complete(c),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, net_op, event);
+            leader_history_facts(c, u, self, host_id, net_op, event);
+
+            assert forall |term: nat, index: nat, later: nat|
+                #[trigger] self.committable(term, index) &&
+                #[trigger] self.leader_logs.contains_key(later) &&
+                term < later implies
+                has_prefix(self.leader_logs[later], self.prefix(term, index)) || self.abandoned(c, term, index)
+            by {
+                if (u.abandoned(c, term, index)) {
+                    self.abandoned_is_monotonic(c, u, host_id, net_op, event, term, index);
+                } else if (!u.committable(term, index)) {
+                    self.new_committable_is_only_leaders(c, u, host_id, net_op, event, host_id as nat, term, index);
+                    if (!u.leader_logs.contains_key(later)) {
+                        // Only one term is new in a step, and it is not the one appended to.
+                        assert(host::become_leader(lc, lu, lv, net_op));
+                        assert(false);
+                    }
+                } else if (u.leader_logs.contains_key(later)) {
+                    let prefix = u.prefix(term, index);
+                    assert(has_prefix(u.leader_logs[later], prefix));
+                    assert(self.prefix(term, index) == prefix);
+                    assert(self.leader_logs[later].subrange(0, index as int) == prefix) by {
+                        assert(self.prefix(later, index) == u.prefix(later, index));
+                    };
+                } else {
+                    self.new_leader_is_complete(c, u, host_id, net_op, event, term, index);
+                }
+            };
+        }
+
+        // A new leader of `later` starts with every committable prefix of an earlier term that its
+        // voters did not abandon: one of its voters acknowledged the prefix, and voted for it only
+        // because its log was at least as up to date.
+        pub proof fn new_leader_is_complete(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event, term: nat, index: nat)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+            u.committable(term, index),
+            !u.abandoned(c, term, index),
+            self.leader_logs.contains_key(u.hosts[host_id].current_term),
+            !u.leader_logs.contains_key(u.hosts[host_id].current_term),
+            term < u.hosts[host_id].current_term,
+        ensures
+            has_prefix(self.leader_logs[u.hosts[host_id].current_term], self.prefix(term, index)) || self.abandoned(c, term, index),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            let later = lu.current_term;
+            host_step_facts(c, u, self, host_id, net_op, event);
+            leader_history_facts(c, u, self, host_id, net_op, event);
+            assert(host::become_leader(lc, lu, lv, net_op));
+            let log = lu.log;
+            let prefix = u.prefix(term, index);
+            assert(self.leader_logs[later] == log);
+            assert(self.prefix(term, index) == prefix);
+
+            if (exists |q: nat| #[trigger] lu.votes.contains(q) && u.acked(q, term, index)) {
+                let q = choose |q: nat| #[trigger] lu.votes.contains(q) && u.acked(q, term, index);
+                assert(u.sent_messages.contains(Message::Vote { term: later, voter: q, candidate: host_id as nat }));
+                let (last_log_index, last_log_term) = choose |last_log_index: nat, last_log_term: nat|
+                    #[trigger] u.sent_messages.contains(Message::RequestVote { term: later, candidate: host_id as nat, last_log_index, last_log_term }) &&
+                    (last_log_term > term || last_log_term == term && last_log_index >= index);
+                assert(last_log_index == log.len() && last_log_term == last_log_term(log));
+                let end = log.len() - 1;
+                let s = log[end].term;
+                assert(log.subrange(0, end + 1) == u.leader_logs[s].subrange(0, end + 1));
+                assert(log.subrange(0, end + 1) =~= log);
+                if (s == term) {
+                    assert(log.len() >= index);
+                } else {
+                    // The candidate's last term is between `term` and `later`, and its leader was
+                    // complete: its log holds the prefix before its first entry of term `s`.
+                    assert(s > term && u.leader_logs.contains_key(s));
+                    assert(has_prefix(u.leader_logs[s], prefix));
+                    assert(u.leader_logs[s][index - 1].term == term) by {
+                        assert(prefix[index - 1] == u.leader_logs[term][index - 1]);
+                        assert(u.leader_logs[s].subrange(0, index as int)[index - 1] == u.leader_logs[s][index - 1]);
+                    };
+                    if (end < index - 1) {
+                        leader_log_terms_are_sorted(c, u, s, end, index - 1);
+                        assert(u.leader_logs[s][end] == log[end]) by {
+                            assert(u.leader_logs[s].subrange(0, end + 1)[end] == u.leader_logs[s][end]);
+                        };
+                        assert(false);
+                    }
+                    assert(log.subrange(0, index as int) =~= u.leader_logs[s].subrange(0, index as int)) by {
+                        assert forall |k: int| 0 <= k < index implies log[k] == u.leader_logs[s][k] by {
+                            assert(log.subrange(0, end + 1)[k] == u.leader_logs[s].subrange(0, end + 1)[k]);
+                        };
+                    };
+                }
+                assert(has_prefix(log, prefix)) by {
+                    if (s == term) {
+                        assert(log.subrange(0, index as int) =~= prefix) by {
+                            assert forall |k: int| 0 <= k < index implies log[k] == prefix[k] by {
+                                assert(log.subrange(0, end + 1)[k] == u.leader_logs[term].subrange(0, end + 1)[k]);
+                            };
+                        };
+                    }
+                };
+            } else {
+                // None of the voters acknowledged the prefix, and all of them are past its term.
+                assert forall |q: nat| #[trigger] lu.votes.contains(q) implies self.hosts[q as int].current_term > term && !self.acked(q, term, index) by {
+                    assert(u.sent_messages.contains(Message::Vote { term: later, voter: q, candidate: host_id as nat }));
+                    assert(u.hosts[q as int].current_term >= later);
+                    if (self.acked(q, term, index)) {
+                        self.new_ack_is_in_current_term(c, u, host_id, net_op, event, q, term, index);
+                    }
+                };
+                assert(self.abandoned_by(term, index, lu.votes));
+            }
+        }
+
+        // Committed entries never change on any host.
+        pub proof fn committed_entries_are_stable(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+        ensures
+            forall |i: int, k: int| #![trigger self.hosts[i].log[k]]
+                0 <= i < self.hosts.len() &&
+                0 <= k < u.hosts[i].commit_index ==>
+                k < self.hosts[i].log.len() && self.hosts[i].log[k] == u.hosts[i].log[k],
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, net_op, event);
+            if (lv.log != lu.log && lu.commit_index > 0) {
+                let (t, n) = choose |t: nat, n: nat| #[trigger] u.committed(c, t, n) && t <= lu.current_term && lu.commit_index <= n && has_prefix(lu.log, u.prefix(t, lu.commit_index));
+                let prefix = u.prefix(t, lu.commit_index);
+                if (host::append_entries(lc, lu, lv, net_op)) {
+                    // The sender's term is at least `t`, so its log starts with the committed prefix.
+                    let Message::AppendEntries { term, leader, prev_index, prev_term, entries, leader_commit } = net_op.recv.unwrap() else { assert(false); return; };
+                    append_entries_matches_leader(c, u, host_id, term, leader, prev_index, prev_term, entries, leader_commit);
+                    if (t < term) {
+                        committed_is_not_abandoned(c, u, t, n);
+                        assert(has_prefix(u.leader_logs[term], u.prefix(t, n)));
+                        assert(u.leader_logs[term].subrange(0, lu.commit_index as int) =~= prefix) by {
+                            assert forall |k: int| 0 <= k < lu.commit_index implies u.leader_logs[term][k] == prefix[k] by {
+                                assert(u.leader_logs[term].subrange(0, n as int)[k] == u.prefix(t, n)[k]);
+                            };
+                        };
+                    }
+                    host::merge_keeps_common_prefix(lu.log, prev_index, entries, u.leader_logs[term], prefix);
+                } else {
+                    let value = choose |value: Value| #[trigger] host::propose(lc, lu, lv, net_op, value);
+                }
+                assert forall |k: int| 0 <= k < lu.commit_index implies k < lv.log.len() && lv.log[k] == lu.log[k] by {
+                    assert(lv.log.subrange(0, lu.commit_index as int)[k] == lu.log.subrange(0, lu.commit_index as int)[k]);
+                };
+            }
+        }
+
+        pub proof fn commits_are_safe_is_inductive(&self, c: &Constants, u: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+        requires
+            inductive(c, u),
+            host_step(c, u, self, host_id, net_op, event),
+        ensures
+            self.commits_are_safe(c),
+        {
+            let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &self.hosts[host_id]);
+            host_step_facts(c, u, self, host_id, net_op, event);
+            leader_history_facts(c, u, self, host_id, net_op, event);
+            self.committed_entries_are_stable(c, u, host_id, net_op, event);
+
+            assert forall |i: int| #![auto] 0 <= i < self.hosts.len() implies
+                self.committed_prefix(c, self.hosts[i].log, self.hosts[i].commit_index, self.hosts[i].current_term)
+            by {
+                let (old, new) = (u.hosts[i], self.hosts[i]);
+                if (new.commit_index == old.commit_index) {
+                    assert(new.log.subrange(0, old.commit_index as int) =~= old.log.subrange(0, old.commit_index as int));
+                    self.committed_prefix_is_monotonic(c, u, host_id, net_op, event, old.log, new.log, old.commit_index, old.current_term, new.current_term);
+                } else if (host::append_entries(lc, lu, lv, net_op)) {
+                    // A follower learns the commit index its leader had.
+                    let Message::AppendEntries { term, leader, prev_index, prev_term, entries, leader_commit } = net_op.recv.unwrap() else { assert(false); return; };
+                    append_entries_matches_leader(c, u, host_id, term, leader, prev_index, prev_term, entries, leader_commit);
+                    let learned = new.commit_index;
+                    let leader_log = u.leader_logs[term];
+                    assert(learned <= leader_commit && learned <= prev_index + entries.len() && learned > 0);
+                    let (t, n) = choose |t: nat, n: nat| #[trigger] u.committed(c, t, n) && t <= term && leader_commit <= n && has_prefix(leader_log, u.prefix(t, leader_commit));
+                    self.committed_is_monotonic(c, u, host_id, net_op, event, t, n);
+                    assert(new.log.subrange(0, learned as int) =~= self.prefix(t, learned)) by {
+                        assert forall |k: int| 0 <= k < learned implies new.log[k] == u.leader_logs[t][k] by {
+                            assert(new.log.subrange(0, (prev_index + entries.len()) as int)[k] == leader_log.subrange(0, (prev_index + entries.len()) as int)[k]);
+                            assert(leader_log.subrange(0, leader_commit as int)[k] == u.prefix(t, leader_commit)[k]);
+                        };
+                        assert(self.prefix(t, learned) == u.prefix(t, learned));
+                    };
+                    assert(self.committed(c, t, n) && t <= new.current_term && learned <= n);
+                } else {
+                    // A leader commits the next entry on the strength of an entry of its term that a
+                    // majority acknowledged.
+                    assert(host::advance_commit(lc, lu, lv, net_op) && i == host_id);
+                    let term = lu.current_term;
+                    let index = choose |index: nat|
+                        lu.commit_index < index <= lu.log.len() &&
+                        term_at(lu.log, index) == term &&
+                        #[trigger] host::is_majority(lc, host::replicated_on(lc, lu, index));
+                    let hosts = host::replicated_on(lc, lu, index);
+                    assert(self.leader_logs[term] == lu.log);
+                    assert forall |h: nat| #[trigger] hosts.contains(h) implies self.acked(h, term, index) by {
+                        if (h != host_id) {
+                            let matched = lu.match_index[h];
+                            self.acked_is_monotonic(c, u, host_id, net_op, event, h, term, matched);
+                            if (!(self.leaders.contains_key(term) && self.leaders[term] == h && matched <= self.leader_logs[term].len())) {
+                                let (leader, match_index) = choose |leader: nat, match_index: nat|
+                                    matched <= match_index &&
+                                    #[trigger] self.sent_messages.contains(Message::AppendEntriesReply { term, sender: h, leader, match_index });
+                                assert(self.sent_messages.contains(Message::AppendEntriesReply { term, sender: h, leader, match_index }));
+                            }
+                        }
+                    };
+                    assert(self.acked_by(term, index, hosts));
+                    assert(self.committed(c, term, index));
+                    assert(new.log.subrange(0, new.commit_index as int) == self.prefix(term, new.commit_index));
+                }
+            };
+        }
+    }
+
+    pub proof fn init_implies_inductive(c: &Constants, u: &Variables)
+    requires
+        init(c, u),
+    ensures
+        inductive(c, u),
+    {
+        assert forall |i: int| #![auto] 0 <= i < u.hosts.len() implies u.hosts[i].log.len() == 0 && u.hosts[i].commit_index == 0 && u.hosts[i].role is Follower by {
+            assert(host::init(&c.hosts[i], &u.hosts[i], i as nat, u.hosts.len()));
+        };
+    }
+
+    pub proof fn inductive_next(c: &Constants, u: &Variables, v: &Variables, event: Event)
+    requires
+        inductive(c, u),
+        next(c, u, v, event),
+    ensures
+        inductive(c, v),
+    {
+        let (host_id, net_op) = choose |host_id: int, net_op: NetworkOperation| #[trigger] host_step(c, u, v, host_id, net_op, event);
+        v.votes_are_unique_is_inductive(c, u, host_id, net_op, event);
+        v.candidates_match_their_requests_is_inductive(c, u, host_id, net_op, event);
+        v.leaders_won_elections_is_inductive(c, u, host_id, net_op, event);
+        v.logs_match_leader_logs_is_inductive(c, u, host_id, net_op, event);
+        v.messages_match_leader_logs_is_inductive(c, u, host_id, net_op, event);
+        v.acknowledgements_are_kept_is_inductive(c, u, host_id, net_op, event);
+        v.leaders_are_complete_is_inductive(c, u, host_id, net_op, event);
+        v.commits_are_safe_is_inductive(c, u, host_id, net_op, event);
+    }
+
+    pub open spec fn some_host_committed(u: &Variables, index: nat) -> bool {
+        exists |i: int| #![auto] 0 <= i < u.hosts.len() && index < u.hosts[i].commit_index
+    }
+
+    // Raft has no reconfiguration: every host votes, always.
+    pub open spec fn constants_abstraction(c: &Constants) -> HighConstants
+    recommends
+        c.well_formed()
+    {
+        HighConstants {
+            acceptors: all_hosts(c.num_hosts),
+            alpha: 0,
+            members: |value: Value| None::<Set<nat>>,
+        }
+    }
+
+    pub open spec fn variables_abstraction(c: &Constants, u: &Variables) -> HighVariables
+    recommends
+        u.well_formed(c)
+    {
+        HighVariables {
+            decided_value: Map::new(
+                |index: nat| some_host_committed(u, index),
+                |index: nat| {
+                    let host = choose |i: int| #![auto] 0 <= i < u.hosts.len() && index < u.hosts[i].commit_index;
+                    u.hosts[host].log[index as int].value
+                },
+            ),
+            submitted: Set::new(|value: Value| u.sent_messages.contains(Message::ClientRequest { value })),
+            proposed: Set::new(|value: Value| u.value_was_proposed(value)),
+            membership: Map::empty(),
+        }
+    }
+
+    // The abstraction's decided value of `index` is any committing host's entry.
+    pub proof fn decided_value_is_any_committed_entry(c: &Constants, u: &Variables, i: int, index: nat)
+    requires
+        inductive(c, u),
+        0 <= i < u.hosts.len(),
+        index < u.hosts[i].commit_index,
+    ensures
+        variables_abstraction(c, u).decided_value.contains_key(index),
+        variables_abstraction(c, u).decided_value[index] == u.hosts[i].log[index as int].value,
+    {
+        let host = choose |j: int| #![auto] 0 <= j < u.hosts.len() && index < u.hosts[j].commit_index;
+        committed_entries_agree(c, u, host, i, index as int);
+    }
+
+    // A step that changes no commit index beyond what some host had committed, and no committed
+    // entry, leaves `decided_value` alone.
+    pub proof fn decided_values_are_unchanged(c: &Constants, u: &Variables, v: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+    requires
+        inductive(c, u),
+        inductive(c, v),
+        host_step(c, u, v, host_id, net_op, event),
+        forall |index: nat| some_host_committed(v, index) ==> some_host_committed(u, index),
+    ensures
+        variables_abstraction(c, v).decided_value == variables_abstraction(c, u).decided_value,
+    {
+        host_step_facts(c, u, v, host_id, net_op, event);
+        v.committed_entries_are_stable(c, u, host_id, net_op, event);
+        let (old, new) = (variables_abstraction(c, u).decided_value, variables_abstraction(c, v).decided_value);
+        assert forall |index: nat| #[trigger] new.contains_key(index) == old.contains_key(index) by {
+            if (old.contains_key(index)) {
+                let i = choose |i: int| #![auto] 0 <= i < u.hosts.len() && index < u.hosts[i].commit_index;
+                assert(index < v.hosts[i].commit_index);
+            }
+        };
+        assert forall |index: nat| #[trigger] old.contains_key(index) implies new[index] == old[index] by {
+            let i = choose |i: int| #![auto] 0 <= i < u.hosts.len() && index < u.hosts[i].commit_index;
+            decided_value_is_any_committed_entry(c, u, i, index);
+            decided_value_is_any_committed_entry(c, v, i, index);
+            assert(v.hosts[i].log[index as int] == u.hosts[i].log[index as int]);
+        };
+        assert(new =~= old);
+    }
+
+    // Only `propose` adds a value to the history of leader logs, and nothing is ever taken out.
+    pub proof fn proposed_values_follow_host_step(c: &Constants, u: &Variables, v: &Variables, host_id: int, net_op: NetworkOperation, event: Event)
+    requires
+        inductive(c, u),
+        host_step(c, u, v, host_id, net_op, event),
+    ensures
+        forall |value: Value| u.value_was_proposed(value) ==> #[trigger] v.value_was_proposed(value),
+        forall |value: Value| #[trigger] v.value_was_proposed(value) && !u.value_was_proposed(value) ==>
+            host::propose(&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id], net_op, value),
+    {
+        let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id]);
+        leader_history_facts(c, u, v, host_id, net_op, event);
+        assert forall |value: Value| u.value_was_proposed(value) implies #[trigger] v.value_was_proposed(value) by {
+            let (term, i) = choose |term: nat, i: int| #![auto] u.leader_logs.contains_key(term) && 0 <= i < u.leader_logs[term].len() && u.leader_logs[term][i].value == value;
+            assert(v.leader_logs[term][i] == u.leader_logs[term][i]) by {
+                assert(v.leader_logs[term].subrange(0, u.leader_logs[term].len() as int)[i] == v.leader_logs[term][i]);
+            };
+        };
+        assert forall |value: Value| #[trigger] v.value_was_proposed(value) && !u.value_was_proposed(value) implies
+            host::propose(lc, lu, lv, net_op, value)
+        by {
+            let (term, i) = choose |term: nat, i: int| #![auto] v.leader_logs.contains_key(term) && 0 <= i < v.leader_logs[term].len() && v.leader_logs[term][i].value == value;
+            if (!u.leader_logs.contains_key(term)) {
+                // A new leader's log is made of entries leaders already had.
+                let s = lu.log[i].term;
+                assert(lu.log.subrange(0, i + 1) == u.leader_logs[s].subrange(0, i + 1));
+                assert(lu.log[i] == u.leader_logs[s][i]);
+                assert(false);
+            } else if (i < u.leader_logs[term].len()) {
+                assert(v.leader_logs[term][i] == u.leader_logs[term][i]) by {
+                    assert(v.leader_logs[term].subrange(0, u.leader_logs[term].len() as int)[i] == v.leader_logs[term][i]);
+                };
+                assert(false);
+            } else {
+                let appended = choose |appended: Value| #[trigger] host::propose(lc, lu, lv, net_op, appended);
+                assert(lv.log.last().value == appended);
+            }
+        };
+    }
+
+    // Corresponds to `init(c, u) ==> inductive(c, u)`
+    proof fn refinement_init(c: &Constants, u: &Variables)
+    requires
+        init(c, u),
+    ensures
+        inductive(c, u),
+        high_init(&constants_abstraction(c), &variables_abstraction(c, u)),
+    {
+        init_implies_inductive(c, u);
+        assert forall |i: int| #![auto] 0 <= i < u.hosts.len() implies u.hosts[i].commit_index == 0 by {
+            assert(host::init(&c.hosts[i], &u.hosts[i], i as nat, u.hosts.len()));
+        };
+        assert(variables_abstraction(c, u).decided_value =~= Map::empty());
+        assert(variables_abstraction(c, u).submitted =~= Set::empty());
+        assert(variables_abstraction(c, u).proposed =~= Set::empty());
+    }
+
+    // Corresponds to `inductive(c, u) && next(c, u, v) ==> inductive(c, v)`
+    proof fn refinement_next(c: &Constants, u: &Variables, v: &Variables, event: Event)
+    requires
+        inductive(c, u),
+        next(c, u, v, event),
+    ensures
+        inductive(c, v),
+        high_next(&constants_abstraction(c), &variables_abstraction(c, u), &variables_abstraction(c, v), event),
+    {
+        inductive_next(c, u, v, event);
+        let (host_id, net_op) = choose |host_id: int, net_op: NetworkOperation| #[trigger] host_step(c, u, v, host_id, net_op, event);
+        let (lc, lu, lv) = (&c.hosts[host_id], &u.hosts[host_id], &v.hosts[host_id]);
+        let (hc, hu, hv) = (constants_abstraction(c), variables_abstraction(c, u), variables_abstraction(c, v));
+        host_step_facts(c, u, v, host_id, net_op, event);
+        leader_history_facts(c, u, v, host_id, net_op, event);
+        proposed_values_follow_host_step(c, u, v, host_id, net_op, event);
+        v.committed_entries_are_stable(c, u, host_id, net_op, event);
+
+        match event {
+            Event::Decide { key, value } => {
+                // The leader commits its next entry, which it holds as the recorded leader of its term.
+                assert(host::advance_commit(lc, lu, lv, net_op));
+                let term = lu.current_term;
+                assert(u.leader_logs[term] == lu.log);
+                let s = lu.log[key as int].term;
+                assert(lu.log.subrange(0, key + 1) == u.leader_logs[s].subrange(0, key + 1));
+                assert(lu.log[key as int] == u.leader_logs[s][key as int]);
+                assert(hu.submitted.contains(value));
+                assert(u.value_was_proposed(value));
+                assert(hu.proposed.contains(value));
+
+                decided_value_is_any_committed_entry(c, v, host_id, key);
+                if (hu.decided_value.contains_key(key)) {
+                    let i = choose |i: int| #![auto] 0 <= i < u.hosts.len() && key < u.hosts[i].commit_index;
+                    decided_value_is_any_committed_entry(c, u, i, key);
+                    decided_value_is_any_committed_entry(c, v, i, key);
+                }
+                assert(hv.decided_value =~= hu.decided_value.insert(key, value)) by {
+                    assert forall |index: nat| #[trigger] hv.decided_value.contains_key(index) == (hu.decided_value.contains_key(index) || index == key) by {
+                        if (hv.decided_value.contains_key(index) && index != key) {
+                            let i = choose |i: int| #![auto] 0 <= i < v.hosts.len() && index < v.hosts[i].commit_index;
+                            assert(index < u.hosts[i].commit_index);
+                        }
+                        if (hu.decided_value.contains_key(index)) {
+                            let i = choose |i: int| #![auto] 0 <= i < u.hosts.len() && index < u.hosts[i].commit_index;
+                            assert(index < v.hosts[i].commit_index);
+                        }
+                    };
+                    assert forall |index: nat| #[trigger] hu.decided_value.contains_key(index) && index != key implies hv.decided_value[index] == hu.decided_value[index] by {
+                        let i = choose |i: int| #![auto] 0 <= i < u.hosts.len() && index < u.hosts[i].commit_index;
+                        decided_value_is_any_committed_entry(c, u, i, index);
+                        decided_value_is_any_committed_entry(c, v, i, index);
+                    };
+                };
+                assert(hv.submitted =~= hu.submitted);
+                assert(hv.proposed =~= hu.proposed);
+                assert(reconfigure(&hc, hu.membership, key, value) == hu.membership);
+            },
+            Event::Propose { key, value } => {
+                let received = net_op.recv.unwrap();
+                assert(received == Message::ClientRequest { value });
+                assert(hu.submitted.contains(value));
+                assert(lv.log.subrange(0, lu.commit_index as int) =~= lu.log.subrange(0, lu.commit_index as int));
+                decided_values_are_unchanged(c, u, v, host_id, net_op, event);
+                assert(hv.submitted =~= hu.submitted);
+                assert(v.value_was_proposed(value)) by {
+                    let term = lu.current_term;
+                    assert(v.leader_logs[term] == lv.log);
+                    assert(lv.log[lu.log.len() as int].value == value);
+                };
+                assert(hv.proposed =~= hu.proposed.insert(value));
+            },
+            Event::Submit { value } => {
+                assert(lv == lu);
+                decided_values_are_unchanged(c, u, v, host_id, net_op, event);
+                assert(hv.submitted =~= hu.submitted.insert(value));
+                assert(hv.proposed =~= hu.proposed);
+            },
+            Event::ProposeBatch { .. } | Event::DecideBatch { .. } => {
+                assert(!host::step(lc, lu, lv, net_op, event));
+            },
+            Event::NoOp => {
+                // A follower only learns commit indices its leader has already reached.
+                assert forall |index: nat| some_host_committed(v, index) implies some_host_committed(u, index) by {
+                    let i = choose |i: int| #![auto] 0 <= i < v.hosts.len() && index < v.hosts[i].commit_index;
+                    if (index >= u.hosts[i].commit_index) {
+                        assert(i == host_id && host::append_entries(lc, lu, lv, net_op));
+                        let Message::AppendEntries { term, leader, prev_index, prev_term, entries, leader_commit } = net_op.recv.unwrap() else { assert(false); return; };
+                        assert(u.sent_messages.contains(Message::AppendEntries { term, leader, prev_index, prev_term, entries, leader_commit }));
+                        assert(index < u.hosts[leader as int].commit_index);
+                    }
+                };
+                decided_values_are_unchanged(c, u, v, host_id, net_op, event);
+                assert(hv.submitted =~= hu.submitted) by {
+                    assert forall |value: Value| v.sent_messages.contains(Message::ClientRequest { value }) implies u.sent_messages.contains(Message::ClientRequest { value }) by {};
+                };
+                assert(hv.proposed =~= hu.proposed);
+            },
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use super::{
+    high_level::{decide_entries, decide_entries_extends, init as high_init, next as high_next, Constants as HighConstants, Variables as HighVariables},
+    Event, Value,
+};
+use vstd::{prelude::*, set_lib::*};
+
+verus! {
+    pub trait StateMachine {
+        type State;
+
+        spec fn initial_state() -> Self::State;
+
+        spec fn apply(state: Self::State, command: Value) -> Self::State;
+    }
+
+    // `applied` holds the `(client_id, seq)` of every request executed so far. A command whose request
+    // is already in it is skipped, so a retried request is executed once even if the log holds it twice.
+    // A no-op only fills its slot and is skipped as well.
+    pub open spec fn apply_commands<SM: StateMachine>(state: SM::State, applied: Set<(nat, nat)>, commands: Seq<Value>) -> (SM::State, Set<(nat, nat)>)
+    decreases
+        commands.len()
+    {
+        if (commands.len() == 0) {
+            (state, applied)
+        } else if (commands[0].is_no_op() || applied.contains(commands[0].request())) {
+            apply_commands::<SM>(state, applied, commands.drop_first())
+        } else {
+            apply_commands::<SM>(SM::apply(state, commands[0]), applied.insert(commands[0].request()), commands.drop_first())
+        }
+    }
+
+    pub struct Constants {}
+
+    // `decided` holds every slot decided so far, including those past a gap that cannot be applied yet.
+    pub struct Variables {
+        pub log: Seq<Value>,
+        pub decided: Map<nat, Value>,
+    }
+
+    impl Variables {
+        pub open spec fn state<SM: StateMachine>(&self) -> SM::State {
+            apply_commands::<SM>(SM::initial_state(), Set::empty(), self.log).0
+        }
+
+        pub open spec fn applied<SM: StateMachine>(&self) -> Set<(nat, nat)> {
+            apply_commands::<SM>(SM::initial_state(), Set::empty(), self.log).1
+        }
+    }
+
+    pub open spec fn init(c: &Constants, u: &Variables) -> bool {
+        &&& u.log.len() == 0
+        &&& u.decided.is_empty()
+    }
+
+    // Commands are only ever appended, so a replica that has applied `u.log` can catch up by applying
+    // exactly `v.log[u.log.len()..]` in order. Every appended entry is the value decided at its slot,
+    // and the log grows as far as the decided slots run without a gap: deciding the next slot appends
+    // it and whatever was already decided behind it, deciding a later slot appends nothing.
+    pub open spec fn append(c: &Constants, u: &Variables, v: &Variables, key: nat, value: Value) -> bool {
+        &&& u.decided.contains_key(key) ==> u.decided[key] == value
+        &&& v.decided == u.decided.insert(key, value)
+        &&& extends_log(u, v)
+    }
+
+    // A batch of decisions appends, in one step, whatever its slots complete.
+    pub open spec fn append_batch(c: &Constants, u: &Variables, v: &Variables, entries: Seq<(nat, Value)>) -> bool {
+        &&& forall |j: int| #![trigger entries[j]]
+                0 <= j < entries.len() && u.decided.contains_key(entries[j].0) ==>
+                u.decided[entries[j].0] == entries[j].1
+        &&& v.decided == decide_entries(u.decided, entries, entries.len())
+        &&& extends_log(u, v)
+    }
+
+    pub open spec fn extends_log(u: &Variables, v: &Variables) -> bool {
+        &&& u.log.is_prefix_of(v.log)
+        &&& forall |slot: int| #![auto] u.log.len() <= slot < v.log.len() ==> v.decided.contains_key(slot as nat) && v.log[slot] == v.decided[slot as nat]
+        &&& !v.decided.contains_key(v.log.len())
+    }
+
+    pub open spec fn next(c: &Constants, u: &Variables, v: &Variables, event: Event) -> bool {
+        match event {
+            Event::Decide { key, value } => { append(c, u, v, key, value) },
+            Event::DecideBatch { entries } => { append_batch(c, u, v, entries) },
+            Event::Propose { .. } | Event::ProposeBatch { .. } | Event::Submit { .. } | Event::NoOp => { v == u },
+        }
+    }
+
+    pub proof fn apply_commands_distributes_over_append<SM: StateMachine>(state: SM::State, applied: Set<(nat, nat)>, first: Seq<Value>, second: Seq<Value>)
+    ensures
+        apply_commands::<SM>(state, applied, first + second) ==
+            apply_commands::<SM>(apply_commands::<SM>(state, applied, first).0, apply_commands::<SM>(state, applied, first).1, second),
+    decreases
+        first.len()
+    {
+        if (first.len() == 0) {
+            assert(first + second =~= second);
+        } else {
+            assert((first + second).drop_first() =~= first.drop_first() + second);
+            assert((first + second)[0] == first[0]);
+            if (first[0].is_no_op() || applied.contains(first[0].request())) {
+                apply_commands_distributes_over_append::<SM>(state, applied, first.drop_first(), second);
+            } else {
+                apply_commands_distributes_over_append::<SM>(SM::apply(state, first[0]), applied.insert(first[0].request()), first.drop_first(), second);
+            }
+        }
+    }
+
+    // Clients of a replica that applied `u.log` reach the state of `v` by applying only the new suffix,
+    // skipping the requests they have already executed.
+    pub proof fn appended_state_is_old_state_with_new_commands_applied<SM: StateMachine>(c: &Constants, u: &Variables, v: &Variables, key: nat, value: Value)
+    requires
+        append(c, u, v, key, value),
+    ensures
+        v.state::<SM>() == apply_commands::<SM>(u.state::<SM>(), u.applied::<SM>(), v.log.subrange(u.log.len() as int, v.log.len() as int)).0,
+    {
+        assert(v.log =~= u.log + v.log.subrange(u.log.len() as int, v.log.len() as int));
+        apply_commands_distributes_over_append::<SM>(SM::initial_state(), Set::empty(), u.log, v.log.subrange(u.log.len() as int, v.log.len() as int));
+    }
+
+    // Longest run of decided slots `0, 1, 2, ...` among the slots below `bound`.
+    pub open spec fn contiguous_prefix(decided_value: Map<nat, Value>, bound: nat) -> Seq<Value>
+    decreases
+        bound
+    {
+        if (bound == 0) {
+            Seq::empty()
+        } else {
+            let slot = (bound - 1) as nat;
+            let prefix = contiguous_prefix(decided_value, slot);
+
+            if (prefix.len() == slot && decided_value.contains_key(slot)) {
+                prefix.push(decided_value[slot])
+            } else {
+                prefix
+            }
+        }
+    }
+
+    pub open spec fn log_of(decided_value: Map<nat, Value>) -> Seq<Value> {
+        contiguous_prefix(decided_value, decided_value.dom().len())
+    }
+
+    pub open spec fn constants_abstraction(hc: &HighConstants) -> Constants {
+        Constants { }
+    }
+
+    pub open spec fn variables_abstraction(hc: &HighConstants, hv: &HighVariables) -> Variables {
+        Variables { log: log_of(hv.decided_value), decided: hv.decided_value }
+    }
+
+    pub proof fn contiguous_prefix_properties(decided_value: Map<nat, Value>, bound: nat)
+    ensures
+        ({
+            let prefix = contiguous_prefix(decided_value, bound);
+
+            &&& prefix.len() <= bound
+            &&& forall |slot: int| #![auto] 0 <= slot < prefix.len() ==> decided_value.contains_key(slot as nat) && prefix[slot] == decided_value[slot as nat]
+            &&& prefix.len() < bound ==> !decided_value.contains_key(prefix.len())
+        }),
+    decreases
+        bound
+    {
+        if (bound > 0) {
+            let slot = (bound - 1) as nat;
+            contiguous_prefix_properties(decided_value, slot);
+        }
+    }
+
+    pub proof fn contiguous_prefix_of_submap_is_prefix(small_map: Map<nat, Value>, big_map: Map<nat, Value>, small_bound: nat, big_bound: nat)
+    requires
+        small_map.submap_of(big_map),
+        small_bound <= big_bound,
+    ensures
+        contiguous_prefix(small_map, small_bound).is_prefix_of(contiguous_prefix(big_map, big_bound)),
+    {
+        let (small_prefix, big_prefix) = (contiguous_prefix(small_map, small_bound), contiguous_prefix(big_map, big_bound));
+        contiguous_prefix_properties(small_map, small_bound);
+        contiguous_prefix_properties(big_map, big_bound);
+
+        if (big_prefix.len() < small_prefix.len()) {
+            assert(small_map.contains_key(big_prefix.len()));
+            assert(big_map.contains_key(big_prefix.len()));
+            assert(false);
+        }
+
+        assert forall |slot: int| 0 <= slot < small_prefix.len() implies #[trigger] small_prefix[slot] == big_prefix[slot] by {
+            assert(small_map.contains_key(slot as nat) && big_map[slot as nat] == small_map[slot as nat]);
+        };
+    }
+
+    // Slots `0..n` are all decided, so there are at least `n` decided slots.
+    pub proof fn decided_run_is_at_most_domain_len(decided_value: Map<nat, Value>, n: nat)
+    requires
+        decided_value.dom().finite(),
+        forall |slot: nat| slot < n ==> #[trigger] decided_value.contains_key(slot),
+    ensures
+        n <= decided_value.dom().len(),
+    decreases
+        n
+    {
+        if (n > 0) {
+            let slot = (n - 1) as nat;
+            let rest = decided_value.remove(slot);
+            assert(rest.dom() =~= decided_value.dom().remove(slot));
+            assert forall |other: nat| other < slot implies #[trigger] rest.contains_key(other) by {
+                assert(decided_value.contains_key(other));
+            };
+            decided_run_is_at_most_domain_len(rest, slot);
+        }
+    }
+
+    // The log of a finite decided map holds the decided value at every one of its slots, and the slot
+    // just past it is undecided.
+    pub proof fn log_of_properties(decided_value: Map<nat, Value>)
+    requires
+        decided_value.dom().finite(),
+    ensures
+        ({
+            let log = log_of(decided_value);
+
+            &&& forall |slot: int| #![auto] 0 <= slot < log.len() ==> decided_value.contains_key(slot as nat) && log[slot] == decided_value[slot as nat]
+            &&& !decided_value.contains_key(log.len())
+        }),
+    {
+        let bound = decided_value.dom().len();
+        let log = log_of(decided_value);
+        contiguous_prefix_properties(decided_value, bound);
+
+        if (log.len() == bound && decided_value.contains_key(log.len())) {
+            assert forall |slot: nat| slot < bound + 1 implies #[trigger] decided_value.contains_key(slot) by {
+                if (slot < bound) {
+                    assert(0 <= slot as int && (slot as int) < log.len());
+                }
+            };
+            decided_run_is_at_most_domain_len(decided_value, bound + 1);
+        }
+    }
+
+    // Corresponds to `init(hc, hu) ==> init(abstraction(hc), abstraction(hu))`
+    pub proof fn refinement_init(hc: &HighConstants, hu: &HighVariables)
+    requires
+        high_init(hc, hu),
+    ensures
+        hu.decided_value.dom().finite(),
+        init(&constants_abstraction(hc), &variables_abstraction(hc, hu)),
+    {
+        assert(hu.decided_value.dom() =~= Set::empty());
+    }
+
+    // Corresponds to `next(hc, hu, hv) ==> next(abstraction(hc), abstraction(hu), abstraction(hv))`
+    pub proof fn refinement_next(hc: &HighConstants, hu: &HighVariables, hv: &HighVariables, event: Event)
+    requires
+        hu.decided_value.dom().finite(),
+        high_next(hc, hu, hv, event),
+    ensures
+        hv.decided_value.dom().finite(),
+        next(&constants_abstraction(hc), &variables_abstraction(hc, hu), &variables_abstraction(hc, hv), event),
+    {
+        match event {
+            Event::Decide { key, value } => {
+                assert(hv.decided_value.dom() =~= hu.decided_value.dom().insert(key));
+                assert(hu.decided_value.submap_of(hv.decided_value));
+                assert(hu.decided_value.dom().len() <= hv.decided_value.dom().len()) by {
+                    lemma_len_subset(hu.decided_value.dom(), hv.decided_value.dom());
+                };
+                contiguous_prefix_of_submap_is_prefix(hu.decided_value, hv.decided_value, hu.decided_value.dom().len(), hv.decided_value.dom().len());
+                log_of_properties(hv.decided_value);
+            },
+            Event::DecideBatch { entries } => {
+                decide_entries_extends(hu.decided_value, entries, entries.len());
+                assert(hu.decided_value.dom().subset_of(hv.decided_value.dom()));
+                assert(hu.decided_value.dom().len() <= hv.decided_value.dom().len()) by {
+                    lemma_len_subset(hu.decided_value.dom(), hv.decided_value.dom());
+                };
+                contiguous_prefix_of_submap_is_prefix(hu.decided_value, hv.decided_value, hu.decided_value.dom().len(), hv.decided_value.dom().len());
+                log_of_properties(hv.decided_value);
+            },
+            Event::Propose { .. } | Event::ProposeBatch { .. } | Event::Submit { .. } | Event::NoOp => { },
+        }
+    }
+}
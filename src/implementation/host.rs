@@ -0,0 +1,2733 @@
+use super::{accepted_view, entries_view, marshal::MAX_BATCH_ENTRIES, Ballot, Batch, BatchOperation, Message, NetworkOperation, Value};
+use crate::distributed_system::{low_level::{self, batch, fast, host, quorum, reconfig}, Value as SpecValue};
+use std::collections::{HashMap, HashSet, VecDeque};
+use vstd::{prelude::*, relations::injective, set_lib::*};
+
+verus! {
+    broadcast use vstd::std_specs::hash::group_hash_axioms;
+
+    pub struct Constants {
+        pub id: u64,
+        pub num_hosts: u64,
+        pub num_failures: u64,
+        pub phase1_size: u64,
+        pub phase2_size: u64,
+        pub fast_size: u64,
+        pub proposers: HashSet<u64>,
+        pub learners: HashSet<u64>,
+        pub owners: Vec<u64>,
+        pub fast_clients: HashSet<u64>,
+    }
+
+    // Every ballot a host keeps in `promised`, `proposed_value` or `accepted` is one of its own
+    // (`pid == c.id`), so the executable maps are keyed by the ballot number alone. `learned` is keyed
+    // by ballot number and then pid, and holds one value per classic ballot: Accepted messages for a
+    // classic ballot all carry the value its owner proposed. The fast ballot has no owner and its
+    // Accepted messages may carry different values, so `fast_learned` counts them per value instead.
+    // The executable hosts never reconfigure, so the ghost `configuration` is always the initial one.
+    pub struct Instance {
+        pub owner: u64,
+        pub configuration: Ghost<fast::FastQuorumSystem>,
+        pub current_ballot: Ballot,
+        pub max_seen_ballot: Ballot,
+        pub accept_ballot: Option<Ballot>,
+        pub accept_value: Option<Value>,
+        pub decide_value: Option<Value>,
+        pub volatile: Volatile,
+    }
+
+    // The in-memory half of an instance, which `recover` replaces with `Volatile::new()`.
+    pub struct Volatile {
+        pub promised: HashMap<u64, HashMap<u64, Option<(Ballot, Value)>>>,
+        pub proposed_value: HashMap<u64, Value>,
+        pub accepted: HashMap<u64, HashSet<u64>>,
+        pub learned: HashMap<u64, HashMap<u64, (Value, HashSet<u64>)>>,
+        pub fast_learned: Vec<(Value, HashSet<u64>)>,
+    }
+
+    impl Volatile {
+        pub fn new() -> (volatile: Volatile)
+        ensures
+            volatile.promised@ == Map::<u64, HashMap<u64, Option<(Ballot, Value)>>>::empty(),
+            volatile.proposed_value@ == Map::<u64, Value>::empty(),
+            volatile.accepted@ == Map::<u64, HashSet<u64>>::empty(),
+            volatile.learned@ == Map::<u64, HashMap<u64, (Value, HashSet<u64>)>>::empty(),
+            volatile.fast_learned@ == Seq::<(Value, HashSet<u64>)>::empty(),
+        {
+            Volatile { promised: HashMap::new(), proposed_value: HashMap::new(), accepted: HashMap::new(), learned: HashMap::new(), fast_learned: Vec::new() }
+        }
+    }
+
+    // `queued` indexes the `(client_id, seq)` of the requests in `pending` so that `receive_request`
+    // can drop a retransmission without scanning the queue. Both request sets are keyed by client and
+    // then by sequence number.
+    //
+    // `keys` lists the domain of `instances` in creation order, so that `recover` and
+    // `send_prepare_from` can visit every instance without iterating the map.
+    pub struct Variables {
+        pub instances: HashMap<u64, Instance>,
+        pub keys: Vec<u64>,
+        pub pending: VecDeque<Value>,
+        pub queued: HashMap<u64, HashSet<u64>>,
+        pub assigned: HashMap<u64, HashSet<u64>>,
+        pub up: bool,
+        pub multi: MultiPaxos,
+    }
+
+    // `leading` only ever holds a ballot this host owns (`Variables::inv`), so `join_prepare_from` can
+    // key the instance maps by its number like `send_prepare` does.
+    pub struct MultiPaxos {
+        pub promise_floor: Ballot,
+        pub floor_from: u64,
+        pub leading: Option<(u64, Ballot)>,
+    }
+
+    impl View for MultiPaxos {
+        type V = host::MultiPaxos;
+
+        open spec fn view(&self) -> host::MultiPaxos {
+            host::MultiPaxos {
+                promise_floor: self.promise_floor@,
+                floor_from: self.floor_from as nat,
+                leading: match self.leading {
+                    Some((from, ballot)) => Some((from as nat, ballot@)),
+                    None => None,
+                },
+            }
+        }
+    }
+
+    // A fast quorum is any `fast_size` of the hosts, as the classic quorums are any `phase1_size` or
+    // `phase2_size` of them. No value is a reconfiguration command, and `alpha` is above every `u64`
+    // key, so every instance runs in these quorums and needs no decision to start.
+    impl View for Constants {
+        type V = host::Constants;
+
+        open spec fn view(&self) -> host::Constants {
+            host::Constants {
+                id: self.id as nat,
+                num_hosts: self.num_hosts as nat,
+                num_failures: self.num_failures as nat,
+                quorums: quorum::quorums_by_size(quorum::all_hosts(self.num_hosts as nat), self.phase1_size as nat, self.phase2_size as nat),
+                fast: |hosts: Set<nat>| hosts.finite() && hosts.intersect(quorum::all_hosts(self.num_hosts as nat)).len() >= self.fast_size,
+                proposers: sender_set_view(self.proposers@),
+                learners: sender_set_view(self.learners@),
+                request_owner: |client_id: nat| self.owners@[(client_id % self.owners@.len()) as int] as nat,
+                fast_clients: sender_set_view(self.fast_clients@),
+                reconfig: reconfig::Reconfiguration { alpha: 0x1_0000_0000_0000_0000, members: |value: SpecValue| None },
+            }
+        }
+    }
+
+    impl Constants {
+        pub open spec fn well_formed(&self) -> bool {
+            &&& self.owners@.len() > 0
+            &&& self@.well_formed()
+        }
+
+        pub fn is_proposer(&self) -> (result: bool)
+        ensures
+            result == self@.is_proposer(),
+        {
+            self.proposers.contains(&self.id)
+        }
+
+        pub fn is_learner(&self) -> (result: bool)
+        ensures
+            result == self@.is_learner(),
+        {
+            self.learners.contains(&self.id)
+        }
+
+        pub fn is_fast_request(&self, value: &Value) -> (result: bool)
+        ensures
+            result == self@.is_fast_request(value@),
+        {
+            self.fast_clients.contains(&value.client_id)
+        }
+
+        pub fn owns_request(&self, client_id: u64) -> (result: bool)
+        requires
+            self.well_formed(),
+        ensures
+            result == self@.owns_request(client_id as nat),
+        {
+            self.owners[(client_id % (self.owners.len() as u64)) as usize] == self.id
+        }
+    }
+
+    pub open spec fn optional_value_view(value: Option<Value>) -> Option<SpecValue> {
+        match value {
+            Some(value) => Some(value@),
+            None => None,
+        }
+    }
+
+    pub open spec fn optional_ballot_view(ballot: Option<Ballot>) -> Option<host::Ballot> {
+        match ballot {
+            Some(ballot) => Some(ballot@),
+            None => None,
+        }
+    }
+
+    pub open spec fn is_owned_ballot(ballot: host::Ballot, owner: u64) -> bool {
+        &&& ballot.pid == owner as nat
+        &&& ballot.num <= u64::MAX
+    }
+
+    pub open spec fn sender_map_view(accepted_map: Map<u64, Option<(Ballot, Value)>>) -> Map<nat, Option<(host::Ballot, SpecValue)>> {
+        Map::new(
+            |sender: nat| sender <= u64::MAX && accepted_map.contains_key(sender as u64),
+            |sender: nat| accepted_view(accepted_map[sender as u64]),
+        )
+    }
+
+    pub open spec fn sender_set_view(senders: Set<u64>) -> Set<nat> {
+        Set::new(|sender: nat| sender <= u64::MAX && senders.contains(sender as u64))
+    }
+
+    pub open spec fn request_set_view(ids: Map<u64, HashSet<u64>>) -> Set<(nat, nat)> {
+        Set::new(|id: (nat, nat)| id.0 <= u64::MAX && id.1 <= u64::MAX && ids.contains_key(id.0 as u64) && ids[id.0 as u64]@.contains(id.1 as u64))
+    }
+
+    impl Instance {
+        pub open spec fn promised_view(&self) -> Map<host::Ballot, Map<nat, Option<(host::Ballot, SpecValue)>>> {
+            Map::new(
+                |ballot: host::Ballot| is_owned_ballot(ballot, self.owner) && self.volatile.promised@.contains_key(ballot.num as u64),
+                |ballot: host::Ballot| sender_map_view(self.volatile.promised@[ballot.num as u64]@),
+            )
+        }
+
+        pub open spec fn proposed_value_view(&self) -> Map<host::Ballot, SpecValue> {
+            Map::new(
+                |ballot: host::Ballot| is_owned_ballot(ballot, self.owner) && self.volatile.proposed_value@.contains_key(ballot.num as u64),
+                |ballot: host::Ballot| self.volatile.proposed_value@[ballot.num as u64]@,
+            )
+        }
+
+        pub open spec fn accepted_view(&self) -> Map<host::Ballot, Set<nat>> {
+            Map::new(
+                |ballot: host::Ballot| is_owned_ballot(ballot, self.owner) && self.volatile.accepted@.contains_key(ballot.num as u64),
+                |ballot: host::Ballot| sender_set_view(self.volatile.accepted@[ballot.num as u64]@),
+            )
+        }
+
+        pub open spec fn fast_learned_index(&self, value: SpecValue) -> int {
+            choose |j: int| 0 <= j < self.volatile.fast_learned@.len() && #[trigger] self.volatile.fast_learned@[j].0@ == value
+        }
+
+        pub open spec fn learned_view(&self) -> Map<(host::Ballot, SpecValue), Set<nat>> {
+            Map::new(
+                |entry: (host::Ballot, SpecValue)| {
+                    let (ballot, value) = entry;
+
+                    if (host::is_fast_ballot(ballot)) {
+                        &&& ballot == host::fast_ballot()
+                        &&& exists |j: int| 0 <= j < self.volatile.fast_learned@.len() && #[trigger] self.volatile.fast_learned@[j].0@ == value
+                    } else {
+                        &&& ballot.num <= u64::MAX
+                        &&& ballot.pid <= u64::MAX
+                        &&& self.volatile.learned@.contains_key(ballot.num as u64)
+                        &&& self.volatile.learned@[ballot.num as u64]@.contains_key(ballot.pid as u64)
+                        &&& self.volatile.learned@[ballot.num as u64]@[ballot.pid as u64].0@ == value
+                    }
+                },
+                |entry: (host::Ballot, SpecValue)| if (host::is_fast_ballot(entry.0)) {
+                    sender_set_view(self.volatile.fast_learned@[self.fast_learned_index(entry.1)].1@)
+                } else {
+                    sender_set_view(self.volatile.learned@[entry.0.num as u64]@[entry.0.pid as u64].1@)
+                },
+            )
+        }
+
+        pub open spec fn inv(&self, c: &Constants) -> bool {
+            &&& self.owner == c.id
+            &&& self.configuration@ == c@.fast_quorums()
+            &&& self.accept_ballot.is_some() == self.accept_value.is_some()
+            &&& forall |ballot_num: u64| #[trigger] self.volatile.promised@.contains_key(ballot_num) ==> ballot_num <= self.max_seen_ballot.num
+            &&& forall |ballot_num: u64| #[trigger] self.volatile.proposed_value@.contains_key(ballot_num) ==> ballot_num <= self.max_seen_ballot.num
+            &&& forall |ballot_num: u64| #[trigger] self.volatile.accepted@.contains_key(ballot_num) ==> ballot_num <= self.max_seen_ballot.num
+            &&& forall |ballot_num: u64, sender: u64| #![trigger self.volatile.promised@[ballot_num]@.contains_key(sender)]
+                    self.volatile.promised@.contains_key(ballot_num) &&
+                    self.volatile.promised@[ballot_num]@.contains_key(sender) ==>
+                    sender < c.num_hosts
+            &&& forall |ballot_num: u64, sender: u64| #![trigger self.volatile.accepted@[ballot_num]@.contains(sender)]
+                    self.volatile.accepted@.contains_key(ballot_num) &&
+                    self.volatile.accepted@[ballot_num]@.contains(sender) ==>
+                    sender < c.num_hosts
+            &&& forall |ballot_num: u64, pid: u64, sender: u64| #![trigger self.volatile.learned@[ballot_num]@[pid].1@.contains(sender)]
+                    self.volatile.learned@.contains_key(ballot_num) &&
+                    self.volatile.learned@[ballot_num]@.contains_key(pid) &&
+                    self.volatile.learned@[ballot_num]@[pid].1@.contains(sender) ==>
+                    sender < c.num_hosts
+            &&& forall |j1: int, j2: int| #![trigger self.volatile.fast_learned@[j1], self.volatile.fast_learned@[j2]]
+                    0 <= j1 < self.volatile.fast_learned@.len() &&
+                    0 <= j2 < self.volatile.fast_learned@.len() &&
+                    j1 != j2 ==>
+                    self.volatile.fast_learned@[j1].0@ != self.volatile.fast_learned@[j2].0@
+            &&& forall |j: int, sender: u64| #![trigger self.volatile.fast_learned@[j].1@.contains(sender)]
+                    0 <= j < self.volatile.fast_learned@.len() &&
+                    self.volatile.fast_learned@[j].1@.contains(sender) ==>
+                    sender < c.num_hosts
+        }
+
+        pub fn new(c: &Constants) -> (instance: Instance)
+        ensures
+            instance.owner == c.id,
+            instance.configuration@ == c@.fast_quorums(),
+            instance.accept_ballot.is_none(),
+            instance.accept_value.is_none(),
+            instance.volatile.promised@ == Map::<u64, HashMap<u64, Option<(Ballot, Value)>>>::empty(),
+            instance@ == (host::Instance {
+                current_ballot: host::Ballot { num: 0, pid: 0 },
+                max_seen_ballot: host::Ballot { num: 0, pid: 0 },
+                accept_ballot: None,
+                accept_value: None,
+                decide_value: None,
+                configuration: c@.fast_quorums(),
+                volatile: host::Volatile::empty(),
+            }),
+        {
+            let instance = Instance {
+                owner: c.id,
+                configuration: Ghost(c@.fast_quorums()),
+                current_ballot: Ballot { num: 0, pid: 0 },
+                max_seen_ballot: Ballot { num: 0, pid: 0 },
+                accept_ballot: None,
+                accept_value: None,
+                decide_value: None,
+                volatile: Volatile::new(),
+            };
+
+            assert(instance.promised_view() =~= Map::empty());
+            assert(instance.proposed_value_view() =~= Map::empty());
+            assert(instance.accepted_view() =~= Map::empty());
+            assert(instance.learned_view() =~= Map::empty());
+
+            instance
+        }
+
+        // Drops the volatile half, leaving what `recover` reads back.
+        pub fn reset_volatile(&mut self)
+        ensures
+            self.owner == old(self).owner,
+            self.current_ballot == old(self).current_ballot,
+            self.max_seen_ballot == old(self).max_seen_ballot,
+            self.accept_ballot == old(self).accept_ballot,
+            self.accept_value == old(self).accept_value,
+            self.decide_value == old(self).decide_value,
+            self.volatile.promised@ == Map::<u64, HashMap<u64, Option<(Ballot, Value)>>>::empty(),
+            self.volatile.proposed_value@ == Map::<u64, Value>::empty(),
+            self.volatile.accepted@ == Map::<u64, HashSet<u64>>::empty(),
+            self.volatile.learned@ == Map::<u64, HashMap<u64, (Value, HashSet<u64>)>>::empty(),
+            self.volatile.fast_learned@ == Seq::<(Value, HashSet<u64>)>::empty(),
+            self@ == host::durable_part(old(self)@),
+        {
+            self.volatile = Volatile::new();
+
+            assert(self.promised_view() =~= Map::empty());
+            assert(self.proposed_value_view() =~= Map::empty());
+            assert(self.accepted_view() =~= Map::empty());
+            assert(self.learned_view() =~= Map::empty());
+        }
+    }
+
+    impl View for Instance {
+        type V = host::Instance;
+
+        open spec fn view(&self) -> host::Instance {
+            host::Instance {
+                current_ballot: self.current_ballot@,
+                max_seen_ballot: self.max_seen_ballot@,
+                accept_ballot: optional_ballot_view(self.accept_ballot),
+                accept_value: optional_value_view(self.accept_value),
+                decide_value: optional_value_view(self.decide_value),
+                configuration: self.configuration@,
+                volatile: host::Volatile {
+                    promised: self.promised_view(),
+                    proposed_value: self.proposed_value_view(),
+                    accepted: self.accepted_view(),
+                    learned: self.learned_view(),
+                },
+            }
+        }
+    }
+
+    pub open spec fn instances_view(instances: Map<u64, Instance>) -> Map<nat, host::Instance> {
+        Map::new(
+            |key: nat| key <= u64::MAX && instances.contains_key(key as u64),
+            |key: nat| instances[key as u64]@,
+        )
+    }
+
+    impl View for Variables {
+        type V = host::Variables;
+
+        open spec fn view(&self) -> host::Variables {
+            host::Variables {
+                instances: instances_view(self.instances@),
+                pending: self.pending@.map_values(|request: Value| request@),
+                assigned: request_set_view(self.assigned@),
+                up: self.up,
+                multi: self.multi@,
+            }
+        }
+    }
+
+    impl Variables {
+        pub open spec fn inv(&self, c: &Constants) -> bool {
+            &&& forall |key: u64| #[trigger] self.instances@.contains_key(key) ==> self.instances@[key].inv(c)
+            &&& forall |id: (nat, nat)| #[trigger] request_set_view(self.queued@).contains(id) <==> host::is_queued(self@.pending, id.0, id.1)
+            &&& forall |j1: int, j2: int| #![trigger self.pending@[j1], self.pending@[j2]]
+                    0 <= j1 < self.pending@.len() &&
+                    0 <= j2 < self.pending@.len() &&
+                    j1 != j2 ==>
+                    (self.pending@[j1].client_id, self.pending@[j1].seq) != (self.pending@[j2].client_id, self.pending@[j2].seq)
+            &&& self.multi.leading is Some ==> self.multi.leading.unwrap().1.pid == c.id
+            &&& self.keys@.no_duplicates()
+            &&& forall |key: u64| #[trigger] self.instances@.contains_key(key) <==> self.keys@.contains(key)
+        }
+
+        pub fn new(c: &Constants) -> (variables: Variables)
+        requires
+            c.well_formed(),
+        ensures
+            variables.inv(c),
+            host::init(&c@, &variables@, c@.id, c@.num_hosts),
+        {
+            let variables = Variables {
+                instances: HashMap::new(),
+                keys: Vec::new(),
+                pending: VecDeque::new(),
+                queued: HashMap::new(),
+                assigned: HashMap::new(),
+                up: true,
+                multi: MultiPaxos { promise_floor: Ballot { num: 0, pid: 0 }, floor_from: 0, leading: None },
+            };
+            assert(variables@.instances =~= Map::empty());
+            assert(variables@.pending =~= Seq::empty());
+            assert(variables@.assigned =~= Set::empty());
+            assert(request_set_view(variables.queued@) =~= Set::empty());
+            variables
+        }
+
+        pub fn crash(&mut self, c: &Constants) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            match net_op {
+                Some(net_op) => host::crash(&c@, &old(self)@, &self@, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            if (!self.up) {
+                return None;
+            }
+
+            self.up = false;
+
+            Some(NetworkOperation { send: None, recv: None })
+        }
+
+        // Reads back what an instance keeps on stable storage, one key at a time.
+        pub fn recover(&mut self, c: &Constants) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            match net_op {
+                Some(net_op) => host::recover(&c@, &old(self)@, &self@, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            if (self.up) {
+                return None;
+            }
+
+            let mut i: usize = 0;
+            while (i < self.keys.len())
+            invariant
+                0 <= i <= self.keys@.len(),
+                self.inv(c),
+                self.keys@ == old(self).keys@,
+                self.up == old(self).up,
+                self.pending@ == old(self).pending@,
+                self.queued@ == old(self).queued@,
+                self.assigned@ == old(self).assigned@,
+                self.multi == old(self).multi,
+                forall |k: u64| #[trigger] self.instances@.contains_key(k) <==> old(self).instances@.contains_key(k),
+                forall |k: u64| #![trigger self.instances@[k]]
+                    self.instances@.contains_key(k) ==>
+                    if (self.keys@.subrange(0, i as int).contains(k)) {
+                        self.instances@[k]@ == host::durable_part(old(self).instances@[k]@)
+                    } else {
+                        self.instances@[k] == old(self).instances@[k]
+                    },
+            decreases
+                self.keys@.len() - i
+            {
+                let key = self.keys[i];
+                assert(self.keys@.contains(key));
+                assert(!self.keys@.subrange(0, i as int).contains(key));
+
+                let mut instance = self.instances.remove(&key).unwrap();
+                instance.reset_volatile();
+                self.instances.insert(key, instance);
+
+                proof {
+                    assert(self.keys@.subrange(0, i + 1) =~= self.keys@.subrange(0, i as int).push(key));
+                    assert forall |k: u64| self.instances@.contains_key(k) implies #[trigger] self.instances@[k].inv(c) by {};
+                }
+
+                i = i + 1;
+            }
+
+            self.pending = VecDeque::new();
+            self.queued = HashMap::new();
+            self.multi.leading = None;
+            self.up = true;
+
+            proof {
+                assert(self.keys@.subrange(0, self.keys@.len() as int) =~= self.keys@);
+                assert(self@.pending =~= Seq::empty());
+                assert(request_set_view(self.queued@) =~= Set::empty());
+                assert(self@.instances =~= old(self)@.instances.map_values(|instance: host::Instance| host::durable_part(instance)));
+            }
+
+            Some(NetworkOperation { send: None, recv: None })
+        }
+
+        pub fn submit_request(&self, c: &Constants, request: Value) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            self.inv(c),
+        ensures
+            match net_op {
+                Some(net_op) => host::submit_request(&c@, &self@, &self@, net_op@, request@),
+                None => request@.is_no_op(),
+            },
+        {
+            if (request.is_no_op()) {
+                return None;
+            }
+
+            Some(NetworkOperation {
+                send: Some(Message::ClientRequest { value: request }),
+                recv: None,
+            })
+        }
+
+        pub fn receive_request(&mut self, c: &Constants, key: u64, recv: Message) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::receive_request(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let request = match recv {
+                Message::ClientRequest { value } => value,
+                _ => { return None; },
+            };
+
+            // A fast client's requests go to the acceptors (`fast_accept`), not into a queue.
+            if (!c.owns_request(request.client_id) || c.is_fast_request(&request)) {
+                return None;
+            }
+
+            if (!contains_request(&self.assigned, request.client_id, request.seq) && !contains_request(&self.queued, request.client_id, request.seq)) {
+                self.pending.push_back(request);
+                insert_request(&mut self.queued, request.client_id, request.seq);
+
+                proof {
+                    assert(self@.pending =~= old(self)@.pending.push(request@));
+                    assert forall |id: (nat, nat)| #[trigger] request_set_view(self.queued@).contains(id) <==> host::is_queued(self@.pending, id.0, id.1) by {
+                        if (id == (request.client_id as nat, request.seq as nat)) {
+                            assert(self@.pending[old(self)@.pending.len() as int] == request@);
+                        } else if (host::is_queued(self@.pending, id.0, id.1)) {
+                            let j = choose |j: int| #![trigger self@.pending[j]] 0 <= j < self@.pending.len() && self@.pending[j].client_id == id.0 && self@.pending[j].seq == id.1;
+                            assert(old(self)@.pending[j] == self@.pending[j]);
+                        } else if (host::is_queued(old(self)@.pending, id.0, id.1)) {
+                            let j = choose |j: int| #![trigger old(self)@.pending[j]] 0 <= j < old(self)@.pending.len() && old(self)@.pending[j].client_id == id.0 && old(self)@.pending[j].seq == id.1;
+                            assert(self@.pending[j] == old(self)@.pending[j]);
+                        }
+                    };
+                }
+            } else {
+                proof {
+                    assert(self@.pending =~= old(self)@.pending);
+                }
+            }
+
+            Some(NetworkOperation { send: None, recv: Some(recv) })
+        }
+
+        pub fn init_request(&mut self, c: &Constants, key: u64) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::init_request(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            if (self.instances.contains_key(&key)) {
+                return None;
+            }
+
+            let floor = self.floor_for(key);
+            let mut instance = Instance::new(c);
+            let ghost empty_instance = instance;
+
+            instance.current_ballot = floor;
+            instance.max_seen_ballot = floor;
+
+            proof {
+                assert(instance.promised_view() =~= empty_instance.promised_view());
+                assert(instance.proposed_value_view() =~= empty_instance.proposed_value_view());
+                assert(instance.accepted_view() =~= empty_instance.accepted_view());
+                assert(instance.learned_view() =~= empty_instance.learned_view());
+                assert(quorum::all_hosts(c.num_hosts as nat).contains(c.id as nat));
+                assert(c@.knows_configuration(&old(self)@, key as nat));
+                assert(c@.configuration(&old(self)@, key as nat) == c@.fast_quorums());
+            }
+
+            let send = if (floor.num > 0) { Some(promise_msg(c, &instance, key, floor)) } else { None };
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+            self.keys.push(key);
+
+            proof {
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+                assert(self@.instances =~= old(self)@.instances.insert(key as nat, new_instance@));
+                assert(!old(self).keys@.contains(key));
+                assert(self.keys@ == old(self).keys@.push(key));
+                assert forall |k: u64| #[trigger] self.instances@.contains_key(k) <==> self.keys@.contains(k) by {
+                    if (k != key && old(self).keys@.contains(k)) {
+                        let j = choose |j: int| 0 <= j < old(self).keys@.len() && old(self).keys@[j] == k;
+                        assert(self.keys@[j] == k);
+                    }
+                    if (self.keys@.contains(k) && k != key) {
+                        let j = choose |j: int| 0 <= j < self.keys@.len() && self.keys@[j] == k;
+                        assert(old(self).keys@[j] == k);
+                    }
+                };
+            }
+
+            Some(NetworkOperation { send, recv: None })
+        }
+
+        // The floor `init_request` starts instance `key` at and acceptor steps on it must be at.
+        fn floor_for(&self, key: u64) -> (floor: Ballot)
+        ensures
+            floor@ == host::floor_for(&self@, key as nat),
+        {
+            if (key >= self.multi.floor_from) {
+                self.multi.promise_floor
+            } else {
+                Ballot { num: 0, pid: 0 }
+            }
+        }
+
+        pub fn send_prepare(&mut self, c: &Constants, key: u64) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::send_prepare(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            if (!c.is_proposer()) {
+                return None;
+            }
+
+            let new_ballot = match self.instances.get(&key) {
+                Some(instance) => {
+                    if (instance.max_seen_ballot.num == u64::MAX || instance.decide_value.is_some()) {
+                        return None;
+                    }
+
+                    // Every ballot number in the maps is at most `max_seen_ballot.num` (`Instance::inv`).
+                    let new_ballot = Ballot { num: instance.max_seen_ballot.num + 1, pid: c.id };
+                    assert(!instance.volatile.promised@.contains_key(new_ballot.num));
+                    assert(!instance.volatile.proposed_value@.contains_key(new_ballot.num));
+                    assert(!instance.volatile.accepted@.contains_key(new_ballot.num));
+
+                    new_ballot
+                },
+                None => { return None; },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            instance.max_seen_ballot = new_ballot;
+            instance.volatile.promised.insert(new_ballot.num, HashMap::new());
+            instance.volatile.accepted.insert(new_ballot.num, HashSet::new());
+
+            proof {
+                assert(sender_map_view(Map::<u64, Option<(Ballot, Value)>>::empty()) =~= Map::empty());
+                assert(sender_set_view(Set::<u64>::empty()) =~= Set::empty());
+                assert(instance.promised_view() =~= old_instance.promised_view().insert(new_ballot@, Map::empty()));
+                assert(instance.proposed_value_view() =~= old_instance.proposed_value_view());
+                assert(instance.accepted_view() =~= old_instance.accepted_view().insert(new_ballot@, Set::empty()));
+                assert(instance.learned_view() =~= old_instance.learned_view());
+                assert(instance.inv(c));
+            }
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+            }
+
+            Some(NetworkOperation { send: Some(Message::Prepare { key, ballot: new_ballot }), recv: None })
+        }
+
+        pub fn send_prepare_from(&mut self, c: &Constants, key: u64) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::send_prepare_from(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            if (!c.is_proposer()) {
+                return None;
+            }
+
+            // The ballot has to clear the floor and every instance it covers that this host already has.
+            let mut highest = self.multi.promise_floor.num;
+            let mut i: usize = 0;
+            while (i < self.keys.len())
+            invariant
+                0 <= i <= self.keys@.len(),
+                self.inv(c),
+                highest >= self.multi.promise_floor.num,
+                forall |j: int| #![trigger self.keys@[j]]
+                    0 <= j < i &&
+                    self.keys@[j] >= key ==>
+                    self.instances@[self.keys@[j]].max_seen_ballot.num <= highest,
+            decreases
+                self.keys@.len() - i
+            {
+                let k = self.keys[i];
+                assert(self.keys@.contains(k));
+                let instance = self.instances.get(&k).unwrap();
+                if (k >= key && instance.max_seen_ballot.num > highest) {
+                    highest = instance.max_seen_ballot.num;
+                }
+                i = i + 1;
+            }
+
+            if (highest == u64::MAX) {
+                return None;
+            }
+
+            let ballot = Ballot { num: highest + 1, pid: c.id };
+
+            proof {
+                assert forall |k: nat| #![trigger self@.instances[k]]
+                    k >= key && self@.instances.contains_key(k) implies
+                    ballot@.cmp(&self@.instances[k].max_seen_ballot) > 0 by {
+                    assert(self.keys@.contains(k as u64));
+                    let j = choose |j: int| 0 <= j < self.keys@.len() && self.keys@[j] == k as u64;
+                    assert(self.instances@[self.keys@[j]].max_seen_ballot.num <= highest);
+                };
+            }
+
+            self.multi.leading = Some((key, ballot));
+
+            Some(NetworkOperation { send: Some(Message::PrepareFrom { from: key, ballot }), recv: None })
+        }
+
+        pub fn promise_from(&mut self, c: &Constants, key: u64, recv: Message) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::promise_from(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let ballot = match recv {
+                Message::PrepareFrom { from, ballot } if (from == key) => ballot,
+                _ => { return None; },
+            };
+
+            if (ballot.cmp(&self.multi.promise_floor) <= 0) {
+                return None;
+            }
+
+            self.multi.promise_floor = ballot;
+            self.multi.floor_from = key;
+
+            Some(NetworkOperation { send: None, recv: Some(recv) })
+        }
+
+        pub fn raise_to_floor(&mut self, c: &Constants, key: u64) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::raise_to_floor(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let floor = self.floor_for(key);
+            let promise = match self.instances.get(&key) {
+                Some(instance) => {
+                    if (instance.current_ballot.cmp(&floor) >= 0) {
+                        return None;
+                    }
+
+                    promise_msg(c, instance, key, floor)
+                },
+                None => { return None; },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            instance.max_seen_ballot = instance.max_seen_ballot.max(&floor);
+            instance.current_ballot = floor;
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+                assert(new_instance@.volatile.promised =~= old_instance@.volatile.promised);
+                assert(new_instance@.volatile.proposed_value =~= old_instance@.volatile.proposed_value);
+                assert(new_instance@.volatile.accepted =~= old_instance@.volatile.accepted);
+                assert(new_instance@.volatile.learned =~= old_instance@.volatile.learned);
+                assert(quorum::all_hosts(c.num_hosts as nat).contains(c.id as nat));
+            }
+
+            Some(NetworkOperation { send: Some(promise), recv: None })
+        }
+
+        pub fn join_prepare_from(&mut self, c: &Constants, key: u64) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::join_prepare_from(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let ballot = match self.multi.leading {
+                Some((from, ballot)) if (from <= key) => ballot,
+                _ => { return None; },
+            };
+
+            match self.instances.get(&key) {
+                Some(instance) => {
+                    if (instance.volatile.promised.contains_key(&ballot.num)
+                        || instance.volatile.proposed_value.contains_key(&ballot.num)
+                        || instance.volatile.accepted.contains_key(&ballot.num)
+                        || instance.decide_value.is_some()) {
+                        return None;
+                    }
+                },
+                None => { return None; },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            instance.max_seen_ballot = instance.max_seen_ballot.max(&ballot);
+            instance.volatile.promised.insert(ballot.num, HashMap::new());
+            instance.volatile.accepted.insert(ballot.num, HashSet::new());
+
+            proof {
+                assert(sender_map_view(Map::<u64, Option<(Ballot, Value)>>::empty()) =~= Map::empty());
+                assert(sender_set_view(Set::<u64>::empty()) =~= Set::empty());
+                assert(instance.promised_view() =~= old_instance.promised_view().insert(ballot@, Map::empty()));
+                assert(instance.proposed_value_view() =~= old_instance.proposed_value_view());
+                assert(instance.accepted_view() =~= old_instance.accepted_view().insert(ballot@, Set::empty()));
+                assert(instance.learned_view() =~= old_instance.learned_view());
+                assert(instance.inv(c));
+            }
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+            }
+
+            Some(NetworkOperation { send: None, recv: None })
+        }
+
+        pub fn promise(&mut self, c: &Constants, key: u64, recv: Message) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::promise(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let ballot = match recv {
+                Message::Prepare { key: instance, ballot } if (instance == key) => ballot,
+                _ => { return None; },
+            };
+
+            let floor = self.floor_for(key);
+            let accepted = match self.instances.get(&key) {
+                Some(instance) => {
+                    if (instance.current_ballot.cmp(&floor) < 0 || ballot.cmp(&instance.current_ballot) != 1) {
+                        return None;
+                    }
+
+                    match (instance.accept_ballot, instance.accept_value) {
+                        (Some(accept_ballot), Some(accept_value)) => Some((accept_ballot, accept_value)),
+                        _ => None,
+                    }
+                },
+                None => { return None; },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            instance.max_seen_ballot = instance.max_seen_ballot.max(&ballot);
+            instance.current_ballot = ballot;
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+                assert(new_instance@.volatile.promised =~= old_instance@.volatile.promised);
+                assert(new_instance@.volatile.proposed_value =~= old_instance@.volatile.proposed_value);
+                assert(new_instance@.volatile.accepted =~= old_instance@.volatile.accepted);
+                assert(new_instance@.volatile.learned =~= old_instance@.volatile.learned);
+            }
+
+            Some(NetworkOperation { send: Some(Message::Promise { key, sender: c.id, ballot, accepted }), recv: Some(recv) })
+        }
+
+        pub fn reject_prepare(&self, c: &Constants, key: u64, recv: Message) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            self.inv(c),
+        ensures
+            match net_op {
+                Some(net_op) => host::reject_prepare(&c@, &self@, &self@, key as nat, net_op@),
+                None => true,
+            },
+        {
+            let ballot = match recv {
+                Message::Prepare { key: instance, ballot } if (instance == key) => ballot,
+                _ => { return None; },
+            };
+
+            let floor = self.floor_for(key);
+            match self.instances.get(&key) {
+                Some(instance) => {
+                    if (instance.current_ballot.cmp(&floor) < 0 || ballot.cmp(&instance.current_ballot) >= 0) {
+                        return None;
+                    }
+
+                    Some(NetworkOperation { send: Some(Message::Nack { key, ballot, higher: instance.current_ballot }), recv: Some(recv) })
+                },
+                None => None,
+            }
+        }
+
+        pub fn promised(&mut self, c: &Constants, key: u64, recv: Message) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::promised(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let (sender, ballot, accepted) = match recv {
+                Message::Promise { key: instance, sender, ballot, accepted } if (instance == key) => (sender, ballot, accepted),
+                _ => { return None; },
+            };
+
+            if (sender >= c.num_hosts || ballot.pid != c.id) {
+                return None;
+            }
+
+            match self.instances.get(&key) {
+                Some(instance) => {
+                    if (!instance.volatile.promised.contains_key(&ballot.num) || instance.volatile.proposed_value.contains_key(&ballot.num)) {
+                        return None;
+                    }
+                },
+                None => { return None; },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            let mut promises = instance.volatile.promised.remove(&ballot.num).unwrap();
+            let ghost old_promises = promises;
+            promises.insert(sender, accepted);
+
+            proof {
+                assert(sender_map_view(promises@) =~= sender_map_view(old_promises@).insert(sender as nat, accepted_view(accepted)));
+            }
+
+            let ghost new_promises = promises;
+            instance.volatile.promised.insert(ballot.num, promises);
+
+            proof {
+                assert(instance.volatile.promised@ =~= old_instance.volatile.promised@.insert(ballot.num, new_promises));
+                assert(instance.promised_view() =~= old_instance.promised_view().insert(ballot@, sender_map_view(new_promises@)));
+                assert(instance.proposed_value_view() =~= old_instance.proposed_value_view());
+                assert(instance.accepted_view() =~= old_instance.accepted_view());
+                assert(instance.learned_view() =~= old_instance.learned_view());
+                assert(instance.inv(c));
+            }
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+            }
+
+            Some(NetworkOperation { send: None, recv: Some(recv) })
+        }
+
+        pub fn send_accept(&mut self, c: &Constants, key: u64) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::send_accept(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let (ballot, accepted_value) = match self.instances.get(&key) {
+                Some(instance) => {
+                    let ballot = instance.current_ballot;
+                    if (ballot.pid != c.id || instance.volatile.proposed_value.contains_key(&ballot.num)) {
+                        return None;
+                    }
+
+                    let promises = match instance.volatile.promised.get(&ballot.num) {
+                        Some(promises) => promises,
+                        None => { return None; },
+                    };
+
+                    proof {
+                        lemma_sender_map_view_len(promises@);
+                        assert(sender_map_view(promises@).dom().intersect(quorum::all_hosts(c.num_hosts as nat)) =~= sender_map_view(promises@).dom());
+                        assert(instance@.configuration == c@.fast_quorums());
+                    }
+                    if ((promises.len() as u64) < c.phase1_size) {
+                        return None;
+                    }
+
+                    if (!same_accepted_ballots_have_same_accepted_value(c, promises)) {
+                        return None;
+                    }
+
+                    match select_value(c, promises) {
+                        Some((_, value)) => (ballot, Some(value)),
+                        None => (ballot, None),
+                    }
+                },
+                None => { return None; },
+            };
+
+            // With no accepted value to re-propose, the oldest pending request is assigned to this slot,
+            // or the slot's no-op if no request is waiting.
+            let value = match accepted_value {
+                Some(value) => value,
+                None => match self.pending.pop_front() {
+                    Some(request) => {
+                        remove_request(&mut self.queued, request.client_id, request.seq);
+                        insert_request(&mut self.assigned, request.client_id, request.seq);
+
+                        proof {
+                            assert(self@.pending =~= old(self)@.pending.drop_first());
+                            assert forall |id: (nat, nat)| #[trigger] request_set_view(self.queued@).contains(id) <==> host::is_queued(self@.pending, id.0, id.1) by {
+                                if (host::is_queued(self@.pending, id.0, id.1)) {
+                                    let j = choose |j: int| #![trigger self@.pending[j]] 0 <= j < self@.pending.len() && self@.pending[j].client_id == id.0 && self@.pending[j].seq == id.1;
+                                    assert(old(self).pending@[j + 1] == self.pending@[j]);
+                                    assert(old(self).pending@[0] == request);
+                                } else if (host::is_queued(old(self)@.pending, id.0, id.1) && id != (request.client_id as nat, request.seq as nat)) {
+                                    let j = choose |j: int| #![trigger old(self)@.pending[j]] 0 <= j < old(self)@.pending.len() && old(self)@.pending[j].client_id == id.0 && old(self)@.pending[j].seq == id.1;
+                                    assert(j != 0);
+                                    assert(self@.pending[j - 1] == old(self)@.pending[j]);
+                                }
+                            };
+                        }
+                        request
+                    },
+                    None => Value::no_op(key),
+                },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            instance.volatile.proposed_value.insert(ballot.num, value);
+
+            proof {
+                assert(instance.promised_view() =~= old_instance.promised_view());
+                assert(instance.proposed_value_view() =~= old_instance.proposed_value_view().insert(ballot@, value@));
+                assert(instance.accepted_view() =~= old_instance.accepted_view());
+                assert(instance.learned_view() =~= old_instance.learned_view());
+            }
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+            }
+
+            Some(NetworkOperation { send: Some(Message::Accept { key, ballot, value }), recv: None })
+        }
+
+        pub fn accept(&mut self, c: &Constants, key: u64, recv: Message) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::accept(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let (ballot, value) = match recv {
+                Message::Accept { key: instance, ballot, value } if (instance == key) => (ballot, value),
+                _ => { return None; },
+            };
+
+            let floor = self.floor_for(key);
+            match self.instances.get(&key) {
+                Some(instance) => {
+                    if (instance.current_ballot.cmp(&floor) < 0 || ballot.cmp(&instance.current_ballot) < 0) {
+                        return None;
+                    }
+                },
+                None => { return None; },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            instance.max_seen_ballot = instance.max_seen_ballot.max(&ballot);
+            instance.current_ballot = ballot;
+            instance.accept_ballot = Some(ballot);
+            instance.accept_value = Some(value);
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+                assert(new_instance@.volatile.promised =~= old_instance@.volatile.promised);
+                assert(new_instance@.volatile.proposed_value =~= old_instance@.volatile.proposed_value);
+                assert(new_instance@.volatile.accepted =~= old_instance@.volatile.accepted);
+                assert(new_instance@.volatile.learned =~= old_instance@.volatile.learned);
+            }
+
+            Some(NetworkOperation { send: Some(Message::Accepted { key, sender: c.id, ballot, value }), recv: Some(recv) })
+        }
+
+        pub fn fast_accept(&mut self, c: &Constants, key: u64, recv: Message) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::fast_accept(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let value = match recv {
+                Message::ClientRequest { value } if (value.seq == key && c.is_fast_request(&value)) => value,
+                _ => { return None; },
+            };
+
+            let floor = self.floor_for(key);
+            match self.instances.get(&key) {
+                Some(instance) => {
+                    if (instance.current_ballot.cmp(&floor) < 0 || instance.current_ballot.num != 0 || instance.current_ballot.pid != 0 || instance.accept_ballot.is_some()) {
+                        return None;
+                    }
+                },
+                None => { return None; },
+            };
+
+            let ballot = Ballot { num: 0, pid: 0 };
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            instance.accept_ballot = Some(ballot);
+            instance.accept_value = Some(value);
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+                assert(new_instance@.volatile.promised =~= old_instance@.volatile.promised);
+                assert(new_instance@.volatile.proposed_value =~= old_instance@.volatile.proposed_value);
+                assert(new_instance@.volatile.accepted =~= old_instance@.volatile.accepted);
+                assert(new_instance@.volatile.learned =~= old_instance@.volatile.learned);
+            }
+
+            Some(NetworkOperation { send: Some(Message::Accepted { key, sender: c.id, ballot, value }), recv: Some(recv) })
+        }
+
+        pub fn reject_accept(&self, c: &Constants, key: u64, recv: Message) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            self.inv(c),
+        ensures
+            match net_op {
+                Some(net_op) => host::reject_accept(&c@, &self@, &self@, key as nat, net_op@),
+                None => true,
+            },
+        {
+            let ballot = match recv {
+                Message::Accept { key: instance, ballot, .. } if (instance == key) => ballot,
+                _ => { return None; },
+            };
+
+            let floor = self.floor_for(key);
+            match self.instances.get(&key) {
+                Some(instance) => {
+                    if (instance.current_ballot.cmp(&floor) < 0 || ballot.cmp(&instance.current_ballot) >= 0) {
+                        return None;
+                    }
+
+                    Some(NetworkOperation { send: Some(Message::Nack { key, ballot, higher: instance.current_ballot }), recv: Some(recv) })
+                },
+                None => None,
+            }
+        }
+
+        pub fn nacked(&mut self, c: &Constants, key: u64, recv: Message) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::nacked(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let (ballot, higher) = match recv {
+                Message::Nack { key: instance, ballot, higher } if (instance == key) => (ballot, higher),
+                _ => { return None; },
+            };
+
+            if (ballot.pid != c.id) {
+                return None;
+            }
+
+            match self.instances.get(&key) {
+                Some(instance) => {
+                    if (higher.cmp(&instance.max_seen_ballot) <= 0) {
+                        return None;
+                    }
+                },
+                None => { return None; },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            instance.max_seen_ballot = higher;
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+                assert(new_instance@.volatile.promised =~= old_instance@.volatile.promised);
+                assert(new_instance@.volatile.proposed_value =~= old_instance@.volatile.proposed_value);
+                assert(new_instance@.volatile.accepted =~= old_instance@.volatile.accepted);
+                assert(new_instance@.volatile.learned =~= old_instance@.volatile.learned);
+            }
+
+            Some(NetworkOperation { send: None, recv: Some(recv) })
+        }
+
+        pub fn accepted(&mut self, c: &Constants, key: u64, recv: Message) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::accepted(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let (sender, ballot) = match recv {
+                Message::Accepted { key: instance, sender, ballot, .. } if (instance == key) => (sender, ballot),
+                _ => { return None; },
+            };
+
+            if (sender >= c.num_hosts || ballot.pid != c.id) {
+                return None;
+            }
+
+            match self.instances.get(&key) {
+                Some(instance) => {
+                    if (!instance.volatile.promised.contains_key(&ballot.num) || !instance.volatile.accepted.contains_key(&ballot.num)) {
+                        return None;
+                    }
+                },
+                None => { return None; },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            let mut senders = instance.volatile.accepted.remove(&ballot.num).unwrap();
+            let ghost old_senders = senders;
+            senders.insert(sender);
+
+            proof {
+                assert(sender_set_view(senders@) =~= sender_set_view(old_senders@).insert(sender as nat));
+            }
+
+            let ghost new_senders = senders;
+            instance.volatile.accepted.insert(ballot.num, senders);
+
+            proof {
+                assert(instance.volatile.accepted@ =~= old_instance.volatile.accepted@.insert(ballot.num, new_senders));
+                assert(instance.promised_view() =~= old_instance.promised_view());
+                assert(instance.proposed_value_view() =~= old_instance.proposed_value_view());
+                assert(instance.accepted_view() =~= old_instance.accepted_view().insert(ballot@, sender_set_view(new_senders@)));
+                assert(instance.learned_view() =~= old_instance.learned_view());
+                assert(instance.inv(c));
+            }
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+            }
+
+            Some(NetworkOperation { send: None, recv: Some(recv) })
+        }
+
+        pub fn observe_accepted(&mut self, c: &Constants, key: u64, recv: Message) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::observe_accepted(&c@, &old(self)@, &self@, key as nat, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let (sender, ballot, value) = match recv {
+                Message::Accepted { key: instance, sender, ballot, value } if (instance == key) => (sender, ballot, value),
+                _ => { return None; },
+            };
+
+            if (!c.is_learner() || sender >= c.num_hosts) {
+                return None;
+            }
+
+            // The only fast ballot a host sends Accepted for is `fast_ballot`.
+            if (ballot.num == 0) {
+                if (ballot.pid != 0 || !self.instances.contains_key(&key)) {
+                    return None;
+                }
+                self.observe_fast_accepted(c, key, sender, value);
+                return Some(NetworkOperation { send: None, recv: Some(recv) });
+            }
+
+            match self.instances.get(&key) {
+                Some(instance) => {
+                    if let Some(ballots) = instance.volatile.learned.get(&ballot.num) {
+                        if let Some((learned_value, _)) = ballots.get(&ballot.pid) {
+                            if (!learned_value.eq(&value)) {
+                                return None;
+                            }
+                        }
+                    }
+                },
+                None => { return None; },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            let mut ballots = match instance.volatile.learned.remove(&ballot.num) {
+                Some(ballots) => ballots,
+                None => HashMap::new(),
+            };
+            let mut senders = match ballots.remove(&ballot.pid) {
+                Some((_, senders)) => senders,
+                None => HashSet::new(),
+            };
+            let ghost old_senders = senders;
+            senders.insert(sender);
+
+            proof {
+                assert(sender_set_view(senders@) =~= sender_set_view(old_senders@).insert(sender as nat));
+                if (!old_instance@.volatile.learned.contains_key((ballot@, value@))) {
+                    assert(sender_set_view(old_senders@) =~= Set::empty());
+                }
+            }
+
+            let ghost new_senders = senders;
+            ballots.insert(ballot.pid, (value, senders));
+            instance.volatile.learned.insert(ballot.num, ballots);
+            instance.max_seen_ballot = instance.max_seen_ballot.max(&ballot);
+
+            proof {
+                assert(instance.learned_view() =~= old_instance.learned_view().insert((ballot@, value@), sender_set_view(new_senders@)));
+                assert(instance.promised_view() =~= old_instance.promised_view());
+                assert(instance.proposed_value_view() =~= old_instance.proposed_value_view());
+                assert(instance.accepted_view() =~= old_instance.accepted_view());
+                assert(instance.inv(c));
+            }
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+            }
+
+            Some(NetworkOperation { send: None, recv: Some(recv) })
+        }
+
+        // `observe_accepted` for the fast ballot, whose Accepted messages are counted per value.
+        fn observe_fast_accepted(&mut self, c: &Constants, key: u64, sender: u64, value: Value)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+            old(self).instances@.contains_key(key),
+            sender < c.num_hosts,
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            host::observe_accepted(&c@, &old(self)@, &self@, key as nat, host::NetworkOperation {
+                send: None,
+                recv: Some(low_level::Message::Accepted { key: key as nat, sender: sender as nat, ballot: host::fast_ballot(), value: value@ }),
+            }),
+        {
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+            let fast_ballot = Ballot { num: 0, pid: 0 };
+
+            match fast_learned_position(&instance.volatile.fast_learned, &value) {
+                Some(j) => {
+                    let (learned_value, mut senders) = instance.volatile.fast_learned.remove(j);
+                    let ghost old_senders = senders;
+                    senders.insert(sender);
+                    instance.volatile.fast_learned.insert(j, (learned_value, senders));
+
+                    proof {
+                        assert(old_instance.fast_learned_index(value@) == j);
+                        assert(sender_set_view(senders@) =~= sender_set_view(old_senders@).insert(sender as nat));
+                        assert(instance.fast_learned_index(value@) == j);
+                    }
+                },
+                None => {
+                    let mut senders = HashSet::new();
+                    senders.insert(sender);
+                    instance.volatile.fast_learned.push((value, senders));
+
+                    proof {
+                        assert(!old_instance@.volatile.learned.contains_key((host::fast_ballot(), value@)));
+                        assert(sender_set_view(senders@) =~= Set::empty().insert(sender as nat));
+                        assert(instance.fast_learned_index(value@) == old_instance.volatile.fast_learned@.len());
+                    }
+                },
+            }
+            instance.max_seen_ballot = instance.max_seen_ballot.max(&fast_ballot);
+
+            proof {
+                assert forall |entry: (host::Ballot, SpecValue)| entry != (host::fast_ballot(), value@) && #[trigger] old_instance.learned_view().contains_key(entry) implies
+                    instance.learned_view().contains_key(entry) && instance.learned_view()[entry] == old_instance.learned_view()[entry]
+                by {
+                    if (host::is_fast_ballot(entry.0)) {
+                        let j = old_instance.fast_learned_index(entry.1);
+                        assert(instance.volatile.fast_learned@[j].0@ == entry.1);
+                        assert(instance.fast_learned_index(entry.1) == j);
+                    }
+                };
+                assert(instance.learned_view().dom() =~= old_instance.learned_view().dom().insert((host::fast_ballot(), value@)));
+                assert(instance.promised_view() =~= old_instance.promised_view());
+                assert(instance.proposed_value_view() =~= old_instance.proposed_value_view());
+                assert(instance.accepted_view() =~= old_instance.accepted_view());
+                assert(instance.inv(c));
+            }
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+            }
+        }
+
+        pub fn learner_decide(&mut self, c: &Constants, key: u64, ballot: Ballot, value: Value) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => host::learner_decide(&c@, &old(self)@, &self@, key as nat, net_op@, ballot@, value@),
+                None => self@ == old(self)@,
+            },
+        {
+            if (!c.is_learner() || (ballot.num == 0 && ballot.pid != 0)) {
+                return None;
+            }
+
+            // The fast round has no leader to send Decide, so a learner needs a fast quorum there.
+            match self.instances.get(&key) {
+                Some(instance) => {
+                    if (ballot.num == 0) {
+                        let senders = match fast_learned_position(&instance.volatile.fast_learned, &value) {
+                            Some(j) => &instance.volatile.fast_learned[j].1,
+                            None => { return None; },
+                        };
+
+                        proof {
+                            lemma_sender_set_view_len(senders@);
+                            assert(sender_set_view(senders@).intersect(quorum::all_hosts(c.num_hosts as nat)) =~= sender_set_view(senders@));
+                            assert(instance@.configuration == c@.fast_quorums());
+                        }
+                        if ((senders.len() as u64) < c.fast_size) {
+                            return None;
+                        }
+                    } else {
+                        let senders = match instance.volatile.learned.get(&ballot.num) {
+                            Some(ballots) => match ballots.get(&ballot.pid) {
+                                Some((learned_value, senders)) if (learned_value.eq(&value)) => senders,
+                                _ => { return None; },
+                            },
+                            None => { return None; },
+                        };
+
+                        proof {
+                            lemma_sender_set_view_len(senders@);
+                            assert(sender_set_view(senders@).intersect(quorum::all_hosts(c.num_hosts as nat)) =~= sender_set_view(senders@));
+                            assert(instance@.configuration == c@.fast_quorums());
+                        }
+                        if ((senders.len() as u64) < c.phase2_size) {
+                            return None;
+                        }
+                    }
+                },
+                None => { return None; },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            instance.decide_value = Some(value);
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+                assert(new_instance@.volatile.promised =~= old_instance@.volatile.promised);
+                assert(new_instance@.volatile.proposed_value =~= old_instance@.volatile.proposed_value);
+                assert(new_instance@.volatile.accepted =~= old_instance@.volatile.accepted);
+                assert(new_instance@.volatile.learned =~= old_instance@.volatile.learned);
+            }
+
+            Some(NetworkOperation { send: Some(Message::Decide { key, ballot, value }), recv: None })
+        }
+
+        pub fn send_decide(&self, c: &Constants, key: u64) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            self.inv(c),
+        ensures
+            match net_op {
+                Some(net_op) => host::send_decide(&c@, &self@, &self@, key as nat, net_op@),
+                None => true,
+            },
+        {
+            match self.instances.get(&key) {
+                Some(instance) => {
+                    let ballot = instance.current_ballot;
+                    if (ballot.pid != c.id) {
+                        return None;
+                    }
+
+                    let value = match instance.volatile.proposed_value.get(&ballot.num) {
+                        Some(value) => *value,
+                        None => { return None; },
+                    };
+
+                    if (!instance.volatile.promised.contains_key(&ballot.num)) {
+                        return None;
+                    }
+
+                    let senders = match instance.volatile.accepted.get(&ballot.num) {
+                        Some(senders) => senders,
+                        None => { return None; },
+                    };
+
+                    proof {
+                        lemma_sender_set_view_len(senders@);
+                        assert(sender_set_view(senders@).intersect(quorum::all_hosts(c.num_hosts as nat)) =~= sender_set_view(senders@));
+                        assert(instance@.configuration == c@.fast_quorums());
+                    }
+                    if ((senders.len() as u64) < c.phase2_size) {
+                        return None;
+                    }
+
+                    Some(NetworkOperation { send: Some(Message::Decide { key, ballot, value }), recv: None })
+                },
+                None => None,
+            }
+        }
+
+        pub fn learn(&mut self, c: &Constants, key: u64) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => net_op.send matches Some(Message::Decide { value, .. }) && host::learn(&c@, &old(self)@, &self@, key as nat, net_op@, value@),
+                None => self@ == old(self)@,
+            },
+        {
+            let net_op = match self.send_decide(c, key) {
+                Some(net_op) => net_op,
+                None => { return None; },
+            };
+            let value = match net_op.send {
+                Some(Message::Decide { value, .. }) => value,
+                _ => { return None; },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            instance.decide_value = Some(value);
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+                assert(new_instance@.volatile.promised =~= old_instance@.volatile.promised);
+                assert(new_instance@.volatile.proposed_value =~= old_instance@.volatile.proposed_value);
+                assert(new_instance@.volatile.accepted =~= old_instance@.volatile.accepted);
+                assert(new_instance@.volatile.learned =~= old_instance@.volatile.learned);
+            }
+
+            Some(net_op)
+        }
+
+        pub fn decide(&mut self, c: &Constants, key: u64, recv: Message) -> (net_op: Option<NetworkOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => recv matches Message::Decide { value, .. } && host::decide(&c@, &old(self)@, &self@, key as nat, net_op@, value@),
+                None => self@ == old(self)@,
+            },
+        {
+            let (ballot, value) = match recv {
+                Message::Decide { key: instance, ballot, value } if (instance == key) => (ballot, value),
+                _ => { return None; },
+            };
+
+            match self.instances.get(&key) {
+                Some(instance) => {
+                    if (ballot.cmp(&instance.current_ballot) < 0) {
+                        return None;
+                    }
+                },
+                None => { return None; },
+            };
+
+            let mut instance = self.instances.remove(&key).unwrap();
+            let ghost old_instance = instance;
+
+            instance.max_seen_ballot = instance.max_seen_ballot.max(&ballot);
+            instance.current_ballot = ballot;
+            instance.decide_value = Some(value);
+
+            let ghost new_instance = instance;
+            self.instances.insert(key, instance);
+
+            proof {
+                assert(self.instances@ =~= old(self).instances@.insert(key, new_instance));
+                lemma_instances_view_insert(old(self).instances@, key, new_instance);
+                assert(new_instance@.volatile.promised =~= old_instance@.volatile.promised);
+                assert(new_instance@.volatile.proposed_value =~= old_instance@.volatile.proposed_value);
+                assert(new_instance@.volatile.accepted =~= old_instance@.volatile.accepted);
+                assert(new_instance@.volatile.learned =~= old_instance@.volatile.learned);
+            }
+
+            Some(NetworkOperation { send: None, recv: Some(recv) })
+        }
+
+        // Batched phase 2 (`low_level::batch`). `propose_batch` runs `send_accept` on each key and
+        // sends only the AcceptBatch, and `send_decide_batch` gathers what `send_decide` would send; the
+        // handlers of a received batch check every entry before touching any instance, so a batch is
+        // taken whole or not at all.
+
+        // Proposes in the instances of `keys` in turn, at the ballot of the first, and stops at the
+        // first one it cannot propose in; the Accepts of those it did go out as one AcceptBatch.
+        pub fn propose_batch(&mut self, c: &Constants, keys: &Vec<u64>) -> (net_op: Option<BatchOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => batch::propose_batch(&c@, &old(self)@, &self@, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            // A batch must fit in one frame (`marshal::MAX_PACKET_LEN`).
+            if (keys.len() == 0 || keys.len() > MAX_BATCH_ENTRIES) {
+                return None;
+            }
+            let ballot = match self.instances.get(&keys[0]) {
+                Some(instance) => instance.current_ballot,
+                None => { return None; },
+            };
+
+            let mut entries: Vec<(u64, Value)> = Vec::new();
+            let mut j: usize = 0;
+            while (j < keys.len())
+            invariant
+                c.well_formed(),
+                self.inv(c),
+                self.up == old(self).up,
+                j <= keys.len(),
+                entries@.len() <= j,
+                self@ == batch::propose_entries(&c@, old(self)@, entries_view(entries@), entries@.len() as nat),
+                batch::has_distinct_keys(entries_view(entries@)),
+                forall |i: int| #![trigger entries_view(entries@)[i]]
+                    0 <= i < entries@.len() ==> {
+                        let (key, value) = entries_view(entries@)[i];
+                        let s = batch::propose_entries(&c@, old(self)@, entries_view(entries@), i as nat);
+                        &&& batch::can_propose(&c@, s, key)
+                        &&& s.instances[key].current_ballot == ballot@
+                        &&& value == batch::proposal(&c@, s, key)
+                        &&& self@.instances.contains_key(key)
+                        &&& self@.instances[key].current_ballot == ballot@
+                        &&& self@.instances[key].volatile.proposed_value.contains_key(ballot@)
+                    },
+            decreases
+                keys.len() - j
+            {
+                let key = keys[j];
+                match self.instances.get(&key) {
+                    Some(instance) => {
+                        if (!instance.current_ballot.eq(&ballot)) {
+                            break;
+                        }
+                    },
+                    None => { break; },
+                };
+
+                let ghost prev = self@;
+                let ghost prev_entries = entries_view(entries@);
+                let net_op = match self.send_accept(c, key) {
+                    Some(net_op) => net_op,
+                    None => { break; },
+                };
+                proof {
+                    batch::send_accept_is_propose_entry(&c@, prev, self@, key as nat, net_op@);
+                }
+                let value = match net_op.send {
+                    Some(Message::Accept { value, .. }) => value,
+                    _ => {
+                        proof { assert(false); }
+                        break;
+                    },
+                };
+                entries.push((key, value));
+
+                proof {
+                    let view_entries = entries_view(entries@);
+                    let n = prev_entries.len();
+                    assert(view_entries =~= prev_entries.push((key as nat, value@)));
+                    assert(view_entries.subrange(0, n as int) =~= prev_entries.subrange(0, n as int));
+                    batch::propose_entries_depends_on_prefix(&c@, old(self)@, view_entries, prev_entries, n);
+                    assert(self@ == batch::propose_entries(&c@, old(self)@, view_entries, view_entries.len() as nat));
+
+                    // An instance already proposed in at `ballot` cannot be proposed in again, so the
+                    // keys stay distinct.
+                    assert forall |i: int| 0 <= i < n implies #[trigger] prev_entries[i].0 != key as nat by {
+                        assert(prev.instances[prev_entries[i].0].volatile.proposed_value.contains_key(ballot@));
+                    };
+                    assert forall |i: int, k: int| #![trigger view_entries[i], view_entries[k]]
+                        0 <= i < view_entries.len() && 0 <= k < view_entries.len() && i != k implies
+                        view_entries[i].0 != view_entries[k].0
+                    by {
+                        if (i < n && k < n) {
+                            assert(prev_entries[i].0 != prev_entries[k].0);
+                        }
+                    };
+                    assert forall |i: int| 0 <= i < n implies
+                        #[trigger] batch::propose_entries(&c@, old(self)@, view_entries, i as nat) == batch::propose_entries(&c@, old(self)@, prev_entries, i as nat)
+                    by {
+                        assert(view_entries.subrange(0, i) =~= prev_entries.subrange(0, i));
+                        batch::propose_entries_depends_on_prefix(&c@, old(self)@, view_entries, prev_entries, i as nat);
+                    };
+                }
+                j += 1;
+            }
+
+            if (entries.len() == 0) {
+                return None;
+            }
+
+            Some(BatchOperation { send: Some(Batch::AcceptBatch { ballot, entries }), recv: None })
+        }
+
+        pub fn accept_batch(&mut self, c: &Constants, recv: Batch) -> (net_op: Option<BatchOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => batch::accept_batch(&c@, &old(self)@, &self@, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let (ballot, entries) = match recv {
+                Batch::AcceptBatch { ballot, entries } => (ballot, entries),
+                _ => { return None; },
+            };
+            if (entries.len() == 0 || !has_distinct_keys(&entries)) {
+                return None;
+            }
+
+            let floor_from = self.multi.floor_from;
+            let mut j: usize = 0;
+            while (j < entries.len())
+            invariant
+                self == old(self),
+                j <= entries@.len(),
+                floor_from == self.multi.floor_from,
+                forall |i: int| #![trigger entries@[i]]
+                    0 <= i < j ==>
+                    self.instances@.contains_key(entries@[i].0) &&
+                    self@.instances[entries@[i].0 as nat].current_ballot.cmp(&host::floor_for(&self@, entries@[i].0 as nat)) >= 0 &&
+                    ballot@.cmp(&self@.instances[entries@[i].0 as nat].current_ballot) >= 0,
+            decreases
+                entries@.len() - j
+            {
+                let key = entries[j].0;
+                let floor = self.floor_for(key);
+                match self.instances.get(&key) {
+                    Some(instance) => {
+                        if (instance.current_ballot.cmp(&floor) < 0 || ballot.cmp(&instance.current_ballot) < 0) {
+                            return None;
+                        }
+                    },
+                    None => { return None; },
+                };
+                j += 1;
+            }
+
+            let ghost view_entries = entries_view(entries@);
+            let ghost update = batch::accept_update(ballot@, view_entries);
+            let mut replies: Vec<(u64, Value)> = Vec::new();
+            let mut j: usize = 0;
+            while (j < entries.len())
+            invariant
+                c.well_formed(),
+                self.inv(c),
+                j <= entries@.len(),
+                batch::has_distinct_keys(view_entries),
+                view_entries == entries_view(entries@),
+                update == batch::accept_update(ballot@, view_entries),
+                self@ == batch::apply_entries(old(self)@, view_entries, update, j as nat),
+                self.up == old(self).up,
+                replies@ == entries@.subrange(0, j as int),
+                forall |i: int| #![trigger entries@[i]] 0 <= i < entries@.len() ==> old(self).instances@.contains_key(entries@[i].0),
+            decreases
+                entries@.len() - j
+            {
+                let (key, value) = entries[j];
+                let ghost prev = self@;
+                let ghost before = self.instances@;
+                proof {
+                    assert forall |i: int| #![trigger view_entries[i]] 0 <= i < j implies view_entries[i].0 != key as nat by {
+                        assert(view_entries[i].0 != view_entries[j as int].0);
+                    };
+                    batch::apply_entries_preserves_untouched_instances(old(self)@, view_entries, update, j as nat, key as nat);
+                }
+
+                let mut instance = self.instances.remove(&key).unwrap();
+                let ghost old_instance = instance;
+
+                instance.max_seen_ballot = instance.max_seen_ballot.max(&ballot);
+                instance.current_ballot = ballot;
+                instance.accept_ballot = Some(ballot);
+                instance.accept_value = Some(value);
+
+                let ghost new_instance = instance;
+                self.instances.insert(key, instance);
+                replies.push((key, value));
+
+                proof {
+                    assert(self.instances@ =~= before.insert(key, new_instance));
+                    lemma_instances_view_insert(before, key, new_instance);
+                    assert(new_instance@.volatile =~= old_instance@.volatile);
+                    assert(new_instance@ == update(prev.instances[key as nat], j as int));
+                    assert(self@.instances =~= prev.instances.insert(key as nat, new_instance@));
+                    assert(self@ == batch::apply_entry(prev, view_entries, update, j as int));
+                    assert(replies@ =~= entries@.subrange(0, j + 1));
+                }
+                j += 1;
+            }
+
+            proof { assert(replies@ =~= entries@); }
+            Some(BatchOperation { send: Some(Batch::AcceptedBatch { sender: c.id, ballot, entries: replies }), recv: Some(Batch::AcceptBatch { ballot, entries }) })
+        }
+
+        pub fn accepted_batch(&mut self, c: &Constants, recv: Batch) -> (net_op: Option<BatchOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => batch::accepted_batch(&c@, &old(self)@, &self@, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let (sender, ballot, entries) = match recv {
+                Batch::AcceptedBatch { sender, ballot, entries } => (sender, ballot, entries),
+                _ => { return None; },
+            };
+            if (sender >= c.num_hosts || ballot.pid != c.id || entries.len() == 0 || !has_distinct_keys(&entries)) {
+                return None;
+            }
+
+            let mut j: usize = 0;
+            while (j < entries.len())
+            invariant
+                self == old(self),
+                j <= entries@.len(),
+                ballot.pid == c.id,
+                forall |i: int| #![trigger entries@[i]]
+                    0 <= i < j ==>
+                    self.instances@.contains_key(entries@[i].0) &&
+                    self.instances@[entries@[i].0].volatile.promised@.contains_key(ballot.num) &&
+                    self.instances@[entries@[i].0].volatile.accepted@.contains_key(ballot.num),
+            decreases
+                entries@.len() - j
+            {
+                match self.instances.get(&entries[j].0) {
+                    Some(instance) => {
+                        if (!instance.volatile.promised.contains_key(&ballot.num) || !instance.volatile.accepted.contains_key(&ballot.num)) {
+                            return None;
+                        }
+                    },
+                    None => { return None; },
+                };
+                j += 1;
+            }
+
+            let ghost view_entries = entries_view(entries@);
+            let ghost update = batch::accepted_update(sender as nat, ballot@);
+            let mut j: usize = 0;
+            while (j < entries.len())
+            invariant
+                c.well_formed(),
+                self.inv(c),
+                sender < c.num_hosts,
+                ballot.pid == c.id,
+                j <= entries@.len(),
+                batch::has_distinct_keys(view_entries),
+                view_entries == entries_view(entries@),
+                update == batch::accepted_update(sender as nat, ballot@),
+                self@ == batch::apply_entries(old(self)@, view_entries, update, j as nat),
+                self.up == old(self).up,
+                forall |i: int| #![trigger entries@[i]]
+                    j <= i < entries@.len() ==>
+                    self.instances@.contains_key(entries@[i].0) &&
+                    self.instances@[entries@[i].0].volatile.promised@.contains_key(ballot.num) &&
+                    self.instances@[entries@[i].0].volatile.accepted@.contains_key(ballot.num),
+            decreases
+                entries@.len() - j
+            {
+                let key = entries[j].0;
+                let ghost prev = self@;
+                let ghost before = self.instances@;
+                let mut instance = self.instances.remove(&key).unwrap();
+                let ghost old_instance = instance;
+
+                let mut senders = instance.volatile.accepted.remove(&ballot.num).unwrap();
+                let ghost old_senders = senders;
+                senders.insert(sender);
+
+                proof {
+                    assert(sender_set_view(senders@) =~= sender_set_view(old_senders@).insert(sender as nat));
+                }
+
+                let ghost new_senders = senders;
+                instance.volatile.accepted.insert(ballot.num, senders);
+
+                proof {
+                    assert(instance.volatile.accepted@ =~= old_instance.volatile.accepted@.insert(ballot.num, new_senders));
+                    assert(instance.promised_view() =~= old_instance.promised_view());
+                    assert(instance.proposed_value_view() =~= old_instance.proposed_value_view());
+                    assert(instance.accepted_view() =~= old_instance.accepted_view().insert(ballot@, sender_set_view(new_senders@)));
+                    assert(instance.learned_view() =~= old_instance.learned_view());
+                    assert(instance.inv(c));
+                }
+
+                let ghost new_instance = instance;
+                self.instances.insert(key, instance);
+
+                proof {
+                    assert(self.instances@ =~= before.insert(key, new_instance));
+                    lemma_instances_view_insert(before, key, new_instance);
+                    assert(new_instance@ == update(prev.instances[key as nat], j as int));
+                    assert(self@.instances =~= prev.instances.insert(key as nat, new_instance@));
+                    assert(self@ == batch::apply_entry(prev, view_entries, update, j as int));
+                    assert forall |i: int| #![trigger entries@[i]] j < i < entries@.len() implies entries@[i].0 != key by {
+                        assert(view_entries[i].0 != view_entries[j as int].0);
+                    };
+                }
+                j += 1;
+            }
+
+            Some(BatchOperation { send: None, recv: Some(Batch::AcceptedBatch { sender, ballot, entries }) })
+        }
+
+        pub fn send_decide_batch(&self, c: &Constants, keys: &Vec<u64>) -> (net_op: Option<BatchOperation>)
+        requires
+            c.well_formed(),
+            self.inv(c),
+        ensures
+            match net_op {
+                Some(net_op) => batch::send_decide_batch(&c@, &self@, &self@, net_op@),
+                None => true,
+            },
+        {
+            if (keys.len() == 0 || keys.len() > MAX_BATCH_ENTRIES) {
+                return None;
+            }
+            let ballot = match self.instances.get(&keys[0]) {
+                Some(instance) => instance.current_ballot,
+                None => { return None; },
+            };
+
+            let mut entries: Vec<(u64, Value)> = Vec::new();
+            let mut j: usize = 0;
+            while (j < keys.len())
+            invariant
+                c.well_formed(),
+                self.inv(c),
+                j <= keys.len(),
+                entries@.len() == j,
+                forall |i: int| #![trigger entries@[i]]
+                    0 <= i < j ==>
+                    self@.instances.contains_key(entries@[i].0 as nat) &&
+                    self@.instances[entries@[i].0 as nat].current_ballot == ballot@ &&
+                    host::send_decide(&c@, &self@, &self@, entries@[i].0 as nat, low_level::NetworkOperation {
+                        recv: None,
+                        send: Some(low_level::Message::Decide { key: entries@[i].0 as nat, ballot: ballot@, value: entries@[i].1@ }),
+                    }),
+            decreases
+                keys.len() - j
+            {
+                let key = keys[j];
+                let value = match self.send_decide(c, key) {
+                    Some(NetworkOperation { send: Some(Message::Decide { ballot: decide_ballot, value, .. }), .. }) => {
+                        if (!decide_ballot.eq(&ballot)) {
+                            return None;
+                        }
+                        value
+                    },
+                    _ => { return None; },
+                };
+                entries.push((key, value));
+                j += 1;
+            }
+
+            if (!has_distinct_keys(&entries)) {
+                return None;
+            }
+
+            proof {
+                assert forall |j: int| #![trigger entries_view(entries@)[j]] 0 <= j < entries@.len() implies {
+                    let (key, value) = entries_view(entries@)[j];
+                    &&& self@.instances.contains_key(key)
+                    &&& self@.instances[key].current_ballot == ballot@
+                    &&& host::send_decide(&c@, &self@, &self@, key, low_level::NetworkOperation {
+                            recv: None,
+                            send: Some(low_level::Message::Decide { key, ballot: ballot@, value }),
+                        })
+                } by {
+                    assert(entries@[j].0 as nat == entries_view(entries@)[j].0);
+                };
+            }
+
+            Some(BatchOperation { send: Some(Batch::DecideBatch { ballot, entries }), recv: None })
+        }
+
+        pub fn decide_batch(&mut self, c: &Constants, recv: Batch) -> (net_op: Option<BatchOperation>)
+        requires
+            c.well_formed(),
+            old(self).inv(c),
+        ensures
+            self.inv(c),
+            self.up == old(self).up,
+            match net_op {
+                Some(net_op) => batch::decide_batch(&c@, &old(self)@, &self@, net_op@),
+                None => self@ == old(self)@,
+            },
+        {
+            let (ballot, entries) = match recv {
+                Batch::DecideBatch { ballot, entries } => (ballot, entries),
+                _ => { return None; },
+            };
+            if (entries.len() == 0 || !has_distinct_keys(&entries)) {
+                return None;
+            }
+
+            let mut j: usize = 0;
+            while (j < entries.len())
+            invariant
+                self == old(self),
+                j <= entries@.len(),
+                forall |i: int| #![trigger entries@[i]]
+                    0 <= i < j ==>
+                    self.instances@.contains_key(entries@[i].0) &&
+                    ballot@.cmp(&self@.instances[entries@[i].0 as nat].current_ballot) >= 0,
+            decreases
+                entries@.len() - j
+            {
+                match self.instances.get(&entries[j].0) {
+                    Some(instance) => {
+                        if (ballot.cmp(&instance.current_ballot) < 0) {
+                            return None;
+                        }
+                    },
+                    None => { return None; },
+                };
+                j += 1;
+            }
+
+            let ghost view_entries = entries_view(entries@);
+            let ghost update = batch::decide_update(ballot@, view_entries);
+            let mut j: usize = 0;
+            while (j < entries.len())
+            invariant
+                c.well_formed(),
+                self.inv(c),
+                j <= entries@.len(),
+                batch::has_distinct_keys(view_entries),
+                view_entries == entries_view(entries@),
+                update == batch::decide_update(ballot@, view_entries),
+                self@ == batch::apply_entries(old(self)@, view_entries, update, j as nat),
+                self.up == old(self).up,
+                forall |i: int| #![trigger entries@[i]] 0 <= i < entries@.len() ==> old(self).instances@.contains_key(entries@[i].0),
+            decreases
+                entries@.len() - j
+            {
+                let (key, value) = entries[j];
+                let ghost prev = self@;
+                let ghost before = self.instances@;
+                proof {
+                    assert forall |i: int| #![trigger view_entries[i]] 0 <= i < j implies view_entries[i].0 != key as nat by {
+                        assert(view_entries[i].0 != view_entries[j as int].0);
+                    };
+                    batch::apply_entries_preserves_untouched_instances(old(self)@, view_entries, update, j as nat, key as nat);
+                }
+
+                let mut instance = self.instances.remove(&key).unwrap();
+                let ghost old_instance = instance;
+
+                instance.max_seen_ballot = instance.max_seen_ballot.max(&ballot);
+                instance.current_ballot = ballot;
+                instance.decide_value = Some(value);
+
+                let ghost new_instance = instance;
+                self.instances.insert(key, instance);
+
+                proof {
+                    assert(self.instances@ =~= before.insert(key, new_instance));
+                    lemma_instances_view_insert(before, key, new_instance);
+                    assert(new_instance@.volatile =~= old_instance@.volatile);
+                    assert(new_instance@ == update(prev.instances[key as nat], j as int));
+                    assert(self@.instances =~= prev.instances.insert(key as nat, new_instance@));
+                    assert(self@ == batch::apply_entry(prev, view_entries, update, j as int));
+                }
+                j += 1;
+            }
+
+            Some(BatchOperation { send: None, recv: Some(Batch::DecideBatch { ballot, entries }) })
+        }
+    }
+
+    // The entries of a batch name distinct instances.
+    fn has_distinct_keys(entries: &Vec<(u64, Value)>) -> (result: bool)
+    ensures
+        result == batch::has_distinct_keys(entries_view(entries@)),
+    {
+        let mut seen: HashSet<u64> = HashSet::new();
+        let mut j: usize = 0;
+        while (j < entries.len())
+        invariant
+            j <= entries@.len(),
+            forall |key: u64| #[trigger] seen@.contains(key) <==> exists |i: int| 0 <= i < j && entries@[i].0 == key,
+            batch::has_distinct_keys(entries_view(entries@.subrange(0, j as int))),
+        decreases
+            entries@.len() - j
+        {
+            let key = entries[j].0;
+            if (seen.contains(&key)) {
+                proof {
+                    let i = choose |i: int| 0 <= i < j && entries@[i].0 == key;
+                    assert(entries_view(entries@)[i].0 == entries_view(entries@)[j as int].0);
+                }
+                return false;
+            }
+            seen.insert(key);
+            proof {
+                assert(entries_view(entries@.subrange(0, j + 1)) =~= entries_view(entries@.subrange(0, j as int)).push(entries_view(entries@)[j as int]));
+            }
+            j += 1;
+        }
+        assert(entries@.subrange(0, j as int) =~= entries@);
+        true
+    }
+
+    // Where `fast_learned` counts the Accepted messages for `value`, if any arrived.
+    fn fast_learned_position(fast_learned: &Vec<(Value, HashSet<u64>)>, value: &Value) -> (result: Option<usize>)
+    ensures
+        match result {
+            Some(j) => j < fast_learned@.len() && fast_learned@[j as int].0@ == value@,
+            None => forall |j: int| 0 <= j < fast_learned@.len() ==> #[trigger] fast_learned@[j].0@ != value@,
+        },
+    {
+        let mut j: usize = 0;
+        while (j < fast_learned.len())
+        invariant
+            j <= fast_learned@.len(),
+            forall |k: int| 0 <= k < j ==> #[trigger] fast_learned@[k].0@ != value@,
+        decreases
+            fast_learned@.len() - j,
+        {
+            if (fast_learned[j].0.eq(value)) {
+                return Some(j);
+            }
+            j += 1;
+        }
+        None
+    }
+
+    fn promise_msg(c: &Constants, instance: &Instance, key: u64, ballot: Ballot) -> (message: Message)
+    requires
+        instance.inv(c),
+    ensures
+        message@ == host::promise_msg(&c@, instance@, key as nat, ballot@),
+    {
+        let accepted = match (instance.accept_ballot, instance.accept_value) {
+            (Some(accept_ballot), Some(accept_value)) => Some((accept_ballot, accept_value)),
+            _ => None,
+        };
+        Message::Promise { key, sender: c.id, ballot, accepted }
+    }
+
+    fn contains_request(ids: &HashMap<u64, HashSet<u64>>, client_id: u64, seq: u64) -> (result: bool)
+    ensures
+        result == request_set_view(ids@).contains((client_id as nat, seq as nat)),
+    {
+        match ids.get(&client_id) {
+            Some(seqs) => seqs.contains(&seq),
+            None => false,
+        }
+    }
+
+    fn insert_request(ids: &mut HashMap<u64, HashSet<u64>>, client_id: u64, seq: u64)
+    ensures
+        request_set_view(ids@) == request_set_view(old(ids)@).insert((client_id as nat, seq as nat)),
+    {
+        let mut seqs = match ids.remove(&client_id) {
+            Some(seqs) => seqs,
+            None => HashSet::new(),
+        };
+        seqs.insert(seq);
+        ids.insert(client_id, seqs);
+        assert(request_set_view(ids@) =~= request_set_view(old(ids)@).insert((client_id as nat, seq as nat)));
+    }
+
+    fn remove_request(ids: &mut HashMap<u64, HashSet<u64>>, client_id: u64, seq: u64)
+    ensures
+        request_set_view(ids@) == request_set_view(old(ids)@).remove((client_id as nat, seq as nat)),
+    {
+        if let Some(mut seqs) = ids.remove(&client_id) {
+            seqs.remove(&seq);
+            ids.insert(client_id, seqs);
+        }
+        assert(request_set_view(ids@) =~= request_set_view(old(ids)@).remove((client_id as nat, seq as nat)));
+    }
+
+    pub open spec fn same_accepted_ballot_implies_same_accepted_value(a1: Option<(Ballot, Value)>, a2: Option<(Ballot, Value)>) -> bool {
+        a1.is_some() && a2.is_some() && a1.unwrap().0.num != 0 && a1.unwrap().0@ == a2.unwrap().0@ ==> a1.unwrap().1 == a2.unwrap().1
+    }
+
+    // Acceptors that collided in the fast round report different values for it, so only classic
+    // ballots are checked.
+    fn same_accepted_ballots_have_same_accepted_value(c: &Constants, accepted_map: &HashMap<u64, Option<(Ballot, Value)>>) -> (result: bool)
+    requires
+        forall |sender: u64| #![auto] accepted_map@.contains_key(sender) ==> sender < c.num_hosts,
+    ensures
+        result ==> host::same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(sender_map_view(accepted_map@)),
+    {
+        let mut i: u64 = 0;
+        while (i < c.num_hosts)
+        invariant
+            0 <= i <= c.num_hosts,
+            forall |sender: u64| #![auto] accepted_map@.contains_key(sender) ==> sender < c.num_hosts,
+            forall |s1: u64, s2: u64| #![auto]
+                s1 < i &&
+                accepted_map@.contains_key(s1) &&
+                accepted_map@.contains_key(s2) ==>
+                same_accepted_ballot_implies_same_accepted_value(accepted_map@[s1], accepted_map@[s2]),
+        decreases
+            c.num_hosts - i
+        {
+            if let Some(first) = accepted_map.get(&i) {
+                let mut j: u64 = 0;
+                while (j < c.num_hosts)
+                invariant
+                    0 <= i < c.num_hosts,
+                    0 <= j <= c.num_hosts,
+                    accepted_map@.contains_key(i),
+                    *first == accepted_map@[i],
+                    forall |sender: u64| #![auto] accepted_map@.contains_key(sender) ==> sender < c.num_hosts,
+                    forall |s1: u64, s2: u64| #![auto]
+                        s1 < i &&
+                        accepted_map@.contains_key(s1) &&
+                        accepted_map@.contains_key(s2) ==>
+                        same_accepted_ballot_implies_same_accepted_value(accepted_map@[s1], accepted_map@[s2]),
+                    forall |s2: u64| #![auto]
+                        s2 < j &&
+                        accepted_map@.contains_key(s2) ==>
+                        same_accepted_ballot_implies_same_accepted_value(accepted_map@[i], accepted_map@[s2]),
+                decreases
+                    c.num_hosts - j
+                {
+                    if let Some(second) = accepted_map.get(&j) {
+                        if let (Some((b1, v1)), Some((b2, v2))) = (*first, *second) {
+                            if (b1.num != 0 && b1.eq(&b2) && v1 != v2) {
+                                return false;
+                            }
+                        }
+                    }
+                    j = j + 1;
+                }
+            }
+            i = i + 1;
+        }
+
+        proof {
+            let view = sender_map_view(accepted_map@);
+            assert forall |s1: nat, s2: nat|
+                view.contains_key(s1) &&
+                view.contains_key(s2) &&
+                view[s1].is_some() &&
+                view[s2].is_some() &&
+                !host::is_fast_ballot(view[s1].unwrap().0) &&
+                #[trigger] host::accepted_map_ballots_are_same(view[s1].unwrap().0, view[s2].unwrap().0) implies
+                view[s1].unwrap().1 == view[s2].unwrap().1
+            by {
+                assert(same_accepted_ballot_implies_same_accepted_value(accepted_map@[s1 as u64], accepted_map@[s2 as u64]));
+            };
+        }
+
+        true
+    }
+
+    // The hosts that reported `(ballot, value)` to the coordinator.
+    pub open spec fn reported_by(promises: Map<nat, Option<(host::Ballot, SpecValue)>>, ballot: host::Ballot, value: SpecValue) -> Set<nat> {
+        promises.dom().filter(|sender: nat| promises[sender] == Some((ballot, value)))
+    }
+
+    // The largest fast quorum that may have accepted `value` at `ballot`: every host that reported it,
+    // and every host that did not report at all.
+    pub open spec fn may_have_accepted_hosts(num_hosts: nat, promises: Map<nat, Option<(host::Ballot, SpecValue)>>, ballot: host::Ballot, value: SpecValue) -> Set<nat> {
+        quorum::all_hosts(num_hosts).difference(promises.dom()).union(reported_by(promises, ballot, value))
+    }
+
+    fn count_reports(c: &Constants, promises: &HashMap<u64, Option<(Ballot, Value)>>, ballot: Ballot, value: Value) -> (count: u64)
+    requires
+        forall |sender: u64| #![auto] promises@.contains_key(sender) ==> sender < c.num_hosts,
+    ensures
+        count as nat == reported_by(sender_map_view(promises@), ballot@, value@).len(),
+        count <= c.num_hosts,
+    {
+        let ghost view = sender_map_view(promises@);
+        let ghost reported = reported_by(view, ballot@, value@);
+        proof { lemma_sender_map_view_len(promises@); }
+
+        let mut count: u64 = 0;
+        let mut k: u64 = 0;
+
+        proof { assert(reported.filter(|s: nat| s < 0) =~= Set::empty()); }
+
+        while (k < c.num_hosts)
+        invariant
+            0 <= k <= c.num_hosts,
+            view == sender_map_view(promises@),
+            reported == reported_by(view, ballot@, value@),
+            view.dom().finite(),
+            forall |sender: u64| #![auto] promises@.contains_key(sender) ==> sender < c.num_hosts,
+            count as nat == reported.filter(|s: nat| s < k).len(),
+            count <= k,
+        decreases
+            c.num_hosts - k
+        {
+            let ghost prefix = reported.filter(|s: nat| s < k);
+            let ghost next_prefix = reported.filter(|s: nat| s < k + 1);
+            proof {
+                lemma_len_subset(prefix, view.dom());
+            }
+
+            match promises.get(&k) {
+                Some(Some((b, v))) if (b.eq(&ballot) && v.eq(&value)) => {
+                    proof {
+                        assert(next_prefix =~= prefix.insert(k as nat));
+                        assert(!prefix.contains(k as nat));
+                    }
+                    count = count + 1;
+                },
+                _ => {
+                    proof { assert(next_prefix =~= prefix); }
+                },
+            }
+            k = k + 1;
+        }
+
+        proof {
+            assert forall |s: nat| #[trigger] reported.contains(s) implies s < c.num_hosts by {
+                assert(promises@.contains_key(s as u64));
+            };
+            assert(reported.filter(|s: nat| s < c.num_hosts) =~= reported);
+        }
+
+        count
+    }
+
+    proof fn lemma_may_have_accepted_hosts_len(num_hosts: nat, promises: Map<nat, Option<(host::Ballot, SpecValue)>>, ballot: host::Ballot, value: SpecValue)
+    requires
+        quorum::are_valid_hosts(promises.dom(), num_hosts),
+    ensures
+        may_have_accepted_hosts(num_hosts, promises, ballot, value).finite(),
+        may_have_accepted_hosts(num_hosts, promises, ballot, value).len() == (num_hosts - promises.dom().len()) + reported_by(promises, ballot, value).len(),
+    {
+        let all = quorum::all_hosts(num_hosts);
+        let silent = all.difference(promises.dom());
+        let reported = reported_by(promises, ballot, value);
+
+        low_level::full_set_size(all, num_hosts);
+        assert(all =~= promises.dom().union(silent));
+        lemma_set_disjoint_lens(promises.dom(), silent);
+        lemma_set_disjoint_lens(silent, reported);
+    }
+
+    // Every fast quorum that may have accepted `value` at `ballot` is among `may_have_accepted_hosts`.
+    proof fn lemma_fast_quorum_within_may_have_accepted_hosts(num_hosts: nat, promises: Map<nat, Option<(host::Ballot, SpecValue)>>, fast_quorum: Set<nat>, ballot: host::Ballot, value: SpecValue)
+    requires
+        quorum::are_valid_hosts(promises.dom(), num_hosts),
+        quorum::are_valid_hosts(fast_quorum, num_hosts),
+        fast::fast_quorum_may_have_accepted(promises, fast_quorum, ballot, value),
+    ensures
+        fast_quorum.intersect(quorum::all_hosts(num_hosts)).len() <= may_have_accepted_hosts(num_hosts, promises, ballot, value).len(),
+    {
+        lemma_may_have_accepted_hosts_len(num_hosts, promises, ballot, value);
+        assert(fast_quorum.intersect(quorum::all_hosts(num_hosts)).subset_of(may_have_accepted_hosts(num_hosts, promises, ballot, value)));
+        lemma_len_subset(fast_quorum.intersect(quorum::all_hosts(num_hosts)), may_have_accepted_hosts(num_hosts, promises, ballot, value));
+    }
+
+    // `fast::select_value` over the senders in order. The highest reported ballot is found first;
+    // at a classic ballot its value is the only one reported there, and at the fast ballot each
+    // reported value is checked for a fast quorum that may have accepted it before falling back to
+    // the value of the lowest-numbered host that reported the ballot.
+    fn select_value(c: &Constants, promises: &HashMap<u64, Option<(Ballot, Value)>>) -> (result: Option<(Ballot, Value)>)
+    requires
+        c.well_formed(),
+        forall |sender: u64| #![auto] promises@.contains_key(sender) ==> sender < c.num_hosts,
+        c@.quorums.is_phase1_quorum(sender_map_view(promises@).dom()),
+        host::same_classic_accepted_ballots_in_accepted_map_have_same_accepted_value(sender_map_view(promises@)),
+    ensures
+        accepted_view(result) == fast::select_value(c@.fast_quorums(), c@.num_hosts, sender_map_view(promises@)),
+    {
+        let ghost view = sender_map_view(promises@);
+        let ghost fq = c@.fast_quorums();
+        proof {
+            lemma_sender_map_view_len(promises@);
+            assert(quorum::are_valid_hosts(view.dom(), c.num_hosts as nat));
+        }
+
+        let mut highest: Option<(u64, Ballot)> = None;
+        let mut i: u64 = 0;
+        while (i < c.num_hosts)
+        invariant
+            0 <= i <= c.num_hosts,
+            view == sender_map_view(promises@),
+            forall |sender: u64| #![auto] promises@.contains_key(sender) ==> sender < c.num_hosts,
+            match highest {
+                None => forall |s: nat| s < i ==> !#[trigger] host::map_has_key_with_some_value(view, s),
+                Some((lowest, ballot)) => {
+                    &&& lowest < i
+                    &&& fast::reports_ballot(view, lowest as nat, ballot@)
+                    &&& forall |s: nat| s < i && #[trigger] host::map_has_key_with_some_value(view, s) ==> ballot@.cmp(&view[s].unwrap().0) >= 0
+                    &&& forall |s: nat| s < lowest ==> !#[trigger] fast::reports_ballot(view, s, ballot@)
+                },
+            },
+        decreases
+            c.num_hosts - i
+        {
+            if let Some(Some((ballot, _))) = promises.get(&i) {
+                match highest {
+                    None => { highest = Some((i, *ballot)); },
+                    Some((_, highest_ballot)) => {
+                        if (ballot.cmp(&highest_ballot) > 0) {
+                            highest = Some((i, *ballot));
+                        }
+                    },
+                }
+            }
+            i = i + 1;
+        }
+
+        let (lowest, ballot) = match highest {
+            Some(highest) => highest,
+            None => {
+                proof {
+                    host::get_max_accepted_value_is_some_implies_accepted_map_has_corresponding_sender(view);
+                    if (host::get_max_accepted_value(view).is_some()) {
+                        let s = choose |s: nat| #[trigger] host::map_has_key_with_some_value_same_as_get_max_accepted_value(view, s);
+                        assert(host::map_has_key_with_some_value(view, s));
+                    }
+                }
+                return None;
+            },
+        };
+
+        proof {
+            assert(host::map_has_key_with_some_value(view, lowest as nat));
+            host::if_accepted_map_has_sender_with_value_as_some_then_larget_accepted_ballot_sender_exists(view);
+            host::get_max_accepted_ballot_corresponds_to_largest_ballot(view);
+            let largest = choose |largest: nat| #[trigger] host::is_largest_accepted_ballot_sender(view, largest);
+            assert(host::map_has_key_with_some_value(view, largest));
+            assert(host::get_max_accepted_value(view).unwrap().0 == ballot@);
+        }
+
+        if (ballot.num != 0) {
+            proof {
+                host::get_max_accepted_value_is_some_implies_accepted_map_has_corresponding_sender(view);
+                let s = choose |s: nat| #[trigger] host::map_has_key_with_some_value_same_as_get_max_accepted_value(view, s);
+                assert(host::accepted_map_ballots_are_same(view[lowest as nat].unwrap().0, view[s].unwrap().0));
+                fast::select_value_at_classic_ballot(fq, c.num_hosts as nat, view);
+            }
+            return *promises.get(&lowest).unwrap();
+        }
+
+        proof {
+            lemma_len_subset(view.dom(), quorum::all_hosts(c.num_hosts as nat));
+            low_level::full_set_size(quorum::all_hosts(c.num_hosts as nat), c.num_hosts as nat);
+        }
+        let silent = c.num_hosts - (promises.len() as u64);
+
+        let mut j: u64 = 0;
+        while (j < c.num_hosts)
+        invariant
+            0 <= j <= c.num_hosts,
+            c.well_formed(),
+            view == sender_map_view(promises@),
+            fq == c@.fast_quorums(),
+            quorum::are_valid_hosts(view.dom(), c.num_hosts as nat),
+            forall |sender: u64| #![auto] promises@.contains_key(sender) ==> sender < c.num_hosts,
+            ballot.num == 0,
+            silent as nat == c.num_hosts - view.dom().len(),
+            host::get_max_accepted_value(view).unwrap().0 == ballot@,
+            forall |s: nat| s < j && #[trigger] fast::reports_ballot(view, s, ballot@) ==>
+                may_have_accepted_hosts(c.num_hosts as nat, view, ballot@, view[s].unwrap().1).len() < c.fast_size,
+        decreases
+            c.num_hosts - j
+        {
+            if let Some(Some((b, value))) = promises.get(&j) {
+                if (b.eq(&ballot)) {
+                    let count = count_reports(c, promises, ballot, *value);
+                    proof { lemma_may_have_accepted_hosts_len(c.num_hosts as nat, view, ballot@, value@); }
+                    if (silent + count >= c.fast_size) {
+                        proof {
+                            let hosts = may_have_accepted_hosts(c.num_hosts as nat, view, ballot@, value@);
+                            assert(hosts.intersect(quorum::all_hosts(c.num_hosts as nat)) =~= hosts);
+                            assert(fq.is_fast_quorum(hosts));
+                            assert(fast::fast_quorum_may_have_accepted(view, hosts, ballot@, value@));
+                            assert(fast::value_may_have_been_chosen(fq, c.num_hosts as nat, view, ballot@, value@));
+                            fast::value_that_may_have_been_chosen_is_selected(fq, c.num_hosts as nat, view, value@);
+                        }
+                        return Some((ballot, *value));
+                    }
+                }
+            }
+            j = j + 1;
+        }
+
+        proof {
+            // A fast quorum that may have accepted some value meets the phase-1 quorum in a host that
+            // reported it, and every reported value was found short of a fast quorum.
+            assert forall |value: SpecValue| !#[trigger] fast::value_may_have_been_chosen(fq, c.num_hosts as nat, view, ballot@, value) by {
+                if (fast::value_may_have_been_chosen(fq, c.num_hosts as nat, view, ballot@, value)) {
+                    let fast_quorum = choose |fast_quorum: Set<nat>| quorum::are_valid_hosts(fast_quorum, c.num_hosts as nat) && #[trigger] fq.is_fast_quorum(fast_quorum) && fast::fast_quorum_may_have_accepted(view, fast_quorum, ballot@, value);
+                    assert(fq.classic.is_phase1_quorum(view.dom()) && fq.is_fast_quorum(fast_quorum));
+                    let h = choose |h: nat| #![auto] view.dom().contains(h) && fast_quorum.contains(h) && fast_quorum.contains(h);
+                    assert(view[h] == Some((ballot@, value)));
+                    assert(fast::reports_ballot(view, h, ballot@));
+                    lemma_fast_quorum_within_may_have_accepted_hosts(c.num_hosts as nat, view, fast_quorum, ballot@, value);
+                }
+            };
+            assert(fast::is_lowest_reporting_sender(view, lowest as nat, ballot@));
+            let chosen = choose |sender: nat| #[trigger] fast::is_lowest_reporting_sender(view, sender, ballot@);
+            assert(chosen == lowest as nat) by {
+                if (chosen < lowest as nat) {
+                    assert(!fast::reports_ballot(view, chosen, ballot@));
+                } else if (chosen > lowest as nat) {
+                    assert(!fast::reports_ballot(view, lowest as nat, ballot@));
+                }
+            };
+        }
+
+        *promises.get(&lowest).unwrap()
+    }
+
+    pub proof fn lemma_sender_map_view_len(accepted_map: Map<u64, Option<(Ballot, Value)>>)
+    requires
+        accepted_map.dom().finite(),
+    ensures
+        sender_map_view(accepted_map).dom().finite(),
+        sender_map_view(accepted_map).len() == accepted_map.len(),
+    {
+        let view = sender_map_view(accepted_map);
+        let to_nat = |sender: u64| sender as nat;
+
+        assert(injective(to_nat));
+        assert forall |sender: u64| accepted_map.dom().contains(sender) implies view.dom().contains(#[trigger] to_nat(sender)) by { };
+        assert forall |sender: nat| #[trigger] view.dom().contains(sender) implies exists |s: u64| accepted_map.dom().contains(s) && to_nat(s) == sender by {
+            assert(to_nat(sender as u64) == sender);
+        };
+
+        lemma_map_size(accepted_map.dom(), view.dom(), to_nat);
+    }
+
+    pub proof fn lemma_sender_set_view_len(senders: Set<u64>)
+    requires
+        senders.finite(),
+    ensures
+        sender_set_view(senders).finite(),
+        sender_set_view(senders).len() == senders.len(),
+    {
+        let view = sender_set_view(senders);
+        let to_nat = |sender: u64| sender as nat;
+
+        assert(injective(to_nat));
+        assert forall |sender: u64| senders.contains(sender) implies view.contains(#[trigger] to_nat(sender)) by { };
+        assert forall |sender: nat| #[trigger] view.contains(sender) implies exists |s: u64| senders.contains(s) && to_nat(s) == sender by {
+            assert(to_nat(sender as u64) == sender);
+        };
+
+        lemma_map_size(senders, view, to_nat);
+    }
+
+    pub proof fn lemma_instances_view_insert(instances: Map<u64, Instance>, key: u64, instance: Instance)
+    ensures
+        instances_view(instances.insert(key, instance)) =~= instances_view(instances).insert(key as nat, instance@),
+    {
+        assert(instances_view(instances.insert(key, instance)) =~= instances_view(instances).insert(key as nat, instance@));
+    }
+}
@@ -0,0 +1,771 @@
+use super::{Ballot, Batch, Message, Packet, Value};
+use vstd::{bytes::*, prelude::*, slice::*};
+
+verus! {
+    pub const PREPARE_TAG: u8 = 0;
+    pub const PROMISE_TAG: u8 = 1;
+    pub const ACCEPT_TAG: u8 = 2;
+    pub const ACCEPTED_TAG: u8 = 3;
+    pub const DECIDE_TAG: u8 = 4;
+    pub const CLIENT_REQUEST_TAG: u8 = 5;
+    pub const NACK_TAG: u8 = 6;
+    pub const PREPARE_FROM_TAG: u8 = 7;
+
+    pub const ACCEPT_BATCH_TAG: u8 = 8;
+    pub const ACCEPTED_BATCH_TAG: u8 = 9;
+    pub const DECIDE_BATCH_TAG: u8 = 10;
+
+    pub const NONE_TAG: u8 = 0;
+    pub const SOME_TAG: u8 = 1;
+
+    // Every variant has a fixed length; a Promise is further split by its option tag at index 33.
+    // A value is 24 bytes: its client id, its sequence number and its operation.
+    pub const VALUE_LEN: usize = 24;
+    pub const PREPARE_LEN: usize = 25;
+    pub const PROMISE_NONE_LEN: usize = 34;
+    pub const PROMISE_SOME_LEN: usize = 74;
+    pub const ACCEPT_LEN: usize = 49;
+    pub const ACCEPTED_LEN: usize = 57;
+    pub const DECIDE_LEN: usize = 49;
+    pub const CLIENT_REQUEST_LEN: usize = 25;
+    pub const NACK_LEN: usize = 41;
+    pub const PREPARE_FROM_LEN: usize = 25;
+
+    // A batch is a header (its tag, its sender for an AcceptedBatch, its ballot and its entry count)
+    // followed by its entries, each a key and a value.
+    pub const ENTRY_LEN: usize = 32;
+    pub const BATCH_HEADER_LEN: usize = 25;
+    pub const ACCEPTED_BATCH_HEADER_LEN: usize = 33;
+    pub const MAX_BATCH_ENTRIES: usize = 64;
+
+    // The longest encoding of a single-key message, and of any packet; the latter bounds the frames
+    // a stream transport accepts and the datagrams it reads.
+    pub const MAX_MESSAGE_LEN: usize = PROMISE_SOME_LEN;
+    pub const MAX_PACKET_LEN: usize = ACCEPTED_BATCH_HEADER_LEN + MAX_BATCH_ENTRIES * ENTRY_LEN;
+
+    pub open spec fn marshal_u64(x: u64) -> Seq<u8> {
+        spec_u64_to_le_bytes(x)
+    }
+
+    pub open spec fn marshal_value(value: Value) -> Seq<u8> {
+        marshal_u64(value.client_id) + marshal_u64(value.seq) + marshal_u64(value.op as u64)
+    }
+
+    pub open spec fn marshal_ballot(ballot: Ballot) -> Seq<u8> {
+        marshal_u64(ballot.num) + marshal_u64(ballot.pid)
+    }
+
+    pub open spec fn marshal_accepted(accepted: Option<(Ballot, Value)>) -> Seq<u8> {
+        match accepted {
+            Some((ballot, value)) => seq![SOME_TAG] + marshal_ballot(ballot) + marshal_value(value),
+            None => seq![NONE_TAG],
+        }
+    }
+
+    pub open spec fn message_marshal(message: Message) -> Seq<u8> {
+        match message {
+            Message::Prepare { key, ballot } => seq![PREPARE_TAG] + marshal_u64(key) + marshal_ballot(ballot),
+            Message::PrepareFrom { from, ballot } => seq![PREPARE_FROM_TAG] + marshal_u64(from) + marshal_ballot(ballot),
+            Message::Promise { key, sender, ballot, accepted } => seq![PROMISE_TAG] + marshal_u64(key) + marshal_u64(sender) + marshal_ballot(ballot) + marshal_accepted(accepted),
+            Message::Accept { key, ballot, value } => seq![ACCEPT_TAG] + marshal_u64(key) + marshal_ballot(ballot) + marshal_value(value),
+            Message::Accepted { key, sender, ballot, value } => seq![ACCEPTED_TAG] + marshal_u64(key) + marshal_u64(sender) + marshal_ballot(ballot) + marshal_value(value),
+            Message::Nack { key, ballot, higher } => seq![NACK_TAG] + marshal_u64(key) + marshal_ballot(ballot) + marshal_ballot(higher),
+            Message::Decide { key, ballot, value } => seq![DECIDE_TAG] + marshal_u64(key) + marshal_ballot(ballot) + marshal_value(value),
+            Message::ClientRequest { value } => seq![CLIENT_REQUEST_TAG] + marshal_value(value),
+        }
+    }
+
+    pub open spec fn marshal_entries(entries: Seq<(u64, Value)>) -> Seq<u8>
+    decreases
+        entries.len()
+    {
+        if (entries.len() == 0) {
+            Seq::empty()
+        } else {
+            marshal_entries(entries.drop_last()) + marshal_u64(entries.last().0) + marshal_value(entries.last().1)
+        }
+    }
+
+    pub open spec fn batch_marshal(batch: Batch) -> Seq<u8> {
+        match batch {
+            Batch::AcceptBatch { ballot, entries } => seq![ACCEPT_BATCH_TAG] + marshal_ballot(ballot) + marshal_u64(entries@.len() as u64) + marshal_entries(entries@),
+            Batch::AcceptedBatch { sender, ballot, entries } => seq![ACCEPTED_BATCH_TAG] + marshal_u64(sender) + marshal_ballot(ballot) + marshal_u64(entries@.len() as u64) + marshal_entries(entries@),
+            Batch::DecideBatch { ballot, entries } => seq![DECIDE_BATCH_TAG] + marshal_ballot(ballot) + marshal_u64(entries@.len() as u64) + marshal_entries(entries@),
+        }
+    }
+
+    pub open spec fn packet_marshal(packet: Packet) -> Seq<u8> {
+        match packet {
+            Packet::Single(message) => message_marshal(message),
+            Packet::Batch(batch) => batch_marshal(batch),
+        }
+    }
+
+    pub open spec fn parse_u64(data: Seq<u8>, offset: int) -> u64 {
+        spec_u64_from_le_bytes(data.subrange(offset, offset + 8))
+    }
+
+    pub open spec fn parse_value(data: Seq<u8>, offset: int) -> Value {
+        Value { client_id: parse_u64(data, offset), seq: parse_u64(data, offset + 8), op: parse_u64(data, offset + 16) as i64 }
+    }
+
+    pub open spec fn parse_ballot(data: Seq<u8>, offset: int) -> Ballot {
+        Ballot { num: parse_u64(data, offset), pid: parse_u64(data, offset + 8) }
+    }
+
+    pub open spec fn message_parse(data: Seq<u8>) -> Option<Message> {
+        if (data.len() == 0) {
+            None
+        } else if (data[0] == PREPARE_TAG && data.len() == PREPARE_LEN) {
+            Some(Message::Prepare { key: parse_u64(data, 1), ballot: parse_ballot(data, 9) })
+        } else if (data[0] == PREPARE_FROM_TAG && data.len() == PREPARE_FROM_LEN) {
+            Some(Message::PrepareFrom { from: parse_u64(data, 1), ballot: parse_ballot(data, 9) })
+        } else if (data[0] == PROMISE_TAG && data.len() == PROMISE_NONE_LEN && data[33] == NONE_TAG) {
+            Some(Message::Promise { key: parse_u64(data, 1), sender: parse_u64(data, 9), ballot: parse_ballot(data, 17), accepted: None })
+        } else if (data[0] == PROMISE_TAG && data.len() == PROMISE_SOME_LEN && data[33] == SOME_TAG) {
+            Some(Message::Promise { key: parse_u64(data, 1), sender: parse_u64(data, 9), ballot: parse_ballot(data, 17), accepted: Some((parse_ballot(data, 34), parse_value(data, 50))) })
+        } else if (data[0] == ACCEPT_TAG && data.len() == ACCEPT_LEN) {
+            Some(Message::Accept { key: parse_u64(data, 1), ballot: parse_ballot(data, 9), value: parse_value(data, 25) })
+        } else if (data[0] == ACCEPTED_TAG && data.len() == ACCEPTED_LEN) {
+            Some(Message::Accepted { key: parse_u64(data, 1), sender: parse_u64(data, 9), ballot: parse_ballot(data, 17), value: parse_value(data, 33) })
+        } else if (data[0] == NACK_TAG && data.len() == NACK_LEN) {
+            Some(Message::Nack { key: parse_u64(data, 1), ballot: parse_ballot(data, 9), higher: parse_ballot(data, 25) })
+        } else if (data[0] == DECIDE_TAG && data.len() == DECIDE_LEN) {
+            Some(Message::Decide { key: parse_u64(data, 1), ballot: parse_ballot(data, 9), value: parse_value(data, 25) })
+        } else if (data[0] == CLIENT_REQUEST_TAG && data.len() == CLIENT_REQUEST_LEN) {
+            Some(Message::ClientRequest { value: parse_value(data, 1) })
+        } else {
+            None
+        }
+    }
+
+    // A batch as plain data, with `sender` 0 unless it is an AcceptedBatch: what `batch_parse`
+    // reads back, since a spec function cannot build the `Vec` of a `Batch`.
+    pub open spec fn batch_fields(batch: Batch) -> (u8, u64, Ballot, Seq<(u64, Value)>) {
+        match batch {
+            Batch::AcceptBatch { ballot, entries } => (ACCEPT_BATCH_TAG, 0, ballot, entries@),
+            Batch::AcceptedBatch { sender, ballot, entries } => (ACCEPTED_BATCH_TAG, sender, ballot, entries@),
+            Batch::DecideBatch { ballot, entries } => (DECIDE_BATCH_TAG, 0, ballot, entries@),
+        }
+    }
+
+    pub open spec fn is_batch_tag(tag: u8) -> bool {
+        tag == ACCEPT_BATCH_TAG || tag == ACCEPTED_BATCH_TAG || tag == DECIDE_BATCH_TAG
+    }
+
+    pub open spec fn batch_header_len(tag: u8) -> int {
+        if (tag == ACCEPTED_BATCH_TAG) { ACCEPTED_BATCH_HEADER_LEN as int } else { BATCH_HEADER_LEN as int }
+    }
+
+    pub open spec fn parse_entries(data: Seq<u8>, offset: int, count: nat) -> Seq<(u64, Value)> {
+        Seq::new(count, |j: int| (parse_u64(data, offset + ENTRY_LEN * j), parse_value(data, offset + ENTRY_LEN * j + 8)))
+    }
+
+    // The count must match the length exactly, so a truncated or padded batch is rejected.
+    pub open spec fn batch_parse(data: Seq<u8>) -> Option<(u8, u64, Ballot, Seq<(u64, Value)>)> {
+        if (data.len() == 0 || !is_batch_tag(data[0]) || data.len() < batch_header_len(data[0])) {
+            None
+        } else {
+            let tag = data[0];
+            let header = batch_header_len(tag);
+            let sender = if (tag == ACCEPTED_BATCH_TAG) { parse_u64(data, 1) } else { 0 };
+            let ballot = parse_ballot(data, header - 24);
+            let count = parse_u64(data, header - 8);
+
+            if (data.len() != header + ENTRY_LEN * count) {
+                None
+            } else {
+                Some((tag, sender, ballot, parse_entries(data, header, count as nat)))
+            }
+        }
+    }
+
+    pub proof fn op_round_trips_through_u64(op: i64)
+    ensures
+        (op as u64) as i64 == op,
+    {
+        assert((op as u64) as i64 == op) by (bit_vector);
+    }
+
+    pub proof fn u64_round_trips_through_op(x: u64)
+    ensures
+        (x as i64) as u64 == x,
+    {
+        assert((x as i64) as u64 == x) by (bit_vector);
+    }
+
+    pub proof fn u64_bytes_round_trip(data: Seq<u8>, offset: int)
+    requires
+        0 <= offset,
+        offset + 8 <= data.len(),
+    ensures
+        marshal_u64(parse_u64(data, offset)) == data.subrange(offset, offset + 8),
+    {
+        lemma_auto_spec_u64_to_from_le_bytes();
+        assert(data.subrange(offset, offset + 8).len() == 8);
+    }
+
+    pub proof fn value_parses_back(data: Seq<u8>, offset: int, value: Value)
+    requires
+        0 <= offset,
+        offset + 24 <= data.len(),
+        data.subrange(offset, offset + 24) == marshal_value(value),
+    ensures
+        parse_value(data, offset) == value,
+    {
+        lemma_auto_spec_u64_to_from_le_bytes();
+        assert(data.subrange(offset, offset + 8) =~= data.subrange(offset, offset + 24).subrange(0, 8));
+        assert(data.subrange(offset + 8, offset + 16) =~= data.subrange(offset, offset + 24).subrange(8, 16));
+        assert(data.subrange(offset + 16, offset + 24) =~= data.subrange(offset, offset + 24).subrange(16, 24));
+        assert(marshal_value(value).subrange(0, 8) =~= marshal_u64(value.client_id));
+        assert(marshal_value(value).subrange(8, 16) =~= marshal_u64(value.seq));
+        assert(marshal_value(value).subrange(16, 24) =~= marshal_u64(value.op as u64));
+        op_round_trips_through_u64(value.op);
+    }
+
+    pub proof fn value_bytes_round_trip(data: Seq<u8>, offset: int)
+    requires
+        0 <= offset,
+        offset + 24 <= data.len(),
+    ensures
+        marshal_value(parse_value(data, offset)) == data.subrange(offset, offset + 24),
+    {
+        u64_bytes_round_trip(data, offset);
+        u64_bytes_round_trip(data, offset + 8);
+        u64_bytes_round_trip(data, offset + 16);
+        u64_round_trips_through_op(parse_u64(data, offset + 16));
+        assert(data.subrange(offset, offset + 24) =~=
+            data.subrange(offset, offset + 8) + data.subrange(offset + 8, offset + 16) + data.subrange(offset + 16, offset + 24));
+    }
+
+    // Corresponds to `decode(encode(m)) == m`
+    pub proof fn parse_of_marshal_is_identity(message: Message)
+    ensures
+        message_parse(message_marshal(message)) == Some(message),
+    {
+        lemma_auto_spec_u64_to_from_le_bytes();
+        let data = message_marshal(message);
+
+        match message {
+            Message::Prepare { key, ballot } => {
+                assert(data.len() == PREPARE_LEN);
+                assert(data.subrange(1, 9) =~= marshal_u64(key));
+                assert(data.subrange(9, 17) =~= marshal_u64(ballot.num));
+                assert(data.subrange(17, 25) =~= marshal_u64(ballot.pid));
+            },
+            Message::PrepareFrom { from, ballot } => {
+                assert(data.len() == PREPARE_FROM_LEN);
+                assert(data.subrange(1, 9) =~= marshal_u64(from));
+                assert(data.subrange(9, 17) =~= marshal_u64(ballot.num));
+                assert(data.subrange(17, 25) =~= marshal_u64(ballot.pid));
+            },
+            Message::Promise { key, sender, ballot, accepted } => {
+                assert(data.subrange(1, 9) =~= marshal_u64(key));
+                assert(data.subrange(9, 17) =~= marshal_u64(sender));
+                assert(data.subrange(17, 25) =~= marshal_u64(ballot.num));
+                assert(data.subrange(25, 33) =~= marshal_u64(ballot.pid));
+
+                match accepted {
+                    Some((accepted_ballot, accepted_value)) => {
+                        assert(data.len() == PROMISE_SOME_LEN && data[33] == SOME_TAG);
+                        assert(data.subrange(34, 42) =~= marshal_u64(accepted_ballot.num));
+                        assert(data.subrange(42, 50) =~= marshal_u64(accepted_ballot.pid));
+                        assert(data.subrange(50, 74) =~= marshal_value(accepted_value));
+                        value_parses_back(data, 50, accepted_value);
+                    },
+                    None => {
+                        assert(data.len() == PROMISE_NONE_LEN && data[33] == NONE_TAG);
+                    },
+                }
+            },
+            Message::Accept { key, ballot, value } => {
+                assert(data.len() == ACCEPT_LEN);
+                assert(data.subrange(1, 9) =~= marshal_u64(key));
+                assert(data.subrange(9, 17) =~= marshal_u64(ballot.num));
+                assert(data.subrange(17, 25) =~= marshal_u64(ballot.pid));
+                assert(data.subrange(25, 49) =~= marshal_value(value));
+                value_parses_back(data, 25, value);
+            },
+            Message::Accepted { key, sender, ballot, value } => {
+                assert(data.len() == ACCEPTED_LEN);
+                assert(data.subrange(1, 9) =~= marshal_u64(key));
+                assert(data.subrange(9, 17) =~= marshal_u64(sender));
+                assert(data.subrange(17, 25) =~= marshal_u64(ballot.num));
+                assert(data.subrange(25, 33) =~= marshal_u64(ballot.pid));
+                assert(data.subrange(33, 57) =~= marshal_value(value));
+                value_parses_back(data, 33, value);
+            },
+            Message::Nack { key, ballot, higher } => {
+                assert(data.len() == NACK_LEN);
+                assert(data.subrange(1, 9) =~= marshal_u64(key));
+                assert(data.subrange(9, 17) =~= marshal_u64(ballot.num));
+                assert(data.subrange(17, 25) =~= marshal_u64(ballot.pid));
+                assert(data.subrange(25, 33) =~= marshal_u64(higher.num));
+                assert(data.subrange(33, 41) =~= marshal_u64(higher.pid));
+            },
+            Message::Decide { key, ballot, value } => {
+                assert(data.len() == DECIDE_LEN);
+                assert(data.subrange(1, 9) =~= marshal_u64(key));
+                assert(data.subrange(9, 17) =~= marshal_u64(ballot.num));
+                assert(data.subrange(17, 25) =~= marshal_u64(ballot.pid));
+                assert(data.subrange(25, 49) =~= marshal_value(value));
+                value_parses_back(data, 25, value);
+            },
+            Message::ClientRequest { value } => {
+                assert(data.len() == CLIENT_REQUEST_LEN);
+                assert(data.subrange(1, 25) =~= marshal_value(value));
+                value_parses_back(data, 1, value);
+            },
+        }
+    }
+
+    // Any buffer that parses is exactly the encoding of the parsed message, so truncated,
+    // padded or otherwise malformed buffers are always rejected.
+    pub proof fn parse_accepts_only_canonical_encodings(data: Seq<u8>)
+    ensures
+        message_parse(data) is Some ==> data == message_marshal(message_parse(data).unwrap()),
+    {
+        if (message_parse(data) is Some) {
+            let message = message_parse(data).unwrap();
+            let tag = data[0];
+
+            assert(data.subrange(1, 9) == marshal_u64(parse_u64(data, 1))) by { u64_bytes_round_trip(data, 1); };
+            assert(data.subrange(9, 17) == marshal_u64(parse_u64(data, 9))) by { u64_bytes_round_trip(data, 9); };
+            assert(data.subrange(17, 25) == marshal_u64(parse_u64(data, 17))) by { u64_bytes_round_trip(data, 17); };
+
+            if (data.len() >= 33) {
+                assert(data.subrange(25, 33) == marshal_u64(parse_u64(data, 25))) by { u64_bytes_round_trip(data, 25); };
+            }
+
+            match message {
+                Message::Prepare { .. } | Message::PrepareFrom { .. } => {
+                    assert(data =~= seq![tag] + data.subrange(1, 9) + data.subrange(9, 17) + data.subrange(17, 25));
+                },
+                Message::Promise { accepted, .. } => {
+                    if (accepted is Some) {
+                        assert(data.subrange(34, 42) == marshal_u64(parse_u64(data, 34))) by { u64_bytes_round_trip(data, 34); };
+                        assert(data.subrange(42, 50) == marshal_u64(parse_u64(data, 42))) by { u64_bytes_round_trip(data, 42); };
+                        value_bytes_round_trip(data, 50);
+                        assert(data =~= seq![tag] + data.subrange(1, 9) + data.subrange(9, 17) + data.subrange(17, 25) + data.subrange(25, 33)
+                            + seq![data[33]] + data.subrange(34, 42) + data.subrange(42, 50) + data.subrange(50, 74));
+                    } else {
+                        assert(data =~= seq![tag] + data.subrange(1, 9) + data.subrange(9, 17) + data.subrange(17, 25) + data.subrange(25, 33) + seq![data[33]]);
+                    }
+                },
+                Message::Accept { .. } | Message::Decide { .. } => {
+                    value_bytes_round_trip(data, 25);
+                    assert(data =~= seq![tag] + data.subrange(1, 9) + data.subrange(9, 17) + data.subrange(17, 25) + data.subrange(25, 49));
+                },
+                Message::Accepted { .. } => {
+                    value_bytes_round_trip(data, 33);
+                    assert(data =~= seq![tag] + data.subrange(1, 9) + data.subrange(9, 17) + data.subrange(17, 25) + data.subrange(25, 33) + data.subrange(33, 57));
+                },
+                Message::Nack { .. } => {
+                    assert(data.subrange(33, 41) == marshal_u64(parse_u64(data, 33))) by { u64_bytes_round_trip(data, 33); };
+                    assert(data =~= seq![tag] + data.subrange(1, 9) + data.subrange(9, 17) + data.subrange(17, 25) + data.subrange(25, 33) + data.subrange(33, 41));
+                },
+                Message::ClientRequest { .. } => {
+                    value_bytes_round_trip(data, 1);
+                    assert(data =~= seq![tag] + data.subrange(1, 25));
+                },
+            }
+        }
+    }
+
+    pub proof fn parse_rejects_truncated_encodings(message: Message, len: int)
+    requires
+        0 <= len < message_marshal(message).len(),
+    ensures
+        message_parse(message_marshal(message).subrange(0, len)) is None,
+    {
+        let data = message_marshal(message);
+        let truncated = data.subrange(0, len);
+
+        parse_accepts_only_canonical_encodings(truncated);
+        if (message_parse(truncated) is Some) {
+            let parsed = message_parse(truncated).unwrap();
+            lemma_auto_spec_u64_to_from_le_bytes();
+
+            // Every tag has a fixed length (Promise is additionally fixed by its option tag at
+            // index 33), so a strict prefix can never be the encoding of another message.
+            assert(truncated.len() > 0 && truncated[0] == data[0]);
+            assert(truncated.len() == message_marshal(parsed).len());
+            if (truncated.len() > 33) {
+                assert(truncated[33] == data[33]);
+            }
+            assert(false);
+        }
+    }
+
+    // Entry `j` sits at `ENTRY_LEN * j`.
+    pub proof fn marshal_entries_layout(entries: Seq<(u64, Value)>)
+    ensures
+        marshal_entries(entries).len() == ENTRY_LEN * entries.len(),
+        forall |j: int| #![trigger entries[j]]
+            0 <= j < entries.len() ==>
+            marshal_entries(entries).subrange(ENTRY_LEN * j, ENTRY_LEN * j + 8) == marshal_u64(entries[j].0) &&
+            marshal_entries(entries).subrange(ENTRY_LEN * j + 8, ENTRY_LEN * j + 32) == marshal_value(entries[j].1),
+    decreases
+        entries.len()
+    {
+        lemma_auto_spec_u64_to_from_le_bytes();
+        if (entries.len() > 0) {
+            let prefix = entries.drop_last();
+            marshal_entries_layout(prefix);
+            let data = marshal_entries(entries);
+            let n = prefix.len() as int;
+
+            assert forall |j: int| #![trigger entries[j]]
+                0 <= j < entries.len() implies
+                data.subrange(ENTRY_LEN * j, ENTRY_LEN * j + 8) == marshal_u64(entries[j].0) &&
+                data.subrange(ENTRY_LEN * j + 8, ENTRY_LEN * j + 32) == marshal_value(entries[j].1)
+            by {
+                if (j < n) {
+                    assert(prefix[j] == entries[j]);
+                    assert(data.subrange(ENTRY_LEN * j, ENTRY_LEN * j + 8) =~= marshal_entries(prefix).subrange(ENTRY_LEN * j, ENTRY_LEN * j + 8));
+                    assert(data.subrange(ENTRY_LEN * j + 8, ENTRY_LEN * j + 32) =~= marshal_entries(prefix).subrange(ENTRY_LEN * j + 8, ENTRY_LEN * j + 32));
+                } else {
+                    assert(data.subrange(ENTRY_LEN * j, ENTRY_LEN * j + 8) =~= marshal_u64(entries.last().0));
+                    assert(data.subrange(ENTRY_LEN * j + 8, ENTRY_LEN * j + 32) =~= marshal_value(entries.last().1));
+                }
+            };
+        }
+    }
+
+    // Corresponds to `decode_batch(encode_batch(b)) == b`
+    pub proof fn batch_parse_of_marshal_is_identity(batch: Batch)
+    requires
+        batch_fields(batch).3.len() <= MAX_BATCH_ENTRIES,
+    ensures
+        batch_parse(batch_marshal(batch)) == Some(batch_fields(batch)),
+    {
+        lemma_auto_spec_u64_to_from_le_bytes();
+        let data = batch_marshal(batch);
+        let (tag, sender, ballot, entries) = batch_fields(batch);
+        let header = batch_header_len(tag);
+        marshal_entries_layout(entries);
+
+        if (tag == ACCEPTED_BATCH_TAG) {
+            assert(data.subrange(1, 9) =~= marshal_u64(sender));
+        }
+        assert(data.subrange(header - 24, header - 16) =~= marshal_u64(ballot.num));
+        assert(data.subrange(header - 16, header - 8) =~= marshal_u64(ballot.pid));
+        assert(data.subrange(header - 8, header) =~= marshal_u64(entries.len() as u64));
+        assert(data.len() == header + ENTRY_LEN * entries.len());
+
+        assert forall |j: int| #![trigger entries[j]] 0 <= j < entries.len() implies
+            parse_entries(data, header, entries.len())[j] == entries[j]
+        by {
+            let offset = header + ENTRY_LEN * j;
+            assert(data.subrange(offset, offset + 8) =~= marshal_entries(entries).subrange(ENTRY_LEN * j, ENTRY_LEN * j + 8));
+            assert(data.subrange(offset + 8, offset + 32) =~= marshal_entries(entries).subrange(ENTRY_LEN * j + 8, ENTRY_LEN * j + 32));
+            value_parses_back(data, offset + 8, entries[j].1);
+        };
+        assert(parse_entries(data, header, entries.len()) =~= entries);
+    }
+
+    fn append_u64(data: &mut Vec<u8>, x: u64)
+    ensures
+        data@ == old(data)@ + marshal_u64(x),
+    {
+        let mut bytes = u64_to_le_bytes(x);
+        data.append(&mut bytes);
+    }
+
+    fn append_ballot(data: &mut Vec<u8>, ballot: &Ballot)
+    ensures
+        data@ == old(data)@ + marshal_ballot(*ballot),
+    {
+        append_u64(data, ballot.num);
+        append_u64(data, ballot.pid);
+        assert(data@ =~= old(data)@ + marshal_ballot(*ballot));
+    }
+
+    fn append_value(data: &mut Vec<u8>, value: Value)
+    ensures
+        data@ == old(data)@ + marshal_value(value),
+    {
+        append_u64(data, value.client_id);
+        append_u64(data, value.seq);
+        append_u64(data, value.op as u64);
+        assert(data@ =~= old(data)@ + marshal_value(value));
+    }
+
+    pub fn encode(message: &Message) -> (data: Vec<u8>)
+    ensures
+        data@ == message_marshal(*message),
+    {
+        let mut data = Vec::new();
+
+        match message {
+            Message::Prepare { key, ballot } => {
+                data.push(PREPARE_TAG);
+                append_u64(&mut data, *key);
+                append_ballot(&mut data, ballot);
+            },
+            Message::PrepareFrom { from, ballot } => {
+                data.push(PREPARE_FROM_TAG);
+                append_u64(&mut data, *from);
+                append_ballot(&mut data, ballot);
+            },
+            Message::Promise { key, sender, ballot, accepted } => {
+                data.push(PROMISE_TAG);
+                append_u64(&mut data, *key);
+                append_u64(&mut data, *sender);
+                append_ballot(&mut data, ballot);
+                match accepted {
+                    Some((accepted_ballot, accepted_value)) => {
+                        data.push(SOME_TAG);
+                        append_ballot(&mut data, accepted_ballot);
+                        append_value(&mut data, *accepted_value);
+                    },
+                    None => {
+                        data.push(NONE_TAG);
+                    },
+                }
+            },
+            Message::Accept { key, ballot, value } => {
+                data.push(ACCEPT_TAG);
+                append_u64(&mut data, *key);
+                append_ballot(&mut data, ballot);
+                append_value(&mut data, *value);
+            },
+            Message::Accepted { key, sender, ballot, value } => {
+                data.push(ACCEPTED_TAG);
+                append_u64(&mut data, *key);
+                append_u64(&mut data, *sender);
+                append_ballot(&mut data, ballot);
+                append_value(&mut data, *value);
+            },
+            Message::Nack { key, ballot, higher } => {
+                data.push(NACK_TAG);
+                append_u64(&mut data, *key);
+                append_ballot(&mut data, ballot);
+                append_ballot(&mut data, higher);
+            },
+            Message::Decide { key, ballot, value } => {
+                data.push(DECIDE_TAG);
+                append_u64(&mut data, *key);
+                append_ballot(&mut data, ballot);
+                append_value(&mut data, *value);
+            },
+            Message::ClientRequest { value } => {
+                data.push(CLIENT_REQUEST_TAG);
+                append_value(&mut data, *value);
+            },
+        }
+
+        assert(data@ =~= message_marshal(*message));
+        data
+    }
+
+    fn read_u64(data: &[u8], offset: usize) -> (x: u64)
+    requires
+        offset + 8 <= data@.len(),
+    ensures
+        x == parse_u64(data@, offset as int),
+    {
+        u64_from_le_bytes(slice_subrange(data, offset, offset + 8))
+    }
+
+    fn read_ballot(data: &[u8], offset: usize) -> (ballot: Ballot)
+    requires
+        offset + 16 <= data@.len(),
+    ensures
+        ballot == parse_ballot(data@, offset as int),
+    {
+        Ballot { num: read_u64(data, offset), pid: read_u64(data, offset + 8) }
+    }
+
+    fn read_value(data: &[u8], offset: usize) -> (value: Value)
+    requires
+        offset + 24 <= data@.len(),
+    ensures
+        value == parse_value(data@, offset as int),
+    {
+        Value { client_id: read_u64(data, offset), seq: read_u64(data, offset + 8), op: read_u64(data, offset + 16) as i64 }
+    }
+
+    pub fn decode(data: &[u8]) -> (message: Option<Message>)
+    ensures
+        message == message_parse(data@),
+        message is Some ==> data@ == message_marshal(message.unwrap()),
+    {
+        proof { parse_accepts_only_canonical_encodings(data@); }
+
+        let len = data.len();
+        if (len == 0) {
+            return None;
+        }
+
+        let tag = data[0];
+        if (tag == PREPARE_TAG && len == PREPARE_LEN) {
+            Some(Message::Prepare { key: read_u64(data, 1), ballot: read_ballot(data, 9) })
+        } else if (tag == PREPARE_FROM_TAG && len == PREPARE_FROM_LEN) {
+            Some(Message::PrepareFrom { from: read_u64(data, 1), ballot: read_ballot(data, 9) })
+        } else if (tag == PROMISE_TAG && len == PROMISE_NONE_LEN && data[33] == NONE_TAG) {
+            Some(Message::Promise { key: read_u64(data, 1), sender: read_u64(data, 9), ballot: read_ballot(data, 17), accepted: None })
+        } else if (tag == PROMISE_TAG && len == PROMISE_SOME_LEN && data[33] == SOME_TAG) {
+            Some(Message::Promise { key: read_u64(data, 1), sender: read_u64(data, 9), ballot: read_ballot(data, 17), accepted: Some((read_ballot(data, 34), read_value(data, 50))) })
+        } else if (tag == ACCEPT_TAG && len == ACCEPT_LEN) {
+            Some(Message::Accept { key: read_u64(data, 1), ballot: read_ballot(data, 9), value: read_value(data, 25) })
+        } else if (tag == ACCEPTED_TAG && len == ACCEPTED_LEN) {
+            Some(Message::Accepted { key: read_u64(data, 1), sender: read_u64(data, 9), ballot: read_ballot(data, 17), value: read_value(data, 33) })
+        } else if (tag == NACK_TAG && len == NACK_LEN) {
+            Some(Message::Nack { key: read_u64(data, 1), ballot: read_ballot(data, 9), higher: read_ballot(data, 25) })
+        } else if (tag == DECIDE_TAG && len == DECIDE_LEN) {
+            Some(Message::Decide { key: read_u64(data, 1), ballot: read_ballot(data, 9), value: read_value(data, 25) })
+        } else if (tag == CLIENT_REQUEST_TAG && len == CLIENT_REQUEST_LEN) {
+            Some(Message::ClientRequest { value: read_value(data, 1) })
+        } else {
+            None
+        }
+    }
+
+    fn append_entries(data: &mut Vec<u8>, entries: &Vec<(u64, Value)>)
+    ensures
+        data@ == old(data)@ + marshal_entries(entries@),
+    {
+        let mut j: usize = 0;
+        while (j < entries.len())
+        invariant
+            j <= entries@.len(),
+            data@ == old(data)@ + marshal_entries(entries@.subrange(0, j as int)),
+        decreases
+            entries@.len() - j
+        {
+            let (key, value) = entries[j];
+            append_u64(data, key);
+            append_value(data, value);
+            proof {
+                assert(entries@.subrange(0, j + 1).drop_last() =~= entries@.subrange(0, j as int));
+                assert(data@ =~= old(data)@ + marshal_entries(entries@.subrange(0, j + 1)));
+            }
+            j += 1;
+        }
+        assert(entries@.subrange(0, j as int) =~= entries@);
+    }
+
+    pub fn encode_batch(batch: &Batch) -> (data: Vec<u8>)
+    ensures
+        data@ == batch_marshal(*batch),
+    {
+        let mut data = Vec::new();
+
+        match batch {
+            Batch::AcceptBatch { ballot, entries } => {
+                data.push(ACCEPT_BATCH_TAG);
+                append_ballot(&mut data, ballot);
+                append_u64(&mut data, entries.len() as u64);
+                append_entries(&mut data, entries);
+            },
+            Batch::AcceptedBatch { sender, ballot, entries } => {
+                data.push(ACCEPTED_BATCH_TAG);
+                append_u64(&mut data, *sender);
+                append_ballot(&mut data, ballot);
+                append_u64(&mut data, entries.len() as u64);
+                append_entries(&mut data, entries);
+            },
+            Batch::DecideBatch { ballot, entries } => {
+                data.push(DECIDE_BATCH_TAG);
+                append_ballot(&mut data, ballot);
+                append_u64(&mut data, entries.len() as u64);
+                append_entries(&mut data, entries);
+            },
+        }
+
+        assert(data@ =~= batch_marshal(*batch));
+        data
+    }
+
+    pub fn encode_packet(packet: &Packet) -> (data: Vec<u8>)
+    ensures
+        data@ == packet_marshal(*packet),
+    {
+        match packet {
+            Packet::Single(message) => encode(message),
+            Packet::Batch(batch) => encode_batch(batch),
+        }
+    }
+
+    pub fn decode_batch(data: &[u8]) -> (batch: Option<Batch>)
+    ensures
+        match batch {
+            Some(batch) => batch_parse(data@) == Some(batch_fields(batch)),
+            None => batch_parse(data@) is None,
+        },
+    {
+        let len = data.len();
+        if (len == 0) {
+            return None;
+        }
+
+        let tag = data[0];
+        if (tag != ACCEPT_BATCH_TAG && tag != ACCEPTED_BATCH_TAG && tag != DECIDE_BATCH_TAG) {
+            return None;
+        }
+        let header = if (tag == ACCEPTED_BATCH_TAG) { ACCEPTED_BATCH_HEADER_LEN } else { BATCH_HEADER_LEN };
+        if (len < header) {
+            return None;
+        }
+
+        let sender = if (tag == ACCEPTED_BATCH_TAG) { read_u64(data, 1) } else { 0 };
+        let ballot = read_ballot(data, header - 24);
+        let count = read_u64(data, header - 8);
+        if (((len - header) % ENTRY_LEN) != 0 || ((len - header) / ENTRY_LEN) as u64 != count) {
+            return None;
+        }
+
+        let mut entries: Vec<(u64, Value)> = Vec::new();
+        let mut j: usize = 0;
+        while (j < (len - header) / ENTRY_LEN)
+        invariant
+            header <= len == data@.len(),
+            (len - header) % (ENTRY_LEN as int) == 0,
+            j <= (len - header) / ENTRY_LEN,
+            entries@ == parse_entries(data@, header as int, j as nat),
+        decreases
+            (len - header) / ENTRY_LEN - j
+        {
+            proof {
+                assert(header + ENTRY_LEN * j + ENTRY_LEN <= len) by (nonlinear_arith)
+                    requires j < (len - header) / (ENTRY_LEN as int), header <= len, ENTRY_LEN == 32;
+            }
+            let offset = header + ENTRY_LEN * j;
+            entries.push((read_u64(data, offset), read_value(data, offset + 8)));
+            proof { assert(entries@ =~= parse_entries(data@, header as int, (j + 1) as nat)); }
+            j += 1;
+        }
+
+        proof { assert(data@.len() == header + ENTRY_LEN * count); }
+        if (tag == ACCEPT_BATCH_TAG) {
+            Some(Batch::AcceptBatch { ballot, entries })
+        } else if (tag == ACCEPTED_BATCH_TAG) {
+            Some(Batch::AcceptedBatch { sender, ballot, entries })
+        } else {
+            Some(Batch::DecideBatch { ballot, entries })
+        }
+    }
+
+    // A batch tag never starts a single-key message, so the two parsers never both accept a buffer.
+    pub fn decode_packet(data: &[u8]) -> (packet: Option<Packet>)
+    ensures
+        match packet {
+            Some(Packet::Single(message)) => message_parse(data@) == Some(message),
+            Some(Packet::Batch(batch)) => batch_parse(data@) == Some(batch_fields(batch)),
+            None => message_parse(data@) is None && batch_parse(data@) is None,
+        },
+    {
+        if (data.len() > 0 && (data[0] == ACCEPT_BATCH_TAG || data[0] == ACCEPTED_BATCH_TAG || data[0] == DECIDE_BATCH_TAG)) {
+            match decode_batch(data) {
+                Some(batch) => Some(Packet::Batch(batch)),
+                None => None,
+            }
+        } else {
+            match decode(data) {
+                Some(message) => Some(Packet::Single(message)),
+                None => None,
+            }
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use crate::distributed_system::{low_level, no_op, Value as SpecValue};
+use vstd::prelude::*;
+
+verus! {
+    pub mod host;
+    pub mod marshal;
+    pub mod simulate;
+    pub mod transport;
+
+    #[derive(Clone, Copy)]
+    pub struct Ballot {
+        pub num: u64,
+        pub pid: u64,
+    }
+
+    impl View for Ballot {
+        type V = low_level::host::Ballot;
+
+        open spec fn view(&self) -> low_level::host::Ballot {
+            low_level::host::Ballot { num: self.num as nat, pid: self.pid as nat }
+        }
+    }
+
+    impl Ballot {
+        pub fn cmp(&self, other: &Ballot) -> (result: i8)
+        ensures
+            result as int == self@.cmp(&other@),
+        {
+            if (self.num < other.num) {
+                -1
+            } else if (self.num > other.num) {
+                1
+            } else if (self.pid < other.pid) {
+                -1
+            } else if (self.pid > other.pid) {
+                1
+            } else {
+                0
+            }
+        }
+
+        pub fn max(&self, other: &Ballot) -> (result: Ballot)
+        ensures
+            result@ == low_level::host::max_ballot(self@, other@),
+        {
+            if (self.cmp(other) >= 0) {
+                *self
+            } else {
+                *other
+            }
+        }
+
+        pub fn eq(&self, other: &Ballot) -> (result: bool)
+        ensures
+            result == (self@ == other@),
+        {
+            self.num == other.num && self.pid == other.pid
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct Value {
+        pub client_id: u64,
+        pub seq: u64,
+        pub op: i64,
+    }
+
+    impl View for Value {
+        type V = SpecValue;
+
+        open spec fn view(&self) -> SpecValue {
+            SpecValue { client_id: self.client_id as nat, seq: self.seq as nat, op: self.op as int }
+        }
+    }
+
+    impl Value {
+        pub fn eq(&self, other: &Value) -> (result: bool)
+        ensures
+            result == (self@ == other@),
+        {
+            self.client_id == other.client_id && self.seq == other.seq && self.op == other.op
+        }
+
+        pub fn no_op(key: u64) -> (value: Value)
+        ensures
+            value@ == no_op(key as nat),
+        {
+            Value { client_id: 0xffff_ffff_ffff_ffff, seq: key, op: 0 }
+        }
+
+        pub fn is_no_op(&self) -> (result: bool)
+        ensures
+            result == self@.is_no_op(),
+        {
+            self.client_id == 0xffff_ffff_ffff_ffff
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    pub enum Message {
+        Prepare { key: u64, ballot: Ballot },
+        PrepareFrom { from: u64, ballot: Ballot },
+        Promise { key: u64, sender: u64, ballot: Ballot, accepted: Option<(Ballot, Value)> },
+        Accept { key: u64, ballot: Ballot, value: Value },
+        Accepted { key: u64, sender: u64, ballot: Ballot, value: Value },
+        Nack { key: u64, ballot: Ballot, higher: Ballot },
+        Decide { key: u64, ballot: Ballot, value: Value },
+        ClientRequest { value: Value },
+    }
+
+    pub open spec fn accepted_view(accepted: Option<(Ballot, Value)>) -> Option<(low_level::host::Ballot, SpecValue)> {
+        match accepted {
+            Some((ballot, value)) => Some((ballot@, value@)),
+            None => None,
+        }
+    }
+
+    impl View for Message {
+        type V = low_level::Message;
+
+        open spec fn view(&self) -> low_level::Message {
+            match *self {
+                Message::Prepare { key, ballot } => low_level::Message::Prepare { key: key as nat, ballot: ballot@ },
+                Message::PrepareFrom { from, ballot } => low_level::Message::PrepareFrom { from: from as nat, ballot: ballot@ },
+                Message::Promise { key, sender, ballot, accepted } => low_level::Message::Promise { key: key as nat, sender: sender as nat, ballot: ballot@, accepted: accepted_view(accepted) },
+                Message::Accept { key, ballot, value } => low_level::Message::Accept { key: key as nat, ballot: ballot@, value: value@ },
+                Message::Accepted { key, sender, ballot, value } => low_level::Message::Accepted { key: key as nat, sender: sender as nat, ballot: ballot@, value: value@ },
+                Message::Nack { key, ballot, higher } => low_level::Message::Nack { key: key as nat, ballot: ballot@, higher: higher@ },
+                Message::Decide { key, ballot, value } => low_level::Message::Decide { key: key as nat, ballot: ballot@, value: value@ },
+                Message::ClientRequest { value } => low_level::Message::ClientRequest { value: value@ },
+            }
+        }
+    }
+
+    pub open spec fn optional_message_view(message: Option<Message>) -> Option<low_level::Message> {
+        match message {
+            Some(message) => Some(message@),
+            None => None,
+        }
+    }
+
+    // A batched phase 2 message, one `(key, value)` entry per instance and all at `ballot`
+    // (`low_level::batch`). It owns its entries, so unlike `Message` it is not `Copy`.
+    pub enum Batch {
+        AcceptBatch { ballot: Ballot, entries: Vec<(u64, Value)> },
+        AcceptedBatch { sender: u64, ballot: Ballot, entries: Vec<(u64, Value)> },
+        DecideBatch { ballot: Ballot, entries: Vec<(u64, Value)> },
+    }
+
+    pub open spec fn entries_view(entries: Seq<(u64, Value)>) -> Seq<(nat, SpecValue)> {
+        entries.map_values(|entry: (u64, Value)| (entry.0 as nat, entry.1@))
+    }
+
+    impl View for Batch {
+        type V = low_level::Message;
+
+        open spec fn view(&self) -> low_level::Message {
+            match *self {
+                Batch::AcceptBatch { ballot, entries } => low_level::Message::AcceptBatch { ballot: ballot@, entries: entries_view(entries@) },
+                Batch::AcceptedBatch { sender, ballot, entries } => low_level::Message::AcceptedBatch { sender: sender as nat, ballot: ballot@, entries: entries_view(entries@) },
+                Batch::DecideBatch { ballot, entries } => low_level::Message::DecideBatch { ballot: ballot@, entries: entries_view(entries@) },
+            }
+        }
+    }
+
+    // What travels on the wire: a single-key message or a batch.
+    pub enum Packet {
+        Single(Message),
+        Batch(Batch),
+    }
+
+    impl View for Packet {
+        type V = low_level::Message;
+
+        open spec fn view(&self) -> low_level::Message {
+            match *self {
+                Packet::Single(message) => message@,
+                Packet::Batch(batch) => batch@,
+            }
+        }
+    }
+
+    pub open spec fn optional_batch_view(batch: Option<Batch>) -> Option<low_level::Message> {
+        match batch {
+            Some(batch) => Some(batch@),
+            None => None,
+        }
+    }
+
+    // The network operation of a `batch::step`: it receives and sends batches only.
+    pub struct BatchOperation {
+        pub send: Option<Batch>,
+        pub recv: Option<Batch>,
+    }
+
+    impl View for BatchOperation {
+        type V = low_level::NetworkOperation;
+
+        open spec fn view(&self) -> low_level::NetworkOperation {
+            low_level::NetworkOperation { send: optional_batch_view(self.send), recv: optional_batch_view(self.recv) }
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct NetworkOperation {
+        pub send: Option<Message>,
+        pub recv: Option<Message>,
+    }
+
+    impl View for NetworkOperation {
+        type V = low_level::NetworkOperation;
+
+        open spec fn view(&self) -> low_level::NetworkOperation {
+            low_level::NetworkOperation { send: optional_message_view(self.send), recv: optional_message_view(self.recv) }
+        }
+    }
+}
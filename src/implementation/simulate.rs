@@ -0,0 +1,498 @@
+use super::{
+    host, marshal,
+    transport::{act, act_batch, deliver, destinations, network_constants, BatchAction, LocalAction, Transport},
+    Ballot, Packet, Value,
+};
+use crate::distributed_system::low_level::network;
+use std::collections::HashMap;
+use vstd::prelude::*;
+
+verus! {
+    // Deterministic simulation of the executable hosts.
+    //
+    // `Simulator` drives `host::Variables` through `transport::act`, `act_batch` and `deliver`, the
+    // same entry points a UDP or TCP host uses, over `SimTransport`. That network encodes every packet
+    // with `marshal`, and drops, duplicates, delays and reorders the copies. Hosts crash and recover.
+    // Every choice is drawn from one seeded generator, so a run is a function of its seed and `Config`.
+    // The scheduler is trusted test code: the handlers it calls are verified, but nothing checks that
+    // it only calls them on well-formed hosts.
+
+    // SplitMix64, as in the checker's `simulate`.
+    #[verifier::external_body]
+    pub struct Rng {
+        state: u64,
+    }
+
+    impl Rng {
+        #[verifier::external_body]
+        pub fn new(seed: u64) -> Rng {
+            Rng { state: seed }
+        }
+
+        #[verifier::external_body]
+        pub fn next_u64(&mut self) -> u64 {
+            self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = self.state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+
+        // Uniform in `0..bound`; `bound` must be positive.
+        #[verifier::external_body]
+        pub fn below(&mut self, bound: u64) -> u64 {
+            self.next_u64() % bound
+        }
+
+        #[verifier::external_body]
+        pub fn percent(&mut self, percent: u64) -> bool {
+            self.below(100) < percent
+        }
+    }
+
+    // Rates are percentages, drawn once per packet copy (drop, duplicate) or per scheduling decision.
+    pub struct Config {
+        pub num_hosts: u64,
+        pub num_failures: u64,
+        pub num_keys: u64,
+        // Client `i` submits the operation `values[i]`, with `seq` 0, or if it is one of `fast_clients`
+        // with `seq` `i % num_keys`, the instance the acceptors take it into.
+        pub values: Vec<i64>,
+        pub steps: u64,
+        pub drop_percent: u64,
+        pub duplicate_percent: u64,
+        // A packet is delivered between 0 and `max_delay` ticks after it is sent, and the simulator picks
+        // uniformly among the packets that are due.
+        pub max_delay: u64,
+        pub prepare_percent: u64,
+        pub prepare_from_percent: u64,
+        pub crash_percent: u64,
+        pub recover_percent: u64,
+        pub batch_percent: u64,
+        pub learners: Vec<u64>,
+        pub fast_clients: Vec<u64>,
+    }
+
+    impl Config {
+        // The defaults of the checker's `simulate::Config`.
+        #[verifier::external_body]
+        pub fn new() -> Config {
+            Config {
+                num_hosts: 3,
+                num_failures: 1,
+                num_keys: 2,
+                values: vec![1, 2, 3],
+                steps: 2_000,
+                drop_percent: 10,
+                duplicate_percent: 10,
+                max_delay: 5,
+                prepare_percent: 5,
+                prepare_from_percent: 0,
+                crash_percent: 1,
+                recover_percent: 20,
+                batch_percent: 0,
+                learners: Vec::new(),
+                fast_clients: Vec::new(),
+            }
+        }
+
+        // Every host proposes and owns the clients `id, id + num_hosts, ...`. Both phases use
+        // majorities and the fast round `fast::fast_quorum_size`.
+        #[verifier::external_body]
+        pub fn constants(&self, id: u64) -> host::Constants {
+            let n = self.num_hosts;
+            host::Constants {
+                id,
+                num_hosts: n,
+                num_failures: self.num_failures,
+                phase1_size: n / 2 + 1,
+                phase2_size: n / 2 + 1,
+                fast_size: (3 * n + 3) / 4,
+                proposers: (0..n).collect(),
+                learners: self.learners.iter().copied().collect(),
+                owners: (0..n).collect(),
+                fast_clients: self.fast_clients.iter().copied().collect(),
+            }
+        }
+    }
+
+    // One encoded copy of a packet on its way to one host.
+    #[verifier::external_body]
+    struct InFlight {
+        to: usize,
+        deliver_at: u64,
+        data: Vec<u8>,
+    }
+
+    #[verifier::external_body]
+    pub struct SimEndpoint {
+        rng: Rng,
+        tick: u64,
+        num_hosts: usize,
+        drop_percent: u64,
+        duplicate_percent: u64,
+        max_delay: u64,
+        packets: Vec<InFlight>,
+        // The packet the next `recv` returns, picked by the scheduler.
+        selected: Option<usize>,
+        dropped: u64,
+        duplicated: u64,
+    }
+
+    pub struct SimTransport {
+        endpoint: SimEndpoint,
+        network: Ghost<network::Variables>,
+    }
+
+    impl SimEndpoint {
+        #[verifier::external_body]
+        pub fn new(config: &Config, seed: u64) -> SimEndpoint {
+            SimEndpoint {
+                rng: Rng::new(seed),
+                tick: 0,
+                num_hosts: config.num_hosts as usize,
+                drop_percent: config.drop_percent,
+                duplicate_percent: config.duplicate_percent,
+                max_delay: config.max_delay,
+                packets: Vec::new(),
+                selected: None,
+                dropped: 0,
+                duplicated: 0,
+            }
+        }
+
+        // Routes like `UdpEndpoint` and `TcpEndpoint`, then loses or copies each delivery.
+        #[verifier::external_body]
+        fn send_bytes(&mut self, packet: &Packet, data: &[u8]) {
+            for to in destinations(self.num_hosts, packet) {
+                if self.rng.percent(self.drop_percent) {
+                    self.dropped += 1;
+                    continue;
+                }
+                let copies = if self.rng.percent(self.duplicate_percent) { 2 } else { 1 };
+                self.duplicated += copies - 1;
+                for _ in 0..copies {
+                    let deliver_at = self.tick + self.rng.below(self.max_delay + 1);
+                    self.packets.push(InFlight { to, deliver_at, data: data.to_vec() });
+                }
+            }
+        }
+
+        #[verifier::external_body]
+        fn recv_bytes(&mut self) -> Option<Vec<u8>> {
+            let index = self.selected.take()?;
+            Some(self.packets.swap_remove(index).data)
+        }
+
+        // The packets that are due, as `(index, destination)`.
+        #[verifier::external_body]
+        fn due(&self) -> Vec<(usize, usize)> {
+            (0..self.packets.len()).filter(|i| self.packets[*i].deliver_at <= self.tick).map(|i| (i, self.packets[i].to)).collect()
+        }
+
+        // A crashed host loses whatever was addressed to it while it was down.
+        #[verifier::external_body]
+        fn forget(&mut self, host_id: usize) {
+            self.packets.retain(|packet| packet.to != host_id);
+        }
+    }
+
+    impl SimTransport {
+        #[verifier::external_body]
+        pub fn new(endpoint: SimEndpoint) -> (transport: SimTransport)
+        ensures
+            transport.network().well_formed(&network_constants()),
+        {
+            SimTransport { endpoint, network: Ghost::assume_new() }
+        }
+    }
+
+    // The simulated network is inside the trusted boundary of `transport`: a dropped copy is a message
+    // that stays in flight but is never delivered, and a duplicate is a second delivery of one.
+    impl Transport for SimTransport {
+        closed spec fn network(&self) -> network::Variables {
+            self.network@
+        }
+
+        #[verifier::external_body]
+        fn send(&mut self, packet: &Packet) -> (pre: Ghost<network::Variables>) {
+            let data = marshal::encode_packet(packet);
+            self.endpoint.send_bytes(packet, &data);
+            Ghost::assume_new()
+        }
+
+        #[verifier::external_body]
+        fn recv(&mut self) -> (packet: Option<Packet>) {
+            marshal::decode_packet(&self.endpoint.recv_bytes()?)
+        }
+    }
+
+    pub struct Report {
+        pub seed: u64,
+        // Scheduling decisions that changed a host.
+        pub steps: u64,
+        pub decided: u64,
+        pub dropped: u64,
+        pub duplicated: u64,
+        // The scheduling decision after which two hosts first disagreed, and on which key.
+        pub violation: Option<(u64, u64)>,
+    }
+
+    pub struct Simulator {
+        pub config: Config,
+        pub constants: Vec<host::Constants>,
+        pub hosts: Vec<host::Variables>,
+        pub transport: SimTransport,
+        pub next_request: usize,
+        // The first value decided in each instance by any host.
+        pub decided: HashMap<u64, Value>,
+        pub report: Report,
+    }
+
+    impl Simulator {
+        #[verifier::external_body]
+        pub fn new(config: Config, seed: u64) -> Simulator {
+            let constants: Vec<host::Constants> = (0..config.num_hosts).map(|id| config.constants(id)).collect();
+            let hosts = constants.iter().map(|c| host::Variables::new(c)).collect();
+            let transport = SimTransport::new(SimEndpoint::new(&config, seed));
+            let report = Report { seed, steps: 0, decided: 0, dropped: 0, duplicated: 0, violation: None };
+
+            Simulator { config, constants, hosts, transport, next_request: 0, decided: HashMap::new(), report }
+        }
+
+        #[verifier::external_body]
+        fn deliver(&mut self, index: usize, to: usize) -> bool {
+            self.transport.endpoint.selected = Some(index);
+            let record = deliver(&mut self.hosts[to], &self.constants[to], &mut self.transport);
+            // A packet no handler takes (stale ballot, unknown instance, host down) is discarded.
+            self.transport.endpoint.selected = None;
+            record.is_some()
+        }
+
+        #[verifier::external_body]
+        fn act(&mut self, host_id: usize, action: LocalAction) -> bool {
+            act(&mut self.hosts[host_id], &self.constants[host_id], &mut self.transport, action).is_some()
+        }
+
+        // The `(ballot, value)` pairs a learner has heard Accepteds for at `key`, sorted so that the
+        // order does not depend on how the maps hash.
+        #[verifier::external_body]
+        fn learnable(&self, host_id: usize, key: u64) -> Vec<(Ballot, Value)> {
+            let Some(instance) = self.hosts[host_id].instances.get(&key) else { return Vec::new() };
+            let mut learnable: Vec<(Ballot, Value)> = instance
+                .volatile
+                .learned
+                .iter()
+                .flat_map(|(num, ballots)| ballots.iter().map(move |(pid, (value, _))| (Ballot { num: *num, pid: *pid }, *value)))
+                .chain(instance.volatile.fast_learned.iter().map(|(value, _)| (Ballot { num: 0, pid: 0 }, *value)))
+                .collect();
+            learnable.sort_by_key(|(ballot, value)| (ballot.num, ballot.pid, value.client_id, value.seq, value.op));
+            learnable
+        }
+
+        // With `batch_percent`, a `SendDecideBatch` or `ProposeBatch` over `key` and the host's other
+        // instances. Then the first enabled one of `InitRequest`, `RaiseToFloor`, `JoinPrepareFrom`,
+        // `SendAccept`, `Learn` and `LearnerDecide` (until the host has decided) and `SendDecide`;
+        // failing those, a `SendPrepare` or `SendPrepareFrom` that fires with `prepare_percent` as a
+        // retry timeout. This is the order of the checker's `Simulator::local`.
+        #[verifier::external_body]
+        fn local(&mut self, host_id: usize, key: u64) -> bool {
+            if self.config.batch_percent > 0 && self.transport.endpoint.rng.percent(self.config.batch_percent) {
+                let keys: Vec<u64> = std::iter::once(key).chain(self.hosts[host_id].keys.iter().copied().filter(|k| *k != key)).take(marshal::MAX_BATCH_ENTRIES).collect();
+                for action in [BatchAction::SendDecideBatch { keys: keys.clone() }, BatchAction::ProposeBatch { keys }] {
+                    if act_batch(&mut self.hosts[host_id], &self.constants[host_id], &mut self.transport, action).is_some() {
+                        return true;
+                    }
+                }
+            }
+
+            for action in [
+                LocalAction::InitRequest { key },
+                LocalAction::RaiseToFloor { key },
+                LocalAction::JoinPrepareFrom { key },
+                LocalAction::SendAccept { key },
+            ] {
+                if self.act(host_id, action) {
+                    return true;
+                }
+            }
+
+            let undecided = self.hosts[host_id].instances.get(&key).is_some_and(|instance| instance.decide_value.is_none());
+            if undecided {
+                if self.act(host_id, LocalAction::Learn { key }) {
+                    return true;
+                }
+                for (ballot, value) in self.learnable(host_id, key) {
+                    if self.act(host_id, LocalAction::LearnerDecide { key, ballot, value }) {
+                        return true;
+                    }
+                }
+            }
+
+            if self.act(host_id, LocalAction::SendDecide { key }) {
+                return true;
+            }
+
+            if !self.transport.endpoint.rng.percent(self.config.prepare_percent) {
+                false
+            } else if self.config.prepare_from_percent > 0 && self.transport.endpoint.rng.percent(self.config.prepare_from_percent) {
+                self.act(host_id, LocalAction::SendPrepareFrom { key })
+            } else {
+                self.act(host_id, LocalAction::SendPrepare { key })
+            }
+        }
+
+        #[verifier::external_body]
+        fn submit(&mut self, host_id: usize) -> bool {
+            let index = self.next_request;
+            self.next_request += 1;
+
+            let client_id = index as u64;
+            let seq = if self.config.fast_clients.contains(&client_id) { client_id % self.config.num_keys.max(1) } else { 0 };
+            let request = Value { client_id, seq, op: self.config.values[index] };
+            self.act(host_id, LocalAction::SubmitRequest { request })
+        }
+
+        // At most `num_failures` hosts are down at once, so a quorum can always make progress.
+        #[verifier::external_body]
+        fn can_crash(&self) -> bool {
+            self.hosts.iter().filter(|host| !host.up).count() < self.config.num_failures as usize
+        }
+
+        // One scheduling decision: deliver a due packet, or let a random host act. Returns whether a
+        // host took a step.
+        #[verifier::external_body]
+        pub fn step(&mut self) -> bool {
+            let due = self.transport.endpoint.due();
+
+            let stepped = if !due.is_empty() && self.transport.endpoint.rng.percent(70) {
+                let (index, to) = due[self.transport.endpoint.rng.below(due.len() as u64) as usize];
+                self.deliver(index, to)
+            } else {
+                let host_id = self.transport.endpoint.rng.below(self.config.num_hosts) as usize;
+                if !self.hosts[host_id].up {
+                    self.transport.endpoint.rng.percent(self.config.recover_percent) && self.act(host_id, LocalAction::Recover)
+                } else if self.can_crash() && self.transport.endpoint.rng.percent(self.config.crash_percent) {
+                    self.transport.endpoint.forget(host_id);
+                    self.act(host_id, LocalAction::Crash)
+                } else if self.next_request < self.config.values.len() && self.transport.endpoint.rng.percent(10) {
+                    self.submit(host_id)
+                } else if self.config.num_keys > 0 {
+                    let key = self.transport.endpoint.rng.below(self.config.num_keys);
+                    self.local(host_id, key)
+                } else {
+                    false
+                }
+            };
+
+            self.transport.endpoint.tick += 1;
+            stepped
+        }
+
+        // Records each host's decisions and returns the first key on which one differs from a value
+        // decided before, by this host or another. Hosts are visited in order and their instances in
+        // `keys` order, so the result does not depend on how the maps hash.
+        #[verifier::external_body]
+        fn check_agreement(&mut self) -> Option<u64> {
+            for host in &self.hosts {
+                for key in &host.keys {
+                    let Some(value) = host.instances[key].decide_value else { continue };
+                    match self.decided.get(key) {
+                        Some(decided) if !decided.eq(&value) => { return Some(*key); },
+                        Some(_) => {},
+                        None => {
+                            self.decided.insert(*key, value);
+                        },
+                    }
+                }
+            }
+            None
+        }
+
+        // Runs `config.steps` scheduling decisions, checking agreement after each one that changed a
+        // host and stopping at the first disagreement.
+        #[verifier::external_body]
+        pub fn run(mut self) -> Report {
+            for decision in 0..self.config.steps {
+                if self.step() {
+                    self.report.steps += 1;
+                    if let Some(key) = self.check_agreement() {
+                        self.report.violation = Some((decision, key));
+                        break;
+                    }
+                }
+            }
+
+            self.report.decided = self.decided.len() as u64;
+            self.report.dropped = self.transport.endpoint.dropped;
+            self.report.duplicated = self.transport.endpoint.duplicated;
+            self.report
+        }
+    }
+
+    #[verifier::external_body]
+    fn parse_list(text: &str) -> Option<Vec<u64>> {
+        text.split(',').filter(|item| !item.is_empty()).map(|item| item.parse().ok()).collect()
+    }
+
+    // The flags after `simulate`, and the seeds to run.
+    #[verifier::external_body]
+    fn parse_args(args: &[String]) -> Option<(Config, std::ops::Range<u64>)> {
+        let mut config = Config::new();
+        let mut seeds = 0..1;
+        let mut rest = args.iter();
+        while let Some(flag) = rest.next() {
+            let value = rest.next()?;
+            match flag.as_str() {
+                "--seed" => {
+                    let seed: u64 = value.parse().ok()?;
+                    seeds = seed..seed + 1;
+                },
+                "--seeds" => seeds = 0..value.parse().ok()?,
+                "--steps" => config.steps = value.parse().ok()?,
+                "--hosts" => config.num_hosts = value.parse().ok()?,
+                "--failures" => config.num_failures = value.parse().ok()?,
+                "--keys" => config.num_keys = value.parse().ok()?,
+                "--batch" => config.batch_percent = value.parse().ok()?,
+                "--learners" => config.learners = parse_list(value)?,
+                "--fast-clients" => config.fast_clients = parse_list(value)?,
+                _ => { return None; },
+            }
+        }
+
+        if config.num_hosts == 0 || config.num_hosts < 2 * config.num_failures + 1 {
+            return None;
+        }
+        Some((config, seeds))
+    }
+
+    // `main simulate [--seed N | --seeds N] [--steps N] [--hosts N] [--failures N] [--keys N]
+    // [--batch PERCENT] [--learners ID,..] [--fast-clients ID,..]` runs one seed, or seeds `0..N`,
+    // and exits 1 at the first that breaks agreement, naming the seed that replays it. Without the
+    // `simulate` argument it does nothing.
+    #[verifier::external_body]
+    pub fn run_from_args() {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        if args.first().map(String::as_str) != Some("simulate") {
+            return;
+        }
+
+        let Some((config, seeds)) = parse_args(&args[1..]) else {
+            eprintln!("usage: main simulate [--seed N | --seeds N] [--steps N] [--hosts N] [--failures N] [--keys N] [--batch PERCENT] [--learners ID,..] [--fast-clients ID,..]");
+            std::process::exit(2);
+        };
+
+        for seed in seeds {
+            let config = Config { values: config.values.clone(), learners: config.learners.clone(), fast_clients: config.fast_clients.clone(), ..config };
+            let report = Simulator::new(config, seed).run();
+            println!(
+                "seed {}: {} steps, {} instances decided, {} packets dropped, {} duplicated",
+                report.seed, report.steps, report.decided, report.dropped, report.duplicated
+            );
+            if let Some((decision, key)) = report.violation {
+                eprintln!("seed {}: hosts disagree on key {} after scheduling decision {}", report.seed, key, decision);
+                std::process::exit(1);
+            }
+        }
+    }
+}
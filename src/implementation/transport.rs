@@ -0,0 +1,718 @@
+use super::{host, marshal, Ballot, Batch, BatchOperation, Message, NetworkOperation, Packet, Value};
+use crate::distributed_system::{low_level::{self, batch, network}, Event};
+use std::{
+    io::{ErrorKind, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, UdpSocket},
+    time::Duration,
+};
+use vstd::prelude::*;
+
+// How long `TcpEndpoint` waits for a peer to accept a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(100);
+
+// How many bytes may wait for one peer. Frames past it are dropped, which the trusted boundary treats
+// like a lost datagram.
+const MAX_PENDING_LEN: usize = 64 * (4 + marshal::MAX_PACKET_LEN);
+
+verus! {
+    // Trusted boundary.
+    //
+    // Each process only sees its own socket, so the shared `network::Variables` is ghost state that
+    // every transport carries. The assumptions below are what the OS and the other processes must
+    // uphold for a loopback cluster to be a run of `low_level::next`:
+    //   1. The sent history and the set of in-flight messages only grow (other hosts' steps happen
+    //      between ours). A lost datagram is a message that stays in flight but is never delivered.
+    //   2. Every received buffer was produced by `marshal::encode_packet` of a packet some host sent.
+    //   3. A send is atomic: right before it, the network is some superset of what we last saw.
+    pub trait Transport {
+        spec fn network(&self) -> network::Variables;
+
+        fn send(&mut self, packet: &Packet) -> (pre: Ghost<network::Variables>)
+        ensures
+            network_grew(old(self).network(), pre@),
+            self.network().sent_messages == record_sent(pre@.sent_messages, packet@),
+            self.network().in_flight_messages == pre@.in_flight_messages.insert(packet@);
+
+        fn recv(&mut self) -> (packet: Option<Packet>)
+        ensures
+            network_grew(old(self).network(), self.network()),
+            packet is Some ==> self.network().in_flight_messages.contains(packet.unwrap()@);
+    }
+
+    // The sent history after sending `message`: a batch is recorded with every message it carries.
+    pub open spec fn record_sent(sent: Set<low_level::Message>, message: low_level::Message) -> Set<low_level::Message> {
+        if (batch::is_batch(message)) {
+            sent.insert(message).union(batch::unbatch(message).to_set())
+        } else {
+            sent.insert(message)
+        }
+    }
+
+    // Every host reads its own copy of a broadcast, so a receive never consumes the message.
+    pub open spec fn network_constants() -> network::Constants {
+        network::Constants { consume_on_receive: false }
+    }
+
+    pub open spec fn network_grew(old_network: network::Variables, new_network: network::Variables) -> bool {
+        &&& old_network.sent_messages.subset_of(new_network.sent_messages)
+        &&& old_network.in_flight_messages.subset_of(new_network.in_flight_messages)
+    }
+
+    #[verifier::external_body]
+    pub struct UdpEndpoint {
+        socket: UdpSocket,
+        peers: Vec<SocketAddr>,
+    }
+
+    #[verifier::external_body]
+    pub struct TcpEndpoint {
+        listener: TcpListener,
+        peers: Vec<SocketAddr>,
+        outgoing: Vec<Option<TcpStream>>,
+        pending: Vec<Vec<u8>>,
+        incoming: Vec<(TcpStream, Vec<u8>)>,
+    }
+
+    pub struct UdpTransport {
+        endpoint: UdpEndpoint,
+        network: Ghost<network::Variables>,
+    }
+
+    pub struct TcpTransport {
+        endpoint: TcpEndpoint,
+        network: Ghost<network::Variables>,
+    }
+
+    // Promise and Nack go back to the ballot owner; every other message goes to every host. Accepted is
+    // wanted by the ballot owner and every learner, and the learner set lives in `host::Constants`.
+    // Returns host ids, which index the peer list of an endpoint.
+    #[verifier::external_body]
+    pub fn destinations(num_hosts: usize, packet: &Packet) -> Vec<usize> {
+        match packet {
+            Packet::Single(Message::Promise { ballot, .. }) | Packet::Single(Message::Nack { ballot, .. }) => {
+                Some(ballot.pid as usize).filter(|id| *id < num_hosts).into_iter().collect()
+            },
+            _ => (0..num_hosts).collect(),
+        }
+    }
+
+    #[verifier::external_body]
+    fn parse_addresses(addresses: &[&str]) -> Option<Vec<SocketAddr>> {
+        addresses.iter().map(|address| address.parse().ok()).collect()
+    }
+
+    impl UdpEndpoint {
+        #[verifier::external_body]
+        pub fn bind(id: u64, addresses: &[&str]) -> Option<UdpEndpoint> {
+            let peers = parse_addresses(addresses)?;
+            let socket = UdpSocket::bind(peers.get(id as usize)?).ok()?;
+            socket.set_nonblocking(true).ok()?;
+            Some(UdpEndpoint { socket, peers })
+        }
+
+        #[verifier::external_body]
+        fn send_bytes(&self, packet: &Packet, data: &[u8]) {
+            for index in destinations(self.peers.len(), packet) {
+                let _ = self.socket.send_to(data, self.peers[index]);
+            }
+        }
+
+        #[verifier::external_body]
+        fn recv_bytes(&self) -> Option<Vec<u8>> {
+            let mut buffer = [0u8; marshal::MAX_PACKET_LEN];
+            match self.socket.recv_from(&mut buffer) {
+                Ok((len, _)) => Some(buffer[..len].to_vec()),
+                Err(_) => None,
+            }
+        }
+    }
+
+    impl TcpEndpoint {
+        #[verifier::external_body]
+        pub fn bind(id: u64, addresses: &[&str]) -> Option<TcpEndpoint> {
+            let peers = parse_addresses(addresses)?;
+            let listener = TcpListener::bind(peers.get(id as usize)?).ok()?;
+            listener.set_nonblocking(true).ok()?;
+            let outgoing = peers.iter().map(|_| None).collect();
+            let pending = peers.iter().map(|_| Vec::new()).collect();
+            Some(TcpEndpoint { listener, peers, outgoing, pending, incoming: Vec::new() })
+        }
+
+        // Frames are a little-endian `u32` length followed by the encoded packet. A frame is queued
+        // for each destination and written by `flush`, so a peer that stops reading never blocks us.
+        #[verifier::external_body]
+        fn send_bytes(&mut self, packet: &Packet, data: &[u8]) {
+            let mut frame = (data.len() as u32).to_le_bytes().to_vec();
+            frame.extend_from_slice(data);
+
+            for index in destinations(self.peers.len(), packet) {
+                if self.pending[index].len() + frame.len() <= MAX_PENDING_LEN {
+                    self.pending[index].extend_from_slice(&frame);
+                }
+            }
+            self.flush();
+        }
+
+        // Writes as much pending output as every peer takes without blocking. Streams are connected
+        // lazily with a bounded wait and then made nonblocking. A stream that fails is dropped with
+        // its pending output, since the next connection could not resume a frame halfway through.
+        #[verifier::external_body]
+        fn flush(&mut self) {
+            for index in 0..self.peers.len() {
+                if self.pending[index].is_empty() {
+                    continue;
+                }
+                if self.outgoing[index].is_none() {
+                    self.outgoing[index] = TcpStream::connect_timeout(&self.peers[index], CONNECT_TIMEOUT)
+                        .ok()
+                        .filter(|stream| stream.set_nonblocking(true).is_ok() && stream.set_nodelay(true).is_ok());
+                }
+                let Some(stream) = &mut self.outgoing[index] else {
+                    self.pending[index].clear();
+                    continue;
+                };
+
+                let buffer = &mut self.pending[index];
+                let mut written = 0;
+                let mut failed = false;
+                while written < buffer.len() {
+                    match stream.write(&buffer[written..]) {
+                        Ok(0) => {
+                            failed = true;
+                            break;
+                        },
+                        Ok(len) => written += len,
+                        Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                        Err(e) if e.kind() == ErrorKind::Interrupted => {},
+                        Err(_) => {
+                            failed = true;
+                            break;
+                        },
+                    }
+                }
+
+                if failed {
+                    self.outgoing[index] = None;
+                    buffer.clear();
+                } else {
+                    buffer.drain(..written);
+                }
+            }
+        }
+
+        #[verifier::external_body]
+        fn recv_bytes(&mut self) -> Option<Vec<u8>> {
+            self.flush();
+            while let Ok((stream, _)) = self.listener.accept() {
+                if stream.set_nonblocking(true).is_ok() {
+                    self.incoming.push((stream, Vec::new()));
+                }
+            }
+
+            // A stream is dropped once it announces a frame longer than any packet, or once it has
+            // closed or failed and no whole frame is left in its buffer, so a peer cannot make `buffer`
+            // grow without bound.
+            let mut index = 0;
+            while index < self.incoming.len() {
+                let (stream, buffer) = &mut self.incoming[index];
+                let mut chunk = [0u8; 1024];
+                let mut open = true;
+                loop {
+                    match stream.read(&mut chunk) {
+                        Ok(0) => {
+                            open = false;
+                            break;
+                        },
+                        Ok(len) => buffer.extend_from_slice(&chunk[..len]),
+                        Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                        Err(_) => {
+                            open = false;
+                            break;
+                        },
+                    }
+                    if buffer.len() >= 4 + marshal::MAX_PACKET_LEN {
+                        break;
+                    }
+                }
+
+                if buffer.len() >= 4 {
+                    let len = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as usize;
+                    if len > marshal::MAX_PACKET_LEN {
+                        self.incoming.swap_remove(index);
+                        continue;
+                    }
+                    if buffer.len() >= 4 + len {
+                        let data = buffer[4..4 + len].to_vec();
+                        buffer.drain(..4 + len);
+                        return Some(data);
+                    }
+                }
+
+                if open {
+                    index += 1;
+                } else {
+                    self.incoming.swap_remove(index);
+                }
+            }
+
+            None
+        }
+    }
+
+    impl UdpTransport {
+        #[verifier::external_body]
+        pub fn new(endpoint: UdpEndpoint) -> (transport: UdpTransport)
+        ensures
+            transport.network().well_formed(&network_constants()),
+        {
+            UdpTransport { endpoint, network: Ghost::assume_new() }
+        }
+    }
+
+    impl TcpTransport {
+        #[verifier::external_body]
+        pub fn new(endpoint: TcpEndpoint) -> (transport: TcpTransport)
+        ensures
+            transport.network().well_formed(&network_constants()),
+        {
+            TcpTransport { endpoint, network: Ghost::assume_new() }
+        }
+    }
+
+    impl Transport for UdpTransport {
+        closed spec fn network(&self) -> network::Variables {
+            self.network@
+        }
+
+        #[verifier::external_body]
+        fn send(&mut self, packet: &Packet) -> (pre: Ghost<network::Variables>) {
+            let data = marshal::encode_packet(packet);
+            self.endpoint.send_bytes(packet, &data);
+            Ghost::assume_new()
+        }
+
+        #[verifier::external_body]
+        fn recv(&mut self) -> (packet: Option<Packet>) {
+            marshal::decode_packet(&self.endpoint.recv_bytes()?)
+        }
+    }
+
+    impl Transport for TcpTransport {
+        closed spec fn network(&self) -> network::Variables {
+            self.network@
+        }
+
+        #[verifier::external_body]
+        fn send(&mut self, packet: &Packet) -> (pre: Ghost<network::Variables>) {
+            let data = marshal::encode_packet(packet);
+            self.endpoint.send_bytes(packet, &data);
+            Ghost::assume_new()
+        }
+
+        #[verifier::external_body]
+        fn recv(&mut self) -> (packet: Option<Packet>) {
+            marshal::decode_packet(&self.endpoint.recv_bytes()?)
+        }
+    }
+
+    // End of trusted boundary.
+
+    pub enum LocalAction {
+        InitRequest { key: u64 },
+        SendPrepare { key: u64 },
+        SendPrepareFrom { key: u64 },
+        RaiseToFloor { key: u64 },
+        JoinPrepareFrom { key: u64 },
+        SendAccept { key: u64 },
+        SendDecide { key: u64 },
+        Learn { key: u64 },
+        LearnerDecide { key: u64, ballot: Ballot, value: Value },
+        SubmitRequest { request: Value },
+        Crash,
+        Recover,
+    }
+
+    // Local actions of a leader that send a batch instead of one message per instance.
+    pub enum BatchAction {
+        ProposeBatch { keys: Vec<u64> },
+        SendDecideBatch { keys: Vec<u64> },
+    }
+
+    #[derive(Clone, Copy)]
+    pub enum StepKind {
+        Decide { value: Value },
+        Propose { value: Value },
+        Submit { request: Value },
+        Internal,
+    }
+
+    pub struct StepRecord {
+        pub key: u64,
+        pub net_op: NetworkOperation,
+        pub kind: StepKind,
+        pub pre: Ghost<network::Variables>,
+        pub post: Ghost<network::Variables>,
+    }
+
+    pub open spec fn step_event(key: u64, kind: StepKind) -> Event {
+        match kind {
+            StepKind::Decide { value } => Event::Decide { key: key as nat, value: value@ },
+            StepKind::Propose { value } => Event::Propose { key: key as nat, value: value@ },
+            StepKind::Submit { request } => Event::Submit { value: request@ },
+            StepKind::Internal => Event::NoOp,
+        }
+    }
+
+    impl StepRecord {
+        pub open spec fn event(&self) -> Event {
+            step_event(self.key, self.kind)
+        }
+
+        // One host step of `low_level::host_step`, as seen from the executing host.
+        pub open spec fn is_valid_step<T: Transport>(&self, c: &host::Constants, u: &host::Variables, v: &host::Variables, old_transport: &T, transport: &T) -> bool {
+            &&& low_level::host::step(&c@, &u@, &v@, self.key as nat, self.net_op@, self.event())
+            &&& network::step(&network_constants(), &self.pre@, &self.post@, self.net_op@)
+            &&& network_grew(old_transport.network(), self.pre@)
+            &&& self.post@ == transport.network()
+        }
+    }
+
+    // Proposing a batch is a `ProposeBatch` event of the AcceptBatch it sends, taking a DecideBatch a
+    // `DecideBatch` event of its entries; the other batch steps are internal.
+    #[derive(Clone, Copy)]
+    pub enum BatchKind {
+        Propose,
+        Decide,
+        Internal,
+    }
+
+    pub open spec fn batch_event(net_op: low_level::NetworkOperation, kind: BatchKind) -> Event {
+        match kind {
+            BatchKind::Propose => Event::ProposeBatch { entries: batch::entries(net_op.send.unwrap()) },
+            BatchKind::Decide => Event::DecideBatch { entries: batch::entries(net_op.recv.unwrap()) },
+            BatchKind::Internal => Event::NoOp,
+        }
+    }
+
+    // A step on a batch (`low_level::batch_host_step`).
+    pub struct BatchRecord {
+        pub net_op: BatchOperation,
+        pub kind: BatchKind,
+        pub pre: Ghost<network::Variables>,
+        pub post: Ghost<network::Variables>,
+    }
+
+    impl BatchRecord {
+        pub open spec fn event(&self) -> Event {
+            batch_event(self.net_op@, self.kind)
+        }
+
+        pub open spec fn is_valid_step<T: Transport>(&self, c: &host::Constants, u: &host::Variables, v: &host::Variables, old_transport: &T, transport: &T) -> bool {
+            &&& batch::step(&c@, &u@, &v@, self.net_op@, self.event())
+            &&& network::step(&network_constants(), &self.pre@, &self.post@, self.net_op@)
+            &&& network_grew(old_transport.network(), self.pre@)
+            &&& self.post@ == transport.network()
+        }
+    }
+
+    pub enum Record {
+        Single(StepRecord),
+        Batch(BatchRecord),
+    }
+
+    impl Record {
+        pub open spec fn is_valid_step<T: Transport>(&self, c: &host::Constants, u: &host::Variables, v: &host::Variables, old_transport: &T, transport: &T) -> bool {
+            match *self {
+                Record::Single(record) => record.is_valid_step(c, u, v, old_transport, transport),
+                Record::Batch(record) => record.is_valid_step(c, u, v, old_transport, transport),
+            }
+        }
+    }
+
+    fn dispatch(host: &mut host::Variables, c: &host::Constants, message: Message) -> (result: Option<(u64, NetworkOperation, StepKind)>)
+    requires
+        c.well_formed(),
+        old(host).inv(c),
+        old(host).up,
+    ensures
+        host.inv(c),
+        match result {
+            Some((key, net_op, kind)) => {
+                &&& net_op.recv == Some(message)
+                &&& low_level::host::step(&c@, &old(host)@, &host@, key as nat, net_op@, step_event(key, kind))
+            },
+            None => host@ == old(host)@,
+        },
+    {
+        let (key, net_op, kind) = match message {
+            Message::Prepare { key, .. } => {
+                let net_op = match host.promise(c, key, message) {
+                    Some(net_op) => Some(net_op),
+                    None => host.reject_prepare(c, key, message),
+                };
+                (key, net_op, StepKind::Internal)
+            },
+            Message::PrepareFrom { from, .. } => (from, host.promise_from(c, from, message), StepKind::Internal),
+            Message::Promise { key, .. } => (key, host.promised(c, key, message), StepKind::Internal),
+            Message::Accept { key, .. } => {
+                let net_op = match host.accept(c, key, message) {
+                    Some(net_op) => Some(net_op),
+                    None => host.reject_accept(c, key, message),
+                };
+                (key, net_op, StepKind::Internal)
+            },
+            Message::Accepted { key, ballot, .. } => {
+                // The fast ballot has no leader, so its Accepted messages only go to learners.
+                let net_op = if (ballot.num != 0 && ballot.pid == c.id) { host.accepted(c, key, message) } else { host.observe_accepted(c, key, message) };
+                (key, net_op, StepKind::Internal)
+            },
+            Message::Nack { key, .. } => (key, host.nacked(c, key, message), StepKind::Internal),
+            Message::Decide { key, value, .. } => (key, host.decide(c, key, message), StepKind::Decide { value }),
+            Message::ClientRequest { value } => {
+                if (c.is_fast_request(&value)) {
+                    (value.seq, host.fast_accept(c, value.seq, message), StepKind::Propose { value })
+                } else {
+                    (0, host.receive_request(c, 0, message), StepKind::Internal)
+                }
+            },
+        };
+
+        match net_op {
+            Some(net_op) => Some((key, net_op, kind)),
+            None => None,
+        }
+    }
+
+    fn complete_step<T: Transport>(transport: &mut T, key: u64, net_op: NetworkOperation, kind: StepKind) -> (record: StepRecord)
+    requires
+        net_op.recv is Some ==> old(transport).network().in_flight_messages.contains(net_op.recv.unwrap()@),
+    ensures
+        record.key == key,
+        record.net_op == net_op,
+        record.kind == kind,
+        network::step(&network_constants(), &record.pre@, &record.post@, net_op@),
+        network_grew(old(transport).network(), record.pre@),
+        record.post@ == transport.network(),
+    {
+        match net_op.send {
+            Some(message) => {
+                let pre = transport.send(&Packet::Single(message));
+                let ghost post = transport.network();
+                proof {
+                    assert(!batch::is_batch(message@));
+                    assert(post.sent_messages =~= pre@.sent_messages.insert(message@));
+                    assert(post.in_flight_messages =~= pre@.in_flight_messages.insert(message@));
+                }
+                StepRecord { key, net_op, kind, pre, post: Ghost(post) }
+            },
+            None => {
+                let ghost current = transport.network();
+                StepRecord { key, net_op, kind, pre: Ghost(current), post: Ghost(current) }
+            },
+        }
+    }
+
+    fn dispatch_batch(host: &mut host::Variables, c: &host::Constants, batch: Batch) -> (result: Option<(BatchOperation, BatchKind)>)
+    requires
+        c.well_formed(),
+        old(host).inv(c),
+        old(host).up,
+    ensures
+        host.inv(c),
+        match result {
+            Some((net_op, kind)) => {
+                &&& net_op.recv is Some && net_op.recv.unwrap()@ == batch@
+                &&& batch::step(&c@, &old(host)@, &host@, net_op@, batch_event(net_op@, kind))
+            },
+            None => host@ == old(host)@,
+        },
+    {
+        match batch {
+            Batch::AcceptBatch { .. } => match host.accept_batch(c, batch) {
+                Some(net_op) => Some((net_op, BatchKind::Internal)),
+                None => None,
+            },
+            Batch::AcceptedBatch { .. } => match host.accepted_batch(c, batch) {
+                Some(net_op) => Some((net_op, BatchKind::Internal)),
+                None => None,
+            },
+            Batch::DecideBatch { .. } => match host.decide_batch(c, batch) {
+                Some(net_op) => Some((net_op, BatchKind::Decide)),
+                None => None,
+            },
+        }
+    }
+
+    fn complete_batch_step<T: Transport>(transport: &mut T, net_op: BatchOperation, kind: BatchKind) -> (record: BatchRecord)
+    requires
+        net_op.recv is Some ==> old(transport).network().in_flight_messages.contains(net_op.recv.unwrap()@),
+        net_op.send is Some ==> batch::is_batch(net_op.send.unwrap()@),
+    ensures
+        record.net_op@ == net_op@,
+        record.kind == kind,
+        network::step(&network_constants(), &record.pre@, &record.post@, net_op@),
+        network_grew(old(transport).network(), record.pre@),
+        record.post@ == transport.network(),
+    {
+        let ghost net_op_view = net_op@;
+        match net_op.send {
+            Some(batch) => {
+                let packet = Packet::Batch(batch);
+                let pre = transport.send(&packet);
+                let ghost post = transport.network();
+                let net_op = BatchOperation { send: match packet { Packet::Batch(batch) => Some(batch), Packet::Single(_) => None }, recv: net_op.recv };
+                proof {
+                    assert(net_op@ == net_op_view);
+                    assert(post.in_flight_messages =~= pre@.in_flight_messages.insert(packet@));
+                }
+                BatchRecord { net_op, kind, pre, post: Ghost(post) }
+            },
+            None => {
+                let ghost current = transport.network();
+                BatchRecord { net_op, kind, pre: Ghost(current), post: Ghost(current) }
+            },
+        }
+    }
+
+    // Crash and recover touch no instance, so they are recorded against key 0 like `SubmitRequest`.
+    fn complete_local<T: Transport>(transport: &mut T, net_op: Option<NetworkOperation>) -> (record: Option<StepRecord>)
+    requires
+        net_op is Some ==> net_op.unwrap().recv is None,
+    ensures
+        match record {
+            Some(record) => {
+                &&& record.key == 0
+                &&& record.net_op == net_op.unwrap()
+                &&& record.kind == StepKind::Internal
+                &&& network::step(&network_constants(), &record.pre@, &record.post@, record.net_op@)
+                &&& network_grew(old(transport).network(), record.pre@)
+                &&& record.post@ == transport.network()
+            },
+            None => net_op is None && transport.network() == old(transport).network(),
+        },
+    {
+        match net_op {
+            Some(net_op) => Some(complete_step(transport, 0, net_op, StepKind::Internal)),
+            None => None,
+        }
+    }
+
+    // Receives one packet, hands it to the matching host handler and sends the handler's reply.
+    pub fn deliver<T: Transport>(host: &mut host::Variables, c: &host::Constants, transport: &mut T) -> (record: Option<Record>)
+    requires
+        c.well_formed(),
+        old(host).inv(c),
+    ensures
+        host.inv(c),
+        match record {
+            Some(record) => record.is_valid_step(c, &old(host), &host, &old(transport), &transport),
+            None => host@ == old(host)@ && network_grew(old(transport).network(), transport.network()),
+        },
+    {
+        // A crashed host leaves its messages in flight until it recovers.
+        if (!host.up) {
+            return None;
+        }
+
+        let packet = match transport.recv() {
+            Some(packet) => packet,
+            None => { return None; },
+        };
+        let ghost received = transport.network();
+        proof { assert(network_grew(old(transport).network(), received)); }
+
+        match packet {
+            Packet::Single(message) => match dispatch(host, c, message) {
+                Some((key, net_op, kind)) => Some(Record::Single(complete_step(transport, key, net_op, kind))),
+                None => None,
+            },
+            Packet::Batch(batch) => match dispatch_batch(host, c, batch) {
+                Some((net_op, kind)) => Some(Record::Batch(complete_batch_step(transport, net_op, kind))),
+                None => None,
+            },
+        }
+    }
+
+    // Performs one host action that does not consume a message.
+    pub fn act<T: Transport>(host: &mut host::Variables, c: &host::Constants, transport: &mut T, action: LocalAction) -> (record: Option<StepRecord>)
+    requires
+        c.well_formed(),
+        old(host).inv(c),
+    ensures
+        host.inv(c),
+        match record {
+            Some(record) => record.is_valid_step(c, &old(host), &host, &old(transport), &transport),
+            None => host@ == old(host)@ && transport.network() == old(transport).network(),
+        },
+    {
+        match action {
+            LocalAction::Crash => { return complete_local(transport, host.crash(c)); },
+            LocalAction::Recover => { return complete_local(transport, host.recover(c)); },
+            _ => {},
+        }
+
+        if (!host.up) {
+            return None;
+        }
+
+        let (key, net_op, kind) = match action {
+            LocalAction::InitRequest { key } => (key, host.init_request(c, key), StepKind::Internal),
+            LocalAction::SendPrepare { key } => (key, host.send_prepare(c, key), StepKind::Internal),
+            LocalAction::SendPrepareFrom { key } => (key, host.send_prepare_from(c, key), StepKind::Internal),
+            LocalAction::RaiseToFloor { key } => (key, host.raise_to_floor(c, key), StepKind::Internal),
+            LocalAction::JoinPrepareFrom { key } => (key, host.join_prepare_from(c, key), StepKind::Internal),
+            LocalAction::SendAccept { key } => {
+                let net_op = host.send_accept(c, key);
+                let kind = match net_op {
+                    Some(NetworkOperation { send: Some(Message::Accept { value, .. }), .. }) => StepKind::Propose { value },
+                    _ => StepKind::Internal,
+                };
+                (key, net_op, kind)
+            },
+            LocalAction::SendDecide { key } => (key, host.send_decide(c, key), StepKind::Internal),
+            LocalAction::Learn { key } => {
+                let net_op = host.learn(c, key);
+                let kind = match net_op {
+                    Some(NetworkOperation { send: Some(Message::Decide { value, .. }), .. }) => StepKind::Decide { value },
+                    _ => StepKind::Internal,
+                };
+                (key, net_op, kind)
+            },
+            LocalAction::LearnerDecide { key, ballot, value } => (key, host.learner_decide(c, key, ballot, value), StepKind::Decide { value }),
+            LocalAction::SubmitRequest { request } => (0, host.submit_request(c, request), StepKind::Submit { request }),
+            LocalAction::Crash | LocalAction::Recover => { return None; },
+        };
+
+        match net_op {
+            Some(net_op) => Some(complete_step(transport, key, net_op, kind)),
+            None => None,
+        }
+    }
+
+    // Proposes in several instances, or announces their decisions, with one batch.
+    pub fn act_batch<T: Transport>(host: &mut host::Variables, c: &host::Constants, transport: &mut T, action: BatchAction) -> (record: Option<BatchRecord>)
+    requires
+        c.well_formed(),
+        old(host).inv(c),
+    ensures
+        host.inv(c),
+        match record {
+            Some(record) => record.is_valid_step(c, &old(host), &host, &old(transport), &transport),
+            None => host@ == old(host)@ && transport.network() == old(transport).network(),
+        },
+    {
+        if (!host.up) {
+            return None;
+        }
+
+        let (net_op, kind) = match action {
+            BatchAction::ProposeBatch { keys } => (host.propose_batch(c, &keys), BatchKind::Propose),
+            BatchAction::SendDecideBatch { keys } => (host.send_decide_batch(c, &keys), BatchKind::Internal),
+        };
+
+        match net_op {
+            Some(net_op) => Some(complete_batch_step(transport, net_op, kind)),
+            None => None,
+        }
+    }
+}
@@ -2,6 +2,9 @@ use vstd::prelude::*;
 
 verus! {
     mod distributed_system;
+    mod implementation;
 
-    fn main() { }
+    fn main() {
+        implementation::simulate::run_from_args();
+    }
 }